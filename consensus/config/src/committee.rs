@@ -172,6 +172,12 @@ impl AuthorityIndex {
     pub const MIN: Self = Self::ZERO;
     pub const MAX: Self = Self(u32::MAX);
 
+    // Only for scanning the rows of an authority in the database, when the committee is not
+    // known, e.g. when inspecting a store. Invalid elsewhere.
+    pub const fn for_scan(index: u32) -> Self {
+        Self(index)
+    }
+
     pub fn value(&self) -> usize {
         self.0 as usize
     }
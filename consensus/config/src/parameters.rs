@@ -92,6 +92,15 @@ pub struct Parameters {
     #[serde(default = "Parameters::default_commit_sync_batches_ahead")]
     pub commit_sync_batches_ahead: usize,
 
+    /// Strategy used to score authorities at each leader schedule change. The lowest scored
+    /// authorities are swapped out as leaders.
+    ///
+    /// NOTE: unlike the other fields, the leader schedule must be identical among authorities.
+    /// So all authorities of a committee must use the same strategy, and the default should only
+    /// be changed for tests and experimental networks.
+    #[serde(default = "LeaderScoringStrategy::default")]
+    pub leader_scoring_strategy: LeaderScoringStrategy,
+
     /// Tonic network settings.
     #[serde(default = "TonicParameters::default")]
     pub tonic: TonicParameters,
@@ -209,11 +218,28 @@ impl Default for Parameters {
             commit_sync_parallel_fetches: Parameters::default_commit_sync_parallel_fetches(),
             commit_sync_batch_size: Parameters::default_commit_sync_batch_size(),
             commit_sync_batches_ahead: Parameters::default_commit_sync_batches_ahead(),
+            leader_scoring_strategy: LeaderScoringStrategy::default(),
             tonic: TonicParameters::default(),
         }
     }
 }
 
+/// Strategies to compute the reputation scores of authorities from the votes for committed leaders.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum LeaderScoringStrategy {
+    /// Each vote for a committed leader scores the stake of the blocks that strongly link to it.
+    #[default]
+    DistributedVotes,
+    /// Each certified vote for a committed leader scores how much faster than
+    /// `latency_budget_ms` it was certified after the leader was proposed, measured with block
+    /// timestamps.
+    CertifiedVoteLatency { latency_budget_ms: u64 },
+    /// Same as `DistributedVotes`, but the scores of votes decay exponentially with the
+    /// number of commits since the vote was committed, halving every `half_life_commits`.
+    StakeWeightedDecay { half_life_commits: u64 },
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct TonicParameters {
     /// Keepalive interval and timeouts for both client and server.
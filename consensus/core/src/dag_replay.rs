@@ -0,0 +1,658 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Record-and-replay of consensus DAG windows.
+//!
+//! A [`DagWindow`] is recorded from the store of an authority for a range of commits. It contains
+//! the committed and uncommitted blocks around the range, and the commits preceding the range that
+//! are needed to recover `DagState` as if the authority restarted right before the range.
+//!
+//! Replaying a window runs the recorded blocks through `UniversalCommitter`, `Linearizer` and
+//! `CommitFinalizer` in memory, the same way `Core` and `CommitObserver` do, and reports the first
+//! commit where the replayed sequence differs from the recorded one.
+
+use std::{
+    collections::{BTreeMap, VecDeque},
+    fmt,
+    sync::Arc,
+};
+
+use bytes::Bytes;
+use consensus_config::{AuthorityIndex, Committee, LeaderScoringStrategy, Parameters};
+use consensus_types::block::{BlockRef, BlockTimestampMs, Round, TransactionIndex};
+use mysten_metrics::monitored_mpsc;
+use parking_lot::RwLock;
+use prometheus::Registry;
+use serde::{Deserialize, Serialize};
+use sui_protocol_config::{Chain, ProtocolConfig, ProtocolVersion};
+
+use crate::{
+    block::{BlockAPI as _, CertifiedBlocksOutput, SignedBlock, VerifiedBlock},
+    block_verifier::NoopBlockVerifier,
+    commit::{
+        CommitAPI as _, CommitDigest, CommitIndex, CommitInfo, CommitRange, CommitRef,
+        CommittedSubDag, DecidedLeader, GENESIS_COMMIT_INDEX, TrustedCommit,
+    },
+    commit_finalizer::CommitFinalizer,
+    context::{Clock, Context},
+    dag_state::DagState,
+    error::{ConsensusError, ConsensusResult},
+    leader_schedule::LeaderSchedule,
+    linearizer::Linearizer,
+    metrics::initialise_metrics,
+    storage::{Store, WriteBatch, mem_store::MemStore},
+    transaction_certifier::TransactionCertifier,
+    universal_committer::{
+        UniversalCommitter, universal_committer_builder::UniversalCommitterBuilder,
+    },
+};
+
+/// Per-epoch configuration of the authority a window was recorded from.
+/// This is needed to rebuild the `Context` used when replaying the window.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DagWindowEpoch {
+    pub epoch_start_timestamp_ms: u64,
+    pub own_index: AuthorityIndex,
+    pub committee: Committee,
+    pub protocol_version: u64,
+    pub chain: Chain,
+    /// The leader scoring strategy of the authority, which decides the leader schedule and so
+    /// the commits.
+    #[serde(default)]
+    pub leader_scoring_strategy: LeaderScoringStrategy,
+}
+
+impl DagWindowEpoch {
+    /// Builds a context for replaying. Panics if the protocol version is not supported by this binary.
+    pub(crate) fn context(&self) -> Context {
+        Context::new(
+            self.epoch_start_timestamp_ms,
+            self.own_index,
+            self.committee.clone(),
+            Parameters {
+                leader_scoring_strategy: self.leader_scoring_strategy.clone(),
+                ..Default::default()
+            },
+            ProtocolConfig::get_for_version(
+                ProtocolVersion::new(self.protocol_version),
+                self.chain,
+            ),
+            initialise_metrics(Registry::new()),
+            Arc::new(Clock::default()),
+        )
+    }
+}
+
+/// A portable recording of a range of commits and the DAG they were sequenced from.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DagWindow {
+    pub epoch: DagWindowEpoch,
+    /// The last commit info stored before the window, used to recover the leader schedule.
+    base_commit_info: Option<(CommitRef, CommitInfo)>,
+    /// Serialized commits preceding the window, needed to recover `DagState`.
+    base_commits: Vec<Bytes>,
+    /// Serialized commits of the window.
+    commits: Vec<Bytes>,
+    /// Rejected transactions of the commits in the window that were finalized when recorded.
+    rejected_transactions: BTreeMap<CommitIndex, BTreeMap<BlockRef, Vec<TransactionIndex>>>,
+    /// Serialized blocks of all authorities within the rounds covered by the window.
+    blocks: Vec<Bytes>,
+}
+
+impl DagWindow {
+    /// Records the commits in `range` from `store`. Blocks are recorded from the lowest round
+    /// that can be referenced when recovering from the commit before the range, up to
+    /// `extra_rounds` rounds above the leader of the last commit in the range. The extra rounds
+    /// allow the replay to decide the last leaders of the range.
+    pub fn record(
+        store: &dyn Store,
+        epoch: DagWindowEpoch,
+        range: CommitRange,
+        extra_rounds: Round,
+    ) -> ConsensusResult<Self> {
+        let commits = store.scan_commits(range.clone())?;
+        if commits.len() != range.size() {
+            return Err(ConsensusError::InvalidDagWindow(format!(
+                "Found {} commits in range {range:?}, expected {}",
+                commits.len(),
+                range.size()
+            )));
+        }
+        let first_index = range.start().max(GENESIS_COMMIT_INDEX + 1);
+        let base_commit_info = store.read_last_commit_info_before(first_index)?;
+        let base_commits = scan_base_commits(
+            store,
+            first_index,
+            base_commit_info
+                .as_ref()
+                .map_or(GENESIS_COMMIT_INDEX, |(commit_ref, _)| commit_ref.index),
+            epoch_gc_depth(&epoch),
+        )?;
+
+        let base_gc_round = base_commits.back().map_or(0, |commit| {
+            commit.leader().round.saturating_sub(epoch_gc_depth(&epoch))
+        });
+        let start_round = base_commits
+            .iter()
+            .chain(commits.iter())
+            .flat_map(|commit| commit.blocks().iter().map(|block_ref| block_ref.round))
+            .min()
+            .unwrap_or(base_gc_round + 1)
+            .min(base_gc_round + 1)
+            .max(1);
+        let end_round = commits.last().unwrap().leader().round + extra_rounds;
+
+        let mut blocks = vec![];
+        for (authority, _) in epoch.committee.authorities() {
+            blocks.extend(
+                store
+                    .scan_blocks_by_author_in_range(authority, start_round, end_round)?
+                    .into_iter()
+                    .map(|block| block.serialized().clone()),
+            );
+        }
+
+        let mut rejected_transactions = BTreeMap::new();
+        for commit in &commits {
+            if let Some(rejected) = store.read_rejected_transactions(commit.reference())? {
+                rejected_transactions.insert(commit.index(), rejected);
+            }
+        }
+
+        Ok(Self {
+            epoch,
+            base_commit_info,
+            base_commits: base_commits
+                .iter()
+                .map(|commit| commit.serialized().clone())
+                .collect(),
+            commits: commits
+                .iter()
+                .map(|commit| commit.serialized().clone())
+                .collect(),
+            rejected_transactions,
+            blocks,
+        })
+    }
+
+    pub fn serialize(&self) -> ConsensusResult<Bytes> {
+        Ok(bcs::to_bytes(self)
+            .map_err(ConsensusError::SerializationFailure)?
+            .into())
+    }
+
+    pub fn deserialize(bytes: &[u8]) -> ConsensusResult<Self> {
+        bcs::from_bytes(bytes)
+            .map_err(|e| ConsensusError::InvalidDagWindow(format!("Failed to deserialize: {e}")))
+    }
+
+    /// Range of the recorded commits.
+    pub fn commit_range(&self) -> ConsensusResult<CommitRange> {
+        let commits = deserialize_commits(&self.commits)?;
+        match (commits.first(), commits.last()) {
+            (Some(first), Some(last)) => Ok((first.index()..=last.index()).into()),
+            _ => Err(ConsensusError::InvalidDagWindow(
+                "Window has no commits".to_string(),
+            )),
+        }
+    }
+
+    pub fn num_blocks(&self) -> usize {
+        self.blocks.len()
+    }
+
+    /// Replays the window and compares the produced commits against the recorded ones.
+    pub async fn replay(&self) -> ConsensusResult<DagReplayReport> {
+        let context = Arc::new(self.epoch.context());
+        let commits = deserialize_commits(&self.commits)?;
+        let store = Arc::new(MemStore::new());
+        store.write(WriteBatch::new(
+            deserialize_blocks(&self.blocks)?,
+            deserialize_commits(&self.base_commits)?,
+            self.base_commit_info.clone().into_iter().collect(),
+            vec![],
+        ))?;
+
+        let mut replayer = CommitRuleReplayer::new(context.clone(), store);
+        let produced_commits = replayer.try_commit();
+        let produced_by_index = produced_commits
+            .iter()
+            .map(|sub_dag| (sub_dag.commit_ref.index, sub_dag))
+            .collect::<BTreeMap<_, _>>();
+
+        let mut finalized = BTreeMap::new();
+        for sub_dag in &produced_commits {
+            if context.protocol_config.mysticeti_fastpath() {
+                for commit in replayer
+                    .commit_finalizer
+                    .process_commit(sub_dag.clone())
+                    .await
+                {
+                    finalized.insert(
+                        commit.commit_ref.index,
+                        commit.rejected_transactions_by_block,
+                    );
+                }
+            } else {
+                finalized.insert(sub_dag.commit_ref.index, BTreeMap::new());
+            }
+        }
+
+        let mut report = DagReplayReport {
+            matched_commits: 0,
+            produced_commits: produced_commits.len(),
+            divergence: None,
+        };
+        for expected in &commits {
+            let index = expected.index();
+            let Some(actual) = produced_by_index.get(&index) else {
+                report.divergence = Some(DagReplayDivergence::MissingCommit {
+                    expected: ReplayedCommit::from_commit(expected),
+                });
+                break;
+            };
+            if actual.commit_ref != expected.reference() {
+                report.divergence = Some(DagReplayDivergence::Commit {
+                    expected: ReplayedCommit::from_commit(expected),
+                    actual: ReplayedCommit::from_sub_dag(actual),
+                });
+                break;
+            }
+            if let Some(expected_rejected) = self.rejected_transactions.get(&index) {
+                let actual_rejected = finalized.get(&index);
+                if actual_rejected != Some(expected_rejected) {
+                    report.divergence = Some(DagReplayDivergence::RejectedTransactions {
+                        index,
+                        expected: expected_rejected.clone(),
+                        actual: actual_rejected.cloned(),
+                    });
+                    break;
+                }
+            }
+            report.matched_commits += 1;
+        }
+        Ok(report)
+    }
+}
+
+/// Outcome of replaying a `DagWindow`.
+#[derive(Clone, Debug)]
+pub struct DagReplayReport {
+    /// Number of recorded commits that were reproduced, before the first divergence.
+    pub matched_commits: usize,
+    /// Number of commits produced by the replay, including the ones past the end of the window.
+    pub produced_commits: usize,
+    /// The first difference between the recorded and the replayed commits, if any.
+    pub divergence: Option<DagReplayDivergence>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum DagReplayDivergence {
+    /// The replay produced a different commit at the same index.
+    Commit {
+        expected: ReplayedCommit,
+        actual: ReplayedCommit,
+    },
+    /// The replay did not produce a commit at this index.
+    MissingCommit { expected: ReplayedCommit },
+    /// The commit matched but was finalized with different rejected transactions,
+    /// or was not finalized by the replay.
+    RejectedTransactions {
+        index: CommitIndex,
+        expected: BTreeMap<BlockRef, Vec<TransactionIndex>>,
+        actual: Option<BTreeMap<BlockRef, Vec<TransactionIndex>>>,
+    },
+}
+
+impl fmt::Display for DagReplayDivergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DagReplayDivergence::Commit { expected, actual } => write!(
+                f,
+                "Commit {} differs: expected {expected:?}, replayed {actual:?}",
+                expected.index
+            ),
+            DagReplayDivergence::MissingCommit { expected } => write!(
+                f,
+                "Commit {} was not replayed: expected {expected:?}",
+                expected.index
+            ),
+            DagReplayDivergence::RejectedTransactions {
+                index,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Commit {index} finalized differently: expected rejected transactions {expected:?}, replayed {actual:?}"
+            ),
+        }
+    }
+}
+
+/// Summary of a recorded or replayed commit.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReplayedCommit {
+    pub index: CommitIndex,
+    pub digest: CommitDigest,
+    pub leader: BlockRef,
+    pub timestamp_ms: BlockTimestampMs,
+    pub blocks: Vec<BlockRef>,
+}
+
+impl ReplayedCommit {
+    fn from_commit(commit: &TrustedCommit) -> Self {
+        Self {
+            index: commit.index(),
+            digest: commit.digest(),
+            leader: commit.leader(),
+            timestamp_ms: commit.timestamp_ms(),
+            blocks: commit.blocks().to_vec(),
+        }
+    }
+
+    fn from_sub_dag(sub_dag: &CommittedSubDag) -> Self {
+        Self {
+            index: sub_dag.commit_ref.index,
+            digest: sub_dag.commit_ref.digest,
+            leader: sub_dag.leader,
+            timestamp_ms: sub_dag.timestamp_ms,
+            blocks: sub_dag
+                .blocks
+                .iter()
+                .map(|block| block.reference())
+                .collect(),
+        }
+    }
+}
+
+/// Runs the commit rule over a `DagState` recovered from a store, the same way `Core::try_commit()`
+/// and `CommitObserver::handle_commit()` do, but without any new block arriving in between.
+struct CommitRuleReplayer {
+    dag_state: Arc<RwLock<DagState>>,
+    leader_schedule: Arc<LeaderSchedule>,
+    committer: UniversalCommitter,
+    linearizer: Linearizer,
+    commit_finalizer: CommitFinalizer,
+    // Keep the output channels open.
+    _blocks_receiver: monitored_mpsc::UnboundedReceiver<CertifiedBlocksOutput>,
+    _commit_receiver: monitored_mpsc::UnboundedReceiver<CommittedSubDag>,
+}
+
+impl CommitRuleReplayer {
+    fn new(context: Arc<Context>, store: Arc<dyn Store>) -> Self {
+        let dag_state = Arc::new(RwLock::new(DagState::new(context.clone(), store)));
+        let leader_schedule = Arc::new(LeaderSchedule::from_store(
+            context.clone(),
+            dag_state.clone(),
+        ));
+        let number_of_leaders = context
+            .protocol_config
+            .mysticeti_num_leaders_per_round()
+            .unwrap_or(1);
+        let committer = UniversalCommitterBuilder::new(
+            context.clone(),
+            leader_schedule.clone(),
+            dag_state.clone(),
+        )
+        .with_number_of_leaders(number_of_leaders)
+        .with_pipeline(true)
+        .build();
+        let linearizer = Linearizer::new(context.clone(), dag_state.clone());
+
+        let (blocks_sender, _blocks_receiver) =
+            monitored_mpsc::unbounded_channel("consensus_block_output");
+        let transaction_certifier = TransactionCertifier::new(
+            context.clone(),
+            Arc::new(NoopBlockVerifier {}),
+            dag_state.clone(),
+            blocks_sender,
+        );
+        transaction_certifier.recover_blocks_after_round(dag_state.read().gc_round());
+        let (commit_sender, _commit_receiver) =
+            monitored_mpsc::unbounded_channel("consensus_commit_output");
+        let commit_finalizer = CommitFinalizer::new(
+            context,
+            dag_state.clone(),
+            transaction_certifier,
+            commit_sender,
+        );
+
+        Self {
+            dag_state,
+            leader_schedule,
+            committer,
+            linearizer,
+            commit_finalizer,
+            _blocks_receiver,
+            _commit_receiver,
+        }
+    }
+
+    /// Decides and linearizes leaders until no more leader can be decided.
+    fn try_commit(&mut self) -> Vec<CommittedSubDag> {
+        let mut last_decided = self.dag_state.read().last_commit_leader();
+        let mut committed_sub_dags = vec![];
+        loop {
+            let mut commits_until_update = self
+                .leader_schedule
+                .commits_until_leader_schedule_update(self.dag_state.clone());
+            if commits_until_update == 0 {
+                self.leader_schedule
+                    .update_leader_schedule_v2(&self.dag_state);
+                commits_until_update = self
+                    .leader_schedule
+                    .commits_until_leader_schedule_update(self.dag_state.clone());
+            }
+
+            let mut decided_leaders = self.committer.try_decide(last_decided);
+            decided_leaders.truncate(commits_until_update);
+            let Some(last) = decided_leaders.last() else {
+                break;
+            };
+            last_decided = last.slot();
+
+            // Unlike Core, keep going when all decided leaders are skipped, since there
+            // will not be any new block to trigger the next attempt.
+            let sequenced_leaders = decided_leaders
+                .into_iter()
+                .filter_map(DecidedLeader::into_committed_block)
+                .collect::<Vec<_>>();
+            let mut sub_dags = self.linearizer.handle_commit(sequenced_leaders);
+            for sub_dag in sub_dags.iter_mut() {
+                sub_dag.decided_with_local_blocks = true;
+            }
+            self.dag_state.write().add_scoring_subdags(sub_dags.clone());
+            committed_sub_dags.extend(sub_dags);
+        }
+        committed_sub_dags
+    }
+}
+
+fn epoch_gc_depth(epoch: &DagWindowEpoch) -> Round {
+    ProtocolConfig::get_for_version(ProtocolVersion::new(epoch.protocol_version), epoch.chain)
+        .gc_depth()
+}
+
+/// Scans backwards from the commit before `first_index`, for the commits that `DagState::new()`
+/// reads when recovering: all commits after the last commit info, and the commits with leaders
+/// above the GC round, whose blocks are marked as committed.
+fn scan_base_commits(
+    store: &dyn Store,
+    first_index: CommitIndex,
+    commit_info_index: CommitIndex,
+    gc_depth: Round,
+) -> ConsensusResult<VecDeque<TrustedCommit>> {
+    let mut base_commits = VecDeque::new();
+    let mut gc_round = None;
+    let mut index = first_index - 1;
+    while index > GENESIS_COMMIT_INDEX {
+        let Some(commit) = store.scan_commits((index..=index).into())?.pop() else {
+            break;
+        };
+        let gc_round =
+            *gc_round.get_or_insert_with(|| commit.leader().round.saturating_sub(gc_depth));
+        let needed_for_commit_status = gc_round == 0 || commit.leader().round > gc_round;
+        if !needed_for_commit_status && index <= commit_info_index {
+            break;
+        }
+        base_commits.push_front(commit);
+        index -= 1;
+    }
+    Ok(base_commits)
+}
+
+fn deserialize_blocks(serialized: &[Bytes]) -> ConsensusResult<Vec<VerifiedBlock>> {
+    serialized
+        .iter()
+        .map(|serialized| {
+            let signed_block: SignedBlock =
+                bcs::from_bytes(serialized).map_err(ConsensusError::MalformedBlock)?;
+            // Blocks were verified before being written to the recorded store.
+            Ok(VerifiedBlock::new_verified(
+                signed_block,
+                serialized.clone(),
+            ))
+        })
+        .collect()
+}
+
+fn deserialize_commits(serialized: &[Bytes]) -> ConsensusResult<Vec<TrustedCommit>> {
+    serialized
+        .iter()
+        .map(|serialized| {
+            Ok(TrustedCommit::new_trusted(
+                bcs::from_bytes(serialized).map_err(ConsensusError::MalformedCommit)?,
+                serialized.clone(),
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_dag_builder::DagBuilder;
+
+    fn test_epoch(num_authorities: usize) -> DagWindowEpoch {
+        let (context, _) = Context::new_for_test(num_authorities);
+        DagWindowEpoch {
+            epoch_start_timestamp_ms: 0,
+            own_index: context.own_index,
+            committee: context.committee,
+            protocol_version: ProtocolVersion::MAX.as_u64(),
+            chain: Chain::Unknown,
+            leader_scoring_strategy: LeaderScoringStrategy::default(),
+        }
+    }
+
+    /// Builds a fully connected DAG and commits it, as an authority would, into the returned store.
+    fn committed_store(epoch: &DagWindowEpoch, num_rounds: Round) -> Arc<MemStore> {
+        let context = Arc::new(epoch.context());
+        let store = Arc::new(MemStore::new());
+        let dag_state = Arc::new(RwLock::new(DagState::new(context.clone(), store.clone())));
+        let mut dag_builder = DagBuilder::new(context.clone());
+        dag_builder
+            .layers(1..=num_rounds)
+            .build()
+            .persist_layers(dag_state.clone());
+        dag_state.write().flush();
+
+        let mut replayer = CommitRuleReplayer::new(context, store.clone());
+        assert!(!replayer.try_commit().is_empty());
+        replayer.dag_state.write().flush();
+        store
+    }
+
+    #[tokio::test]
+    async fn test_replay_dag_window() {
+        let epoch = test_epoch(4);
+        let store = committed_store(&epoch, 30);
+
+        let window = DagWindow::record(store.as_ref(), epoch, (10..=15).into(), 3).unwrap();
+        let window = DagWindow::deserialize(&window.serialize().unwrap()).unwrap();
+        assert_eq!(window.commit_range().unwrap(), (10..=15).into());
+        assert_eq!(window.base_commits.len(), 9);
+
+        let report = window.replay().await.unwrap();
+        assert!(report.divergence.is_none(), "{:?}", report.divergence);
+        assert_eq!(report.matched_commits, 6);
+        assert!(report.produced_commits >= 6);
+    }
+
+    #[tokio::test]
+    async fn test_replay_dag_window_divergence() {
+        let epoch = test_epoch(4);
+        let store = committed_store(&epoch, 30);
+        let window = DagWindow::record(store.as_ref(), epoch, (10..=15).into(), 3).unwrap();
+
+        // Altering the timestamp of a recorded commit diverges at that commit.
+        let mut altered = window.clone();
+        let commit = deserialize_commits(&altered.commits[2..3])
+            .unwrap()
+            .remove(0);
+        let commit = TrustedCommit::new_for_test(
+            commit.index(),
+            commit.previous_digest(),
+            commit.timestamp_ms() + 1,
+            commit.leader(),
+            commit.blocks().to_vec(),
+        );
+        altered.commits[2] = commit.serialized().clone();
+        let report = altered.replay().await.unwrap();
+        assert_eq!(report.matched_commits, 2);
+        let Some(DagReplayDivergence::Commit { expected, actual }) = report.divergence else {
+            panic!("Unexpected divergence {:?}", report.divergence);
+        };
+        assert_eq!(expected.index, 12);
+        assert_eq!(actual.index, 12);
+        assert_eq!(expected.leader, actual.leader);
+        assert_eq!(expected.timestamp_ms, actual.timestamp_ms + 1);
+
+        // Without the blocks above the window, the last leaders cannot be decided.
+        let mut truncated = window.clone();
+        let last_round = deserialize_blocks(&truncated.blocks)
+            .unwrap()
+            .iter()
+            .map(|block| block.round())
+            .max()
+            .unwrap();
+        truncated.blocks = deserialize_blocks(&truncated.blocks)
+            .unwrap()
+            .into_iter()
+            .filter(|block| block.round() + 3 < last_round)
+            .map(|block| block.serialized().clone())
+            .collect();
+        let report = truncated.replay().await.unwrap();
+        assert!(
+            matches!(
+                report.divergence,
+                Some(DagReplayDivergence::MissingCommit { .. })
+            ),
+            "{:?}",
+            report.divergence
+        );
+    }
+
+    #[tokio::test]
+    async fn test_replay_dag_window_leader_scoring_strategy() {
+        let strategy = LeaderScoringStrategy::CertifiedVoteLatency {
+            latency_budget_ms: 500,
+        };
+        let mut epoch = test_epoch(4);
+        epoch.leader_scoring_strategy = strategy.clone();
+        let window = DagWindow::record(
+            committed_store(&epoch, 20).as_ref(),
+            epoch,
+            (5..=8).into(),
+            3,
+        )
+        .unwrap();
+        let window = DagWindow::deserialize(&window.serialize().unwrap()).unwrap();
+        assert_eq!(
+            window.epoch.context().parameters.leader_scoring_strategy,
+            strategy
+        );
+        let report = window.replay().await.unwrap();
+        assert!(report.divergence.is_none(), "{:?}", report.divergence);
+    }
+}
@@ -164,6 +164,9 @@ pub enum ConsensusError {
         commit_index: CommitIndex,
     },
 
+    #[error("Invalid DAG window: {0}")]
+    InvalidDagWindow(String),
+
     #[error("RocksDB failure: {0}")]
     RocksDBFailure(#[from] TypedStoreError),
 
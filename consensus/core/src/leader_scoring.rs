@@ -2,17 +2,17 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use std::{
-    collections::{BTreeMap, HashSet},
+    collections::{BTreeMap, HashMap},
     sync::Arc,
 };
 
-use consensus_config::AuthorityIndex;
-use consensus_types::block::BlockRef;
+use consensus_config::{AuthorityIndex, LeaderScoringStrategy, Stake};
+use consensus_types::block::{BlockRef, BlockTimestampMs};
 use serde::{Deserialize, Serialize};
 
 use crate::{
     block::BlockAPI,
-    commit::{CommitRange, CommittedSubDag},
+    commit::{CommitIndex, CommitRange, CommittedSubDag},
     context::Context,
     stake_aggregator::{QuorumThreshold, StakeAggregator},
 };
@@ -73,6 +73,183 @@ impl ReputationScores {
     }
 }
 
+/// A strategy to compute reputation scores from the votes collected by a `ScoringSubdag`.
+pub(crate) trait ScoringStrategy: Send + Sync {
+    /// Name of the strategy, used in metrics.
+    fn name(&self) -> &'static str;
+
+    /// Returns the score of each authority. Vec index is the `AuthorityIndex`.
+    fn calculate_scores(&self, scoring_subdag: &ScoringSubdag) -> Vec<u64>;
+}
+
+pub(crate) fn scoring_strategy(strategy: &LeaderScoringStrategy) -> Box<dyn ScoringStrategy> {
+    match strategy {
+        LeaderScoringStrategy::DistributedVotes => Box::new(DistributedVoteScoring),
+        LeaderScoringStrategy::CertifiedVoteLatency { latency_budget_ms } => {
+            Box::new(CertifiedVoteLatencyScoring {
+                latency_budget_ms: *latency_budget_ms,
+            })
+        }
+        LeaderScoringStrategy::StakeWeightedDecay { half_life_commits } => {
+            Box::new(StakeWeightedDecayScoring {
+                half_life_commits: (*half_life_commits).max(1),
+            })
+        }
+    }
+}
+
+/// This scoring strategy aims to give scores based on overall vote distribution.
+/// Instead of only giving one point for each vote that is included in 2f+1
+/// blocks. We give a score equal to the amount of stake of all blocks that
+/// included the vote.
+pub(crate) struct DistributedVoteScoring;
+
+impl ScoringStrategy for DistributedVoteScoring {
+    fn name(&self) -> &'static str {
+        "distributed_votes"
+    }
+
+    fn calculate_scores(&self, scoring_subdag: &ScoringSubdag) -> Vec<u64> {
+        let num_authorities = scoring_subdag.context.committee.size();
+        let mut scores_per_authority = vec![0_u64; num_authorities];
+
+        for (vote, stake_agg) in scoring_subdag.votes.iter() {
+            let authority = vote.author;
+            let stake = stake_agg.stake();
+            tracing::trace!(
+                "[{}] scores +{stake} reputation for {authority}!",
+                scoring_subdag.context.own_index,
+            );
+            scores_per_authority[authority.value()] += stake;
+        }
+        scores_per_authority
+    }
+}
+
+/// This scoring strategy rewards authorities whose votes get certified quickly. Each vote
+/// that is strongly linked by a quorum of blocks scores the remainder of the latency budget,
+/// after subtracting the time between the leader it votes for and its certification. Votes that
+/// are never certified do not score.
+pub(crate) struct CertifiedVoteLatencyScoring {
+    latency_budget_ms: u64,
+}
+
+impl ScoringStrategy for CertifiedVoteLatencyScoring {
+    fn name(&self) -> &'static str {
+        "certified_vote_latency"
+    }
+
+    fn calculate_scores(&self, scoring_subdag: &ScoringSubdag) -> Vec<u64> {
+        let num_authorities = scoring_subdag.context.committee.size();
+        let mut scores_per_authority = vec![0_u64; num_authorities];
+
+        for (vote, details) in scoring_subdag.vote_details.iter() {
+            let Some(certified_timestamp_ms) = details.certified_timestamp_ms else {
+                continue;
+            };
+            let latency_ms = certified_timestamp_ms.saturating_sub(details.leader_timestamp_ms);
+            scores_per_authority[vote.author.value()] +=
+                self.latency_budget_ms.saturating_sub(latency_ms);
+        }
+        scores_per_authority
+    }
+}
+
+/// This scoring strategy scores the same stake as `DistributedVoteScoring`, but discounts
+/// older votes so that the schedule reacts faster to authorities that recently became slow.
+/// The stake scored by a vote halves every `half_life_commits` commits between the commit
+/// that included the vote and the last scored commit.
+pub(crate) struct StakeWeightedDecayScoring {
+    half_life_commits: u64,
+}
+
+impl StakeWeightedDecayScoring {
+    // Decayed scores are fractional, so they are scaled before rounding to keep precision.
+    const SCORE_SCALE: f64 = 1000.0;
+}
+
+impl ScoringStrategy for StakeWeightedDecayScoring {
+    fn name(&self) -> &'static str {
+        "stake_weighted_decay"
+    }
+
+    fn calculate_scores(&self, scoring_subdag: &ScoringSubdag) -> Vec<u64> {
+        let num_authorities = scoring_subdag.context.committee.size();
+        let mut scores_per_authority = vec![0_f64; num_authorities];
+        let Some(last_commit_index) = scoring_subdag
+            .commit_range
+            .as_ref()
+            .map(|commit_range| commit_range.end())
+        else {
+            return vec![0; num_authorities];
+        };
+
+        for (vote, stake_agg) in scoring_subdag.votes.iter() {
+            let Some(details) = scoring_subdag.vote_details.get(vote) else {
+                continue;
+            };
+            let age = last_commit_index.saturating_sub(details.commit_index) as f64;
+            let decay = 0.5_f64.powf(age / self.half_life_commits as f64);
+            scores_per_authority[vote.author.value()] += stake_agg.stake() as f64 * decay;
+        }
+        scores_per_authority
+            .into_iter()
+            .map(|score| (score * Self::SCORE_SCALE).round() as u64)
+            .collect()
+    }
+}
+
+/// Details of a vote for a committed leader, used by scoring strategies beyond the stake
+/// of the blocks linking to the vote.
+pub(crate) struct VoteDetails {
+    /// Index of the commit that included the vote.
+    pub(crate) commit_index: CommitIndex,
+    /// Timestamp of the leader block the vote is for.
+    pub(crate) leader_timestamp_ms: BlockTimestampMs,
+    /// The time at which blocks strongly linking to the vote reached a quorum of stake. It is the
+    /// stake weighted quorum timestamp among the linking blocks seen until the quorum is reached.
+    pub(crate) certified_timestamp_ms: Option<BlockTimestampMs>,
+    // Linking blocks seen before the vote is certified.
+    pending_links: Vec<(Stake, BlockTimestampMs)>,
+}
+
+impl VoteDetails {
+    fn new(commit_index: CommitIndex, leader_timestamp_ms: BlockTimestampMs) -> Self {
+        Self {
+            commit_index,
+            leader_timestamp_ms,
+            certified_timestamp_ms: None,
+            pending_links: vec![],
+        }
+    }
+
+    fn add_link(
+        &mut self,
+        stake: Stake,
+        timestamp_ms: BlockTimestampMs,
+        certified: bool,
+        quorum_threshold: Stake,
+    ) {
+        if self.certified_timestamp_ms.is_some() {
+            return;
+        }
+        self.pending_links.push((stake, timestamp_ms));
+        if !certified {
+            return;
+        }
+        self.pending_links
+            .sort_by_key(|(_, timestamp_ms)| *timestamp_ms);
+        let mut total_stake = 0;
+        for (stake, timestamp_ms) in self.pending_links.drain(..) {
+            total_stake += stake;
+            if total_stake >= quorum_threshold {
+                self.certified_timestamp_ms = Some(timestamp_ms);
+                break;
+            }
+        }
+    }
+}
+
 /// ScoringSubdag represents the scoring votes in a collection of subdags across
 /// multiple commits.
 /// These subdags are "scoring" for the purposes of leader schedule change. As
@@ -82,22 +259,28 @@ impl ReputationScores {
 pub(crate) struct ScoringSubdag {
     pub(crate) context: Arc<Context>,
     pub(crate) commit_range: Option<CommitRange>,
-    // Only includes committed leaders for now.
+    // Only includes committed leaders for now, with the timestamps of their blocks.
     // TODO: Include skipped leaders as well
-    pub(crate) leaders: HashSet<BlockRef>,
+    pub(crate) leaders: HashMap<BlockRef, BlockTimestampMs>,
     // A map of votes to the stake of strongly linked blocks that include that vote
     // Note: Including stake aggregator so that we can quickly check if it exceeds
     // quourum threshold and only include those scores for certain scoring strategies.
     pub(crate) votes: BTreeMap<BlockRef, StakeAggregator<QuorumThreshold>>,
+    // Details of each vote in `votes`.
+    pub(crate) vote_details: BTreeMap<BlockRef, VoteDetails>,
+    strategy: Box<dyn ScoringStrategy>,
 }
 
 impl ScoringSubdag {
     pub(crate) fn new(context: Arc<Context>) -> Self {
+        let strategy = scoring_strategy(&context.parameters.leader_scoring_strategy);
         Self {
             context,
             commit_range: None,
-            leaders: HashSet::new(),
+            leaders: HashMap::new(),
             votes: BTreeMap::new(),
+            vote_details: BTreeMap::new(),
+            strategy,
         }
     }
 
@@ -123,7 +306,12 @@ impl ScoringSubdag {
 
             // Add the committed leader to the list of leaders we will be scoring.
             tracing::trace!("Adding new committed leader {} for scoring", subdag.leader);
-            self.leaders.insert(subdag.leader);
+            let leader_timestamp_ms = subdag
+                .blocks
+                .iter()
+                .find(|block| block.reference() == subdag.leader)
+                .map_or(subdag.timestamp_ms, |block| block.timestamp_ms());
+            self.leaders.insert(subdag.leader, leader_timestamp_ms);
 
             // Check each block in subdag. Blocks are in order so we should traverse the
             // oldest blocks first
@@ -137,7 +325,7 @@ impl ScoringSubdag {
 
                     // If a blocks strong linked ancestor is in leaders, then
                     // it's a vote for leader.
-                    if self.leaders.contains(ancestor) {
+                    if let Some(leader_timestamp_ms) = self.leaders.get(ancestor) {
                         // There should never be duplicate references to blocks
                         // with strong linked ancestors to leader.
                         tracing::trace!(
@@ -151,6 +339,10 @@ impl ScoringSubdag {
                                 .is_none(),
                             "Vote {block} already exists. Duplicate vote found for leader {ancestor}"
                         );
+                        self.vote_details.insert(
+                            block.reference(),
+                            VoteDetails::new(subdag.commit_ref.index, *leader_timestamp_ms),
+                        );
                     }
 
                     if let Some(stake) = self.votes.get_mut(ancestor) {
@@ -160,7 +352,15 @@ impl ScoringSubdag {
                             "Found a distributed vote {ancestor} from authority {}",
                             ancestor.author
                         );
-                        stake.add(block.author(), &self.context.committee);
+                        let certified = stake.add(block.author(), &self.context.committee);
+                        if let Some(details) = self.vote_details.get_mut(ancestor) {
+                            details.add_link(
+                                self.context.committee.stake(block.author()),
+                                block.timestamp_ms(),
+                                certified,
+                                self.context.committee.quorum_threshold(),
+                            );
+                        }
                     }
                 }
             }
@@ -168,45 +368,25 @@ impl ScoringSubdag {
     }
 
     // Iterate through votes and calculate scores for each authority based on
-    // distributed vote scoring strategy.
-    pub(crate) fn calculate_distributed_vote_scores(&self) -> ReputationScores {
-        let scores_per_authority = self.distributed_votes_scores();
-
-        // TODO: Normalize scores
-        ReputationScores::new(
-            self.commit_range
-                .clone()
-                .expect("CommitRange should be set if calculate_scores is called."),
-            scores_per_authority,
-        )
-    }
-
-    /// This scoring strategy aims to give scores based on overall vote distribution.
-    /// Instead of only giving one point for each vote that is included in 2f+1
-    /// blocks. We give a score equal to the amount of stake of all blocks that
-    /// included the vote.
-    fn distributed_votes_scores(&self) -> Vec<u64> {
+    // the configured scoring strategy.
+    pub(crate) fn calculate_scores(&self) -> ReputationScores {
         let _s = self
             .context
             .metrics
             .node_metrics
             .scope_processing_time
-            .with_label_values(&["ScoringSubdag::score_distributed_votes"])
+            .with_label_values(&[&format!("ScoringSubdag::score_{}", self.strategy.name())])
             .start_timer();
 
-        let num_authorities = self.context.committee.size();
-        let mut scores_per_authority = vec![0_u64; num_authorities];
+        let scores_per_authority = self.strategy.calculate_scores(self);
 
-        for (vote, stake_agg) in self.votes.iter() {
-            let authority = vote.author;
-            let stake = stake_agg.stake();
-            tracing::trace!(
-                "[{}] scores +{stake} reputation for {authority}!",
-                self.context.own_index,
-            );
-            scores_per_authority[authority.value()] += stake;
-        }
-        scores_per_authority
+        // TODO: Normalize scores
+        ReputationScores::new(
+            self.commit_range
+                .clone()
+                .expect("CommitRange should be set if calculate_scores is called."),
+            scores_per_authority,
+        )
     }
 
     pub(crate) fn scored_subdags_count(&self) -> usize {
@@ -224,12 +404,15 @@ impl ScoringSubdag {
     pub(crate) fn clear(&mut self) {
         self.leaders.clear();
         self.votes.clear();
+        self.vote_details.clear();
         self.commit_range = None;
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use consensus_config::Parameters;
+
     use super::*;
     use crate::test_dag_builder::DagBuilder;
 
@@ -310,8 +493,79 @@ mod tests {
             scoring_subdag.add_subdags(vec![sub_dag]);
         }
 
-        let scores = scoring_subdag.calculate_distributed_vote_scores();
+        let scores = scoring_subdag.calculate_scores();
         assert_eq!(scores.scores_per_authority, vec![5, 5, 5, 5]);
         assert_eq!(scores.commit_range, (1..=4).into());
     }
+
+    /// Builds the DAG of `test_scoring_subdag` and scores it with the given strategy.
+    fn score_test_dag(strategy: LeaderScoringStrategy) -> ReputationScores {
+        let context = Arc::new(Context::new_for_test(4).0.with_parameters(Parameters {
+            leader_scoring_strategy: strategy,
+            ..Default::default()
+        }));
+
+        let mut dag_builder = DagBuilder::new(context.clone());
+        dag_builder.layers(1..=3).build();
+        dag_builder
+            .layer(4)
+            .authorities(vec![
+                AuthorityIndex::new_for_test(1),
+                AuthorityIndex::new_for_test(2),
+                AuthorityIndex::new_for_test(3),
+            ])
+            .skip_block()
+            .build();
+
+        let mut scoring_subdag = ScoringSubdag::new(context.clone());
+        for (sub_dag, _commit) in dag_builder.get_sub_dag_and_commits(1..=4) {
+            scoring_subdag.add_subdags(vec![sub_dag]);
+        }
+        scoring_subdag.calculate_scores()
+    }
+
+    #[tokio::test]
+    async fn test_certified_vote_latency_scoring() {
+        telemetry_subscribers::init_for_testing();
+        let scores = score_test_dag(LeaderScoringStrategy::CertifiedVoteLatency {
+            latency_budget_ms: 3000,
+        });
+        // Only the votes for the leader of round 1 are certified, by round 3 blocks. The leader
+        // has timestamp 1002, and every vote is certified when the quorum of links from round 3
+        // is reached at timestamp 3006, so all votes score the same.
+        assert_eq!(scores.scores_per_authority, vec![996, 996, 996, 996]);
+
+        // Votes for the same leader that are certified earlier score higher, and votes that
+        // are never certified, or only after the budget is spent, do not score.
+        let context = Arc::new(Context::new_for_test(4).0);
+        let mut scoring_subdag = ScoringSubdag::new(context.clone());
+        let leader_timestamp_ms = 1000;
+        for (author, certified_timestamp_ms) in
+            [(0, Some(1100)), (1, Some(1500)), (2, None), (3, Some(5000))]
+        {
+            let vote = BlockRef::new(2, AuthorityIndex::new_for_test(author), Default::default());
+            let mut details = VoteDetails::new(1, leader_timestamp_ms);
+            details.certified_timestamp_ms = certified_timestamp_ms;
+            scoring_subdag.vote_details.insert(vote, details);
+        }
+        let strategy = CertifiedVoteLatencyScoring {
+            latency_budget_ms: 2000,
+        };
+        assert_eq!(
+            strategy.calculate_scores(&scoring_subdag),
+            vec![1900, 1500, 0, 0]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_stake_weighted_decay_scoring() {
+        telemetry_subscribers::init_for_testing();
+        let scores = score_test_dag(LeaderScoringStrategy::StakeWeightedDecay {
+            half_life_commits: 1,
+        });
+        // Votes for the leader of round 1 score 4 stake, and votes for the leader of round 2
+        // score 1 stake. The vote of authority 2 for round 1 is the leader of round 2 and was
+        // committed one commit earlier than the others, so it decays by another half.
+        assert_eq!(scores.scores_per_authority, vec![3000, 3000, 2000, 2500]);
+    }
 }
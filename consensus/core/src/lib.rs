@@ -18,6 +18,7 @@ mod commit_vote_monitor;
 mod context;
 mod core;
 mod core_thread;
+mod dag_replay;
 mod dag_state;
 mod error;
 mod leader_schedule;
@@ -59,6 +60,9 @@ pub use block::{TestBlock, Transaction, VerifiedBlock};
 pub use commit::{CommitAPI, CommitDigest, CommitIndex, CommitRange, CommitRef, CommittedSubDag};
 pub use commit_consumer::{CommitConsumerArgs, CommitConsumerMonitor};
 pub use context::Clock;
+pub use dag_replay::{
+    DagReplayDivergence, DagReplayReport, DagWindow, DagWindowEpoch, ReplayedCommit,
+};
 pub use metrics::Metrics;
 pub use transaction::{
     BlockStatus, ClientError, TransactionClient, TransactionVerifier, ValidationError,
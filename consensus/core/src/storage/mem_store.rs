@@ -142,6 +142,36 @@ impl Store for MemStore {
         Ok(blocks)
     }
 
+    fn scan_blocks_by_author_in_range(
+        &self,
+        author: AuthorityIndex,
+        start_round: Round,
+        end_round: Round,
+    ) -> ConsensusResult<Vec<VerifiedBlock>> {
+        if start_round > end_round {
+            return Ok(vec![]);
+        }
+        let inner = self.inner.read();
+        let mut refs = vec![];
+        for &(author, round, digest) in inner.digests_by_authorities.range((
+            Included((author, start_round, BlockDigest::MIN)),
+            Included((author, end_round, BlockDigest::MAX)),
+        )) {
+            refs.push(BlockRef::new(round, author, digest));
+        }
+        drop(inner);
+        let results = self.read_blocks(refs.as_slice())?;
+        let mut blocks = vec![];
+        for (r, block) in refs.into_iter().zip(results.into_iter()) {
+            if let Some(block) = block {
+                blocks.push(block);
+            } else {
+                panic!("Block {:?} not found!", r);
+            }
+        }
+        Ok(blocks)
+    }
+
     fn scan_last_blocks_by_author(
         &self,
         author: AuthorityIndex,
@@ -214,6 +244,18 @@ impl Store for MemStore {
             .map(|(k, v)| (CommitRef::new(k.0, k.1), v.clone())))
     }
 
+    fn read_last_commit_info_before(
+        &self,
+        before_index: CommitIndex,
+    ) -> ConsensusResult<Option<(CommitRef, CommitInfo)>> {
+        let inner = self.inner.read();
+        Ok(inner
+            .commit_info
+            .range(..(before_index, CommitDigest::MIN))
+            .next_back()
+            .map(|(k, v)| (CommitRef::new(k.0, k.1), v.clone())))
+    }
+
     fn read_last_finalized_commit(&self) -> ConsensusResult<Option<CommitRef>> {
         let inner = self.inner.read();
         Ok(inner
@@ -37,6 +37,14 @@ pub trait Store: Send + Sync {
         start_round: Round,
     ) -> ConsensusResult<Vec<VerifiedBlock>>;
 
+    /// Reads blocks for an authority, with rounds in `[start_round, end_round]`.
+    fn scan_blocks_by_author_in_range(
+        &self,
+        author: AuthorityIndex,
+        start_round: Round,
+        end_round: Round,
+    ) -> ConsensusResult<Vec<VerifiedBlock>>;
+
     // The method returns the last `num_of_rounds` rounds blocks by author in round ascending order.
     // When a `before_round` is defined then the blocks of round `<=before_round` are returned. If not
     // then the max value for round will be used as cut off.
@@ -59,6 +67,12 @@ pub trait Store: Send + Sync {
     /// Reads the last commit info, written atomically with the last commit.
     fn read_last_commit_info(&self) -> ConsensusResult<Option<(CommitRef, CommitInfo)>>;
 
+    /// Reads the last commit info written for a commit with index lower than `before_index`.
+    fn read_last_commit_info_before(
+        &self,
+        before_index: CommitIndex,
+    ) -> ConsensusResult<Option<(CommitRef, CommitInfo)>>;
+
     /// Reads the last finalized commit.
     fn read_last_finalized_commit(&self) -> ConsensusResult<Option<CommitRef>>;
 
@@ -87,6 +87,44 @@ impl RocksDBStore {
         )
     }
 
+    /// Opens an existing RocksDB storage in read only mode, as a secondary instance.
+    /// Writes to the returned store fail. Used for inspecting the store of a running
+    /// or stopped node.
+    #[cfg(not(tidehunter))]
+    pub fn new_read_only(path: &str) -> Self {
+        let tables =
+            Self::get_read_only_handle(path.into(), None, None, MetricConf::new("consensus"));
+        Self {
+            blocks: tables.blocks,
+            digests_by_authorities: tables.digests_by_authorities,
+            commits: tables.commits,
+            commit_votes: tables.commit_votes,
+            commit_info: tables.commit_info,
+            finalized_commits: tables.finalized_commits,
+        }
+    }
+
+    /// Scans the blocks of all authorities with rounds in `[start_round, end_round]`, in
+    /// (round, author) order. Not part of `Store` as consensus only reads blocks by author or
+    /// reference; used for inspecting the store.
+    pub fn scan_blocks_by_rounds(
+        &self,
+        start_round: Round,
+        end_round: Round,
+    ) -> ConsensusResult<Vec<VerifiedBlock>> {
+        let mut blocks = vec![];
+        for kv in self.blocks.safe_range_iter((
+            Included((start_round, AuthorityIndex::MIN, BlockDigest::MIN)),
+            Included((end_round, AuthorityIndex::MAX, BlockDigest::MAX)),
+        )) {
+            let (_, serialized) = kv?;
+            let signed_block: SignedBlock =
+                bcs::from_bytes(&serialized).map_err(ConsensusError::MalformedBlock)?;
+            blocks.push(VerifiedBlock::new_verified(signed_block, serialized));
+        }
+        Ok(blocks)
+    }
+
     #[cfg(tidehunter)]
     pub fn new(path: &str) -> Self {
         tracing::warn!("Consensus store using tidehunter");
@@ -269,6 +307,33 @@ impl Store for RocksDBStore {
         Ok(blocks)
     }
 
+    fn scan_blocks_by_author_in_range(
+        &self,
+        author: AuthorityIndex,
+        start_round: Round,
+        end_round: Round,
+    ) -> ConsensusResult<Vec<VerifiedBlock>> {
+        if start_round > end_round {
+            return Ok(vec![]);
+        }
+        let mut refs = vec![];
+        for kv in self.digests_by_authorities.safe_range_iter((
+            Included((author, start_round, BlockDigest::MIN)),
+            Included((author, end_round, BlockDigest::MAX)),
+        )) {
+            let ((author, round, digest), _) = kv?;
+            refs.push(BlockRef::new(round, author, digest));
+        }
+        let results = self.read_blocks(refs.as_slice())?;
+        let mut blocks = Vec::with_capacity(refs.len());
+        for (r, block) in refs.into_iter().zip(results.into_iter()) {
+            blocks.push(
+                block.unwrap_or_else(|| panic!("Storage inconsistency: block {:?} not found!", r)),
+            );
+        }
+        Ok(blocks)
+    }
+
     // The method returns the last `num_of_rounds` rounds blocks by author in round ascending order.
     // When a `before_round` is defined then the blocks of round `<=before_round` are returned. If not
     // then the max value for round will be used as cut off.
@@ -359,6 +424,24 @@ impl Store for RocksDBStore {
         Ok(Some((CommitRef::new(key.0, key.1), commit_info)))
     }
 
+    fn read_last_commit_info_before(
+        &self,
+        before_index: CommitIndex,
+    ) -> ConsensusResult<Option<(CommitRef, CommitInfo)>> {
+        let Some(upper_index) = before_index.checked_sub(1) else {
+            return Ok(None);
+        };
+        let Some(result) = self
+            .commit_info
+            .reversed_safe_iter_with_bounds(None, Some((upper_index, CommitDigest::MAX)))?
+            .next()
+        else {
+            return Ok(None);
+        };
+        let (key, commit_info) = result.map_err(ConsensusError::RocksDBFailure)?;
+        Ok(Some((CommitRef::new(key.0, key.1), commit_info)))
+    }
+
     fn read_last_finalized_commit(&self) -> ConsensusResult<Option<CommitRef>> {
         let Some(result) = self
             .finalized_commits
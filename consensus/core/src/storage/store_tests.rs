@@ -9,7 +9,8 @@ use tempfile::TempDir;
 use super::{Store, WriteBatch, mem_store::MemStore, rocksdb_store::RocksDBStore};
 use crate::{
     block::{TestBlock, VerifiedBlock},
-    commit::{CommitDigest, TrustedCommit},
+    commit::{CommitDigest, CommitInfo, CommitRef, TrustedCommit},
+    leader_scoring::ReputationScores,
 };
 
 /// Test fixture for store tests. Wraps around various store implementations.
@@ -285,3 +286,77 @@ async fn read_and_scan_commits(
         assert_eq!(scanned_commits, written_commits,);
     }
 }
+
+#[rstest]
+#[tokio::test]
+async fn read_last_commit_info_before(
+    #[values(new_rocksdb_teststore(), new_mem_teststore())] test_store: TestStore,
+) {
+    let store = test_store.store();
+
+    let commit_info = |index| {
+        (
+            CommitRef::new(index, CommitDigest::MIN),
+            CommitInfo {
+                committed_rounds: vec![index; 4],
+                reputation_scores: ReputationScores::new((1..=index).into(), vec![0; 4]),
+            },
+        )
+    };
+    store
+        .write(WriteBatch::default().commit_info(vec![commit_info(10), commit_info(20)]))
+        .unwrap();
+
+    let before = |index| {
+        store
+            .read_last_commit_info_before(index)
+            .expect("Read commit info should not fail")
+            .map(|(commit_ref, _)| commit_ref.index)
+    };
+    assert_eq!(before(0), None);
+    assert_eq!(before(10), None);
+    assert_eq!(before(11), Some(10));
+    assert_eq!(before(20), Some(10));
+    assert_eq!(before(21), Some(20));
+    assert_eq!(before(100), Some(20));
+}
+
+#[rstest]
+#[tokio::test]
+async fn scan_blocks_by_author_in_range(
+    #[values(new_rocksdb_teststore(), new_mem_teststore())] test_store: TestStore,
+) {
+    let store = test_store.store();
+
+    let written_blocks: Vec<VerifiedBlock> = (1..=5)
+        .flat_map(|round| {
+            (0..2).map(move |author| {
+                VerifiedBlock::new_for_test(TestBlock::new(round, author).build())
+            })
+        })
+        .collect();
+    store
+        .write(WriteBatch::default().blocks(written_blocks.clone()))
+        .unwrap();
+
+    let scanned_rounds = |author: u32, start_round, end_round| {
+        store
+            .scan_blocks_by_author_in_range(
+                AuthorityIndex::new_for_test(author),
+                start_round,
+                end_round,
+            )
+            .expect("Scan blocks should not fail")
+            .iter()
+            .map(|block| {
+                assert_eq!(block.author(), AuthorityIndex::new_for_test(author));
+                block.round()
+            })
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(scanned_rounds(1, 2, 4), vec![2, 3, 4]);
+    assert_eq!(scanned_rounds(0, 0, 1), vec![1]);
+    assert_eq!(scanned_rounds(0, 5, 10), vec![5]);
+    assert!(scanned_rounds(0, 6, 10).is_empty());
+    assert!(scanned_rounds(1, 4, 2).is_empty());
+}
@@ -9,7 +9,9 @@ use std::{
 
 use anyhow::Result;
 use arc_swap::ArcSwapOption;
-use consensus_config::{AuthorityIndex, Committee, NetworkKeyPair, Parameters, ProtocolKeyPair};
+use consensus_config::{
+    AuthorityIndex, Committee, LeaderScoringStrategy, NetworkKeyPair, Parameters, ProtocolKeyPair,
+};
 use consensus_core::{
     Clock, CommitConsumerArgs, CommitConsumerMonitor, CommittedSubDag, ConsensusAuthority,
     NetworkType, TransactionClient, TransactionVerifier, to_socket_addr,
@@ -34,6 +36,9 @@ pub struct Config {
     pub clock_drift: BlockTimestampMs,
     pub protocol_config: ProtocolConfig,
     pub transaction_verifier: Arc<dyn TransactionVerifier>,
+    pub leader_scoring_strategy: LeaderScoringStrategy,
+    /// Overrides the minimum delay between proposals, to simulate a slow authority.
+    pub min_round_delay: Option<Duration>,
 }
 
 pub struct AuthorityNode {
@@ -272,19 +277,25 @@ pub(crate) async fn make_authority(
         protocol_config,
         clock_drift,
         transaction_verifier,
+        leader_scoring_strategy,
+        min_round_delay,
     } = config;
 
     let registry = Registry::new();
 
     // Cache less blocks to exercise commit sync.
-    let parameters = Parameters {
+    let mut parameters = Parameters {
         db_path: db_dir.path().to_path_buf(),
         dag_state_cached_rounds: 5,
         commit_sync_parallel_fetches: 2,
         commit_sync_batch_size: 3,
         sync_last_known_own_block_timeout: Duration::from_millis(2_000),
+        leader_scoring_strategy,
         ..Default::default()
     };
+    if let Some(min_round_delay) = min_round_delay {
+        parameters.min_round_delay = min_round_delay;
+    }
 
     let protocol_keypair = keypairs[authority_index].1.clone();
     let network_keypair = keypairs[authority_index].0.clone();
@@ -4,11 +4,14 @@
 #[cfg(msim)]
 mod consensus_tests {
     use std::sync::atomic::{AtomicU64, Ordering};
-    use std::{sync::Arc, time::Duration};
+    use std::{
+        sync::Arc,
+        time::{Duration, SystemTime, UNIX_EPOCH},
+    };
 
     use consensus_config::{
-        Authority, AuthorityIndex, AuthorityKeyPair, Committee, Epoch, NetworkKeyPair,
-        ProtocolKeyPair, Stake,
+        Authority, AuthorityIndex, AuthorityKeyPair, Committee, Epoch, LeaderScoringStrategy,
+        NetworkKeyPair, ProtocolKeyPair, Stake,
     };
     use consensus_core::NoopTransactionVerifier;
     use consensus_core::{BlockAPI, BlockStatus, TransactionVerifier, ValidationError};
@@ -81,6 +84,8 @@ mod consensus_tests {
                 protocol_config: protocol_config.clone(),
                 clock_drift: clock_drifts[authority_index.value() as usize],
                 transaction_verifier: Arc::new(NoopTransactionVerifier {}),
+                leader_scoring_strategy: LeaderScoringStrategy::default(),
+                min_round_delay: None,
             };
             let node = AuthorityNode::new(config);
 
@@ -174,6 +179,8 @@ mod consensus_tests {
                 transaction_verifier: Arc::new(RandomizedTransactionVerifier::new(
                     REJECTION_PROBABILITY,
                 )),
+                leader_scoring_strategy: LeaderScoringStrategy::default(),
+                min_round_delay: None,
             };
             let node = AuthorityNode::new(config);
             node.start().await.unwrap();
@@ -335,6 +342,100 @@ mod consensus_tests {
             total_rejected_transactions
         );
     }
+
+    // Compares the commit latency of the leader scoring strategies, when one of the authorities
+    // proposes blocks much slower than the rest of the committee. Each strategy runs on a fresh
+    // committee, and the commit latency is measured on a healthy authority.
+    #[sim_test(config = "test_config()")]
+    async fn test_leader_scoring_strategies_with_slow_authority() {
+        telemetry_subscribers::init_for_testing();
+        let db_registry = Registry::new();
+        DBMetrics::init(RegistryService::new(db_registry));
+
+        const NUM_OF_AUTHORITIES: usize = 7;
+        const SLOW_AUTHORITY: usize = NUM_OF_AUTHORITIES - 1;
+        const RUN_DURATION: Duration = Duration::from_secs(120);
+
+        let strategies = [
+            LeaderScoringStrategy::DistributedVotes,
+            LeaderScoringStrategy::CertifiedVoteLatency {
+                latency_budget_ms: 2_000,
+            },
+            LeaderScoringStrategy::StakeWeightedDecay {
+                half_life_commits: 50,
+            },
+        ];
+
+        let mut results = vec![];
+        for strategy in strategies {
+            let (committee, keypairs) =
+                local_committee_and_keys(0, [1; NUM_OF_AUTHORITIES].to_vec());
+            let protocol_config = ProtocolConfig::get_for_max_version_UNSAFE();
+
+            let mut authorities = Vec::with_capacity(committee.size());
+            for (authority_index, _authority_info) in committee.authorities() {
+                let min_round_delay = (authority_index.value() == SLOW_AUTHORITY)
+                    .then_some(Duration::from_millis(2_000));
+                let config = Config {
+                    authority_index,
+                    db_dir: Arc::new(TempDir::new().unwrap()),
+                    committee: committee.clone(),
+                    keypairs: keypairs.clone(),
+                    network_type: sui_protocol_config::ConsensusNetwork::Tonic,
+                    boot_counter: 0,
+                    protocol_config: protocol_config.clone(),
+                    clock_drift: 0,
+                    transaction_verifier: Arc::new(NoopTransactionVerifier {}),
+                    leader_scoring_strategy: strategy.clone(),
+                    min_round_delay,
+                };
+                let node = AuthorityNode::new(config);
+                node.start().await.unwrap();
+                node.spawn_committed_subdag_consumer().unwrap();
+                authorities.push(node);
+            }
+
+            // Measure the latency from the leader timestamp to the commit output on a healthy authority.
+            let mut commit_receiver = authorities[0].commit_consumer_receiver();
+            let mut num_commits = 0_u64;
+            let mut total_latency_ms = 0_u64;
+            let _ = timeout(RUN_DURATION, async {
+                while let Some(sub_dag) = commit_receiver.recv().await {
+                    let now_ms = SystemTime::now()
+                        .duration_since(UNIX_EPOCH)
+                        .unwrap()
+                        .as_millis() as u64;
+                    total_latency_ms += now_ms.saturating_sub(sub_dag.timestamp_ms);
+                    num_commits += 1;
+                }
+            })
+            .await;
+
+            for authority in &authorities {
+                authority.stop();
+            }
+
+            assert!(num_commits > 0, "No commits with strategy {strategy:?}");
+            let average_latency_ms = total_latency_ms / num_commits;
+            tracing::info!(
+                "Strategy {strategy:?}: {num_commits} commits, average commit latency {average_latency_ms}ms"
+            );
+            results.push((strategy, num_commits, average_latency_ms));
+        }
+
+        for (strategy, num_commits, average_latency_ms) in &results {
+            tracing::info!(
+                "Leader scoring strategy {strategy:?}: {num_commits} commits, average commit latency {average_latency_ms}ms"
+            );
+            // The slow authority should be excluded from the leader schedule by every strategy, so
+            // the commit latency stays well below its round delay.
+            assert!(
+                *average_latency_ms < 2_000,
+                "Average commit latency {average_latency_ms}ms with strategy {strategy:?} is too high"
+            );
+        }
+    }
+
     /// Creates a committee for local testing, and the corresponding key pairs for the authorities.
     pub fn local_committee_and_keys(
         epoch: Epoch,
@@ -1129,6 +1129,35 @@ pub struct AuthorityStorePruningConfig {
     pub enable_compaction_filter: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub num_epochs_to_retain_for_indexes: Option<u64>,
+    /// keeps the full history of the configured objects, packages and addresses, regardless of
+    /// the retention settings above, so that everything else can be pruned aggressively.
+    /// It is enforced through the compaction filter, which is enabled whenever a policy is set.
+    #[serde(default, skip_serializing_if = "HistoryRetentionConfig::is_empty")]
+    pub history_retention: HistoryRetentionConfig,
+}
+
+/// Selects the history that the pruner never removes.
+///
+/// A transaction is retained if it touches any of the configured object IDs (by reading or
+/// writing them), calls into or publishes any of the configured packages, or is sent or sponsored
+/// by, or transfers objects to, any of the configured addresses. Retained transactions keep their
+/// transaction, effects and events, as well as the object versions they modified. All versions of
+/// the configured objects are retained.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct HistoryRetentionConfig {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub object_ids: Vec<ObjectID>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub package_ids: Vec<ObjectID>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub addresses: Vec<SuiAddress>,
+}
+
+impl HistoryRetentionConfig {
+    pub fn is_empty(&self) -> bool {
+        self.object_ids.is_empty() && self.package_ids.is_empty() && self.addresses.is_empty()
+    }
 }
 
 fn default_num_latest_epoch_dbs_to_retain() -> usize {
@@ -1170,6 +1199,7 @@ impl Default for AuthorityStorePruningConfig {
             smooth: true,
             enable_compaction_filter: cfg!(test) || cfg!(msim),
             num_epochs_to_retain_for_indexes: None,
+            history_retention: HistoryRetentionConfig::default(),
         }
     }
 }
@@ -1199,6 +1229,12 @@ impl AuthorityStorePruningConfig {
     pub fn set_killswitch_tombstone_pruning(&mut self, killswitch_tombstone_pruning: bool) {
         self.killswitch_tombstone_pruning = killswitch_tombstone_pruning;
     }
+
+    /// Whether the objects table is pruned using the compaction filter. History retention relies
+    /// on the compaction filter, so it is enabled whenever a retention policy is configured.
+    pub fn enable_compaction_filter(&self) -> bool {
+        self.enable_compaction_filter || !self.history_retention.is_empty()
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -187,6 +187,24 @@ pub struct StateSyncConfig {
     /// If unspecified, this will set to default value
     #[serde(skip_serializing_if = "Option::is_none")]
     pub wait_interval_when_no_peer_to_sync_content_ms: Option<u64>,
+
+    /// Half-life of the observations used to score peers. Peers are ranked by their observed
+    /// latency and failure rate when requesting checkpoint summaries and contents, and an
+    /// observation's weight halves every half-life, so peers that were slow or unreliable in the
+    /// past are retried eventually.
+    ///
+    /// If unspecified, this will default to `60,000` milliseconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub peer_score_half_life_ms: Option<u64>,
+
+    /// Percentile of recently observed latencies after which a request for checkpoint summaries
+    /// or contents is hedged, by sending the same request to the next best peer, and using
+    /// whichever response arrives first. Must be between 0 and 1, and values of 1 or more disable
+    /// hedging.
+    ///
+    /// If unspecified, this will default to `0.95`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hedge_latency_percentile: Option<f64>,
 }
 
 impl StateSyncConfig {
@@ -252,6 +270,25 @@ impl StateSyncConfig {
             .unwrap_or(self.default_wait_interval_when_no_peer_to_sync_content())
     }
 
+    pub fn peer_score_half_life(&self) -> Duration {
+        const PEER_SCORE_HALF_LIFE_MS: u64 = 60_000; // 1 minute
+
+        Duration::from_millis(
+            self.peer_score_half_life_ms
+                .unwrap_or(PEER_SCORE_HALF_LIFE_MS),
+        )
+    }
+
+    /// The latency percentile after which requests are hedged, or `None` if hedging is disabled.
+    pub fn hedge_latency_percentile(&self) -> Option<f64> {
+        const HEDGE_LATENCY_PERCENTILE: f64 = 0.95;
+
+        let percentile = self
+            .hedge_latency_percentile
+            .unwrap_or(HEDGE_LATENCY_PERCENTILE);
+        (0.0..1.0).contains(&percentile).then_some(percentile)
+    }
+
     fn default_wait_interval_when_no_peer_to_sync_content(&self) -> Duration {
         if cfg!(msim) {
             Duration::from_secs(5)
@@ -52,6 +52,17 @@ pub struct RpcConfig {
     /// Defaults to `false`, with authenticated events indexing and API disabled
     #[serde(skip_serializing_if = "Option::is_none")]
     pub authenticated_events_indexing: Option<bool>,
+
+    /// Enable indexing of transactions and events by filter
+    ///
+    /// This controls whether transactions are indexed by sender, affected address, affected
+    /// object, Move call and emitted event type, and events by sender, emitting module and type,
+    /// and whether the APIs which list transactions and query events by these filters are
+    /// available. Requires `enable-indexing`.
+    ///
+    /// Defaults to `false`, with transaction and event indexing and APIs disabled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction_and_event_indexing: Option<bool>,
 }
 
 impl RpcConfig {
@@ -79,6 +90,10 @@ impl RpcConfig {
     pub fn authenticated_events_indexing(&self) -> bool {
         self.authenticated_events_indexing.unwrap_or(false)
     }
+
+    pub fn transaction_and_event_indexing(&self) -> bool {
+        self.transaction_and_event_indexing.unwrap_or(false)
+    }
 }
 
 #[derive(Clone, Debug, Default, serde::Deserialize, serde::Serialize)]
@@ -6,7 +6,7 @@ use crate::accumulators::AccumulatorSettlementTxBuilder;
 use crate::accumulators::balance_read::AccountBalanceRead;
 use crate::checkpoints::CheckpointBuilderError;
 use crate::checkpoints::CheckpointBuilderResult;
-use crate::congestion_tracker::CongestionTracker;
+use crate::congestion_tracker::{CongestionTracker, exclusively_accessed_objects};
 use crate::consensus_adapter::ConsensusOverloadChecker;
 use crate::execution_cache::ExecutionCacheTraitPointers;
 use crate::execution_cache::TransactionCacheRead;
@@ -145,7 +145,8 @@ use sui_types::messages_grpc::{
 use sui_types::metrics::{BytecodeVerifierMetrics, LimitsMetrics};
 use sui_types::object::{MoveObject, OBJECT_START_VERSION, Owner, PastObjectRead};
 use sui_types::storage::{
-    BackingPackageStore, BackingStore, ObjectKey, ObjectOrTombstone, ObjectStore, WriteKind,
+    BackingPackageStore, BackingStore, GasPriceSuggestion, ObjectKey, ObjectOrTombstone,
+    ObjectStore, WriteKind,
 };
 use sui_types::sui_system_state::SuiSystemStateTrait;
 use sui_types::sui_system_state::epoch_start_sui_system_state::EpochStartSystemStateTrait;
@@ -2887,6 +2888,30 @@ impl AuthorityState {
         )
     }
 
+    /// Suggest a gas price for `transaction`, if provided, that also accesses `object_ids`
+    /// mutably, based on recent congestion on the shared objects it accesses mutably and on
+    /// `object_ids`.
+    pub fn get_gas_price_suggestion(
+        &self,
+        transaction: Option<&TransactionData>,
+        object_ids: &[ObjectID],
+    ) -> GasPriceSuggestion {
+        let mut objects: Vec<_> = transaction
+            .into_iter()
+            .flat_map(exclusively_accessed_objects)
+            .collect();
+
+        for object_id in object_ids {
+            if !objects.contains(object_id) {
+                objects.push(*object_id);
+            }
+        }
+
+        let epoch_store = self.load_epoch_store_one_call_per_task();
+        self.congestion_tracker
+            .get_gas_price_suggestion(objects, epoch_store.reference_gas_price())
+    }
+
     // Only used for testing because of how epoch store is loaded.
     pub fn reference_gas_price_for_testing(&self) -> Result<u64, anyhow::Error> {
         let epoch_store = self.epoch_store_for_testing();
@@ -17,12 +17,12 @@ use prometheus::{
 #[cfg(tidehunter)]
 use serde::de::DeserializeOwned;
 use std::cmp::{max, min};
-use std::collections::{BTreeSet, HashMap};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::sync::atomic::AtomicU64;
 use std::sync::{Mutex, Weak};
 use std::time::{SystemTime, UNIX_EPOCH};
 use std::{sync::Arc, time::Duration};
-use sui_config::node::AuthorityStorePruningConfig;
+use sui_config::node::{AuthorityStorePruningConfig, HistoryRetentionConfig};
 use sui_types::committee::EpochId;
 use sui_types::effects::TransactionEffects;
 use sui_types::effects::TransactionEffectsAPI;
@@ -30,8 +30,9 @@ use sui_types::message_envelope::Message;
 use sui_types::messages_checkpoint::{
     CheckpointContents, CheckpointDigest, CheckpointSequenceNumber,
 };
+use sui_types::transaction::{TransactionData, TransactionDataAPI};
 use sui_types::{
-    base_types::{ObjectID, SequenceNumber, TransactionDigest, VersionNumber},
+    base_types::{ObjectID, SequenceNumber, SuiAddress, TransactionDigest, VersionNumber},
     storage::ObjectKey,
 };
 use tokio::sync::oneshot::{self, Sender};
@@ -75,6 +76,8 @@ pub struct AuthorityStorePruningMetrics {
     pub last_pruned_indexes_transaction: IntGauge,
     pub num_epochs_to_retain_for_objects: IntGauge,
     pub num_epochs_to_retain_for_checkpoints: IntGauge,
+    pub num_retained_objects: IntCounter,
+    pub num_retained_transactions: IntCounter,
 }
 
 impl AuthorityStorePruningMetrics {
@@ -122,6 +125,18 @@ impl AuthorityStorePruningMetrics {
                 registry
             )
             .unwrap(),
+            num_retained_objects: register_int_counter_with_registry!(
+                "num_retained_objects",
+                "Number of object versions kept by the history retention policy",
+                registry
+            )
+            .unwrap(),
+            num_retained_transactions: register_int_counter_with_registry!(
+                "num_retained_transactions",
+                "Number of transactions kept by the history retention policy",
+                registry
+            )
+            .unwrap(),
         };
         Arc::new(this)
     }
@@ -137,8 +152,89 @@ pub enum PruningMode {
     Checkpoints,
 }
 
+/// The history that must survive pruning, as configured by [HistoryRetentionConfig].
+#[derive(Debug, Clone, Default)]
+pub struct HistoryRetentionPolicy {
+    object_ids: HashSet<ObjectID>,
+    package_ids: HashSet<ObjectID>,
+    addresses: HashSet<SuiAddress>,
+}
+
+impl HistoryRetentionPolicy {
+    pub fn new(config: &HistoryRetentionConfig) -> Self {
+        Self {
+            object_ids: config.object_ids.iter().copied().collect(),
+            package_ids: config.package_ids.iter().copied().collect(),
+            addresses: config.addresses.iter().copied().collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.object_ids.is_empty() && self.package_ids.is_empty() && self.addresses.is_empty()
+    }
+
+    /// Whether every version of `object_id` is retained.
+    pub fn retains_object(&self, object_id: &ObjectID) -> bool {
+        self.object_ids.contains(object_id)
+    }
+
+    /// Whether the transaction data is needed to decide if a transaction is retained, or its
+    /// effects are enough.
+    fn needs_transaction_data(&self) -> bool {
+        !self.package_ids.is_empty() || !self.addresses.is_empty()
+    }
+
+    /// Whether the transaction with these effects is retained. Rules that depend on the contents
+    /// of the transaction are only checked if `transaction` is provided.
+    pub fn retains_transaction(
+        &self,
+        effects: &TransactionEffects,
+        transaction: Option<&TransactionData>,
+    ) -> bool {
+        if self.is_empty() {
+            return false;
+        }
+
+        // Packages are objects too, so publishing or upgrading a package shows up as a change to
+        // its ID.
+        let is_retained_id =
+            |id: &ObjectID| self.object_ids.contains(id) || self.package_ids.contains(id);
+        if effects
+            .object_changes()
+            .iter()
+            .any(|change| is_retained_id(&change.id))
+            || effects
+                .input_consensus_objects()
+                .iter()
+                .any(|object| is_retained_id(&object.id_and_version().0))
+        {
+            return true;
+        }
+
+        if !self.addresses.is_empty()
+            && effects.all_changed_objects().iter().any(|(_, owner, _)| {
+                owner
+                    .get_owner_address()
+                    .is_ok_and(|address| self.addresses.contains(&address))
+            })
+        {
+            return true;
+        }
+
+        transaction.is_some_and(|transaction| {
+            self.addresses.contains(&transaction.sender())
+                || self.addresses.contains(&transaction.gas_owner())
+                || transaction
+                    .move_calls()
+                    .iter()
+                    .any(|(package, _, _)| self.package_ids.contains(*package))
+        })
+    }
+}
+
 impl AuthorityStorePruner {
-    /// prunes old versions of objects based on transaction effects
+    /// prunes old versions of objects based on transaction effects, except for the versions
+    /// retained by `retention`, or modified by `retained_transactions`
     async fn prune_objects(
         transaction_effects: Vec<TransactionEffects>,
         perpetual_db: &Arc<AuthorityPerpetualTables>,
@@ -146,6 +242,8 @@ impl AuthorityStorePruner {
         checkpoint_number: CheckpointSequenceNumber,
         metrics: Arc<AuthorityStorePruningMetrics>,
         enable_pruning_tombstones: bool,
+        retention: &HistoryRetentionPolicy,
+        retained_transactions: &HashSet<TransactionDigest>,
     ) -> anyhow::Result<()> {
         let _scope = monitored_scope("ObjectsLivePruner");
         let mut wb = perpetual_db.objects.batch();
@@ -154,15 +252,23 @@ impl AuthorityStorePruner {
         // Collect objects keys that need to be deleted from `transaction_effects`.
         let mut live_object_keys_to_prune = vec![];
         let mut object_tombstones_to_prune = vec![];
+        let mut retained_object_keys = HashSet::new();
         for effects in &transaction_effects {
+            let is_retained = retained_transactions.contains(effects.transaction_digest());
             for (object_id, seq_number) in effects.modified_at_versions() {
-                live_object_keys_to_prune.push(ObjectKey(object_id, seq_number));
+                if is_retained || retention.retains_object(&object_id) {
+                    retained_object_keys.insert(ObjectKey(object_id, seq_number));
+                } else {
+                    live_object_keys_to_prune.push(ObjectKey(object_id, seq_number));
+                }
             }
 
-            if enable_pruning_tombstones {
+            if enable_pruning_tombstones && !is_retained {
                 for deleted_object_key in effects.all_tombstones() {
-                    object_tombstones_to_prune
-                        .push(ObjectKey(deleted_object_key.0, deleted_object_key.1));
+                    if !retention.retains_object(&deleted_object_key.0) {
+                        object_tombstones_to_prune
+                            .push(ObjectKey(deleted_object_key.0, deleted_object_key.1));
+                    }
                 }
             }
         }
@@ -173,9 +279,22 @@ impl AuthorityStorePruner {
         metrics
             .num_pruned_tombstones
             .inc_by(object_tombstones_to_prune.len() as u64);
+        metrics
+            .num_retained_objects
+            .inc_by(retained_object_keys.len() as u64);
+
+        // Objects with some, but not all, of their versions in this batch retained. A range
+        // delete would also remove the retained versions in between, so these are deleted
+        // version by version instead.
+        let partially_retained: HashSet<ObjectID> = retained_object_keys
+            .iter()
+            .map(|ObjectKey(object_id, _)| *object_id)
+            .filter(|object_id| !retention.retains_object(object_id))
+            .collect();
 
         let mut updates: HashMap<ObjectID, (VersionNumber, VersionNumber)> = HashMap::new();
-        for ObjectKey(object_id, seq_number) in live_object_keys_to_prune {
+        for ObjectKey(object_id, seq_number) in &live_object_keys_to_prune {
+            let (object_id, seq_number) = (*object_id, *seq_number);
             updates
                 .entry(object_id)
                 .and_modify(|range| *range = (min(range.0, seq_number), max(range.1, seq_number)))
@@ -194,6 +313,14 @@ impl AuthorityStorePruner {
                         std::iter::once((object_id, max_version)),
                     )?;
                 }
+                None if partially_retained.contains(&object_id) => {
+                    wb.delete_batch(
+                        &perpetual_db.objects,
+                        live_object_keys_to_prune
+                            .iter()
+                            .filter(|ObjectKey(id, _)| *id == object_id),
+                    )?;
+                }
                 None => {
                     let start_range = ObjectKey(object_id, min_version);
                     let end_range = ObjectKey(object_id, (max_version.value() + 1).into());
@@ -202,6 +329,22 @@ impl AuthorityStorePruner {
             }
         }
 
+        // The compaction filter removes every version up to the tombstone, so it needs to know
+        // which of them to keep. Versions of retained objects are recognized by their ID.
+        if let Some(ref mut batch) = pruner_db_wb
+            && !partially_retained.is_empty()
+        {
+            batch.insert_batch(
+                &pruner_db
+                    .expect("invariant checked")
+                    .retained_object_versions,
+                retained_object_keys
+                    .iter()
+                    .filter(|ObjectKey(object_id, _)| partially_retained.contains(object_id))
+                    .map(|key| (*key, ())),
+            )?;
+        }
+
         // When enable_pruning_tombstones is enabled, instead of using range deletes, we need to do a scan of all the keys
         // for the deleted objects and then do point deletes to delete all the existing keys. This is because to improve read
         // performance, we set `ignore_range_deletions` on all read options, and using range delete to delete tombstones
@@ -216,7 +359,14 @@ impl AuthorityStorePruner {
                 ) {
                     let (object_key, _) = result?;
                     assert_eq!(object_key.0, object_id);
-                    object_keys_to_delete.push(object_key);
+                    if !Self::is_retained_object_version(
+                        &object_key,
+                        &retained_object_keys,
+                        pruner_db,
+                        retention,
+                    )? {
+                        object_keys_to_delete.push(object_key);
+                    }
                 }
             }
 
@@ -233,6 +383,63 @@ impl AuthorityStorePruner {
         Ok(())
     }
 
+    /// Whether `object_key` was modified by a retained transaction, either in the batch being
+    /// pruned (`retained_object_keys`), or in an earlier one.
+    fn is_retained_object_version(
+        object_key: &ObjectKey,
+        retained_object_keys: &HashSet<ObjectKey>,
+        pruner_db: Option<&Arc<AuthorityPrunerTables>>,
+        retention: &HistoryRetentionPolicy,
+    ) -> anyhow::Result<bool> {
+        if retention.is_empty() {
+            return Ok(false);
+        }
+
+        if retained_object_keys.contains(object_key) {
+            return Ok(true);
+        }
+
+        Ok(match pruner_db {
+            Some(db) => db.retained_object_versions.contains_key(object_key)?,
+            None => false,
+        })
+    }
+
+    /// The transactions among `effects` that are retained by `retention`.
+    fn retained_transactions(
+        perpetual_db: &AuthorityPerpetualTables,
+        retention: &HistoryRetentionPolicy,
+        effects: &[TransactionEffects],
+    ) -> anyhow::Result<HashSet<TransactionDigest>> {
+        if retention.is_empty() {
+            return Ok(HashSet::new());
+        }
+
+        let transactions = if retention.needs_transaction_data() {
+            perpetual_db
+                .transactions
+                .multi_get(effects.iter().map(|effects| effects.transaction_digest()))?
+        } else {
+            std::iter::repeat_with(|| None)
+                .take(effects.len())
+                .collect()
+        };
+
+        Ok(effects
+            .iter()
+            .zip(transactions)
+            .filter(|(effects, transaction)| {
+                retention.retains_transaction(
+                    effects,
+                    transaction
+                        .as_ref()
+                        .map(|transaction| transaction.data().transaction_data()),
+                )
+            })
+            .map(|(effects, _)| *effects.transaction_digest())
+            .collect())
+    }
+
     fn prune_checkpoints(
         perpetual_db: &Arc<AuthorityPerpetualTables>,
         checkpoint_db: &Arc<CheckpointStore>,
@@ -242,13 +449,17 @@ impl AuthorityStorePruner {
         checkpoint_content_to_prune: Vec<CheckpointContents>,
         effects_to_prune: &Vec<TransactionEffects>,
         metrics: Arc<AuthorityStorePruningMetrics>,
+        retained_transactions: &HashSet<TransactionDigest>,
     ) -> anyhow::Result<()> {
         let _scope = monitored_scope("EffectsLivePruner");
 
+        // Retained transactions keep their transaction, effects and events, while the checkpoints
+        // that contain them are pruned.
         let mut perpetual_batch = perpetual_db.objects.batch();
         let transactions: Vec<_> = checkpoint_content_to_prune
             .iter()
             .flat_map(|content| content.iter().map(|tx| tx.transaction))
+            .filter(|digest| !retained_transactions.contains(digest))
             .collect();
 
         perpetual_batch.delete_batch(&perpetual_db.transactions, transactions.iter())?;
@@ -260,6 +471,9 @@ impl AuthorityStorePruner {
 
         let mut effect_digests = vec![];
         for effects in effects_to_prune {
+            if retained_transactions.contains(effects.transaction_digest()) {
+                continue;
+            }
             let effects_digest = effects.digest();
             debug!("Pruning effects {:?}", effects_digest);
             effect_digests.push(effects_digest);
@@ -309,6 +523,9 @@ impl AuthorityStorePruner {
         metrics
             .last_pruned_effects_checkpoint
             .set(checkpoint_number as i64);
+        metrics
+            .num_retained_transactions
+            .inc_by(retained_transactions.len() as u64);
 
         Ok(())
     }
@@ -442,9 +659,12 @@ impl AuthorityStorePruner {
             .map(|c| c.epoch())
             .unwrap_or_default();
 
+        let retention = HistoryRetentionPolicy::new(&config.history_retention);
+
         let mut checkpoints_to_prune = vec![];
         let mut checkpoint_content_to_prune = vec![];
         let mut effects_to_prune = vec![];
+        let mut retained_transactions = HashSet::new();
 
         loop {
             let Some(ckpt) = checkpoint_store
@@ -474,14 +694,22 @@ impl AuthorityStorePruner {
                         checkpoint.sequence_number
                     )
                 })?;
-            let effects = perpetual_db
+            let effects: Vec<_> = perpetual_db
                 .effects
-                .multi_get(content.iter().map(|tx| tx.effects))?;
+                .multi_get(content.iter().map(|tx| tx.effects))?
+                .into_iter()
+                .flatten()
+                .collect();
 
             info!("scheduling pruning for checkpoint {:?}", checkpoint_number);
+            retained_transactions.extend(Self::retained_transactions(
+                perpetual_db,
+                &retention,
+                &effects,
+            )?);
             checkpoints_to_prune.push(*checkpoint.digest());
             checkpoint_content_to_prune.push(content);
-            effects_to_prune.extend(effects.into_iter().flatten());
+            effects_to_prune.extend(effects);
 
             if effects_to_prune.len() >= config.max_transactions_in_batch
                 || checkpoints_to_prune.len() >= config.max_checkpoints_in_batch
@@ -495,6 +723,8 @@ impl AuthorityStorePruner {
                             checkpoint_number,
                             metrics.clone(),
                             !config.killswitch_tombstone_pruning,
+                            &retention,
+                            &retained_transactions,
                         )
                         .await?
                     }
@@ -507,11 +737,13 @@ impl AuthorityStorePruner {
                         checkpoint_content_to_prune,
                         &effects_to_prune,
                         metrics.clone(),
+                        &retained_transactions,
                     )?,
                 };
                 checkpoints_to_prune = vec![];
                 checkpoint_content_to_prune = vec![];
                 effects_to_prune = vec![];
+                retained_transactions = HashSet::new();
                 // yield back to the tokio runtime. Prevent potential halt of other tasks
                 tokio::task::yield_now().await;
             }
@@ -527,6 +759,8 @@ impl AuthorityStorePruner {
                         checkpoint_number,
                         metrics.clone(),
                         !config.killswitch_tombstone_pruning,
+                        &retention,
+                        &retained_transactions,
                     )
                     .await?
                 }
@@ -539,6 +773,7 @@ impl AuthorityStorePruner {
                     checkpoint_content_to_prune,
                     &effects_to_prune,
                     metrics.clone(),
+                    &retained_transactions,
                 )?,
             };
         }
@@ -966,13 +1201,19 @@ pub(crate) fn apply_relocation_filter<T: DeserializeOwned>(
 #[derive(Clone)]
 pub struct ObjectsCompactionFilter {
     db: Weak<AuthorityPrunerTables>,
+    retention: Arc<HistoryRetentionPolicy>,
     metrics: Arc<ObjectCompactionMetrics>,
 }
 
 impl ObjectsCompactionFilter {
-    pub fn new(db: Arc<AuthorityPrunerTables>, registry: &Registry) -> Self {
+    pub fn new(
+        db: Arc<AuthorityPrunerTables>,
+        retention: &HistoryRetentionConfig,
+        registry: &Registry,
+    ) -> Self {
         Self {
             db: Arc::downgrade(&db),
+            retention: Arc::new(HistoryRetentionPolicy::new(retention)),
             metrics: ObjectCompactionMetrics::new(registry),
         }
     }
@@ -988,6 +1229,15 @@ impl ObjectsCompactionFilter {
             match db.object_tombstones.get(&object_id)? {
                 Some(gc_version) => {
                     if version <= gc_version {
+                        if self.retention.retains_object(&object_id)
+                            || (!self.retention.is_empty()
+                                && db
+                                    .retained_object_versions
+                                    .contains_key(&ObjectKey(object_id, version))?)
+                        {
+                            self.metrics.key_retained.inc();
+                            return Ok(Decision::Keep);
+                        }
                         self.metrics.key_removed.inc();
                         return Ok(Decision::Remove);
                     }
@@ -1004,6 +1254,7 @@ struct ObjectCompactionMetrics {
     key_removed: IntCounter,
     key_kept: IntCounter,
     key_not_found: IntCounter,
+    key_retained: IntCounter,
 }
 
 impl ObjectCompactionMetrics {
@@ -1027,27 +1278,48 @@ impl ObjectCompactionMetrics {
                 registry
             )
             .unwrap(),
+            key_retained: register_int_counter_with_registry!(
+                "objects_compaction_filter_key_retained",
+                "Compaction key kept by the history retention policy",
+                registry
+            )
+            .unwrap(),
         })
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use bincode::Options;
     use more_asserts as ma;
+    use move_core_types::identifier::Identifier;
+    use std::collections::{BTreeMap, BTreeSet};
     use std::path::Path;
     use std::time::Duration;
     use std::{collections::HashSet, sync::Arc};
     use tracing::log::info;
 
     use crate::authority::authority_store_pruner::AuthorityStorePruningMetrics;
-    use crate::authority::authority_store_tables::AuthorityPerpetualTables;
+    use crate::authority::authority_store_tables::{
+        AuthorityPerpetualTables, AuthorityPrunerTables,
+    };
     use crate::authority::authority_store_types::{
         StoreObject, StoreObjectWrapper, get_store_object,
     };
+    use crate::checkpoints::CheckpointStore;
     use prometheus::Registry;
-    use sui_types::base_types::ObjectDigest;
+    use sui_config::node::HistoryRetentionConfig;
+    use sui_types::base_types::{ObjectDigest, SuiAddress, TransactionDigest, random_object_ref};
     use sui_types::effects::TransactionEffects;
     use sui_types::effects::TransactionEffectsAPI;
+    use sui_types::effects::TransactionEvents;
+    use sui_types::execution_status::ExecutionStatus;
+    use sui_types::gas::GasCostSummary;
+    use sui_types::message_envelope::Message;
+    use sui_types::messages_checkpoint::CheckpointContents;
+    use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+    use sui_types::transaction::{TransactionData, VerifiedTransaction};
+    use sui_types::utils::create_fake_transaction;
     use sui_types::{
         base_types::{ObjectID, SequenceNumber},
         object::Object,
@@ -1055,8 +1327,9 @@ mod tests {
     };
     use typed_store::Map;
     use typed_store::rocks::{DBMap, MetricConf, ReadWriteOptions, default_db_options};
+    use typed_store::rocksdb::compaction_filter::Decision;
 
-    use super::AuthorityStorePruner;
+    use super::{AuthorityStorePruner, HistoryRetentionPolicy, ObjectsCompactionFilter};
 
     fn get_keys_after_pruning(path: &Path) -> anyhow::Result<HashSet<ObjectKey>> {
         let perpetual_db_path = path.join(Path::new("perpetual"));
@@ -1178,9 +1451,18 @@ mod tests {
                     ObjectDigest::MIN,
                 ));
             }
-            AuthorityStorePruner::prune_objects(vec![effects], &db, None, 0, metrics, true)
-                .await
-                .unwrap();
+            AuthorityStorePruner::prune_objects(
+                vec![effects],
+                &db,
+                None,
+                0,
+                metrics,
+                true,
+                &HistoryRetentionPolicy::default(),
+                &HashSet::new(),
+            )
+            .await
+            .unwrap();
             to_keep
         };
         tokio::time::sleep(Duration::from_secs(3)).await;
@@ -1213,6 +1495,317 @@ mod tests {
         assert_eq!(get_keys_after_pruning(&path).unwrap().len(), 0);
     }
 
+    // Tests that every version of an object retained by the history retention policy survives
+    // pruning, and that the other objects are still pruned.
+    #[tokio::test]
+    async fn test_pruning_objects_with_history_retention() {
+        let path = tempfile::tempdir().unwrap().keep();
+        let registry = Registry::default();
+        let metrics = AuthorityStorePruningMetrics::new(&registry);
+        let db = Arc::new(AuthorityPerpetualTables::open(&path, None, None));
+        let (to_keep, to_delete, _) = generate_test_data(db.clone(), 3, 1, 10).unwrap();
+
+        let retention = HistoryRetentionPolicy::new(&HistoryRetentionConfig {
+            object_ids: vec![ObjectID::ZERO],
+            ..Default::default()
+        });
+
+        let mut effects = TransactionEffects::default();
+        for object in &to_delete {
+            effects.unsafe_add_deleted_live_object_for_testing((
+                object.0,
+                object.1,
+                ObjectDigest::MIN,
+            ));
+        }
+        AuthorityStorePruner::prune_objects(
+            vec![effects],
+            &db,
+            None,
+            0,
+            metrics,
+            true,
+            &retention,
+            &HashSet::new(),
+        )
+        .await
+        .unwrap();
+        drop(db);
+        tokio::time::sleep(Duration::from_secs(3)).await;
+
+        let expected: HashSet<_> = to_keep
+            .into_iter()
+            .chain(to_delete.into_iter().filter(|key| key.0 == ObjectID::ZERO))
+            .collect();
+        assert_eq!(expected, get_keys_after_pruning(&path).unwrap());
+    }
+
+    // Tests that the versions of an object modified by a retained transaction survive pruning,
+    // while the versions of the same object around them are still deleted, one by one.
+    #[tokio::test]
+    async fn test_pruning_objects_modified_by_retained_transactions() {
+        let path = tempfile::tempdir().unwrap().keep();
+        let registry = Registry::default();
+        let metrics = AuthorityStorePruningMetrics::new(&registry);
+        let db = Arc::new(AuthorityPerpetualTables::open(&path, None, None));
+        let (to_keep, to_delete, _) = generate_test_data(db.clone(), 4, 1, 2).unwrap();
+
+        // A retained transaction modified the second version of the first object.
+        let retained_key = ObjectKey(ObjectID::ZERO, SequenceNumber::from_u64(1));
+        let mut retained = TransactionEffects::default();
+        *retained.transaction_digest_mut_for_testing() = TransactionDigest::random();
+        retained.unsafe_add_deleted_live_object_for_testing((
+            retained_key.0,
+            retained_key.1,
+            ObjectDigest::MIN,
+        ));
+
+        let mut effects = TransactionEffects::default();
+        for object in to_delete.iter().filter(|key| **key != retained_key) {
+            effects.unsafe_add_deleted_live_object_for_testing((
+                object.0,
+                object.1,
+                ObjectDigest::MIN,
+            ));
+        }
+
+        let retention = HistoryRetentionPolicy::new(&HistoryRetentionConfig {
+            addresses: vec![SuiAddress::random_for_testing_only()],
+            ..Default::default()
+        });
+        let retained_transactions = HashSet::from([*retained.transaction_digest()]);
+        AuthorityStorePruner::prune_objects(
+            vec![retained, effects],
+            &db,
+            None,
+            0,
+            metrics,
+            true,
+            &retention,
+            &retained_transactions,
+        )
+        .await
+        .unwrap();
+        drop(db);
+        tokio::time::sleep(Duration::from_secs(3)).await;
+
+        let expected: HashSet<_> = to_keep.into_iter().chain([retained_key]).collect();
+        assert_eq!(expected, get_keys_after_pruning(&path).unwrap());
+    }
+
+    // Tests that pruning checkpoints keeps the transaction, effects and events of retained
+    // transactions, and removes those of the other transactions.
+    #[tokio::test]
+    async fn test_pruning_checkpoints_with_retained_transactions() {
+        let path = tempfile::tempdir().unwrap().keep();
+        let registry = Registry::default();
+        let metrics = AuthorityStorePruningMetrics::new(&registry);
+        let db = Arc::new(AuthorityPerpetualTables::open(&path, None, None));
+        let checkpoint_store = CheckpointStore::new_for_tests();
+
+        let mut digests = vec![];
+        let mut all_effects = vec![];
+        let mut batch = db.transactions.batch();
+        for _ in 0..2 {
+            let transaction = VerifiedTransaction::new_unchecked(create_fake_transaction());
+            let digest = *transaction.digest();
+            let events = TransactionEvents::default();
+            let effects = TransactionEffects::new_from_execution_v2(
+                ExecutionStatus::Success,
+                0,
+                GasCostSummary::default(),
+                vec![],
+                BTreeSet::new(),
+                digest,
+                SequenceNumber::new(),
+                BTreeMap::new(),
+                None,
+                Some(events.digest()),
+                vec![],
+            );
+
+            batch
+                .insert_batch(&db.transactions, [(digest, transaction.serializable())])
+                .unwrap();
+            batch
+                .insert_batch(&db.executed_effects, [(digest, effects.digest())])
+                .unwrap();
+            batch
+                .insert_batch(&db.effects, [(effects.digest(), effects.clone())])
+                .unwrap();
+            batch
+                .insert_batch(&db.events_2, [(digest, events)])
+                .unwrap();
+            digests.push(digest);
+            all_effects.push(effects);
+        }
+        batch.write().unwrap();
+
+        let contents = CheckpointContents::new_with_digests_only_for_tests(
+            all_effects
+                .iter()
+                .map(|effects| effects.execution_digests()),
+        );
+        AuthorityStorePruner::prune_checkpoints(
+            &db,
+            &checkpoint_store,
+            None,
+            0,
+            vec![],
+            vec![contents],
+            &all_effects,
+            metrics,
+            &HashSet::from([digests[0]]),
+        )
+        .unwrap();
+
+        let (kept, pruned) = (digests[0], digests[1]);
+        assert!(db.transactions.contains_key(&kept).unwrap());
+        assert!(db.executed_effects.contains_key(&kept).unwrap());
+        assert!(db.effects.contains_key(&all_effects[0].digest()).unwrap());
+        assert!(db.events_2.contains_key(&kept).unwrap());
+
+        assert!(!db.transactions.contains_key(&pruned).unwrap());
+        assert!(!db.executed_effects.contains_key(&pruned).unwrap());
+        assert!(!db.effects.contains_key(&all_effects[1].digest()).unwrap());
+        assert!(!db.events_2.contains_key(&pruned).unwrap());
+    }
+
+    #[test]
+    fn test_retains_transaction() {
+        let object_id = ObjectID::random();
+        let package_id = ObjectID::random();
+        let address = SuiAddress::random_for_testing_only();
+        let no_changes = TransactionEffects::default();
+
+        // The object is mutated, and left owned by `SuiAddress::ZERO`.
+        let mut effects = TransactionEffects::default();
+        effects.unsafe_add_deleted_live_object_for_testing((
+            object_id,
+            SequenceNumber::from_u64(1),
+            ObjectDigest::MIN,
+        ));
+
+        let mut builder = ProgrammableTransactionBuilder::new();
+        builder.programmable_move_call(
+            package_id,
+            Identifier::new("module").unwrap(),
+            Identifier::new("function").unwrap(),
+            vec![],
+            vec![],
+        );
+        let transaction = TransactionData::new_programmable(
+            address,
+            vec![random_object_ref()],
+            builder.finish(),
+            1_000_000,
+            1000,
+        );
+
+        // Nothing is retained without a policy.
+        let policy = HistoryRetentionPolicy::default();
+        assert!(!policy.retains_transaction(&effects, Some(&transaction)));
+
+        // Objects are recognized from the effects alone.
+        let policy = HistoryRetentionPolicy::new(&HistoryRetentionConfig {
+            object_ids: vec![object_id],
+            ..Default::default()
+        });
+        assert!(policy.retains_transaction(&effects, None));
+        assert!(!policy.retains_transaction(&no_changes, Some(&transaction)));
+
+        // Addresses are recognized as the owners of changed objects, or as the sender of the
+        // transaction, which needs the transaction data.
+        let policy = HistoryRetentionPolicy::new(&HistoryRetentionConfig {
+            addresses: vec![SuiAddress::ZERO],
+            ..Default::default()
+        });
+        assert!(policy.retains_transaction(&effects, None));
+
+        let policy = HistoryRetentionPolicy::new(&HistoryRetentionConfig {
+            addresses: vec![address],
+            ..Default::default()
+        });
+        assert!(policy.retains_transaction(&no_changes, Some(&transaction)));
+        assert!(!policy.retains_transaction(&no_changes, None));
+        assert!(!policy.retains_transaction(&effects, None));
+
+        // Packages are recognized from calls into them, which needs the transaction data.
+        let policy = HistoryRetentionPolicy::new(&HistoryRetentionConfig {
+            package_ids: vec![package_id],
+            ..Default::default()
+        });
+        assert!(policy.retains_transaction(&no_changes, Some(&transaction)));
+        assert!(!policy.retains_transaction(&effects, None));
+    }
+
+    #[test]
+    fn test_compaction_filter_with_history_retention() {
+        let path = tempfile::tempdir().unwrap().keep();
+        let pruner_db = Arc::new(AuthorityPrunerTables::open(&path));
+        let object_id = ObjectID::random();
+        pruner_db
+            .object_tombstones
+            .insert(&object_id, &SequenceNumber::from_u64(5))
+            .unwrap();
+        pruner_db
+            .retained_object_versions
+            .insert(&ObjectKey(object_id, SequenceNumber::from_u64(2)), &())
+            .unwrap();
+
+        let key = |version: u64| {
+            bincode::DefaultOptions::new()
+                .with_big_endian()
+                .with_fixint_encoding()
+                .serialize(&ObjectKey(object_id, SequenceNumber::from_u64(version)))
+                .unwrap()
+        };
+        let value = bcs::to_bytes(&get_store_object(Object::immutable_with_id_for_testing(
+            object_id,
+        )))
+        .unwrap();
+
+        // Versions up to the tombstone are removed, unless a retained transaction modified them.
+        let config = HistoryRetentionConfig {
+            addresses: vec![SuiAddress::random_for_testing_only()],
+            ..Default::default()
+        };
+        let mut filter =
+            ObjectsCompactionFilter::new(pruner_db.clone(), &config, &Registry::default());
+        assert!(matches!(
+            filter.filter(&key(1), &value).unwrap(),
+            Decision::Remove
+        ));
+        assert!(matches!(
+            filter.filter(&key(2), &value).unwrap(),
+            Decision::Keep
+        ));
+        assert!(matches!(
+            filter.filter(&key(6), &value).unwrap(),
+            Decision::Keep
+        ));
+
+        // Without a policy, the retained versions are not consulted.
+        let config = HistoryRetentionConfig::default();
+        let mut filter =
+            ObjectsCompactionFilter::new(pruner_db.clone(), &config, &Registry::default());
+        assert!(matches!(
+            filter.filter(&key(2), &value).unwrap(),
+            Decision::Remove
+        ));
+
+        // Every version of a retained object is kept.
+        let config = HistoryRetentionConfig {
+            object_ids: vec![object_id],
+            ..Default::default()
+        };
+        let mut filter = ObjectsCompactionFilter::new(pruner_db, &config, &Registry::default());
+        assert!(matches!(
+            filter.filter(&key(1), &value).unwrap(),
+            Decision::Keep
+        ));
+    }
+
     #[cfg(not(target_env = "msvc"))]
     #[tokio::test]
     async fn test_db_size_after_compaction() -> Result<(), anyhow::Error> {
@@ -1273,6 +1866,8 @@ mod tests {
             0,
             metrics,
             true,
+            &HistoryRetentionPolicy::default(),
+            &HashSet::new(),
         )
         .await;
         info!("Total pruned keys = {:?}", total_pruned);
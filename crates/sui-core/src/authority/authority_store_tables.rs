@@ -13,6 +13,7 @@ use sui_types::global_state_hash::GlobalStateHash;
 use sui_types::storage::{FullObjectKey, MarkerValue};
 use tracing::error;
 use typed_store::metrics::SamplingInterval;
+use typed_store::migration::{MigrationRegistry, SchemaMigrator};
 use typed_store::rocks::{
     DBBatch, DBMap, DBMapTableConfigMap, DBOptions, MetricConf, default_db_options,
     read_size_from_env,
@@ -151,6 +152,9 @@ pub struct AuthorityPerpetualTables {
 #[derive(DBMapUtils)]
 pub struct AuthorityPrunerTables {
     pub(crate) object_tombstones: DBMap<ObjectID, SequenceNumber>,
+    /// Object versions below their tombstone that must be kept, because they were modified by
+    /// transactions retained by the history retention policy.
+    pub(crate) retained_object_versions: DBMap<ObjectKey, ()>,
 }
 
 impl AuthorityPrunerTables {
@@ -169,6 +173,12 @@ impl AuthorityPrunerTables {
     }
 }
 
+/// The schema migrations of the perpetual tables, see `typed_store::migration`. A table migrated
+/// here must not be used before `MigrationHandle::wait_migrated` returns for it.
+fn perpetual_tables_migrations() -> MigrationRegistry {
+    MigrationRegistry::new()
+}
+
 impl AuthorityPerpetualTables {
     pub fn path(parent_path: &Path) -> PathBuf {
         parent_path.join("perpetual")
@@ -202,13 +212,19 @@ impl AuthorityPerpetualTables {
             ),
         ]));
 
-        Self::open_tables_read_write(
+        let tables = Self::open_tables_read_write(
             Self::path(parent_path),
             MetricConf::new("perpetual")
                 .with_sampling(SamplingInterval::new(Duration::from_secs(60), 0)),
             Some(db_options.options),
             Some(table_options),
-        )
+        );
+        // Records the schema version of every table, and migrates the tables whose version was
+        // bumped in the background.
+        SchemaMigrator::new(tables.objects.db.clone(), perpetual_tables_migrations())
+            .and_then(|migrator| migrator.start(&Self::table_descriptors()))
+            .expect("failed to start the schema migrations of the perpetual tables");
+        tables
     }
 
     #[cfg(tidehunter)]
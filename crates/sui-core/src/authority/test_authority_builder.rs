@@ -205,13 +205,17 @@ impl<'a> TestAuthorityBuilder<'a> {
         let mut pruner_db = None;
         if config
             .authority_store_pruning_config
-            .enable_compaction_filter
+            .enable_compaction_filter()
         {
             pruner_db = Some(Arc::new(AuthorityPrunerTables::open(&path.join("store"))));
         }
-        let compaction_filter = pruner_db
-            .clone()
-            .map(|db| ObjectsCompactionFilter::new(db, &registry));
+        let compaction_filter = pruner_db.clone().map(|db| {
+            ObjectsCompactionFilter::new(
+                db,
+                &config.authority_store_pruning_config.history_retention,
+                &registry,
+            )
+        });
 
         let authority_store = match self.store {
             Some(store) => store,
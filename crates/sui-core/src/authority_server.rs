@@ -2104,6 +2104,7 @@ impl ValidatorService {
     fn handle_traffic_resp<T>(
         &self,
         client: Option<IpAddr>,
+        request_type: &str,
         wrapped_response: WrappedServiceResponse<T>,
     ) -> Result<tonic::Response<T>, tonic::Status> {
         let (error, spam_weight, unwrapped_response) = match wrapped_response {
@@ -2125,6 +2126,7 @@ impl ValidatorService {
                     (error_weight, error_type)
                 }),
                 spam_weight,
+                request_type: Some(request_type.to_string()),
                 timestamp: SystemTime::now(),
             })
         }
@@ -2177,7 +2179,7 @@ macro_rules! handle_with_decoration {
 
         // handle traffic tallying
         let wrapped_response = $self.$func_name($request).await;
-        $self.handle_traffic_resp(client, wrapped_response)
+        $self.handle_traffic_resp(client, stringify!($func_name), wrapped_response)
     }};
 }
 
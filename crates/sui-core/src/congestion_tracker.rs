@@ -9,6 +9,7 @@ use sui_types::base_types::ObjectID;
 use sui_types::effects::{InputConsensusObject, TransactionEffects, TransactionEffectsAPI};
 use sui_types::execution_status::CongestedObjects;
 use sui_types::messages_checkpoint::{CheckpointTimestamp, VerifiedCheckpoint};
+use sui_types::storage::{GasPriceConfidence, GasPriceSuggestion, ObjectCongestionInfo};
 use sui_types::transaction::{TransactionData, TransactionDataAPI};
 
 use crate::execution_cache::TransactionCacheRead;
@@ -40,6 +41,33 @@ impl CongestionInfo {
         }
     }
 
+    /// The price a transaction accessing the object mutably needs to pay, if the object is still
+    /// congested, and how confident we are that the price will clear.
+    fn clearing_price(&self) -> (Option<u64>, GasPriceConfidence) {
+        match self
+            .last_success_time
+            .cmp(&Some(self.last_cancellation_time))
+        {
+            std::cmp::Ordering::Greater => {
+                // there were no cancellations in the most recent checkpoint,
+                // so the object is probably not congested any more
+                (None, GasPriceConfidence::High)
+            }
+            std::cmp::Ordering::Less => {
+                // there were no successes in the most recent checkpoint. This should be a rare case,
+                // but we know we will have to bid at least as much as the highest cancelled price.
+                (
+                    Some(self.highest_cancelled_gas_price),
+                    GasPriceConfidence::Low,
+                )
+            }
+            std::cmp::Ordering::Equal => {
+                // there were both successes and cancellations.
+                (self.lowest_executed_gas_price, GasPriceConfidence::Medium)
+            }
+        }
+    }
+
     fn update_cancellation_gas_price(&mut self, gas_price: u64) {
         self.highest_cancelled_gas_price =
             std::cmp::max(self.highest_cancelled_gas_price, gas_price);
@@ -118,16 +146,81 @@ impl CongestionTracker {
     /// For all the mutable shared inputs, get the highest minimum clearing price (if any exists)
     /// and the lowest maximum cancelled price.
     pub fn get_suggested_gas_prices(&self, transaction: &TransactionData) -> Option<u64> {
-        self.get_suggested_gas_price_for_objects(
-            transaction
-                .shared_input_objects()
-                .into_iter()
-                .filter(|id| id.is_accessed_exclusively())
-                .map(|id| id.id),
-        )
+        self.get_suggested_gas_price_for_objects(exclusively_accessed_objects(transaction))
+    }
+
+    /// Suggest a gas price for a transaction that accesses `objects` mutably, based on the
+    /// clearing prices of those objects. The suggestion is never below `reference_gas_price`, and
+    /// its confidence is the lowest confidence across all the objects.
+    pub fn get_gas_price_suggestion(
+        &self,
+        objects: impl IntoIterator<Item = ObjectID>,
+        reference_gas_price: u64,
+    ) -> GasPriceSuggestion {
+        let objects: Vec<_> = objects
+            .into_iter()
+            .map(|object_id| match self.get_congestion_info(object_id) {
+                Some(info) => {
+                    let (mut suggested_gas_price, confidence) = info.clearing_price();
+                    if confidence == GasPriceConfidence::Low {
+                        // The highest cancelled price did not clear, so bid strictly above it.
+                        suggested_gas_price = suggested_gas_price.map(|p| p.saturating_add(1));
+                    }
+
+                    ObjectCongestionInfo {
+                        object_id,
+                        suggested_gas_price,
+                        confidence,
+                        last_cancellation_timestamp_ms: Some(info.last_cancellation_time),
+                        highest_cancelled_gas_price: Some(info.highest_cancelled_gas_price),
+                        last_success_timestamp_ms: info.last_success_time,
+                        lowest_executed_gas_price: info.lowest_executed_gas_price,
+                    }
+                }
+                None => ObjectCongestionInfo {
+                    object_id,
+                    suggested_gas_price: None,
+                    confidence: GasPriceConfidence::High,
+                    last_cancellation_timestamp_ms: None,
+                    highest_cancelled_gas_price: None,
+                    last_success_timestamp_ms: None,
+                    lowest_executed_gas_price: None,
+                },
+            })
+            .collect();
+
+        let gas_price = objects
+            .iter()
+            .filter_map(|o| o.suggested_gas_price)
+            .fold(reference_gas_price, std::cmp::max);
+
+        let confidence = objects
+            .iter()
+            .map(|o| o.confidence)
+            .min()
+            .unwrap_or(GasPriceConfidence::High);
+
+        GasPriceSuggestion {
+            gas_price,
+            reference_gas_price,
+            confidence,
+            objects,
+        }
     }
 }
 
+/// The shared objects that `transaction` accesses mutably, which are the ones it can be
+/// cancelled on due to congestion.
+pub fn exclusively_accessed_objects(
+    transaction: &TransactionData,
+) -> impl Iterator<Item = ObjectID> {
+    transaction
+        .shared_input_objects()
+        .into_iter()
+        .filter(|id| id.is_accessed_exclusively())
+        .map(|id| id.id)
+}
+
 impl CongestionTracker {
     fn process_per_checkpoint_events(
         &self,
@@ -147,25 +240,7 @@ impl CongestionTracker {
         let mut clearing_price = None;
         for object_id in objects {
             if let Some(info) = self.get_congestion_info(object_id) {
-                let clearing_price_for_object = match info
-                    .last_success_time
-                    .cmp(&Some(info.last_cancellation_time))
-                {
-                    std::cmp::Ordering::Greater => {
-                        // there were no cancellations in the most recent checkpoint,
-                        // so the object is probably not congested any more
-                        None
-                    }
-                    std::cmp::Ordering::Less => {
-                        // there were no successes in the most recent checkpoint. This should be a rare case,
-                        // but we know we will have to bid at least as much as the highest cancelled price.
-                        Some(info.highest_cancelled_gas_price)
-                    }
-                    std::cmp::Ordering::Equal => {
-                        // there were both successes and cancellations.
-                        info.lowest_executed_gas_price
-                    }
-                };
+                let (clearing_price_for_object, _) = info.clearing_price();
                 clearing_price = std::cmp::max(clearing_price, clearing_price_for_object);
             }
         }
@@ -330,4 +405,53 @@ mod tests {
             Some(150)
         );
     }
+
+    #[test]
+    fn test_get_gas_price_suggestion() {
+        let tracker = CongestionTracker::new();
+        let obj1 = ObjectID::random();
+        let obj2 = ObjectID::random();
+        let rgp = 50;
+
+        // No congestion observed, so the reference gas price is enough.
+        let suggestion = tracker.get_gas_price_suggestion([obj1, obj2], rgp);
+        assert_eq!(suggestion.gas_price, rgp);
+        assert_eq!(suggestion.reference_gas_price, rgp);
+        assert_eq!(suggestion.confidence, GasPriceConfidence::High);
+        assert_eq!(suggestion.objects.len(), 2);
+
+        // Cancellations only, so the suggestion is just above the highest cancelled price.
+        tracker.process_per_checkpoint_events(1000, &[(100, vec![obj1])], &[]);
+        let suggestion = tracker.get_gas_price_suggestion([obj1, obj2], rgp);
+        assert_eq!(suggestion.gas_price, 101);
+        assert_eq!(suggestion.confidence, GasPriceConfidence::Low);
+        assert_eq!(suggestion.objects[0].suggested_gas_price, Some(101));
+        assert_eq!(suggestion.objects[0].highest_cancelled_gas_price, Some(100));
+        assert_eq!(suggestion.objects[1].confidence, GasPriceConfidence::High);
+
+        // Cancellations and successes in the same checkpoint, so the lowest success price is used.
+        tracker.process_per_checkpoint_events(2000, &[(100, vec![obj1])], &[(125, vec![obj1])]);
+        let suggestion = tracker.get_gas_price_suggestion([obj1], rgp);
+        assert_eq!(suggestion.gas_price, 125);
+        assert_eq!(suggestion.confidence, GasPriceConfidence::Medium);
+        assert_eq!(suggestion.objects[0].last_success_timestamp_ms, Some(2000));
+
+        // Congestion has cleared.
+        tracker.process_per_checkpoint_events(3000, &[], &[(150, vec![obj1])]);
+        let suggestion = tracker.get_gas_price_suggestion([obj1], rgp);
+        assert_eq!(suggestion.gas_price, rgp);
+        assert_eq!(suggestion.confidence, GasPriceConfidence::High);
+        assert_eq!(suggestion.objects[0].suggested_gas_price, None);
+    }
+
+    #[test]
+    fn test_get_gas_price_suggestion_never_below_reference_gas_price() {
+        let tracker = CongestionTracker::new();
+        let obj = ObjectID::random();
+
+        tracker.process_per_checkpoint_events(1000, &[(100, vec![obj])], &[]);
+        let suggestion = tracker.get_gas_price_suggestion([obj], 500);
+        assert_eq!(suggestion.gas_price, 500);
+        assert_eq!(suggestion.confidence, GasPriceConfidence::Low);
+    }
 }
@@ -6,13 +6,15 @@ use crate::authority::authority_per_epoch_store::AuthorityPerEpochStore;
 use crate::checkpoints::CheckpointStore;
 use crate::par_index_live_object_set::LiveObjectIndexer;
 use crate::par_index_live_object_set::ParMakeLiveObjectIndexer;
+use bincode::Options;
 use itertools::Itertools;
 use move_core_types::language_storage::{StructTag, TypeTag};
 use rayon::iter::IntoParallelIterator;
 use rayon::iter::ParallelIterator;
 use serde::Deserialize;
 use serde::Serialize;
-use std::collections::{BTreeMap, HashMap};
+use serde::de::DeserializeOwned;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -38,6 +40,8 @@ use sui_types::object::Owner;
 use sui_types::storage::BackingPackageStore;
 use sui_types::storage::DynamicFieldKey;
 use sui_types::storage::EpochInfo;
+use sui_types::storage::EventIndexFilter;
+use sui_types::storage::TransactionIndexFilter;
 use sui_types::storage::TransactionInfo;
 use sui_types::storage::error::Error as StorageError;
 use sui_types::sui_system_state::SuiSystemStateTrait;
@@ -50,7 +54,7 @@ use typed_store::rocks::{DBMap, DBMapTableConfigMap, MetricConf};
 use typed_store::rocksdb::{MergeOperands, WriteOptions, compaction_filter::Decision};
 use typed_store::traits::Map;
 
-const CURRENT_DB_VERSION: u64 = 3;
+const CURRENT_DB_VERSION: u64 = 4;
 // I tried increasing this to 100k and 1M and it didn't speed up indexing at all.
 const BALANCE_FLUSH_THRESHOLD: usize = 10_000;
 
@@ -251,6 +255,54 @@ fn events_table_options(
     options
 }
 
+/// Options for a table keyed by `K`, whose entries are removed by `compaction_filter` once the
+/// checkpoint returned by `checkpoint_of` has been pruned.
+fn checkpoint_index_table_options<K: DeserializeOwned + 'static>(
+    name: &'static str,
+    compaction_filter: Option<EventsCompactionFilter>,
+    checkpoint_of: fn(&K) -> u64,
+) -> typed_store::rocks::DBOptions {
+    let mut options = default_table_options();
+    if let Some(filter) = compaction_filter {
+        options
+            .options
+            .set_compaction_filter(name, move |_, key, _| {
+                match bincode::DefaultOptions::new()
+                    .with_big_endian()
+                    .with_fixint_encoding()
+                    .deserialize::<K>(key)
+                {
+                    Ok(key) => filter.decision(checkpoint_of(&key)),
+                    Err(e) => {
+                        warn!("Failed to parse {name} key during compaction: {e}, key: {key:?}");
+                        Decision::Keep
+                    }
+                }
+            });
+    }
+    options
+}
+
+fn transactions_by_filter_table_options(
+    compaction_filter: Option<EventsCompactionFilter>,
+) -> typed_store::rocks::DBOptions {
+    checkpoint_index_table_options(
+        "transactions_by_filter",
+        compaction_filter,
+        |key: &TransactionFilterKey| key.checkpoint_seq,
+    )
+}
+
+fn events_by_filter_table_options(
+    compaction_filter: Option<EventsCompactionFilter>,
+) -> typed_store::rocks::DBOptions {
+    checkpoint_index_table_options(
+        "events_by_filter",
+        compaction_filter,
+        |key: &EventFilterKey| key.checkpoint_seq,
+    )
+}
+
 fn balance_delta_merge_operator(
     _key: &[u8],
     existing_val: Option<&[u8]>,
@@ -380,6 +432,16 @@ struct IndexStoreTables {
 
     /// Authenticated events index by (stream_id, checkpoint_seq, transaction_idx, event_index)
     events_by_stream: DBMap<EventIndexKey, ()>,
+
+    /// An index of transactions by the conditions in [TransactionIndexFilter].
+    ///
+    /// Entries are removed by the compaction filter once their checkpoint has been pruned.
+    transactions_by_filter: DBMap<TransactionFilterKey, TransactionDigest>,
+
+    /// An index of events by the conditions in [EventIndexFilter].
+    ///
+    /// Entries are removed by the compaction filter once their checkpoint has been pruned.
+    events_by_filter: DBMap<EventFilterKey, TransactionDigest>,
     // NOTE: Authors and Reviewers before adding any new tables ensure that they are either:
     // - bounded in size by the live object set
     // - are prune-able and have corresponding logic in the `prune` function
@@ -395,6 +457,21 @@ pub struct EventIndexKey {
     pub event_index: u32,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TransactionFilterKey {
+    pub filter: TransactionIndexFilter,
+    pub checkpoint_seq: u64,
+    pub transaction_idx: u32,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub struct EventFilterKey {
+    pub filter: EventIndexFilter,
+    pub checkpoint_seq: u64,
+    pub transaction_idx: u32,
+    pub event_idx: u32,
+}
+
 /// Compaction filter for automatic pruning of old authenticated events during RocksDB compaction.
 #[derive(Clone)]
 pub struct EventsCompactionFilter {
@@ -408,14 +485,19 @@ impl EventsCompactionFilter {
 
     pub fn filter(&self, key: &[u8], _value: &[u8]) -> anyhow::Result<Decision> {
         let event_key: EventIndexKey = bcs::from_bytes(key)?;
+        Ok(self.decision(event_key.checkpoint_seq))
+    }
+
+    /// Whether to remove an entry indexed at `checkpoint_seq`.
+    fn decision(&self, checkpoint_seq: u64) -> Decision {
         let watermark = self
             .pruning_watermark
             .load(std::sync::atomic::Ordering::Relaxed);
 
-        if event_key.checkpoint_seq <= watermark {
-            Ok(Decision::Remove)
+        if checkpoint_seq <= watermark {
+            Decision::Remove
         } else {
-            Ok(Decision::Keep)
+            Decision::Keep
         }
     }
 }
@@ -469,7 +551,15 @@ impl IndexStoreTables {
         table_options.insert("balance".to_string(), balance_table_options());
         table_options.insert(
             "events_by_stream".to_string(),
-            events_table_options(index_options.events_compaction_filter),
+            events_table_options(index_options.events_compaction_filter.clone()),
+        );
+        table_options.insert(
+            "transactions_by_filter".to_string(),
+            transactions_by_filter_table_options(index_options.events_compaction_filter.clone()),
+        );
+        table_options.insert(
+            "events_by_filter".to_string(),
+            events_by_filter_table_options(index_options.events_compaction_filter),
         );
 
         IndexStoreTables::open_tables_read_write(
@@ -606,7 +696,8 @@ impl IndexStoreTables {
         let start_time = Instant::now();
 
         checkpoint_range.into_par_iter().try_for_each(|seq| {
-            let load_events = rpc_config.authenticated_events_indexing();
+            let load_events = rpc_config.authenticated_events_indexing()
+                || rpc_config.transaction_and_event_indexing();
             let checkpoint_data = sparse_checkpoint_data_for_backfill(
                 authority_store,
                 checkpoint_store,
@@ -617,7 +708,12 @@ impl IndexStoreTables {
             let mut batch = self.transactions.batch();
 
             self.index_epoch(&checkpoint_data, &mut batch)?;
-            self.index_transactions(&checkpoint_data, &mut batch, load_events)?;
+            self.index_transactions(
+                &checkpoint_data,
+                &mut batch,
+                rpc_config.authenticated_events_indexing(),
+                rpc_config.transaction_and_event_indexing(),
+            )?;
 
             batch
                 .write_opt(&(bulk_ingestion_write_options()))
@@ -671,6 +767,7 @@ impl IndexStoreTables {
             checkpoint,
             &mut batch,
             rpc_config.authenticated_events_indexing(),
+            rpc_config.transaction_and_event_indexing(),
         )?;
         self.index_objects(checkpoint, &mut batch)?;
 
@@ -827,6 +924,7 @@ impl IndexStoreTables {
         checkpoint: &CheckpointData,
         batch: &mut typed_store::rocks::DBBatch,
         index_events: bool,
+        index_filters: bool,
     ) -> Result<(), StorageError> {
         let cp = checkpoint.checkpoint_summary.sequence_number;
         let mut current_accumulator_version: Option<u64> = None;
@@ -857,8 +955,106 @@ impl IndexStoreTables {
                     batch,
                 )?;
             }
+
+            if index_filters {
+                self.index_transaction_filters(tx, cp, tx_idx as u32, batch)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Index the transaction `tx`, and the events it emitted, by the conditions they match (see
+    /// [TransactionIndexFilter] and [EventIndexFilter]).
+    fn index_transaction_filters(
+        &self,
+        tx: &sui_types::full_checkpoint_content::CheckpointTransaction,
+        checkpoint_seq: u64,
+        transaction_idx: u32,
+        batch: &mut typed_store::rocks::DBBatch,
+    ) -> Result<(), StorageError> {
+        let digest = *tx.transaction.digest();
+        let data = tx.transaction.transaction_data();
+
+        let mut filters = BTreeSet::new();
+        filters.insert(TransactionIndexFilter::Sender(data.sender()));
+        filters.insert(TransactionIndexFilter::AffectedAddress(data.sender()));
+        filters.insert(TransactionIndexFilter::AffectedAddress(data.gas_owner()));
+        for object in tx.input_objects.iter().chain(&tx.output_objects) {
+            if let Owner::AddressOwner(owner) | Owner::ConsensusAddressOwner { owner, .. } =
+                object.owner()
+            {
+                filters.insert(TransactionIndexFilter::AffectedAddress(*owner));
+            }
+        }
+
+        for change in tx.effects.object_changes() {
+            filters.insert(TransactionIndexFilter::AffectedObject(change.id));
+        }
+
+        // Calls are indexed by package, module and function, so that calls can be queried by
+        // any prefix of the three.
+        for (package, module, function) in data.move_calls() {
+            filters.insert(TransactionIndexFilter::MoveCall {
+                package: *package,
+                module: None,
+                function: None,
+            });
+            filters.insert(TransactionIndexFilter::MoveCall {
+                package: *package,
+                module: Some(module.to_owned()),
+                function: None,
+            });
+            filters.insert(TransactionIndexFilter::MoveCall {
+                package: *package,
+                module: Some(module.to_owned()),
+                function: Some(function.to_owned()),
+            });
+        }
+
+        let mut event_entries = vec![];
+        for (event_idx, event) in tx.events.iter().flat_map(|events| &events.data).enumerate() {
+            let mut event_filters = vec![
+                EventIndexFilter::Sender(event.sender),
+                EventIndexFilter::EmittingModule {
+                    package: event.package_id,
+                    module: None,
+                },
+                EventIndexFilter::EmittingModule {
+                    package: event.package_id,
+                    module: Some(event.transaction_module.to_string()),
+                },
+            ];
+
+            for event_type in event_type_filters(&event.type_) {
+                filters.insert(TransactionIndexFilter::EventType(event_type.clone()));
+                event_filters.push(EventIndexFilter::EventType(event_type));
+            }
+
+            event_entries.extend(event_filters.into_iter().map(|filter| {
+                let key = EventFilterKey {
+                    filter,
+                    checkpoint_seq,
+                    transaction_idx,
+                    event_idx: event_idx as u32,
+                };
+                (key, digest)
+            }));
         }
 
+        batch.insert_batch(
+            &self.transactions_by_filter,
+            filters.into_iter().map(|filter| {
+                let key = TransactionFilterKey {
+                    filter,
+                    checkpoint_seq,
+                    transaction_idx,
+                };
+                (key, digest)
+            }),
+        )?;
+        batch.insert_batch(&self.events_by_filter, event_entries)?;
+
         Ok(())
     }
 
@@ -1020,6 +1216,61 @@ impl IndexStoreTables {
             .take(limit as usize))
     }
 
+    fn transaction_filter_iter(
+        &self,
+        filter: TransactionIndexFilter,
+        cursor: Option<(u64, u32)>,
+        descending: bool,
+    ) -> Result<
+        impl Iterator<Item = Result<(TransactionFilterKey, TransactionDigest), TypedStoreError>> + '_,
+        TypedStoreError,
+    > {
+        let key = |(checkpoint_seq, transaction_idx): (u64, u32)| TransactionFilterKey {
+            filter: filter.clone(),
+            checkpoint_seq,
+            transaction_idx,
+        };
+        let lower = key((0, 0));
+        let upper = key((u64::MAX, u32::MAX));
+
+        if descending {
+            self.transactions_by_filter
+                .reversed_safe_iter_with_bounds(Some(lower), Some(cursor.map(key).unwrap_or(upper)))
+        } else {
+            Ok(self
+                .transactions_by_filter
+                .safe_iter_with_bounds(Some(cursor.map(key).unwrap_or(lower)), Some(upper)))
+        }
+    }
+
+    fn event_filter_iter(
+        &self,
+        filter: EventIndexFilter,
+        cursor: Option<(u64, u32, u32)>,
+        descending: bool,
+    ) -> Result<
+        impl Iterator<Item = Result<(EventFilterKey, TransactionDigest), TypedStoreError>> + '_,
+        TypedStoreError,
+    > {
+        let key = |(checkpoint_seq, transaction_idx, event_idx): (u64, u32, u32)| EventFilterKey {
+            filter: filter.clone(),
+            checkpoint_seq,
+            transaction_idx,
+            event_idx,
+        };
+        let lower = key((0, 0, 0));
+        let upper = key((u64::MAX, u32::MAX, u32::MAX));
+
+        if descending {
+            self.events_by_filter
+                .reversed_safe_iter_with_bounds(Some(lower), Some(cursor.map(key).unwrap_or(upper)))
+        } else {
+            Ok(self
+                .events_by_filter
+                .safe_iter_with_bounds(Some(cursor.map(key).unwrap_or(lower)), Some(upper)))
+        }
+    }
+
     fn owner_iter(
         &self,
         owner: SuiAddress,
@@ -1362,6 +1613,18 @@ impl RpcIndexStore {
                         "events_by_stream".to_string(),
                         events_table_options(index_options.events_compaction_filter.clone()),
                     );
+                    table_config_map.insert(
+                        "transactions_by_filter".to_string(),
+                        transactions_by_filter_table_options(
+                            index_options.events_compaction_filter.clone(),
+                        ),
+                    );
+                    table_config_map.insert(
+                        "events_by_filter".to_string(),
+                        events_by_filter_table_options(
+                            index_options.events_compaction_filter.clone(),
+                        ),
+                    );
 
                     IndexStoreTables::open_with_options(
                         &path,
@@ -1586,6 +1849,31 @@ impl RpcIndexStore {
         )
     }
 
+    pub fn transaction_filter_iter(
+        &self,
+        filter: TransactionIndexFilter,
+        cursor: Option<(u64, u32)>,
+        descending: bool,
+    ) -> Result<
+        impl Iterator<Item = Result<(TransactionFilterKey, TransactionDigest), TypedStoreError>> + '_,
+        TypedStoreError,
+    > {
+        self.tables
+            .transaction_filter_iter(filter, cursor, descending)
+    }
+
+    pub fn event_filter_iter(
+        &self,
+        filter: EventIndexFilter,
+        cursor: Option<(u64, u32, u32)>,
+        descending: bool,
+    ) -> Result<
+        impl Iterator<Item = Result<(EventFilterKey, TransactionDigest), TypedStoreError>> + '_,
+        TypedStoreError,
+    > {
+        self.tables.event_filter_iter(filter, cursor, descending)
+    }
+
     pub fn get_highest_indexed_checkpoint_seq_number(
         &self,
     ) -> Result<Option<CheckpointSequenceNumber>, TypedStoreError> {
@@ -1593,6 +1881,19 @@ impl RpcIndexStore {
     }
 }
 
+/// The types an event of type `event_type` is indexed by: the type itself, and if it has type
+/// parameters, the type without them, which matches all instantiations of the type.
+fn event_type_filters(event_type: &StructTag) -> Vec<StructTag> {
+    let mut types = vec![event_type.clone()];
+    if !event_type.type_params.is_empty() {
+        types.push(StructTag {
+            type_params: vec![],
+            ..event_type.clone()
+        });
+    }
+    types
+}
+
 fn should_index_dynamic_field(object: &Object) -> bool {
     // Skip any objects that aren't of type `Field<Name, Value>`
     //
@@ -1980,4 +2281,54 @@ mod tests {
             "Event with checkpoint equal to watermark should be removed"
         );
     }
+
+    #[tokio::test]
+    async fn test_transaction_filter_iter() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let db_path = temp_dir.path().join("rpc-index");
+        let tables =
+            IndexStoreTables::open_with_index_options(&db_path, IndexStoreOptions::default());
+
+        let sender = SuiAddress::random_for_testing_only();
+        let filter = TransactionIndexFilter::Sender(sender);
+        let other = TransactionIndexFilter::Sender(SuiAddress::random_for_testing_only());
+
+        let mut batch = tables.transactions_by_filter.batch();
+        for (filter, checkpoint_seq, transaction_idx) in [
+            (&filter, 1, 0),
+            (&filter, 1, 2),
+            (&other, 2, 0),
+            (&filter, 3, 1),
+        ] {
+            let key = TransactionFilterKey {
+                filter: filter.clone(),
+                checkpoint_seq,
+                transaction_idx,
+            };
+            batch
+                .insert_batch(
+                    &tables.transactions_by_filter,
+                    [(key, TransactionDigest::random())],
+                )
+                .unwrap();
+        }
+        batch.write().unwrap();
+
+        let positions = |cursor, descending| {
+            tables
+                .transaction_filter_iter(filter.clone(), cursor, descending)
+                .unwrap()
+                .map(|res| {
+                    let (key, _) = res.unwrap();
+                    (key.checkpoint_seq, key.transaction_idx)
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(positions(None, false), [(1, 0), (1, 2), (3, 1)]);
+        assert_eq!(positions(None, true), [(3, 1), (1, 2), (1, 0)]);
+        assert_eq!(positions(Some((1, 2)), false), [(1, 2), (3, 1)]);
+        assert_eq!(positions(Some((1, 2)), true), [(1, 2), (1, 0)]);
+        assert_eq!(positions(Some((2, 0)), true), [(1, 2), (1, 0)]);
+    }
 }
@@ -30,15 +30,21 @@ use sui_types::storage::BalanceInfo;
 use sui_types::storage::BalanceIterator;
 use sui_types::storage::CoinInfo;
 use sui_types::storage::DynamicFieldKey;
+use sui_types::storage::EventIndexFilter;
+use sui_types::storage::GasPriceSuggestion;
+use sui_types::storage::IndexedEventRecord;
+use sui_types::storage::IndexedTransactionRecord;
 use sui_types::storage::ObjectStore;
 use sui_types::storage::OwnedObjectInfo;
 use sui_types::storage::RpcIndexes;
 use sui_types::storage::RpcStateReader;
+use sui_types::storage::TransactionIndexFilter;
 use sui_types::storage::TransactionInfo;
 use sui_types::storage::WriteStore;
 use sui_types::storage::error::Error as StorageError;
 use sui_types::storage::error::Result;
 use sui_types::storage::{ObjectKey, ReadStore};
+use sui_types::transaction::TransactionData;
 use sui_types::transaction::VerifiedTransaction;
 use tap::Pipe;
 use tap::TapFallible;
@@ -525,6 +531,16 @@ impl RpcStateReader for RestReadStore {
             .map(Some)
             .map_err(StorageError::custom)
     }
+
+    fn get_gas_price_suggestion(
+        &self,
+        transaction: Option<&TransactionData>,
+        object_ids: &[ObjectID],
+    ) -> Result<Option<GasPriceSuggestion>> {
+        Ok(Some(
+            self.state.get_gas_price_suggestion(transaction, object_ids),
+        ))
+    }
 }
 
 struct BatchedEventIterator<'a, I>
@@ -764,4 +780,56 @@ impl RpcIndexes for RestReadStore {
 
         Ok(Box::new(iter))
     }
+
+    fn transactions_iter(
+        &self,
+        filter: TransactionIndexFilter,
+        cursor: Option<(u64, u32)>,
+        descending: bool,
+    ) -> Result<Box<dyn Iterator<Item = Result<IndexedTransactionRecord, TypedStoreError>> + '_>>
+    {
+        let iter = self
+            .index()?
+            .transaction_filter_iter(filter, cursor, descending)?
+            .map(|res| res.map(|(key, digest)| (key.checkpoint_seq, key.transaction_idx, digest)));
+
+        Ok(Box::new(iter))
+    }
+
+    fn events_iter(
+        &self,
+        filter: EventIndexFilter,
+        cursor: Option<(u64, u32, u32)>,
+        descending: bool,
+    ) -> Result<Box<dyn Iterator<Item = Result<IndexedEventRecord, TypedStoreError>> + '_>> {
+        let key_iter = self
+            .index()?
+            .event_filter_iter(filter, cursor, descending)?;
+
+        // Consecutive events are usually from the same transaction, so hold on to the last
+        // transaction's events.
+        let mut cached: Option<(TransactionDigest, Option<TransactionEvents>)> = None;
+        let iter = key_iter.filter_map(move |res| {
+            let (key, digest) = match res {
+                Ok(entry) => entry,
+                Err(e) => return Some(Err(e)),
+            };
+
+            if cached.as_ref().is_none_or(|(cached, _)| *cached != digest) {
+                cached = Some((digest, self.rocks.get_events(&digest)));
+            }
+
+            let (_, events) = cached.as_ref()?;
+            let event = events.as_ref()?.data.get(key.event_idx as usize)?.clone();
+            Some(Ok((
+                key.checkpoint_seq,
+                key.transaction_idx,
+                key.event_idx,
+                digest,
+                event,
+            )))
+        });
+
+        Ok(Box::new(iter))
+    }
 }
@@ -29,6 +29,8 @@ pub struct TrafficControllerMetrics {
     pub spam_proxied_client_threshold: IntGauge,
     pub error_proxied_client_threshold: IntGauge,
     pub dry_run_enabled: IntGauge,
+    pub composite_child_tallies: IntCounterVec,
+    pub composite_child_blocks: IntCounterVec,
 }
 
 impl TrafficControllerMetrics {
@@ -164,6 +166,21 @@ impl TrafficControllerMetrics {
                 registry
             )
             .unwrap(),
+            composite_child_tallies: register_int_counter_vec_with_registry!(
+                "traffic_control_composite_child_tallies",
+                "Number of tallies handled by each child of a composite policy",
+                &["role", "policy", "dry_run"],
+                registry
+            )
+            .unwrap(),
+            composite_child_blocks: register_int_counter_vec_with_registry!(
+                "traffic_control_composite_child_blocks",
+                "Number of clients each child of a composite policy decided to block, \
+                    whether or not the decision was enforced",
+                &["role", "policy", "dry_run", "client_type"],
+                registry
+            )
+            .unwrap(),
         }
     }
 
@@ -4,12 +4,14 @@
 pub mod metrics;
 pub mod nodefw_client;
 pub mod nodefw_test_server;
+pub mod persistence;
 pub mod policies;
 
 use dashmap::DashMap;
 use fs::File;
 use mysten_common::fatal;
 use prometheus::IntGauge;
+use serde::Serialize;
 use std::fs;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::ops::Add;
@@ -19,7 +21,7 @@ use sui_types::error::{SuiError, SuiErrorKind};
 use self::metrics::TrafficControllerMetrics;
 use crate::traffic_controller::nodefw_client::{BlockAddress, BlockAddresses, NodeFWClient};
 use crate::traffic_controller::policies::{
-    Policy, PolicyResponse, TrafficControlPolicy, TrafficTally,
+    Policy, PolicyClientUsage, PolicyResponse, TrafficControlPolicy, TrafficTally,
 };
 use mysten_metrics::spawn_monitored_task;
 use parking_lot::Mutex as ParkingLotMutex;
@@ -36,12 +38,84 @@ use tracing::{debug, error, info, trace, warn};
 pub const METRICS_INTERVAL_SECS: u64 = 2;
 pub const DEFAULT_DRAIN_TIMEOUT_SECS: u64 = 300;
 
-type Blocklist = Arc<DashMap<IpAddr, SystemTime>>;
+/// Why a client was added to a blocklist.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BlockReason {
+    Spam,
+    Error,
+    /// Added manually through the admin interface
+    Admin,
+    /// Restored from this node's blocklist snapshot
+    Restored,
+    /// Imported from another node's shared blocklist
+    Imported,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct BlocklistEntry {
+    pub expiration: SystemTime,
+    pub reason: BlockReason,
+}
+
+/// A client unblocked through the admin interface. Blocks from snapshots
+/// taken before the unblock are not imported again, until the tombstone
+/// expires with the block it removed.
+#[derive(Clone, Copy, Debug)]
+pub struct UnblockEntry {
+    pub unblocked_at: SystemTime,
+    pub expiration: SystemTime,
+}
+
+type Blocklist = Arc<DashMap<IpAddr, BlocklistEntry>>;
+
+/// Unblocked clients, keyed by IP and whether the client is proxied.
+type Unblocklist = Arc<DashMap<(IpAddr, bool), UnblockEntry>>;
+
+/// A blocklist entry as reported through the admin interface.
+#[derive(Clone, Debug, Serialize)]
+pub struct BlocklistEntryInfo {
+    pub ip: IpAddr,
+    pub proxied: bool,
+    pub reason: BlockReason,
+    pub expires_in_secs: u64,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ClientRate {
+    pub ip: IpAddr,
+    pub rate: u64,
+}
+
+/// Highest recent request rates seen by a `FreqThresholdPolicy`.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct TopClients {
+    pub direct: Vec<ClientRate>,
+    pub proxied: Vec<ClientRate>,
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct TrafficControlTopClients {
+    pub spam: Option<TopClients>,
+    pub error: Option<TopClients>,
+}
+
+/// Where a single client stands relative to the blocklists
+/// and to the thresholds of the spam and error policies.
+#[derive(Clone, Debug, Serialize)]
+pub struct ClientStatus {
+    pub ip: IpAddr,
+    pub blocked: Option<BlocklistEntryInfo>,
+    pub blocked_as_proxied: Option<BlocklistEntryInfo>,
+    pub spam: Option<PolicyClientUsage>,
+    pub error: Option<PolicyClientUsage>,
+}
 
 #[derive(Clone)]
 pub struct Blocklists {
     clients: Blocklist,
     proxied_clients: Blocklist,
+    unblocked: Unblocklist,
 }
 
 #[derive(Clone)]
@@ -61,7 +135,7 @@ pub struct TrafficController {
     metrics: Arc<TrafficControllerMetrics>,
     spam_policy: Option<Arc<Mutex<TrafficControlPolicy>>>,
     error_policy: Option<Arc<Mutex<TrafficControlPolicy>>>,
-    policy_config: Arc<RwLock<PolicyConfig>>,
+    policy_config: Arc<RwLock<Arc<PolicyConfig>>>,
     fw_config: Option<RemoteFirewallConfig>,
 }
 
@@ -106,7 +180,7 @@ impl TrafficController {
                     tally_channel: Arc::new(ParkingLotMutex::new(None)),
                     acl: Acl::Allowlist(allowlist),
                     metrics,
-                    policy_config: Arc::new(RwLock::new(policy_config)),
+                    policy_config: Arc::new(RwLock::new(Arc::new(policy_config))),
                     fw_config,
                     spam_policy: None,
                     error_policy: None,
@@ -114,19 +188,27 @@ impl TrafficController {
             }
             None => {
                 let spam_policy = Arc::new(Mutex::new(
-                    TrafficControlPolicy::from_spam_config(policy_config.clone()).await,
+                    TrafficControlPolicy::from_spam_config(policy_config.clone(), metrics.clone())
+                        .await,
                 ));
                 let error_policy = Arc::new(Mutex::new(
-                    TrafficControlPolicy::from_error_config(policy_config.clone()).await,
+                    TrafficControlPolicy::from_error_config(policy_config.clone(), metrics.clone())
+                        .await,
                 ));
+                let blocklists = Blocklists {
+                    clients: Arc::new(DashMap::new()),
+                    proxied_clients: Arc::new(DashMap::new()),
+                    unblocked: Arc::new(DashMap::new()),
+                };
+                if let Some(persistence_config) = &policy_config.blocklist_persistence {
+                    persistence::restore_blocklists(persistence_config, &blocklists, &metrics)
+                        .await;
+                }
                 let this = Self {
                     tally_channel: Arc::new(ParkingLotMutex::new(None)),
-                    acl: Acl::Blocklists(Blocklists {
-                        clients: Arc::new(DashMap::new()),
-                        proxied_clients: Arc::new(DashMap::new()),
-                    }),
+                    acl: Acl::Blocklists(blocklists),
                     metrics,
-                    policy_config: Arc::new(RwLock::new(policy_config)),
+                    policy_config: Arc::new(RwLock::new(Arc::new(policy_config))),
                     fw_config,
                     spam_policy: Some(spam_policy),
                     error_policy: Some(error_policy),
@@ -169,7 +251,7 @@ impl TrafficController {
         let clear_loop_blocklists = blocklists.clone();
         let tally_loop_metrics = self.metrics.clone();
         let clear_loop_metrics = self.metrics.clone();
-        let tally_loop_policy_config = policy_config.clone();
+        let tally_loop_policy_config = self.policy_config.clone();
         let tally_loop_fw_config = self.fw_config.clone();
 
         let spam_policy = self
@@ -197,6 +279,19 @@ impl TrafficController {
             clear_loop_blocklists,
             clear_loop_metrics,
         ));
+        if let Some(persistence_config) = policy_config.blocklist_persistence.clone() {
+            let destination_port = self
+                .fw_config
+                .as_ref()
+                .map(|fw_config| fw_config.destination_port)
+                .unwrap_or_default();
+            spawn_monitored_task!(persistence::run_persist_blocklists_loop(
+                persistence_config,
+                blocklists,
+                destination_port,
+                self.metrics.clone(),
+            ));
+        }
         self.open_tally_channel(tx);
     }
 
@@ -205,21 +300,20 @@ impl TrafficController {
             error_threshold: None,
             spam_threshold: None,
             dry_run: None,
+            policy_config: None,
         };
 
-        if let Some(error_policy) = self.error_policy.as_ref()
-            && let TrafficControlPolicy::FreqThreshold(ref policy) = *error_policy.lock().await
-        {
-            result.error_threshold = Some(policy.client_threshold);
+        if let Some(error_policy) = self.error_policy.as_ref() {
+            result.error_threshold = Self::policy_threshold(&*error_policy.lock().await);
         }
 
-        if let Some(spam_policy) = self.spam_policy.as_ref()
-            && let TrafficControlPolicy::FreqThreshold(ref policy) = *spam_policy.lock().await
-        {
-            result.spam_threshold = Some(policy.client_threshold);
+        if let Some(spam_policy) = self.spam_policy.as_ref() {
+            result.spam_threshold = Self::policy_threshold(&*spam_policy.lock().await);
         }
 
-        result.dry_run = Some(self.policy_config.read().await.dry_run);
+        let policy_config = self.policy_config.read().await.clone();
+        result.dry_run = Some(policy_config.dry_run);
+        result.policy_config = Some(PolicyConfig::clone(&policy_config));
         result
     }
 
@@ -231,7 +325,11 @@ impl TrafficController {
             error_threshold,
             spam_threshold,
             dry_run,
+            policy_config,
         } = params;
+        if let Some(policy_config) = policy_config {
+            self.swap_policy_config(policy_config).await?;
+        }
         if let Some(error_threshold) = error_threshold {
             self.metrics
                 .error_client_threshold
@@ -256,12 +354,198 @@ impl TrafficController {
         }
         if let Some(dry_run) = dry_run {
             self.metrics.dry_run_enabled.set(dry_run as i64);
-            self.policy_config.write().await.dry_run = dry_run;
+            Arc::make_mut(&mut *self.policy_config.write().await).dry_run = dry_run;
         }
 
         Ok(self.get_current_state().await)
     }
 
+    /// Rebuilds the spam and error policies from `policy_config` and
+    /// makes it the active config. The tally loop shares the policies
+    /// and config with us, so the swap takes effect from its next tally.
+    async fn swap_policy_config(&self, policy_config: PolicyConfig) -> Result<(), SuiError> {
+        let (Some(spam_policy), Some(error_policy)) = (&self.spam_policy, &self.error_policy)
+        else {
+            return Err(SuiErrorKind::InvalidAdminRequest(
+                "Traffic control policies cannot be swapped in allowlist mode".to_string(),
+            )
+            .into());
+        };
+        if policy_config.allow_list.is_some() {
+            return Err(SuiErrorKind::InvalidAdminRequest(
+                "Allowlist mode cannot be enabled at runtime".to_string(),
+            )
+            .into());
+        }
+        let new_spam_policy =
+            TrafficControlPolicy::from_spam_config(policy_config.clone(), self.metrics.clone())
+                .await;
+        let new_error_policy =
+            TrafficControlPolicy::from_error_config(policy_config.clone(), self.metrics.clone())
+                .await;
+        *spam_policy.lock().await = new_spam_policy;
+        *error_policy.lock().await = new_error_policy;
+        Self::set_policy_config_metrics(&policy_config, self.metrics.clone());
+        self.metrics
+            .dry_run_enabled
+            .set(policy_config.dry_run as i64);
+        info!("Swapped traffic control policy config: {:?}", policy_config);
+        *self.policy_config.write().await = Arc::new(policy_config);
+        Ok(())
+    }
+
+    fn blocklists(&self) -> Result<&Blocklists, SuiError> {
+        match &self.acl {
+            Acl::Blocklists(blocklists) => Ok(blocklists),
+            Acl::Allowlist(_) => Err(SuiErrorKind::InvalidAdminRequest(
+                "Traffic controller is running in allowlist mode".to_string(),
+            )
+            .into()),
+        }
+    }
+
+    /// Lists unexpired blocklist entries whose IP contains `search`, up to `limit`.
+    pub fn admin_list_blocklists(
+        &self,
+        search: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<BlocklistEntryInfo>, SuiError> {
+        let blocklists = self.blocklists()?;
+        let now = SystemTime::now();
+        let entries = [
+            (&blocklists.clients, false),
+            (&blocklists.proxied_clients, true),
+        ]
+        .into_iter()
+        .flat_map(|(blocklist, proxied)| {
+            blocklist
+                .iter()
+                .filter_map(move |entry| {
+                    blocklist_entry_info(*entry.key(), entry.value(), proxied, now)
+                })
+                .collect::<Vec<_>>()
+        })
+        .filter(|info| search.is_none_or(|search| info.ip.to_string().contains(search)))
+        .take(limit)
+        .collect();
+        Ok(entries)
+    }
+
+    pub fn admin_block(&self, ip: IpAddr, proxied: bool, ttl: Duration) -> Result<(), SuiError> {
+        let blocklists = self.blocklists()?;
+        let (blocklist, gauge) = if proxied {
+            (
+                &blocklists.proxied_clients,
+                &self.metrics.proxy_ip_blocklist_len,
+            )
+        } else {
+            (
+                &blocklists.clients,
+                &self.metrics.connection_ip_blocklist_len,
+            )
+        };
+        let expiration = SystemTime::now().checked_add(ttl).ok_or_else(|| {
+            SuiError::from(SuiErrorKind::InvalidAdminRequest(format!(
+                "TTL of {} seconds is too large",
+                ttl.as_secs()
+            )))
+        })?;
+        let entry = BlocklistEntry {
+            expiration,
+            reason: BlockReason::Admin,
+        };
+        info!(
+            "Admin blocking {:?} (proxied: {}) for {:?}",
+            ip, proxied, ttl
+        );
+        blocklists.unblocked.remove(&(ip, proxied));
+        if blocklist.insert(ip, entry).is_none() {
+            gauge.inc();
+        }
+        Ok(())
+    }
+
+    /// Returns true if the client was in the blocklist. The unblock is
+    /// remembered until the removed block would have expired, so that
+    /// it is not undone by importing an older snapshot.
+    pub fn admin_unblock(&self, ip: IpAddr, proxied: bool) -> Result<bool, SuiError> {
+        let blocklists = self.blocklists()?;
+        let (blocklist, gauge) = if proxied {
+            (
+                &blocklists.proxied_clients,
+                &self.metrics.proxy_ip_blocklist_len,
+            )
+        } else {
+            (
+                &blocklists.clients,
+                &self.metrics.connection_ip_blocklist_len,
+            )
+        };
+        info!("Admin unblocking {:?} (proxied: {})", ip, proxied);
+        let Some((_, removed)) = blocklist.remove(&ip) else {
+            return Ok(false);
+        };
+        gauge.dec();
+        blocklists.unblocked.insert(
+            (ip, proxied),
+            UnblockEntry {
+                unblocked_at: SystemTime::now(),
+                expiration: removed.expiration,
+            },
+        );
+        Ok(true)
+    }
+
+    /// Returns up to `n` clients with the highest recent rates, for each of
+    /// the spam and error policies that is a `FreqThresholdPolicy`.
+    pub async fn admin_top_clients(&self, n: usize) -> TrafficControlTopClients {
+        let top_clients = |policy: &TrafficControlPolicy| match policy {
+            TrafficControlPolicy::FreqThreshold(policy) => Some(policy.top_clients(n)),
+            _ => None,
+        };
+        TrafficControlTopClients {
+            spam: match &self.spam_policy {
+                Some(policy) => top_clients(&*policy.lock().await),
+                None => None,
+            },
+            error: match &self.error_policy {
+                Some(policy) => top_clients(&*policy.lock().await),
+                None => None,
+            },
+        }
+    }
+
+    pub async fn admin_client_status(&self, ip: IpAddr) -> Result<ClientStatus, SuiError> {
+        let blocklists = self.blocklists()?;
+        let now = SystemTime::now();
+        let lookup = |blocklist: &Blocklist, proxied| {
+            blocklist
+                .get(&ip)
+                .and_then(|entry| blocklist_entry_info(ip, entry.value(), proxied, now))
+        };
+        Ok(ClientStatus {
+            ip,
+            blocked: lookup(&blocklists.clients, false),
+            blocked_as_proxied: lookup(&blocklists.proxied_clients, true),
+            spam: match &self.spam_policy {
+                Some(policy) => policy.lock().await.client_usage(&ip),
+                None => None,
+            },
+            error: match &self.error_policy {
+                Some(policy) => policy.lock().await.client_usage(&ip),
+                None => None,
+            },
+        })
+    }
+
+    fn policy_threshold(policy: &TrafficControlPolicy) -> Option<u64> {
+        match policy {
+            TrafficControlPolicy::FreqThreshold(policy) => Some(policy.client_threshold),
+            TrafficControlPolicy::SlidingWindowLog(policy) => Some(policy.client_threshold()),
+            _ => None,
+        }
+    }
+
     async fn update_policy_threshold(
         policy: &Arc<Mutex<TrafficControlPolicy>>,
         threshold: u64,
@@ -275,6 +559,13 @@ impl TrafficController {
                 }
                 Ok(())
             }
+            TrafficControlPolicy::SlidingWindowLog(ref mut policy) => {
+                policy.set_client_threshold(threshold);
+                if let Some(dry_run) = dry_run {
+                    policy.config.dry_run = dry_run;
+                }
+                Ok(())
+            }
             TrafficControlPolicy::TestNConnIP(ref mut policy) => {
                 policy.threshold = threshold;
                 if let Some(dry_run) = dry_run {
@@ -297,21 +588,33 @@ impl TrafficController {
         policy_config: &PolicyConfig,
         metrics: Arc<TrafficControllerMetrics>,
     ) {
-        if let PolicyType::FreqThreshold(config) = &policy_config.spam_policy_type {
-            metrics
-                .spam_client_threshold
-                .set(config.client_threshold as i64);
+        if let Some((client_threshold, proxied_client_threshold)) =
+            Self::policy_type_thresholds(&policy_config.spam_policy_type)
+        {
+            metrics.spam_client_threshold.set(client_threshold as i64);
             metrics
                 .spam_proxied_client_threshold
-                .set(config.proxied_client_threshold as i64);
+                .set(proxied_client_threshold as i64);
         }
-        if let PolicyType::FreqThreshold(config) = &policy_config.error_policy_type {
-            metrics
-                .error_client_threshold
-                .set(config.client_threshold as i64);
+        if let Some((client_threshold, proxied_client_threshold)) =
+            Self::policy_type_thresholds(&policy_config.error_policy_type)
+        {
+            metrics.error_client_threshold.set(client_threshold as i64);
             metrics
                 .error_proxied_client_threshold
-                .set(config.proxied_client_threshold as i64);
+                .set(proxied_client_threshold as i64);
+        }
+    }
+
+    fn policy_type_thresholds(policy_type: &PolicyType) -> Option<(u64, u64)> {
+        match policy_type {
+            PolicyType::FreqThreshold(config) => {
+                Some((config.client_threshold, config.proxied_client_threshold))
+            }
+            PolicyType::SlidingWindowLog(config) => {
+                Some((config.client_threshold, config.proxied_client_threshold))
+            }
+            _ => None,
         }
     }
 
@@ -413,7 +716,7 @@ impl TrafficController {
         // due to aquiring the lock on get, then holding across the remove
         let (should_block, should_remove) = {
             match blocklist.get(client) {
-                Some(expiration) if now >= *expiration => (false, true),
+                Some(entry) if now >= entry.expiration => (false, true),
                 None => (false, false),
                 _ => (true, false),
             }
@@ -435,10 +738,10 @@ async fn run_clear_blocklists_loop(blocklists: Blocklists, metrics: Arc<TrafficC
     loop {
         tokio::time::sleep(Duration::from_secs(3)).await;
         let now = SystemTime::now();
-        blocklists.clients.retain(|_, expiration| now < *expiration);
+        blocklists.clients.retain(|_, entry| now < entry.expiration);
         blocklists
             .proxied_clients
-            .retain(|_, expiration| now < *expiration);
+            .retain(|_, entry| now < entry.expiration);
         metrics
             .connection_ip_blocklist_len
             .set(blocklists.clients.len() as i64);
@@ -450,7 +753,7 @@ async fn run_clear_blocklists_loop(blocklists: Blocklists, metrics: Arc<TrafficC
 
 async fn run_tally_loop(
     mut receiver: mpsc::Receiver<TrafficTally>,
-    policy_config: PolicyConfig,
+    shared_policy_config: Arc<RwLock<Arc<PolicyConfig>>>,
     spam_policy: Arc<Mutex<TrafficControlPolicy>>,
     error_policy: Arc<Mutex<TrafficControlPolicy>>,
     fw_config: Option<RemoteFirewallConfig>,
@@ -476,6 +779,9 @@ async fn run_tally_loop(
                 metrics.tallies.inc();
                 match received {
                     Some(tally) => {
+                        // Read per tally, as the config may be swapped at runtime
+                        // through the admin interface
+                        let policy_config = { shared_policy_config.read().await.clone() };
                         // TODO: spawn a task to handle tallying concurrently
                         if let Err(err) = handle_spam_tally(
                             spam_policy.clone(),
@@ -618,7 +924,7 @@ async fn handle_error_tally(
         )
         .await;
     }
-    handle_policy_response(resp, policy_config, blocklists, metrics).await;
+    handle_policy_response(resp, policy_config, blocklists, metrics, BlockReason::Error).await;
     Ok(())
 }
 
@@ -653,7 +959,7 @@ async fn handle_spam_tally(
         )
         .await;
     }
-    handle_policy_response(resp, policy_config, blocklists, metrics).await;
+    handle_policy_response(resp, policy_config, blocklists, metrics, BlockReason::Spam).await;
     Ok(())
 }
 
@@ -662,6 +968,7 @@ async fn handle_policy_response(
     policy_config: &PolicyConfig,
     blocklists: Arc<Blocklists>,
     metrics: Arc<TrafficControllerMetrics>,
+    reason: BlockReason,
 ) {
     let PolicyResponse {
         block_client,
@@ -677,7 +984,11 @@ async fn handle_policy_response(
             .clients
             .insert(
                 client,
-                SystemTime::now() + Duration::from_secs(*connection_blocklist_ttl_sec),
+                BlocklistEntry {
+                    expiration: SystemTime::now()
+                        + Duration::from_secs(*connection_blocklist_ttl_sec),
+                    reason,
+                },
             )
             .is_none()
     {
@@ -690,7 +1001,10 @@ async fn handle_policy_response(
             .proxied_clients
             .insert(
                 client,
-                SystemTime::now() + Duration::from_secs(*proxy_blocklist_ttl_sec),
+                BlocklistEntry {
+                    expiration: SystemTime::now() + Duration::from_secs(*proxy_blocklist_ttl_sec),
+                    reason,
+                },
             )
             .is_none()
     {
@@ -992,6 +1306,21 @@ impl TrafficSim {
     }
 }
 
+fn blocklist_entry_info(
+    ip: IpAddr,
+    entry: &BlocklistEntry,
+    proxied: bool,
+    now: SystemTime,
+) -> Option<BlocklistEntryInfo> {
+    let expires_in = entry.expiration.duration_since(now).ok()?;
+    Some(BlocklistEntryInfo {
+        ip,
+        proxied,
+        reason: entry.reason,
+        expires_in_secs: expires_in.as_secs(),
+    })
+}
+
 pub fn parse_ip(ip: &str) -> Option<IpAddr> {
     ip.parse::<IpAddr>().ok().or_else(|| {
         ip.parse::<SocketAddr>()
@@ -0,0 +1,579 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Snapshotting of `Blocklists` to disk, so that they survive restarts,
+//! and import/export of blocklists through a directory shared between
+//! a fleet of nodes. Files use the `NodeFWClient` shapes, with TTLs
+//! relative to the time at which the file was written.
+//!
+//! Admin unblocks are persisted as tombstones alongside the blocks, so
+//! that a block is not brought back by a snapshot taken before it was
+//! lifted, whether that snapshot is our own or another node's.
+
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use sui_types::traffic_control::BlocklistPersistenceConfig;
+use tokio::fs;
+use tracing::{debug, info, warn};
+
+use super::metrics::TrafficControllerMetrics;
+use super::nodefw_client::{BlockAddress, BlockAddresses};
+use super::{
+    BlockReason, Blocklist, BlocklistEntry, Blocklists, UnblockEntry, Unblocklist, parse_ip,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BlocklistSnapshot {
+    /// Seconds since the unix epoch at which the snapshot was
+    /// taken. The `ttl` of each address is relative to this.
+    pub timestamp_secs: u64,
+    pub clients: BlockAddresses,
+    pub proxied_clients: BlockAddresses,
+    /// Clients unblocked through the admin interface.
+    #[serde(default)]
+    pub unblocked: Vec<UnblockedAddress>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct UnblockedAddress {
+    pub source_address: String,
+    pub proxied: bool,
+    /// Seconds since the unix epoch at which the client was unblocked.
+    pub unblocked_at_secs: u64,
+    /// Seconds after the snapshot's timestamp at which the tombstone expires.
+    pub ttl: u64,
+}
+
+impl BlocklistSnapshot {
+    pub fn new(blocklists: &Blocklists, destination_port: u16) -> Self {
+        let now = SystemTime::now();
+        Self {
+            timestamp_secs: unix_secs(now),
+            clients: to_block_addresses(&blocklists.clients, now, destination_port),
+            proxied_clients: to_block_addresses(&blocklists.proxied_clients, now, destination_port),
+            unblocked: to_unblocked_addresses(&blocklists.unblocked, now),
+        }
+    }
+
+    /// Merges the unexpired entries of this snapshot into `blocklists`.
+    /// An entry already present keeps whichever expiration is later, and
+    /// entries unblocked after the snapshot was taken are skipped.
+    /// Returns the number of entries that were merged.
+    pub fn merge_into(&self, blocklists: &Blocklists, reason: BlockReason) -> usize {
+        let taken_at = UNIX_EPOCH + Duration::from_secs(self.timestamp_secs);
+        let now = SystemTime::now();
+        merge_unblocked_addresses(&self.unblocked, blocklists, taken_at, now);
+        merge_block_addresses(
+            &self.clients,
+            &blocklists.clients,
+            &blocklists.unblocked,
+            false,
+            taken_at,
+            now,
+            reason,
+        ) + merge_block_addresses(
+            &self.proxied_clients,
+            &blocklists.proxied_clients,
+            &blocklists.unblocked,
+            true,
+            taken_at,
+            now,
+            reason,
+        )
+    }
+
+    pub async fn read(path: &Path) -> anyhow::Result<Self> {
+        let bytes = fs::read(path)
+            .await
+            .with_context(|| format!("Failed to read {:?}", path))?;
+        serde_json::from_slice(&bytes).with_context(|| format!("Failed to parse {:?}", path))
+    }
+
+    /// Writes the snapshot through a temporary file, so that readers
+    /// (including other nodes syncing a shared directory) never observe
+    /// a partially written file.
+    pub async fn write(&self, path: &Path) -> anyhow::Result<()> {
+        let tmp_path = path.with_extension("tmp");
+        fs::write(&tmp_path, serde_json::to_vec_pretty(self)?)
+            .await
+            .with_context(|| format!("Failed to write {:?}", tmp_path))?;
+        fs::rename(&tmp_path, path)
+            .await
+            .with_context(|| format!("Failed to rename {:?} to {:?}", tmp_path, path))
+    }
+}
+
+fn to_block_addresses(
+    blocklist: &Blocklist,
+    now: SystemTime,
+    destination_port: u16,
+) -> BlockAddresses {
+    let addresses = blocklist
+        .iter()
+        .filter_map(|entry| {
+            let ttl = entry.value().expiration.duration_since(now).ok()?.as_secs();
+            (ttl > 0).then(|| BlockAddress {
+                source_address: entry.key().to_string(),
+                destination_port,
+                ttl,
+            })
+        })
+        .collect();
+    BlockAddresses { addresses }
+}
+
+fn to_unblocked_addresses(unblocked: &Unblocklist, now: SystemTime) -> Vec<UnblockedAddress> {
+    unblocked
+        .iter()
+        .filter_map(|entry| {
+            let (ip, proxied) = *entry.key();
+            let ttl = entry.value().expiration.duration_since(now).ok()?.as_secs();
+            (ttl > 0).then(|| UnblockedAddress {
+                source_address: ip.to_string(),
+                proxied,
+                unblocked_at_secs: unix_secs(entry.value().unblocked_at),
+                ttl,
+            })
+        })
+        .collect()
+}
+
+fn merge_block_addresses(
+    addresses: &BlockAddresses,
+    blocklist: &Blocklist,
+    unblocked: &Unblocklist,
+    proxied: bool,
+    taken_at: SystemTime,
+    now: SystemTime,
+    reason: BlockReason,
+) -> usize {
+    let mut merged = 0;
+    for address in &addresses.addresses {
+        let Some(ip) = parse_ip(&address.source_address) else {
+            continue;
+        };
+        let Some(expiration) = taken_at.checked_add(Duration::from_secs(address.ttl)) else {
+            continue;
+        };
+        if expiration <= now {
+            continue;
+        }
+        let unblocked = unblocked.get(&(ip, proxied)).map(|entry| *entry);
+        if merge_entry(blocklist, unblocked, ip, expiration, reason, taken_at, now) {
+            merged += 1;
+        }
+    }
+    merged
+}
+
+/// Merges a block from a snapshot taken at `taken_at`, unless the client
+/// has been unblocked since. Returns whether it was merged. A block that
+/// already exists keeps its reason and only has its expiration extended,
+/// so that a local block is not later lifted as if it came from a snapshot.
+///
+/// Our own snapshot is consistent with our unblocks, so only its blocks
+/// that predate the unblock are skipped. Other nodes may still hold blocks
+/// they imported from us before they learnt of the unblock, so none of
+/// their blocks are imported until the unblock expires.
+fn merge_entry(
+    blocklist: &Blocklist,
+    unblocked: Option<UnblockEntry>,
+    ip: IpAddr,
+    expiration: SystemTime,
+    reason: BlockReason,
+    taken_at: SystemTime,
+    now: SystemTime,
+) -> bool {
+    if let Some(unblocked) = unblocked
+        && unblocked.expiration > now
+        && (reason == BlockReason::Imported || unblocked.unblocked_at >= taken_at)
+    {
+        return false;
+    }
+    blocklist
+        .entry(ip)
+        .and_modify(|existing| existing.expiration = existing.expiration.max(expiration))
+        .or_insert(BlocklistEntry { expiration, reason });
+    true
+}
+
+/// Merges the tombstones of a snapshot, keeping the latest unblock of each
+/// client. Blocks that this node only has from snapshots, and that predate
+/// the unblock, are lifted too, so that an unblock spreads through the fleet.
+fn merge_unblocked_addresses(
+    addresses: &[UnblockedAddress],
+    blocklists: &Blocklists,
+    taken_at: SystemTime,
+    now: SystemTime,
+) {
+    for address in addresses {
+        let Some(ip) = parse_ip(&address.source_address) else {
+            continue;
+        };
+        let unblocked_at = UNIX_EPOCH + Duration::from_secs(address.unblocked_at_secs);
+        let Some(expiration) = taken_at.checked_add(Duration::from_secs(address.ttl)) else {
+            continue;
+        };
+        if expiration <= now {
+            continue;
+        }
+
+        let key = (ip, address.proxied);
+        if blocklists
+            .unblocked
+            .get(&key)
+            .is_some_and(|existing| existing.unblocked_at >= unblocked_at)
+        {
+            continue;
+        }
+        blocklists.unblocked.insert(
+            key,
+            UnblockEntry {
+                unblocked_at,
+                expiration,
+            },
+        );
+
+        let blocklist = if address.proxied {
+            &blocklists.proxied_clients
+        } else {
+            &blocklists.clients
+        };
+        blocklist.remove_if(&ip, |_, entry| {
+            matches!(entry.reason, BlockReason::Restored | BlockReason::Imported)
+        });
+    }
+}
+
+fn unix_secs(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+fn export_path(config: &BlocklistPersistenceConfig) -> Option<PathBuf> {
+    match (&config.shared_dir, &config.export_name) {
+        (Some(shared_dir), Some(export_name)) => {
+            Some(shared_dir.join(format!("{}.json", export_name)))
+        }
+        _ => None,
+    }
+}
+
+/// Restores the local snapshot, if any, and imports the shared directory.
+pub async fn restore_blocklists(
+    config: &BlocklistPersistenceConfig,
+    blocklists: &Blocklists,
+    metrics: &TrafficControllerMetrics,
+) {
+    if fs::try_exists(&config.snapshot_path)
+        .await
+        .unwrap_or_default()
+    {
+        match BlocklistSnapshot::read(&config.snapshot_path).await {
+            Ok(snapshot) => {
+                let restored = snapshot.merge_into(blocklists, BlockReason::Restored);
+                info!(
+                    "Restored {} blocklist entries from {:?}",
+                    restored, config.snapshot_path
+                );
+            }
+            Err(err) => warn!("Failed to restore blocklist snapshot: {:?}", err),
+        }
+    }
+    import_shared_dir(config, blocklists).await;
+    update_blocklist_len_metrics(blocklists, metrics);
+}
+
+/// Merges every snapshot in the shared directory other than our own export.
+async fn import_shared_dir(config: &BlocklistPersistenceConfig, blocklists: &Blocklists) {
+    let Some(shared_dir) = &config.shared_dir else {
+        return;
+    };
+    let own_export = export_path(config);
+    let mut entries = match fs::read_dir(shared_dir).await {
+        Ok(entries) => entries,
+        Err(err) => {
+            warn!(
+                "Failed to read shared blocklist directory {:?}: {:?}",
+                shared_dir, err
+            );
+            return;
+        }
+    };
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let path = entry.path();
+        if path.extension().is_none_or(|extension| extension != "json")
+            || own_export.as_ref() == Some(&path)
+        {
+            continue;
+        }
+        match BlocklistSnapshot::read(&path).await {
+            Ok(snapshot) => {
+                let imported = snapshot.merge_into(blocklists, BlockReason::Imported);
+                debug!("Imported {} blocklist entries from {:?}", imported, path);
+            }
+            Err(err) => warn!("Failed to import shared blocklist: {:?}", err),
+        }
+    }
+}
+
+fn update_blocklist_len_metrics(blocklists: &Blocklists, metrics: &TrafficControllerMetrics) {
+    metrics
+        .connection_ip_blocklist_len
+        .set(blocklists.clients.len() as i64);
+    metrics
+        .proxy_ip_blocklist_len
+        .set(blocklists.proxied_clients.len() as i64);
+}
+
+/// Periodically snapshots the blocklists, exports them to the shared
+/// directory, and imports the snapshots of other nodes from it.
+pub(crate) async fn run_persist_blocklists_loop(
+    config: BlocklistPersistenceConfig,
+    blocklists: Blocklists,
+    destination_port: u16,
+    metrics: Arc<TrafficControllerMetrics>,
+) {
+    let export_path = export_path(&config);
+    loop {
+        tokio::time::sleep(Duration::from_secs(config.snapshot_interval_secs)).await;
+        let snapshot = BlocklistSnapshot::new(&blocklists, destination_port);
+        if let Err(err) = snapshot.write(&config.snapshot_path).await {
+            warn!("Failed to snapshot blocklists: {:?}", err);
+        }
+        if let Some(export_path) = &export_path
+            && let Err(err) = snapshot.write(export_path).await
+        {
+            warn!("Failed to export blocklists: {:?}", err);
+        }
+        import_shared_dir(&config, &blocklists).await;
+        let now = SystemTime::now();
+        blocklists
+            .unblocked
+            .retain(|_, unblocked| unblocked.expiration > now);
+        update_blocklist_len_metrics(&blocklists, &metrics);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dashmap::DashMap;
+    use std::net::Ipv4Addr;
+
+    fn empty_blocklists() -> Blocklists {
+        Blocklists {
+            clients: Arc::new(DashMap::new()),
+            proxied_clients: Arc::new(DashMap::new()),
+            unblocked: Arc::new(DashMap::new()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_blocklist_snapshot_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let now = SystemTime::now();
+        let alice = IpAddr::V4(Ipv4Addr::new(8, 7, 6, 5));
+        let bob = IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4));
+        let charlie = IpAddr::V4(Ipv4Addr::new(5, 6, 7, 8));
+
+        let blocklists = empty_blocklists();
+        let entry = |expiration| BlocklistEntry {
+            expiration,
+            reason: BlockReason::Spam,
+        };
+        blocklists
+            .clients
+            .insert(alice, entry(now + Duration::from_secs(600)));
+        // already expired, should not be snapshotted
+        blocklists
+            .clients
+            .insert(charlie, entry(now - Duration::from_secs(1)));
+        blocklists
+            .proxied_clients
+            .insert(bob, entry(now + Duration::from_secs(60)));
+
+        let path = dir.path().join("blocklists.json");
+        BlocklistSnapshot::new(&blocklists, 8080)
+            .write(&path)
+            .await
+            .unwrap();
+        let snapshot = BlocklistSnapshot::read(&path).await.unwrap();
+        assert_eq!(snapshot.clients.addresses.len(), 1);
+        assert_eq!(snapshot.clients.addresses[0].source_address, "8.7.6.5");
+        assert_eq!(snapshot.clients.addresses[0].destination_port, 8080);
+        assert!(snapshot.clients.addresses[0].ttl > 590);
+
+        let restored = empty_blocklists();
+        // an existing entry with a later expiration is kept
+        let later = now + Duration::from_secs(6000);
+        restored.proxied_clients.insert(bob, entry(later));
+        assert_eq!(snapshot.merge_into(&restored, BlockReason::Restored), 2);
+        assert_eq!(
+            restored.clients.get(&alice).unwrap().reason,
+            BlockReason::Restored
+        );
+        assert!(!restored.clients.contains_key(&charlie));
+        let bob_entry = *restored.proxied_clients.get(&bob).unwrap();
+        assert_eq!(bob_entry.expiration, later);
+        assert_eq!(bob_entry.reason, BlockReason::Spam);
+    }
+
+    #[tokio::test]
+    async fn test_import_shared_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = BlocklistPersistenceConfig {
+            snapshot_path: dir.path().join("local.snapshot"),
+            snapshot_interval_secs: 1,
+            shared_dir: Some(dir.path().to_path_buf()),
+            export_name: Some("node-a".to_string()),
+        };
+        let alice = IpAddr::V4(Ipv4Addr::new(8, 7, 6, 5));
+        let bob = IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4));
+        let timestamp_secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let snapshot_of = |ip: IpAddr| BlocklistSnapshot {
+            timestamp_secs,
+            clients: BlockAddresses {
+                addresses: vec![BlockAddress {
+                    source_address: ip.to_string(),
+                    destination_port: 0,
+                    ttl: 600,
+                }],
+            },
+            proxied_clients: BlockAddresses { addresses: vec![] },
+            unblocked: vec![],
+        };
+        // our own export is skipped, other nodes' exports are imported
+        snapshot_of(alice)
+            .write(&dir.path().join("node-a.json"))
+            .await
+            .unwrap();
+        snapshot_of(bob)
+            .write(&dir.path().join("node-b.json"))
+            .await
+            .unwrap();
+
+        let blocklists = empty_blocklists();
+        let metrics = TrafficControllerMetrics::new_for_tests();
+        restore_blocklists(&config, &blocklists, &metrics).await;
+        assert!(!blocklists.clients.contains_key(&alice));
+        assert_eq!(
+            blocklists.clients.get(&bob).unwrap().reason,
+            BlockReason::Imported
+        );
+        assert_eq!(metrics.connection_ip_blocklist_len.get(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_unblock_tombstones() {
+        let dir = tempfile::tempdir().unwrap();
+        let now = SystemTime::now();
+        let alice = IpAddr::V4(Ipv4Addr::new(8, 7, 6, 5));
+        let bob = IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4));
+        let entry = |reason| BlocklistEntry {
+            expiration: now + Duration::from_secs(600),
+            reason,
+        };
+
+        // A snapshot taken while alice and bob were blocked.
+        let blocklists = empty_blocklists();
+        blocklists.clients.insert(alice, entry(BlockReason::Admin));
+        blocklists.clients.insert(bob, entry(BlockReason::Spam));
+        let mut before = BlocklistSnapshot::new(&blocklists, 0);
+        before.timestamp_secs -= 10;
+
+        // Alice is unblocked afterwards, which is persisted as a tombstone.
+        blocklists.clients.remove(&alice);
+        blocklists.unblocked.insert(
+            (alice, false),
+            UnblockEntry {
+                unblocked_at: now,
+                expiration: now + Duration::from_secs(600),
+            },
+        );
+        let path = dir.path().join("blocklists.json");
+        BlocklistSnapshot::new(&blocklists, 0)
+            .write(&path)
+            .await
+            .unwrap();
+        let after = BlocklistSnapshot::read(&path).await.unwrap();
+        assert_eq!(after.unblocked.len(), 1);
+        assert_eq!(after.unblocked[0].source_address, "8.7.6.5");
+        assert!(!after.unblocked[0].proxied);
+
+        // Neither our own older snapshot nor another node's snapshot bring the block back.
+        assert_eq!(before.merge_into(&blocklists, BlockReason::Restored), 1);
+        assert!(!blocklists.clients.contains_key(&alice));
+        assert_eq!(before.merge_into(&blocklists, BlockReason::Imported), 1);
+        assert!(!blocklists.clients.contains_key(&alice));
+        assert!(blocklists.clients.contains_key(&bob));
+
+        // Another node importing the tombstone lifts the blocks it imported, but
+        // not the ones it decided on itself.
+        let other = empty_blocklists();
+        other.clients.insert(alice, entry(BlockReason::Imported));
+        other
+            .proxied_clients
+            .insert(alice, entry(BlockReason::Imported));
+        after.merge_into(&other, BlockReason::Imported);
+        assert!(!other.clients.contains_key(&alice));
+        assert!(other.proxied_clients.contains_key(&alice));
+        assert!(other.unblocked.contains_key(&(alice, false)));
+
+        let other = empty_blocklists();
+        other.clients.insert(alice, entry(BlockReason::Spam));
+        after.merge_into(&other, BlockReason::Imported);
+        assert_eq!(other.clients.get(&alice).unwrap().reason, BlockReason::Spam);
+    }
+
+    #[test]
+    fn test_import_keeps_local_reason() {
+        let now = SystemTime::now();
+        let alice = IpAddr::V4(Ipv4Addr::new(8, 7, 6, 5));
+
+        // Another node blocked alice for longer than we did.
+        let remote = empty_blocklists();
+        remote.clients.insert(
+            alice,
+            BlocklistEntry {
+                expiration: now + Duration::from_secs(6000),
+                reason: BlockReason::Spam,
+            },
+        );
+        let snapshot = BlocklistSnapshot::new(&remote, 0);
+
+        let blocklists = empty_blocklists();
+        blocklists.clients.insert(
+            alice,
+            BlocklistEntry {
+                expiration: now + Duration::from_secs(60),
+                reason: BlockReason::Error,
+            },
+        );
+        assert_eq!(snapshot.merge_into(&blocklists, BlockReason::Imported), 1);
+        let merged = *blocklists.clients.get(&alice).unwrap();
+        assert_eq!(merged.reason, BlockReason::Error);
+        assert!(merged.expiration > now + Duration::from_secs(5000));
+
+        // So a remote tombstone does not lift our own block.
+        remote.clients.remove(&alice);
+        remote.unblocked.insert(
+            (alice, false),
+            UnblockEntry {
+                unblocked_at: now,
+                expiration: now + Duration::from_secs(600),
+            },
+        );
+        BlocklistSnapshot::new(&remote, 0).merge_into(&blocklists, BlockReason::Imported);
+        assert!(blocklists.clients.contains_key(&alice));
+    }
+}
@@ -5,19 +5,31 @@
 use std::{collections::HashMap, net::IpAddr, sync::Arc};
 
 use count_min_sketch::CountMinSketch32;
+use lru::LruCache;
 use mysten_metrics::spawn_monitored_task;
+
+use super::metrics::TrafficControllerMetrics;
+use super::{ClientRate, TopClients};
 use parking_lot::RwLock;
+use serde::Serialize;
 use std::cmp::Reverse;
 use std::collections::{BinaryHeap, VecDeque};
 use std::fmt::Debug;
 use std::hash::Hash;
+use std::num::NonZeroUsize;
 use std::time::Duration;
 use std::time::{Instant, SystemTime};
-use sui_types::traffic_control::{FreqThresholdConfig, PolicyConfig, PolicyType, Weight};
-use tracing::{info, trace};
+use sui_types::traffic_control::{
+    CompositeConfig, CompositeMode, FreqThresholdConfig, PolicyConfig, PolicyType,
+    SlidingWindowLogConfig, TallyWeights, TokenBucketConfig, Weight,
+};
+use tracing::{debug, info, trace};
 
 const HIGHEST_RATES_CAPACITY: usize = 20;
 
+pub const SPAM_POLICY_ROLE: &str = "spam";
+pub const ERROR_POLICY_ROLE: &str = "error";
+
 /// The type of request client.
 #[derive(Hash, Eq, PartialEq, Debug)]
 enum ClientType {
@@ -221,6 +233,31 @@ impl TrafficSketch {
             .copied()
     }
 
+    /// Up to `n` of the highest recent rates, highest first. At most
+    /// `HIGHEST_RATES_CAPACITY` rates are tracked per client type.
+    fn top_rates(&self, client_type: ClientType, n: usize) -> Vec<(u64, IpAddr)> {
+        let heap = match client_type {
+            ClientType::Direct => &self.highest_rates.direct,
+            ClientType::ThroughFullnode => &self.highest_rates.proxied,
+        };
+        let mut rates: Vec<_> = heap.iter().map(|Reverse(v)| *v).collect();
+        rates.sort_by(|a, b| b.cmp(a));
+        rates.truncate(n);
+        rates
+    }
+
+    /// Like `get_request_rate`, but without updating the highest rates.
+    /// Windows are only rotated on tally, so this reflects the rate as
+    /// of the most recent tally.
+    fn estimate_request_rate(&self, key: &SketchKey) -> f64 {
+        let count: u32 = self
+            .sketches
+            .iter()
+            .map(|sketch| sketch.estimate(key))
+            .sum();
+        count as f64 / self.window_size.as_secs() as f64
+    }
+
     fn rotate_window(&mut self) {
         self.current_sketch_index = (self.current_sketch_index + 1) % self.sketches.len();
         self.sketches[self.current_sketch_index].clear();
@@ -234,6 +271,9 @@ pub struct TrafficTally {
     pub through_fullnode: Option<IpAddr>,
     pub error_info: Option<(Weight, String)>,
     pub spam_weight: Weight,
+    /// Identifies the endpoint or method that was served, so that
+    /// policies may weigh request types differently
+    pub request_type: Option<String>,
     pub timestamp: SystemTime,
 }
 
@@ -249,9 +289,35 @@ impl TrafficTally {
             through_fullnode,
             error_info,
             spam_weight,
+            request_type: None,
             timestamp: SystemTime::now(),
         }
     }
+
+    pub fn with_request_type(mut self, request_type: impl Into<String>) -> Self {
+        self.request_type = Some(request_type.into());
+        self
+    }
+
+    fn cost(&self, weights: &TallyWeights) -> f64 {
+        weights.cost(
+            self.request_type.as_deref(),
+            self.error_info
+                .as_ref()
+                .map(|(_, error_type)| error_type.as_str()),
+        )
+    }
+}
+
+/// How much of its thresholds a client has used up under a policy,
+/// in the units of the policy (a rate for `FreqThreshold`, a weighted
+/// cost for `SlidingWindowLog`).
+#[derive(Clone, Debug, Serialize)]
+pub struct PolicyClientUsage {
+    pub direct: Option<f64>,
+    pub direct_threshold: u64,
+    pub proxied: Option<f64>,
+    pub proxied_threshold: u64,
 }
 
 #[derive(Clone, Debug, Default)]
@@ -271,6 +337,9 @@ pub trait Policy {
 // not object safe, so we can't use a trait object instead
 pub enum TrafficControlPolicy {
     FreqThreshold(FreqThresholdPolicy),
+    TokenBucket(TokenBucketPolicy),
+    SlidingWindowLog(SlidingWindowLogPolicy),
+    Composite(CompositePolicy),
     NoOp(NoOpPolicy),
     // Test policies below this point
     TestNConnIP(TestNConnIPPolicy),
@@ -282,6 +351,9 @@ impl Policy for TrafficControlPolicy {
         match self {
             TrafficControlPolicy::NoOp(policy) => policy.handle_tally(tally),
             TrafficControlPolicy::FreqThreshold(policy) => policy.handle_tally(tally),
+            TrafficControlPolicy::TokenBucket(policy) => policy.handle_tally(tally),
+            TrafficControlPolicy::SlidingWindowLog(policy) => policy.handle_tally(tally),
+            TrafficControlPolicy::Composite(policy) => policy.handle_tally(tally),
             TrafficControlPolicy::TestNConnIP(policy) => policy.handle_tally(tally),
             TrafficControlPolicy::TestPanicOnInvocation(policy) => policy.handle_tally(tally),
         }
@@ -291,6 +363,9 @@ impl Policy for TrafficControlPolicy {
         match self {
             TrafficControlPolicy::NoOp(policy) => policy.policy_config(),
             TrafficControlPolicy::FreqThreshold(policy) => policy.policy_config(),
+            TrafficControlPolicy::TokenBucket(policy) => policy.policy_config(),
+            TrafficControlPolicy::SlidingWindowLog(policy) => policy.policy_config(),
+            TrafficControlPolicy::Composite(policy) => policy.policy_config(),
             TrafficControlPolicy::TestNConnIP(policy) => policy.policy_config(),
             TrafficControlPolicy::TestPanicOnInvocation(policy) => policy.policy_config(),
         }
@@ -298,18 +373,61 @@ impl Policy for TrafficControlPolicy {
 }
 
 impl TrafficControlPolicy {
-    pub async fn from_spam_config(policy_config: PolicyConfig) -> Self {
-        Self::from_config(policy_config.clone().spam_policy_type, policy_config).await
+    /// Returns None for policies that do not track usage per client.
+    pub fn client_usage(&self, ip_addr: &IpAddr) -> Option<PolicyClientUsage> {
+        match self {
+            TrafficControlPolicy::FreqThreshold(policy) => Some(policy.client_usage(ip_addr)),
+            TrafficControlPolicy::SlidingWindowLog(policy) => Some(policy.client_usage(ip_addr)),
+            _ => None,
+        }
     }
-    pub async fn from_error_config(policy_config: PolicyConfig) -> Self {
-        Self::from_config(policy_config.clone().error_policy_type, policy_config).await
+
+    pub async fn from_spam_config(
+        policy_config: PolicyConfig,
+        metrics: Arc<TrafficControllerMetrics>,
+    ) -> Self {
+        Self::from_config(
+            policy_config.clone().spam_policy_type,
+            policy_config,
+            SPAM_POLICY_ROLE,
+            metrics,
+        )
+        .await
     }
-    pub async fn from_config(policy_type: PolicyType, policy_config: PolicyConfig) -> Self {
+    pub async fn from_error_config(
+        policy_config: PolicyConfig,
+        metrics: Arc<TrafficControllerMetrics>,
+    ) -> Self {
+        Self::from_config(
+            policy_config.clone().error_policy_type,
+            policy_config,
+            ERROR_POLICY_ROLE,
+            metrics,
+        )
+        .await
+    }
+    /// `role` and `metrics` are only used by composite policies, to
+    /// label the metrics of their child policies.
+    pub async fn from_config(
+        policy_type: PolicyType,
+        policy_config: PolicyConfig,
+        role: &'static str,
+        metrics: Arc<TrafficControllerMetrics>,
+    ) -> Self {
         match policy_type {
             PolicyType::NoOp => Self::NoOp(NoOpPolicy::new(policy_config)),
             PolicyType::FreqThreshold(freq_threshold_config) => Self::FreqThreshold(
                 FreqThresholdPolicy::new(policy_config, freq_threshold_config),
             ),
+            PolicyType::TokenBucket(token_bucket_config) => {
+                Self::TokenBucket(TokenBucketPolicy::new(policy_config, token_bucket_config))
+            }
+            PolicyType::SlidingWindowLog(sliding_window_log_config) => Self::SlidingWindowLog(
+                SlidingWindowLogPolicy::new(policy_config, sliding_window_log_config),
+            ),
+            PolicyType::Composite(composite_config) => Self::Composite(
+                CompositePolicy::new(policy_config, composite_config, role, metrics).await,
+            ),
             PolicyType::TestNConnIP(n) => {
                 Self::TestNConnIP(TestNConnIPPolicy::new(policy_config, n).await)
             }
@@ -373,6 +491,35 @@ impl FreqThresholdPolicy {
         self.sketch.highest_proxied_rate()
     }
 
+    pub fn top_clients(&self, n: usize) -> TopClients {
+        let to_client_rates = |rates: Vec<(u64, IpAddr)>| {
+            rates
+                .into_iter()
+                .map(|(rate, ip)| ClientRate { ip, rate })
+                .collect()
+        };
+        TopClients {
+            direct: to_client_rates(self.sketch.top_rates(ClientType::Direct, n)),
+            proxied: to_client_rates(self.sketch.top_rates(ClientType::ThroughFullnode, n)),
+        }
+    }
+
+    pub fn client_usage(&self, ip_addr: &IpAddr) -> PolicyClientUsage {
+        let rate = |client_type| {
+            self.sketch.estimate_request_rate(&SketchKey {
+                salt: self.salt,
+                ip_addr: *ip_addr,
+                client_type,
+            })
+        };
+        PolicyClientUsage {
+            direct: Some(rate(ClientType::Direct)),
+            direct_threshold: self.client_threshold,
+            proxied: Some(rate(ClientType::ThroughFullnode)),
+            proxied_threshold: self.proxied_client_threshold,
+        }
+    }
+
     pub fn handle_tally(&mut self, tally: TrafficTally) -> PolicyResponse {
         let block_client = if let Some(source) = tally.direct {
             let key = SketchKey {
@@ -420,6 +567,416 @@ impl FreqThresholdPolicy {
     }
 }
 
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+/// Token buckets for a single client type, keyed by IP. Once
+/// `max_tracked_clients` are tracked, a new client replaces the one
+/// charged least recently, whose bucket has had the longest to refill.
+struct TokenBuckets {
+    buckets: LruCache<IpAddr, TokenBucket>,
+    capacity: f64,
+    refill_rate: f64,
+}
+
+impl TokenBuckets {
+    fn new(capacity: f64, refill_rate: f64, max_tracked_clients: usize) -> Self {
+        assert!(capacity > 0.0, "Token bucket capacity must be positive");
+        assert!(
+            refill_rate >= 0.0,
+            "Token bucket refill rate may not be negative"
+        );
+        let max_tracked_clients = NonZeroUsize::new(max_tracked_clients)
+            .expect("Token bucket policy must track at least one client");
+        Self {
+            buckets: LruCache::new(max_tracked_clients),
+            capacity,
+            refill_rate,
+        }
+    }
+
+    /// Charges `cost` to the bucket of `ip_addr`, returning true if the
+    /// bucket did not hold enough tokens to pay for it.
+    fn charge(&mut self, ip_addr: IpAddr, cost: f64, now: Instant) -> bool {
+        let capacity = self.capacity;
+        let refill_rate = self.refill_rate;
+        if !self.buckets.contains(&ip_addr) {
+            self.buckets.put(
+                ip_addr,
+                TokenBucket {
+                    tokens: capacity,
+                    last_refill: now,
+                },
+            );
+        }
+        let bucket = self
+            .buckets
+            .get_mut(&ip_addr)
+            .expect("bucket was just inserted");
+        let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * refill_rate).min(capacity);
+        bucket.last_refill = now;
+        if bucket.tokens >= cost {
+            bucket.tokens -= cost;
+            false
+        } else {
+            // Drain the bucket so that a blocked client must wait for
+            // a full refill of `cost` before it may be admitted again.
+            bucket.tokens = 0.0;
+            true
+        }
+    }
+}
+
+pub struct TokenBucketPolicy {
+    pub config: PolicyConfig,
+    weights: TallyWeights,
+    clients: TokenBuckets,
+    proxied_clients: TokenBuckets,
+}
+
+impl TokenBucketPolicy {
+    pub fn new(
+        config: PolicyConfig,
+        TokenBucketConfig {
+            client_capacity,
+            client_refill_rate,
+            proxied_client_capacity,
+            proxied_client_refill_rate,
+            max_tracked_clients,
+            weights,
+        }: TokenBucketConfig,
+    ) -> Self {
+        Self {
+            config,
+            weights,
+            clients: TokenBuckets::new(client_capacity, client_refill_rate, max_tracked_clients),
+            proxied_clients: TokenBuckets::new(
+                proxied_client_capacity,
+                proxied_client_refill_rate,
+                max_tracked_clients,
+            ),
+        }
+    }
+
+    pub fn handle_tally(&mut self, tally: TrafficTally) -> PolicyResponse {
+        let cost = tally.cost(&self.weights);
+        let now = Instant::now();
+        let block_client = tally
+            .direct
+            .filter(|source| self.clients.charge(*source, cost, now));
+        let block_proxied_client = tally
+            .through_fullnode
+            .filter(|source| self.proxied_clients.charge(*source, cost, now));
+        trace!(
+            "TokenBucketPolicy handling tally -- cost: {:?}, block_client: {:?}, block_proxied_client: {:?}",
+            cost, block_client, block_proxied_client,
+        );
+        PolicyResponse {
+            block_client,
+            block_proxied_client,
+        }
+    }
+
+    fn policy_config(&self) -> &PolicyConfig {
+        &self.config
+    }
+}
+
+/// Entries closer together than this are merged into a single log
+/// entry, bounding the memory used per client to
+/// `window_size / SLIDING_WINDOW_LOG_RESOLUTION` entries.
+const SLIDING_WINDOW_LOG_RESOLUTION: Duration = Duration::from_millis(100);
+
+#[derive(Default)]
+struct ClientLog {
+    entries: VecDeque<(Instant, f64)>,
+    total: f64,
+}
+
+impl ClientLog {
+    fn expire(&mut self, cutoff: Instant) {
+        while let Some(&(timestamp, cost)) = self.entries.front() {
+            if timestamp > cutoff {
+                break;
+            }
+            self.entries.pop_front();
+            self.total = (self.total - cost).max(0.0);
+        }
+        if self.entries.is_empty() {
+            // avoid accumulating floating point error over long-lived clients
+            self.total = 0.0;
+        }
+    }
+
+    fn record(&mut self, now: Instant, cost: f64) {
+        match self.entries.back_mut() {
+            Some((timestamp, entry_cost))
+                if now.duration_since(*timestamp) < SLIDING_WINDOW_LOG_RESOLUTION =>
+            {
+                *entry_cost += cost;
+            }
+            _ => self.entries.push_back((now, cost)),
+        }
+        self.total += cost;
+    }
+}
+
+/// Sliding window logs for a single client type, keyed by IP. Once
+/// `max_tracked_clients` are tracked, a new client replaces the one
+/// seen least recently.
+struct ClientLogs {
+    logs: LruCache<IpAddr, ClientLog>,
+    threshold: f64,
+    window_size: Duration,
+}
+
+impl ClientLogs {
+    fn new(threshold: u64, window_size: Duration, max_tracked_clients: usize) -> Self {
+        assert!(
+            window_size >= Duration::from_secs(1),
+            "Sliding window size too short, must be at least 1 second"
+        );
+        let max_tracked_clients = NonZeroUsize::new(max_tracked_clients)
+            .expect("Sliding window log policy must track at least one client");
+        Self {
+            logs: LruCache::new(max_tracked_clients),
+            threshold: threshold as f64,
+            window_size,
+        }
+    }
+
+    /// Records `cost` for `ip_addr`, returning true if the total cost
+    /// over the trailing window has reached the threshold.
+    fn record(&mut self, ip_addr: IpAddr, cost: f64, now: Instant) -> bool {
+        if !self.logs.contains(&ip_addr) {
+            self.logs.put(ip_addr, ClientLog::default());
+        }
+        let log = self.logs.get_mut(&ip_addr).expect("log was just inserted");
+        if let Some(cutoff) = now.checked_sub(self.window_size) {
+            log.expire(cutoff);
+        }
+        log.record(now, cost);
+        log.total >= self.threshold
+    }
+
+    fn window_total(&self, ip_addr: &IpAddr) -> Option<f64> {
+        self.logs.peek(ip_addr).map(|log| log.total)
+    }
+}
+
+pub struct SlidingWindowLogPolicy {
+    pub config: PolicyConfig,
+    weights: TallyWeights,
+    clients: ClientLogs,
+    proxied_clients: ClientLogs,
+}
+
+impl SlidingWindowLogPolicy {
+    pub fn new(
+        config: PolicyConfig,
+        SlidingWindowLogConfig {
+            client_threshold,
+            proxied_client_threshold,
+            window_size_secs,
+            max_tracked_clients,
+            weights,
+        }: SlidingWindowLogConfig,
+    ) -> Self {
+        let window_size = Duration::from_secs(window_size_secs);
+        Self {
+            config,
+            weights,
+            clients: ClientLogs::new(client_threshold, window_size, max_tracked_clients),
+            proxied_clients: ClientLogs::new(
+                proxied_client_threshold,
+                window_size,
+                max_tracked_clients,
+            ),
+        }
+    }
+
+    pub fn client_threshold(&self) -> u64 {
+        self.clients.threshold as u64
+    }
+
+    pub fn set_client_threshold(&mut self, threshold: u64) {
+        self.clients.threshold = threshold as f64;
+    }
+
+    /// Total weighted cost recorded for the given direct client within
+    /// the current window, as of its most recent tally.
+    pub fn client_window_total(&self, ip_addr: &IpAddr) -> Option<f64> {
+        self.clients.window_total(ip_addr)
+    }
+
+    pub fn proxied_client_window_total(&self, ip_addr: &IpAddr) -> Option<f64> {
+        self.proxied_clients.window_total(ip_addr)
+    }
+
+    pub fn client_usage(&self, ip_addr: &IpAddr) -> PolicyClientUsage {
+        PolicyClientUsage {
+            direct: self.client_window_total(ip_addr),
+            direct_threshold: self.clients.threshold as u64,
+            proxied: self.proxied_client_window_total(ip_addr),
+            proxied_threshold: self.proxied_clients.threshold as u64,
+        }
+    }
+
+    pub fn handle_tally(&mut self, tally: TrafficTally) -> PolicyResponse {
+        let cost = tally.cost(&self.weights);
+        let now = Instant::now();
+        let block_client = tally
+            .direct
+            .filter(|source| self.clients.record(*source, cost, now));
+        let block_proxied_client = tally
+            .through_fullnode
+            .filter(|source| self.proxied_clients.record(*source, cost, now));
+        trace!(
+            "SlidingWindowLogPolicy handling tally -- cost: {:?}, block_client: {:?}, block_proxied_client: {:?}",
+            cost, block_client, block_proxied_client,
+        );
+        PolicyResponse {
+            block_client,
+            block_proxied_client,
+        }
+    }
+
+    fn policy_config(&self) -> &PolicyConfig {
+        &self.config
+    }
+}
+
+struct CompositeChild {
+    name: String,
+    dry_run: bool,
+    policy: TrafficControlPolicy,
+}
+
+pub struct CompositePolicy {
+    pub config: PolicyConfig,
+    mode: CompositeMode,
+    children: Vec<CompositeChild>,
+    role: &'static str,
+    metrics: Arc<TrafficControllerMetrics>,
+}
+
+impl CompositePolicy {
+    pub async fn new(
+        config: PolicyConfig,
+        CompositeConfig { mode, policies }: CompositeConfig,
+        role: &'static str,
+        metrics: Arc<TrafficControllerMetrics>,
+    ) -> Self {
+        let mut children: Vec<CompositeChild> = Vec::with_capacity(policies.len());
+        for child in policies {
+            assert!(
+                children.iter().all(|existing| existing.name != child.name),
+                "Composite policy child names must be unique, found duplicate {:?}",
+                child.name,
+            );
+            // Boxed, as composite policies may themselves be nested
+            let policy = Box::pin(TrafficControlPolicy::from_config(
+                child.policy_type,
+                config.clone(),
+                role,
+                metrics.clone(),
+            ))
+            .await;
+            children.push(CompositeChild {
+                name: child.name,
+                dry_run: child.dry_run,
+                policy,
+            });
+        }
+        Self {
+            config,
+            mode,
+            children,
+            role,
+            metrics,
+        }
+    }
+
+    /// Every child sees every tally, regardless of mode or dry-run, so
+    /// that stateful children stay accurate when their responses are not
+    /// used.
+    pub fn handle_tally(&mut self, tally: TrafficTally) -> PolicyResponse {
+        let mut enforced = Vec::with_capacity(self.children.len());
+        for child in self.children.iter_mut() {
+            let response = child.policy.handle_tally(tally.clone());
+            let dry_run = if child.dry_run { "true" } else { "false" };
+            self.metrics
+                .composite_child_tallies
+                .with_label_values(&[self.role, child.name.as_str(), dry_run])
+                .inc();
+            for (blocked, client_type) in [
+                (response.block_client, "direct"),
+                (response.block_proxied_client, "proxied"),
+            ] {
+                if blocked.is_some() {
+                    self.metrics
+                        .composite_child_blocks
+                        .with_label_values(&[self.role, child.name.as_str(), dry_run, client_type])
+                        .inc();
+                }
+            }
+            if child.dry_run {
+                if response.block_client.is_some() || response.block_proxied_client.is_some() {
+                    debug!(
+                        "Dry run composite child policy {:?} would block: {:?}",
+                        child.name, response,
+                    );
+                }
+            } else {
+                enforced.push(response);
+            }
+        }
+        Self::combine(self.mode, enforced)
+    }
+
+    fn combine(mode: CompositeMode, responses: Vec<PolicyResponse>) -> PolicyResponse {
+        match mode {
+            CompositeMode::All => {
+                if responses.is_empty() {
+                    return PolicyResponse::default();
+                }
+                // The responses all concern the same tally, so each is
+                // either None or the same address.
+                let all_block = |get: fn(&PolicyResponse) -> Option<IpAddr>| {
+                    responses.iter().map(get).reduce(|a, b| a.and(b)).flatten()
+                };
+                PolicyResponse {
+                    block_client: all_block(|response| response.block_client),
+                    block_proxied_client: all_block(|response| response.block_proxied_client),
+                }
+            }
+            CompositeMode::Any => {
+                responses
+                    .into_iter()
+                    .fold(PolicyResponse::default(), |acc, response| PolicyResponse {
+                        block_client: acc.block_client.or(response.block_client),
+                        block_proxied_client: acc
+                            .block_proxied_client
+                            .or(response.block_proxied_client),
+                    })
+            }
+            CompositeMode::FirstMatch => responses
+                .into_iter()
+                .find(|response| {
+                    response.block_client.is_some() || response.block_proxied_client.is_some()
+                })
+                .unwrap_or_default(),
+        }
+    }
+
+    fn policy_config(&self) -> &PolicyConfig {
+        &self.config
+    }
+}
+
 ////////////// *** Test policies below this point *** //////////////
 
 #[derive(Clone)]
@@ -521,7 +1078,8 @@ mod tests {
     use std::net::{IpAddr, Ipv4Addr};
     use sui_macros::sim_test;
     use sui_types::traffic_control::{
-        DEFAULT_SKETCH_CAPACITY, DEFAULT_SKETCH_PROBABILITY, DEFAULT_SKETCH_TOLERANCE,
+        CompositeChildConfig, DEFAULT_SKETCH_CAPACITY, DEFAULT_SKETCH_PROBABILITY,
+        DEFAULT_SKETCH_TOLERANCE,
     };
 
     #[sim_test]
@@ -547,6 +1105,7 @@ mod tests {
             through_fullnode: Some(IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4))),
             error_info: None,
             spam_weight: Weight::one(),
+            request_type: None,
             timestamp: SystemTime::now(),
         };
         let bob = TrafficTally {
@@ -554,6 +1113,7 @@ mod tests {
             through_fullnode: Some(IpAddr::V4(Ipv4Addr::new(4, 3, 2, 1))),
             error_info: None,
             spam_weight: Weight::one(),
+            request_type: None,
             timestamp: SystemTime::now(),
         };
         let charlie = TrafficTally {
@@ -561,6 +1121,7 @@ mod tests {
             through_fullnode: Some(IpAddr::V4(Ipv4Addr::new(5, 6, 7, 8))),
             error_info: None,
             spam_weight: Weight::one(),
+            request_type: None,
             timestamp: SystemTime::now(),
         };
 
@@ -678,6 +1239,325 @@ mod tests {
         assert_eq!(proxied_rate, 1);
     }
 
+    #[sim_test]
+    async fn test_freq_threshold_policy_top_clients() {
+        let mut policy = FreqThresholdPolicy::new(
+            PolicyConfig::default(),
+            FreqThresholdConfig {
+                client_threshold: 100,
+                proxied_client_threshold: 100,
+                window_size_secs: 2,
+                update_interval_secs: 1,
+                ..Default::default()
+            },
+        );
+        let clients: Vec<_> = (1..=3)
+            .map(|i| IpAddr::V4(Ipv4Addr::new(10, 0, 0, i)))
+            .collect();
+        // client i sends 2 * i requests
+        for (i, client) in clients.iter().enumerate() {
+            for _ in 0..2 * (i + 1) {
+                policy.handle_tally(TrafficTally::new(Some(*client), None, None, Weight::one()));
+            }
+        }
+
+        let top_clients = policy.top_clients(2);
+        assert!(top_clients.proxied.is_empty());
+        let top: Vec<_> = top_clients
+            .direct
+            .iter()
+            .map(|client_rate| (client_rate.ip, client_rate.rate))
+            .collect();
+        assert_eq!(top, vec![(clients[2], 3), (clients[1], 2)]);
+
+        let usage = policy.client_usage(&clients[0]);
+        assert_eq!(usage.direct, Some(1.0));
+        assert_eq!(usage.direct_threshold, 100);
+        assert_eq!(usage.proxied, Some(0.0));
+    }
+
+    #[sim_test]
+    async fn test_token_bucket_policy() {
+        // Direct clients may burst up to 10 requests and then sustain
+        // 5 requests per second. Errors of type "InvalidSignature" and
+        // requests of type "expensive" cost double.
+        let mut policy = TokenBucketPolicy::new(
+            PolicyConfig::default(),
+            TokenBucketConfig {
+                client_capacity: 10.0,
+                client_refill_rate: 5.0,
+                proxied_client_capacity: 2.0,
+                proxied_client_refill_rate: 1.0,
+                max_tracked_clients: 10,
+                weights: TallyWeights {
+                    request_types: [("expensive".to_string(), 2.0)].into_iter().collect(),
+                    error_types: [("InvalidSignature".to_string(), 2.0)]
+                        .into_iter()
+                        .collect(),
+                },
+            },
+        );
+        let alice = TrafficTally::new(
+            Some(IpAddr::V4(Ipv4Addr::new(8, 7, 6, 5))),
+            None,
+            None,
+            Weight::one(),
+        );
+        let bob = TrafficTally::new(
+            Some(IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4))),
+            Some(IpAddr::V4(Ipv4Addr::new(4, 3, 2, 1))),
+            None,
+            Weight::one(),
+        );
+
+        // alice bursts through her full bucket, then is blocked
+        for i in 0..10 {
+            let response = policy.handle_tally(alice.clone());
+            assert_eq!(response.block_client, None, "Blocked at i = {}", i);
+        }
+        let response = policy.handle_tally(alice.clone());
+        assert_eq!(response.block_client, alice.direct);
+
+        // after a second she has earned 5 tokens back
+        tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+        for i in 0..4 {
+            let response = policy.handle_tally(alice.clone());
+            assert_eq!(response.block_client, None, "Blocked at i = {}", i);
+        }
+
+        // bob is unaffected by alice, but his proxied client has a
+        // smaller bucket and is blocked after two requests
+        for _ in 0..2 {
+            let response = policy.handle_tally(bob.clone());
+            assert_eq!(response.block_client, None);
+            assert_eq!(response.block_proxied_client, None);
+        }
+        let response = policy.handle_tally(bob.clone());
+        assert_eq!(response.block_client, None);
+        assert_eq!(response.block_proxied_client, bob.through_fullnode);
+
+        // weighted requests drain the bucket faster
+        tokio::time::sleep(tokio::time::Duration::from_secs(3)).await;
+        let expensive_error = TrafficTally::new(
+            alice.direct,
+            None,
+            Some((Weight::one(), "InvalidSignature".to_string())),
+            Weight::one(),
+        )
+        .with_request_type("expensive");
+        for i in 0..2 {
+            let response = policy.handle_tally(expensive_error.clone());
+            assert_eq!(response.block_client, None, "Blocked at i = {}", i);
+        }
+        let response = policy.handle_tally(expensive_error.clone());
+        assert_eq!(response.block_client, alice.direct);
+    }
+
+    #[test]
+    fn test_token_buckets_evict_least_recently_charged() {
+        let ip = |i| IpAddr::V4(Ipv4Addr::new(10, 0, 0, i));
+        let now = Instant::now();
+        let mut buckets = TokenBuckets::new(2.0, 0.0, 2);
+
+        // drain the first two clients' buckets, charging the first one last
+        for i in [1, 2, 1] {
+            buckets.charge(ip(i), 1.0, now);
+        }
+        assert!(buckets.charge(ip(1), 1.0, now));
+
+        // a third client replaces the second, which starts over with a
+        // full bucket, while the first stays drained
+        assert!(!buckets.charge(ip(3), 1.0, now));
+        assert!(buckets.charge(ip(1), 1.0, now));
+        assert!(!buckets.charge(ip(2), 2.0, now));
+        assert_eq!(buckets.buckets.len(), 2);
+    }
+
+    #[test]
+    fn test_client_logs_evict_least_recently_seen() {
+        let ip = |i| IpAddr::V4(Ipv4Addr::new(10, 0, 0, i));
+        let now = Instant::now();
+        let mut logs = ClientLogs::new(10, Duration::from_secs(60), 2);
+
+        for i in [1, 2, 1] {
+            logs.record(ip(i), 1.0, now);
+        }
+
+        // a third client replaces the second, which was seen least recently
+        logs.record(ip(3), 1.0, now);
+        assert_eq!(logs.window_total(&ip(1)), Some(2.0));
+        assert_eq!(logs.window_total(&ip(2)), None);
+        assert_eq!(logs.window_total(&ip(3)), Some(1.0));
+    }
+
+    #[sim_test]
+    async fn test_sliding_window_log_policy() {
+        // Block direct clients that send 6 or more requests within any
+        // 3 second window.
+        let mut policy = SlidingWindowLogPolicy::new(
+            PolicyConfig::default(),
+            SlidingWindowLogConfig {
+                client_threshold: 6,
+                proxied_client_threshold: 6,
+                window_size_secs: 3,
+                max_tracked_clients: 10,
+                weights: TallyWeights {
+                    request_types: [("free".to_string(), 0.0)].into_iter().collect(),
+                    ..Default::default()
+                },
+            },
+        );
+        let scraper = TrafficTally::new(
+            Some(IpAddr::V4(Ipv4Addr::new(8, 7, 6, 5))),
+            None,
+            None,
+            Weight::one(),
+        );
+
+        // a slow, steady client at 2 requests per second is blocked once
+        // the window fills, which a coarse per-second view would not see
+        for _ in 0..2 {
+            for _ in 0..2 {
+                let response = policy.handle_tally(scraper.clone());
+                assert_eq!(response.block_client, None);
+            }
+            tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+        }
+        let response = policy.handle_tally(scraper.clone());
+        assert_eq!(response.block_client, None);
+        let response = policy.handle_tally(scraper.clone());
+        assert_eq!(response.block_client, scraper.direct);
+        assert_eq!(
+            policy.client_window_total(&scraper.direct.unwrap()),
+            Some(6.0)
+        );
+
+        // zero weight requests are never counted
+        let free = scraper.clone().with_request_type("free");
+        let response = policy.handle_tally(free);
+        assert_eq!(response.block_client, scraper.direct);
+        assert_eq!(
+            policy.client_window_total(&scraper.direct.unwrap()),
+            Some(6.0)
+        );
+
+        // once the old entries leave the window the client is allowed again
+        tokio::time::sleep(tokio::time::Duration::from_secs(3)).await;
+        let response = policy.handle_tally(scraper.clone());
+        assert_eq!(response.block_client, None);
+        assert_eq!(
+            policy.client_window_total(&scraper.direct.unwrap()),
+            Some(1.0)
+        );
+    }
+
+    #[sim_test]
+    async fn test_composite_policy_dry_run_child() {
+        let sliding_window = |client_threshold| {
+            PolicyType::SlidingWindowLog(SlidingWindowLogConfig {
+                client_threshold,
+                window_size_secs: 60,
+                ..Default::default()
+            })
+        };
+        let metrics = Arc::new(TrafficControllerMetrics::new_for_tests());
+        // "candidate" is being trialed in dry-run mode alongside "current"
+        let mut policy = CompositePolicy::new(
+            PolicyConfig::default(),
+            CompositeConfig {
+                mode: CompositeMode::Any,
+                policies: vec![
+                    CompositeChildConfig {
+                        name: "candidate".to_string(),
+                        policy_type: sliding_window(2),
+                        dry_run: true,
+                    },
+                    CompositeChildConfig {
+                        name: "current".to_string(),
+                        policy_type: sliding_window(4),
+                        dry_run: false,
+                    },
+                ],
+            },
+            SPAM_POLICY_ROLE,
+            metrics.clone(),
+        )
+        .await;
+        let tally = TrafficTally::new(
+            Some(IpAddr::V4(Ipv4Addr::new(8, 7, 6, 5))),
+            None,
+            None,
+            Weight::one(),
+        );
+
+        for i in 0..3 {
+            let response = policy.handle_tally(tally.clone());
+            assert_eq!(response.block_client, None, "Blocked at i = {}", i);
+        }
+        let response = policy.handle_tally(tally.clone());
+        assert_eq!(response.block_client, tally.direct);
+
+        let blocks = |name| {
+            metrics
+                .composite_child_blocks
+                .with_label_values(&[SPAM_POLICY_ROLE, name, "true", "direct"])
+                .get()
+                + metrics
+                    .composite_child_blocks
+                    .with_label_values(&[SPAM_POLICY_ROLE, name, "false", "direct"])
+                    .get()
+        };
+        assert_eq!(blocks("candidate"), 3);
+        assert_eq!(blocks("current"), 1);
+        assert_eq!(
+            metrics
+                .composite_child_tallies
+                .with_label_values(&[SPAM_POLICY_ROLE, "candidate", "true"])
+                .get(),
+            4
+        );
+    }
+
+    #[test]
+    fn test_composite_policy_combine() {
+        let alice = Some(IpAddr::V4(Ipv4Addr::new(8, 7, 6, 5)));
+        let bob = Some(IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4)));
+        let responses = vec![
+            PolicyResponse {
+                block_client: None,
+                block_proxied_client: bob,
+            },
+            PolicyResponse {
+                block_client: alice,
+                block_proxied_client: None,
+            },
+            PolicyResponse {
+                block_client: alice,
+                block_proxied_client: bob,
+            },
+        ];
+
+        let response = CompositePolicy::combine(CompositeMode::Any, responses.clone());
+        assert_eq!(response.block_client, alice);
+        assert_eq!(response.block_proxied_client, bob);
+
+        let response = CompositePolicy::combine(CompositeMode::All, responses.clone());
+        assert_eq!(response.block_client, None);
+        assert_eq!(response.block_proxied_client, None);
+        let response = CompositePolicy::combine(CompositeMode::All, responses[1..].to_vec());
+        assert_eq!(response.block_client, alice);
+        assert_eq!(response.block_proxied_client, None);
+
+        let response = CompositePolicy::combine(CompositeMode::FirstMatch, responses.clone());
+        assert_eq!(response.block_client, None);
+        assert_eq!(response.block_proxied_client, bob);
+
+        let response = CompositePolicy::combine(CompositeMode::Any, vec![]);
+        assert_eq!(response.block_client, None);
+        let response = CompositePolicy::combine(CompositeMode::All, vec![]);
+        assert_eq!(response.block_client, None);
+    }
+
     #[sim_test]
     async fn test_traffic_sketch_mem_estimate() {
         // Test for getting a rough estimate of memory usage for the traffic sketch
@@ -20,6 +20,7 @@
 //! - [`stores::InMemoryStore`] - Unbounded in-memory cache
 //! - [`stores::LruMemoryStore`] - Bounded LRU cache
 //! - [`stores::ReadThroughStore`] - Composable two-tier caching pattern
+//! - [`stores::CheckpointArchiveStore`] - Offline store indexed from a directory of checkpoint files
 //!
 //! ## Composition
 //!
@@ -27,6 +28,8 @@
 //! - `ReadThroughStore<LruMemoryStore, DataStore>` - LRU + remote
 //! - `ReadThroughStore<InMemoryStore, FileSystemStore>` - Memory + disk
 //!   (e.g., for testing in CI with pre-populated disk cache)
+//! - `ReadThroughStore<FileSystemStore, CheckpointArchiveStore>` - Disk + checkpoint archive
+//!   (e.g., for replaying a pinned range of checkpoints offline)
 
 mod gql_queries;
 pub mod node;
@@ -0,0 +1,720 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Checkpoint archive implementation of the data store interfaces: `TransactionStore`,
+//! `EpochStore`, and `ObjectStore`.
+//! The `CheckpointArchiveStore` indexes a directory of checkpoint files (`<sequence_number>.chk`,
+//! the blob encoded `CheckpointData` read by the indexer framework's local ingestion client)
+//! and serves data from it without any network access.
+//!
+//! Checkpoints are read and indexed lazily, in order from the start of the range: a lookup
+//! indexes checkpoints until it finds what it is looking for or the whole range is indexed, so
+//! replaying the transactions at the start of a large range only reads the checkpoints up to
+//! them. The checkpoint files of the range must all be present. The store only knows what the
+//! checkpoints contain:
+//! - transactions executed in the range
+//! - epochs whose start is in the range (the system state is read from the output of the
+//!   genesis or end-of-epoch transaction), so a range should start at the last checkpoint of
+//!   the epoch before the first epoch to replay
+//! - objects that were inputs or outputs of transactions in the range. Objects outside of
+//!   that set are not found. Packages published before the range are not in the archive either,
+//!   and looking up one called by a transaction of the range fails with an error, so the store
+//!   is commonly composed with a `FileSystemStore` holding them, i.e.
+//!   `ReadThroughStore<FileSystemStore, CheckpointArchiveStore>`
+//!
+//! Other missing data results in `Ok(None)`.
+//!
+//! # Usage Examples
+//!
+//! ```ignore
+//! use crate::stores::CheckpointArchiveStore;
+//! use crate::Node;
+//!
+//! // Index checkpoints 1000 to 2000 of the directory
+//! let store = CheckpointArchiveStore::new(Node::Mainnet, "/data/checkpoints", Some(1000..=2000))?;
+//! let txn = store.transaction_data_and_effects("...")?;
+//! ```
+
+use crate::{
+    EpochData, EpochStore, ObjectKey, ObjectStore, SetupStore, StoreSummary, TransactionInfo,
+    TransactionStore, VersionQuery, node::Node,
+};
+use anyhow::{Context, Error, Result, anyhow, bail};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+    sync::{
+        RwLock, RwLockReadGuard,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+use sui_storage::blob::Blob;
+use sui_types::{
+    base_types::ObjectID,
+    committee::ProtocolVersion,
+    digests::ChainIdentifier,
+    full_checkpoint_content::{CheckpointData, CheckpointTransaction},
+    object::Object,
+    sui_system_state::{SuiSystemStateTrait, get_sui_system_state},
+    supported_protocol_versions::{Chain, ProtocolConfig},
+    transaction::{InputObjectKind, TransactionDataAPI},
+};
+use tracing::{debug, warn};
+
+/// Extension of the checkpoint files in an archive directory.
+pub const CHECKPOINT_FILE_EXT: &str = "chk";
+
+/// Store serving transactions, epochs and objects out of a directory of checkpoint files.
+pub struct CheckpointArchiveStore {
+    node: Node,
+    path: PathBuf,
+    checkpoints: RangeInclusive<u64>,
+    /// The checkpoints indexed so far
+    index: RwLock<ArchiveIndex>,
+    metrics: ArchiveStoreMetrics,
+}
+
+/// Data of the checkpoints indexed so far, in order from the start of the range.
+#[derive(Default)]
+struct ArchiveIndex {
+    /// Next checkpoint to index, past the end of the range once all of them are indexed
+    next_checkpoint: u64,
+    /// Chain identifier, known if the genesis checkpoint is indexed
+    chain_id: Option<String>,
+    transactions: BTreeMap<String, TransactionInfo>,
+    /// Digests of the indexed transactions in execution order
+    transaction_order: Vec<String>,
+    epochs: BTreeMap<u64, EpochData>,
+    /// All known versions of each object
+    objects: BTreeMap<ObjectID, BTreeMap<u64, Object>>,
+    /// Version of each object at the end of a checkpoint, recorded at the checkpoints where
+    /// the version changed. `None` if the object was deleted or wrapped.
+    /// Used for VersionQuery::AtCheckpoint lookups
+    checkpoint_versions: BTreeMap<ObjectID, BTreeMap<u64, Option<u64>>>,
+    /// Packages called by the indexed transactions
+    called_packages: BTreeSet<ObjectID>,
+}
+
+#[derive(Default)]
+struct ArchiveStoreMetrics {
+    txn_hit: AtomicU64,
+    txn_miss: AtomicU64,
+    epoch_hit: AtomicU64,
+    epoch_miss: AtomicU64,
+    obj_version_hit: AtomicU64,
+    obj_version_miss: AtomicU64,
+    obj_root_hit: AtomicU64,
+    obj_root_miss: AtomicU64,
+    obj_checkpoint_hit: AtomicU64,
+    obj_checkpoint_miss: AtomicU64,
+}
+
+impl CheckpointArchiveStore {
+    /// Create a store over the checkpoint files in `path`. If `range` is provided only the
+    /// checkpoints in the range are indexed, and all of them must be present.
+    pub fn new(
+        node: Node,
+        path: impl Into<PathBuf>,
+        range: Option<RangeInclusive<u64>>,
+    ) -> Result<Self, Error> {
+        let path = path.into();
+        let available = checkpoint_files(&path)?;
+        let checkpoints = match range {
+            Some(range) => range,
+            None => match (available.first(), available.last()) {
+                (Some(first), Some(last)) => *first..=*last,
+                _ => bail!("No checkpoint files found in {}", path.display()),
+            },
+        };
+        if checkpoints.is_empty() {
+            bail!("Empty checkpoint range {:?}", checkpoints);
+        }
+        // The first missing checkpoint is at most `available.len()` checkpoints into the range.
+        if let Some(missing) = checkpoints
+            .clone()
+            .find(|sequence_number| available.binary_search(sequence_number).is_err())
+        {
+            bail!("Missing checkpoint {missing} in {}", path.display());
+        }
+
+        debug!(
+            path = %path.display(),
+            checkpoints = ?checkpoints,
+            "opened checkpoint archive",
+        );
+        Ok(Self {
+            node,
+            path,
+            index: RwLock::new(ArchiveIndex {
+                next_checkpoint: *checkpoints.start(),
+                ..Default::default()
+            }),
+            checkpoints,
+            metrics: ArchiveStoreMetrics::default(),
+        })
+    }
+
+    /// Get the chain for this store
+    pub fn chain(&self) -> Chain {
+        self.node.chain()
+    }
+
+    /// Get the node for this store
+    pub fn node(&self) -> Node {
+        self.node.clone()
+    }
+
+    /// The range of checkpoints indexed by this store
+    pub fn checkpoints(&self) -> &RangeInclusive<u64> {
+        &self.checkpoints
+    }
+
+    /// Digests of the transactions in the range of checkpoints, in execution order.
+    /// This indexes the whole range.
+    pub fn transaction_digests(&self) -> Result<Vec<String>, Error> {
+        Ok(self
+            .index_through(*self.checkpoints.end())?
+            .transaction_order
+            .clone())
+    }
+
+    // Index checkpoints in order until `find` finds what it is looking for in the index, or the
+    // whole range is indexed.
+    fn lookup<T>(&self, find: impl Fn(&ArchiveIndex) -> Option<T>) -> Result<Option<T>, Error> {
+        if let Some(found) = find(&self.index.read().unwrap()) {
+            return Ok(Some(found));
+        }
+        let mut index = self.index.write().unwrap();
+        loop {
+            if let Some(found) = find(&index) {
+                return Ok(Some(found));
+            }
+            if !self.index_next(&mut index)? {
+                return Ok(None);
+            }
+        }
+    }
+
+    // Index checkpoints in order through `sequence_number`, or through the end of the range.
+    fn index_through(&self, sequence_number: u64) -> Result<RwLockReadGuard<'_, ArchiveIndex>> {
+        let last = sequence_number.min(*self.checkpoints.end());
+        {
+            let index = self.index.read().unwrap();
+            if index.next_checkpoint > last {
+                return Ok(index);
+            }
+        }
+        {
+            let mut index = self.index.write().unwrap();
+            while index.next_checkpoint <= last {
+                self.index_next(&mut index)?;
+            }
+        }
+        Ok(self.index.read().unwrap())
+    }
+
+    // Index the next checkpoint of the range. Returns false if all of them are indexed.
+    fn index_next(&self, index: &mut ArchiveIndex) -> Result<bool, Error> {
+        if index.next_checkpoint > *self.checkpoints.end() {
+            return Ok(false);
+        }
+        let checkpoint = self.read_checkpoint(index.next_checkpoint)?;
+        index.index_checkpoint(checkpoint, *self.checkpoints.start());
+        index.next_checkpoint += 1;
+        debug!(
+            checkpoint = index.next_checkpoint - 1,
+            transactions = index.transactions.len(),
+            objects = index.objects.len(),
+            "indexed checkpoint",
+        );
+        Ok(true)
+    }
+
+    fn read_checkpoint(&self, sequence_number: u64) -> Result<CheckpointData, Error> {
+        let file = self
+            .path
+            .join(format!("{sequence_number}.{CHECKPOINT_FILE_EXT}"));
+        let bytes = std::fs::read(&file).with_context(|| {
+            format!(
+                "Missing checkpoint {sequence_number} in {}",
+                self.path.display()
+            )
+        })?;
+        let checkpoint: CheckpointData = Blob::from_bytes(&bytes)
+            .with_context(|| format!("Failed to decode checkpoint file {}", file.display()))?;
+        if checkpoint.checkpoint_summary.sequence_number != sequence_number {
+            bail!(
+                "Checkpoint file {} holds checkpoint {}",
+                file.display(),
+                checkpoint.checkpoint_summary.sequence_number
+            );
+        }
+        Ok(checkpoint)
+    }
+}
+
+impl ArchiveIndex {
+    fn index_checkpoint(&mut self, checkpoint: CheckpointData, first_checkpoint: u64) {
+        let sequence_number = checkpoint.checkpoint_summary.sequence_number;
+        if sequence_number == 0 {
+            self.chain_id =
+                Some(ChainIdentifier::from(*checkpoint.checkpoint_summary.digest()).to_string());
+        }
+        let starts_epoch =
+            sequence_number == 0 || checkpoint.checkpoint_summary.end_of_epoch_data.is_some();
+        if starts_epoch && let Some(transaction) = checkpoint.transactions.last() {
+            self.index_epoch(sequence_number, transaction);
+        }
+
+        for transaction in checkpoint.transactions {
+            self.index_objects(sequence_number, first_checkpoint, &transaction);
+            let CheckpointTransaction {
+                transaction,
+                effects,
+                ..
+            } = transaction;
+            if let Ok(input_objects) = transaction.transaction_data().input_objects() {
+                self.called_packages
+                    .extend(input_objects.iter().filter_map(|kind| match kind {
+                        InputObjectKind::MovePackage(package_id) => Some(*package_id),
+                        _ => None,
+                    }));
+            }
+            let digest = transaction.digest().to_string();
+            self.transaction_order.push(digest.clone());
+            self.transactions.insert(
+                digest,
+                TransactionInfo {
+                    data: transaction.transaction_data().clone(),
+                    effects,
+                    checkpoint: sequence_number,
+                },
+            );
+        }
+    }
+
+    // The genesis and end-of-epoch transactions output the system state of the next epoch.
+    fn index_epoch(&mut self, sequence_number: u64, transaction: &CheckpointTransaction) {
+        match get_sui_system_state(&transaction.output_objects.as_slice()) {
+            Ok(system_state) => {
+                self.epochs.insert(
+                    system_state.epoch(),
+                    EpochData {
+                        epoch_id: system_state.epoch(),
+                        protocol_version: system_state.protocol_version(),
+                        rgp: system_state.reference_gas_price(),
+                        start_timestamp: system_state.epoch_start_timestamp_ms(),
+                    },
+                );
+            }
+            Err(e) => warn!(
+                checkpoint = sequence_number,
+                "Failed to read system state from epoch change: {e}"
+            ),
+        }
+    }
+
+    fn index_objects(
+        &mut self,
+        sequence_number: u64,
+        first_checkpoint: u64,
+        transaction: &CheckpointTransaction,
+    ) {
+        for object in &transaction.input_objects {
+            let version = object.version().value();
+            let history = self.checkpoint_versions.entry(object.id()).or_default();
+            match history.last_key_value() {
+                // Unchanged since the start of the range.
+                None => {
+                    history.insert(first_checkpoint.saturating_sub(1), Some(version));
+                }
+                Some((_, last)) if *last != Some(version) => {
+                    history.insert(sequence_number.saturating_sub(1), Some(version));
+                }
+                Some(_) => (),
+            }
+            self.add_object(object);
+        }
+        for object in transaction.removed_objects_pre_version() {
+            self.checkpoint_versions
+                .entry(object.id())
+                .or_default()
+                .insert(sequence_number, None);
+        }
+        for object in &transaction.output_objects {
+            self.checkpoint_versions
+                .entry(object.id())
+                .or_default()
+                .insert(sequence_number, Some(object.version().value()));
+            self.add_object(object);
+        }
+    }
+
+    fn add_object(&mut self, object: &Object) {
+        self.objects
+            .entry(object.id())
+            .or_default()
+            .entry(object.version().value())
+            .or_insert_with(|| object.clone());
+    }
+
+    fn object_at_version(&self, object_id: &ObjectID, version: u64) -> Option<(Object, u64)> {
+        self.objects
+            .get(object_id)?
+            .get(&version)
+            .map(|object| (object.clone(), version))
+    }
+
+    fn object_at_root_version(
+        &self,
+        object_id: &ObjectID,
+        max_version: u64,
+    ) -> Option<(Object, u64)> {
+        self.objects.get(object_id).and_then(|versions| {
+            versions
+                .range(..=max_version)
+                .next_back()
+                .map(|(version, object)| (object.clone(), *version))
+        })
+    }
+
+    fn object_at_checkpoint(&self, object_id: &ObjectID, checkpoint: u64) -> Option<(Object, u64)> {
+        self.checkpoint_versions
+            .get(object_id)
+            .and_then(|history| history.range(..=checkpoint).next_back())
+            .and_then(|(_, version)| *version)
+            .and_then(|version| self.object_at_version(object_id, version))
+    }
+}
+
+// Sequence numbers of the checkpoint files in the directory, sorted.
+fn checkpoint_files(path: &Path) -> Result<Vec<u64>, Error> {
+    let mut sequence_numbers = vec![];
+    for entry in std::fs::read_dir(path).map_err(|e| {
+        anyhow!(
+            "Failed to read checkpoint directory {}: {e}",
+            path.display()
+        )
+    })? {
+        let file = entry?.path();
+        if file
+            .extension()
+            .is_some_and(|ext| ext == CHECKPOINT_FILE_EXT)
+            && let Some(sequence_number) = file
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(|stem| stem.parse::<u64>().ok())
+        {
+            sequence_numbers.push(sequence_number);
+        }
+    }
+    sequence_numbers.sort_unstable();
+    Ok(sequence_numbers)
+}
+
+impl TransactionStore for CheckpointArchiveStore {
+    fn transaction_data_and_effects(
+        &self,
+        tx_digest: &str,
+    ) -> Result<Option<TransactionInfo>, Error> {
+        let transaction = self.lookup(|index| index.transactions.get(tx_digest).cloned())?;
+        let counter = if transaction.is_some() {
+            &self.metrics.txn_hit
+        } else {
+            &self.metrics.txn_miss
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        Ok(transaction)
+    }
+}
+
+impl EpochStore for CheckpointArchiveStore {
+    fn epoch_info(&self, epoch: u64) -> Result<Option<EpochData>, Error> {
+        let epoch_data = self.lookup(|index| index.epochs.get(&epoch).cloned())?;
+        let counter = if epoch_data.is_some() {
+            &self.metrics.epoch_hit
+        } else {
+            &self.metrics.epoch_miss
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        Ok(epoch_data)
+    }
+
+    fn protocol_config(&self, epoch: u64) -> Result<Option<ProtocolConfig>, Error> {
+        Ok(self.epoch_info(epoch)?.map(|epoch_data| {
+            ProtocolConfig::get_for_version(
+                ProtocolVersion::new(epoch_data.protocol_version),
+                self.chain(),
+            )
+        }))
+    }
+}
+
+impl ObjectStore for CheckpointArchiveStore {
+    fn get_objects(&self, keys: &[ObjectKey]) -> Result<Vec<Option<(Object, u64)>>, Error> {
+        let mut results = Vec::with_capacity(keys.len());
+        for key in keys {
+            let (object_and_version, hit_ctr, miss_ctr) = match &key.version_query {
+                VersionQuery::Version(version) => (
+                    self.lookup(|index| index.object_at_version(&key.object_id, *version))?,
+                    &self.metrics.obj_version_hit,
+                    &self.metrics.obj_version_miss,
+                ),
+                VersionQuery::RootVersion(max_version) => (
+                    self.lookup(|index| {
+                        index.object_at_root_version(&key.object_id, *max_version)
+                    })?,
+                    &self.metrics.obj_root_hit,
+                    &self.metrics.obj_root_miss,
+                ),
+                VersionQuery::AtCheckpoint(checkpoint) => {
+                    let index = self.index_through(*checkpoint)?;
+                    let res = index.object_at_checkpoint(&key.object_id, *checkpoint);
+                    if res.is_none()
+                        && !index.objects.contains_key(&key.object_id)
+                        && index.called_packages.contains(&key.object_id)
+                    {
+                        bail!(
+                            "Package {} is called in checkpoints {}..={} of {} but was published \
+                             before them; it must be read from another store",
+                            key.object_id,
+                            self.checkpoints.start(),
+                            self.checkpoints.end(),
+                            self.path.display(),
+                        );
+                    }
+                    (
+                        res,
+                        &self.metrics.obj_checkpoint_hit,
+                        &self.metrics.obj_checkpoint_miss,
+                    )
+                }
+            };
+            if object_and_version.is_some() {
+                hit_ctr.fetch_add(1, Ordering::Relaxed);
+            } else {
+                miss_ctr.fetch_add(1, Ordering::Relaxed);
+            }
+            results.push(object_and_version);
+        }
+        Ok(results)
+    }
+}
+
+impl SetupStore for CheckpointArchiveStore {
+    fn setup(&self, _chain_id: Option<String>) -> Result<Option<String>, Error> {
+        // The chain identifier is the digest of the genesis checkpoint.
+        if *self.checkpoints.start() != 0 {
+            return Ok(None);
+        }
+        Ok(self.index_through(0)?.chain_id.clone())
+    }
+}
+
+impl StoreSummary for CheckpointArchiveStore {
+    fn summary<W: std::io::Write>(&self, w: &mut W) -> Result<()> {
+        let m = &self.metrics;
+        let index = self.index.read().unwrap();
+        let object_versions: usize = index.objects.values().map(|versions| versions.len()).sum();
+        writeln!(w, "CheckpointArchiveStore summary")?;
+        writeln!(w, "  Node: {:?}", self.node)?;
+        writeln!(w, "  Path: {}", self.path.display())?;
+        writeln!(
+            w,
+            "  Checkpoints: {}..={}",
+            self.checkpoints.start(),
+            self.checkpoints.end()
+        )?;
+        writeln!(
+            w,
+            "  Indexed: {} checkpoints",
+            index.next_checkpoint - self.checkpoints.start()
+        )?;
+        writeln!(w, "  Index sizes:")?;
+        writeln!(w, "    Transactions: {} entries", index.transactions.len())?;
+        writeln!(w, "    Epochs: {} entries", index.epochs.len())?;
+        writeln!(w, "    Objects: {} versions", object_versions)?;
+        writeln!(w, "  Hits/Misses:")?;
+        writeln!(
+            w,
+            "    Transaction: hit={} miss={}",
+            m.txn_hit.load(Ordering::Relaxed),
+            m.txn_miss.load(Ordering::Relaxed)
+        )?;
+        writeln!(
+            w,
+            "    Epoch:       hit={} miss={}",
+            m.epoch_hit.load(Ordering::Relaxed),
+            m.epoch_miss.load(Ordering::Relaxed)
+        )?;
+        writeln!(w, "    Objects:")?;
+        writeln!(
+            w,
+            "      Version:     hit={} miss={}",
+            m.obj_version_hit.load(Ordering::Relaxed),
+            m.obj_version_miss.load(Ordering::Relaxed)
+        )?;
+        writeln!(
+            w,
+            "      RootVersion: hit={} miss={}",
+            m.obj_root_hit.load(Ordering::Relaxed),
+            m.obj_root_miss.load(Ordering::Relaxed)
+        )?;
+        writeln!(
+            w,
+            "      Checkpoint:  hit={} miss={}",
+            m.obj_checkpoint_hit.load(Ordering::Relaxed),
+            m.obj_checkpoint_miss.load(Ordering::Relaxed)
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_storage::blob::BlobEncoding;
+    use sui_types::test_checkpoint_data_builder::TestCheckpointBuilder;
+
+    const PACKAGE: u64 = 100;
+
+    // Write checkpoints 0 to 2 to `dir`:
+    // 0. creates object 0
+    // 1. mutates object 0
+    // 2. calls a package published before the checkpoints
+    // and return the digests of their transactions.
+    fn write_checkpoints(dir: &Path) -> Vec<String> {
+        let mut builder = TestCheckpointBuilder::new(0);
+        let mut checkpoints = vec![];
+        builder = builder
+            .start_transaction(0)
+            .create_owned_object(0)
+            .finish_transaction();
+        checkpoints.push(builder.build_checkpoint());
+        builder = builder
+            .start_transaction(0)
+            .mutate_owned_object(0)
+            .finish_transaction();
+        checkpoints.push(builder.build_checkpoint());
+        builder = builder
+            .start_transaction(0)
+            .add_move_call(TestCheckpointBuilder::derive_object_id(PACKAGE), "m", "f")
+            .finish_transaction();
+        checkpoints.push(builder.build_checkpoint());
+
+        let mut digests = vec![];
+        for checkpoint in checkpoints {
+            let checkpoint = CheckpointData::from(checkpoint);
+            digests.extend(
+                checkpoint
+                    .transactions
+                    .iter()
+                    .map(|transaction| transaction.transaction.digest().to_string()),
+            );
+            let file = dir.join(format!(
+                "{}.{CHECKPOINT_FILE_EXT}",
+                checkpoint.checkpoint_summary.sequence_number
+            ));
+            let bytes = Blob::encode(&checkpoint, BlobEncoding::Bcs)
+                .unwrap()
+                .to_bytes();
+            std::fs::write(file, bytes).unwrap();
+        }
+        digests
+    }
+
+    fn indexed(store: &CheckpointArchiveStore) -> u64 {
+        store.index.read().unwrap().next_checkpoint
+    }
+
+    fn object_key(object_idx: u64, version_query: VersionQuery) -> ObjectKey {
+        ObjectKey {
+            object_id: TestCheckpointBuilder::derive_object_id(object_idx),
+            version_query,
+        }
+    }
+
+    #[test]
+    fn test_lazy_indexing() {
+        let dir = tempfile::tempdir().unwrap();
+        let digests = write_checkpoints(dir.path());
+        // A corrupt checkpoint past the end of the range is never read.
+        std::fs::write(dir.path().join("3.chk"), b"corrupt").unwrap();
+
+        let store = CheckpointArchiveStore::new(Node::Mainnet, dir.path(), Some(0..=2)).unwrap();
+        assert_eq!(indexed(&store), 0);
+
+        // Looking up a transaction indexes the checkpoints up to its own.
+        let txn = store
+            .transaction_data_and_effects(&digests[1])
+            .unwrap()
+            .unwrap();
+        assert_eq!(txn.checkpoint, 1);
+        assert_eq!(indexed(&store), 2);
+
+        // Already indexed data is served without indexing more checkpoints.
+        let (object, version) = store
+            .get_objects(&[object_key(0, VersionQuery::AtCheckpoint(0))])
+            .unwrap()
+            .pop()
+            .flatten()
+            .unwrap();
+        assert_eq!(object.version().value(), version);
+        assert_eq!(indexed(&store), 2);
+
+        // The object was mutated in checkpoint 1.
+        let (_, mutated_version) = store
+            .get_objects(&[object_key(0, VersionQuery::AtCheckpoint(1))])
+            .unwrap()
+            .pop()
+            .flatten()
+            .unwrap();
+        assert!(mutated_version > version);
+
+        // A miss indexes the whole range.
+        assert!(
+            store
+                .transaction_data_and_effects("missing")
+                .unwrap()
+                .is_none()
+        );
+        assert_eq!(indexed(&store), 3);
+        assert_eq!(store.transaction_digests().unwrap(), digests);
+    }
+
+    #[test]
+    fn test_missing_checkpoints() {
+        let dir = tempfile::tempdir().unwrap();
+        write_checkpoints(dir.path());
+
+        let err = CheckpointArchiveStore::new(Node::Mainnet, dir.path(), Some(1..=5))
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("Missing checkpoint 3"), "{err}");
+
+        // Files that can't be decoded fail the lookups that read them.
+        std::fs::write(dir.path().join("1.chk"), b"corrupt").unwrap();
+        let store = CheckpointArchiveStore::new(Node::Mainnet, dir.path(), None).unwrap();
+        assert!(store.transaction_digests().is_err());
+    }
+
+    #[test]
+    fn test_package_before_range() {
+        let dir = tempfile::tempdir().unwrap();
+        write_checkpoints(dir.path());
+        let store = CheckpointArchiveStore::new(Node::Mainnet, dir.path(), None).unwrap();
+
+        // Before the transaction calling it is indexed the package is just not found.
+        let package = object_key(PACKAGE, VersionQuery::AtCheckpoint(1));
+        assert!(store.get_objects(&[package]).unwrap()[0].is_none());
+
+        let package = object_key(PACKAGE, VersionQuery::AtCheckpoint(2));
+        let err = store.get_objects(&[package]).unwrap_err();
+        assert!(err.to_string().contains("published before"), "{err}");
+
+        // Objects that are not called as packages are just not found.
+        let object = object_key(PACKAGE + 1, VersionQuery::AtCheckpoint(2));
+        assert!(store.get_objects(&[object]).unwrap()[0].is_none());
+    }
+}
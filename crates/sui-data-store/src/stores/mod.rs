@@ -6,12 +6,14 @@
 //! This module provides various store implementations for caching and retrieving
 //! Sui blockchain data.
 
+mod checkpoint_archive;
 mod filesystem;
 mod graphql;
 mod in_memory;
 mod in_memory_lru;
 mod read_through;
 
+pub use checkpoint_archive::{CHECKPOINT_FILE_EXT, CheckpointArchiveStore};
 pub use filesystem::{
     CHECKPOINT_VERSIONS_FILE, DATA_STORE_DIR, EPOCH_DIR, FileSystemStore, NODE_MAPPING_FILE,
     OBJECTS_DIR, ROOT_VERSIONS_FILE, TRANSACTION_DIR,
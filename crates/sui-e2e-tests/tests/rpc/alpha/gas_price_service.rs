@@ -0,0 +1,147 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use sui_macros::sim_test;
+use sui_rpc::proto::sui::rpc::v2::Bcs;
+use sui_rpc_api::grpc::alpha::gas_price_service_proto::gas_price_service_client::GasPriceServiceClient;
+use sui_rpc_api::grpc::alpha::gas_price_service_proto::{
+    GasPriceConfidence, SuggestGasPriceRequest,
+};
+use sui_types::SUI_CLOCK_OBJECT_ID;
+use sui_types::SUI_SYSTEM_STATE_OBJECT_ID;
+use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+use sui_types::storage::MAX_GAS_PRICE_SUGGESTION_OBJECTS;
+use sui_types::transaction::{CallArg, TransactionData};
+use test_cluster::TestClusterBuilder;
+use tonic::transport::Channel;
+
+#[sim_test]
+async fn suggest_gas_price_for_objects() {
+    let test_cluster = TestClusterBuilder::new().build().await;
+    let rgp = test_cluster.get_reference_gas_price().await;
+    let mut client = client(test_cluster.rpc_url()).await;
+
+    let objects = [SUI_CLOCK_OBJECT_ID, SUI_SYSTEM_STATE_OBJECT_ID];
+    let mut request = SuggestGasPriceRequest::default();
+    request.object_ids = objects.iter().map(|id| id.to_string()).collect();
+
+    // Nothing is congested, so the reference gas price is enough.
+    let response = client
+        .suggest_gas_price(request)
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(response.gas_price, Some(rgp));
+    assert_eq!(response.reference_gas_price, Some(rgp));
+    assert_eq!(response.confidence(), GasPriceConfidence::High);
+
+    let ids: Vec<_> = response
+        .objects
+        .iter()
+        .map(|o| o.object_id.clone().unwrap())
+        .collect();
+    assert_eq!(ids, objects.map(|id| id.to_string()));
+    for object in &response.objects {
+        assert_eq!(object.suggested_gas_price, None);
+        assert_eq!(object.confidence(), GasPriceConfidence::High);
+        assert_eq!(object.last_cancellation, None);
+    }
+}
+
+#[sim_test]
+async fn suggest_gas_price_for_transaction() {
+    let test_cluster = TestClusterBuilder::new().build().await;
+    let rgp = test_cluster.get_reference_gas_price().await;
+    let mut client = client(test_cluster.rpc_url()).await;
+
+    let (sender, gas) = test_cluster
+        .wallet
+        .get_one_gas_object()
+        .await
+        .unwrap()
+        .unwrap();
+
+    // The transaction accesses the system state mutably, and the clock only immutably, so the
+    // suggestion only covers the system state, and the clock if asked about explicitly.
+    let mut builder = ProgrammableTransactionBuilder::new();
+    builder.input(CallArg::SUI_SYSTEM_MUT).unwrap();
+    builder.input(CallArg::CLOCK_IMM).unwrap();
+    let tx_data =
+        TransactionData::new_programmable(sender, vec![gas], builder.finish(), 1_000_000, rgp);
+
+    let mut request = SuggestGasPriceRequest::default();
+    request.transaction = Some(Bcs::serialize(&tx_data).unwrap());
+    let response = client
+        .suggest_gas_price(request)
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(response.gas_price, Some(rgp));
+    let ids: Vec<_> = response
+        .objects
+        .iter()
+        .map(|o| o.object_id.clone().unwrap())
+        .collect();
+    assert_eq!(ids, vec![SUI_SYSTEM_STATE_OBJECT_ID.to_string()]);
+
+    // Objects that the transaction already accesses are not repeated.
+    let mut request = SuggestGasPriceRequest::default();
+    request.transaction = Some(Bcs::serialize(&tx_data).unwrap());
+    request.object_ids = vec![
+        SUI_CLOCK_OBJECT_ID.to_string(),
+        SUI_SYSTEM_STATE_OBJECT_ID.to_string(),
+    ];
+    let response = client
+        .suggest_gas_price(request)
+        .await
+        .unwrap()
+        .into_inner();
+    let ids: Vec<_> = response
+        .objects
+        .iter()
+        .map(|o| o.object_id.clone().unwrap())
+        .collect();
+    assert_eq!(
+        ids,
+        vec![
+            SUI_SYSTEM_STATE_OBJECT_ID.to_string(),
+            SUI_CLOCK_OBJECT_ID.to_string()
+        ]
+    );
+}
+
+#[sim_test]
+async fn suggest_gas_price_invalid_requests() {
+    let test_cluster = TestClusterBuilder::new().build().await;
+    let mut client = client(test_cluster.rpc_url()).await;
+
+    // Neither a transaction nor objects.
+    let request = SuggestGasPriceRequest::default();
+    let status = client.suggest_gas_price(request).await.unwrap_err();
+    assert_eq!(status.code(), tonic::Code::InvalidArgument);
+
+    // Too many objects.
+    let mut request = SuggestGasPriceRequest::default();
+    request.object_ids =
+        vec![SUI_CLOCK_OBJECT_ID.to_string(); MAX_GAS_PRICE_SUGGESTION_OBJECTS + 1];
+    let status = client.suggest_gas_price(request).await.unwrap_err();
+    assert_eq!(status.code(), tonic::Code::InvalidArgument);
+
+    // An object ID that does not parse.
+    let mut request = SuggestGasPriceRequest::default();
+    request.object_ids = vec!["not an object id".to_owned()];
+    let status = client.suggest_gas_price(request).await.unwrap_err();
+    assert_eq!(status.code(), tonic::Code::InvalidArgument);
+
+    // A transaction that does not deserialize.
+    let mut request = SuggestGasPriceRequest::default();
+    request.transaction = Some(Bcs::from(vec![1, 2, 3]));
+    let status = client.suggest_gas_price(request).await.unwrap_err();
+    assert_eq!(status.code(), tonic::Code::InvalidArgument);
+}
+
+async fn client(url: &str) -> GasPriceServiceClient<Channel> {
+    GasPriceServiceClient::connect(url.to_owned())
+        .await
+        .unwrap()
+}
@@ -0,0 +1,284 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::time::Duration;
+
+use sui_macros::sim_test;
+use sui_rpc::field::FieldMask;
+use sui_rpc::field::FieldMaskUtil;
+use sui_rpc_api::grpc::alpha::ledger_service_proto::ledger_service_client::LedgerServiceClient;
+use sui_rpc_api::grpc::alpha::ledger_service_proto::transaction_filter::Filter;
+use sui_rpc_api::grpc::alpha::ledger_service_proto::{
+    ListTransactionsRequest, ListTransactionsResponse, MoveCallFilter, TransactionFilter,
+};
+use test_cluster::TestClusterBuilder;
+use tonic::transport::Channel;
+
+use super::rpc_config_with_filter_indexes;
+use crate::{stake_with_validator, transfer_coin};
+
+#[sim_test]
+async fn list_transactions_by_filter() {
+    let test_cluster = TestClusterBuilder::new()
+        .with_rpc_config(rpc_config_with_filter_indexes())
+        .build()
+        .await;
+
+    let accounts = test_cluster
+        .wallet
+        .get_all_accounts_and_gas_objects()
+        .await
+        .unwrap();
+    let sender = accounts[0].0.to_string();
+    let receiver = accounts[1].0.to_string();
+    let transferred = accounts[0].1[1].0.to_string();
+
+    let transfer = transfer_coin(&test_cluster.wallet).await.to_string();
+    let stake = stake_with_validator(&test_cluster).await.to_string();
+
+    let mut client = LedgerServiceClient::connect(test_cluster.rpc_url().to_owned())
+        .await
+        .unwrap();
+
+    let by_sender = wait_for_transactions(&mut client, Filter::Sender(sender.clone()), 2).await;
+    assert_eq!(by_sender, vec![transfer.clone(), stake.clone()]);
+
+    // The transfer touched the receiver and the transferred object (as did genesis, which created
+    // the object and gave it to the sender).
+    let by_receiver = list_all(&mut client, Filter::AffectedAddress(receiver), 10, false).await;
+    assert_eq!(by_receiver.last(), Some(&transfer));
+    assert!(!by_receiver.contains(&stake));
+
+    let by_object = list_all(&mut client, Filter::AffectedObject(transferred), 10, false).await;
+    assert_eq!(by_object.last(), Some(&transfer));
+    assert!(!by_object.contains(&stake));
+
+    // Calls can be filtered by package, module and function.
+    for (module, function) in [
+        (None, None),
+        (Some("sui_system"), None),
+        (Some("sui_system"), Some("request_add_stake")),
+    ] {
+        let filter = move_call("0x3", module, function);
+        assert_eq!(
+            list_all(&mut client, filter, 10, false).await,
+            vec![stake.clone()]
+        );
+    }
+
+    let filter = move_call("0x3", Some("sui_system"), Some("request_withdraw_stake"));
+    assert!(list_all(&mut client, filter, 10, false).await.is_empty());
+
+    // Transactions can be filtered by the types of the events they emitted.
+    let filter = Filter::EventType("0x3::validator::StakingRequestEvent".to_owned());
+    let by_event = list_all(&mut client, filter, 10, false).await;
+    assert_eq!(by_event.last(), Some(&stake));
+    assert!(!by_event.contains(&transfer));
+
+    // Malformed filters are rejected.
+    for filter in [
+        None,
+        Some(Filter::Sender("not an address".to_owned())),
+        Some(move_call("0x3", None, Some("request_add_stake"))),
+    ] {
+        let mut request = ListTransactionsRequest::default();
+        request.filter = filter.map(transaction_filter);
+        let status = client.list_transactions(request).await.unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+    }
+}
+
+#[sim_test]
+async fn list_transactions_pagination() {
+    let test_cluster = TestClusterBuilder::new()
+        .with_rpc_config(rpc_config_with_filter_indexes())
+        .build()
+        .await;
+
+    let sender = test_cluster
+        .wallet
+        .get_all_accounts_and_gas_objects()
+        .await
+        .unwrap()[0]
+        .0
+        .to_string();
+
+    let mut digests = vec![];
+    for _ in 0..3 {
+        digests.push(transfer_coin(&test_cluster.wallet).await.to_string());
+    }
+
+    let mut client = LedgerServiceClient::connect(test_cluster.rpc_url().to_owned())
+        .await
+        .unwrap();
+
+    let filter = Filter::Sender(sender.clone());
+    wait_for_transactions(&mut client, filter.clone(), 3).await;
+
+    // Following page tokens visits every transaction once, in either order.
+    assert_eq!(
+        list_all(&mut client, filter.clone(), 1, false).await,
+        digests
+    );
+    assert_eq!(
+        list_all(&mut client, filter.clone(), 2, true).await,
+        digests.iter().rev().cloned().collect::<Vec<_>>(),
+    );
+
+    let first = list_page(&mut client, filter.clone(), 1, false, None).await;
+    let token = first.next_page_token.unwrap();
+
+    // Page tokens can only be used with the request that produced them.
+    let other = Filter::AffectedAddress(sender.clone());
+    let mut request = ListTransactionsRequest::default();
+    request.filter = Some(transaction_filter(other));
+    request.page_token = Some(token.clone());
+    let status = client.list_transactions(request).await.unwrap_err();
+    assert_eq!(status.code(), tonic::Code::InvalidArgument);
+
+    let mut request = ListTransactionsRequest::default();
+    request.filter = Some(transaction_filter(filter.clone()));
+    request.page_token = Some(token.clone());
+    request.descending = Some(true);
+    let status = client.list_transactions(request).await.unwrap_err();
+    assert_eq!(status.code(), tonic::Code::InvalidArgument);
+
+    let mut request = ListTransactionsRequest::default();
+    request.filter = Some(transaction_filter(filter.clone()));
+    request.page_token = Some(vec![0xff; 3]);
+    let status = client.list_transactions(request).await.unwrap_err();
+    assert_eq!(status.code(), tonic::Code::InvalidArgument);
+}
+
+#[cfg(msim)]
+#[sim_test]
+async fn list_transactions_pruned() {
+    let test_cluster = TestClusterBuilder::new()
+        .with_rpc_config(rpc_config_with_filter_indexes())
+        .with_epoch_duration_ms(5000)
+        .build()
+        .await;
+
+    let sender = test_cluster
+        .wallet
+        .get_all_accounts_and_gas_objects()
+        .await
+        .unwrap()[0]
+        .0
+        .to_string();
+
+    transfer_coin(&test_cluster.wallet).await;
+    transfer_coin(&test_cluster.wallet).await;
+
+    let mut client = LedgerServiceClient::connect(test_cluster.rpc_url().to_owned())
+        .await
+        .unwrap();
+
+    let filter = Filter::Sender(sender);
+    wait_for_transactions(&mut client, filter.clone(), 2).await;
+
+    let first = list_page(&mut client, filter.clone(), 1, false, None).await;
+    let token = first.next_page_token.unwrap();
+    let checkpoint = list_page(&mut client, filter.clone(), 1, true, None)
+        .await
+        .transactions[0]
+        .checkpoint
+        .unwrap();
+
+    super::wait_for_pruned_checkpoint(&test_cluster, checkpoint).await;
+    let latest = transfer_coin(&test_cluster.wallet).await.to_string();
+    wait_for_transactions(&mut client, filter.clone(), 1).await;
+
+    // Transactions from pruned checkpoints are not listed, in either order, even when resuming
+    // from a page token that points at them.
+    assert_eq!(
+        list_all(&mut client, filter.clone(), 10, false).await,
+        vec![latest.clone()]
+    );
+    assert_eq!(
+        list_all(&mut client, filter.clone(), 10, true).await,
+        vec![latest.clone()]
+    );
+
+    let resumed = list_page(&mut client, filter, 10, false, Some(token)).await;
+    let digests: Vec<_> = resumed
+        .transactions
+        .into_iter()
+        .map(|t| t.digest.unwrap())
+        .collect();
+    assert_eq!(digests, vec![latest]);
+}
+
+fn transaction_filter(filter: Filter) -> TransactionFilter {
+    let mut message = TransactionFilter::default();
+    message.filter = Some(filter);
+    message
+}
+
+fn move_call(package: &str, module: Option<&str>, function: Option<&str>) -> Filter {
+    let mut filter = MoveCallFilter::default();
+    filter.package = Some(package.to_owned());
+    filter.module = module.map(str::to_owned);
+    filter.function = function.map(str::to_owned);
+    Filter::MoveCall(filter)
+}
+
+async fn list_page(
+    client: &mut LedgerServiceClient<Channel>,
+    filter: Filter,
+    page_size: u32,
+    descending: bool,
+    page_token: Option<Vec<u8>>,
+) -> ListTransactionsResponse {
+    let mut request = ListTransactionsRequest::default();
+    request.filter = Some(transaction_filter(filter));
+    request.page_size = Some(page_size);
+    request.page_token = page_token;
+    request.descending = Some(descending);
+    request.read_mask = Some(FieldMask::from_str("digest,checkpoint"));
+    client
+        .list_transactions(request)
+        .await
+        .unwrap()
+        .into_inner()
+}
+
+/// The digests of every transaction matching `filter`, fetched `page_size` at a time.
+async fn list_all(
+    client: &mut LedgerServiceClient<Channel>,
+    filter: Filter,
+    page_size: u32,
+    descending: bool,
+) -> Vec<String> {
+    let mut digests = vec![];
+    let mut page_token = None;
+    loop {
+        let page = list_page(client, filter.clone(), page_size, descending, page_token).await;
+        assert!(page.transactions.len() <= page_size as usize);
+        digests.extend(page.transactions.into_iter().map(|t| t.digest.unwrap()));
+
+        page_token = page.next_page_token;
+        if page_token.is_none() {
+            return digests;
+        }
+    }
+}
+
+/// Wait until at least `count` transactions matching `filter` have been indexed, and return their
+/// digests.
+async fn wait_for_transactions(
+    client: &mut LedgerServiceClient<Channel>,
+    filter: Filter,
+    count: usize,
+) -> Vec<String> {
+    for _ in 0..100 {
+        let digests = list_all(client, filter.clone(), 50, false).await;
+        if digests.len() >= count {
+            return digests;
+        }
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    panic!("{count} transactions were not indexed");
+}
@@ -0,0 +1,52 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+mod gas_price_service;
+mod list_transactions;
+mod query_events;
+mod subscription_service;
+
+/// RPC config for a fullnode that indexes transactions and events by filter.
+fn rpc_config_with_filter_indexes() -> sui_config::RpcConfig {
+    sui_config::RpcConfig {
+        enable_indexing: Some(true),
+        transaction_and_event_indexing: Some(true),
+        ..Default::default()
+    }
+}
+
+/// Wait until the fullnode has pruned checkpoint `sequence_number`, moving through epochs so that
+/// it falls out of the fullnode's retention. Only available in simtests, where fullnodes retain
+/// checkpoints for a limited number of epochs.
+#[cfg(msim)]
+async fn wait_for_pruned_checkpoint(
+    test_cluster: &test_cluster::TestCluster,
+    sequence_number: u64,
+) {
+    use std::time::Duration;
+    use sui_rpc::proto::sui::rpc::v2::GetServiceInfoRequest;
+    use sui_rpc::proto::sui::rpc::v2::ledger_service_client::LedgerServiceClient;
+
+    let mut client = LedgerServiceClient::connect(test_cluster.rpc_url().to_owned())
+        .await
+        .unwrap();
+
+    for _ in 0..20 {
+        test_cluster.wait_for_epoch(None).await;
+        tokio::time::sleep(Duration::from_secs(30)).await;
+
+        let lowest = client
+            .get_service_info(GetServiceInfoRequest::default())
+            .await
+            .unwrap()
+            .into_inner()
+            .lowest_available_checkpoint
+            .unwrap();
+
+        if lowest > sequence_number {
+            return;
+        }
+    }
+
+    panic!("checkpoint {sequence_number} was not pruned");
+}
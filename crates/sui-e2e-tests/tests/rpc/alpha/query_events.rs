@@ -0,0 +1,284 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::time::Duration;
+
+use sui_macros::sim_test;
+use sui_rpc_api::grpc::alpha::ledger_service_proto::event_filter::Filter;
+use sui_rpc_api::grpc::alpha::ledger_service_proto::ledger_service_client::LedgerServiceClient;
+use sui_rpc_api::grpc::alpha::ledger_service_proto::{
+    EventFilter, IndexedEvent, MoveModuleFilter, QueryEventsRequest, QueryEventsResponse,
+};
+use test_cluster::TestClusterBuilder;
+use tonic::transport::Channel;
+
+use super::rpc_config_with_filter_indexes;
+use crate::stake_with_validator;
+
+const STAKING_REQUEST_EVENT: &str = "0x3::validator::StakingRequestEvent";
+
+#[sim_test]
+async fn query_events_by_filter() {
+    let test_cluster = TestClusterBuilder::new()
+        .with_rpc_config(rpc_config_with_filter_indexes())
+        .build()
+        .await;
+
+    let sender = test_cluster
+        .wallet
+        .get_all_accounts_and_gas_objects()
+        .await
+        .unwrap()[0]
+        .0
+        .to_string();
+
+    let first = stake_with_validator(&test_cluster).await.to_string();
+    let second = stake_with_validator(&test_cluster).await.to_string();
+    let stakes = vec![first.clone(), second.clone()];
+
+    let mut client = LedgerServiceClient::connect(test_cluster.rpc_url().to_owned())
+        .await
+        .unwrap();
+
+    let by_sender = wait_for_events(&mut client, Filter::Sender(sender), 2).await;
+    assert_eq!(digests(&by_sender), stakes);
+    for event in &by_sender {
+        let event_type = event.event.as_ref().unwrap().event_type.as_ref().unwrap();
+        assert!(event_type.ends_with("::validator::StakingRequestEvent"));
+    }
+
+    // Events can be filtered by type, and by the package and module of the function the
+    // transaction called to emit them.
+    for filter in [
+        Filter::EventType(STAKING_REQUEST_EVENT.to_owned()),
+        emitting_module("0x3", None),
+        emitting_module("0x3", Some("sui_system")),
+    ] {
+        let events = query_all(&mut client, filter, 10, false).await;
+        assert!(digests(&events).ends_with(&stakes));
+    }
+
+    let events = query_all(
+        &mut client,
+        emitting_module("0x3", Some("validator")),
+        10,
+        false,
+    )
+    .await;
+    assert!(!digests(&events).contains(&first));
+
+    // Malformed filters are rejected.
+    for filter in [
+        None,
+        Some(Filter::EventType("not a type".to_owned())),
+        Some(Filter::EmittingModule(MoveModuleFilter::default())),
+    ] {
+        let mut request = QueryEventsRequest::default();
+        request.filter = filter.map(event_filter);
+        let status = client.query_events(request).await.unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+    }
+}
+
+#[sim_test]
+async fn query_events_pagination() {
+    let test_cluster = TestClusterBuilder::new()
+        .with_rpc_config(rpc_config_with_filter_indexes())
+        .build()
+        .await;
+
+    let sender = test_cluster
+        .wallet
+        .get_all_accounts_and_gas_objects()
+        .await
+        .unwrap()[0]
+        .0
+        .to_string();
+
+    for _ in 0..3 {
+        stake_with_validator(&test_cluster).await;
+    }
+
+    let mut client = LedgerServiceClient::connect(test_cluster.rpc_url().to_owned())
+        .await
+        .unwrap();
+
+    let filter = Filter::Sender(sender.clone());
+    let all = wait_for_events(&mut client, filter.clone(), 3).await;
+    let positions: Vec<_> = all.iter().map(position).collect();
+    assert!(positions.is_sorted());
+
+    // Following page tokens visits every event once, in either order.
+    let ascending = query_all(&mut client, filter.clone(), 1, false).await;
+    assert_eq!(
+        ascending.iter().map(position).collect::<Vec<_>>(),
+        positions
+    );
+
+    let descending = query_all(&mut client, filter.clone(), 2, true).await;
+    assert_eq!(
+        descending.iter().rev().map(position).collect::<Vec<_>>(),
+        positions
+    );
+
+    let token = query_page(&mut client, filter.clone(), 1, false, None)
+        .await
+        .next_page_token
+        .unwrap();
+
+    // Page tokens can only be used with the request that produced them.
+    let mut request = QueryEventsRequest::default();
+    request.filter = Some(event_filter(Filter::EventType(
+        STAKING_REQUEST_EVENT.to_owned(),
+    )));
+    request.page_token = Some(token.clone());
+    let status = client.query_events(request).await.unwrap_err();
+    assert_eq!(status.code(), tonic::Code::InvalidArgument);
+
+    let mut request = QueryEventsRequest::default();
+    request.filter = Some(event_filter(filter.clone()));
+    request.page_token = Some(token);
+    request.descending = Some(true);
+    let status = client.query_events(request).await.unwrap_err();
+    assert_eq!(status.code(), tonic::Code::InvalidArgument);
+
+    let mut request = QueryEventsRequest::default();
+    request.filter = Some(event_filter(filter));
+    request.page_token = Some(vec![0xff; 3]);
+    let status = client.query_events(request).await.unwrap_err();
+    assert_eq!(status.code(), tonic::Code::InvalidArgument);
+}
+
+#[cfg(msim)]
+#[sim_test]
+async fn query_events_pruned() {
+    let test_cluster = TestClusterBuilder::new()
+        .with_rpc_config(rpc_config_with_filter_indexes())
+        .with_epoch_duration_ms(5000)
+        .build()
+        .await;
+
+    let sender = test_cluster
+        .wallet
+        .get_all_accounts_and_gas_objects()
+        .await
+        .unwrap()[0]
+        .0
+        .to_string();
+
+    stake_with_validator(&test_cluster).await;
+    stake_with_validator(&test_cluster).await;
+
+    let mut client = LedgerServiceClient::connect(test_cluster.rpc_url().to_owned())
+        .await
+        .unwrap();
+
+    let filter = Filter::Sender(sender);
+    let events = wait_for_events(&mut client, filter.clone(), 2).await;
+    let checkpoint = events.last().unwrap().checkpoint.unwrap();
+    let token = query_page(&mut client, filter.clone(), 1, false, None)
+        .await
+        .next_page_token
+        .unwrap();
+
+    super::wait_for_pruned_checkpoint(&test_cluster, checkpoint).await;
+    let latest = stake_with_validator(&test_cluster).await.to_string();
+    wait_for_events(&mut client, filter.clone(), 1).await;
+
+    // Events from pruned checkpoints are not returned, in either order, even when resuming from a
+    // page token that points at them.
+    let ascending = query_all(&mut client, filter.clone(), 10, false).await;
+    assert_eq!(digests(&ascending), vec![latest.clone()]);
+
+    let descending = query_all(&mut client, filter.clone(), 10, true).await;
+    assert_eq!(digests(&descending), vec![latest.clone()]);
+
+    let resumed = query_page(&mut client, filter, 10, false, Some(token)).await;
+    assert_eq!(digests(&resumed.events), vec![latest]);
+}
+
+fn event_filter(filter: Filter) -> EventFilter {
+    let mut message = EventFilter::default();
+    message.filter = Some(filter);
+    message
+}
+
+fn emitting_module(package: &str, module: Option<&str>) -> Filter {
+    let mut filter = MoveModuleFilter::default();
+    filter.package = Some(package.to_owned());
+    filter.module = module.map(str::to_owned);
+    Filter::EmittingModule(filter)
+}
+
+/// Where `event` was emitted, in the order events are indexed.
+fn position(event: &IndexedEvent) -> (u64, u32, u32) {
+    (
+        event.checkpoint.unwrap(),
+        event.transaction_idx.unwrap(),
+        event.event_idx.unwrap(),
+    )
+}
+
+/// The digests of the transactions that emitted `events`, without repeats.
+fn digests(events: &[IndexedEvent]) -> Vec<String> {
+    let mut digests: Vec<String> = events
+        .iter()
+        .map(|e| e.transaction_digest.clone().unwrap())
+        .collect();
+    digests.dedup();
+    digests
+}
+
+async fn query_page(
+    client: &mut LedgerServiceClient<Channel>,
+    filter: Filter,
+    page_size: u32,
+    descending: bool,
+    page_token: Option<Vec<u8>>,
+) -> QueryEventsResponse {
+    let mut request = QueryEventsRequest::default();
+    request.filter = Some(event_filter(filter));
+    request.page_size = Some(page_size);
+    request.page_token = page_token;
+    request.descending = Some(descending);
+    client.query_events(request).await.unwrap().into_inner()
+}
+
+/// Every event matching `filter`, fetched `page_size` at a time.
+async fn query_all(
+    client: &mut LedgerServiceClient<Channel>,
+    filter: Filter,
+    page_size: u32,
+    descending: bool,
+) -> Vec<IndexedEvent> {
+    let mut events = vec![];
+    let mut page_token = None;
+    loop {
+        let page = query_page(client, filter.clone(), page_size, descending, page_token).await;
+        assert!(page.events.len() <= page_size as usize);
+        events.extend(page.events);
+
+        page_token = page.next_page_token;
+        if page_token.is_none() {
+            return events;
+        }
+    }
+}
+
+/// Wait until at least `count` events matching `filter` have been indexed, and return them.
+async fn wait_for_events(
+    client: &mut LedgerServiceClient<Channel>,
+    filter: Filter,
+    count: usize,
+) -> Vec<IndexedEvent> {
+    for _ in 0..100 {
+        let events = query_all(client, filter.clone(), 50, false).await;
+        if events.len() >= count {
+            return events;
+        }
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    panic!("{count} events were not indexed");
+}
@@ -0,0 +1,212 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::time::Duration;
+
+use sui_macros::sim_test;
+use sui_rpc::field::FieldMask;
+use sui_rpc::field::FieldMaskUtil;
+use sui_rpc_api::Client;
+use sui_rpc_api::grpc::alpha::subscription_service_proto::subscription_service_client::SubscriptionServiceClient;
+use sui_rpc_api::grpc::alpha::subscription_service_proto::transaction_filter::Filter;
+use sui_rpc_api::grpc::alpha::subscription_service_proto::{
+    SubscribeCheckpointsRequest, SubscribeCheckpointsResponse, TransactionFilter,
+};
+use test_cluster::{TestCluster, TestClusterBuilder};
+use tokio_stream::StreamExt;
+
+use crate::transfer_coin;
+
+/// Number of checkpoints to let pass without reading from a subscription, so that the node's
+/// subscription service drops it for falling behind (it buffers 256 checkpoints per subscriber).
+const LAG: u64 = 512;
+
+#[sim_test]
+async fn subscribe_checkpoints_from_the_past() {
+    let test_cluster = TestClusterBuilder::new().build().await;
+    let latest = wait_for_checkpoint(&test_cluster, 5).await;
+
+    let mut request = SubscribeCheckpointsRequest::default();
+    request.read_mask = Some(FieldMask::from_str("sequence_number"));
+    request.start_cursor = Some(1);
+
+    let mut stream = subscribe(&test_cluster, request).await;
+
+    // Checkpoints are streamed from the store up to the latest checkpoint, and then live, without
+    // gaps or repeats at the handover.
+    let mut expected = 1;
+    while expected <= latest + 10 {
+        let response = stream.next().await.unwrap().unwrap();
+        let cursor = response.cursor.unwrap();
+        assert_eq!(cursor, expected);
+        assert_eq!(response.checkpoint.unwrap().sequence_number, Some(cursor));
+        assert!(response.latest_checkpoint.unwrap() >= cursor);
+        expected += 1;
+    }
+}
+
+#[sim_test]
+async fn subscribe_checkpoints_after_falling_behind() {
+    let test_cluster = TestClusterBuilder::new().build().await;
+
+    // A tiny flow-control window stops the node from sending (and reading) checkpoints for the
+    // subscription while the client is not reading from it.
+    let channel = tonic::transport::Endpoint::from_shared(test_cluster.rpc_url().to_owned())
+        .unwrap()
+        .initial_stream_window_size(Some(256))
+        .initial_connection_window_size(Some(256))
+        .connect()
+        .await
+        .unwrap();
+
+    let mut request = SubscribeCheckpointsRequest::default();
+    request.read_mask = Some(FieldMask::from_str("sequence_number"));
+
+    let mut stream = SubscriptionServiceClient::new(channel)
+        .subscribe_checkpoints(request)
+        .await
+        .unwrap()
+        .into_inner();
+
+    let first = stream.next().await.unwrap().unwrap().cursor.unwrap();
+    wait_for_checkpoint(&test_cluster, first + LAG).await;
+
+    // The subscription is dropped by the node's subscription service while it is not being read,
+    // after which it registers again and catches up from the store, so the client sees no gaps.
+    let mut expected = first + 1;
+    while expected <= first + LAG + 10 {
+        let response = stream.next().await.unwrap().unwrap();
+        assert_eq!(response.cursor, Some(expected));
+        expected += 1;
+    }
+}
+
+#[sim_test]
+async fn subscribe_checkpoints_filtered() {
+    let test_cluster = TestClusterBuilder::new().build().await;
+    let sender = test_cluster
+        .wallet
+        .get_all_accounts_and_gas_objects()
+        .await
+        .unwrap()[0]
+        .0;
+
+    // Filters must be well-formed.
+    let mut request = SubscribeCheckpointsRequest::default();
+    request.filters = vec![TransactionFilter::default()];
+    let status = SubscriptionServiceClient::connect(test_cluster.rpc_url().to_owned())
+        .await
+        .unwrap()
+        .subscribe_checkpoints(request)
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), tonic::Code::InvalidArgument);
+
+    let mut filter = TransactionFilter::default();
+    filter.filter = Some(Filter::Sender(sender.to_string()));
+
+    let mut request = SubscribeCheckpointsRequest::default();
+    request.read_mask = Some(FieldMask::from_str("sequence_number,transactions.digest"));
+    request.filters = vec![filter];
+
+    let mut stream = subscribe(&test_cluster, request).await;
+    let digest = transfer_coin(&test_cluster.wallet).await.to_string();
+
+    // Only the transaction sent by `sender` is included, and the checkpoints it is not in (which
+    // only contain system transactions) are streamed empty.
+    let mut found = false;
+    let mut checkpoints = 0;
+    while !found {
+        let response = stream.next().await.unwrap().unwrap();
+        let checkpoint = response.checkpoint.unwrap();
+        for transaction in checkpoint.transactions {
+            assert_eq!(transaction.digest.as_ref(), Some(&digest));
+            found = true;
+        }
+
+        checkpoints += 1;
+        assert!(checkpoints < 100, "transaction not streamed");
+    }
+}
+
+#[sim_test]
+async fn subscribe_checkpoints_heartbeats() {
+    let test_cluster = TestClusterBuilder::new().build().await;
+
+    // The heartbeat interval must be positive.
+    let mut request = SubscribeCheckpointsRequest::default();
+    request.heartbeat_interval_ms = Some(0);
+    let status = SubscriptionServiceClient::connect(test_cluster.rpc_url().to_owned())
+        .await
+        .unwrap()
+        .subscribe_checkpoints(request)
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), tonic::Code::InvalidArgument);
+
+    let mut request = SubscribeCheckpointsRequest::default();
+    request.read_mask = Some(FieldMask::from_str("sequence_number"));
+    request.heartbeat_interval_ms = Some(1);
+
+    let mut stream = subscribe(&test_cluster, request).await;
+
+    // Heartbeats carry no checkpoint, and their cursor is the last checkpoint streamed before
+    // them, so the subscription can resume from them.
+    let mut last = None;
+    let mut heartbeats = 0;
+    let mut checkpoints = 0;
+    while heartbeats < 5 || checkpoints < 5 {
+        let response = stream.next().await.unwrap().unwrap();
+        let cursor = response.cursor.unwrap();
+        assert!(response.latest_checkpoint.unwrap() >= cursor);
+
+        match response.checkpoint {
+            None => {
+                if let Some(last) = last {
+                    assert_eq!(cursor, last);
+                }
+                heartbeats += 1;
+            }
+
+            Some(checkpoint) => {
+                assert_eq!(checkpoint.sequence_number, Some(cursor));
+                if let Some(last) = last {
+                    assert_eq!(cursor, last + 1);
+                }
+                last = Some(cursor);
+                checkpoints += 1;
+            }
+        }
+    }
+}
+
+async fn subscribe(
+    test_cluster: &TestCluster,
+    request: SubscribeCheckpointsRequest,
+) -> tonic::Streaming<SubscribeCheckpointsResponse> {
+    SubscriptionServiceClient::connect(test_cluster.rpc_url().to_owned())
+        .await
+        .unwrap()
+        .subscribe_checkpoints(request)
+        .await
+        .unwrap()
+        .into_inner()
+}
+
+/// Wait until the fullnode has executed checkpoint `sequence_number`, returning its latest
+/// checkpoint.
+async fn wait_for_checkpoint(test_cluster: &TestCluster, sequence_number: u64) -> u64 {
+    let mut client = Client::new(test_cluster.rpc_url()).unwrap();
+    loop {
+        let latest = client
+            .get_latest_checkpoint()
+            .await
+            .unwrap()
+            .sequence_number;
+        if latest >= sequence_number {
+            return latest;
+        }
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+}
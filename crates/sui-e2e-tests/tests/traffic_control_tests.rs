@@ -314,6 +314,7 @@ async fn test_validator_traffic_control_error_blocked_with_policy_reconfig()
                 error_threshold: None,
                 spam_threshold: None,
                 dry_run: Some(false),
+                policy_config: None,
             })
             .await
             .unwrap();
@@ -677,6 +678,26 @@ async fn test_traffic_control_manual_set_dead_mans_switch() -> Result<(), anyhow
     Ok(())
 }
 
+#[tokio::test]
+async fn test_traffic_control_admin_block_and_unblock() -> Result<(), anyhow::Error> {
+    let tc = TrafficController::init_for_test(PolicyConfig::default(), None).await;
+    let ip = "8.7.6.5".parse().unwrap();
+
+    // A TTL that overflows the clock is rejected rather than panicking.
+    assert!(tc.admin_block(ip, false, Duration::MAX).is_err());
+    assert!(tc.admin_client_status(ip).await?.blocked.is_none());
+
+    tc.admin_block(ip, false, Duration::from_secs(60))?;
+    let status = tc.admin_client_status(ip).await?;
+    assert!(status.blocked.is_some());
+    assert!(status.blocked_as_proxied.is_none());
+
+    assert!(tc.admin_unblock(ip, false)?);
+    assert!(!tc.admin_unblock(ip, false)?);
+    assert!(tc.admin_client_status(ip).await?.blocked.is_none());
+    Ok(())
+}
+
 #[sim_test]
 async fn test_traffic_sketch_no_blocks() {
     telemetry_subscribers::init_for_testing();
@@ -74,6 +74,36 @@ pub trait Connection: Send {
         pipeline: &'static str,
         pruner_hi: u64,
     ) -> anyhow::Result<bool>;
+
+    /// Record that `pipeline` failed to process `checkpoint` with a permanent `error`, so that the
+    /// pipeline can move past it and the checkpoint can be re-driven later. Recording the same
+    /// checkpoint again replaces its error and increments its attempt count.
+    ///
+    /// Stores that do not support dead-lettering can rely on the default implementation, which
+    /// returns an error. Stores that do must also set [Store::SUPPORTS_DEAD_LETTERS].
+    async fn record_dead_letter(
+        &mut self,
+        pipeline: &str,
+        _checkpoint: u64,
+        _error: &str,
+    ) -> anyhow::Result<()> {
+        anyhow::bail!("Store does not support dead-lettering checkpoints for {pipeline}")
+    }
+
+    /// The checkpoints dead-lettered for `pipeline`, in checkpoint order.
+    async fn dead_letters(&mut self, pipeline: &str) -> anyhow::Result<Vec<DeadLetter>> {
+        anyhow::bail!("Store does not support dead-lettering checkpoints for {pipeline}")
+    }
+
+    /// Remove the dead-letter entry for `checkpoint` in `pipeline`, once it has been re-driven
+    /// successfully. Returns a boolean indicating whether an entry was removed.
+    async fn delete_dead_letter(
+        &mut self,
+        pipeline: &str,
+        _checkpoint: u64,
+    ) -> anyhow::Result<bool> {
+        anyhow::bail!("Store does not support dead-lettering checkpoints for {pipeline}")
+    }
 }
 
 /// A storage-agnostic interface that provides database connections for both watermark management
@@ -90,6 +120,10 @@ pub trait Store: Send + Sync + 'static + Clone {
     /// committer watermark.
     const DELIMITER: &'static str = "@";
 
+    /// Whether the store's connections implement the dead-letter operations. The indexer only
+    /// allows pipelines to dead-letter checkpoints if they do.
+    const SUPPORTS_DEAD_LETTERS: bool = false;
+
     async fn connect<'c>(&'c self) -> Result<Self::Connection<'c>, anyhow::Error>;
 }
 
@@ -149,6 +183,17 @@ pub struct PrunerWatermark {
     pub pruner_hi: u64,
 }
 
+/// A checkpoint that a pipeline failed to process with a permanent error, and skipped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeadLetter {
+    pub pipeline: String,
+    pub checkpoint: u64,
+    /// The error from the latest attempt at processing the checkpoint.
+    pub error: String,
+    /// The number of times processing the checkpoint has failed.
+    pub attempts: u64,
+}
+
 impl CommitterWatermark {
     pub fn timestamp(&self) -> DateTime<Utc> {
         DateTime::from_timestamp_millis(self.timestamp_ms_hi_inclusive as i64).unwrap_or_default()
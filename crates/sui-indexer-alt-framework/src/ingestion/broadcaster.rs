@@ -13,12 +13,25 @@ use tracing::{debug, error, info, warn};
 
 use super::{IngestionConfig, ingestion_client::IngestionClient};
 use crate::{
-    ingestion::{error::Error, streaming_client::CheckpointStreamingClient},
+    ingestion::{
+        error::Error, filter::CheckpointFilter, streaming_client::CheckpointStreamingClient,
+    },
     metrics::IngestionMetrics,
     task::TrySpawnStreamExt,
     types::full_checkpoint_content::Checkpoint,
 };
 
+/// A subscriber to the broadcaster: the channel it receives checkpoints on, and the filter for the
+/// transactions it is interested in, if any.
+pub(super) struct Subscriber {
+    pub(super) filter: Option<CheckpointFilter>,
+    pub(super) tx: mpsc::Sender<Arc<Checkpoint>>,
+}
+
+/// Subscribers grouped by filter, so that each distinct filter is only evaluated once per
+/// checkpoint.
+struct Subscribers(Vec<(Option<CheckpointFilter>, Vec<mpsc::Sender<Arc<Checkpoint>>>)>);
+
 /// Broadcaster task that manages checkpoint flow and spawns broadcast tasks for ranges
 /// via either streaming or ingesting, or both.
 ///
@@ -41,7 +54,7 @@ pub(super) fn broadcaster<R, S>(
     config: IngestionConfig,
     client: IngestionClient,
     mut commit_hi_rx: mpsc::UnboundedReceiver<(&'static str, u64)>,
-    subscribers: Vec<mpsc::Sender<Arc<Checkpoint>>>,
+    subscribers: Vec<Subscriber>,
     metrics: Arc<IngestionMetrics>,
     cancel: CancellationToken,
 ) -> JoinHandle<()>
@@ -72,7 +85,15 @@ where
 
         let buffer_size = config.checkpoint_buffer_size as u64;
 
-        let subscribers = Arc::new(subscribers);
+        // Only ask the server for the transactions that some subscriber is interested in.
+        if let Some(streaming_client) = &mut streaming_client
+            && let Some(filters) =
+                CheckpointFilter::push_down(subscribers.iter().map(|s| s.filter.as_ref()))
+        {
+            streaming_client.push_down_filters(filters);
+        }
+
+        let subscribers = Arc::new(Subscribers::new(subscribers));
 
         // Track subscriber watermarks
         let mut subscribers_hi = HashMap::<&'static str, u64>::new();
@@ -194,7 +215,7 @@ async fn ingest_and_broadcast_range(
     ingest_concurrency: usize,
     ingest_hi_rx: watch::Receiver<Option<u64>>,
     client: IngestionClient,
-    subscribers: Arc<Vec<mpsc::Sender<Arc<Checkpoint>>>>,
+    subscribers: Arc<Subscribers>,
     cancel: CancellationToken,
 ) -> Result<(), Error> {
     stream::iter(start..end)
@@ -260,7 +281,7 @@ async fn setup_streaming_task<S>(
     end_cp: u64,
     streaming_backoff_batch_size: &mut u64,
     config: &IngestionConfig,
-    subscribers: &Arc<Vec<mpsc::Sender<Arc<Checkpoint>>>>,
+    subscribers: &Arc<Subscribers>,
     ingest_hi_watch_rx: &watch::Receiver<Option<u64>>,
     metrics: &Arc<IngestionMetrics>,
     cancel: &CancellationToken,
@@ -341,7 +362,7 @@ async fn stream_and_broadcast_range(
     mut lo: u64,
     hi: u64,
     mut stream: impl Stream<Item = Result<Checkpoint, Error>> + std::marker::Unpin,
-    subscribers: Arc<Vec<mpsc::Sender<Arc<Checkpoint>>>>,
+    subscribers: Arc<Subscribers>,
     mut ingest_hi_rx: watch::Receiver<Option<u64>>,
     metrics: Arc<IngestionMetrics>,
     cancel: CancellationToken,
@@ -407,16 +428,46 @@ async fn stream_and_broadcast_range(
     lo
 }
 
-/// Send a checkpoint to all subscribers.
-/// Returns an error if any subscriber's channel is closed.
+/// Send a checkpoint to all subscribers, only including the transactions that match each
+/// subscriber's filter. Returns an error if any subscriber's channel is closed.
 async fn send_checkpoint(
     checkpoint: Arc<Checkpoint>,
-    subscribers: &[mpsc::Sender<Arc<Checkpoint>>],
+    subscribers: &Subscribers,
 ) -> Result<Vec<()>, mpsc::error::SendError<Arc<Checkpoint>>> {
-    let futures = subscribers.iter().map(|s| s.send(checkpoint.clone()));
+    let mut futures = vec![];
+    for (filter, senders) in &subscribers.0 {
+        let checkpoint = match filter {
+            Some(filter) => filter.apply(&checkpoint),
+            None => checkpoint.clone(),
+        };
+
+        futures.extend(senders.iter().map(|s| s.send(checkpoint.clone())));
+    }
+
     try_join_all(futures).await
 }
 
+impl Subscribers {
+    fn new(subscribers: Vec<Subscriber>) -> Self {
+        let mut groups: Vec<(Option<CheckpointFilter>, Vec<_>)> = vec![];
+        for Subscriber { filter, tx } in subscribers {
+            if let Some((_, senders)) = groups.iter_mut().find(|(f, _)| *f == filter) {
+                senders.push(tx);
+            } else {
+                groups.push((filter, vec![tx]));
+            }
+        }
+
+        Self(groups)
+    }
+}
+
+impl From<mpsc::Sender<Arc<Checkpoint>>> for Subscriber {
+    fn from(tx: mpsc::Sender<Arc<Checkpoint>>) -> Self {
+        Self { filter: None, tx }
+    }
+}
+
 // A noop streaming task that just returns the provided checkpoint_hi, used to simplify
 // join logic when streaming is not used.
 fn noop_streaming_task(checkpoint_hi: u64) -> JoinHandle<u64> {
@@ -522,7 +573,7 @@ mod tests {
             test_config(),
             mock_client(metrics.clone()),
             hi_rx,
-            vec![subscriber_tx],
+            vec![subscriber_tx.into()],
             metrics,
             cancel.clone(),
         );
@@ -550,7 +601,7 @@ mod tests {
             test_config(),
             mock_client(metrics.clone()),
             hi_rx,
-            vec![subscriber_tx],
+            vec![subscriber_tx.into()],
             metrics,
             cancel.clone(),
         );
@@ -578,7 +629,7 @@ mod tests {
             test_config(),
             mock_client(metrics.clone()),
             hi_rx,
-            vec![subscriber_tx],
+            vec![subscriber_tx.into()],
             metrics,
             cancel.clone(),
         );
@@ -609,7 +660,7 @@ mod tests {
             config,
             mock_client(metrics.clone()),
             hi_rx,
-            vec![subscriber_tx],
+            vec![subscriber_tx.into()],
             metrics,
             cancel.clone(),
         );
@@ -643,7 +694,7 @@ mod tests {
             config,
             mock_client(metrics.clone()),
             hi_rx,
-            vec![subscriber_tx],
+            vec![subscriber_tx.into()],
             metrics,
             cancel.clone(),
         );
@@ -677,7 +728,7 @@ mod tests {
             config,
             mock_client(metrics.clone()),
             hi_rx,
-            vec![subscriber_tx],
+            vec![subscriber_tx.into()],
             metrics,
             cancel.clone(),
         );
@@ -724,7 +775,7 @@ mod tests {
             config,
             mock_client(metrics.clone()),
             hi_rx,
-            vec![subscriber_tx],
+            vec![subscriber_tx.into()],
             metrics,
             cancel.clone(),
         );
@@ -777,7 +828,7 @@ mod tests {
             test_config(),
             mock_client(metrics.clone()),
             hi_rx,
-            vec![subscriber_tx1, subscriber_tx2],
+            vec![subscriber_tx1.into(), subscriber_tx2.into()],
             metrics,
             cancel.clone(),
         );
@@ -819,7 +870,7 @@ mod tests {
             config,
             mock_client(metrics.clone()),
             hi_rx,
-            vec![subscriber_tx],
+            vec![subscriber_tx.into()],
             metrics,
             cancel.clone(),
         );
@@ -866,7 +917,7 @@ mod tests {
             test_config(),
             mock_client(metrics.clone()),
             hi_rx,
-            vec![subscriber_tx],
+            vec![subscriber_tx.into()],
             metrics.clone(),
             cancel.clone(),
         );
@@ -901,7 +952,7 @@ mod tests {
             test_config(),
             mock_client(metrics.clone()),
             hi_rx,
-            vec![subscriber_tx],
+            vec![subscriber_tx.into()],
             metrics.clone(),
             cancel.clone(),
         );
@@ -940,7 +991,7 @@ mod tests {
             test_config(),
             mock_client(metrics.clone()),
             hi_rx,
-            vec![subscriber_tx],
+            vec![subscriber_tx.into()],
             metrics.clone(),
             cancel.clone(),
         );
@@ -977,7 +1028,7 @@ mod tests {
             test_config(),
             mock_client(metrics.clone()),
             hi_rx,
-            vec![subscriber_tx],
+            vec![subscriber_tx.into()],
             metrics.clone(),
             cancel.clone(),
         );
@@ -1019,7 +1070,7 @@ mod tests {
             test_config(),
             mock_client(metrics.clone()),
             hi_rx,
-            vec![subscriber_tx],
+            vec![subscriber_tx.into()],
             metrics.clone(),
             cancel.clone(),
         );
@@ -1058,7 +1109,7 @@ mod tests {
             test_config(),
             mock_client(metrics.clone()),
             hi_rx,
-            vec![subscriber_tx],
+            vec![subscriber_tx.into()],
             metrics.clone(),
             cancel.clone(),
         );
@@ -1104,7 +1155,7 @@ mod tests {
             config,
             mock_client(metrics.clone()),
             hi_rx,
-            vec![subscriber_tx],
+            vec![subscriber_tx.into()],
             metrics.clone(),
             cancel.clone(),
         );
@@ -1156,7 +1207,7 @@ mod tests {
             test_config(),
             mock_client(metrics.clone()),
             hi_rx,
-            vec![subscriber_tx],
+            vec![subscriber_tx.into()],
             metrics.clone(),
             cancel.clone(),
         );
@@ -1202,7 +1253,7 @@ mod tests {
             test_config(),
             mock_client(metrics.clone()),
             hi_rx,
-            vec![subscriber_tx],
+            vec![subscriber_tx.into()],
             metrics.clone(),
             cancel.clone(),
         );
@@ -1243,7 +1294,7 @@ mod tests {
             config,
             mock_client(metrics.clone()),
             hi_rx,
-            vec![subscriber_tx],
+            vec![subscriber_tx.into()],
             metrics.clone(),
             cancel.clone(),
         );
@@ -1290,7 +1341,7 @@ mod tests {
             config,
             mock_client(metrics.clone()),
             hi_rx,
-            vec![subscriber_tx],
+            vec![subscriber_tx.into()],
             metrics.clone(),
             cancel.clone(),
         );
@@ -1333,7 +1384,7 @@ mod tests {
             test_config(),
             mock_client(metrics.clone()),
             hi_rx,
-            vec![subscriber_tx],
+            vec![subscriber_tx.into()],
             metrics.clone(),
             cancel.clone(),
         );
@@ -1377,7 +1428,7 @@ mod tests {
             test_config(),
             mock_client(metrics.clone()),
             hi_rx,
-            vec![subscriber_tx],
+            vec![subscriber_tx.into()],
             metrics.clone(),
             cancel.clone(),
         );
@@ -1416,4 +1467,111 @@ mod tests {
         cancel.cancel();
         h_broadcaster.await.unwrap();
     }
+
+    #[tokio::test]
+    async fn filtered_subscribers() {
+        use crate::ingestion::filter::TransactionFilter;
+        use crate::types::test_checkpoint_data_builder::TestCheckpointBuilder;
+        use crate::types::transaction::TransactionDataAPI;
+
+        let sender = TestCheckpointBuilder::derive_address(1);
+        let checkpoint = Arc::new(
+            TestCheckpointBuilder::new(0)
+                .start_transaction(0)
+                .finish_transaction()
+                .start_transaction(1)
+                .finish_transaction()
+                .build_checkpoint(),
+        );
+
+        let filter = CheckpointFilter::new([TransactionFilter::Sender(sender)]);
+        let (unfiltered_tx, mut unfiltered_rx) = mpsc::channel(1);
+        let (filtered_tx1, mut filtered_rx1) = mpsc::channel(1);
+        let (filtered_tx2, mut filtered_rx2) = mpsc::channel(1);
+
+        let subscribers = Subscribers::new(vec![
+            unfiltered_tx.into(),
+            Subscriber {
+                filter: Some(filter.clone()),
+                tx: filtered_tx1,
+            },
+            Subscriber {
+                filter: Some(filter),
+                tx: filtered_tx2,
+            },
+        ]);
+
+        // Subscribers with the same filter share a group.
+        assert_eq!(subscribers.0.len(), 2);
+
+        send_checkpoint(checkpoint.clone(), &subscribers)
+            .await
+            .unwrap();
+
+        let unfiltered = expect_recv(&mut unfiltered_rx).await.unwrap();
+        assert!(Arc::ptr_eq(&unfiltered, &checkpoint));
+
+        // The filter is evaluated once, and its result shared between its subscribers.
+        let filtered1 = expect_recv(&mut filtered_rx1).await.unwrap();
+        let filtered2 = expect_recv(&mut filtered_rx2).await.unwrap();
+        assert!(Arc::ptr_eq(&filtered1, &filtered2));
+        assert_eq!(filtered1.transactions.len(), 1);
+        assert_eq!(filtered1.transactions[0].transaction.sender(), sender);
+    }
+
+    #[tokio::test]
+    async fn streaming_pushes_down_filters() {
+        use crate::ingestion::filter::TransactionFilter;
+        use crate::types::test_checkpoint_data_builder::TestCheckpointBuilder;
+
+        let filter = TransactionFilter::Sender(TestCheckpointBuilder::derive_address(1));
+        let run = |unfiltered: bool| {
+            let filter = filter.clone();
+            async move {
+                let (_, hi_rx) = mpsc::unbounded_channel();
+                let (filtered_tx, mut filtered_rx) = mpsc::channel(10);
+                let (unfiltered_tx, mut unfiltered_rx) = mpsc::channel(10);
+                let cancel = CancellationToken::new();
+
+                let mut subscribers = vec![Subscriber {
+                    filter: Some(CheckpointFilter::new([filter.clone()])),
+                    tx: filtered_tx,
+                }];
+                if unfiltered {
+                    subscribers.push(unfiltered_tx.into());
+                }
+
+                let streaming_client = MockStreamingClient::new(0..5);
+                let pushed_filters = streaming_client.pushed_filters.clone();
+                let metrics = test_ingestion_metrics();
+                let h_broadcaster = broadcaster(
+                    0..5,
+                    None,
+                    Some(streaming_client),
+                    test_config(),
+                    mock_client(metrics.clone()),
+                    hi_rx,
+                    subscribers,
+                    metrics.clone(),
+                    cancel.clone(),
+                );
+
+                assert_eq!(recv_vec(&mut filtered_rx, 5).await, Vec::from_iter(0..5));
+                if unfiltered {
+                    assert_eq!(recv_vec(&mut unfiltered_rx, 5).await, Vec::from_iter(0..5));
+                }
+
+                cancel.cancel();
+                h_broadcaster.await.unwrap();
+                pushed_filters.lock().unwrap().clone()
+            }
+        };
+
+        // Every subscriber is filtered, so the server only needs to send the matching
+        // transactions.
+        assert_eq!(run(false).await, Some(vec![filter.to_proto().unwrap()]));
+
+        // An unfiltered subscriber needs every transaction.
+        assert_eq!(run(true).await, None);
+    }
 }
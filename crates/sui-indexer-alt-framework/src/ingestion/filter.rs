@@ -0,0 +1,448 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Declarative filters that pipelines can use to only receive the transactions they are
+//! interested in.
+//!
+//! A pipeline declares its filter through [crate::pipeline::Processor::filter]. The ingestion
+//! service evaluates each distinct filter once per checkpoint, and delivers a copy of the
+//! checkpoint to the pipeline that only contains the matching transactions (and the objects they
+//! touched). The checkpoint's summary and contents are delivered unchanged, so that watermarks
+//! still account for every transaction in the checkpoint, and pipelines still receive every
+//! checkpoint, even those without any matching transactions.
+//!
+//! Because the contents are kept, a filtered checkpoint's transactions can no longer be numbered
+//! by their position in `transactions`. Pipelines that use filters must get each transaction's
+//! sequence number from [Checkpoint::transactions_with_sequence_numbers] instead.
+//!
+//! Filters are evaluated by the ingestion service, after the checkpoint is fetched. When
+//! checkpoints are streamed and every pipeline declares a filter the server can evaluate, the
+//! union of the filters is also pushed down to the server (see [CheckpointFilter::push_down]), so
+//! that it only sends the matching transactions and the objects they touched. Checkpoints fetched
+//! outside of the stream are fetched in full, as `GetCheckpoint` does not accept filters.
+
+use std::{collections::BTreeSet, sync::Arc};
+
+use move_core_types::language_storage::StructTag;
+use sui_rpc_api::grpc::alpha::subscription_service_proto::{
+    MoveCallFilter, TransactionFilter as ProtoTransactionFilter, transaction_filter::Filter,
+};
+
+use crate::types::{
+    base_types::{ObjectID, SuiAddress},
+    effects::TransactionEffectsAPI,
+    full_checkpoint_content::{Checkpoint, ExecutedTransaction, ObjectSet},
+    object::{Object, Owner},
+    transaction::TransactionDataAPI,
+};
+
+/// A condition on a single transaction.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum TransactionFilter {
+    /// The transaction was sent by this address.
+    Sender(SuiAddress),
+
+    /// The transaction was sent or sponsored by this address, or touched an object owned by
+    /// this address (before or after the transaction).
+    AffectedAddress(SuiAddress),
+
+    /// The transaction calls a function in this package, and optionally this module and
+    /// function.
+    MoveCall {
+        package: ObjectID,
+        module: Option<String>,
+        function: Option<String>,
+    },
+
+    /// The transaction emitted an event of this type. If the type has no type parameters,
+    /// events with any instantiation of it match.
+    EventType(StructTag),
+
+    /// The transaction touched an object of this type. If the type has no type parameters,
+    /// objects with any instantiation of it match. The server cannot evaluate this filter.
+    ObjectType(StructTag),
+}
+
+/// A set of [TransactionFilter]s: a transaction matches if it matches any of them.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CheckpointFilter {
+    filters: Vec<TransactionFilter>,
+}
+
+impl TransactionFilter {
+    /// Whether the transaction `tx`, whose objects are in `objects`, matches this filter.
+    pub fn matches(&self, tx: &ExecutedTransaction, objects: &ObjectSet) -> bool {
+        match self {
+            TransactionFilter::Sender(address) => tx.transaction.sender() == *address,
+
+            TransactionFilter::AffectedAddress(address) => {
+                tx.transaction.sender() == *address
+                    || tx.transaction.gas_owner() == *address
+                    || touched_objects(tx, objects).any(|o| match o.owner() {
+                        Owner::AddressOwner(owner) | Owner::ConsensusAddressOwner { owner, .. } => {
+                            owner == address
+                        }
+                        _ => false,
+                    })
+            }
+
+            TransactionFilter::MoveCall {
+                package,
+                module,
+                function,
+            } => tx.transaction.move_calls().into_iter().any(|(p, m, f)| {
+                p == package
+                    && module.as_ref().is_none_or(|module| module == m)
+                    && function.as_ref().is_none_or(|function| function == f)
+            }),
+
+            TransactionFilter::EventType(type_) => tx
+                .events
+                .as_ref()
+                .is_some_and(|events| events.data.iter().any(|e| type_matches(type_, &e.type_))),
+
+            TransactionFilter::ObjectType(type_) => touched_objects(tx, objects)
+                .filter_map(|o| o.struct_tag())
+                .any(|tag| type_matches(type_, &tag)),
+        }
+    }
+
+    /// This filter as a filter of the server's `SubscribeCheckpoints`, if the server can evaluate
+    /// it. The server matches the same transactions.
+    pub(crate) fn to_proto(&self) -> Option<ProtoTransactionFilter> {
+        let filter = match self {
+            TransactionFilter::Sender(address) => Filter::Sender(address.to_string()),
+            TransactionFilter::AffectedAddress(address) => {
+                Filter::AffectedAddress(address.to_string())
+            }
+
+            // The server requires the module of a function.
+            TransactionFilter::MoveCall {
+                module: None,
+                function: Some(_),
+                ..
+            } => return None,
+
+            TransactionFilter::MoveCall {
+                package,
+                module,
+                function,
+            } => {
+                let mut call = MoveCallFilter::default();
+                call.package = Some(package.to_string());
+                call.module = module.clone();
+                call.function = function.clone();
+                Filter::MoveCall(call)
+            }
+
+            TransactionFilter::EventType(type_) => {
+                Filter::EventType(type_.to_canonical_string(/* with_prefix */ true))
+            }
+
+            TransactionFilter::ObjectType(_) => return None,
+        };
+
+        let mut proto = ProtoTransactionFilter::default();
+        proto.filter = Some(filter);
+        Some(proto)
+    }
+}
+
+impl CheckpointFilter {
+    pub fn new(filters: impl IntoIterator<Item = TransactionFilter>) -> Self {
+        Self {
+            filters: filters.into_iter().collect(),
+        }
+    }
+
+    /// The filters to push down to the server when streaming checkpoints for subscribers with
+    /// `filters`: the union of their filters, or `None` if the server must send every transaction,
+    /// because a subscriber has no filter, or one the server cannot evaluate.
+    pub(crate) fn push_down<'a>(
+        filters: impl IntoIterator<Item = Option<&'a CheckpointFilter>>,
+    ) -> Option<Vec<ProtoTransactionFilter>> {
+        let mut pushed = vec![];
+        for filter in filters {
+            for filter in &filter?.filters {
+                let filter = filter.to_proto()?;
+                if !pushed.contains(&filter) {
+                    pushed.push(filter);
+                }
+            }
+        }
+
+        // The server sends every transaction if it is given no filters.
+        (!pushed.is_empty()).then_some(pushed)
+    }
+
+    /// Whether the transaction `tx`, whose objects are in `objects`, matches any of the filters.
+    pub fn matches(&self, tx: &ExecutedTransaction, objects: &ObjectSet) -> bool {
+        self.filters.iter().any(|f| f.matches(tx, objects))
+    }
+
+    /// A copy of `checkpoint` that only contains the transactions that match the filter, and the
+    /// objects those transactions touched. The checkpoint is returned as is if all its
+    /// transactions match.
+    pub fn apply(&self, checkpoint: &Arc<Checkpoint>) -> Arc<Checkpoint> {
+        let matching: Vec<_> = checkpoint
+            .transactions
+            .iter()
+            .filter(|tx| self.matches(tx, &checkpoint.object_set))
+            .collect();
+
+        if matching.len() == checkpoint.transactions.len() {
+            return checkpoint.clone();
+        }
+
+        let mut ids = BTreeSet::new();
+        for tx in &matching {
+            ids.extend(tx.effects.object_changes().into_iter().map(|c| c.id));
+            ids.extend(tx.unchanged_loaded_runtime_objects.iter().map(|k| k.0));
+            if let Ok(inputs) = tx.transaction.input_objects() {
+                ids.extend(inputs.into_iter().map(|i| i.object_id()));
+            }
+        }
+
+        let mut object_set = ObjectSet::default();
+        for object in checkpoint.object_set.iter() {
+            if ids.contains(&object.id()) {
+                object_set.insert(object.clone());
+            }
+        }
+
+        Arc::new(Checkpoint {
+            summary: checkpoint.summary.clone(),
+            contents: checkpoint.contents.clone(),
+            transactions: matching.into_iter().cloned().collect(),
+            object_set,
+        })
+    }
+}
+
+/// The objects that `tx` read or wrote, that are available in `objects`.
+fn touched_objects<'a>(
+    tx: &ExecutedTransaction,
+    objects: &'a ObjectSet,
+) -> impl Iterator<Item = &'a Object> + 'a {
+    tx.input_objects(objects).chain(tx.output_objects(objects))
+}
+
+fn type_matches(filter: &StructTag, tag: &StructTag) -> bool {
+    filter.address == tag.address
+        && filter.module == tag.module
+        && filter.name == tag.name
+        && (filter.type_params.is_empty() || filter.type_params == tag.type_params)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::types::{
+        Identifier, event::Event, test_checkpoint_data_builder::TestCheckpointBuilder,
+    };
+
+    use super::*;
+
+    fn checkpoint() -> Arc<Checkpoint> {
+        let package = ObjectID::from_single_byte(0xab);
+        let event = Event {
+            package_id: package,
+            transaction_module: Identifier::new("pool").unwrap(),
+            sender: TestCheckpointBuilder::derive_address(1),
+            type_: StructTag::from_str("0xab::pool::Swap<0x2::sui::SUI>").unwrap(),
+            contents: vec![],
+        };
+
+        Arc::new(
+            TestCheckpointBuilder::new(1)
+                .start_transaction(0)
+                .create_owned_object(0)
+                .finish_transaction()
+                .start_transaction(1)
+                .add_move_call(package, "pool", "swap")
+                .with_events(vec![event])
+                .finish_transaction()
+                .start_transaction(2)
+                .transfer_object(0, 3)
+                .finish_transaction()
+                .build_checkpoint(),
+        )
+    }
+
+    fn senders(checkpoint: &Checkpoint) -> Vec<SuiAddress> {
+        checkpoint
+            .transactions
+            .iter()
+            .map(|tx| tx.transaction.sender())
+            .collect()
+    }
+
+    #[test]
+    fn test_filter_by_sender() {
+        let checkpoint = checkpoint();
+        let sender = TestCheckpointBuilder::derive_address(1);
+        let filtered =
+            CheckpointFilter::new([TransactionFilter::Sender(sender)]).apply(&checkpoint);
+
+        assert_eq!(senders(&filtered), vec![sender]);
+        assert_eq!(filtered.summary, checkpoint.summary);
+    }
+
+    #[test]
+    fn test_filter_keeps_sequence_numbers() {
+        let checkpoint = checkpoint();
+        let sender = TestCheckpointBuilder::derive_address(2);
+        let filtered =
+            CheckpointFilter::new([TransactionFilter::Sender(sender)]).apply(&checkpoint);
+
+        let expect: Vec<_> = checkpoint
+            .transactions_with_sequence_numbers()
+            .unwrap()
+            .filter(|(_, tx)| tx.transaction.sender() == sender)
+            .map(|(seq, _)| seq)
+            .collect();
+
+        let actual: Vec<_> = filtered
+            .transactions_with_sequence_numbers()
+            .unwrap()
+            .map(|(seq, _)| seq)
+            .collect();
+
+        // The transaction keeps the sequence number it had in the full checkpoint, which is not
+        // the one its position in the filtered checkpoint would give it.
+        assert_eq!(actual, expect);
+        assert_eq!(
+            actual,
+            vec![checkpoint.summary.network_total_transactions - 1]
+        );
+        assert_eq!(
+            filtered.first_tx_sequence_number(),
+            checkpoint.first_tx_sequence_number()
+        );
+    }
+
+    #[test]
+    fn test_filter_by_affected_address() {
+        let checkpoint = checkpoint();
+        let recipient = TestCheckpointBuilder::derive_address(3);
+        let filtered = CheckpointFilter::new([TransactionFilter::AffectedAddress(recipient)])
+            .apply(&checkpoint);
+
+        assert_eq!(
+            senders(&filtered),
+            vec![TestCheckpointBuilder::derive_address(2)]
+        );
+
+        // The objects the transaction touched are kept.
+        let object = TestCheckpointBuilder::derive_object_id(0);
+        assert!(filtered.object_set.iter().any(|o| o.id() == object));
+    }
+
+    #[test]
+    fn test_filter_by_move_call() {
+        let checkpoint = checkpoint();
+        let package = ObjectID::from_single_byte(0xab);
+        let call = |module: Option<&str>, function: Option<&str>| {
+            CheckpointFilter::new([TransactionFilter::MoveCall {
+                package,
+                module: module.map(str::to_owned),
+                function: function.map(str::to_owned),
+            }])
+            .apply(&checkpoint)
+            .transactions
+            .len()
+        };
+
+        assert_eq!(call(None, None), 1);
+        assert_eq!(call(Some("pool"), None), 1);
+        assert_eq!(call(Some("pool"), Some("swap")), 1);
+        assert_eq!(call(Some("pool"), Some("add_liquidity")), 0);
+        assert_eq!(call(Some("router"), None), 0);
+    }
+
+    #[test]
+    fn test_filter_by_event_type() {
+        let checkpoint = checkpoint();
+        let events = |type_: &str| {
+            CheckpointFilter::new([TransactionFilter::EventType(
+                StructTag::from_str(type_).unwrap(),
+            )])
+            .apply(&checkpoint)
+            .transactions
+            .len()
+        };
+
+        assert_eq!(events("0xab::pool::Swap"), 1);
+        assert_eq!(events("0xab::pool::Swap<0x2::sui::SUI>"), 1);
+        assert_eq!(events("0xab::pool::Swap<0x2::coin::Coin>"), 0);
+        assert_eq!(events("0xab::pool::Deposit"), 0);
+    }
+
+    #[test]
+    fn test_push_down() {
+        let sender = TransactionFilter::Sender(TestCheckpointBuilder::derive_address(1));
+        let call = TransactionFilter::MoveCall {
+            package: ObjectID::from_single_byte(0xab),
+            module: Some("pool".to_owned()),
+            function: None,
+        };
+        let a = CheckpointFilter::new([sender.clone(), call.clone()]);
+        let b = CheckpointFilter::new([sender.clone()]);
+
+        // The union of the filters, without duplicates.
+        let pushed = CheckpointFilter::push_down([Some(&a), Some(&b)]).unwrap();
+        assert_eq!(
+            pushed,
+            vec![sender.to_proto().unwrap(), call.to_proto().unwrap()]
+        );
+        let Some(Filter::MoveCall(proto)) = &pushed[1].filter else {
+            panic!("expected a move call filter");
+        };
+        assert_eq!(
+            proto.package,
+            Some(ObjectID::from_single_byte(0xab).to_string())
+        );
+        assert_eq!(proto.module.as_deref(), Some("pool"));
+        assert_eq!(proto.function, None);
+
+        // Nothing is pushed down if any subscriber needs every transaction, or if the server
+        // cannot evaluate a filter.
+        assert_eq!(CheckpointFilter::push_down([Some(&a), None]), None);
+        let object_type = CheckpointFilter::new([TransactionFilter::ObjectType(
+            StructTag::from_str("0x2::coin::Coin").unwrap(),
+        )]);
+        assert_eq!(
+            CheckpointFilter::push_down([Some(&a), Some(&object_type)]),
+            None
+        );
+        let any_module = CheckpointFilter::new([TransactionFilter::MoveCall {
+            package: ObjectID::from_single_byte(0xab),
+            module: None,
+            function: Some("swap".to_owned()),
+        }]);
+        assert_eq!(CheckpointFilter::push_down([Some(&any_module)]), None);
+        assert_eq!(
+            CheckpointFilter::push_down([Some(&CheckpointFilter::new([]))]),
+            None
+        );
+    }
+
+    #[test]
+    fn test_filter_any_and_unchanged() {
+        let checkpoint = checkpoint();
+        let filter = CheckpointFilter::new([
+            TransactionFilter::Sender(TestCheckpointBuilder::derive_address(0)),
+            TransactionFilter::Sender(TestCheckpointBuilder::derive_address(1)),
+            TransactionFilter::Sender(TestCheckpointBuilder::derive_address(2)),
+        ]);
+
+        // Every transaction matches, so the checkpoint is not copied.
+        assert!(Arc::ptr_eq(&filter.apply(&checkpoint), &checkpoint));
+
+        let filtered = CheckpointFilter::new([]).apply(&checkpoint);
+        assert!(filtered.transactions.is_empty());
+        assert!(filtered.object_set.is_empty());
+        assert_eq!(filtered.contents, checkpoint.contents);
+    }
+}
@@ -13,8 +13,9 @@ use serde::{Deserialize, Serialize};
 use tokio::{sync::mpsc, task::JoinHandle};
 use tokio_util::sync::CancellationToken;
 
-use crate::ingestion::broadcaster::broadcaster;
+use crate::ingestion::broadcaster::{Subscriber, broadcaster};
 use crate::ingestion::error::{Error, Result};
+use crate::ingestion::filter::CheckpointFilter;
 use crate::ingestion::ingestion_client::{IngestionClient, IngestionClientArgs};
 use crate::ingestion::streaming_client::{GrpcStreamingClient, StreamingClientArgs};
 use crate::metrics::IngestionMetrics;
@@ -22,13 +23,14 @@ use crate::types::full_checkpoint_content::Checkpoint;
 
 mod broadcaster;
 pub mod error;
+pub mod filter;
 pub mod ingestion_client;
 mod local_client;
 pub mod remote_client;
 mod rpc_client;
 pub mod streaming_client;
 #[cfg(test)]
-mod test_utils;
+pub(crate) mod test_utils;
 
 pub(crate) const MAX_GRPC_MESSAGE_SIZE_BYTES: usize = 128 * 1024 * 1024;
 
@@ -67,7 +69,7 @@ pub struct IngestionService {
     streaming_client: Option<GrpcStreamingClient>,
     commit_hi_tx: mpsc::UnboundedSender<(&'static str, u64)>,
     commit_hi_rx: mpsc::UnboundedReceiver<(&'static str, u64)>,
-    subscribers: Vec<mpsc::Sender<Arc<Checkpoint>>>,
+    subscribers: Vec<Subscriber>,
     metrics: Arc<IngestionMetrics>,
     cancel: CancellationToken,
 }
@@ -138,8 +140,21 @@ impl IngestionService {
         mpsc::Receiver<Arc<Checkpoint>>,
         mpsc::UnboundedSender<(&'static str, u64)>,
     ) {
-        let (sender, receiver) = mpsc::channel(self.config.checkpoint_buffer_size);
-        self.subscribers.push(sender);
+        self.subscribe_with_filter(None)
+    }
+
+    /// Like [Self::subscribe], but if a `filter` is provided, the checkpoints received on the
+    /// channel only contain the transactions that match it (see [filter] for details). Each
+    /// distinct filter is evaluated once per checkpoint, no matter how many subscribers share it.
+    pub fn subscribe_with_filter(
+        &mut self,
+        filter: Option<CheckpointFilter>,
+    ) -> (
+        mpsc::Receiver<Arc<Checkpoint>>,
+        mpsc::UnboundedSender<(&'static str, u64)>,
+    ) {
+        let (tx, receiver) = mpsc::channel(self.config.checkpoint_buffer_size);
+        self.subscribers.push(Subscriber { filter, tx });
         (receiver, self.commit_hi_tx.clone())
     }
 
@@ -6,10 +6,17 @@ use async_trait::async_trait;
 use futures::{Stream, StreamExt};
 use std::pin::Pin;
 use sui_rpc::proto::sui::rpc::v2::{
-    SubscribeCheckpointsRequest, subscription_service_client::SubscriptionServiceClient,
+    Checkpoint as ProtoCheckpoint, SubscribeCheckpointsRequest,
+    subscription_service_client::SubscriptionServiceClient,
 };
 use sui_rpc_api::client::checkpoint_data_field_mask;
-use tonic::{Status, transport::Uri};
+use sui_rpc_api::grpc::alpha::subscription_service_proto::{
+    SubscribeCheckpointsRequest as FilteredSubscribeCheckpointsRequest,
+    TransactionFilter as ProtoTransactionFilter,
+    subscription_service_client::SubscriptionServiceClient as FilteredSubscriptionServiceClient,
+};
+use tonic::{Code, Status, transport::Uri};
+use tracing::warn;
 
 use crate::ingestion::MAX_GRPC_MESSAGE_SIZE_BYTES;
 use crate::ingestion::error::{Error, Result};
@@ -22,6 +29,10 @@ pub type CheckpointStream = Pin<Box<dyn Stream<Item = Result<Checkpoint>> + Send
 #[async_trait]
 pub trait CheckpointStreamingClient {
     async fn connect(&mut self) -> Result<CheckpointStream>;
+
+    /// Asks the server to only send the transactions that match any of `filters`, and the objects
+    /// they touched, on the next connections. Clients whose server cannot filter ignore this.
+    fn push_down_filters(&mut self, _filters: Vec<ProtoTransactionFilter>) {}
 }
 
 #[derive(clap::Args, Clone, Debug, Default)]
@@ -32,19 +43,62 @@ pub struct StreamingClientArgs {
 }
 
 /// gRPC-based implementation of the CheckpointStreamingClient trait.
+///
+/// Filters pushed down to it are sent to the alpha `SubscriptionService`, which filters the
+/// transactions of each checkpoint. If the server does not support it, the client falls back to
+/// streaming every transaction.
 pub struct GrpcStreamingClient {
     uri: Uri,
+    filters: Vec<ProtoTransactionFilter>,
 }
 
 impl GrpcStreamingClient {
     pub fn new(uri: Uri) -> Self {
-        Self { uri }
+        Self {
+            uri,
+            filters: vec![],
+        }
+    }
+
+    async fn connect_filtered(&self) -> Result<CheckpointStream> {
+        let mut client = FilteredSubscriptionServiceClient::connect(self.uri.clone())
+            .await
+            .map_err(|err| Error::RpcClientError(Status::from_error(err.into())))?
+            .max_decoding_message_size(MAX_GRPC_MESSAGE_SIZE_BYTES);
+
+        let mut request = FilteredSubscribeCheckpointsRequest::default();
+        request.read_mask = Some(checkpoint_data_field_mask());
+        request.filters = self.filters.clone();
+
+        let stream = client
+            .subscribe_checkpoints(request)
+            .await
+            .map_err(Error::RpcClientError)?
+            .into_inner();
+
+        Ok(Box::pin(stream.map(|result| match result {
+            Ok(response) => to_checkpoint(response.checkpoint),
+            Err(e) => Err(Error::RpcClientError(e)),
+        })))
     }
 }
 
 #[async_trait]
 impl CheckpointStreamingClient for GrpcStreamingClient {
     async fn connect(&mut self) -> Result<CheckpointStream> {
+        if !self.filters.is_empty() {
+            match self.connect_filtered().await {
+                Err(Error::RpcClientError(status)) if status.code() == Code::Unimplemented => {
+                    warn!(
+                        "Server does not support filtered checkpoint subscriptions, streaming all \
+                         transactions instead"
+                    );
+                    self.filters.clear();
+                }
+                result => return result,
+            }
+        }
+
         let mut client = SubscriptionServiceClient::connect(self.uri.clone())
             .await
             .map_err(|err| Error::RpcClientError(Status::from_error(err.into())))?
@@ -60,19 +114,25 @@ impl CheckpointStreamingClient for GrpcStreamingClient {
             .into_inner();
 
         let converted_stream = stream.map(|result| match result {
-            Ok(response) => response
-                .checkpoint
-                .context("Checkpoint data missing in response")
-                .and_then(|checkpoint| {
-                    sui_types::full_checkpoint_content::Checkpoint::try_from(&checkpoint)
-                        .context("Failed to parse checkpoint")
-                })
-                .map_err(Error::StreamingError),
+            Ok(response) => to_checkpoint(response.checkpoint),
             Err(e) => Err(Error::RpcClientError(e)),
         });
 
         Ok(Box::pin(converted_stream))
     }
+
+    fn push_down_filters(&mut self, filters: Vec<ProtoTransactionFilter>) {
+        self.filters = filters;
+    }
+}
+
+fn to_checkpoint(checkpoint: Option<ProtoCheckpoint>) -> Result<Checkpoint> {
+    checkpoint
+        .context("Checkpoint data missing in response")
+        .and_then(|checkpoint| {
+            Checkpoint::try_from(&checkpoint).context("Failed to parse checkpoint")
+        })
+        .map_err(Error::StreamingError)
 }
 
 #[cfg(test)]
@@ -108,6 +168,8 @@ pub mod test_utils {
     pub struct MockStreamingClient {
         checkpoints: Arc<Mutex<Vec<Result<u64>>>>,
         connection_failures_remaining: usize,
+        /// The filters pushed down to the client, if any.
+        pub pushed_filters: Arc<Mutex<Option<Vec<ProtoTransactionFilter>>>>,
     }
 
     impl MockStreamingClient {
@@ -118,6 +180,7 @@ pub mod test_utils {
             Self {
                 checkpoints: Arc::new(Mutex::new(checkpoint_range.into_iter().map(Ok).collect())),
                 connection_failures_remaining: 0,
+                pushed_filters: Arc::new(Mutex::new(None)),
             }
         }
 
@@ -168,5 +231,9 @@ pub mod test_utils {
 
             Ok(Box::pin(stream))
         }
+
+        fn push_down_filters(&mut self, filters: Vec<ProtoTransactionFilter>) {
+            *self.pushed_filters.lock().unwrap() = Some(filters);
+        }
     }
 }
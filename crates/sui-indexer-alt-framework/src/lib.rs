@@ -8,7 +8,7 @@ use futures::future;
 use ingestion::{ClientArgs, IngestionConfig, IngestionService, ingestion_client::IngestionClient};
 use metrics::IndexerMetrics;
 use pipeline::{
-    Processor,
+    DeadLetterPolicy, Processor,
     concurrent::{self, ConcurrentConfig},
     sequential::{self, Handler, SequentialConfig},
 };
@@ -18,7 +18,7 @@ use sui_indexer_alt_framework_store_traits::{
 };
 use tokio::task::JoinHandle;
 use tokio_util::sync::CancellationToken;
-use tracing::info;
+use tracing::{info, warn};
 
 pub use anyhow::Result;
 pub use sui_field_count::FieldCount;
@@ -62,6 +62,18 @@ pub struct IndexerArgs {
     #[arg(long, action = clap::ArgAction::Append)]
     pub pipeline: Vec<String>,
 
+    /// What pipelines do with a checkpoint that their processor failed to process with a
+    /// permanent error: `halt` stops the indexer, `skip` records the checkpoint in the store's
+    /// dead-letter table and moves on.
+    #[arg(long, value_enum, default_value_t = DeadLetterPolicy::Halt)]
+    pub dead_letter_policy: DeadLetterPolicy,
+
+    /// Instead of indexing new checkpoints, re-process the checkpoints that were dead-lettered
+    /// for each concurrent pipeline, and remove them from the dead-letter table once their rows
+    /// have been committed.
+    #[arg(long)]
+    pub redrive_dead_letters: bool,
+
     /// Additional configurations for running a tasked indexer.
     #[clap(flatten)]
     pub task: TaskArgs,
@@ -132,6 +144,13 @@ pub struct Indexer<S: Store> {
     /// a warning when the indexer is run.
     enabled_pipelines: Option<BTreeSet<String>>,
 
+    /// What pipelines do with checkpoints that fail with a permanent error.
+    dead_letter_policy: DeadLetterPolicy,
+
+    /// Whether pipelines re-drive their dead-lettered checkpoints instead of indexing new
+    /// checkpoints. Only concurrent pipelines can be re-driven.
+    redrive_dead_letters: bool,
+
     /// Pipelines that have already been registered with the indexer. Used to make sure a pipeline
     /// with the same name isn't added twice.
     added_pipelines: BTreeSet<&'static str>,
@@ -204,9 +223,18 @@ impl<S: Store> Indexer<S> {
             first_checkpoint,
             last_checkpoint,
             pipeline,
+            dead_letter_policy,
+            redrive_dead_letters,
             task,
         } = indexer_args;
 
+        ensure!(
+            S::SUPPORTS_DEAD_LETTERS
+                || (dead_letter_policy == DeadLetterPolicy::Halt && !redrive_dead_letters),
+            "The store does not support dead letters, so pipelines can only halt on permanent \
+            errors, and there are no dead letters to re-drive",
+        );
+
         let metrics = IndexerMetrics::new(metrics_prefix, registry);
 
         let ingestion_service = IngestionService::new(
@@ -228,6 +256,8 @@ impl<S: Store> Indexer<S> {
             } else {
                 Some(pipeline.into_iter().collect())
             },
+            dead_letter_policy,
+            redrive_dead_letters,
             added_pipelines: BTreeSet::new(),
             cancel,
             first_ingestion_checkpoint: u64::MAX,
@@ -291,13 +321,30 @@ impl<S: Store> Indexer<S> {
             return Ok(());
         };
 
+        if self.redrive_dead_letters {
+            self.handles.push(concurrent::redrive::<H>(
+                handler,
+                self.store.clone(),
+                self.ingestion_client().clone(),
+                self.metrics.clone(),
+                self.cancel.clone(),
+            ));
+
+            return Ok(());
+        }
+
+        let (checkpoint_rx, _) = self
+            .ingestion_service
+            .subscribe_with_filter(handler.filter());
+
         self.handles.push(concurrent::pipeline::<H>(
             handler,
             next_checkpoint,
             config,
             self.store.clone(),
             self.task.clone(),
-            self.ingestion_service.subscribe().0,
+            self.dead_letter_policy,
+            checkpoint_rx,
             self.metrics.clone(),
             self.cancel.clone(),
         ));
@@ -319,6 +366,14 @@ impl<S: Store> Indexer<S> {
             );
         }
 
+        if self.redrive_dead_letters {
+            info!("Re-driving dead-lettered checkpoints");
+            return Ok(tokio::spawn(async move {
+                future::join_all(self.handles).await;
+                info!("Dead-lettered checkpoints re-driven");
+            }));
+        }
+
         let last_checkpoint = self.last_checkpoint.unwrap_or(u64::MAX);
 
         info!(self.first_ingestion_checkpoint, last_checkpoint = ?self.last_checkpoint, "Ingestion range");
@@ -414,6 +469,23 @@ impl<T: TransactionalStore> Indexer<T> {
             return Ok(());
         };
 
+        // A dead-lettered checkpoint can't be committed after the checkpoints that followed it
+        // without breaking the ordering guarantees of sequential pipelines.
+        if self.redrive_dead_letters {
+            warn!(
+                pipeline = H::NAME,
+                "Sequential pipelines do not support re-driving dead letters, skipping"
+            );
+            return Ok(());
+        }
+
+        ensure!(
+            self.dead_letter_policy == DeadLetterPolicy::Halt,
+            "Sequential pipeline {:?} does not support skipping dead letters. Skipping a \
+            checkpoint would corrupt the state it accumulates, and it could not be re-driven",
+            H::NAME,
+        );
+
         if self.task.is_some() {
             bail!(
                 "Sequential pipelines do not support pipeline tasks. \
@@ -428,7 +500,9 @@ impl<T: TransactionalStore> Indexer<T> {
                 .map_or(next_checkpoint, |n| n.min(next_checkpoint)),
         );
 
-        let (checkpoint_rx, watermark_tx) = self.ingestion_service.subscribe();
+        let (checkpoint_rx, watermark_tx) = self
+            .ingestion_service
+            .subscribe_with_filter(handler.filter());
 
         self.handles.push(sequential::pipeline::<H>(
             handler,
@@ -2094,4 +2168,54 @@ mod tests {
             500
         );
     }
+
+    /// Sequential pipelines can't skip checkpoints, even though concurrent pipelines can.
+    #[tokio::test]
+    async fn test_sequential_pipeline_rejects_skipping_dead_letters() {
+        let cancel = CancellationToken::new();
+        let registry = Registry::new();
+
+        test_pipeline!(A, "concurrent_a");
+        test_pipeline!(B, "sequential_b");
+
+        let indexer_args = IndexerArgs {
+            dead_letter_policy: DeadLetterPolicy::Skip,
+            ..Default::default()
+        };
+        let temp_dir = tempfile::tempdir().unwrap();
+        let client_args = ClientArgs {
+            ingestion: IngestionClientArgs {
+                local_ingestion_path: Some(temp_dir.path().to_owned()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let mut indexer = Indexer::new(
+            MockStore::default(),
+            indexer_args,
+            client_args,
+            IngestionConfig::default(),
+            None,
+            &registry,
+            cancel,
+        )
+        .await
+        .unwrap();
+
+        indexer
+            .concurrent_pipeline::<A>(A, ConcurrentConfig::default())
+            .await
+            .unwrap();
+
+        let err = indexer
+            .sequential_pipeline::<B>(B, SequentialConfig::default())
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("does not support skipping dead letters"),
+            "{err}"
+        );
+    }
 }
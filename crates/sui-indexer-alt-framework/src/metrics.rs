@@ -73,6 +73,8 @@ pub struct IndexerMetrics {
     pub total_handler_checkpoints_received: IntCounterVec,
     pub total_handler_checkpoints_processed: IntCounterVec,
     pub total_handler_rows_created: IntCounterVec,
+    pub total_handler_checkpoints_dead_lettered: IntCounterVec,
+    pub total_handler_checkpoints_redriven: IntCounterVec,
 
     pub latest_processed_checkpoint: IntGaugeVec,
     pub latest_processed_checkpoint_timestamp_lag_ms: IntGaugeVec,
@@ -311,6 +313,20 @@ impl IndexerMetrics {
                 registry,
             )
             .unwrap(),
+            total_handler_checkpoints_dead_lettered: register_int_counter_vec_with_registry!(
+                name("total_handler_checkpoints_dead_lettered"),
+                "Total number of checkpoints skipped by this handler after a permanent error",
+                &["pipeline"],
+                registry,
+            )
+            .unwrap(),
+            total_handler_checkpoints_redriven: register_int_counter_vec_with_registry!(
+                name("total_handler_checkpoints_redriven"),
+                "Total number of dead-lettered checkpoints successfully re-driven by this handler",
+                &["pipeline"],
+                registry,
+            )
+            .unwrap(),
             latest_processed_checkpoint: register_int_gauge_vec_with_registry!(
                 name("latest_processed_checkpoint"),
                 "Latest checkpoint sequence number processed by this handler",
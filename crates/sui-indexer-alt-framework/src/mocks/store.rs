@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use std::{
+    collections::BTreeMap,
     sync::{
         Arc, Mutex,
         atomic::{AtomicUsize, Ordering},
@@ -17,7 +18,8 @@ use scoped_futures::ScopedBoxFuture;
 use tokio::time::Duration;
 
 use crate::store::{
-    CommitterWatermark, Connection, PrunerWatermark, ReaderWatermark, Store, TransactionalStore,
+    CommitterWatermark, Connection, DeadLetter, PrunerWatermark, ReaderWatermark, Store,
+    TransactionalStore,
 };
 
 #[derive(Default, Clone)]
@@ -77,6 +79,8 @@ pub struct MockStore {
     pub commit_watermark_failures: Arc<Failures>,
     /// Delay in milliseconds for each transaction commit
     pub commit_delay_ms: u64,
+    /// Maps each pipeline's name to its dead-lettered checkpoints.
+    pub dead_letters: Arc<DashMap<String, BTreeMap<u64, DeadLetter>>>,
 }
 
 #[derive(Clone)]
@@ -197,12 +201,53 @@ impl Connection for MockConnection<'_> {
         curr.pruner_hi = pruner_hi;
         Ok(true)
     }
+
+    async fn record_dead_letter(
+        &mut self,
+        pipeline: &str,
+        checkpoint: u64,
+        error: &str,
+    ) -> anyhow::Result<()> {
+        let mut dead_letters = self.0.dead_letters.entry(pipeline.to_string()).or_default();
+        let dead_letter = dead_letters.entry(checkpoint).or_insert(DeadLetter {
+            pipeline: pipeline.to_string(),
+            checkpoint,
+            error: String::new(),
+            attempts: 0,
+        });
+        dead_letter.error = error.to_string();
+        dead_letter.attempts += 1;
+        Ok(())
+    }
+
+    async fn dead_letters(&mut self, pipeline: &str) -> anyhow::Result<Vec<DeadLetter>> {
+        Ok(self
+            .0
+            .dead_letters
+            .get(pipeline)
+            .map(|d| d.values().cloned().collect())
+            .unwrap_or_default())
+    }
+
+    async fn delete_dead_letter(
+        &mut self,
+        pipeline: &str,
+        checkpoint: u64,
+    ) -> anyhow::Result<bool> {
+        Ok(self
+            .0
+            .dead_letters
+            .get_mut(pipeline)
+            .is_some_and(|mut d| d.remove(&checkpoint).is_some()))
+    }
 }
 
 #[async_trait]
 impl Store for MockStore {
     type Connection<'c> = MockConnection<'c>;
 
+    const SUPPORTS_DEAD_LETTERS: bool = true;
+
     async fn connect(&self) -> anyhow::Result<Self::Connection<'_>> {
         // Check for connection failure simulation and increment attempts counter
         let (should_fail, delay_ms) = {
@@ -425,6 +470,14 @@ impl MockStore {
         self
     }
 
+    /// Helper to get the checkpoints dead-lettered for a pipeline, in checkpoint order.
+    pub fn dead_lettered_checkpoints(&self, pipeline: &str) -> Vec<u64> {
+        self.dead_letters
+            .get(pipeline)
+            .map(|d| d.keys().copied().collect())
+            .unwrap_or_default()
+    }
+
     /// Helper to get the current watermark state for testing.
     pub fn watermark(&self, pipeline_task: &str) -> Option<MockWatermark> {
         self.watermarks.get(pipeline_task).map(|w| w.clone())
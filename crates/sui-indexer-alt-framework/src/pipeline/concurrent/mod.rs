@@ -19,19 +19,25 @@ use crate::{
     Task, metrics::IndexerMetrics, store::Store, types::full_checkpoint_content::Checkpoint,
 };
 
-use super::{CommitterConfig, PIPELINE_BUFFER, Processor, WatermarkPart, processor::processor};
+use super::{
+    CommitterConfig, DeadLetterPolicy, PIPELINE_BUFFER, Processor, WatermarkPart,
+    processor::processor,
+};
 
 use self::{
     collector::collector, commit_watermark::commit_watermark, committer::committer,
     main_reader_lo::track_main_reader_lo, pruner::pruner, reader_watermark::reader_watermark,
 };
 
+pub(crate) use self::redrive::redrive;
+
 mod collector;
 mod commit_watermark;
 mod committer;
 mod main_reader_lo;
 mod pruner;
 mod reader_watermark;
+mod redrive;
 
 /// Status returned by `Handler::batch` to indicate whether the batch is ready to be committed.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -215,6 +221,7 @@ pub(crate) fn pipeline<H: Handler + Send + Sync + 'static>(
     config: ConcurrentConfig,
     store: H::Store,
     task: Option<Task>,
+    dead_letter_policy: DeadLetterPolicy,
     checkpoint_rx: mpsc::Receiver<Arc<Checkpoint>>,
     metrics: Arc<IndexerMetrics>,
     cancel: CancellationToken,
@@ -256,6 +263,8 @@ pub(crate) fn pipeline<H: Handler + Send + Sync + 'static>(
         handler.clone(),
         checkpoint_rx,
         processor_tx,
+        store.clone(),
+        dead_letter_policy,
         metrics.clone(),
         cancel.clone(),
     );
@@ -435,6 +444,7 @@ mod tests {
                 config,
                 store.clone(),
                 None,
+                DeadLetterPolicy::Halt,
                 checkpoint_rx,
                 metrics,
                 cancel.clone(),
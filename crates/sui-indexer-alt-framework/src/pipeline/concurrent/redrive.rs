@@ -0,0 +1,291 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::Arc;
+
+use anyhow::{Context, ensure};
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, warn};
+
+use crate::{
+    ingestion::ingestion_client::IngestionClient,
+    metrics::IndexerMetrics,
+    pipeline::PermanentError,
+    store::{Connection, DeadLetter, Store},
+};
+
+use super::Handler;
+
+/// Re-drive the checkpoints that were dead-lettered for the pipeline served by the handler, `H`.
+///
+/// Each dead-lettered checkpoint is fetched again using the ingestion `client`, processed, and
+/// its rows committed, after which its entry is removed from the dead-letter table. Checkpoints
+/// that fail with a permanent error again stay in the table, with their error and attempt count
+/// updated, and checkpoints that fail with any other error are left as they are to be re-driven
+/// again later. Checkpoints below the pipeline's `reader_lo` are out of its retention, so they
+/// are removed from the table without being re-driven.
+///
+/// Watermarks are not touched: dead-lettered checkpoints are already below the pipeline's
+/// committer watermark.
+///
+/// The task stops once every dead-lettered checkpoint has been attempted, or if the `cancel`
+/// token is cancelled.
+pub(crate) fn redrive<H: Handler + 'static>(
+    handler: H,
+    store: H::Store,
+    client: IngestionClient,
+    metrics: Arc<IndexerMetrics>,
+    cancel: CancellationToken,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let dead_letters = match dead_letters::<H>(&store).await {
+            Ok(dead_letters) => dead_letters,
+            Err(e) => {
+                error!(pipeline = H::NAME, "Failed to read dead letters: {e:#}");
+                return;
+            }
+        };
+
+        let reader_lo = match store.connect().await {
+            Ok(mut conn) => conn
+                .reader_watermark(H::NAME)
+                .await
+                .ok()
+                .flatten()
+                .map_or(0, |w| w.reader_lo),
+            Err(_) => 0,
+        };
+
+        info!(
+            pipeline = H::NAME,
+            dead_letters = dead_letters.len(),
+            "Re-driving dead letters"
+        );
+
+        let mut redriven = 0;
+        for DeadLetter { checkpoint, .. } in dead_letters {
+            if cancel.is_cancelled() {
+                info!(pipeline = H::NAME, "Shutdown received, stopping re-drive");
+                return;
+            }
+
+            match redrive_checkpoint(&handler, &store, &client, checkpoint, reader_lo).await {
+                Ok(()) => {
+                    redriven += 1;
+                    metrics
+                        .total_handler_checkpoints_redriven
+                        .with_label_values(&[H::NAME])
+                        .inc();
+                }
+
+                Err(e) if e.is::<PermanentError>() => {
+                    warn!(
+                        pipeline = H::NAME,
+                        checkpoint, "Checkpoint failed again with a permanent error: {e:#}",
+                    );
+
+                    let error = format!("{e:#}");
+                    if let Err(e) = async {
+                        let mut conn = store.connect().await?;
+                        conn.record_dead_letter(H::NAME, checkpoint, &error).await
+                    }
+                    .await
+                    {
+                        error!(
+                            pipeline = H::NAME,
+                            checkpoint, "Failed to update dead letter: {e:#}",
+                        );
+                    }
+                }
+
+                Err(e) => {
+                    warn!(
+                        pipeline = H::NAME,
+                        checkpoint, "Failed to re-drive checkpoint: {e:#}",
+                    );
+                }
+            }
+        }
+
+        info!(
+            pipeline = H::NAME,
+            redriven, "Finished re-driving dead letters"
+        );
+    })
+}
+
+async fn dead_letters<H: Handler>(store: &H::Store) -> anyhow::Result<Vec<DeadLetter>> {
+    let mut conn = store
+        .connect()
+        .await
+        .context("Failed to establish connection to store")?;
+
+    conn.dead_letters(H::NAME).await
+}
+
+async fn redrive_checkpoint<H: Handler>(
+    handler: &H,
+    store: &H::Store,
+    client: &IngestionClient,
+    checkpoint: u64,
+    reader_lo: u64,
+) -> anyhow::Result<()> {
+    if checkpoint >= reader_lo {
+        let data = client
+            .fetch(checkpoint)
+            .await
+            .with_context(|| format!("Failed to fetch checkpoint {checkpoint}"))?;
+
+        // Apply the same filter as the live pipeline, so that re-driving a checkpoint writes the
+        // same rows as processing it would have.
+        let data = match handler.filter() {
+            Some(filter) => filter.apply(&data),
+            None => data,
+        };
+
+        let mut values = handler.process(&data).await?.into_iter();
+        let mut conn = store.connect().await?;
+        while values.len() > 0 {
+            let remaining = values.len();
+            let mut batch = H::Batch::default();
+            handler.batch(&mut batch, &mut values);
+            ensure!(
+                values.len() < remaining,
+                "Handler did not accept any rows from checkpoint {checkpoint}"
+            );
+
+            handler.commit(&batch, &mut conn).await?;
+        }
+    } else {
+        info!(
+            pipeline = H::NAME,
+            checkpoint, reader_lo, "Dead-lettered checkpoint has been pruned, dropping it",
+        );
+    }
+
+    let mut conn = store.connect().await?;
+    conn.delete_dead_letter(H::NAME, checkpoint).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+    use sui_types::full_checkpoint_content::Checkpoint;
+
+    use crate::{
+        ingestion::test_utils::test_checkpoint_data,
+        metrics::tests::test_ingestion_metrics,
+        mocks::store::{MockStore, MockWatermark},
+        pipeline::{Processor, concurrent::BatchStatus},
+    };
+
+    use super::*;
+
+    /// Fails with a permanent error on checkpoint 2, and writes every other checkpoint's sequence
+    /// number.
+    struct PoisonHandler;
+
+    #[async_trait]
+    impl Processor for PoisonHandler {
+        const NAME: &'static str = "poison";
+        type Value = u64;
+
+        async fn process(&self, checkpoint: &Arc<Checkpoint>) -> anyhow::Result<Vec<u64>> {
+            let cp = checkpoint.summary.sequence_number;
+            if cp == 2 {
+                return Err(PermanentError::new(anyhow::anyhow!("Poison checkpoint {cp}")).into());
+            }
+
+            Ok(vec![cp])
+        }
+    }
+
+    #[async_trait]
+    impl Handler for PoisonHandler {
+        type Store = MockStore;
+        type Batch = Vec<u64>;
+
+        fn batch(
+            &self,
+            batch: &mut Self::Batch,
+            values: &mut std::vec::IntoIter<u64>,
+        ) -> BatchStatus {
+            batch.extend(values);
+            BatchStatus::Pending
+        }
+
+        async fn commit<'a>(
+            &self,
+            batch: &Self::Batch,
+            conn: &mut <Self::Store as Store>::Connection<'a>,
+        ) -> anyhow::Result<usize> {
+            for cp in batch {
+                conn.0.commit_data(Self::NAME, *cp, vec![*cp]).await?;
+            }
+            Ok(batch.len())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_redrive_dead_letters() {
+        let dir = tempfile::tempdir().unwrap();
+        for cp in 1..=3 {
+            let path = dir.path().join(format!("{cp}.chk"));
+            tokio::fs::write(path, test_checkpoint_data(cp))
+                .await
+                .unwrap();
+        }
+
+        let store = MockStore::default();
+        store.watermarks.insert(
+            PoisonHandler::NAME.to_string(),
+            MockWatermark {
+                checkpoint_hi_inclusive: 10,
+                reader_lo: 1,
+                ..Default::default()
+            },
+        );
+
+        let mut conn = store.connect().await.unwrap();
+        for cp in 0..=3 {
+            conn.record_dead_letter(PoisonHandler::NAME, cp, "Poisoned")
+                .await
+                .unwrap();
+        }
+
+        let client = IngestionClient::new_local(dir.path().to_owned(), test_ingestion_metrics());
+        let metrics = IndexerMetrics::new(None, &Default::default());
+        redrive(
+            PoisonHandler,
+            store.clone(),
+            client,
+            metrics.clone(),
+            CancellationToken::new(),
+        )
+        .await
+        .unwrap();
+
+        // Checkpoints 1 and 3 are written and removed from the dead-letter table, checkpoint 0
+        // is below `reader_lo`, so it is removed without being written.
+        let data = store.data.get(PoisonHandler::NAME).unwrap();
+        let mut written: Vec<_> = data.iter().map(|e| *e.key()).collect();
+        written.sort();
+        assert_eq!(written, vec![1, 3]);
+        assert_eq!(
+            metrics
+                .total_handler_checkpoints_redriven
+                .with_label_values(&[PoisonHandler::NAME])
+                .get(),
+            2
+        );
+
+        // Checkpoint 2 fails again, and stays in the table with its attempts counted.
+        let dead_letters = conn.dead_letters(PoisonHandler::NAME).await.unwrap();
+        assert_eq!(dead_letters.len(), 1);
+        assert_eq!(dead_letters[0].checkpoint, 2);
+        assert_eq!(dead_letters[0].attempts, 2);
+        assert!(dead_letters[0].error.contains("Poison checkpoint 2"));
+    }
+}
@@ -3,7 +3,7 @@
 
 use std::time::Duration;
 
-pub use processor::Processor;
+pub use processor::{PermanentError, Processor};
 use serde::{Deserialize, Serialize};
 
 use crate::store::CommitterWatermark;
@@ -35,6 +35,20 @@ pub struct CommitterConfig {
     pub watermark_interval_ms: u64,
 }
 
+/// What a pipeline does with a checkpoint that its processor failed to process with a
+/// [PermanentError].
+#[derive(clap::ValueEnum, Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DeadLetterPolicy {
+    /// Stop the indexer, so that operators can intervene.
+    #[default]
+    Halt,
+
+    /// Record the checkpoint in the store's dead-letter table and carry on as if it produced no
+    /// rows. Dead-lettered checkpoints can be re-driven later with `--redrive-dead-letters`.
+    Skip,
+}
+
 /// Processed values associated with a single checkpoint. This is an internal type used to
 /// communicate between the processor and the collector parts of the pipeline.
 struct IndexedCheckpoint<P: Processor> {
@@ -9,15 +9,17 @@ use sui_types::full_checkpoint_content::Checkpoint;
 use tokio::{sync::mpsc, task::JoinHandle};
 use tokio_stream::wrappers::ReceiverStream;
 use tokio_util::sync::CancellationToken;
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 
 use crate::{
+    ingestion::filter::CheckpointFilter,
     metrics::{CheckpointLagMetricReporter, IndexerMetrics},
     pipeline::Break,
+    store::{Connection, Store},
     task::TrySpawnStreamExt,
 };
 
-use super::IndexedCheckpoint;
+use super::{DeadLetterPolicy, IndexedCheckpoint};
 use async_trait::async_trait;
 
 /// If the processor needs to retry processing a checkpoint, it will wait this long initially.
@@ -42,17 +44,46 @@ pub trait Processor: Send + Sync + 'static {
 
     /// The processing logic for turning a checkpoint into rows of the table.
     ///
-    /// All errors returned from this method are treated as transient and will be retried
-    /// indefinitely with exponential backoff.
+    /// Errors returned from this method are treated as transient and will be retried
+    /// indefinitely with exponential backoff, unless they are a [PermanentError].
     ///
     /// If you encounter a permanent error that will never succeed on retry (e.g., invalid data
-    /// format, unsupported protocol version), you should panic! This stops the indexer and alerts
-    /// operators that manual intervention is required. Do not return permanent errors as they will
-    /// cause infinite retries and block the pipeline.
+    /// format, unsupported protocol version), return it wrapped in a [PermanentError]. What
+    /// happens next depends on the indexer's [DeadLetterPolicy]: by default the indexer is
+    /// stopped so that operators can intervene, but it can also be configured to record the
+    /// checkpoint in the store's dead-letter table and carry on, to re-drive it later.
     ///
     /// For transient errors (e.g., network issues, rate limiting), simply return the error and
     /// let the framework retry automatically.
     async fn process(&self, checkpoint: &Arc<Checkpoint>) -> anyhow::Result<Vec<Self::Value>>;
+
+    /// The transactions this pipeline is interested in. If a filter is returned, the checkpoints
+    /// passed to [Self::process] only contain the transactions that match it, and the objects
+    /// those transactions touched. The ingestion service evaluates the filter once per
+    /// checkpoint, and shares the result between all pipelines with the same filter.
+    ///
+    /// Pipelines still receive every checkpoint, even if none of its transactions match. The
+    /// checkpoint's summary and contents are not filtered, so pipelines that use a filter must
+    /// number transactions using [Checkpoint::transactions_with_sequence_numbers], and not by
+    /// their position in the checkpoint.
+    ///
+    /// By default, pipelines receive every transaction.
+    fn filter(&self) -> Option<CheckpointFilter> {
+        None
+    }
+}
+
+/// An error from [Processor::process] that will not succeed on retry. Wrap an error in this type
+/// (e.g. `Err(PermanentError::new(e))?`) to stop the framework from retrying the checkpoint and
+/// handle it according to the indexer's [DeadLetterPolicy] instead.
+#[derive(thiserror::Error, Debug)]
+#[error(transparent)]
+pub struct PermanentError(#[from] anyhow::Error);
+
+impl PermanentError {
+    pub fn new(error: impl Into<anyhow::Error>) -> Self {
+        Self(error.into())
+    }
 }
 
 /// The processor task is responsible for taking checkpoint data and breaking it down into rows
@@ -62,11 +93,17 @@ pub trait Processor: Send + Sync + 'static {
 /// Each worker processes a checkpoint into rows and sends them on to the committer using the `tx`
 /// channel.
 ///
+/// Checkpoints that fail with a [PermanentError] either stop the pipeline (and the indexer), or
+/// are recorded in the `store`'s dead-letter table and passed on without any rows, depending on
+/// the `dead_letter_policy`.
+///
 /// The task will shutdown if the `cancel` token is cancelled.
-pub(super) fn processor<P: Processor>(
+pub(super) fn processor<P: Processor, S: Store>(
     processor: Arc<P>,
     rx: mpsc::Receiver<Arc<Checkpoint>>,
     tx: mpsc::Sender<IndexedCheckpoint<P>>,
+    store: S,
+    dead_letter_policy: DeadLetterPolicy,
     metrics: Arc<IndexerMetrics>,
     cancel: CancellationToken,
 ) -> JoinHandle<()> {
@@ -85,6 +122,7 @@ pub(super) fn processor<P: Processor>(
                 let cancel = cancel.clone();
                 let checkpoint_lag_reporter = checkpoint_lag_reporter.clone();
                 let processor = processor.clone();
+                let store = store.clone();
 
                 async move {
                     if cancel.is_cancelled() {
@@ -101,27 +139,57 @@ pub(super) fn processor<P: Processor>(
                         .with_label_values(&[P::NAME])
                         .start_timer();
 
-                    // Retry processing with exponential backoff - treat all errors as transient
-                    let backoff = ExponentialBackoff {
-                        initial_interval: INITIAL_RETRY_INTERVAL,
-                        current_interval: INITIAL_RETRY_INTERVAL,
-                        max_interval: MAX_RETRY_INTERVAL,
-                        max_elapsed_time: None, // Retry indefinitely
-                        ..Default::default()
-                    };
+                    let epoch = checkpoint.summary.epoch;
+                    let cp_sequence_number = checkpoint.summary.sequence_number;
 
-                    let values = backoff::future::retry(backoff, || async {
-                        processor
-                            .process(&checkpoint)
-                            .await
-                            .map_err(backoff::Error::transient)
+                    // Retry processing with exponential backoff - treat all errors except
+                    // permanent errors as transient
+                    let values = match backoff::future::retry(retry_backoff(), || async {
+                        processor.process(&checkpoint).await.map_err(|e| {
+                            if e.is::<PermanentError>() {
+                                backoff::Error::permanent(e)
+                            } else {
+                                backoff::Error::transient(e)
+                            }
+                        })
                     })
-                    .await?;
+                    .await
+                    {
+                        Ok(values) => values,
+
+                        // The indexer rejects the skip policy for stores without dead letters, but
+                        // the checkpoint must not be dropped without a record either way.
+                        Err(e)
+                            if dead_letter_policy == DeadLetterPolicy::Halt
+                                || !S::SUPPORTS_DEAD_LETTERS =>
+                        {
+                            return Err(Break::Err(e.context(format!(
+                                "Permanent error processing checkpoint {cp_sequence_number}"
+                            ))));
+                        }
+
+                        Err(e) => {
+                            warn!(
+                                pipeline = P::NAME,
+                                checkpoint = cp_sequence_number,
+                                "Dead-lettering checkpoint after permanent error: {e:#}",
+                            );
+
+                            record_dead_letter::<P, S>(&store, cp_sequence_number, &e).await;
+
+                            metrics
+                                .total_handler_checkpoints_dead_lettered
+                                .with_label_values(&[P::NAME])
+                                .inc();
+
+                            // The checkpoint is passed on without any rows, so that the
+                            // pipeline's watermark can move past it.
+                            vec![]
+                        }
+                    };
 
                     let elapsed = guard.stop_and_record();
 
-                    let epoch = checkpoint.summary.epoch;
-                    let cp_sequence_number = checkpoint.summary.sequence_number;
                     let tx_hi = checkpoint.summary.network_total_transactions;
                     let timestamp_ms = checkpoint.summary.timestamp_ms;
 
@@ -175,9 +243,41 @@ pub(super) fn processor<P: Processor>(
     })
 }
 
+/// Record `checkpoint` as dead-lettered for the pipeline, retrying until the store accepts it.
+async fn record_dead_letter<P: Processor, S: Store>(
+    store: &S,
+    checkpoint: u64,
+    error: &anyhow::Error,
+) {
+    let error = format!("{error:#}");
+    let _ = backoff::future::retry(retry_backoff(), || async {
+        let mut conn = store.connect().await.map_err(backoff::Error::transient)?;
+        conn.record_dead_letter(P::NAME, checkpoint, &error)
+            .await
+            .map_err(|e| {
+                warn!(
+                    pipeline = P::NAME,
+                    checkpoint, "Failed to record dead letter, retrying: {e:#}",
+                );
+                backoff::Error::transient(e)
+            })
+    })
+    .await;
+}
+
+fn retry_backoff() -> ExponentialBackoff {
+    ExponentialBackoff {
+        initial_interval: INITIAL_RETRY_INTERVAL,
+        current_interval: INITIAL_RETRY_INTERVAL,
+        max_interval: MAX_RETRY_INTERVAL,
+        max_elapsed_time: None, // Retry indefinitely
+        ..Default::default()
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::metrics::IndexerMetrics;
+    use crate::{metrics::IndexerMetrics, mocks::store::MockStore};
     use anyhow::ensure;
     use std::{
         sync::{
@@ -242,7 +342,15 @@ mod tests {
         let cancel = CancellationToken::new();
 
         // Spawn the processor task
-        let handle = super::processor(processor, data_rx, indexed_tx, metrics, cancel.clone());
+        let handle = super::processor(
+            processor,
+            data_rx,
+            indexed_tx,
+            MockStore::default(),
+            DeadLetterPolicy::Halt,
+            metrics,
+            cancel.clone(),
+        );
 
         // Send both checkpoints
         data_tx.send(checkpoint1.clone()).await.unwrap();
@@ -301,7 +409,15 @@ mod tests {
         let cancel = CancellationToken::new();
 
         // Spawn the processor task
-        let handle = super::processor(processor, data_rx, indexed_tx, metrics, cancel.clone());
+        let handle = super::processor(
+            processor,
+            data_rx,
+            indexed_tx,
+            MockStore::default(),
+            DeadLetterPolicy::Halt,
+            metrics,
+            cancel.clone(),
+        );
 
         // Send first checkpoint.
         data_tx.send(checkpoint1.clone()).await.unwrap();
@@ -372,7 +488,15 @@ mod tests {
         let cancel = CancellationToken::new();
 
         // Spawn the processor task
-        let handle = super::processor(processor, data_rx, indexed_tx, metrics, cancel.clone());
+        let handle = super::processor(
+            processor,
+            data_rx,
+            indexed_tx,
+            MockStore::default(),
+            DeadLetterPolicy::Halt,
+            metrics,
+            cancel.clone(),
+        );
 
         // Send and verify first checkpoint (should succeed immediately)
         data_tx.send(checkpoint1.clone()).await.unwrap();
@@ -437,7 +561,15 @@ mod tests {
         let cancel = CancellationToken::new();
 
         // Spawn processor task
-        let handle = super::processor(processor, data_rx, indexed_tx, metrics, cancel.clone());
+        let handle = super::processor(
+            processor,
+            data_rx,
+            indexed_tx,
+            MockStore::default(),
+            DeadLetterPolicy::Halt,
+            metrics,
+            cancel.clone(),
+        );
 
         // Send all checkpoints and measure time
         let start = std::time::Instant::now();
@@ -464,4 +596,99 @@ mod tests {
         // Clean up
         let _ = handle.await;
     }
+
+    struct PoisonPipeline;
+
+    #[async_trait]
+    impl Processor for PoisonPipeline {
+        const NAME: &'static str = "poison";
+        type Value = StoredData;
+        async fn process(&self, checkpoint: &Arc<Checkpoint>) -> anyhow::Result<Vec<Self::Value>> {
+            let cp = checkpoint.summary.sequence_number;
+            if cp == 2 {
+                return Err(PermanentError::new(anyhow::anyhow!("Poison checkpoint {cp}")).into());
+            }
+
+            Ok(vec![StoredData { value: cp }])
+        }
+    }
+
+    #[tokio::test]
+    async fn test_processor_dead_letters_permanent_errors() {
+        let store = MockStore::default();
+        let (data_tx, data_rx) = mpsc::channel(3);
+        let (indexed_tx, mut indexed_rx) = mpsc::channel(3);
+        let metrics = IndexerMetrics::new(None, &Default::default());
+        let cancel = CancellationToken::new();
+
+        let handle = super::processor(
+            Arc::new(PoisonPipeline),
+            data_rx,
+            indexed_tx,
+            store.clone(),
+            DeadLetterPolicy::Skip,
+            metrics.clone(),
+            cancel.clone(),
+        );
+
+        for cp in 1..=3 {
+            let checkpoint = Arc::new(TestCheckpointBuilder::new(cp).build_checkpoint());
+            data_tx.send(checkpoint).await.unwrap();
+        }
+        drop(data_tx);
+
+        let mut received = Vec::new();
+        while let Some(indexed) = indexed_rx.recv().await {
+            received.push(indexed);
+        }
+        received.sort_by_key(|indexed| indexed.watermark.checkpoint_hi_inclusive);
+
+        // The poisoned checkpoint is still passed on, without any rows.
+        let rows: Vec<_> = received
+            .iter()
+            .map(|indexed| indexed.values.len())
+            .collect();
+        assert_eq!(rows, vec![1, 0, 1]);
+        assert_eq!(store.dead_lettered_checkpoints("poison"), vec![2]);
+        assert_eq!(
+            metrics
+                .total_handler_checkpoints_dead_lettered
+                .with_label_values(&["poison"])
+                .get(),
+            1
+        );
+        assert!(!cancel.is_cancelled());
+
+        let _ = handle.await;
+    }
+
+    #[tokio::test]
+    async fn test_processor_halts_on_permanent_errors() {
+        let store = MockStore::default();
+        let (data_tx, data_rx) = mpsc::channel(2);
+        let (indexed_tx, _indexed_rx) = mpsc::channel(2);
+        let metrics = IndexerMetrics::new(None, &Default::default());
+        let cancel = CancellationToken::new();
+
+        let handle = super::processor(
+            Arc::new(PoisonPipeline),
+            data_rx,
+            indexed_tx,
+            store.clone(),
+            DeadLetterPolicy::Halt,
+            metrics,
+            cancel.clone(),
+        );
+
+        let checkpoint = Arc::new(TestCheckpointBuilder::new(2).build_checkpoint());
+        data_tx.send(checkpoint).await.unwrap();
+
+        // The permanent error is not retried, and shuts the indexer down.
+        timeout(Duration::from_secs(1), cancel.cancelled())
+            .await
+            .expect("Processor should cancel on a permanent error");
+        assert!(store.dead_lettered_checkpoints("poison").is_empty());
+
+        let _ = handle.await;
+    }
 }
@@ -7,7 +7,7 @@ use serde::{Deserialize, Serialize};
 use tokio::{sync::mpsc, task::JoinHandle};
 use tokio_util::sync::CancellationToken;
 
-use super::{CommitterConfig, PIPELINE_BUFFER, Processor, processor::processor};
+use super::{CommitterConfig, DeadLetterPolicy, PIPELINE_BUFFER, Processor, processor::processor};
 
 use crate::{
     metrics::IndexerMetrics,
@@ -127,6 +127,10 @@ pub(crate) fn pipeline<H: Handler + Send + Sync + 'static>(
         handler.clone(),
         checkpoint_rx,
         processor_tx,
+        db.clone(),
+        // Skipping a checkpoint would corrupt the state that sequential pipelines accumulate, and
+        // it could not be re-driven later, so permanent errors always halt the pipeline.
+        DeadLetterPolicy::Halt,
         metrics.clone(),
         cancel.clone(),
     );
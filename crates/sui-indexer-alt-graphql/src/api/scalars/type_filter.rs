@@ -73,6 +73,16 @@ impl TypeFilter {
         }
     }
 
+    /// Whether the struct type `tag` is selected by this filter.
+    pub(crate) fn matches(&self, tag: &StructTag) -> bool {
+        self.package() == SuiAddress::from(tag.address)
+            && self.module().is_none_or(|m| m == tag.module.as_str())
+            && self.type_name().is_none_or(|n| n == tag.name.as_str())
+            && self
+                .type_params()
+                .is_none_or(|ps| ps == tag.type_params.as_slice())
+    }
+
     /// Try to create a filter whose results are the intersection of `self`'s results and `other`'s
     /// results. May return `None` if the filters are incompatible (would result in no matches)
     pub(crate) fn intersect(self, other: Self) -> Option<Self> {
@@ -0,0 +1,505 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{future::Future, sync::Arc, time::Duration};
+
+use anyhow::{Context as _, anyhow};
+use async_graphql::{
+    Context, OutputType, Subscription,
+    connection::{Connection, CursorType},
+};
+use async_stream::{stream, try_stream};
+use futures::{Stream, future::try_join_all};
+use tokio::{
+    sync::{Semaphore, SemaphorePermit},
+    time::{Instant, timeout_at},
+};
+
+use crate::{
+    error::{RpcError, request_timeout, resource_exhausted},
+    pagination::{Page, PageLimits, PaginationConfig},
+    scope::Scope,
+    task::watermark::{WatermarkUpdates, Watermarks},
+};
+
+use super::{
+    scalars::{owner_kind::OwnerKind, uint53::UInt53},
+    types::{
+        checkpoint::Checkpoint,
+        event::{Event, filter::EventFilter},
+        object::Object,
+        object_change::ObjectChange,
+        object_filter::{ObjectFilter, ObjectFilterValidator as OFValidator},
+        transaction::{
+            Transaction,
+            filter::{TransactionFilter, TransactionFilterValidator as TFValidator},
+        },
+    },
+};
+
+#[derive(Default)]
+pub struct Subscription;
+
+/// Limits on subscriptions, shared by the WebSocket handler and the schema.
+#[derive(Clone)]
+pub(crate) struct SubscriptionLimits {
+    /// Maximum size in bytes of a message received over a subscription's WebSocket.
+    pub(crate) max_message_size: usize,
+
+    /// Maximum number of subscriptions served at the same time.
+    max_subscriptions: usize,
+
+    /// Time allowed to produce the results for a single watermark update.
+    update_timeout: Duration,
+
+    /// One permit for each subscription that can be served at the same time.
+    permits: Arc<Semaphore>,
+}
+
+/// The time by which a subscription must finish producing the results for a watermark update.
+#[derive(Clone, Copy)]
+struct Deadline {
+    at: Instant,
+    timeout: Duration,
+}
+
+/// Subscriptions are served over a WebSocket, and are driven by the service's watermark: every time the watermark advances, a subscription produces the data in the checkpoints that were added, in order, viewed at the new watermark.
+#[Subscription]
+impl Subscription {
+    /// Stream checkpoints as they become available, starting from the first checkpoint after the service's latest checkpoint at the time of subscribing.
+    async fn checkpoints<'ctx>(
+        &self,
+        ctx: &'ctx Context<'_>,
+    ) -> impl Stream<Item = Result<Checkpoint, RpcError>> + 'ctx {
+        try_stream! {
+            for await update in watermark_updates(ctx) {
+                let (scope, after) = update?;
+                let Some(hi) = scope.checkpoint_viewed_at() else {
+                    continue;
+                };
+
+                for sequence_number in after + 1..=hi {
+                    if let Some(checkpoint) =
+                        Checkpoint::with_sequence_number(scope.clone(), Some(sequence_number))
+                    {
+                        yield checkpoint;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Stream transactions as they become available, optionally filtered by transaction filters, starting from the first checkpoint after the service's latest checkpoint at the time of subscribing.
+    async fn transactions<'ctx>(
+        &self,
+        ctx: &'ctx Context<'_>,
+        #[graphql(validator(custom = "TFValidator"))] filter: Option<TransactionFilter>,
+    ) -> impl Stream<Item = Result<Transaction, RpcError>> + 'ctx {
+        try_stream! {
+            let pagination: &PaginationConfig = ctx.data()?;
+            let subscriptions: &SubscriptionLimits = ctx.data()?;
+            let limits = pagination.limits("Subscription", "transactions");
+            let filter = filter.unwrap_or_default();
+
+            for await update in watermark_updates(ctx) {
+                let (scope, after) = update?;
+                let Some(filter) = filter.clone().intersect(TransactionFilter {
+                    after_checkpoint: Some(UInt53::from(after)),
+                    ..Default::default()
+                }) else {
+                    continue;
+                };
+
+                let pages = drain(limits, subscriptions.deadline(), |page| {
+                    Transaction::paginate(ctx, scope.clone(), page, filter.clone())
+                });
+
+                for await page in pages {
+                    for transaction in page? {
+                        yield transaction;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Stream events as they become available, optionally filtered by event filters, starting from the first checkpoint after the service's latest checkpoint at the time of subscribing.
+    async fn events<'ctx>(
+        &self,
+        ctx: &'ctx Context<'_>,
+        filter: Option<EventFilter>,
+    ) -> impl Stream<Item = Result<Event, RpcError>> + 'ctx {
+        try_stream! {
+            let pagination: &PaginationConfig = ctx.data()?;
+            let subscriptions: &SubscriptionLimits = ctx.data()?;
+            let limits = pagination.limits("Subscription", "events");
+            let filter = filter.unwrap_or_default();
+
+            for await update in watermark_updates(ctx) {
+                let (scope, after) = update?;
+                let filter = EventFilter {
+                    after_checkpoint: Some(UInt53::from(
+                        filter.after_checkpoint.map_or(after, |a| u64::from(a).max(after)),
+                    )),
+                    ..filter.clone()
+                };
+
+                let pages = drain(limits, subscriptions.deadline(), |page| {
+                    Event::paginate(ctx, scope.clone(), page, filter.clone())
+                });
+
+                for await page in pages {
+                    for event in page? {
+                        yield event;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Stream changes to objects that match the filter (by owner and/or type) as they become available, starting from the first checkpoint after the service's latest checkpoint at the time of subscribing.
+    ///
+    /// A change matches if the object matches the filter after the transaction, or before it, if the transaction deleted or wrapped the object.
+    async fn object_changes<'ctx>(
+        &self,
+        ctx: &'ctx Context<'_>,
+        #[graphql(validator(custom = "OFValidator::default()"))] filter: ObjectFilter,
+    ) -> impl Stream<Item = Result<ObjectChange, RpcError>> + 'ctx {
+        try_stream! {
+            let pagination: &PaginationConfig = ctx.data()?;
+            let subscriptions: &SubscriptionLimits = ctx.data()?;
+            let limits = pagination.limits("Subscription", "objectChanges");
+
+            // Changes to objects owned by an address can only come from transactions that affect
+            // that address, so use the address to narrow down the transactions to look at.
+            let affected_address = match filter.owner_kind {
+                None | Some(OwnerKind::Address) => filter.owner,
+                Some(_) => None,
+            };
+
+            for await update in watermark_updates(ctx) {
+                let (scope, after) = update?;
+                let tx_filter = TransactionFilter {
+                    after_checkpoint: Some(UInt53::from(after)),
+                    affected_address,
+                    ..Default::default()
+                };
+
+                let deadline = subscriptions.deadline();
+                let pages = drain(limits, deadline, |page| {
+                    Transaction::paginate(ctx, scope.clone(), page, tx_filter.clone())
+                });
+
+                for await page in pages {
+                    // Fetch the contents of every transaction in the page, and then of every
+                    // object they changed, concurrently, so that the loaders can batch the reads.
+                    let transactions = page?;
+                    let contents = deadline
+                        .run(try_join_all(
+                            transactions.iter().map(|tx| tx.contents.fetch(ctx, tx.digest)),
+                        ))
+                        .await?;
+
+                    let mut changes = vec![];
+                    for contents in &contents {
+                        let Some(contents) = &contents.contents else {
+                            continue;
+                        };
+
+                        for native in contents.effects()?.object_changes() {
+                            let state = match (native.output_version, native.output_digest) {
+                                (Some(version), Some(digest)) => Some((version, digest)),
+                                _ => native.input_version.zip(native.input_digest),
+                            };
+
+                            let Some((version, digest)) = state else {
+                                continue;
+                            };
+
+                            let object =
+                                Object::with_ref(&scope, native.id.into(), version, digest);
+                            changes.push((native, object));
+                        }
+                    }
+
+                    let matches: Vec<bool> = deadline
+                        .run(try_join_all(changes.iter().map(|(_, o)| o.contents(ctx))))
+                        .await?
+                        .into_iter()
+                        .map(|o| o.as_ref().is_some_and(|o| filter.matches(o)))
+                        .collect();
+
+                    for ((native, _), matches) in changes.into_iter().zip(matches) {
+                        if matches {
+                            yield ObjectChange {
+                                scope: scope.clone(),
+                                native,
+                            };
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// A stream that yields every time the service's high watermark advances. Each update is a scope
+/// that views data at the new watermark, paired with the previous high watermark, so that the
+/// checkpoints that were added are the ones strictly after it, up to and including the scope's
+/// checkpoint.
+///
+/// The stream starts from the watermark at the time it was created, and ends when the service
+/// stops updating its watermarks. It holds one of the service's subscription permits for as long
+/// as it lives, and fails immediately if there are none left.
+fn watermark_updates<'ctx>(
+    ctx: &'ctx Context<'_>,
+) -> impl Stream<Item = Result<(Scope, u64), RpcError>> + 'ctx {
+    try_stream! {
+        let subscriptions: &SubscriptionLimits = ctx.data()?;
+        let _permit = subscriptions.permit()?;
+
+        let updates: &WatermarkUpdates = ctx.data()?;
+        for await (watermarks, checkpoint) in new_watermarks(updates.clone()) {
+            yield (Scope::with_watermarks(ctx, watermarks)?, checkpoint);
+        }
+    }
+}
+
+/// The watermarks behind [`watermark_updates`]: every new snapshot of the watermarks whose high
+/// watermark is ahead of the previous one, paired with the previous high watermark.
+fn new_watermarks(mut updates: WatermarkUpdates) -> impl Stream<Item = (Arc<Watermarks>, u64)> {
+    stream! {
+        let mut checkpoint = updates.borrow_and_update().high_watermark().checkpoint();
+
+        while updates.changed().await.is_ok() {
+            let watermarks = updates.borrow_and_update().clone();
+            let hi = watermarks.high_watermark().checkpoint();
+
+            // The watermark can appear to go backwards if the subscription started before the
+            // service read its first watermark. In that case, there is nothing to yield, and the
+            // subscription starts from the new watermark.
+            if hi > checkpoint {
+                yield (watermarks, checkpoint);
+            }
+
+            checkpoint = hi;
+        }
+    }
+}
+
+/// Yield every page of nodes in the range described by `fetch`, by fetching the largest pages
+/// allowed by `limits`, from the front of the range, until there are no more pages. Fails with a
+/// timeout if the pages have not all been fetched by the `deadline`.
+fn drain<'a, C, N, F, Fut>(
+    limits: &'a PageLimits,
+    deadline: Deadline,
+    mut fetch: F,
+) -> impl Stream<Item = Result<Vec<N>, RpcError>> + 'a
+where
+    C: CursorType + Send + Sync + 'a,
+    C::Error: std::error::Error + Send + Sync + 'static,
+    N: OutputType + 'a,
+    F: FnMut(Page<C>) -> Fut + 'a,
+    Fut: Future<Output = Result<Connection<String, N>, RpcError>> + 'a,
+{
+    try_stream! {
+        let mut after = None;
+        loop {
+            let page = Page::from_params(limits, Some(limits.max as u64), after.take(), None, None)?;
+            let conn = deadline.run(fetch(page)).await?;
+
+            let Some(last) = conn.edges.last() else {
+                break;
+            };
+
+            let cursor = C::decode_cursor(&last.cursor).context("Failed to decode cursor")?;
+            let has_next_page = conn.has_next_page;
+            yield conn.edges.into_iter().map(|edge| edge.node).collect();
+
+            if !has_next_page {
+                break;
+            }
+
+            after = Some(cursor);
+        }
+    }
+}
+
+impl SubscriptionLimits {
+    pub(crate) fn new(
+        max_message_size: usize,
+        max_subscriptions: usize,
+        update_timeout: Duration,
+    ) -> Self {
+        Self {
+            max_message_size,
+            max_subscriptions,
+            update_timeout,
+            permits: Arc::new(Semaphore::new(max_subscriptions)),
+        }
+    }
+
+    /// Reserve a place for a subscription, for as long as the permit is held. Fails if the
+    /// service is already serving as many subscriptions as it can.
+    fn permit(&self) -> Result<SemaphorePermit<'_>, RpcError> {
+        self.permits.try_acquire().map_err(|_| {
+            resource_exhausted(anyhow!(
+                "Too many subscriptions, the service serves at most {} at a time",
+                self.max_subscriptions,
+            ))
+        })
+    }
+
+    /// The deadline for producing the results of a watermark update that starts now.
+    fn deadline(&self) -> Deadline {
+        Deadline {
+            at: Instant::now() + self.update_timeout,
+            timeout: self.update_timeout,
+        }
+    }
+}
+
+impl Deadline {
+    /// Wait for `fut` to complete, failing with a timeout if it does not complete by the deadline.
+    async fn run<T>(self, fut: impl Future<Output = Result<T, RpcError>>) -> Result<T, RpcError> {
+        timeout_at(self.at, fut)
+            .await
+            .map_err(|_| request_timeout("Subscription update", self.timeout))?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{pin::pin, sync::Mutex};
+
+    use futures::StreamExt;
+    use tokio::{sync::watch, time::timeout};
+
+    use crate::api::scalars::cursor::JsonCursor;
+
+    use super::*;
+
+    fn limits(max_subscriptions: usize, update_timeout: Duration) -> SubscriptionLimits {
+        SubscriptionLimits::new(5_000, max_subscriptions, update_timeout)
+    }
+
+    #[tokio::test]
+    async fn test_drain_pages() {
+        let page_limits = PageLimits { default: 2, max: 3 };
+        let deadline = limits(1, Duration::from_secs(60)).deadline();
+        let requested = Mutex::new(vec![]);
+
+        let pages: Vec<Vec<u64>> =
+            drain(&page_limits, deadline, |page: Page<JsonCursor<usize>>| {
+                requested.lock().unwrap().push(page.limit());
+                async move { page.paginate_indices(8, |i| Ok(i as u64)) }
+            })
+            .map(|page| page.unwrap())
+            .collect()
+            .await;
+
+        // Pages are fetched at the maximum page size, until the range is exhausted.
+        assert_eq!(pages, vec![vec![0, 1, 2], vec![3, 4, 5], vec![6, 7]]);
+        assert_eq!(*requested.lock().unwrap(), vec![3, 3, 3]);
+    }
+
+    #[tokio::test]
+    async fn test_drain_empty() {
+        let page_limits = PageLimits { default: 2, max: 3 };
+        let deadline = limits(1, Duration::from_secs(60)).deadline();
+
+        let pages: Vec<_> =
+            drain(
+                &page_limits,
+                deadline,
+                |page: Page<JsonCursor<usize>>| async move {
+                    page.paginate_indices(0, |i| Ok(i as u64))
+                },
+            )
+            .collect()
+            .await;
+
+        assert!(pages.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_drain_timeout() {
+        let page_limits = PageLimits { default: 2, max: 3 };
+        let deadline = limits(1, Duration::from_millis(10)).deadline();
+
+        let pages: Vec<_> = drain(
+            &page_limits,
+            deadline,
+            |page: Page<JsonCursor<usize>>| async move {
+                tokio::time::sleep(Duration::from_secs(60)).await;
+                page.paginate_indices(8, |i| Ok(i as u64))
+            },
+        )
+        .collect()
+        .await;
+
+        // The first fetch times out, which ends the stream.
+        assert_eq!(pages.len(), 1);
+        assert!(matches!(pages[0], Err(RpcError::RequestTimeout { .. })));
+    }
+
+    #[test]
+    fn test_subscription_permits() {
+        let limits = limits(2, Duration::from_secs(60));
+
+        let first = limits.permit().unwrap();
+        let _second = limits.permit().unwrap();
+        assert!(matches!(
+            limits.permit(),
+            Err(RpcError::ResourceExhausted(_))
+        ));
+
+        // Ending a subscription frees up its place.
+        drop(first);
+        limits.permit().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_new_watermarks() {
+        let (tx, rx) = watch::channel(Arc::new(Watermarks::default()));
+        let mut updates = pin!(new_watermarks(rx));
+
+        // Nothing is yielded until the watermark changes.
+        assert!(
+            timeout(Duration::from_millis(50), updates.next())
+                .await
+                .is_err()
+        );
+
+        // The subscription started before the first watermark was read, so the watermark appears
+        // to go backwards, and the subscription starts from the new watermark without yielding.
+        tx.send(Arc::new(Watermarks::at_checkpoint(5))).unwrap();
+        assert!(
+            timeout(Duration::from_millis(50), updates.next())
+                .await
+                .is_err()
+        );
+
+        tx.send(Arc::new(Watermarks::at_checkpoint(8))).unwrap();
+        let (watermarks, after) = updates.next().await.unwrap();
+        assert_eq!(watermarks.high_watermark().checkpoint(), 8);
+        assert_eq!(after, 5);
+
+        // A watermark that has not advanced produces no update.
+        tx.send(Arc::new(Watermarks::at_checkpoint(8))).unwrap();
+        assert!(
+            timeout(Duration::from_millis(50), updates.next())
+                .await
+                .is_err()
+        );
+
+        tx.send(Arc::new(Watermarks::at_checkpoint(10))).unwrap();
+        let (watermarks, after) = updates.next().await.unwrap();
+        assert_eq!(watermarks.high_watermark().checkpoint(), 10);
+        assert_eq!(after, 8);
+
+        // The stream ends when the service stops updating its watermarks.
+        drop(tx);
+        assert!(updates.next().await.is_none());
+    }
+}
@@ -6,7 +6,7 @@ use async_graphql::{
     Context, Object,
     registry::{MetaType, Registry},
 };
-use std::{collections::BTreeSet, sync::Arc};
+use std::collections::BTreeSet;
 
 use crate::{
     error::{RpcError, bad_user_input, feature_unavailable, upcast},
@@ -77,8 +77,9 @@ impl AvailableRange {
         available_range_key: AvailableRangeKey,
     ) -> Result<Self, RpcError<Error>> {
         available_range_key.validate(&ctx.schema_env.registry)?;
-        let watermarks: &Arc<Watermarks> = ctx.data()?;
-        let first = available_range_key.reader_lo(watermarks).map_err(upcast)?;
+        let first = available_range_key
+            .reader_lo(scope.watermarks())
+            .map_err(upcast)?;
 
         Ok(Self {
             scope: scope.clone(),
@@ -1,8 +1,6 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::sync::Arc;
-
 use anyhow::Context as _;
 use async_graphql::{Context, Object, connection::Connection};
 
@@ -24,7 +22,6 @@ use crate::{
     error::RpcError,
     pagination::{Page, PaginationConfig},
     scope::Scope,
-    task::watermark::Watermarks,
 };
 
 use super::{
@@ -245,13 +242,12 @@ impl Checkpoint {
         page: Page<CCheckpoint>,
         filter: CheckpointFilter,
     ) -> Result<Connection<String, Checkpoint>, RpcError> {
-        let watermarks: &Arc<Watermarks> = ctx.data()?;
         let available_range_key = AvailableRangeKey {
             type_: "Query".to_string(),
             field: Some("checkpoints".to_string()),
             filters: Some(filter.active_filters()),
         };
-        let reader_lo = available_range_key.reader_lo(watermarks)?;
+        let reader_lo = available_range_key.reader_lo(scope.watermarks())?;
 
         let Some(cp_hi_inclusive) = scope.checkpoint_viewed_at() else {
             // In execution scope, checkpoint pagination returns empty results
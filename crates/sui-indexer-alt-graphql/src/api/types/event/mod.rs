@@ -1,8 +1,6 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::sync::Arc;
-
 use anyhow::Context as _;
 use async_graphql::{Context, Object, connection::Connection};
 use diesel::{prelude::QueryableByName, sql_types::BigInt};
@@ -26,7 +24,6 @@ use crate::{
     error::RpcError,
     pagination::Page,
     scope::Scope,
-    task::watermark::Watermarks,
 };
 
 use super::{
@@ -132,15 +129,14 @@ impl Event {
     ) -> Result<Connection<String, Event>, RpcError> {
         let pg_reader: &PgReader = ctx.data()?;
 
-        let watermarks: &Arc<Watermarks> = ctx.data()?;
         let available_range_key = AvailableRangeKey {
             type_: "Query".to_string(),
             field: Some("events".to_string()),
             filters: Some(filter.active_filters()),
         };
-        let reader_lo = available_range_key.reader_lo(watermarks)?;
+        let reader_lo = available_range_key.reader_lo(scope.watermarks())?;
 
-        let Some(mut query) = filter.tx_bounds(ctx, &scope, reader_lo, &page).await? else {
+        let Some(mut query) = filter.tx_bounds(&scope, reader_lo, &page).await? else {
             return Ok(Connection::new(false, false));
         };
 
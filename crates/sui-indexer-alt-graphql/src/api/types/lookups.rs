@@ -1,9 +1,6 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::sync::Arc;
-
-use async_graphql::Context;
 use diesel::sql_types::BigInt;
 use sui_pg_db::query::Query;
 use sui_sql_macro::query;
@@ -13,7 +10,6 @@ use crate::{
     error::RpcError,
     pagination::Page,
     scope::Scope,
-    task::watermark::Watermarks,
 };
 
 pub(crate) trait CheckpointBounds {
@@ -27,7 +23,7 @@ pub(crate) trait CheckpointBounds {
     ///
     /// tx_lo: The cp_sequence_number of the checkpoint at the start of the bounds.
     /// tx_hi: The tx_lo of the checkpoint directly after the cp_bounds.end(). If it does not exist
-    ///      at cp_bounds.end(), fallback to the maximum tx_sequence_number in the context's watermark
+    ///      at cp_bounds.end(), fallback to the maximum tx_sequence_number in the scope's watermark
     ///      (global_tx_hi).
     ///
     /// NOTE: for consistency, assume that lowerbounds are inclusive and upperbounds are exclusive.
@@ -35,7 +31,6 @@ pub(crate) trait CheckpointBounds {
     /// `hi_inclusive`).
     async fn tx_bounds<'a>(
         &self,
-        ctx: &Context<'_>,
         scope: &Scope,
         reader_lo: u64,
         page: &Page<impl TxBoundsCursor>,
@@ -58,8 +53,7 @@ pub(crate) trait CheckpointBounds {
             return Ok(None);
         };
 
-        let watermarks: &Arc<Watermarks> = ctx.data()?;
-        let global_tx_hi = watermarks.high_watermark().transaction();
+        let global_tx_hi = scope.watermarks().high_watermark().transaction();
 
         let query = query!(
             r#"
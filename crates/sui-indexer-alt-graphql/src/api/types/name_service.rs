@@ -1,8 +1,6 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::sync::Arc;
-
 use anyhow::Context as _;
 use async_graphql::Context;
 use futures::future::OptionFuture;
@@ -10,9 +8,7 @@ use sui_name_service::{Domain as NativeDomain, NameRecord, NameServiceConfig};
 use sui_types::{base_types::SuiAddress, dynamic_field::Field};
 use tokio::join;
 
-use crate::{
-    api::scalars::domain::Domain, error::RpcError, scope::Scope, task::watermark::Watermarks,
-};
+use crate::{api::scalars::domain::Domain, error::RpcError, scope::Scope};
 
 use super::{address::Address, object::Object};
 
@@ -23,8 +19,7 @@ pub(crate) async fn name_to_address(
     scope: &Scope,
     domain: &Domain,
 ) -> Result<Option<Address>, RpcError> {
-    let watermark: &Arc<Watermarks> = ctx.data()?;
-    let timestamp_ms = watermark.timestamp_hi_ms();
+    let timestamp_ms = scope.watermarks().timestamp_hi_ms();
 
     let domain_record = name_record(ctx, scope.clone(), domain);
     let parent_record: OptionFuture<_> = domain
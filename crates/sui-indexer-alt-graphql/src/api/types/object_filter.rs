@@ -3,6 +3,10 @@
 #![allow(dead_code)]
 
 use async_graphql::{CustomValidator, InputObject, InputValueError};
+use sui_types::{
+    base_types::SuiAddress as NativeSuiAddress,
+    object::{Object as NativeObject, Owner},
+};
 
 use crate::{
     api::scalars::{owner_kind::OwnerKind, sui_address::SuiAddress, type_filter::TypeFilter},
@@ -67,6 +71,32 @@ impl ObjectFilter {
             type_: intersect!(type_, TypeFilter::intersect)?,
         })
     }
+
+    /// Whether `object` is selected by this filter.
+    pub(crate) fn matches(&self, object: &NativeObject) -> bool {
+        let owner_kind = self
+            .owner_kind
+            .or(self.owner.is_some().then_some(OwnerKind::Address));
+
+        let owner = self.owner.map(NativeSuiAddress::from);
+        let owner_matches = match (owner_kind, object.owner()) {
+            (None, _) => true,
+            (
+                Some(OwnerKind::Address),
+                Owner::AddressOwner(address) | Owner::ConsensusAddressOwner { owner: address, .. },
+            ) => owner == Some(*address),
+            (Some(OwnerKind::Object), Owner::ObjectOwner(address)) => owner == Some(*address),
+            (Some(OwnerKind::Shared), Owner::Shared { .. }) => true,
+            (Some(OwnerKind::Immutable), Owner::Immutable) => true,
+            (Some(_), _) => false,
+        };
+
+        owner_matches
+            && self
+                .type_
+                .as_ref()
+                .is_none_or(|t| object.struct_tag().is_some_and(|tag| t.matches(&tag)))
+    }
 }
 
 impl CustomValidator<ObjectFilter> for ObjectFilterValidator {
@@ -36,7 +36,6 @@ use crate::{
     error::RpcError,
     pagination::Page,
     scope::Scope,
-    task::watermark::Watermarks,
 };
 
 use super::{
@@ -211,15 +210,14 @@ impl Transaction {
         page: Page<CTransaction>,
         filter: TransactionFilter,
     ) -> Result<Connection<String, Transaction>, RpcError> {
-        let watermarks: &Arc<Watermarks> = ctx.data()?;
         let available_range_key = AvailableRangeKey {
             type_: "Query".to_string(),
             field: Some("transactions".to_string()),
             filters: Some(filter.active_filters()),
         };
-        let reader_lo = available_range_key.reader_lo(watermarks)?;
+        let reader_lo = available_range_key.reader_lo(scope.watermarks())?;
 
-        let Some(query) = filter.tx_bounds(ctx, &scope, reader_lo, &page).await? else {
+        let Some(query) = filter.tx_bounds(&scope, reader_lo, &page).await? else {
             return Ok(Connection::new(false, false));
         };
 
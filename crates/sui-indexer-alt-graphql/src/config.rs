@@ -13,6 +13,7 @@ use sui_protocol_config::{Chain, ProtocolConfig, ProtocolVersion};
 use sui_types::base_types::{ObjectID, SuiAddress};
 
 use crate::{
+    api::subscription::SubscriptionLimits,
     extensions::{query_limits::QueryLimitsConfig, timeout::TimeoutConfig},
     pagination::{PageLimits, PaginationConfig},
 };
@@ -76,7 +77,9 @@ pub struct Limits {
     pub mutation_timeout_ms: u32,
 
     /// Time (in milliseconds) to wait for a read request from the GraphQL service. Requests that
-    /// take longer than this time to return a result will return a timeout error.
+    /// take longer than this time to return a result will return a timeout error. Subscriptions
+    /// that take longer than this time to produce the results for a single update will return a
+    /// timeout error and end.
     pub query_timeout_ms: u32,
 
     /// Maximum depth of a GraphQL query that can be accepted by this service.
@@ -99,6 +102,10 @@ pub struct Limits {
     /// `max_transaction_payload_size`.
     pub max_query_payload_size: u32,
 
+    /// Maximum number of subscriptions the service will serve at the same time, across all
+    /// WebSocket connections. Requests for further subscriptions will result in an error.
+    pub max_subscriptions: u32,
+
     /// By default, paginated queries will return this many elements if a page size is not
     /// provided. This may be overridden for paginated queries that are limited by the protocol.
     pub default_page_size: u32,
@@ -158,6 +165,7 @@ pub struct LimitsLayer {
     pub max_output_nodes: Option<u32>,
     pub max_tx_payload_size: Option<u32>,
     pub max_query_payload_size: Option<u32>,
+    pub max_subscriptions: Option<u32>,
     pub default_page_size: Option<u32>,
     pub max_page_size: Option<u32>,
     pub max_multi_get_size: Option<u32>,
@@ -272,6 +280,14 @@ impl Limits {
         }
     }
 
+    pub(crate) fn subscriptions(&self) -> SubscriptionLimits {
+        SubscriptionLimits::new(
+            self.max_query_payload_size as usize,
+            self.max_subscriptions as usize,
+            Duration::from_millis(self.query_timeout_ms as u64),
+        )
+    }
+
     pub(crate) fn pagination(&self) -> PaginationConfig {
         PaginationConfig::new(
             self.max_multi_get_size,
@@ -320,6 +336,7 @@ impl LimitsLayer {
             max_query_payload_size: self
                 .max_query_payload_size
                 .unwrap_or(base.max_query_payload_size),
+            max_subscriptions: self.max_subscriptions.unwrap_or(base.max_subscriptions),
             default_page_size: self.default_page_size.unwrap_or(base.default_page_size),
             max_page_size: self.max_page_size.unwrap_or(base.max_page_size),
             max_multi_get_size: self.max_multi_get_size.unwrap_or(base.max_multi_get_size),
@@ -405,6 +422,7 @@ impl From<Limits> for LimitsLayer {
             max_output_nodes: Some(value.max_output_nodes),
             max_tx_payload_size: Some(value.max_tx_payload_size),
             max_query_payload_size: Some(value.max_query_payload_size),
+            max_subscriptions: Some(value.max_subscriptions),
             default_page_size: Some(value.default_page_size),
             max_page_size: Some(value.max_page_size),
             max_multi_get_size: Some(value.max_multi_get_size),
@@ -490,6 +508,7 @@ impl Default for Limits {
             // Add a 30% buffer to the protocol limit, rounded up to account Base64 overhead.
             max_tx_payload_size: (max_tx_size_bytes * 4).div_ceil(3) as u32,
             max_query_payload_size: 5_000,
+            max_subscriptions: 1_000,
             default_page_size: 20,
             max_page_size: 50,
             max_multi_get_size: 200,
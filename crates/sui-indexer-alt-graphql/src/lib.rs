@@ -8,15 +8,16 @@ use api::types::{
     address::IAddressable, move_datatype::IMoveDatatype, move_object::IMoveObject, object::IObject,
 };
 use async_graphql::{
-    EmptySubscription, ObjectType, Schema, SchemaBuilder, SubscriptionType,
-    extensions::ExtensionFactory, http::GraphiQLSource,
+    Data, ObjectType, Schema, SchemaBuilder, SubscriptionType,
+    extensions::ExtensionFactory,
+    http::{ALL_WEBSOCKET_PROTOCOLS, GraphiQLSource},
 };
-use async_graphql_axum::{GraphQLRequest, GraphQLResponse};
+use async_graphql_axum::{GraphQLProtocol, GraphQLRequest, GraphQLResponse, GraphQLWebSocket};
 use axum::{
     Extension, Router,
-    extract::{ConnectInfo, MatchedPath},
+    extract::{ConnectInfo, MatchedPath, WebSocketUpgrade},
     http::Method,
-    response::Html,
+    response::{Html, Response},
     routing::{MethodRouter, get, post},
 };
 use axum_extra::TypedHeader;
@@ -49,7 +50,11 @@ use tower_http::cors;
 use tracing::{error, info};
 use url::Url;
 
-use crate::api::{mutation::Mutation, query::Query};
+use crate::api::{
+    mutation::Mutation,
+    query::Query,
+    subscription::{Subscription, SubscriptionLimits},
+};
 use crate::extensions::logging::{Logging, Session};
 use crate::metrics::RpcMetrics;
 use crate::middleware::version::Version;
@@ -245,8 +250,8 @@ impl Default for RpcArgs {
 }
 
 /// The GraphQL schema this service will serve, without any extensions or context added.
-pub fn schema() -> SchemaBuilder<Query, Mutation, EmptySubscription> {
-    Schema::build(Query::default(), Mutation, EmptySubscription)
+pub fn schema() -> SchemaBuilder<Query, Mutation, Subscription> {
+    Schema::build(Query::default(), Mutation, Subscription)
         .register_output_type::<IAddressable>()
         .register_output_type::<IMoveDatatype>()
         .register_output_type::<IMoveObject>()
@@ -370,21 +375,26 @@ pub async fn start_rpc(
         cancel.child_token(),
     );
 
+    let subscriptions = config.limits.subscriptions();
     let rpc = rpc
         .route("/graphql", post(graphql))
+        .route("/graphql/ws", get(subscriptions))
         .route("/graphql/health", get(health::check))
         .layer(watermark_task.watermarks())
         .layer(config.health)
         .layer(DbProbe(database_url))
+        .layer(subscriptions.clone())
         .extension(Timeout::new(config.limits.timeouts()))
         .extension(QueryLimitsChecker::new(
             config.limits.query_limits(),
             metrics,
         ))
         .data(config.limits.pagination())
+        .data(subscriptions)
         .data(config.limits)
         .data(config.name_service)
         .data(config.zklogin)
+        .data(watermark_task.updates())
         .data(chain_identifier)
         .data(pg_reader)
         .data(consistent_reader)
@@ -408,7 +418,7 @@ pub async fn start_rpc(
 /// Handler for RPC requests (POST requests making GraphQL queries).
 async fn graphql(
     ConnectInfo(addr): ConnectInfo<SocketAddr>,
-    Extension(schema): Extension<Schema<Query, Mutation, EmptySubscription>>,
+    Extension(schema): Extension<Schema<Query, Mutation, Subscription>>,
     Extension(watermark): Extension<WatermarksLock>,
     TypedHeader(content_length): TypedHeader<ContentLength>,
     show_usage: Option<TypedHeader<ShowUsage>>,
@@ -427,6 +437,31 @@ async fn graphql(
     schema.execute(request).await.into()
 }
 
+/// Handler for subscriptions (GraphQL requests over a WebSocket connection).
+async fn subscriptions(
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    Extension(schema): Extension<Schema<Query, Mutation, Subscription>>,
+    Extension(limits): Extension<SubscriptionLimits>,
+    protocol: GraphQLProtocol,
+    upgrade: WebSocketUpgrade,
+) -> Response {
+    // Subscription requests arrive as WebSocket messages, whose size is not known up-front, so
+    // instead of checking their content length against the payload size limit, the WebSocket
+    // rejects messages that exceed it (the other query limits still apply).
+    let mut data = Data::default();
+    data.insert(ContentLength(0));
+    data.insert(Session::new(addr));
+
+    upgrade
+        .max_message_size(limits.max_message_size)
+        .protocols(ALL_WEBSOCKET_PROTOCOLS)
+        .on_upgrade(move |stream| {
+            GraphQLWebSocket::new(stream, schema, protocol)
+                .with_data(data)
+                .serve()
+        })
+}
+
 /// Handler for GET requests for the online IDE. GraphQL requests are forwarded to the POST handler
 /// at the same path.
 async fn graphiql(path: MatchedPath) -> Html<String> {
@@ -63,22 +63,36 @@ pub(crate) struct Scope {
 
     /// Limits for package/type resolution.
     resolver_limits: sui_package_resolver::Limits,
+
+    /// The snapshot of the service's watermarks that the request is being served at.
+    watermarks: Arc<Watermarks>,
 }
 
 impl Scope {
     /// Create a new scope at the top-level (initialized by information we have at the root of a
     /// request).
     pub(crate) fn new<E: std::error::Error>(ctx: &Context<'_>) -> Result<Self, RpcError<E>> {
-        let watermark: &Arc<Watermarks> = ctx.data()?;
+        let watermarks: &Arc<Watermarks> = ctx.data()?;
+        Self::with_watermarks(ctx, watermarks.clone())
+    }
+
+    /// Create a new top-level scope that views data at the given snapshot of the service's
+    /// watermarks, rather than the snapshot taken at the start of the request. This is used by
+    /// subscriptions, which outlive the watermarks they started at.
+    pub(crate) fn with_watermarks<E: std::error::Error>(
+        ctx: &Context<'_>,
+        watermarks: Arc<Watermarks>,
+    ) -> Result<Self, RpcError<E>> {
         let package_store: &Arc<PackageCache> = ctx.data()?;
         let limits: &Limits = ctx.data()?;
 
         Ok(Self {
-            checkpoint_viewed_at: Some(watermark.high_watermark().checkpoint()),
+            checkpoint_viewed_at: Some(watermarks.high_watermark().checkpoint()),
             root_version: None,
             execution_objects: Arc::new(BTreeMap::new()),
             package_store: package_store.clone(),
             resolver_limits: limits.package_resolver(),
+            watermarks,
         })
     }
 
@@ -92,6 +106,7 @@ impl Scope {
             execution_objects: Arc::clone(&self.execution_objects),
             package_store: self.package_store.clone(),
             resolver_limits: self.resolver_limits.clone(),
+            watermarks: self.watermarks.clone(),
         })
     }
 
@@ -103,6 +118,7 @@ impl Scope {
             execution_objects: Arc::clone(&self.execution_objects),
             package_store: self.package_store.clone(),
             resolver_limits: self.resolver_limits.clone(),
+            watermarks: self.watermarks.clone(),
         }
     }
 
@@ -114,6 +130,7 @@ impl Scope {
             execution_objects: Arc::clone(&self.execution_objects),
             package_store: self.package_store.clone(),
             resolver_limits: self.resolver_limits.clone(),
+            watermarks: self.watermarks.clone(),
         }
     }
 
@@ -126,6 +143,11 @@ impl Scope {
         self.checkpoint_viewed_at
     }
 
+    /// The snapshot of the service's watermarks that this scope views data at.
+    pub(crate) fn watermarks(&self) -> &Watermarks {
+        &self.watermarks
+    }
+
     /// Root parent object version for dynamic fields.
     pub(crate) fn root_version(&self) -> Option<u64> {
         self.root_version
@@ -176,6 +198,7 @@ impl Scope {
             execution_objects,
             package_store: self.package_store.clone(),
             resolver_limits: self.resolver_limits.clone(),
+            watermarks: self.watermarks.clone(),
         })
     }
 
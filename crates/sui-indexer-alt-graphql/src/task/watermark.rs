@@ -21,7 +21,11 @@ use sui_indexer_alt_reader::{
     pg_reader::PgReader,
 };
 use sui_sql_macro::query;
-use tokio::{sync::RwLock, task::JoinHandle, time};
+use tokio::{
+    sync::{RwLock, watch},
+    task::JoinHandle,
+    time,
+};
 use tokio_util::sync::CancellationToken;
 use tracing::{debug, info, warn};
 
@@ -40,6 +44,9 @@ pub(crate) struct WatermarkTask {
     /// efficiently swap in new watermark values.
     watermarks: WatermarksLock,
 
+    /// Notifies subscribers every time the watermarks are updated.
+    updates: watch::Sender<Arc<Watermarks>>,
+
     /// Access to the Postgres DB
     pg_reader: PgReader,
 
@@ -116,6 +123,9 @@ struct WatermarkRow {
 
 pub(crate) type WatermarksLock = Arc<RwLock<Arc<Watermarks>>>;
 
+/// Receives the latest watermarks every time the watermark task updates them.
+pub(crate) type WatermarkUpdates = watch::Receiver<Arc<Watermarks>>;
+
 impl WatermarkTask {
     pub(crate) fn new(
         config: WatermarkConfig,
@@ -133,6 +143,7 @@ impl WatermarkTask {
 
         Self {
             watermarks: Default::default(),
+            updates: watch::channel(Default::default()).0,
             pg_reader,
             bigtable_reader,
             ledger_grpc_reader,
@@ -149,6 +160,11 @@ impl WatermarkTask {
         self.watermarks.clone()
     }
 
+    /// A receiver that is notified every time this task updates the watermarks.
+    pub(crate) fn updates(&self) -> WatermarkUpdates {
+        self.updates.subscribe()
+    }
+
     /// Start a new task that regularly polls the database for watermarks.
     ///
     /// This operation consume the `self` and returns a handle to the spawned tokio task. The task
@@ -157,6 +173,7 @@ impl WatermarkTask {
         tokio::spawn(async move {
             let Self {
                 watermarks,
+                updates,
                 pg_reader,
                 bigtable_reader,
                 ledger_grpc_reader,
@@ -215,7 +232,9 @@ impl WatermarkTask {
                             "Watermark updated"
                         );
 
-                        *watermarks.write().await = Arc::new(w);
+                        let w = Arc::new(w);
+                        *watermarks.write().await = w.clone();
+                        updates.send_replace(w);
                     }
                 }
             }
@@ -357,6 +376,22 @@ impl Default for Watermarks {
     }
 }
 
+#[cfg(test)]
+impl Watermarks {
+    /// Watermarks whose global upperbound is at `checkpoint`.
+    pub(crate) fn at_checkpoint(checkpoint: u64) -> Self {
+        Self {
+            global_hi: Watermark {
+                epoch: 0,
+                checkpoint: checkpoint as i64,
+                transaction: 0,
+            },
+            timestamp_ms_hi_inclusive: 0,
+            pipeline_lo: BTreeMap::new(),
+        }
+    }
+}
+
 async fn watermark_from_bigtable(bigtable_reader: &BigtableReader) -> anyhow::Result<WatermarkRow> {
     let summary = bigtable_reader
         .checkpoint_watermark()
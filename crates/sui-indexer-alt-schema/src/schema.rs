@@ -28,6 +28,16 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    dead_letters (pipeline, checkpoint) {
+        pipeline -> Text,
+        checkpoint -> Int8,
+        error -> Text,
+        attempts -> Int8,
+        updated_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     ev_emit_mod (package, module, tx_sequence_number) {
         package -> Bytea,
@@ -244,6 +254,7 @@ diesel::allow_tables_to_appear_in_same_query!(
     coin_balance_buckets,
     coin_balance_buckets_deletion_reference,
     cp_sequence_numbers,
+    dead_letters,
     ev_emit_mod,
     ev_struct_inst,
     kv_checkpoints,
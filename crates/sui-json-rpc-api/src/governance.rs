@@ -1,10 +1,11 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use fastcrypto::encoding::Base64;
 use jsonrpsee::core::RpcResult;
 use jsonrpsee::proc_macros::rpc;
 
-use sui_json_rpc_types::{DelegatedStake, SuiCommittee, ValidatorApys};
+use sui_json_rpc_types::{DelegatedStake, SuiCommittee, SuiGasPriceSuggestion, ValidatorApys};
 use sui_open_rpc_macros::open_rpc;
 use sui_types::base_types::{ObjectID, SuiAddress};
 use sui_types::sui_serde::BigInt;
@@ -40,6 +41,18 @@ pub trait GovernanceReadApi {
     #[method(name = "getReferenceGasPrice")]
     async fn get_reference_gas_price(&self) -> RpcResult<BigInt<u64>>;
 
+    /// Return a gas price suggestion for a transaction, based on recent congestion on the shared
+    /// objects it accesses mutably. The suggestion is never below the reference gas price, and
+    /// comes with a confidence level, and the congestion information it is based on.
+    #[method(name = "getGasPriceSuggestion")]
+    async fn get_gas_price_suggestion(
+        &self,
+        /// BCS serialized TransactionData. The shared objects it accesses mutably are taken into account.
+        tx_bytes: Option<Base64>,
+        /// IDs of shared objects to take into account, in addition to the ones accessed by `tx_bytes`.
+        object_ids: Option<Vec<ObjectID>>,
+    ) -> RpcResult<SuiGasPriceSuggestion>;
+
     /// Return the validator APY
     #[method(name = "getValidatorsApy")]
     async fn get_validators_apy(&self) -> RpcResult<ValidatorApys>;
@@ -6,6 +6,7 @@ use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 use sui_types::base_types::{AuthorityName, EpochId, ObjectID, SuiAddress};
 use sui_types::committee::{Committee, StakeUnit};
+use sui_types::storage::{GasPriceConfidence, GasPriceSuggestion, ObjectCongestionInfo};
 use sui_types::sui_serde::BigInt;
 
 /// RPC representation of the [Committee] type.
@@ -88,3 +89,98 @@ pub struct ValidatorApy {
     pub address: SuiAddress,
     pub apy: f64,
 }
+
+/// How likely a transaction paying a suggested gas price is to avoid being cancelled due to
+/// congestion on the shared objects it accesses.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, JsonSchema)]
+#[serde(rename = "GasPriceConfidence")]
+pub enum SuiGasPriceConfidence {
+    /// Only cancellations have been seen recently, so the suggestion is a lower bound.
+    Low,
+    /// Both cancellations and successful transactions have been seen recently.
+    Medium,
+    /// No congestion has been seen recently.
+    High,
+}
+
+/// A gas price suggestion for a transaction accessing a set of shared objects mutably.
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(rename = "GasPriceSuggestion", rename_all = "camelCase")]
+pub struct SuiGasPriceSuggestion {
+    /// The suggested gas price, which is never below the reference gas price.
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub gas_price: u64,
+    #[schemars(with = "BigInt<u64>")]
+    #[serde_as(as = "BigInt<u64>")]
+    pub reference_gas_price: u64,
+    /// The lowest confidence across all the objects.
+    pub confidence: SuiGasPriceConfidence,
+    pub objects: Vec<SuiObjectCongestion>,
+}
+
+/// Recent congestion on a single shared object.
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(rename = "ObjectCongestion", rename_all = "camelCase")]
+pub struct SuiObjectCongestion {
+    pub object_id: ObjectID,
+    /// The gas price needed to access this object mutably, if it is congested.
+    #[schemars(with = "Option<BigInt<u64>>")]
+    #[serde_as(as = "Option<BigInt<u64>>")]
+    pub suggested_gas_price: Option<u64>,
+    pub confidence: SuiGasPriceConfidence,
+    /// Timestamp of the most recent checkpoint with a transaction cancelled on this object.
+    #[schemars(with = "Option<BigInt<u64>>")]
+    #[serde_as(as = "Option<BigInt<u64>>")]
+    pub last_cancellation_timestamp_ms: Option<u64>,
+    /// Highest gas price of the transactions cancelled in that checkpoint.
+    #[schemars(with = "Option<BigInt<u64>>")]
+    #[serde_as(as = "Option<BigInt<u64>>")]
+    pub highest_cancelled_gas_price: Option<u64>,
+    /// Timestamp of the most recent checkpoint with a transaction that accessed this object
+    /// mutably while it was congested.
+    #[schemars(with = "Option<BigInt<u64>>")]
+    #[serde_as(as = "Option<BigInt<u64>>")]
+    pub last_success_timestamp_ms: Option<u64>,
+    /// Lowest gas price of the transactions executed on this object in that checkpoint.
+    #[schemars(with = "Option<BigInt<u64>>")]
+    #[serde_as(as = "Option<BigInt<u64>>")]
+    pub lowest_executed_gas_price: Option<u64>,
+}
+
+impl From<GasPriceConfidence> for SuiGasPriceConfidence {
+    fn from(confidence: GasPriceConfidence) -> Self {
+        match confidence {
+            GasPriceConfidence::Low => Self::Low,
+            GasPriceConfidence::Medium => Self::Medium,
+            GasPriceConfidence::High => Self::High,
+        }
+    }
+}
+
+impl From<GasPriceSuggestion> for SuiGasPriceSuggestion {
+    fn from(suggestion: GasPriceSuggestion) -> Self {
+        Self {
+            gas_price: suggestion.gas_price,
+            reference_gas_price: suggestion.reference_gas_price,
+            confidence: suggestion.confidence.into(),
+            objects: suggestion.objects.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<ObjectCongestionInfo> for SuiObjectCongestion {
+    fn from(info: ObjectCongestionInfo) -> Self {
+        Self {
+            object_id: info.object_id,
+            suggested_gas_price: info.suggested_gas_price,
+            confidence: info.confidence.into(),
+            last_cancellation_timestamp_ms: info.last_cancellation_timestamp_ms,
+            highest_cancelled_gas_price: info.highest_cancelled_gas_price,
+            last_success_timestamp_ms: info.last_success_timestamp_ms,
+            lowest_executed_gas_price: info.lowest_executed_gas_price,
+        }
+    }
+}
@@ -34,7 +34,7 @@ use sui_types::messages_checkpoint::{
     VerifiedCheckpoint,
 };
 use sui_types::object::{Object, ObjectRead, PastObjectRead};
-use sui_types::storage::{BackingPackageStore, ObjectStore, WriteKind};
+use sui_types::storage::{BackingPackageStore, GasPriceSuggestion, ObjectStore, WriteKind};
 use sui_types::sui_serde::BigInt;
 use sui_types::sui_system_state::SuiSystemState;
 use sui_types::transaction::{Transaction, TransactionData, TransactionKind};
@@ -70,6 +70,12 @@ pub trait StateRead: Send + Sync {
 
     fn load_epoch_store_one_call_per_task(&self) -> Guard<Arc<AuthorityPerEpochStore>>;
 
+    fn get_gas_price_suggestion(
+        &self,
+        transaction: Option<&TransactionData>,
+        object_ids: &[ObjectID],
+    ) -> GasPriceSuggestion;
+
     fn get_dynamic_fields(
         &self,
         owner: ObjectID,
@@ -264,6 +270,14 @@ impl StateRead for AuthorityState {
         self.load_epoch_store_one_call_per_task()
     }
 
+    fn get_gas_price_suggestion(
+        &self,
+        transaction: Option<&TransactionData>,
+        object_ids: &[ObjectID],
+    ) -> GasPriceSuggestion {
+        self.get_gas_price_suggestion(transaction, object_ids)
+    }
+
     fn get_dynamic_fields(
         &self,
         owner: ObjectID,
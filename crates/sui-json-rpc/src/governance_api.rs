@@ -8,6 +8,7 @@ use std::sync::Arc;
 use async_trait::async_trait;
 use cached::SizedCache;
 use cached::proc_macro::cached;
+use fastcrypto::encoding::Base64;
 use itertools::Itertools;
 use jsonrpsee::RpcModule;
 use jsonrpsee::core::RpcResult;
@@ -16,7 +17,7 @@ use tracing::{info, instrument};
 use sui_core::authority::AuthorityState;
 use sui_json_rpc_api::{GovernanceReadApiOpenRpc, GovernanceReadApiServer, JsonRpcMetrics};
 use sui_json_rpc_types::{DelegatedStake, Stake, StakeStatus};
-use sui_json_rpc_types::{SuiCommittee, ValidatorApy, ValidatorApys};
+use sui_json_rpc_types::{SuiCommittee, SuiGasPriceSuggestion, ValidatorApy, ValidatorApys};
 use sui_open_rpc::Module;
 use sui_types::base_types::{ObjectID, SuiAddress};
 use sui_types::committee::EpochId;
@@ -25,11 +26,13 @@ use sui_types::error::{SuiError, SuiErrorKind, UserInputError};
 use sui_types::governance::StakedSui;
 use sui_types::id::ID;
 use sui_types::object::ObjectRead;
+use sui_types::storage::MAX_GAS_PRICE_SUGGESTION_OBJECTS;
 use sui_types::sui_serde::BigInt;
 use sui_types::sui_system_state::PoolTokenExchangeRate;
 use sui_types::sui_system_state::SuiSystemStateTrait;
 use sui_types::sui_system_state::sui_system_state_summary::SuiSystemStateSummary;
 use sui_types::sui_system_state::{SuiSystemState, get_validator_from_table};
+use sui_types::transaction::TransactionData;
 
 use crate::authority_state::StateRead;
 use crate::error::{Error, RpcInterimResult, SuiRpcInputError};
@@ -202,6 +205,35 @@ impl GovernanceReadApi {
     fn get_system_state(&self) -> Result<SuiSystemState, Error> {
         Ok(self.state.get_system_state()?)
     }
+
+    fn get_gas_price_suggestion(
+        &self,
+        tx_bytes: Option<Base64>,
+        object_ids: Vec<ObjectID>,
+    ) -> Result<SuiGasPriceSuggestion, Error> {
+        if tx_bytes.is_none() && object_ids.is_empty() {
+            Err(SuiRpcInputError::GenericInvalid(
+                "One of tx_bytes or object_ids must be provided".to_string(),
+            ))?
+        }
+
+        if object_ids.len() > MAX_GAS_PRICE_SUGGESTION_OBJECTS {
+            Err(SuiRpcInputError::SizeLimitExceeded(
+                MAX_GAS_PRICE_SUGGESTION_OBJECTS.to_string(),
+            ))?
+        }
+
+        let tx_data = tx_bytes
+            .map(|tx_bytes| -> Result<TransactionData, SuiRpcInputError> {
+                Ok(bcs::from_bytes(&tx_bytes.to_vec()?)?)
+            })
+            .transpose()?;
+
+        Ok(self
+            .state
+            .get_gas_price_suggestion(tx_data.as_ref(), &object_ids)
+            .into())
+    }
 }
 
 #[async_trait]
@@ -248,6 +280,17 @@ impl GovernanceReadApiServer for GovernanceReadApi {
         })
     }
 
+    #[instrument(skip(self))]
+    async fn get_gas_price_suggestion(
+        &self,
+        tx_bytes: Option<Base64>,
+        object_ids: Option<Vec<ObjectID>>,
+    ) -> RpcResult<SuiGasPriceSuggestion> {
+        with_tracing!(async move {
+            self.get_gas_price_suggestion(tx_bytes, object_ids.unwrap_or_default())
+        })
+    }
+
     #[instrument(skip(self))]
     async fn get_validators_apy(&self) -> RpcResult<ValidatorApys> {
         info!("get_validator_apy");
@@ -489,7 +532,21 @@ impl SuiRpcModule for GovernanceReadApi {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::authority_state::MockStateRead;
+    use fastcrypto::encoding::Encoding;
+    use sui_json_rpc_types::SuiGasPriceConfidence;
+    use sui_types::base_types::random_object_ref;
+    use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+    use sui_types::storage::{GasPriceConfidence, GasPriceSuggestion, ObjectCongestionInfo};
     use sui_types::sui_system_state::PoolTokenExchangeRate;
+    use sui_types::transaction::CallArg;
+
+    fn governance_read_api(state: MockStateRead) -> GovernanceReadApi {
+        GovernanceReadApi {
+            state: Arc::new(state),
+            metrics: Arc::new(JsonRpcMetrics::new_for_tests()),
+        }
+    }
 
     #[test]
     fn test_backfill_rates_empty() {
@@ -525,4 +582,92 @@ mod tests {
         ];
         assert_eq!(backfill_rates(rates), expected);
     }
+
+    #[test]
+    fn test_get_gas_price_suggestion() {
+        // A transaction that accesses the clock mutably.
+        let mut builder = ProgrammableTransactionBuilder::new();
+        builder.input(CallArg::CLOCK_MUT).unwrap();
+        let tx_data = TransactionData::new_programmable(
+            SuiAddress::ZERO,
+            vec![random_object_ref()],
+            builder.finish(),
+            1_000_000,
+            1000,
+        );
+
+        let object_id = ObjectID::random();
+        let expected_tx = tx_data.clone();
+
+        let mut state = MockStateRead::new();
+        state
+            .expect_get_gas_price_suggestion()
+            .withf(move |tx, ids| *tx == Some(&expected_tx) && ids == [object_id])
+            .returning(|_, ids| GasPriceSuggestion {
+                gas_price: 1000,
+                reference_gas_price: 750,
+                confidence: GasPriceConfidence::Medium,
+                objects: ids
+                    .iter()
+                    .map(|object_id| ObjectCongestionInfo {
+                        object_id: *object_id,
+                        suggested_gas_price: Some(1000),
+                        confidence: GasPriceConfidence::Medium,
+                        last_cancellation_timestamp_ms: Some(2000),
+                        highest_cancelled_gas_price: Some(900),
+                        last_success_timestamp_ms: Some(2000),
+                        lowest_executed_gas_price: Some(1000),
+                    })
+                    .collect(),
+            });
+
+        let api = governance_read_api(state);
+        let tx_bytes = Base64::from_bytes(&bcs::to_bytes(&tx_data).unwrap());
+        let suggestion = api
+            .get_gas_price_suggestion(Some(tx_bytes), vec![object_id])
+            .unwrap();
+
+        assert_eq!(suggestion.gas_price, 1000);
+        assert_eq!(suggestion.reference_gas_price, 750);
+        assert_eq!(suggestion.confidence, SuiGasPriceConfidence::Medium);
+        assert_eq!(suggestion.objects.len(), 1);
+        assert_eq!(suggestion.objects[0].object_id, object_id);
+        assert_eq!(suggestion.objects[0].highest_cancelled_gas_price, Some(900));
+    }
+
+    #[test]
+    fn test_get_gas_price_suggestion_requires_input() {
+        let api = governance_read_api(MockStateRead::new());
+        let err = api.get_gas_price_suggestion(None, vec![]).unwrap_err();
+        assert!(matches!(
+            err,
+            Error::SuiRpcInputError(SuiRpcInputError::GenericInvalid(_))
+        ));
+    }
+
+    #[test]
+    fn test_get_gas_price_suggestion_invalid_transaction() {
+        let api = governance_read_api(MockStateRead::new());
+        let tx_bytes = Base64::from_bytes(&[1, 2, 3]);
+        let err = api
+            .get_gas_price_suggestion(Some(tx_bytes), vec![])
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            Error::SuiRpcInputError(SuiRpcInputError::Bcs(_))
+        ));
+    }
+
+    #[test]
+    fn test_get_gas_price_suggestion_too_many_objects() {
+        let api = governance_read_api(MockStateRead::new());
+        let object_ids = (0..=MAX_GAS_PRICE_SUGGESTION_OBJECTS)
+            .map(|_| ObjectID::random())
+            .collect();
+        let err = api.get_gas_price_suggestion(None, object_ids).unwrap_err();
+        assert!(matches!(
+            err,
+            Error::SuiRpcInputError(SuiRpcInputError::SizeLimitExceeded(_))
+        ));
+    }
 }
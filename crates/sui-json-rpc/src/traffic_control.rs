@@ -49,8 +49,9 @@ where
                 if let Err(response) = handle_traffic_req(&traffic_controller, &client).await {
                     response
                 } else {
+                    let method = req.method_name().to_string();
                     let response = service.call(req).await;
-                    handle_traffic_resp(&traffic_controller, client, &response).await;
+                    handle_traffic_resp(&traffic_controller, client, method, &response).await;
                     response
                 }
             } else {
@@ -78,6 +79,7 @@ async fn handle_traffic_req(
 async fn handle_traffic_resp(
     traffic_controller: &Arc<TrafficController>,
     client: Option<IpAddr>,
+    method: String,
     response: &MethodResponse,
 ) {
     let error = response.as_error_code().map(ErrorCode::from);
@@ -97,6 +99,7 @@ async fn handle_traffic_resp(
         // suitable rpc provider (or run their own). Later we may want
         // to provide a weight distribution based on the method being called.
         spam_weight: Weight::one(),
+        request_type: Some(method),
         timestamp: SystemTime::now(),
     });
 }
@@ -2,8 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use prometheus::Registry;
+use std::path::PathBuf;
 use std::sync::Arc;
-use sui_kvstore::{BigTableClient, KeyValueStoreReader};
+use sui_kvstore::{BigTableClient, KeyValueStoreReader, RocksDbClient};
 use sui_rpc::proto::sui::rpc::v2::GetServiceInfoResponse;
 use sui_rpc_api::ServerVersion;
 use sui_types::digests::ChainIdentifier;
@@ -14,24 +15,29 @@ use tracing::error;
 
 mod v2;
 
+/// How often a RocksDB secondary picks up the writes of the ingestion process.
+const ROCKSDB_CATCH_UP_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Serves the ledger service from a KV store, for example a `BigTableClient` or a
+/// `RocksDbClient`.
 #[derive(Clone)]
-pub struct KvRpcServer {
+pub struct KvRpcServer<C> {
     chain_id: ChainIdentifier,
-    client: BigTableClient,
+    client: C,
     server_version: Option<ServerVersion>,
     checkpoint_bucket: Option<String>,
     cache: Arc<RwLock<Option<GetServiceInfoResponse>>>,
 }
 
-impl KvRpcServer {
-    pub async fn new(
+impl KvRpcServer<BigTableClient> {
+    pub async fn new_bigtable(
         instance_id: String,
         app_profile_id: Option<String>,
         checkpoint_bucket: Option<String>,
         server_version: Option<ServerVersion>,
         registry: &Registry,
     ) -> anyhow::Result<Self> {
-        let mut client = BigTableClient::new_remote(
+        let client = BigTableClient::new_remote(
             instance_id,
             false,
             None,
@@ -40,6 +46,41 @@ impl KvRpcServer {
             app_profile_id,
         )
         .await?;
+        Self::new(client, checkpoint_bucket, server_version).await
+    }
+}
+
+impl KvRpcServer<RocksDbClient> {
+    /// Serve from the RocksDB database at `path`, which is populated by a separate
+    /// `sui-kvstore --rocksdb-path <path> ingestion` process.
+    pub async fn new_rocksdb(
+        path: PathBuf,
+        checkpoint_bucket: Option<String>,
+        server_version: Option<ServerVersion>,
+    ) -> anyhow::Result<Self> {
+        let client = RocksDbClient::new_secondary(path);
+        let secondary = client.clone();
+        tokio::spawn(async move {
+            loop {
+                if let Err(e) = secondary.try_catch_up_with_primary() {
+                    error!("Failed to catch up with the primary database: {:?}", e);
+                }
+                sleep(ROCKSDB_CATCH_UP_INTERVAL).await;
+            }
+        });
+        Self::new(client, checkpoint_bucket, server_version).await
+    }
+}
+
+impl<C> KvRpcServer<C>
+where
+    C: KeyValueStoreReader + Clone + Send + Sync + 'static,
+{
+    pub async fn new(
+        mut client: C,
+        checkpoint_bucket: Option<String>,
+        server_version: Option<ServerVersion>,
+    ) -> anyhow::Result<Self> {
         let genesis = client
             .get_checkpoints(&[0])
             .await?
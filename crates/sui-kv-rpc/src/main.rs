@@ -7,8 +7,10 @@ use axum::routing::get;
 use clap::Parser;
 use mysten_network::callback::CallbackLayer;
 use prometheus::Registry;
+use std::path::PathBuf;
 use std::sync::Arc;
 use sui_kv_rpc::KvRpcServer;
+use sui_rpc::proto::sui::rpc::v2::ledger_service_server::LedgerServiceServer;
 use sui_rpc_api::{RpcMetrics, RpcMetricsMakeCallbackHandler, ServerVersion};
 use telemetry_subscribers::TelemetryConfig;
 use tonic::transport::{Identity, Server, ServerTlsConfig};
@@ -17,13 +19,15 @@ bin_version::bin_version!();
 
 #[derive(Parser)]
 struct App {
-    credentials: String,
-    instance_id: String,
-    #[clap(default_value = "[::1]:8000")]
+    #[clap(required_unless_present = "rocksdb_path")]
+    credentials: Option<String>,
+    #[clap(required_unless_present = "rocksdb_path")]
+    instance_id: Option<String>,
+    #[clap(long = "address", default_value = "[::1]:8000")]
     address: String,
-    #[clap(default_value = "127.0.0.1")]
+    #[clap(long = "metrics-host", default_value = "127.0.0.1")]
     metrics_host: String,
-    #[clap(default_value_t = 9184)]
+    #[clap(long = "metrics-port", default_value_t = 9184)]
     metrics_port: usize,
     #[clap(long = "tls-cert", default_value = "")]
     tls_cert: String,
@@ -33,6 +37,13 @@ struct App {
     app_profile_id: Option<String>,
     #[clap(long = "checkpoint-bucket")]
     checkpoint_bucket: Option<String>,
+    /// Serve from the RocksDB database at this path, written by `sui-kvstore ingestion`, instead
+    /// of Bigtable.
+    #[clap(
+        long = "rocksdb-path",
+        conflicts_with_all = ["credentials", "instance_id", "app_profile_id"]
+    )]
+    rocksdb_path: Option<PathBuf>,
 }
 
 async fn health_check() -> &'static str {
@@ -43,23 +54,17 @@ async fn health_check() -> &'static str {
 async fn main() -> Result<()> {
     let _guard = TelemetryConfig::new().with_env().init();
     let app = App::parse();
-    unsafe {
-        std::env::set_var("GOOGLE_APPLICATION_CREDENTIALS", app.credentials.clone());
-    };
+    if let Some(credentials) = &app.credentials {
+        unsafe {
+            std::env::set_var("GOOGLE_APPLICATION_CREDENTIALS", credentials);
+        };
+    }
     let server_version = Some(ServerVersion::new("sui-kv-rpc", VERSION));
     let registry_service = mysten_metrics::start_prometheus_server(
         format!("{}:{}", app.metrics_host, app.metrics_port).parse()?,
     );
     let registry: Registry = registry_service.default_registry();
     mysten_metrics::init_metrics(&registry);
-    let server = KvRpcServer::new(
-        app.instance_id,
-        app.app_profile_id,
-        app.checkpoint_bucket,
-        server_version,
-        &registry,
-    )
-    .await?;
     let addr = app.address.parse()?;
     let mut builder = Server::builder();
     if !app.tls_cert.is_empty() && !app.tls_key.is_empty() {
@@ -91,16 +96,31 @@ async fn main() -> Result<()> {
             .await
             .expect("healh check service failed");
     });
-    builder
+    let router = builder
         .layer(CallbackLayer::new(RpcMetricsMakeCallbackHandler::new(
             Arc::new(RpcMetrics::new(&registry)),
         )))
-        .add_service(
-            sui_rpc::proto::sui::rpc::v2::ledger_service_server::LedgerServiceServer::new(server),
-        )
         .add_service(reflection_v1)
-        .add_service(reflection_v1alpha)
-        .serve(addr)
-        .await?;
+        .add_service(reflection_v1alpha);
+    let router = match (app.rocksdb_path, app.instance_id) {
+        (Some(path), _) => {
+            let server =
+                KvRpcServer::new_rocksdb(path, app.checkpoint_bucket, server_version).await?;
+            router.add_service(LedgerServiceServer::new(server))
+        }
+        (None, Some(instance_id)) => {
+            let server = KvRpcServer::new_bigtable(
+                instance_id,
+                app.app_profile_id,
+                app.checkpoint_bucket,
+                server_version,
+                &registry,
+            )
+            .await?;
+            router.add_service(LedgerServiceServer::new(server))
+        }
+        (None, None) => unreachable!("clap requires an instance id or a RocksDB path"),
+    };
+    router.serve(addr).await?;
     Ok(())
 }
@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use sui_data_ingestion_core::{CheckpointReader, create_remote_store_client};
-use sui_kvstore::{BigTableClient, KeyValueStoreReader};
+use sui_kvstore::KeyValueStoreReader;
 use sui_rpc::field::{FieldMask, FieldMaskTree, FieldMaskUtil};
 use sui_rpc::merge::Merge;
 use sui_rpc::proto::sui::rpc::v2::get_checkpoint_request::CheckpointId;
@@ -15,7 +15,7 @@ use sui_types::digests::CheckpointDigest;
 pub const READ_MASK_DEFAULT: &str = "sequence_number,digest";
 
 pub async fn get_checkpoint(
-    mut client: BigTableClient,
+    mut client: impl KeyValueStoreReader,
     request: GetCheckpointRequest,
     checkpoint_bucket: Option<String>,
 ) -> Result<GetCheckpointResponse, RpcError> {
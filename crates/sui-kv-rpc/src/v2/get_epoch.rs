@@ -1,7 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use sui_kvstore::{BigTableClient, KeyValueStoreReader};
+use sui_kvstore::KeyValueStoreReader;
 use sui_protocol_config::{Chain, ProtocolConfig};
 use sui_rpc::field::{FieldMask, FieldMaskTree, FieldMaskUtil};
 use sui_rpc::merge::Merge;
@@ -19,7 +19,7 @@ use sui_types::sui_system_state::SuiSystemStateTrait;
 pub const READ_MASK_DEFAULT: &str = "epoch,first_checkpoint,last_checkpoint,start,end,reference_gas_price,protocol_config.protocol_version";
 
 pub async fn get_epoch(
-    mut client: BigTableClient,
+    mut client: impl KeyValueStoreReader,
     request: GetEpochRequest,
     chain: Chain,
 ) -> sui_rpc_api::Result<GetEpochResponse> {
@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use std::collections::HashMap;
-use sui_kvstore::{BigTableClient, KeyValueStoreReader};
+use sui_kvstore::KeyValueStoreReader;
 use sui_rpc::merge::Merge;
 use sui_rpc::proto::sui::rpc::v2::BatchGetObjectsRequest;
 use sui_rpc::proto::sui::rpc::v2::BatchGetObjectsResponse;
@@ -16,7 +16,7 @@ use sui_rpc_api::{
 use sui_types::storage::ObjectKey;
 
 pub(crate) async fn get_object(
-    mut client: BigTableClient,
+    mut client: impl KeyValueStoreReader,
     GetObjectRequest {
         object_id,
         version,
@@ -45,7 +45,7 @@ pub(crate) async fn get_object(
 }
 
 pub(crate) async fn batch_get_objects(
-    mut client: BigTableClient,
+    mut client: impl KeyValueStoreReader,
     BatchGetObjectsRequest {
         requests,
         read_mask,
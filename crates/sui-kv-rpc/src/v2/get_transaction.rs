@@ -3,7 +3,7 @@
 
 use std::collections::HashMap;
 use std::str::FromStr;
-use sui_kvstore::{BigTableClient, KeyValueStoreReader, TransactionData};
+use sui_kvstore::{KeyValueStoreReader, TransactionData};
 use sui_rpc::field::{FieldMask, FieldMaskTree, FieldMaskUtil};
 use sui_rpc::merge::Merge;
 use sui_rpc::proto::sui::rpc::v2::{
@@ -20,7 +20,7 @@ use sui_types::base_types::TransactionDigest;
 pub const READ_MASK_DEFAULT: &str = "digest";
 
 pub async fn get_transaction(
-    mut client: BigTableClient,
+    mut client: impl KeyValueStoreReader,
     request: GetTransactionRequest,
 ) -> Result<GetTransactionResponse, RpcError> {
     let transaction_digest = request
@@ -62,7 +62,7 @@ pub async fn get_transaction(
 }
 
 pub async fn batch_get_transactions(
-    mut client: BigTableClient,
+    mut client: impl KeyValueStoreReader,
     BatchGetTransactionsRequest {
         digests, read_mask, ..
     }: BatchGetTransactionsRequest,
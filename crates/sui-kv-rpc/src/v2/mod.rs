@@ -1,7 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use sui_kvstore::{BigTableClient, KeyValueStoreReader};
+use sui_kvstore::KeyValueStoreReader;
 use sui_rpc::proto::sui::rpc::v2::{
     BatchGetObjectsRequest, BatchGetObjectsResponse, BatchGetTransactionsRequest,
     BatchGetTransactionsResponse, GetCheckpointRequest, GetCheckpointResponse, GetEpochRequest,
@@ -22,7 +22,10 @@ mod get_object;
 mod get_transaction;
 
 #[tonic::async_trait]
-impl LedgerService for KvRpcServer {
+impl<C> LedgerService for KvRpcServer<C>
+where
+    C: KeyValueStoreReader + Clone + Send + Sync + 'static,
+{
     async fn get_service_info(
         &self,
         _: tonic::Request<GetServiceInfoRequest>,
@@ -114,7 +117,7 @@ impl LedgerService for KvRpcServer {
 }
 
 pub(crate) async fn get_service_info(
-    mut client: BigTableClient,
+    mut client: impl KeyValueStoreReader,
     chain_id: ChainIdentifier,
     server_version: Option<ServerVersion>,
 ) -> Result<GetServiceInfoResponse, RpcError> {
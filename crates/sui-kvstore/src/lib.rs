@@ -1,11 +1,14 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 mod bigtable;
+mod rocksdb;
+mod worker;
 use anyhow::Result;
 use async_trait::async_trait;
 pub use bigtable::client::BigTableClient;
 pub use bigtable::progress_store::BigTableProgressStore;
-pub use bigtable::worker::KvWorker;
+pub use rocksdb::client::RocksDbClient;
+pub use rocksdb::progress_store::RocksDbProgressStore;
 use serde::{Deserialize, Serialize};
 use sui_types::base_types::ObjectID;
 use sui_types::committee::EpochId;
@@ -21,6 +24,7 @@ use sui_types::messages_consensus::TimestampMs;
 use sui_types::object::Object;
 use sui_types::storage::{EpochInfo, ObjectKey};
 use sui_types::transaction::Transaction;
+pub use worker::KvWorker;
 
 #[async_trait]
 pub trait KeyValueStoreReader {
@@ -4,9 +4,15 @@ use anyhow::Result;
 use clap::{Parser, Subcommand};
 use prometheus::Registry;
 use std::io::{self, Write};
+use std::path::PathBuf;
 use std::str::FromStr;
-use sui_data_ingestion_core::{DataIngestionMetrics, IndexerExecutor, ReaderOptions, WorkerPool};
-use sui_kvstore::{BigTableClient, BigTableProgressStore, KeyValueStoreReader, KvWorker};
+use sui_data_ingestion_core::{
+    DataIngestionMetrics, IndexerExecutor, ProgressStore, ReaderOptions, Worker, WorkerPool,
+};
+use sui_kvstore::{
+    BigTableClient, BigTableProgressStore, KeyValueStoreReader, KvWorker, RocksDbClient,
+    RocksDbProgressStore,
+};
 use sui_types::base_types::ObjectID;
 use sui_types::digests::TransactionDigest;
 use sui_types::storage::ObjectKey;
@@ -15,7 +21,12 @@ use tokio::sync::oneshot;
 
 #[derive(Parser)]
 struct App {
-    instance_id: String,
+    /// The Bigtable instance to use.
+    #[arg(required_unless_present = "rocksdb_path")]
+    instance_id: Option<String>,
+    /// Use the RocksDB database at this path instead of Bigtable.
+    #[arg(long, conflicts_with = "instance_id")]
+    rocksdb_path: Option<PathBuf>,
     #[command(subcommand)]
     command: Option<Command>,
 }
@@ -44,10 +55,20 @@ pub enum Entry {
 async fn main() -> Result<()> {
     let _guard = TelemetryConfig::new().with_env().init();
     let app = App::parse();
-    match app.command {
-        Some(Command::Ingestion { network }) => {
+    match (app.command, app.rocksdb_path, app.instance_id) {
+        (Some(Command::Ingestion { network }), Some(path), _) => {
+            let client = RocksDbClient::new(path);
+            ingest(
+                RocksDbProgressStore::new(client.clone()),
+                KvWorker { client },
+                "rocksdb",
+                network,
+            )
+            .await?;
+        }
+        (Some(Command::Ingestion { network }), None, Some(instance_id)) => {
             let client = BigTableClient::new_remote(
-                app.instance_id,
+                instance_id,
                 false,
                 None,
                 "ingestion".to_string(),
@@ -55,64 +76,82 @@ async fn main() -> Result<()> {
                 None,
             )
             .await?;
-            let (_exit_sender, exit_receiver) = oneshot::channel();
-            let mut executor = IndexerExecutor::new(
+            ingest(
                 BigTableProgressStore::new(client.clone()),
-                1,
-                DataIngestionMetrics::new(&Registry::new()),
-            );
-            let worker_pool = WorkerPool::new(KvWorker { client }, "bigtable".to_string(), 50);
-            executor.register(worker_pool).await?;
-            executor
-                .run(
-                    tempfile::tempdir()?.keep(),
-                    Some(format!("https://checkpoints.{}.sui.io", network)),
-                    vec![],
-                    ReaderOptions::default(),
-                    exit_receiver,
-                )
-                .await?;
-        }
-        Some(Command::Fetch { entry }) => {
-            let mut client = BigTableClient::new_remote(
-                app.instance_id,
-                true,
-                None,
-                "cli".to_string(),
-                None,
-                None,
+                KvWorker { client },
+                "bigtable",
+                network,
             )
             .await?;
-            let result = match entry {
-                Entry::Epoch { id } => client.get_epoch(id).await?.map(|e| bcs::to_bytes(&e)),
-                Entry::Object { id, version } => {
-                    let objects = client
-                        .get_objects(&[ObjectKey(ObjectID::from_str(&id)?, version.into())])
-                        .await?;
-                    objects.first().map(bcs::to_bytes)
-                }
-                Entry::Checkpoint { id } => {
-                    let checkpoints = client.get_checkpoints(&[id]).await?;
-                    checkpoints.first().map(bcs::to_bytes)
-                }
-                Entry::Transaction { id } => {
-                    let transactions = client
-                        .get_transactions(&[TransactionDigest::from_str(&id)?])
-                        .await?;
-                    transactions.first().map(bcs::to_bytes)
-                }
-                Entry::Watermark => {
-                    let watermark = client.get_latest_checkpoint().await?;
-                    println!("watermark is {}", watermark);
-                    return Ok(());
-                }
-            };
-            match result {
-                Some(bytes) => io::stdout().write_all(&bytes?)?,
-                None => println!("not found"),
-            }
         }
-        None => println!("no command provided"),
+        (Some(Command::Fetch { entry }), Some(path), _) => {
+            fetch(RocksDbClient::new_secondary(path), entry).await?;
+        }
+        (Some(Command::Fetch { entry }), None, Some(instance_id)) => {
+            let client =
+                BigTableClient::new_remote(instance_id, true, None, "cli".to_string(), None, None)
+                    .await?;
+            fetch(client, entry).await?;
+        }
+        (Some(_), None, None) => unreachable!("clap requires an instance id or a RocksDB path"),
+        (None, _, _) => println!("no command provided"),
+    }
+    Ok(())
+}
+
+async fn ingest<P, W>(progress_store: P, worker: W, task_name: &str, network: String) -> Result<()>
+where
+    P: ProgressStore,
+    W: Worker + 'static,
+{
+    let (_exit_sender, exit_receiver) = oneshot::channel();
+    let mut executor = IndexerExecutor::new(
+        progress_store,
+        1,
+        DataIngestionMetrics::new(&Registry::new()),
+    );
+    let worker_pool = WorkerPool::new(worker, task_name.to_string(), 50);
+    executor.register(worker_pool).await?;
+    executor
+        .run(
+            tempfile::tempdir()?.keep(),
+            Some(format!("https://checkpoints.{}.sui.io", network)),
+            vec![],
+            ReaderOptions::default(),
+            exit_receiver,
+        )
+        .await?;
+    Ok(())
+}
+
+async fn fetch(mut client: impl KeyValueStoreReader, entry: Entry) -> Result<()> {
+    let result = match entry {
+        Entry::Epoch { id } => client.get_epoch(id).await?.map(|e| bcs::to_bytes(&e)),
+        Entry::Object { id, version } => {
+            let objects = client
+                .get_objects(&[ObjectKey(ObjectID::from_str(&id)?, version.into())])
+                .await?;
+            objects.first().map(bcs::to_bytes)
+        }
+        Entry::Checkpoint { id } => {
+            let checkpoints = client.get_checkpoints(&[id]).await?;
+            checkpoints.first().map(bcs::to_bytes)
+        }
+        Entry::Transaction { id } => {
+            let transactions = client
+                .get_transactions(&[TransactionDigest::from_str(&id)?])
+                .await?;
+            transactions.first().map(bcs::to_bytes)
+        }
+        Entry::Watermark => {
+            let watermark = client.get_latest_checkpoint().await?;
+            println!("watermark is {}", watermark);
+            return Ok(());
+        }
+    };
+    match result {
+        Some(bytes) => io::stdout().write_all(&bytes?)?,
+        None => println!("not found"),
     }
     Ok(())
 }
@@ -0,0 +1,452 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use sui_types::{
+    base_types::{EpochId, ObjectID, TransactionDigest},
+    digests::CheckpointDigest,
+    full_checkpoint_content::CheckpointData,
+    messages_checkpoint::{CheckpointSequenceNumber, CheckpointSummary},
+    messages_consensus::TimestampMs,
+    object::Object,
+    storage::{EpochInfo, ObjectKey},
+};
+use typed_store::DBMapUtils;
+use typed_store::Map;
+use typed_store::rocks::{DBMap, MetricConf};
+
+use crate::{
+    Checkpoint, KeyValueStoreReader, KeyValueStoreWriter, TransactionData, TransactionEventsData,
+};
+
+/// The tables of the KV store, mirroring the tables of the Bigtable instance.
+#[derive(DBMapUtils)]
+struct KvStoreTables {
+    /// Objects by ID and version.
+    objects: DBMap<ObjectKey, Object>,
+    /// Transactions, with their effects and events, by digest.
+    transactions: DBMap<TransactionDigest, TransactionData>,
+    /// Certified checkpoints, with their contents, by sequence number.
+    checkpoints: DBMap<CheckpointSequenceNumber, Checkpoint>,
+    checkpoints_by_digest: DBMap<CheckpointDigest, CheckpointSequenceNumber>,
+    epochs: DBMap<EpochId, EpochInfo>,
+    /// The sequence number of the next checkpoint to ingest.
+    watermark: DBMap<(), CheckpointSequenceNumber>,
+}
+
+/// A KV store backed by a local RocksDB database.
+#[derive(Clone)]
+pub struct RocksDbClient {
+    tables: Arc<KvStoreTables>,
+}
+
+impl RocksDbClient {
+    /// Open the database at `path` for reading and writing. Only one process can do this at a
+    /// time.
+    pub fn new(path: PathBuf) -> Self {
+        let tables =
+            KvStoreTables::open_tables_read_write(path, MetricConf::new("kvstore"), None, None);
+        Self {
+            tables: Arc::new(tables),
+        }
+    }
+
+    /// Open the database at `path` as a secondary instance, which can read the database while
+    /// another process writes to it. The secondary only sees the writes made before it was opened
+    /// or last caught up, see [Self::try_catch_up_with_primary].
+    pub fn new_secondary(path: PathBuf) -> Self {
+        let KvStoreTablesReadOnly {
+            objects,
+            transactions,
+            checkpoints,
+            checkpoints_by_digest,
+            epochs,
+            watermark,
+        } = KvStoreTables::get_read_only_handle(path, None, None, MetricConf::new("kvstore"));
+        let tables = KvStoreTables {
+            objects,
+            transactions,
+            checkpoints,
+            checkpoints_by_digest,
+            epochs,
+            watermark,
+        };
+        Self {
+            tables: Arc::new(tables),
+        }
+    }
+
+    /// Make the writes of the primary instance visible to this secondary instance.
+    pub fn try_catch_up_with_primary(&self) -> Result<()> {
+        // All tables share the same database, so catching up one of them catches up all of them.
+        self.tables.watermark.try_catch_up_with_primary()?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl KeyValueStoreWriter for RocksDbClient {
+    async fn save_objects(&mut self, objects: &[&Object], _: TimestampMs) -> Result<()> {
+        let mut batch = self.tables.objects.batch();
+        batch.insert_batch(
+            &self.tables.objects,
+            objects
+                .iter()
+                .map(|object| (ObjectKey(object.id(), object.version()), *object)),
+        )?;
+        batch.write()?;
+        Ok(())
+    }
+
+    async fn save_transactions(&mut self, transactions: &[TransactionData]) -> Result<()> {
+        let mut batch = self.tables.transactions.batch();
+        batch.insert_batch(
+            &self.tables.transactions,
+            transactions
+                .iter()
+                .map(|transaction| (transaction.transaction.digest(), transaction)),
+        )?;
+        batch.write()?;
+        Ok(())
+    }
+
+    async fn save_checkpoint(&mut self, checkpoint: &CheckpointData) -> Result<()> {
+        let summary = &checkpoint.checkpoint_summary;
+        let sequence_number = summary.sequence_number;
+        let stored = Checkpoint {
+            summary: summary.data().clone(),
+            contents: checkpoint.checkpoint_contents.clone(),
+            signatures: summary.auth_sig().clone(),
+        };
+
+        let mut batch = self.tables.checkpoints.batch();
+        batch.insert_batch(&self.tables.checkpoints, [(sequence_number, stored)])?;
+        batch.insert_batch(
+            &self.tables.checkpoints_by_digest,
+            [(*summary.digest(), sequence_number)],
+        )?;
+        batch.write()?;
+        Ok(())
+    }
+
+    async fn save_watermark(&mut self, watermark: CheckpointSequenceNumber) -> Result<()> {
+        self.tables.watermark.insert(&(), &watermark)?;
+        Ok(())
+    }
+
+    async fn save_epoch(&mut self, epoch: EpochInfo) -> Result<()> {
+        self.tables.epochs.insert(&epoch.epoch, &epoch)?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl KeyValueStoreReader for RocksDbClient {
+    async fn get_objects(&mut self, objects: &[ObjectKey]) -> Result<Vec<Object>> {
+        let objects = self.tables.objects.multi_get(objects)?;
+        Ok(objects.into_iter().flatten().collect())
+    }
+
+    async fn get_transactions(
+        &mut self,
+        transactions: &[TransactionDigest],
+    ) -> Result<Vec<TransactionData>> {
+        let transactions = self.tables.transactions.multi_get(transactions)?;
+        Ok(transactions.into_iter().flatten().collect())
+    }
+
+    async fn get_checkpoints(
+        &mut self,
+        sequence_numbers: &[CheckpointSequenceNumber],
+    ) -> Result<Vec<Checkpoint>> {
+        let checkpoints = self.tables.checkpoints.multi_get(sequence_numbers)?;
+        Ok(checkpoints.into_iter().flatten().collect())
+    }
+
+    async fn get_checkpoint_by_digest(
+        &mut self,
+        digest: CheckpointDigest,
+    ) -> Result<Option<Checkpoint>> {
+        let Some(sequence_number) = self.tables.checkpoints_by_digest.get(&digest)? else {
+            return Ok(None);
+        };
+        Ok(self.tables.checkpoints.get(&sequence_number)?)
+    }
+
+    async fn get_latest_checkpoint(&mut self) -> Result<CheckpointSequenceNumber> {
+        Ok(self.tables.watermark.get(&())?.unwrap_or(0))
+    }
+
+    async fn get_latest_checkpoint_summary(&mut self) -> Result<Option<CheckpointSummary>> {
+        let sequence_number = self.get_latest_checkpoint().await?;
+        if sequence_number == 0 {
+            return Ok(None);
+        }
+
+        let checkpoint = self.tables.checkpoints.get(&(sequence_number - 1))?;
+        Ok(checkpoint.map(|checkpoint| checkpoint.summary))
+    }
+
+    async fn get_latest_object(&mut self, object_id: &ObjectID) -> Result<Option<Object>> {
+        let latest = self
+            .tables
+            .objects
+            .reversed_safe_iter_with_bounds(
+                Some(ObjectKey::min_for_id(object_id)),
+                Some(ObjectKey::max_for_id(object_id)),
+            )?
+            .next()
+            .transpose()?;
+        Ok(latest.map(|(_, object)| object))
+    }
+
+    async fn get_epoch(&mut self, epoch_id: EpochId) -> Result<Option<EpochInfo>> {
+        Ok(self.tables.epochs.get(&epoch_id)?)
+    }
+
+    async fn get_latest_epoch(&mut self) -> Result<Option<EpochInfo>> {
+        let latest = self
+            .tables
+            .epochs
+            .reversed_safe_iter_with_bounds(None, None)?
+            .next()
+            .transpose()?;
+        Ok(latest.map(|(_, epoch)| epoch))
+    }
+
+    async fn get_events_for_transactions(
+        &mut self,
+        transaction_digests: &[TransactionDigest],
+    ) -> Result<Vec<(TransactionDigest, TransactionEventsData)>> {
+        let transactions = self.tables.transactions.multi_get(transaction_digests)?;
+        Ok(transaction_digests
+            .iter()
+            .zip(transactions)
+            .filter_map(|(digest, transaction)| {
+                let transaction = transaction?;
+                let events = TransactionEventsData {
+                    events: transaction.events.map(|e| e.data).unwrap_or_default(),
+                    timestamp_ms: transaction.timestamp,
+                };
+                Some((*digest, events))
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_types::base_types::SequenceNumber;
+    use sui_types::event::Event;
+    use sui_types::object::Owner;
+    use sui_types::test_checkpoint_data_builder::TestCheckpointBuilder;
+
+    /// A checkpoint with two transactions, only the first of which emits an event.
+    fn test_checkpoint(sequence_number: u64) -> CheckpointData {
+        TestCheckpointBuilder::new(sequence_number)
+            .with_timestamp_ms(sequence_number * 1000)
+            .start_transaction(0)
+            .create_owned_object(0)
+            .with_events(vec![Event::random_for_testing()])
+            .finish_transaction()
+            .start_transaction(1)
+            .create_owned_object(1)
+            .finish_transaction()
+            .build_checkpoint()
+            .into()
+    }
+
+    /// The transactions of `checkpoint`, as the worker stores them.
+    fn transactions(checkpoint: &CheckpointData) -> Vec<TransactionData> {
+        checkpoint
+            .transactions
+            .iter()
+            .map(|transaction| TransactionData {
+                transaction: transaction.transaction.clone(),
+                effects: transaction.effects.clone(),
+                events: transaction.events.clone(),
+                checkpoint_number: checkpoint.checkpoint_summary.sequence_number,
+                timestamp: checkpoint.checkpoint_summary.timestamp_ms,
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_watermark_objects_and_epochs() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut client = RocksDbClient::new(dir.path().to_path_buf());
+
+        assert_eq!(client.get_latest_checkpoint().await.unwrap(), 0);
+        assert!(
+            client
+                .get_latest_checkpoint_summary()
+                .await
+                .unwrap()
+                .is_none()
+        );
+        client.save_watermark(42).await.unwrap();
+        assert_eq!(client.get_latest_checkpoint().await.unwrap(), 42);
+
+        let id = ObjectID::random();
+        let other = Object::immutable_with_id_for_testing(ObjectID::random());
+        let versions: Vec<_> = [1, 5, 3]
+            .into_iter()
+            .map(|version| {
+                Object::with_id_owner_version_for_testing(
+                    id,
+                    SequenceNumber::from_u64(version),
+                    Owner::Immutable,
+                )
+            })
+            .collect();
+        let mut objects: Vec<_> = versions.iter().collect();
+        objects.push(&other);
+        client.save_objects(&objects, 0).await.unwrap();
+
+        let latest = client.get_latest_object(&id).await.unwrap().unwrap();
+        assert_eq!(latest.version(), SequenceNumber::from_u64(5));
+        let found = client
+            .get_objects(&[
+                ObjectKey(id, SequenceNumber::from_u64(3)),
+                ObjectKey(id, SequenceNumber::from_u64(4)),
+            ])
+            .await
+            .unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].version(), SequenceNumber::from_u64(3));
+        assert!(
+            client
+                .get_latest_object(&ObjectID::random())
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        assert!(client.get_latest_epoch().await.unwrap().is_none());
+        for epoch in [0, 2, 1] {
+            let info = EpochInfo {
+                epoch,
+                ..Default::default()
+            };
+            client.save_epoch(info).await.unwrap();
+        }
+        assert_eq!(client.get_latest_epoch().await.unwrap().unwrap().epoch, 2);
+        assert_eq!(client.get_epoch(1).await.unwrap().unwrap().epoch, 1);
+        assert!(client.get_epoch(3).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_transactions_and_events() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut client = RocksDbClient::new(dir.path().to_path_buf());
+
+        let checkpoint = test_checkpoint(3);
+        let transactions = transactions(&checkpoint);
+        client.save_transactions(&transactions).await.unwrap();
+
+        let emitter = *transactions[0].transaction.digest();
+        let quiet = *transactions[1].transaction.digest();
+        let missing = TransactionDigest::random();
+
+        // Missing transactions are skipped, and the rest are returned in the order requested.
+        let found = client
+            .get_transactions(&[quiet, missing, emitter])
+            .await
+            .unwrap();
+        let digests: Vec<_> = found.iter().map(|t| *t.transaction.digest()).collect();
+        assert_eq!(digests, vec![quiet, emitter]);
+        assert!(found[0].events.is_none());
+        assert_eq!(found[1].events.as_ref().unwrap().data.len(), 1);
+        assert_eq!(found[1].effects, transactions[0].effects);
+        assert_eq!(found[1].checkpoint_number, 3);
+        assert_eq!(found[1].timestamp, 3000);
+
+        let events = client
+            .get_events_for_transactions(&[emitter, missing, quiet])
+            .await
+            .unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].0, emitter);
+        assert_eq!(
+            events[0].1.events,
+            transactions[0].events.as_ref().unwrap().data
+        );
+        assert_eq!(events[0].1.timestamp_ms, 3000);
+        assert_eq!(events[1].0, quiet);
+        assert!(events[1].1.events.is_empty());
+        assert_eq!(events[1].1.timestamp_ms, 3000);
+    }
+
+    #[tokio::test]
+    async fn test_checkpoints() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut client = RocksDbClient::new(dir.path().to_path_buf());
+
+        let checkpoints = [test_checkpoint(0), test_checkpoint(1)];
+        for checkpoint in &checkpoints {
+            client.save_checkpoint(checkpoint).await.unwrap();
+        }
+        client.save_watermark(2).await.unwrap();
+
+        let found = client.get_checkpoints(&[1, 5, 0]).await.unwrap();
+        assert_eq!(found.len(), 2);
+        for (found, expected) in found.iter().zip([&checkpoints[1], &checkpoints[0]]) {
+            assert_eq!(&found.summary, expected.checkpoint_summary.data());
+            assert_eq!(found.contents, expected.checkpoint_contents);
+            assert_eq!(
+                found.signatures.epoch,
+                expected.checkpoint_summary.auth_sig().epoch
+            );
+        }
+
+        let digest = *checkpoints[0].checkpoint_summary.digest();
+        let by_digest = client.get_checkpoint_by_digest(digest).await.unwrap();
+        assert_eq!(by_digest.unwrap().summary.sequence_number, 0);
+        assert!(
+            client
+                .get_checkpoint_by_digest(CheckpointDigest::random())
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        let latest = client.get_latest_checkpoint_summary().await.unwrap();
+        assert_eq!(latest.unwrap().sequence_number, 1);
+    }
+
+    #[tokio::test]
+    async fn test_secondary_catches_up_with_primary() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut primary = RocksDbClient::new(dir.path().to_path_buf());
+        primary.save_checkpoint(&test_checkpoint(0)).await.unwrap();
+        primary.save_watermark(1).await.unwrap();
+
+        let mut secondary = RocksDbClient::new_secondary(dir.path().to_path_buf());
+        assert_eq!(secondary.get_latest_checkpoint().await.unwrap(), 1);
+
+        // Writes made after the secondary was opened are only visible once it catches up.
+        let checkpoint = test_checkpoint(1);
+        let transactions = transactions(&checkpoint);
+        primary.save_transactions(&transactions).await.unwrap();
+        primary.save_checkpoint(&checkpoint).await.unwrap();
+        primary.save_watermark(2).await.unwrap();
+        assert_eq!(secondary.get_latest_checkpoint().await.unwrap(), 1);
+        assert!(secondary.get_checkpoints(&[1]).await.unwrap().is_empty());
+
+        secondary.try_catch_up_with_primary().unwrap();
+        assert_eq!(secondary.get_latest_checkpoint().await.unwrap(), 2);
+        let latest = secondary.get_latest_checkpoint_summary().await.unwrap();
+        assert_eq!(latest.unwrap().sequence_number, 1);
+        let digests: Vec<_> = transactions
+            .iter()
+            .map(|t| *t.transaction.digest())
+            .collect();
+        let found = secondary.get_transactions(&digests).await.unwrap();
+        assert_eq!(found.len(), 2);
+    }
+}
@@ -0,0 +1,29 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{KeyValueStoreReader, KeyValueStoreWriter, RocksDbClient};
+use anyhow::Result;
+use async_trait::async_trait;
+use sui_data_ingestion_core::ProgressStore;
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+
+pub struct RocksDbProgressStore {
+    client: RocksDbClient,
+}
+
+impl RocksDbProgressStore {
+    pub fn new(client: RocksDbClient) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl ProgressStore for RocksDbProgressStore {
+    async fn load(&mut self, _: String) -> Result<CheckpointSequenceNumber> {
+        self.client.get_latest_checkpoint().await
+    }
+
+    async fn save(&mut self, _: String, checkpoint_number: CheckpointSequenceNumber) -> Result<()> {
+        self.client.save_watermark(checkpoint_number).await
+    }
+}
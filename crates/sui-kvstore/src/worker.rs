@@ -1,17 +1,20 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{BigTableClient, KeyValueStoreReader, KeyValueStoreWriter, TransactionData};
+use crate::{KeyValueStoreReader, KeyValueStoreWriter, TransactionData};
 use async_trait::async_trait;
 use sui_data_ingestion_core::Worker;
 use sui_types::full_checkpoint_content::CheckpointData;
 
-pub struct KvWorker {
-    pub client: BigTableClient,
+pub struct KvWorker<C> {
+    pub client: C,
 }
 
 #[async_trait]
-impl Worker for KvWorker {
+impl<C> Worker for KvWorker<C>
+where
+    C: KeyValueStoreReader + KeyValueStoreWriter + Clone + Send + Sync,
+{
     type Result = ();
 
     async fn process_checkpoint(&self, checkpoint: &CheckpointData) -> anyhow::Result<()> {
@@ -4,8 +4,11 @@
 use anyhow::{Result, anyhow};
 use serde::{Deserialize, Serialize};
 use std::fs;
+use std::net::SocketAddr;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 use sui_config::object_storage_config::ObjectStoreConfig;
+use sui_types::base_types::SuiAddress;
 use url::Url;
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
@@ -16,6 +19,23 @@ pub struct Config {
     pub archive_store_config: Option<ObjectStoreConfig>,
     pub graphql_url: Option<String>,
     pub genesis_filename: String,
+    #[serde(default)]
+    pub follow: FollowConfig,
+}
+
+/// Configuration for the `follow` mode, which tracks the chain and authenticated event streams.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct FollowConfig {
+    /// Authenticated event streams (identified by their stream ID) to maintain heads for.
+    #[serde(default)]
+    pub streams: Vec<SuiAddress>,
+    /// Address to serve verified events on (default: 127.0.0.1:9010).
+    pub listen_address: Option<SocketAddr>,
+    /// How long to wait before checking again for a checkpoint that is not available yet
+    /// (default: 1000ms).
+    pub poll_interval_ms: Option<u64>,
+    /// Number of verified events to keep in memory, per stream (default: 10000).
+    pub max_events_per_stream: Option<usize>,
 }
 
 impl Config {
@@ -58,6 +78,25 @@ impl Config {
     pub fn genesis_path(&self) -> PathBuf {
         self.checkpoint_summary_dir.join(&self.genesis_filename)
     }
+
+    pub fn follow_state_path(&self) -> PathBuf {
+        self.checkpoint_summary_dir.join("follow_state.bcs")
+    }
+}
+
+impl FollowConfig {
+    pub fn listen_address(&self) -> SocketAddr {
+        self.listen_address
+            .unwrap_or_else(|| SocketAddr::from(([127, 0, 0, 1], 9010)))
+    }
+
+    pub fn poll_interval(&self) -> Duration {
+        Duration::from_millis(self.poll_interval_ms.unwrap_or(1000))
+    }
+
+    pub fn max_events_per_stream(&self) -> usize {
+        self.max_events_per_stream.unwrap_or(10_000)
+    }
 }
 
 #[cfg(test)]
@@ -79,6 +118,7 @@ mod tests {
             }),
             graphql_url: Some("http://localhost:9003".to_string()),
             genesis_filename: "genesis.blob".to_string(),
+            follow: FollowConfig::default(),
         };
         (config, temp_dir)
     }
@@ -0,0 +1,612 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Follow mode: verify every checkpoint as it is produced, advancing the trusted committee across
+//! epochs, and maintaining the heads of authenticated event streams, whose verified events are
+//! served over a local HTTP API (see [server]).
+
+pub mod server;
+pub mod state;
+
+use crate::checkpoint::{read_checkpoint_list, write_checkpoint, write_checkpoint_list};
+use crate::committee::extract_new_committee_info;
+use crate::config::Config;
+use crate::mmr::apply_stream_updates;
+use crate::object_store::SuiObjectStore;
+use anyhow::{Result, anyhow};
+use move_core_types::language_storage::{StructTag, TypeTag};
+use state::TrustedState;
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::{Arc, RwLock};
+use sui_types::accumulator_root::{
+    ACCUMULATOR_SETTLEMENT_EVENT_STREAM_HEAD, ACCUMULATOR_SETTLEMENT_MODULE, AccumulatorKey,
+    EventCommitment, EventStreamHead, stream_id_from_accumulator_event,
+};
+use sui_types::base_types::{ObjectID, SequenceNumber, SuiAddress};
+use sui_types::committee::Committee;
+use sui_types::digests::{ObjectDigest, TransactionDigest};
+use sui_types::dynamic_field::{DynamicFieldKey, Field};
+use sui_types::effects::{AccumulatorValue, TransactionEffectsAPI};
+use sui_types::event::Event;
+use sui_types::full_checkpoint_content::CheckpointData;
+use sui_types::object::Object;
+use sui_types::{MoveTypeTagTraitGeneric, SUI_ACCUMULATOR_ROOT_OBJECT_ID, SUI_FRAMEWORK_ADDRESS};
+use tracing::{debug, info};
+
+/// An event from an authenticated event stream, taken from a verified checkpoint.
+#[derive(Debug, Clone)]
+pub struct VerifiedEvent {
+    /// Position of the event in its stream.
+    pub index: u64,
+    pub checkpoint: u64,
+    pub transaction_idx: u64,
+    pub event_idx: u64,
+    pub tx_digest: TransactionDigest,
+    pub event: Event,
+}
+
+/// What follow mode has verified so far, shared with the HTTP API.
+#[derive(Debug, Default)]
+pub struct FollowView {
+    pub next_checkpoint: u64,
+    pub epoch: u64,
+    pub streams: BTreeMap<SuiAddress, StreamView>,
+}
+
+#[derive(Debug, Default)]
+pub struct StreamView {
+    pub head: EventStreamHead,
+    /// The most recent verified events in the stream, oldest first.
+    pub events: VecDeque<VerifiedEvent>,
+}
+
+pub struct Follower {
+    config: Config,
+    store: SuiObjectStore,
+    state: TrustedState,
+    committee: Committee,
+    view: Arc<RwLock<FollowView>>,
+}
+
+impl Follower {
+    /// Resume following from the state persisted by a previous run, or start following from
+    /// `start_checkpoint` if there is no such state.
+    pub fn new(config: Config, start_checkpoint: Option<u64>) -> Result<Self> {
+        let mut state = match TrustedState::load(&config)? {
+            Some(state) => state,
+            None => TrustedState::init(&config, start_checkpoint)?,
+        };
+
+        state.add_streams(&config.follow.streams);
+        let committee = state.committee(&config)?;
+        let store = SuiObjectStore::new(&config)?;
+
+        let view = FollowView {
+            next_checkpoint: state.next_checkpoint,
+            epoch: committee.epoch,
+            streams: state
+                .streams
+                .iter()
+                .map(|(id, head)| {
+                    let view = StreamView {
+                        head: head.clone(),
+                        events: VecDeque::new(),
+                    };
+                    (*id, view)
+                })
+                .collect(),
+        };
+
+        Ok(Self {
+            config,
+            store,
+            state,
+            committee,
+            view: Arc::new(RwLock::new(view)),
+        })
+    }
+
+    pub fn view(&self) -> Arc<RwLock<FollowView>> {
+        self.view.clone()
+    }
+
+    /// Verify checkpoints one after the other, waiting for each to become available, until a
+    /// checkpoint fails verification, or cannot be fetched for any reason other than not being
+    /// available yet.
+    pub async fn run(mut self) -> Result<()> {
+        let poll_interval = self.config.follow.poll_interval();
+        loop {
+            let seq = self.state.next_checkpoint;
+            let Some(checkpoint) = self.store.try_get_full_checkpoint(seq).await? else {
+                debug!("Checkpoint {} not available yet", seq);
+                tokio::time::sleep(poll_interval).await;
+                continue;
+            };
+
+            self.process_checkpoint(&checkpoint)?;
+        }
+    }
+
+    fn process_checkpoint(&mut self, checkpoint: &CheckpointData) -> Result<()> {
+        let summary = &checkpoint.checkpoint_summary;
+        let seq = *summary.sequence_number();
+
+        anyhow::ensure!(
+            seq == self.state.next_checkpoint,
+            "Expected checkpoint {}, got {}",
+            self.state.next_checkpoint,
+            seq
+        );
+
+        verify_checkpoint(checkpoint, &self.committee)?;
+
+        let mut new_events = BTreeMap::new();
+        for (stream_id, head) in &mut self.state.streams {
+            let (commitments, events): (Vec<_>, Vec<_>) =
+                stream_events(checkpoint, *stream_id)?.into_iter().unzip();
+            if commitments.is_empty() {
+                continue;
+            }
+
+            // The checkpoint has been verified, so the heads it settles can be trusted.
+            let (before, after) =
+                stream_head_changes(checkpoint, *stream_id)?.ok_or_else(|| {
+                    anyhow!(
+                        "Events for stream {} in checkpoint {} are not settled in its head",
+                        stream_id,
+                        seq
+                    )
+                })?;
+
+            // A stream that has had no events since it started being followed picks up from its
+            // head on chain. Any other stream must agree with the chain on its head.
+            let before = before.unwrap_or_default();
+            if *head == EventStreamHead::new() {
+                if before.num_events > 0 {
+                    info!(
+                        "Event stream {} already has {} event(s), continuing from its head on chain",
+                        stream_id, before.num_events
+                    );
+                }
+                *head = before;
+            } else {
+                anyhow::ensure!(
+                    head.num_events == before.num_events && head.mmr == before.mmr,
+                    "Head of event stream {} does not match its head on chain before checkpoint {}",
+                    stream_id,
+                    seq
+                );
+            }
+
+            let first_index = head.num_events;
+            *head = apply_stream_updates(head, vec![commitments.clone()]);
+            head.checkpoint_seq = seq;
+
+            anyhow::ensure!(
+                head.num_events == after.num_events && head.mmr == after.mmr,
+                "Head of event stream {} does not match its head on chain after checkpoint {}",
+                stream_id,
+                seq
+            );
+
+            let verified: Vec<_> = commitments
+                .into_iter()
+                .zip(events)
+                .enumerate()
+                .map(|(i, (commitment, event))| VerifiedEvent {
+                    index: first_index + i as u64,
+                    checkpoint: seq,
+                    transaction_idx: commitment.transaction_idx,
+                    event_idx: commitment.event_idx,
+                    tx_digest: *checkpoint.transactions[commitment.transaction_idx as usize]
+                        .transaction
+                        .digest(),
+                    event,
+                })
+                .collect();
+
+            info!(
+                "Verified {} event(s) in stream {} at checkpoint {}",
+                verified.len(),
+                stream_id,
+                seq
+            );
+            new_events.insert(*stream_id, verified);
+        }
+
+        // The committee for the next epoch is signed off by the committee of this epoch, in its
+        // last checkpoint. Record the checkpoint alongside the ones found by `sync`.
+        if summary.end_of_epoch_data.is_some() {
+            let committee = extract_new_committee_info(summary)?;
+            write_checkpoint(&self.config, summary)?;
+
+            let mut list = read_checkpoint_list(&self.config)?;
+            if !list.checkpoints.contains(&seq) {
+                list.checkpoints.push(seq);
+                list.checkpoints.sort();
+                write_checkpoint_list(&self.config, &list)?;
+            }
+
+            info!(
+                "Advanced trusted committee to epoch {} at checkpoint {}",
+                committee.epoch, seq
+            );
+
+            self.committee = committee;
+            self.state.committee_checkpoint = Some(seq);
+        }
+
+        self.state.next_checkpoint = seq + 1;
+        self.state.save(&self.config)?;
+
+        let max_events = self.config.follow.max_events_per_stream();
+        let mut view = self.view.write().expect("follow view lock poisoned");
+        view.next_checkpoint = self.state.next_checkpoint;
+        view.epoch = self.committee.epoch;
+        for (stream_id, head) in &self.state.streams {
+            let stream = view.streams.entry(*stream_id).or_default();
+            stream.head = head.clone();
+            stream
+                .events
+                .extend(new_events.remove(stream_id).unwrap_or_default());
+            while stream.events.len() > max_events {
+                stream.events.pop_front();
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Follow the chain, and serve the verified events over HTTP, until either fails.
+pub async fn follow(config: Config, start_checkpoint: Option<u64>) -> Result<()> {
+    let follower = Follower::new(config.clone(), start_checkpoint)?;
+    let listen_address = config.follow.listen_address();
+    let view = follower.view();
+
+    tokio::select! {
+        res = follower.run() => res,
+        res = server::serve(listen_address, view) => res,
+    }
+}
+
+/// Verify that `checkpoint` was signed by `committee`, and that all the transactions, effects and
+/// events it contains are the ones that the signed summary commits to.
+pub fn verify_checkpoint(checkpoint: &CheckpointData, committee: &Committee) -> Result<()> {
+    let summary = &checkpoint.checkpoint_summary;
+    anyhow::ensure!(
+        summary.epoch() == committee.epoch,
+        "Checkpoint {} is in epoch {}, but the trusted committee is for epoch {}. Need to Sync.",
+        summary.sequence_number(),
+        summary.epoch(),
+        committee.epoch
+    );
+
+    summary.verify_with_contents(committee, Some(&checkpoint.checkpoint_contents))?;
+
+    let contents = &checkpoint.checkpoint_contents;
+    anyhow::ensure!(
+        checkpoint.transactions.len() == contents.size(),
+        "Number of transactions does not match checkpoint contents"
+    );
+
+    for (tx, digests) in checkpoint.transactions.iter().zip(contents.iter()) {
+        anyhow::ensure!(
+            tx.effects.execution_digests() == *digests,
+            "Effects of transaction {} do not match checkpoint contents",
+            digests.transaction
+        );
+
+        let events_digest = tx.events.as_ref().map(|events| events.digest());
+        anyhow::ensure!(
+            events_digest.as_ref() == tx.effects.events_digest(),
+            "Events digest does not match for transaction {}",
+            digests.transaction
+        );
+    }
+
+    Ok(())
+}
+
+/// The events in `checkpoint` that belong to the authenticated event stream `stream_id`, with
+/// their commitments, in stream order.
+pub fn stream_events(
+    checkpoint: &CheckpointData,
+    stream_id: SuiAddress,
+) -> Result<Vec<(EventCommitment, Event)>> {
+    let seq = *checkpoint.checkpoint_summary.sequence_number();
+    let mut stream_events = vec![];
+
+    for (tx_idx, tx) in checkpoint.transactions.iter().enumerate() {
+        for acc in tx.effects.accumulator_events() {
+            let AccumulatorValue::EventDigest(event_digests) = &acc.write.value else {
+                continue;
+            };
+
+            if stream_id_from_accumulator_event(&acc) != Some(stream_id) {
+                continue;
+            }
+
+            for (event_idx, digest) in event_digests {
+                let event = tx
+                    .events
+                    .as_ref()
+                    .and_then(|events| events.data.get(*event_idx as usize))
+                    .ok_or_else(|| {
+                        anyhow!(
+                            "Event {} of transaction {} is missing",
+                            event_idx,
+                            tx.transaction.digest()
+                        )
+                    })?;
+
+                anyhow::ensure!(
+                    event.digest() == *digest,
+                    "Event {} of transaction {} does not match its commitment",
+                    event_idx,
+                    tx.transaction.digest()
+                );
+
+                let commitment = EventCommitment::new(seq, tx_idx as u64, *event_idx, *digest);
+                stream_events.push((commitment, event.clone()));
+            }
+        }
+    }
+
+    stream_events.sort_by_key(|(c, _)| (c.transaction_idx, c.event_idx));
+    Ok(stream_events)
+}
+
+/// The ID of the object holding the head of the authenticated event stream `stream_id`, a dynamic
+/// field of the accumulator root.
+pub fn event_stream_head_id(stream_id: SuiAddress) -> Result<ObjectID> {
+    let head_type = TypeTag::Struct(Box::new(StructTag {
+        address: SUI_FRAMEWORK_ADDRESS,
+        module: ACCUMULATOR_SETTLEMENT_MODULE.to_owned(),
+        name: ACCUMULATOR_SETTLEMENT_EVENT_STREAM_HEAD.to_owned(),
+        type_params: vec![],
+    }));
+
+    Ok(DynamicFieldKey(
+        SUI_ACCUMULATOR_ROOT_OBJECT_ID,
+        AccumulatorKey { owner: stream_id },
+        AccumulatorKey::get_type_tag(&[head_type]),
+    )
+    .object_id()?)
+}
+
+/// The head of the authenticated event stream `stream_id` on chain before and after `checkpoint`,
+/// or `None` if the checkpoint does not touch it. The head before is `None` if the checkpoint
+/// creates it. Heads are read from the objects in the checkpoint, and checked against the digests
+/// in the effects, so they can only be trusted once the checkpoint has been verified.
+pub fn stream_head_changes(
+    checkpoint: &CheckpointData,
+    stream_id: SuiAddress,
+) -> Result<Option<(Option<EventStreamHead>, EventStreamHead)>> {
+    let seq = *checkpoint.checkpoint_summary.sequence_number();
+    let head_id = event_stream_head_id(stream_id)?;
+    let mut changes = None;
+
+    for tx in &checkpoint.transactions {
+        let effects = tx.effects.object_changes();
+        let Some(change) = effects.iter().find(|c| c.id == head_id) else {
+            continue;
+        };
+
+        // The head before the checkpoint is the one read by the first transaction to touch it,
+        // and the head after is the one written by the last.
+        let before = match changes {
+            Some((before, _)) => before,
+            None => match (change.input_version, change.input_digest) {
+                (Some(version), Some(digest)) => Some(verified_stream_head(
+                    &tx.input_objects,
+                    head_id,
+                    version,
+                    digest,
+                )?),
+                _ => None,
+            },
+        };
+
+        let after = match (change.output_version, change.output_digest) {
+            (Some(version), Some(digest)) => Some(verified_stream_head(
+                &tx.output_objects,
+                head_id,
+                version,
+                digest,
+            )?),
+            _ => None,
+        };
+
+        changes = Some((before, after));
+    }
+
+    let Some((before, after)) = changes else {
+        return Ok(None);
+    };
+
+    let after = after.ok_or_else(|| {
+        anyhow!(
+            "Head of event stream {} is deleted in checkpoint {}",
+            stream_id,
+            seq
+        )
+    })?;
+
+    Ok(Some((before, after)))
+}
+
+/// The event stream head in the object `id` at `version` among `objects`, provided its contents
+/// match `digest`.
+fn verified_stream_head(
+    objects: &[Object],
+    id: ObjectID,
+    version: SequenceNumber,
+    digest: ObjectDigest,
+) -> Result<EventStreamHead> {
+    let object = objects
+        .iter()
+        .find(|o| o.id() == id && o.version() == version)
+        .ok_or_else(|| anyhow!("Event stream head {} at version {} is missing", id, version))?;
+
+    anyhow::ensure!(
+        object.digest() == digest,
+        "Event stream head {} at version {} does not match its digest in the effects",
+        id,
+        version
+    );
+
+    let field: Field<AccumulatorKey, EventStreamHead> = object
+        .data
+        .try_as_move()
+        .and_then(|o| o.to_rust())
+        .ok_or_else(|| anyhow!("Object {} is not an event stream head", id))?;
+
+    Ok(field.value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::FollowConfig;
+    use std::fs;
+    use std::path::PathBuf;
+    use std::time::Duration;
+    use sui_types::crypto::AuthorityQuorumSignInfo;
+    use sui_types::message_envelope::Envelope;
+    use sui_types::messages_checkpoint::CheckpointSummary;
+    use tempfile::TempDir;
+    use url::Url;
+
+    fn read_data() -> (Committee, CheckpointData) {
+        let d = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        let bytes = fs::read(d.join("test_files/20873329.yaml")).unwrap();
+        let summary: Envelope<CheckpointSummary, AuthorityQuorumSignInfo<true>> =
+            bcs::from_bytes(&bytes).unwrap();
+        let committee = extract_new_committee_info(&summary).unwrap();
+
+        let bytes = fs::read(d.join("test_files/20958462.bcs")).unwrap();
+        let checkpoint = bcs::from_bytes(&bytes).unwrap();
+
+        (committee, checkpoint)
+    }
+
+    #[test]
+    fn test_verify_checkpoint() {
+        let (committee, checkpoint) = read_data();
+        verify_checkpoint(&checkpoint, &committee).unwrap();
+    }
+
+    #[test]
+    fn test_verify_checkpoint_wrong_committee() {
+        let (mut committee, checkpoint) = read_data();
+        committee.epoch += 1;
+        assert!(verify_checkpoint(&checkpoint, &committee).is_err());
+    }
+
+    #[test]
+    fn test_verify_checkpoint_tampered_events() {
+        let (committee, mut checkpoint) = read_data();
+        let tx = checkpoint
+            .transactions
+            .iter_mut()
+            .find(|tx| tx.events.as_ref().is_some_and(|e| !e.data.is_empty()))
+            .unwrap();
+
+        let events = tx.events.as_mut().unwrap();
+        let event = events.data[0].clone();
+        events.data.push(event);
+
+        assert!(verify_checkpoint(&checkpoint, &committee).is_err());
+    }
+
+    #[test]
+    fn test_stream_events_unknown_stream() {
+        let (_, checkpoint) = read_data();
+        let events = stream_events(&checkpoint, SuiAddress::ZERO).unwrap();
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn test_stream_head_changes_unknown_stream() {
+        let (_, checkpoint) = read_data();
+        let changes = stream_head_changes(&checkpoint, SuiAddress::ZERO).unwrap();
+        assert!(changes.is_none());
+    }
+
+    /// A follower that trusts the committee that signed checkpoint 20958462, and expects it next,
+    /// reading full checkpoints from the `store` directory.
+    fn create_test_follower(store: &TempDir) -> (Follower, TempDir) {
+        let d = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        let summaries = TempDir::new().unwrap();
+        fs::copy(
+            d.join("test_files/20873329.yaml"),
+            summaries.path().join("20873329.yaml"),
+        )
+        .unwrap();
+
+        let config = Config {
+            checkpoint_summary_dir: summaries.path().to_path_buf(),
+            object_store_url: Url::from_directory_path(store.path()).unwrap().to_string(),
+            follow: FollowConfig {
+                poll_interval_ms: Some(10),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        TrustedState {
+            next_checkpoint: 20958462,
+            committee_checkpoint: Some(20873329),
+            streams: BTreeMap::new(),
+        }
+        .save(&config)
+        .unwrap();
+
+        (Follower::new(config, None).unwrap(), summaries)
+    }
+
+    #[tokio::test]
+    async fn test_run_waits_for_checkpoint() {
+        let store = TempDir::new().unwrap();
+        let (follower, _summaries) = create_test_follower(&store);
+
+        // The checkpoint is not in the store, so the follower keeps waiting for it.
+        let res = tokio::time::timeout(Duration::from_millis(200), follower.run()).await;
+        assert!(res.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_run_verifies_checkpoint() {
+        let d = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        let store = TempDir::new().unwrap();
+        fs::copy(
+            d.join("test_files/20958462.chk"),
+            store.path().join("20958462.chk"),
+        )
+        .unwrap();
+
+        let (follower, _summaries) = create_test_follower(&store);
+        let config = follower.config.clone();
+        let view = follower.view();
+
+        // The follower verifies the checkpoint, then waits for the next one.
+        let res = tokio::time::timeout(Duration::from_millis(500), follower.run()).await;
+        assert!(res.is_err());
+
+        let state = TrustedState::load(&config).unwrap().unwrap();
+        assert_eq!(state.next_checkpoint, 20958463);
+        assert_eq!(view.read().unwrap().next_checkpoint, 20958463);
+    }
+
+    #[tokio::test]
+    async fn test_run_fails_on_corrupt_checkpoint() {
+        let store = TempDir::new().unwrap();
+        fs::write(store.path().join("20958462.chk"), b"corrupt").unwrap();
+
+        // Unlike a missing checkpoint, a checkpoint that cannot be read stops the follower.
+        let (follower, _summaries) = create_test_follower(&store);
+        let res = tokio::time::timeout(Duration::from_millis(500), follower.run()).await;
+        assert!(res.unwrap().is_err());
+    }
+}
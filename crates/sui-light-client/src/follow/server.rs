@@ -0,0 +1,173 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A local HTTP/JSON API over the state verified by follow mode:
+//!
+//! - `GET /status`: the next checkpoint to verify, the trusted epoch, and the head of every stream
+//!   being followed.
+//! - `GET /streams/{stream_id}/events?start=&limit=`: verified events in a stream, from position
+//!   `start` onwards. Only the most recent events are retained, so `first_available` reports the
+//!   position of the oldest event that can still be served.
+
+use super::{FollowView, VerifiedEvent};
+use anyhow::Result;
+use axum::Json;
+use axum::Router;
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use fastcrypto::encoding::{Base64, Encoding};
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+use sui_types::accumulator_root::EventStreamHead;
+use sui_types::base_types::SuiAddress;
+use tracing::info;
+
+const DEFAULT_LIMIT: usize = 100;
+const MAX_LIMIT: usize = 1000;
+
+type SharedView = Arc<RwLock<FollowView>>;
+
+pub async fn serve(address: SocketAddr, view: SharedView) -> Result<()> {
+    let listener = tokio::net::TcpListener::bind(address).await?;
+    info!("Serving verified events on http://{}", address);
+    axum::serve(listener, router(view)).await?;
+    Ok(())
+}
+
+pub fn router(view: SharedView) -> Router {
+    Router::new()
+        .route("/status", get(status))
+        .route("/streams/{stream_id}/events", get(events))
+        .with_state(view)
+}
+
+#[derive(Serialize)]
+struct StatusResponse {
+    next_checkpoint: u64,
+    epoch: u64,
+    streams: Vec<StreamStatus>,
+}
+
+#[derive(Serialize)]
+struct StreamStatus {
+    stream_id: SuiAddress,
+    num_events: u64,
+    checkpoint_seq: u64,
+    /// The peaks of the stream's MMR, as decimal strings.
+    mmr: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct EventsQuery {
+    start: Option<u64>,
+    limit: Option<usize>,
+}
+
+#[derive(Serialize)]
+struct EventsResponse {
+    stream_id: SuiAddress,
+    num_events: u64,
+    first_available: Option<u64>,
+    events: Vec<EventEntry>,
+}
+
+#[derive(Serialize)]
+struct EventEntry {
+    index: u64,
+    checkpoint: u64,
+    transaction_idx: u64,
+    event_idx: u64,
+    tx_digest: String,
+    package_id: String,
+    module: String,
+    sender: SuiAddress,
+    #[serde(rename = "type")]
+    type_: String,
+    /// The BCS-encoded contents of the event, in Base64.
+    contents: String,
+}
+
+async fn status(State(view): State<SharedView>) -> Json<StatusResponse> {
+    let view = view.read().expect("follow view lock poisoned");
+    Json(StatusResponse {
+        next_checkpoint: view.next_checkpoint,
+        epoch: view.epoch,
+        streams: view
+            .streams
+            .iter()
+            .map(|(stream_id, stream)| StreamStatus::new(*stream_id, &stream.head))
+            .collect(),
+    })
+}
+
+async fn events(
+    State(view): State<SharedView>,
+    Path(stream_id): Path<String>,
+    Query(query): Query<EventsQuery>,
+) -> Response {
+    let Ok(stream_id) = SuiAddress::from_str(&stream_id) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            format!("Invalid stream id: {stream_id}"),
+        )
+            .into_response();
+    };
+
+    let view = view.read().expect("follow view lock poisoned");
+    let Some(stream) = view.streams.get(&stream_id) else {
+        return (
+            StatusCode::NOT_FOUND,
+            format!("Stream {stream_id} is not being followed"),
+        )
+            .into_response();
+    };
+
+    let start = query.start.unwrap_or(0);
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT);
+
+    Json(EventsResponse {
+        stream_id,
+        num_events: stream.head.num_events,
+        first_available: stream.events.front().map(|e| e.index),
+        events: stream
+            .events
+            .iter()
+            .skip_while(|e| e.index < start)
+            .take(limit)
+            .map(EventEntry::from)
+            .collect(),
+    })
+    .into_response()
+}
+
+impl StreamStatus {
+    fn new(stream_id: SuiAddress, head: &EventStreamHead) -> Self {
+        Self {
+            stream_id,
+            num_events: head.num_events,
+            checkpoint_seq: head.checkpoint_seq,
+            mmr: head.mmr.iter().map(|peak| peak.to_string()).collect(),
+        }
+    }
+}
+
+impl From<&VerifiedEvent> for EventEntry {
+    fn from(e: &VerifiedEvent) -> Self {
+        Self {
+            index: e.index,
+            checkpoint: e.checkpoint,
+            transaction_idx: e.transaction_idx,
+            event_idx: e.event_idx,
+            tx_digest: e.tx_digest.to_string(),
+            package_id: e.event.package_id.to_string(),
+            module: e.event.transaction_module.to_string(),
+            sender: e.event.sender,
+            type_: e.event.type_.to_canonical_string(/* with_prefix */ true),
+            contents: Base64::encode(&e.event.contents),
+        }
+    }
+}
@@ -0,0 +1,173 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::checkpoint::{read_checkpoint, read_checkpoint_list};
+use crate::committee::extract_new_committee_info;
+use crate::config::Config;
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use sui_config::genesis::Genesis;
+use sui_types::accumulator_root::EventStreamHead;
+use sui_types::base_types::SuiAddress;
+use sui_types::committee::Committee;
+use tracing::info;
+
+/// The state that follow mode persists between runs. Everything in it is derived from
+/// checkpoints that were verified by a committee the light client trusts.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrustedState {
+    /// The next checkpoint to verify.
+    pub next_checkpoint: u64,
+
+    /// The end of epoch checkpoint that the trusted committee was read from, or `None` if the
+    /// trusted committee is the genesis committee.
+    pub committee_checkpoint: Option<u64>,
+
+    /// The heads of the authenticated event streams being followed, accounting for all their
+    /// events up to (but excluding) `next_checkpoint`. Streams that have had no events since they
+    /// started being followed have empty heads, until their next events are verified.
+    pub streams: BTreeMap<SuiAddress, EventStreamHead>,
+}
+
+impl TrustedState {
+    /// Start following from `start_checkpoint`, or if it is not provided, from the checkpoint
+    /// after the last end of epoch checkpoint that has been synced. The committee is taken from
+    /// the synced end of epoch checkpoints, so these need to have been synced first.
+    pub fn init(config: &Config, start_checkpoint: Option<u64>) -> Result<Self> {
+        let list = read_checkpoint_list(config)
+            .map_err(|e| anyhow!("Cannot read checkpoint list, run `sync` first: {e}"))?;
+
+        let (next_checkpoint, committee_checkpoint) = match start_checkpoint {
+            Some(start) => (
+                start,
+                list.checkpoints.iter().filter(|c| **c < start).next_back(),
+            ),
+            None => (
+                list.checkpoints.last().map_or(0, |c| c + 1),
+                list.checkpoints.last(),
+            ),
+        };
+
+        Ok(Self {
+            next_checkpoint,
+            committee_checkpoint: committee_checkpoint.copied(),
+            streams: BTreeMap::new(),
+        })
+    }
+
+    /// Load the state persisted by a previous run, if there is one.
+    pub fn load(config: &Config) -> Result<Option<Self>> {
+        let path = config.follow_state_path();
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let bytes = fs::read(&path)?;
+        let state =
+            bcs::from_bytes(&bytes).map_err(|_| anyhow!("Unable to parse follow state file"))?;
+        Ok(Some(state))
+    }
+
+    /// Persist the state, replacing the state from any previous run.
+    pub fn save(&self, config: &Config) -> Result<()> {
+        let path = config.follow_state_path();
+        let bytes = bcs::to_bytes(self).map_err(|_| anyhow!("Unable to serialize follow state"))?;
+
+        // Write to a temporary file first, so that a crash mid-write does not corrupt the state.
+        let tmp = path.with_extension("bcs.tmp");
+        fs::write(&tmp, bytes)?;
+        fs::rename(&tmp, &path)?;
+        Ok(())
+    }
+
+    /// Start maintaining heads for any configured streams that are not being followed yet.
+    /// Their heads start out empty, and are initialized from the verified head on chain when the
+    /// first of their events is verified, so that they account for events before
+    /// `next_checkpoint` as well.
+    pub fn add_streams(&mut self, streams: &[SuiAddress]) {
+        for stream_id in streams {
+            self.streams.entry(*stream_id).or_insert_with(|| {
+                info!(
+                    "Following event stream {} from checkpoint {}",
+                    stream_id, self.next_checkpoint
+                );
+                EventStreamHead::new()
+            });
+        }
+    }
+
+    /// The committee that is trusted to sign checkpoints from `next_checkpoint` until the end of
+    /// its epoch.
+    pub fn committee(&self, config: &Config) -> Result<Committee> {
+        match self.committee_checkpoint {
+            Some(seq) => Ok(extract_new_committee_info(&read_checkpoint(config, seq)?)?),
+            None => Genesis::load(config.genesis_path())?
+                .committee()
+                .map_err(|e| anyhow!(format!("Cannot load Genesis: {e}"))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checkpoint::{CheckpointsList, write_checkpoint_list};
+    use move_core_types::u256::U256;
+    use tempfile::TempDir;
+
+    fn create_test_config() -> (Config, TempDir) {
+        let temp_dir = TempDir::new().unwrap();
+        let config = Config {
+            checkpoint_summary_dir: temp_dir.path().to_path_buf(),
+            ..Default::default()
+        };
+        (config, temp_dir)
+    }
+
+    #[test]
+    fn test_init_requires_sync() {
+        let (config, _temp_dir) = create_test_config();
+        assert!(TrustedState::init(&config, None).is_err());
+    }
+
+    #[test]
+    fn test_init_from_checkpoint_list() {
+        let (config, _temp_dir) = create_test_config();
+        let list = CheckpointsList {
+            checkpoints: vec![10, 20, 30],
+        };
+        write_checkpoint_list(&config, &list).unwrap();
+
+        let state = TrustedState::init(&config, None).unwrap();
+        assert_eq!(state.next_checkpoint, 31);
+        assert_eq!(state.committee_checkpoint, Some(30));
+
+        let state = TrustedState::init(&config, Some(25)).unwrap();
+        assert_eq!(state.next_checkpoint, 25);
+        assert_eq!(state.committee_checkpoint, Some(20));
+
+        let state = TrustedState::init(&config, Some(5)).unwrap();
+        assert_eq!(state.next_checkpoint, 5);
+        assert_eq!(state.committee_checkpoint, None);
+    }
+
+    #[test]
+    fn test_save_load() {
+        let (config, _temp_dir) = create_test_config();
+        assert!(TrustedState::load(&config).unwrap().is_none());
+
+        let mut state = TrustedState {
+            next_checkpoint: 42,
+            committee_checkpoint: Some(40),
+            streams: BTreeMap::new(),
+        };
+
+        state.add_streams(&[SuiAddress::ZERO]);
+        state.streams.get_mut(&SuiAddress::ZERO).unwrap().mmr = vec![U256::from(1u64)];
+        state.save(&config).unwrap();
+
+        assert_eq!(TrustedState::load(&config).unwrap(), Some(state));
+    }
+}
@@ -7,6 +7,8 @@ pub mod checkpoint;
 
 pub mod config;
 
+pub mod follow;
+
 pub mod object_store;
 pub mod package_store;
 
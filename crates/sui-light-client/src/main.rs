@@ -13,6 +13,7 @@ use clap::{Parser, Subcommand};
 use std::{fs, path::PathBuf, str::FromStr};
 use sui_light_client::checkpoint::check_and_sync_checkpoints;
 use sui_light_client::config::Config;
+use sui_light_client::follow::follow;
 use sui_light_client::package_store::RemotePackageStore;
 use sui_light_client::verifier::{get_verified_effects_and_events, get_verified_object};
 
@@ -48,6 +49,14 @@ enum SCommands {
         #[arg(short, long, value_name = "OID")]
         oid: String,
     },
+
+    /// Verify checkpoints as they are produced, tracking committee changes and the configured
+    /// authenticated event streams, and serve verified events over HTTP
+    Follow {
+        /// Checkpoint to start following from, if there is no state from a previous run
+        #[arg(long, value_name = "SEQ")]
+        start_checkpoint: Option<u64>,
+    },
 }
 
 #[tokio::main]
@@ -146,6 +155,12 @@ pub async fn main() {
                 .await
                 .expect("Failed to sync checkpoints");
         }
+
+        Some(SCommands::Follow { start_checkpoint }) => {
+            follow(config, start_checkpoint)
+                .await
+                .expect("Failed to follow checkpoints");
+        }
         _ => {
             println!("No command...");
         }
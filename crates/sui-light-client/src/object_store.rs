@@ -36,16 +36,32 @@ impl SuiObjectStore {
     }
 
     pub async fn get_full_checkpoint(&self, checkpoint_number: u64) -> Result<CheckpointData> {
+        self.try_get_full_checkpoint(checkpoint_number)
+            .await?
+            .ok_or_else(|| anyhow!("Cannot get full checkpoint from object store"))
+    }
+
+    /// Like [Self::get_full_checkpoint], but returns `None` if the checkpoint is not in the
+    /// store (yet), rather than an error.
+    pub async fn try_get_full_checkpoint(
+        &self,
+        checkpoint_number: u64,
+    ) -> Result<Option<CheckpointData>> {
         let path = Path::from(format!("{}.chk", checkpoint_number));
         info!("Request full checkpoint: {}", path);
-        let response = self
-            .store
-            .get(&path)
-            .await
-            .map_err(|_| anyhow!("Cannot get full checkpoint from object store"))?;
+        let response = match self.store.get(&path).await {
+            Ok(response) => response,
+            Err(object_store::Error::NotFound { .. }) => return Ok(None),
+            Err(e) => {
+                return Err(anyhow!(
+                    "Cannot get full checkpoint {checkpoint_number} from object store: {e}"
+                ));
+            }
+        };
+
         let bytes = response.bytes().await?;
         let (_, full_checkpoint) = bcs::from_bytes::<(u8, CheckpointData)>(&bytes)?;
-        Ok(full_checkpoint)
+        Ok(Some(full_checkpoint))
     }
 }
 
@@ -4,6 +4,7 @@
 use super::{
     Handle, PeerHeights, StateSync, StateSyncEventLoop, StateSyncMessage, StateSyncServer,
     metrics::Metrics,
+    peer_scores::PeerScores,
     server::{CheckpointContentsDownloadLimitLayer, Server},
 };
 use anemo::codegen::InboundRequestLayer;
@@ -142,6 +143,10 @@ where
             peers: HashMap::new(),
             unprocessed_checkpoints: HashMap::new(),
             sequence_number_to_digest: HashMap::new(),
+            peer_scores: PeerScores::new(
+                config.peer_score_half_life(),
+                config.hedge_latency_percentile(),
+            ),
             wait_interval_when_no_peer_to_sync_content: config
                 .wait_interval_when_no_peer_to_sync_content(),
         }
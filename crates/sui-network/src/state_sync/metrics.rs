@@ -1,10 +1,14 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use super::PeerCheckpointRequestType;
+use super::peer_scores::PeerScores;
 use mysten_metrics::histogram::Histogram as MystenHistogram;
 use prometheus::{
-    Histogram, IntCounter, IntGauge, Registry, register_histogram_with_registry,
-    register_int_counter_with_registry, register_int_gauge_with_registry,
+    GaugeVec, Histogram, IntCounter, IntCounterVec, IntGauge, Registry,
+    register_gauge_vec_with_registry, register_histogram_with_registry,
+    register_int_counter_vec_with_registry, register_int_counter_with_registry,
+    register_int_gauge_with_registry,
 };
 use std::sync::Arc;
 use sui_types::messages_checkpoint::CheckpointSequenceNumber;
@@ -54,6 +58,60 @@ impl Metrics {
         }
     }
 
+    pub fn inc_hedged_requests(&self, request_type: PeerCheckpointRequestType) {
+        if let Some(inner) = &self.0 {
+            inner
+                .hedged_requests
+                .with_label_values(&[request_type.as_str()])
+                .inc();
+        }
+    }
+
+    /// Replace the reported peer scores with the current scores of connected peers.
+    pub fn update_peer_scores(&self, network: &anemo::Network, scores: &PeerScores) {
+        let Some(inner) = &self.0 else {
+            return;
+        };
+
+        inner.peer_expected_latency.reset();
+        inner.peer_latency.reset();
+        inner.peer_failure_rate.reset();
+        inner.peer_throughput.reset();
+
+        for (peer_id, request_type, score) in scores.iter() {
+            let Some(peer) = network.peer(*peer_id) else {
+                continue;
+            };
+
+            let peer_label = peer_id.short_display(4).to_string();
+            let labels = [peer_label.as_str(), request_type.as_str()];
+            let expected_latency =
+                scores.expected_latency(*peer_id, request_type, peer.connection_rtt());
+            inner
+                .peer_expected_latency
+                .with_label_values(&labels)
+                .set(expected_latency.as_secs_f64());
+            if let Some(latency) = score.latency() {
+                inner
+                    .peer_latency
+                    .with_label_values(&labels)
+                    .set(latency.as_secs_f64());
+            }
+            if let Some(failure_rate) = score.failure_rate() {
+                inner
+                    .peer_failure_rate
+                    .with_label_values(&labels)
+                    .set(failure_rate);
+            }
+            if let Some(throughput) = score.throughput() {
+                inner
+                    .peer_throughput
+                    .with_label_values(&[peer_label.as_str()])
+                    .set(throughput);
+            }
+        }
+    }
+
     pub fn checkpoint_summary_age_metrics(&self) -> Option<(&Histogram, &MystenHistogram)> {
         if let Some(inner) = &self.0 {
             return Some((
@@ -73,6 +131,11 @@ struct Inner {
     checkpoint_summary_age: Histogram,
     // TODO: delete once users are migrated to non-Mysten histogram.
     checkpoint_summary_age_ms: MystenHistogram,
+    hedged_requests: IntCounterVec,
+    peer_expected_latency: GaugeVec,
+    peer_latency: GaugeVec,
+    peer_failure_rate: GaugeVec,
+    peer_throughput: GaugeVec,
 }
 
 impl Inner {
@@ -116,6 +179,41 @@ impl Inner {
                 "Age of checkpoints summaries when they arrive and are verified.",
                 registry,
             ),
+            hedged_requests: register_int_counter_vec_with_registry!(
+                "state_sync_hedged_requests",
+                "Requests to peers that were hedged by sending them to another peer",
+                &["request_type"],
+                registry
+            )
+            .unwrap(),
+            peer_expected_latency: register_gauge_vec_with_registry!(
+                "state_sync_peer_expected_latency",
+                "Score used to rank peers: the expected time in seconds for a peer to successfully serve a request",
+                &["peer", "request_type"],
+                registry
+            )
+            .unwrap(),
+            peer_latency: register_gauge_vec_with_registry!(
+                "state_sync_peer_latency",
+                "Decaying average latency in seconds of a peer's successful responses",
+                &["peer", "request_type"],
+                registry
+            )
+            .unwrap(),
+            peer_failure_rate: register_gauge_vec_with_registry!(
+                "state_sync_peer_failure_rate",
+                "Decaying average fraction of requests to a peer that failed",
+                &["peer", "request_type"],
+                registry
+            )
+            .unwrap(),
+            peer_throughput: register_gauge_vec_with_registry!(
+                "state_sync_peer_throughput",
+                "Decaying average number of transactions per second downloaded from a peer",
+                &["peer"],
+                registry
+            )
+            .unwrap(),
         }
         .pipe(Arc::new)
     }
@@ -27,7 +27,9 @@
 //!   our latest checkpoint, and is intended to be used as a guarantee of data availability.
 //!
 //! The `PeerHeights` struct is used to track the highest_synced_checkpoint watermark for all of
-//! our peers.
+//! our peers, and how well each of them has served our requests recently (see `PeerScores`).
+//! Checkpoint summaries and contents are requested from the best scoring peers first, and requests
+//! that take longer than most recent requests are hedged by sending them to the next best peer.
 //!
 //! When a new checkpoint is discovered, and we've determined that it is higher than our
 //! highest_verified_checkpoint, then StateSync will kick off a task to synchronize and verify all
@@ -48,10 +50,14 @@
 //! of the newly synchronized checkpoint so that it can help other peers synchronize.
 
 use anemo::{PeerId, Request, Response, Result, types::PeerEvent};
-use futures::{FutureExt, StreamExt, stream::FuturesOrdered};
+use futures::{
+    FutureExt, StreamExt,
+    stream::{FuturesOrdered, FuturesUnordered},
+};
 use rand::Rng;
 use std::{
     collections::{HashMap, VecDeque},
+    future::Future,
     sync::{Arc, RwLock},
     time::Duration,
 };
@@ -78,12 +84,15 @@ mod generated {
 }
 mod builder;
 mod metrics;
+mod peer_scores;
 mod server;
 #[cfg(test)]
 mod tests;
 mod worker;
 
-use self::{metrics::Metrics, server::CheckpointContentsDownloadLimitLayer};
+use self::{
+    metrics::Metrics, peer_scores::PeerScores, server::CheckpointContentsDownloadLimitLayer,
+};
 use crate::state_sync::worker::StateSyncWorker;
 pub use builder::{Builder, UnstartedStateSync};
 pub use generated::{
@@ -133,6 +142,8 @@ struct PeerHeights {
     peers: HashMap<PeerId, PeerStateSyncInfo>,
     unprocessed_checkpoints: HashMap<CheckpointDigest, Checkpoint>,
     sequence_number_to_digest: HashMap<CheckpointSequenceNumber, CheckpointDigest>,
+    /// How well each of our peers has served our requests recently.
+    peer_scores: PeerScores,
 
     // The amount of time to wait before retry if there are no peers to sync content from.
     wait_interval_when_no_peer_to_sync_content: Duration,
@@ -272,38 +283,55 @@ impl PeerHeights {
     }
 }
 
-// PeerBalancer is an Iterator that selects peers based on RTT with some added randomness.
+// PeerBalancer is an Iterator that selects peers based on their scores with some added
+// randomness.
 #[derive(Clone)]
 struct PeerBalancer {
     peers: VecDeque<(anemo::Peer, PeerStateSyncInfo)>,
     requested_checkpoint: Option<CheckpointSequenceNumber>,
     request_type: PeerCheckpointRequestType,
+    peer_heights: Arc<RwLock<PeerHeights>>,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 enum PeerCheckpointRequestType {
     Summary,
     Content,
 }
 
+impl PeerCheckpointRequestType {
+    fn as_str(&self) -> &'static str {
+        match self {
+            PeerCheckpointRequestType::Summary => "summary",
+            PeerCheckpointRequestType::Content => "content",
+        }
+    }
+}
+
 impl PeerBalancer {
     pub fn new(
         network: &anemo::Network,
         peer_heights: Arc<RwLock<PeerHeights>>,
         request_type: PeerCheckpointRequestType,
     ) -> Self {
-        let mut peers: Vec<_> = peer_heights
-            .read()
-            .unwrap()
-            .peers_on_same_chain()
-            // Filter out any peers who we aren't connected with.
-            .filter_map(|(peer_id, info)| {
-                network
-                    .peer(*peer_id)
-                    .map(|peer| (peer.connection_rtt(), peer, *info))
-            })
-            .collect();
-        peers.sort_by(|(rtt_a, _, _), (rtt_b, _, _)| rtt_a.cmp(rtt_b));
+        let mut peers: Vec<_> = {
+            let heights = peer_heights.read().unwrap();
+            heights
+                .peers_on_same_chain()
+                // Filter out any peers who we aren't connected with.
+                .filter_map(|(peer_id, info)| {
+                    network.peer(*peer_id).map(|peer| {
+                        let score = heights.peer_scores.expected_latency(
+                            *peer_id,
+                            request_type,
+                            peer.connection_rtt(),
+                        );
+                        (score, peer, *info)
+                    })
+                })
+                .collect()
+        };
+        peers.sort_by(|(score_a, _, _), (score_b, _, _)| score_a.cmp(score_b));
         Self {
             peers: peers
                 .into_iter()
@@ -311,6 +339,7 @@ impl PeerBalancer {
                 .collect(),
             requested_checkpoint: None,
             request_type,
+            peer_heights,
         }
     }
 
@@ -348,6 +377,124 @@ impl Iterator for PeerBalancer {
     }
 }
 
+/// Send a request to the peers selected by `peers`, one at a time, until one of them responds
+/// successfully. `request` sends the request to a peer, and returns its response if it is valid.
+/// `size` counts the units in a response (e.g. transactions), which its latency is scored against,
+/// and `expected_size` is the number of units the response is expected to have.
+///
+/// If a peer takes longer to respond than most recent requests of the same type would have taken
+/// for a response of `expected_size` units, the request is hedged by also sending it to the next
+/// peer, and whichever valid response arrives first is used.
+/// Every response is recorded in the scores of the peer that sent it, and requests that are still
+/// in flight when another peer wins are recorded with the time they were given.
+///
+/// Returns the response, the peer that sent it, and how long the peer took to respond.
+async fn request_from_peers<T, F, Fut>(
+    peers: PeerBalancer,
+    metrics: &Metrics,
+    expected_size: usize,
+    size: impl Fn(&T) -> usize,
+    request: F,
+) -> Option<(T, PeerId, Duration)>
+where
+    F: Fn(StateSyncClient<anemo::Peer>) -> Fut,
+    Fut: Future<Output = Option<T>>,
+{
+    let request_type = peers.request_type;
+    let peer_heights = peers.peer_heights.clone();
+    let peers = peers.map(|peer| (peer.inner().peer_id(), peer));
+    hedged_request(
+        peers,
+        request_type,
+        &peer_heights,
+        metrics,
+        expected_size,
+        size,
+        request,
+    )
+    .await
+}
+
+/// The implementation of [`request_from_peers`], for any kind of peer handle, `P`.
+async fn hedged_request<P, T, F, Fut>(
+    mut peers: impl Iterator<Item = (PeerId, P)>,
+    request_type: PeerCheckpointRequestType,
+    peer_heights: &RwLock<PeerHeights>,
+    metrics: &Metrics,
+    expected_size: usize,
+    size: impl Fn(&T) -> usize,
+    request: F,
+) -> Option<(T, PeerId, Duration)>
+where
+    F: Fn(P) -> Fut,
+    Fut: Future<Output = Option<T>>,
+{
+    let hedge_delay = peer_heights
+        .read()
+        .unwrap()
+        .peer_scores
+        .hedge_delay(request_type, expected_size);
+
+    let send = |peer_id: PeerId, peer: P, start: tokio::time::Instant| {
+        let response = request(peer);
+        async move { (peer_id, response.await, start.elapsed()) }
+    };
+
+    let mut inflight = FuturesUnordered::new();
+
+    // When each request still in flight was sent, to score the peers whose requests are abandoned.
+    let mut started = HashMap::new();
+
+    loop {
+        if inflight.is_empty() {
+            let (peer_id, peer) = peers.next()?;
+            let start = tokio::time::Instant::now();
+            started.insert(peer_id, start);
+            inflight.push(send(peer_id, peer, start));
+        }
+
+        // Only hedge a request that is not already hedged.
+        let hedge_after = hedge_delay.filter(|_| inflight.len() == 1);
+        let hedge = async {
+            match hedge_after {
+                Some(delay) => tokio::time::sleep(delay).await,
+                None => std::future::pending().await,
+            }
+        };
+
+        tokio::select! {
+            Some((peer_id, response, latency)) = inflight.next() => {
+                started.remove(&peer_id);
+                let mut heights = peer_heights.write().unwrap();
+                match response {
+                    Some(response) => {
+                        let units = size(&response);
+                        let scores = &mut heights.peer_scores;
+                        scores.record_success(peer_id, request_type, latency, units);
+                        for (peer_id, start) in started {
+                            scores.record_cancelled(peer_id, request_type, start.elapsed(), units);
+                        }
+                        return Some((response, peer_id, latency));
+                    }
+                    None => heights.peer_scores.record_failure(peer_id, request_type),
+                }
+            }
+            _ = hedge => {
+                if let Some((peer_id, peer)) = peers.next() {
+                    debug!(
+                        request_type = request_type.as_str(),
+                        "hedging request to {peer_id}",
+                    );
+                    metrics.inc_hedged_requests(request_type);
+                    let start = tokio::time::Instant::now();
+                    started.insert(peer_id, start);
+                    inflight.push(send(peer_id, peer, start));
+                }
+            }
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 enum StateSyncMessage {
     StartSyncJob,
@@ -423,6 +570,7 @@ where
             self.peer_heights.clone(),
             self.weak_sender.clone(),
             self.checkpoint_event_sender.clone(),
+            self.metrics.clone(),
             self.config.checkpoint_content_download_concurrency(),
             self.config.checkpoint_content_download_tx_concurrency(),
             self.config.checkpoint_content_timeout(),
@@ -620,7 +768,9 @@ where
                 self.spawn_get_latest_from_peer(peer_id);
             }
             Ok(PeerEvent::LostPeer(peer_id, _)) => {
-                self.peer_heights.write().unwrap().peers.remove(&peer_id);
+                let mut peer_heights = self.peer_heights.write().unwrap();
+                peer_heights.peers.remove(&peer_id);
+                peer_heights.peer_scores.remove_peer(&peer_id);
             }
 
             Err(RecvError::Closed) => {
@@ -662,6 +812,11 @@ where
         if let Some(layer) = self.download_limit_layer.as_ref() {
             layer.maybe_prune_map();
         }
+
+        self.metrics.update_peer_scores(
+            &self.network,
+            &self.peer_heights.read().unwrap().peer_scores,
+        );
     }
 
     fn maybe_start_checkpoint_summary_sync_task(&mut self) {
@@ -993,6 +1148,7 @@ where
             let peers = peer_balancer.clone().with_checkpoint(next);
             let peer_heights = peer_heights.clone();
             let pinned_checkpoints = &pinned_checkpoints;
+            let metrics = &metrics;
             async move {
                 if let Some(checkpoint) = peer_heights
                     .read()
@@ -1002,52 +1158,56 @@ where
                     return (Some(checkpoint.to_owned()), next, None);
                 }
 
-                // Iterate through peers trying each one in turn until we're able to
-                // successfully get the target checkpoint
-                for mut peer in peers {
+                // Try peers in turn until we're able to successfully get the target checkpoint
+                let response = request_from_peers(peers, metrics, 1, |_| 1, |mut peer| async move {
                     let request = Request::new(GetCheckpointSummaryRequest::BySequenceNumber(next))
                         .with_timeout(timeout);
-                    if let Some(checkpoint) = peer
+                    let checkpoint = peer
                         .get_checkpoint_summary(request)
                         .await
                         .tap_err(|e| trace!("{e:?}"))
                         .ok()
                         .and_then(Response::into_inner)
-                        .tap_none(|| trace!("peer unable to help sync"))
-                    {
-                        // peer didn't give us a checkpoint with the height that we requested
-                        if *checkpoint.sequence_number() != next {
+                        .tap_none(|| trace!("peer unable to help sync"))?;
+
+                    // peer didn't give us a checkpoint with the height that we requested
+                    if *checkpoint.sequence_number() != next {
+                        tracing::debug!(
+                            "peer returned checkpoint with wrong sequence number: expected {next}, got {}",
+                            checkpoint.sequence_number()
+                        );
+                        return None;
+                    }
+
+                    // peer gave us a checkpoint whose digest does not match pinned digest
+                    let checkpoint_digest = checkpoint.digest();
+                    if let Ok(pinned_digest_index) = pinned_checkpoints.binary_search_by_key(
+                        checkpoint.sequence_number(),
+                        |(seq_num, _digest)| *seq_num
+                    )
+                        && pinned_checkpoints[pinned_digest_index].1 != *checkpoint_digest {
                             tracing::debug!(
-                                "peer returned checkpoint with wrong sequence number: expected {next}, got {}",
-                                checkpoint.sequence_number()
+                                "peer returned checkpoint with digest that does not match pinned digest: expected {:?}, got {:?}",
+                                pinned_checkpoints[pinned_digest_index].1,
+                                checkpoint_digest
                             );
-                            continue;
+                            return None;
                         }
 
-                        // peer gave us a checkpoint whose digest does not match pinned digest
-                        let checkpoint_digest = checkpoint.digest();
-                        if let Ok(pinned_digest_index) = pinned_checkpoints.binary_search_by_key(
-                            checkpoint.sequence_number(),
-                            |(seq_num, _digest)| *seq_num
-                        )
-                            && pinned_checkpoints[pinned_digest_index].1 != *checkpoint_digest {
-                                tracing::debug!(
-                                    "peer returned checkpoint with digest that does not match pinned digest: expected {:?}, got {:?}",
-                                    pinned_checkpoints[pinned_digest_index].1,
-                                    checkpoint_digest
-                                );
-                                continue;
-                            }
+                    Some(checkpoint)
+                })
+                .await;
 
-                        // Insert in our store in the event that things fail and we need to retry
-                        peer_heights
-                            .write()
-                            .unwrap()
-                            .insert_checkpoint(checkpoint.clone());
-                        return (Some(checkpoint), next, Some(peer.inner().peer_id()));
-                    }
-                }
-                (None, next, None)
+                let Some((checkpoint, peer_id, _latency)) = response else {
+                    return (None, next, None);
+                };
+
+                // Insert in our store in the event that things fail and we need to retry
+                peer_heights
+                    .write()
+                    .unwrap()
+                    .insert_checkpoint(checkpoint.clone());
+                (Some(checkpoint), next, Some(peer_id))
             }
         })
         .pipe(futures::stream::iter)
@@ -1226,6 +1386,7 @@ async fn sync_checkpoint_contents<S>(
     peer_heights: Arc<RwLock<PeerHeights>>,
     sender: mpsc::WeakSender<StateSyncMessage>,
     checkpoint_event_sender: broadcast::Sender<VerifiedCheckpoint>,
+    metrics: Metrics,
     checkpoint_content_download_concurrency: usize,
     checkpoint_content_download_tx_concurrency: u64,
     timeout: Duration,
@@ -1287,6 +1448,7 @@ async fn sync_checkpoint_contents<S>(
                             network.clone(),
                             &store,
                             peer_heights.clone(),
+                            metrics.clone(),
                             timeout,
                             checkpoint,
                         ));
@@ -1320,6 +1482,7 @@ async fn sync_checkpoint_contents<S>(
                 network.clone(),
                 &store,
                 peer_heights.clone(),
+                metrics.clone(),
                 timeout,
                 next_checkpoint,
             ));
@@ -1344,6 +1507,7 @@ async fn sync_one_checkpoint_contents<S>(
     network: anemo::Network,
     store: S,
     peer_heights: Arc<RwLock<PeerHeights>>,
+    metrics: Metrics,
     timeout: Duration,
     checkpoint: VerifiedCheckpoint,
 ) -> Result<VerifiedCheckpoint, VerifiedCheckpoint>
@@ -1372,7 +1536,8 @@ where
     )
     .with_checkpoint(*checkpoint.sequence_number());
     let now = tokio::time::Instant::now();
-    let Some(_contents) = get_full_checkpoint_contents(peers, &store, &checkpoint, timeout).await
+    let Some(_contents) =
+        get_full_checkpoint_contents(peers, &store, &metrics, &checkpoint, timeout).await
     else {
         // Delay completion in case of error so we don't hammer the network with retries.
         let duration = peer_heights
@@ -1394,6 +1559,7 @@ where
 async fn get_full_checkpoint_contents<S>(
     peers: PeerBalancer,
     store: S,
+    metrics: &Metrics,
     checkpoint: &VerifiedCheckpoint,
     timeout: Duration,
 ) -> Option<FullCheckpointContents>
@@ -1407,33 +1573,47 @@ where
         return Some(contents);
     }
 
-    // Iterate through our selected peers trying each one in turn until we're able to
-    // successfully get the target checkpoint
-    for mut peer in peers {
+    // The number of transactions in the checkpoint, to judge how long a response should take.
+    let expected_size = match sequence_number.checked_sub(1) {
+        None => checkpoint.network_total_transactions,
+        Some(previous) => store
+            .get_checkpoint_by_sequence_number(previous)
+            .map_or(1, |previous| {
+                checkpoint
+                    .network_total_transactions
+                    .saturating_sub(previous.network_total_transactions)
+            }),
+    } as usize;
+
+    // Try our selected peers in turn until we're able to successfully get the target checkpoint
+    let size = |contents: &FullCheckpointContents| contents.size();
+    let response = request_from_peers(peers, metrics, expected_size, size, |mut peer| async move {
         debug!(
             ?timeout,
             "requesting checkpoint contents from {}",
             peer.inner().peer_id(),
         );
         let request = Request::new(digest).with_timeout(timeout);
-        if let Some(contents) = peer
-            .get_checkpoint_contents(request)
+        peer.get_checkpoint_contents(request)
             .await
             .tap_err(|e| trace!("{e:?}"))
             .ok()
             .and_then(Response::into_inner)
             .tap_none(|| trace!("peer unable to help sync"))
-            && contents.verify_digests(digest).is_ok()
-        {
-            let verified_contents = VerifiedCheckpointContents::new_unchecked(contents.clone());
-            store
-                .insert_checkpoint_contents(checkpoint, verified_contents)
-                .expect("store operation should not fail");
-            return Some(contents);
-        }
-    }
-    debug!("no peers had checkpoint contents");
-    None
+            .filter(|contents| contents.verify_digests(digest).is_ok())
+    })
+    .await;
+
+    let Some((contents, _peer_id, _latency)) = response else {
+        debug!("no peers had checkpoint contents");
+        return None;
+    };
+
+    let verified_contents = VerifiedCheckpointContents::new_unchecked(contents.clone());
+    store
+        .insert_checkpoint_contents(checkpoint, verified_contents)
+        .expect("store operation should not fail");
+    Some(contents)
 }
 
 async fn update_checkpoint_watermark_metrics<S>(
@@ -0,0 +1,238 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Scoring of peers by how well they serve state sync requests.
+//!
+//! For each peer and kind of request, we keep averages of the latency of successful responses,
+//! the rate of failed requests, and for checkpoint contents, the number of transactions downloaded
+//! per second. Observations decay exponentially with age, so that scores reflect how peers have
+//! performed recently, and peers that performed badly in the past are given another chance.
+//!
+//! Latencies are recorded per unit of response (per transaction, for checkpoint contents), so that
+//! peers are not ranked down for serving large checkpoints. Likewise, the delay before hedging a
+//! request is chosen per unit, and scaled by the size of the response expected.
+
+use super::PeerCheckpointRequestType;
+use anemo::PeerId;
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// The number of recent responses (across all peers) used to decide when to hedge a request.
+const RECENT_RESPONSES: usize = 256;
+
+/// Requests are not hedged until at least this many responses have been observed.
+const MIN_RESPONSES_TO_HEDGE: usize = 20;
+
+/// Lower bound on the success rate used when scoring a peer, so that peers that have only failed
+/// recently still have a finite score.
+const MIN_SUCCESS_RATE: f64 = 0.05;
+
+pub(super) struct PeerScores {
+    half_life: Duration,
+    hedge_latency_percentile: Option<f64>,
+    scores: HashMap<(PeerId, PeerCheckpointRequestType), PeerScore>,
+    /// The latency per unit and the number of units of recent responses.
+    recent_responses: HashMap<PeerCheckpointRequestType, VecDeque<(f64, usize)>>,
+}
+
+/// How well a single peer has served a single kind of request.
+#[derive(Default)]
+pub(super) struct PeerScore {
+    latency: DecayingAverage,
+    failures: DecayingAverage,
+    throughput: DecayingAverage,
+}
+
+/// An average of observations, where each observation's weight halves every half-life.
+#[derive(Default)]
+struct DecayingAverage {
+    sum: f64,
+    weight: f64,
+    updated: Option<Instant>,
+}
+
+impl PeerScores {
+    pub fn new(half_life: Duration, hedge_latency_percentile: Option<f64>) -> Self {
+        Self {
+            half_life,
+            hedge_latency_percentile,
+            scores: HashMap::new(),
+            recent_responses: HashMap::new(),
+        }
+    }
+
+    /// Record that `peer_id` responded successfully to a request after `latency`, with a response
+    /// made up of `units` units (transactions, for checkpoint contents).
+    pub fn record_success(
+        &mut self,
+        peer_id: PeerId,
+        request_type: PeerCheckpointRequestType,
+        latency: Duration,
+        units: usize,
+    ) {
+        let now = Instant::now();
+        let latency_per_unit = per_unit(latency, units);
+        let score = self.scores.entry((peer_id, request_type)).or_default();
+        score.latency.add(latency_per_unit, now, self.half_life);
+        score.failures.add(0.0, now, self.half_life);
+        if request_type == PeerCheckpointRequestType::Content {
+            let throughput = units as f64 / latency.as_secs_f64().max(f64::EPSILON);
+            score.throughput.add(throughput, now, self.half_life);
+        }
+
+        let recent = self.recent_responses.entry(request_type).or_default();
+        if recent.len() == RECENT_RESPONSES {
+            recent.pop_front();
+        }
+        recent.push_back((latency_per_unit, units));
+    }
+
+    /// Record that a request to `peer_id` was abandoned after `elapsed`, because another peer
+    /// responded first with a response of `units` units. The peer's latency was at least
+    /// `elapsed`, so it is recorded as a lower bound on its latency, so that peers that are
+    /// repeatedly beaten by a hedged request are ranked down.
+    pub fn record_cancelled(
+        &mut self,
+        peer_id: PeerId,
+        request_type: PeerCheckpointRequestType,
+        elapsed: Duration,
+        units: usize,
+    ) {
+        let now = Instant::now();
+        self.scores
+            .entry((peer_id, request_type))
+            .or_default()
+            .latency
+            .add(per_unit(elapsed, units), now, self.half_life);
+    }
+
+    /// Record that a request to `peer_id` failed, timed out, or returned invalid data.
+    pub fn record_failure(&mut self, peer_id: PeerId, request_type: PeerCheckpointRequestType) {
+        let now = Instant::now();
+        self.scores
+            .entry((peer_id, request_type))
+            .or_default()
+            .failures
+            .add(1.0, now, self.half_life);
+    }
+
+    /// The expected time for `peer_id` to successfully serve a unit of response: its average
+    /// latency per unit divided by its success rate. Lower is better.
+    ///
+    /// The peer's connection RTT, spread over the average number of units in recent responses,
+    /// counts as one observation of its latency, so that peers we have not sent requests to yet are
+    /// ranked by their RTT, and peers we have not sent requests to in a long time drift back
+    /// towards it.
+    pub fn expected_latency(
+        &self,
+        peer_id: PeerId,
+        request_type: PeerCheckpointRequestType,
+        rtt: Duration,
+    ) -> Duration {
+        let now = Instant::now();
+        let rtt = rtt.as_secs_f64() / self.average_units(request_type);
+        let (latency, success_rate) = match self.scores.get(&(peer_id, request_type)) {
+            None => (rtt, 1.0),
+            Some(score) => {
+                let (sum, weight) = score.latency.decayed(now, self.half_life);
+                let latency = (sum + rtt) / (weight + 1.0);
+
+                // Likewise, count one success towards the failure rate.
+                let (failures, weight) = score.failures.decayed(now, self.half_life);
+                (latency, 1.0 - failures / (weight + 1.0))
+            }
+        };
+
+        Duration::from_secs_f64(latency / success_rate.max(MIN_SUCCESS_RATE))
+    }
+
+    /// How long to wait for a response of `units` units before hedging a request, or `None` if the
+    /// request should not be hedged.
+    pub fn hedge_delay(
+        &self,
+        request_type: PeerCheckpointRequestType,
+        units: usize,
+    ) -> Option<Duration> {
+        let percentile = self.hedge_latency_percentile?;
+        let recent = self.recent_responses.get(&request_type)?;
+        if recent.len() < MIN_RESPONSES_TO_HEDGE {
+            return None;
+        }
+
+        let mut latencies: Vec<_> = recent.iter().map(|(latency, _)| *latency).collect();
+        latencies.sort_by(f64::total_cmp);
+        let idx = ((latencies.len() - 1) as f64 * percentile).round() as usize;
+        Some(Duration::from_secs_f64(
+            latencies[idx] * units.max(1) as f64,
+        ))
+    }
+
+    /// The average number of units in recent responses, or 1 if there are none.
+    fn average_units(&self, request_type: PeerCheckpointRequestType) -> f64 {
+        let Some(recent) = self.recent_responses.get(&request_type) else {
+            return 1.0;
+        };
+
+        let total: usize = recent.iter().map(|(_, units)| (*units).max(1)).sum();
+        (total as f64 / recent.len() as f64).max(1.0)
+    }
+
+    pub fn remove_peer(&mut self, peer_id: &PeerId) {
+        self.scores.retain(|(id, _), _| id != peer_id);
+    }
+
+    pub fn iter(
+        &self,
+    ) -> impl Iterator<Item = (&PeerId, PeerCheckpointRequestType, &PeerScore)> + '_ {
+        self.scores
+            .iter()
+            .map(|((peer_id, request_type), score)| (peer_id, *request_type, score))
+    }
+}
+
+impl PeerScore {
+    /// Average latency of successful responses, per unit of response.
+    pub fn latency(&self) -> Option<Duration> {
+        self.latency.mean().map(Duration::from_secs_f64)
+    }
+
+    /// Fraction of requests that failed.
+    pub fn failure_rate(&self) -> Option<f64> {
+        self.failures.mean()
+    }
+
+    /// Average number of transactions downloaded per second, for checkpoint contents.
+    pub fn throughput(&self) -> Option<f64> {
+        self.throughput.mean()
+    }
+}
+
+/// `latency` in seconds, spread over the `units` units of a response.
+fn per_unit(latency: Duration, units: usize) -> f64 {
+    latency.as_secs_f64() / units.max(1) as f64
+}
+
+impl DecayingAverage {
+    fn add(&mut self, value: f64, now: Instant, half_life: Duration) {
+        let (sum, weight) = self.decayed(now, half_life);
+        self.sum = sum + value;
+        self.weight = weight + 1.0;
+        self.updated = Some(now);
+    }
+
+    /// The sum and total weight of the observations, as of `now`.
+    fn decayed(&self, now: Instant, half_life: Duration) -> (f64, f64) {
+        let Some(updated) = self.updated else {
+            return (0.0, 0.0);
+        };
+
+        let age = now.saturating_duration_since(updated).as_secs_f64();
+        let decay = 0.5f64.powf(age / half_life.as_secs_f64().max(f64::EPSILON));
+        (self.sum * decay, self.weight * decay)
+    }
+
+    fn mean(&self) -> Option<f64> {
+        (self.weight > 0.0).then(|| self.sum / self.weight)
+    }
+}
@@ -3,8 +3,9 @@
 
 use crate::{
     state_sync::{
-        Builder, GetCheckpointSummaryRequest, PeerStateSyncInfo, StateSync, StateSyncMessage,
-        UnstartedStateSync,
+        Builder, GetCheckpointSummaryRequest, PeerCheckpointRequestType, PeerHeights,
+        PeerStateSyncInfo, StateSync, StateSyncMessage, UnstartedStateSync, hedged_request,
+        metrics::Metrics, peer_scores::PeerScores,
     },
     utils::build_network,
 };
@@ -12,7 +13,7 @@ use anemo::{PeerId, Request};
 use anyhow::anyhow;
 use std::io::Write;
 use std::num::NonZeroUsize;
-use std::{collections::HashMap, time::Duration};
+use std::{collections::HashMap, sync::RwLock, time::Duration};
 use sui_config::node::ArchiveReaderConfig;
 use sui_config::object_storage_config::ObjectStoreConfig;
 use sui_storage::blob::{Blob, BlobEncoding};
@@ -873,3 +874,285 @@ async fn sync_with_checkpoints_watermark() {
         &last_checkpoint_seq
     );
 }
+
+#[tokio::test(flavor = "current_thread", start_paused = true)]
+async fn peer_scores_rank_by_latency_and_failures() {
+    let mut scores = PeerScores::new(Duration::from_secs(60), Some(0.95));
+    let summary = PeerCheckpointRequestType::Summary;
+    let rtt = Duration::from_millis(10);
+    let fast = PeerId([1; 32]);
+    let slow = PeerId([2; 32]);
+    let flaky = PeerId([3; 32]);
+    let unknown = PeerId([4; 32]);
+
+    for _ in 0..10 {
+        scores.record_success(fast, summary, Duration::from_millis(20), 1);
+        scores.record_success(slow, summary, Duration::from_millis(200), 1);
+        scores.record_success(flaky, summary, Duration::from_millis(20), 1);
+        scores.record_failure(flaky, summary);
+        scores.record_failure(flaky, summary);
+    }
+
+    let fast_score = scores.expected_latency(fast, summary, rtt);
+    let slow_score = scores.expected_latency(slow, summary, rtt);
+    let flaky_score = scores.expected_latency(flaky, summary, rtt);
+    assert!(fast_score < slow_score);
+    assert!(fast_score < flaky_score);
+
+    // Peers we haven't sent requests to are ranked by their RTT.
+    assert_eq!(scores.expected_latency(unknown, summary, rtt), rtt);
+
+    // Scores are tracked separately for each kind of request.
+    assert_eq!(
+        scores.expected_latency(slow, PeerCheckpointRequestType::Content, rtt),
+        rtt
+    );
+
+    // Old observations decay, so peers drift back towards their RTT.
+    tokio::time::advance(Duration::from_secs(600)).await;
+    let decayed = scores.expected_latency(slow, summary, rtt);
+    assert!(decayed < slow_score);
+    assert!(decayed < Duration::from_millis(12));
+
+    scores.remove_peer(&slow);
+    assert_eq!(scores.expected_latency(slow, summary, rtt), rtt);
+}
+
+#[tokio::test(flavor = "current_thread", start_paused = true)]
+async fn peer_scores_hedge_delay() {
+    let summary = PeerCheckpointRequestType::Summary;
+    let peer = PeerId([1; 32]);
+
+    let mut scores = PeerScores::new(Duration::from_secs(60), Some(0.9));
+    for ms in 1..=10 {
+        scores.record_success(peer, summary, Duration::from_millis(ms), 1);
+    }
+    // Not enough latencies observed yet.
+    assert_eq!(scores.hedge_delay(summary, 1), None);
+
+    for ms in 11..=100 {
+        scores.record_success(peer, summary, Duration::from_millis(ms), 1);
+    }
+    assert_eq!(
+        scores.hedge_delay(summary, 1),
+        Some(Duration::from_millis(90))
+    );
+    assert_eq!(
+        scores.hedge_delay(PeerCheckpointRequestType::Content, 1),
+        None
+    );
+
+    // Hedging can be disabled.
+    let mut scores = PeerScores::new(Duration::from_secs(60), None);
+    for ms in 1..=100 {
+        scores.record_success(peer, summary, Duration::from_millis(ms), 1);
+    }
+    assert_eq!(scores.hedge_delay(summary, 1), None);
+}
+
+#[tokio::test(flavor = "current_thread", start_paused = true)]
+async fn peer_scores_normalize_content_latency() {
+    let mut scores = PeerScores::new(Duration::from_secs(60), None);
+    let content = PeerCheckpointRequestType::Content;
+    let rtt = Duration::from_millis(10);
+    let large = PeerId([1; 32]);
+    let small = PeerId([2; 32]);
+
+    // Serving a checkpoint with 100 transactions in 200ms is faster per transaction than serving
+    // a checkpoint with one transaction in 20ms.
+    for _ in 0..10 {
+        scores.record_success(large, content, Duration::from_millis(200), 100);
+        scores.record_success(small, content, Duration::from_millis(20), 1);
+    }
+
+    assert!(
+        scores.expected_latency(large, content, rtt) < scores.expected_latency(small, content, rtt)
+    );
+
+    let throughput = |peer| {
+        scores
+            .iter()
+            .find(|(id, request_type, _)| **id == peer && *request_type == content)
+            .and_then(|(_, _, score)| score.throughput())
+            .unwrap()
+    };
+    assert_eq!(throughput(large), 500.0);
+    assert_eq!(throughput(small), 50.0);
+}
+
+#[tokio::test(flavor = "current_thread", start_paused = true)]
+async fn peer_scores_scale_content_latency_by_size() {
+    let mut scores = PeerScores::new(Duration::from_secs(60), Some(0.5));
+    let content = PeerCheckpointRequestType::Content;
+    let rtt = Duration::from_millis(10);
+    let peer = PeerId([1; 32]);
+    let unknown = PeerId([2; 32]);
+
+    // Checkpoints of 10 transactions take 100ms, or 10ms per transaction.
+    for _ in 0..20 {
+        scores.record_success(peer, content, Duration::from_millis(100), 10);
+    }
+
+    // The hedging delay is scaled by the size of the response expected.
+    assert_eq!(
+        scores.hedge_delay(content, 1),
+        Some(Duration::from_millis(10))
+    );
+    assert_eq!(
+        scores.hedge_delay(content, 50),
+        Some(Duration::from_millis(500))
+    );
+
+    // The RTT of peers is spread over the average size of recent responses.
+    assert_eq!(
+        scores.expected_latency(unknown, content, rtt),
+        Duration::from_millis(1)
+    );
+}
+
+fn test_peer_heights(hedge_latency_percentile: Option<f64>) -> RwLock<PeerHeights> {
+    RwLock::new(PeerHeights {
+        peers: HashMap::new(),
+        unprocessed_checkpoints: HashMap::new(),
+        sequence_number_to_digest: HashMap::new(),
+        peer_scores: PeerScores::new(Duration::from_secs(60), hedge_latency_percentile),
+        wait_interval_when_no_peer_to_sync_content: Duration::from_secs(1),
+    })
+}
+
+#[tokio::test(flavor = "current_thread", start_paused = true)]
+async fn hedged_request_falls_back_on_failure() {
+    let summary = PeerCheckpointRequestType::Summary;
+    let rtt = Duration::from_millis(10);
+    let failing = PeerId([1; 32]);
+    let working = PeerId([2; 32]);
+    let heights = test_peer_heights(None);
+
+    // Each peer responds with its handle after 10ms, if it has one.
+    let request = |response: Option<u64>| async move {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        response
+    };
+
+    let peers = vec![(failing, None), (working, Some(42))];
+    let (response, peer_id, latency) = hedged_request(
+        peers.into_iter(),
+        summary,
+        &heights,
+        &Metrics::disabled(),
+        1,
+        |_| 1,
+        request,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(response, 42);
+    assert_eq!(peer_id, working);
+    assert_eq!(latency, Duration::from_millis(10));
+
+    let heights = heights.read().unwrap();
+    let failure_rate = |peer| {
+        heights
+            .peer_scores
+            .iter()
+            .find(|(id, request_type, _)| **id == peer && *request_type == summary)
+            .and_then(|(_, _, score)| score.failure_rate())
+    };
+    assert_eq!(failure_rate(failing), Some(1.0));
+    assert_eq!(failure_rate(working), Some(0.0));
+    assert!(
+        heights.peer_scores.expected_latency(working, summary, rtt)
+            < heights.peer_scores.expected_latency(failing, summary, rtt)
+    );
+    drop(heights);
+
+    // If every peer fails, there is no response.
+    let heights = test_peer_heights(None);
+    let peers = vec![(failing, None), (working, None)];
+    let response = hedged_request(
+        peers.into_iter(),
+        summary,
+        &heights,
+        &Metrics::disabled(),
+        1,
+        |_| 1,
+        request,
+    )
+    .await;
+    assert!(response.is_none());
+}
+
+#[tokio::test(flavor = "current_thread", start_paused = true)]
+async fn hedged_request_records_abandoned_requests() {
+    let summary = PeerCheckpointRequestType::Summary;
+    let rtt = Duration::from_millis(10);
+    let slow = PeerId([1; 32]);
+    let fast = PeerId([2; 32]);
+    let heights = test_peer_heights(Some(0.5));
+
+    // Observe enough 100ms latencies from some other peer to start hedging after 100ms.
+    {
+        let mut heights = heights.write().unwrap();
+        for _ in 0..20 {
+            heights.peer_scores.record_success(
+                PeerId([3; 32]),
+                summary,
+                Duration::from_millis(100),
+                1,
+            );
+        }
+        assert_eq!(
+            heights.peer_scores.hedge_delay(summary, 1),
+            Some(Duration::from_millis(100))
+        );
+    }
+
+    // Each peer responds after the delay it is given.
+    let request = |delay: Duration| async move {
+        tokio::time::sleep(delay).await;
+        Some(delay)
+    };
+
+    let start = Instant::now();
+    let peers = vec![
+        (slow, Duration::from_secs(10)),
+        (fast, Duration::from_millis(10)),
+    ];
+    let (response, peer_id, latency) = hedged_request(
+        peers.into_iter(),
+        summary,
+        &heights,
+        &Metrics::disabled(),
+        1,
+        |_| 1,
+        request,
+    )
+    .await
+    .unwrap();
+
+    // The request is hedged to the fast peer after 100ms, which wins without waiting for the slow
+    // peer.
+    assert_eq!(response, Duration::from_millis(10));
+    assert_eq!(peer_id, fast);
+    assert_eq!(latency, Duration::from_millis(10));
+    assert_eq!(start.elapsed(), Duration::from_millis(110));
+
+    // The slow peer's abandoned request is recorded as a lower bound on its latency, without
+    // counting as a failure.
+    let heights = heights.read().unwrap();
+    let score = |peer| {
+        heights
+            .peer_scores
+            .iter()
+            .find(|(id, request_type, _)| **id == peer && *request_type == summary)
+            .map(|(_, _, score)| (score.latency().unwrap().as_millis(), score.failure_rate()))
+            .unwrap()
+    };
+    assert_eq!(score(slow), (110, None));
+    assert_eq!(score(fast), (10, Some(0.0)));
+    assert!(
+        heights.peer_scores.expected_latency(fast, summary, rtt)
+            < heights.peer_scores.expected_latency(slow, summary, rtt)
+    );
+}
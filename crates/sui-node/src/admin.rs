@@ -3,7 +3,7 @@
 
 use crate::SuiNode;
 use axum::{
-    Router,
+    Json, Router,
     extract::{Query, State},
     http::StatusCode,
     routing::{get, post},
@@ -12,10 +12,14 @@ use base64::Engine;
 use humantime::parse_duration;
 use serde::Deserialize;
 use std::sync::Arc;
+use std::time::Duration;
 use std::{
     net::{IpAddr, Ipv4Addr, SocketAddr},
     str::FromStr,
 };
+use sui_core::traffic_controller::{
+    BlocklistEntryInfo, ClientStatus, TrafficControlTopClients, TrafficController,
+};
 use sui_types::{
     base_types::AuthorityName,
     crypto::{RandomnessPartialSignature, RandomnessRound, RandomnessSignature},
@@ -77,6 +81,29 @@ use tracing::info;
 // Reconfigure traffic control policy
 //
 //  $ curl 'http://127.0.0.1:1337/traffic-control?error_threshold=100&spam_threshold=100&dry_run=true'
+//
+// View the full traffic control state, or swap the policy config (the body is a
+// `TrafficControlReconfigParams`, whose `policy_config` replaces the running one)
+//
+//  $ curl 'http://127.0.0.1:1337/traffic-control/policy'
+//  $ curl -X POST -H 'Content-Type: application/json' -d @params.json 'http://127.0.0.1:1337/traffic-control/policy'
+//
+// List blocked clients, optionally only those whose IP contains the search string
+//
+//  $ curl 'http://127.0.0.1:1337/traffic-control/blocklist?search=10.0.&limit=100'
+//
+// Manually block or unblock a client. Set `proxied=true` to target the proxied client blocklist.
+//
+//  $ curl -X POST 'http://127.0.0.1:1337/traffic-control/block?ip=1.2.3.4&ttl_secs=600'
+//  $ curl -X POST 'http://127.0.0.1:1337/traffic-control/unblock?ip=1.2.3.4'
+//
+// Show the clients with the highest recent request rates
+//
+//  $ curl 'http://127.0.0.1:1337/traffic-control/top-clients?n=10'
+//
+// Show a client's blocklist status and how close it is to the policy thresholds
+//
+//  $ curl 'http://127.0.0.1:1337/traffic-control/client?ip=1.2.3.4'
 
 const LOGGING_ROUTE: &str = "/logging";
 const TRACING_ROUTE: &str = "/enable-tracing";
@@ -92,6 +119,15 @@ const RANDOMNESS_INJECT_FULL_SIG_ROUTE: &str = "/randomness-inject-full-sig";
 const GET_TX_COST_ROUTE: &str = "/get-tx-cost";
 const DUMP_CONSENSUS_TX_COST_ESTIMATES_ROUTE: &str = "/dump-consensus-tx-cost-estimates";
 const TRAFFIC_CONTROL: &str = "/traffic-control";
+const TRAFFIC_CONTROL_POLICY: &str = "/traffic-control/policy";
+const TRAFFIC_CONTROL_BLOCKLIST: &str = "/traffic-control/blocklist";
+const TRAFFIC_CONTROL_BLOCK: &str = "/traffic-control/block";
+const TRAFFIC_CONTROL_UNBLOCK: &str = "/traffic-control/unblock";
+const TRAFFIC_CONTROL_TOP_CLIENTS: &str = "/traffic-control/top-clients";
+const TRAFFIC_CONTROL_CLIENT: &str = "/traffic-control/client";
+
+const DEFAULT_BLOCKLIST_LIMIT: usize = 1000;
+const DEFAULT_TOP_CLIENTS: usize = 10;
 
 struct AppState {
     node: Arc<SuiNode>,
@@ -137,6 +173,18 @@ pub async fn run_admin_server(node: Arc<SuiNode>, port: u16, tracing_handle: Tra
             get(dump_consensus_tx_cost_estimates),
         )
         .route(TRAFFIC_CONTROL, post(traffic_control))
+        .route(
+            TRAFFIC_CONTROL_POLICY,
+            get(traffic_control_state).post(traffic_control_swap_policy),
+        )
+        .route(TRAFFIC_CONTROL_BLOCKLIST, get(traffic_control_blocklist))
+        .route(TRAFFIC_CONTROL_BLOCK, post(traffic_control_block))
+        .route(TRAFFIC_CONTROL_UNBLOCK, post(traffic_control_unblock))
+        .route(
+            TRAFFIC_CONTROL_TOP_CLIENTS,
+            get(traffic_control_top_clients),
+        )
+        .route(TRAFFIC_CONTROL_CLIENT, get(traffic_control_client))
         .with_state(Arc::new(app_state));
 
     let socket_address = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), port);
@@ -523,3 +571,141 @@ async fn traffic_control(
         Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()),
     }
 }
+
+type JsonResult<T> = Result<Json<T>, (StatusCode, String)>;
+
+fn traffic_controller(state: &AppState) -> Result<Arc<TrafficController>, (StatusCode, String)> {
+    state
+        .node
+        .state()
+        .traffic_controller
+        .clone()
+        .ok_or_else(|| {
+            (
+                StatusCode::BAD_REQUEST,
+                "Traffic controller is not configured on this node".to_string(),
+            )
+        })
+}
+
+async fn traffic_control_state(
+    State(state): State<Arc<AppState>>,
+) -> JsonResult<TrafficControlReconfigParams> {
+    let traffic_controller = traffic_controller(&state)?;
+    Ok(Json(traffic_controller.get_current_state().await))
+}
+
+async fn traffic_control_swap_policy(
+    State(state): State<Arc<AppState>>,
+    Json(params): Json<TrafficControlReconfigParams>,
+) -> JsonResult<TrafficControlReconfigParams> {
+    match state.node.state().reconfigure_traffic_control(params).await {
+        Ok(updated_state) => Ok(Json(updated_state)),
+        Err(err) => Err((StatusCode::BAD_REQUEST, err.to_string())),
+    }
+}
+
+#[derive(Deserialize)]
+struct TrafficControlBlocklist {
+    search: Option<String>,
+    limit: Option<usize>,
+}
+
+async fn traffic_control_blocklist(
+    State(state): State<Arc<AppState>>,
+    args: Query<TrafficControlBlocklist>,
+) -> JsonResult<Vec<BlocklistEntryInfo>> {
+    let Query(TrafficControlBlocklist { search, limit }) = args;
+    traffic_controller(&state)?
+        .admin_list_blocklists(search.as_deref(), limit.unwrap_or(DEFAULT_BLOCKLIST_LIMIT))
+        .map(Json)
+        .map_err(|err| (StatusCode::BAD_REQUEST, err.to_string()))
+}
+
+#[derive(Deserialize)]
+struct TrafficControlBlock {
+    ip: IpAddr,
+    ttl_secs: u64,
+    #[serde(default)]
+    proxied: bool,
+}
+
+async fn traffic_control_block(
+    State(state): State<Arc<AppState>>,
+    args: Query<TrafficControlBlock>,
+) -> (StatusCode, String) {
+    let Query(TrafficControlBlock {
+        ip,
+        ttl_secs,
+        proxied,
+    }) = args;
+    let traffic_controller = match traffic_controller(&state) {
+        Ok(traffic_controller) => traffic_controller,
+        Err(err) => return err,
+    };
+    match traffic_controller.admin_block(ip, proxied, Duration::from_secs(ttl_secs)) {
+        Ok(()) => (
+            StatusCode::OK,
+            format!("{} blocked for {} seconds\n", ip, ttl_secs),
+        ),
+        Err(err) => (StatusCode::BAD_REQUEST, err.to_string()),
+    }
+}
+
+#[derive(Deserialize)]
+struct TrafficControlUnblock {
+    ip: IpAddr,
+    #[serde(default)]
+    proxied: bool,
+}
+
+async fn traffic_control_unblock(
+    State(state): State<Arc<AppState>>,
+    args: Query<TrafficControlUnblock>,
+) -> (StatusCode, String) {
+    let Query(TrafficControlUnblock { ip, proxied }) = args;
+    let traffic_controller = match traffic_controller(&state) {
+        Ok(traffic_controller) => traffic_controller,
+        Err(err) => return err,
+    };
+    match traffic_controller.admin_unblock(ip, proxied) {
+        Ok(true) => (StatusCode::OK, format!("{} unblocked\n", ip)),
+        Ok(false) => (StatusCode::NOT_FOUND, format!("{} was not blocked\n", ip)),
+        Err(err) => (StatusCode::BAD_REQUEST, err.to_string()),
+    }
+}
+
+#[derive(Deserialize)]
+struct TrafficControlTopClientsArgs {
+    n: Option<usize>,
+}
+
+async fn traffic_control_top_clients(
+    State(state): State<Arc<AppState>>,
+    args: Query<TrafficControlTopClientsArgs>,
+) -> JsonResult<TrafficControlTopClients> {
+    let Query(TrafficControlTopClientsArgs { n }) = args;
+    let traffic_controller = traffic_controller(&state)?;
+    Ok(Json(
+        traffic_controller
+            .admin_top_clients(n.unwrap_or(DEFAULT_TOP_CLIENTS))
+            .await,
+    ))
+}
+
+#[derive(Deserialize)]
+struct TrafficControlClient {
+    ip: IpAddr,
+}
+
+async fn traffic_control_client(
+    State(state): State<Arc<AppState>>,
+    args: Query<TrafficControlClient>,
+) -> JsonResult<ClientStatus> {
+    let Query(TrafficControlClient { ip }) = args;
+    traffic_controller(&state)?
+        .admin_client_status(ip)
+        .await
+        .map(Json)
+        .map_err(|err| (StatusCode::BAD_REQUEST, err.to_string()))
+}
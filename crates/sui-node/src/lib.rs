@@ -506,15 +506,19 @@ impl SuiNode {
         let mut pruner_db = None;
         if config
             .authority_store_pruning_config
-            .enable_compaction_filter
+            .enable_compaction_filter()
         {
             pruner_db = Some(Arc::new(AuthorityPrunerTables::open(
                 &config.db_path().join("store"),
             )));
         }
-        let compaction_filter = pruner_db
-            .clone()
-            .map(|db| ObjectsCompactionFilter::new(db, &prometheus_registry));
+        let compaction_filter = pruner_db.clone().map(|db| {
+            ObjectsCompactionFilter::new(
+                db,
+                &config.authority_store_pruning_config.history_retention,
+                &prometheus_registry,
+            )
+        });
 
         // By default, only enable write stall on validators for perpetual db.
         let enable_write_stall = config.enable_db_write_stall.unwrap_or(is_validator);
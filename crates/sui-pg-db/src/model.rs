@@ -5,7 +5,7 @@ use chrono::NaiveDateTime;
 use diesel::prelude::*;
 use sui_field_count::FieldCount;
 
-use crate::schema::watermarks;
+use crate::schema::{dead_letters, watermarks};
 
 #[derive(Insertable, Selectable, Queryable, Debug, Clone, FieldCount)]
 #[diesel(table_name = watermarks)]
@@ -19,3 +19,13 @@ pub struct StoredWatermark {
     pub pruner_timestamp: NaiveDateTime,
     pub pruner_hi: i64,
 }
+
+#[derive(Selectable, Queryable, Debug, Clone)]
+#[diesel(table_name = dead_letters)]
+pub struct StoredDeadLetter {
+    pub pipeline: String,
+    pub checkpoint: i64,
+    pub error: String,
+    pub attempts: i64,
+    pub updated_at: NaiveDateTime,
+}
@@ -2,6 +2,16 @@
 // SPDX-License-Identifier: Apache-2.0
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    dead_letters (pipeline, checkpoint) {
+        pipeline -> Text,
+        checkpoint -> Int8,
+        error -> Text,
+        attempts -> Int8,
+        updated_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     watermarks (pipeline) {
         pipeline -> Text,
@@ -14,3 +24,5 @@ diesel::table! {
         pruner_hi -> Int8,
     }
 }
+
+diesel::allow_tables_to_appear_in_same_query!(dead_letters, watermarks,);
@@ -14,8 +14,8 @@ use scoped_futures::ScopedBoxFuture;
 use sui_indexer_alt_framework_store_traits as store;
 use sui_sql_macro::sql;
 
-use crate::model::StoredWatermark;
-use crate::schema::watermarks;
+use crate::model::{StoredDeadLetter, StoredWatermark};
+use crate::schema::{dead_letters, watermarks};
 use crate::{Connection, Db};
 
 pub use sui_indexer_alt_framework_store_traits::Store;
@@ -171,12 +171,74 @@ impl store::Connection for Connection<'_> {
             .await?
             > 0)
     }
+
+    async fn record_dead_letter(
+        &mut self,
+        pipeline: &str,
+        checkpoint: u64,
+        error: &str,
+    ) -> anyhow::Result<()> {
+        diesel::insert_into(dead_letters::table)
+            .values((
+                dead_letters::pipeline.eq(pipeline),
+                dead_letters::checkpoint.eq(checkpoint as i64),
+                dead_letters::error.eq(error),
+                dead_letters::attempts.eq(1),
+                dead_letters::updated_at.eq(diesel::dsl::now),
+            ))
+            // The checkpoint was dead-lettered before, so this is a failed attempt to re-drive it.
+            .on_conflict((dead_letters::pipeline, dead_letters::checkpoint))
+            .do_update()
+            .set((
+                dead_letters::error.eq(error),
+                dead_letters::attempts.eq(dead_letters::attempts + 1),
+                dead_letters::updated_at.eq(diesel::dsl::now),
+            ))
+            .execute(self)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn dead_letters(&mut self, pipeline: &str) -> anyhow::Result<Vec<store::DeadLetter>> {
+        let stored: Vec<StoredDeadLetter> = dead_letters::table
+            .select(StoredDeadLetter::as_select())
+            .filter(dead_letters::pipeline.eq(pipeline))
+            .order_by(dead_letters::checkpoint)
+            .load(self)
+            .await?;
+
+        Ok(stored
+            .into_iter()
+            .map(|d| store::DeadLetter {
+                pipeline: d.pipeline,
+                checkpoint: d.checkpoint as u64,
+                error: d.error,
+                attempts: d.attempts as u64,
+            })
+            .collect())
+    }
+
+    async fn delete_dead_letter(
+        &mut self,
+        pipeline: &str,
+        checkpoint: u64,
+    ) -> anyhow::Result<bool> {
+        Ok(diesel::delete(dead_letters::table)
+            .filter(dead_letters::pipeline.eq(pipeline))
+            .filter(dead_letters::checkpoint.eq(checkpoint as i64))
+            .execute(self)
+            .await?
+            > 0)
+    }
 }
 
 #[async_trait]
 impl store::Store for Db {
     type Connection<'c> = Connection<'c>;
 
+    const SUPPORTS_DEAD_LETTERS: bool = true;
+
     async fn connect<'c>(&'c self) -> anyhow::Result<Self::Connection<'c>> {
         Ok(Connection(self.0.get().await?))
     }
@@ -0,0 +1,289 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use sui_types::{
+    base_types::{ObjectID, SequenceNumber},
+    digests::{ObjectDigest, TransactionDigest, TransactionEventsDigest},
+    effects::{IDOperation, ObjectChange, TransactionEffects, TransactionEffectsAPI},
+    execution_status::ExecutionStatus,
+    gas::GasCostSummary,
+};
+
+/// A value that is different in the on-chain and in the replayed effects.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValueChange<T> {
+    pub expected: T,
+    pub actual: T,
+}
+
+/// How an object was changed by a transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ObjectOperation {
+    Created,
+    Mutated,
+    Deleted,
+    Wrapped,
+    Unwrapped,
+    /// Neither created nor deleted, and with no input or output version. Well-formed effects
+    /// never hold such a change, but forked effects may.
+    Invalid,
+}
+
+/// The change to an object as recorded in transaction effects.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ObjectChangeSummary {
+    pub operation: ObjectOperation,
+    pub output_version: Option<SequenceNumber>,
+    pub output_digest: Option<ObjectDigest>,
+}
+
+/// An object whose change differs between the on-chain and the replayed effects.
+/// `None` means the object was not changed by that execution.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ObjectChangeDiff {
+    pub object_id: ObjectID,
+    pub expected: Option<ObjectChangeSummary>,
+    pub actual: Option<ObjectChangeSummary>,
+}
+
+/// Structured difference between the on-chain effects of a transaction and the effects
+/// of a replay of the transaction. Only the parts that differ are reported.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EffectsDiff {
+    pub transaction_digest: TransactionDigest,
+    pub status: Option<ValueChange<ExecutionStatus>>,
+    pub gas_cost_summary: Option<ValueChange<GasCostSummary>>,
+    pub events_digest: Option<ValueChange<Option<TransactionEventsDigest>>>,
+    pub object_changes: Vec<ObjectChangeDiff>,
+}
+
+impl EffectsDiff {
+    pub fn new(expected: &TransactionEffects, actual: &TransactionEffects) -> Self {
+        let expected_changes = object_changes(expected);
+        let mut actual_changes = object_changes(actual);
+        let mut object_changes = vec![];
+        for (object_id, expected) in expected_changes {
+            let actual = actual_changes.remove(&object_id);
+            if actual.as_ref() != Some(&expected) {
+                object_changes.push(ObjectChangeDiff {
+                    object_id,
+                    expected: Some(expected),
+                    actual,
+                });
+            }
+        }
+        object_changes.extend(actual_changes.into_iter().map(|(object_id, actual)| {
+            ObjectChangeDiff {
+                object_id,
+                expected: None,
+                actual: Some(actual),
+            }
+        }));
+        object_changes.sort_by_key(|change| change.object_id);
+
+        Self {
+            transaction_digest: *expected.transaction_digest(),
+            status: value_change(expected.status(), actual.status()),
+            gas_cost_summary: value_change(expected.gas_cost_summary(), actual.gas_cost_summary()),
+            events_digest: value_change(
+                &expected.events_digest().copied(),
+                &actual.events_digest().copied(),
+            ),
+            object_changes,
+        }
+    }
+
+    /// Whether the replayed effects match the on-chain effects in everything reported.
+    pub fn is_empty(&self) -> bool {
+        self.status.is_none()
+            && self.gas_cost_summary.is_none()
+            && self.events_digest.is_none()
+            && self.object_changes.is_empty()
+    }
+}
+
+fn value_change<T: Clone + PartialEq>(expected: &T, actual: &T) -> Option<ValueChange<T>> {
+    (expected != actual).then(|| ValueChange {
+        expected: expected.clone(),
+        actual: actual.clone(),
+    })
+}
+
+fn object_changes(effects: &TransactionEffects) -> BTreeMap<ObjectID, ObjectChangeSummary> {
+    effects
+        .object_changes()
+        .into_iter()
+        .map(|change| (change.id, ObjectChangeSummary::from(change)))
+        .collect()
+}
+
+impl From<ObjectChange> for ObjectChangeSummary {
+    fn from(change: ObjectChange) -> Self {
+        let operation = match (
+            change.id_operation,
+            change.input_version,
+            change.output_version,
+        ) {
+            (IDOperation::Created, _, _) => ObjectOperation::Created,
+            (IDOperation::Deleted, _, _) => ObjectOperation::Deleted,
+            (IDOperation::None, Some(_), Some(_)) => ObjectOperation::Mutated,
+            (IDOperation::None, Some(_), None) => ObjectOperation::Wrapped,
+            (IDOperation::None, None, Some(_)) => ObjectOperation::Unwrapped,
+            (IDOperation::None, None, None) => ObjectOperation::Invalid,
+        };
+        Self {
+            operation,
+            output_version: change.output_version,
+            output_digest: change.output_digest,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_types::{
+        base_types::SuiAddress,
+        crypto::{AccountKeyPair, get_key_pair},
+        effects::TestEffectsBuilder,
+        execution_status::ExecutionFailureStatus,
+        object::Owner,
+        programmable_transaction_builder::ProgrammableTransactionBuilder,
+        transaction::{SenderSignedData, TransactionData},
+        utils::to_sender_signed_transaction,
+    };
+
+    fn test_transaction() -> (SuiAddress, SenderSignedData) {
+        let (sender, keypair): (_, AccountKeyPair) = get_key_pair();
+        let gas = (
+            ObjectID::random(),
+            SequenceNumber::from_u64(1),
+            ObjectDigest::random(),
+        );
+        let data = TransactionData::new_programmable(
+            sender,
+            vec![gas],
+            ProgrammableTransactionBuilder::new().finish(),
+            1000,
+            1,
+        );
+        (
+            sender,
+            to_sender_signed_transaction(data, &keypair).into_data(),
+        )
+    }
+
+    fn change(
+        id_operation: IDOperation,
+        input_version: Option<u64>,
+        output_version: Option<u64>,
+    ) -> ObjectChange {
+        ObjectChange {
+            id: ObjectID::random(),
+            input_version: input_version.map(SequenceNumber::from_u64),
+            input_digest: input_version.map(|_| ObjectDigest::MIN),
+            output_version: output_version.map(SequenceNumber::from_u64),
+            output_digest: output_version.map(|_| ObjectDigest::MAX),
+            id_operation,
+        }
+    }
+
+    #[test]
+    fn test_object_change_summary() {
+        use ObjectOperation as O;
+        let operation = |change| ObjectChangeSummary::from(change).operation;
+        assert_eq!(
+            operation(change(IDOperation::Created, None, Some(2))),
+            O::Created
+        );
+        assert_eq!(
+            operation(change(IDOperation::Deleted, Some(1), None)),
+            O::Deleted
+        );
+        assert_eq!(
+            operation(change(IDOperation::Deleted, None, None)),
+            O::Deleted
+        );
+        assert_eq!(
+            operation(change(IDOperation::None, Some(1), Some(2))),
+            O::Mutated
+        );
+        assert_eq!(
+            operation(change(IDOperation::None, Some(1), None)),
+            O::Wrapped
+        );
+        assert_eq!(
+            operation(change(IDOperation::None, None, Some(2))),
+            O::Unwrapped
+        );
+        assert_eq!(operation(change(IDOperation::None, None, None)), O::Invalid);
+
+        let summary = ObjectChangeSummary::from(change(IDOperation::None, Some(1), Some(2)));
+        assert_eq!(summary.output_version, Some(SequenceNumber::from_u64(2)));
+        assert_eq!(summary.output_digest, Some(ObjectDigest::MAX));
+    }
+
+    #[test]
+    fn test_effects_diff_identical() {
+        let (sender, txn) = test_transaction();
+        let object_id = ObjectID::random();
+        let effects = TestEffectsBuilder::new(&txn)
+            .with_created_objects([(object_id, Owner::AddressOwner(sender))])
+            .build();
+
+        let diff = EffectsDiff::new(&effects, &effects);
+        assert!(diff.is_empty());
+        assert_eq!(diff.transaction_digest, *effects.transaction_digest());
+    }
+
+    #[test]
+    fn test_effects_diff() {
+        let (sender, txn) = test_transaction();
+        let expected_object = ObjectID::random();
+        let actual_object = ObjectID::random();
+        let expected = TestEffectsBuilder::new(&txn)
+            .with_created_objects([(expected_object, Owner::AddressOwner(sender))])
+            .build();
+        let actual = TestEffectsBuilder::new(&txn)
+            .with_status(ExecutionStatus::new_failure(
+                ExecutionFailureStatus::InsufficientGas,
+                None,
+            ))
+            .with_created_objects([(actual_object, Owner::AddressOwner(sender))])
+            .build();
+
+        let diff = EffectsDiff::new(&expected, &actual);
+        assert!(!diff.is_empty());
+        let status = diff.status.unwrap();
+        assert_eq!(&status.expected, expected.status());
+        assert_eq!(&status.actual, actual.status());
+        assert!(diff.gas_cost_summary.is_none());
+        assert!(diff.events_digest.is_none());
+
+        // The gas object is mutated the same way by both, so only the created objects differ.
+        let mut ids = vec![expected_object, actual_object];
+        ids.sort();
+        assert_eq!(
+            diff.object_changes
+                .iter()
+                .map(|change| change.object_id)
+                .collect::<Vec<_>>(),
+            ids
+        );
+        for change in &diff.object_changes {
+            let (present, missing) = if change.object_id == expected_object {
+                (&change.expected, &change.actual)
+            } else {
+                (&change.actual, &change.expected)
+            };
+            assert_eq!(
+                present.as_ref().unwrap().operation,
+                ObjectOperation::Created
+            );
+            assert!(missing.is_none());
+        }
+    }
+}
@@ -1,7 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::artifacts::{MoveCallInfo, ReplayCacheSummary};
+use crate::artifacts::{EffectsDiff, MoveCallInfo, ReplayCacheSummary};
 use anyhow::{Result, anyhow, bail};
 use move_trace_format::format::{MoveTrace, MoveTraceReader};
 use std::{
@@ -13,7 +13,7 @@ use sui_types::{effects::TransactionEffects, gas::GasUsageReport};
 pub const ARTIFACTS_ENCODING_EXT: &str = "json";
 pub const ARTIFACTS_ENCODING_COMPRESSION_EXT: &str = "json.zst";
 
-pub const ARTIFACTS: [Artifact; 7] = [
+pub const ARTIFACTS: [Artifact; 8] = [
     Artifact::Trace,
     Artifact::TransactionData,
     Artifact::TransactionEffects,
@@ -21,6 +21,7 @@ pub const ARTIFACTS: [Artifact; 7] = [
     Artifact::ForkedTransactionEffects,
     Artifact::ReplayCacheSummary,
     Artifact::MoveCallInfo,
+    Artifact::EffectsDiff,
 ];
 
 /// The types of artifacts that the replay tool knows about and may output.
@@ -33,6 +34,7 @@ pub enum Artifact {
     ForkedTransactionEffects,
     ReplayCacheSummary,
     MoveCallInfo,
+    EffectsDiff,
 }
 
 /// Encoding types for artifacts that may be output by the replay tool.
@@ -103,6 +105,7 @@ impl Artifact {
             Artifact::TransactionGasReport => "transaction_gas_report",
             Artifact::ReplayCacheSummary => "replay_cache_summary",
             Artifact::MoveCallInfo => "move_call_info",
+            Artifact::EffectsDiff => "effects_diff",
         }
     }
 
@@ -115,7 +118,8 @@ impl Artifact {
             | Artifact::TransactionEffects
             | Artifact::TransactionGasReport
             | Artifact::ReplayCacheSummary
-            | Artifact::MoveCallInfo => EncodingType::Json,
+            | Artifact::MoveCallInfo
+            | Artifact::EffectsDiff => EncodingType::Json,
         }
     }
 
@@ -236,6 +240,23 @@ impl ArtifactMember<'_, '_> {
             None
         }
     }
+
+    /// Try to get the EffectsDiff if the artifact type is `EffectsDiff`.
+    /// If the artifact type is not `EffectsDiff` `None` is returned.
+    pub fn try_get_effects_diff(&self) -> Option<Result<EffectsDiff>> {
+        if self.artifact_type == Artifact::EffectsDiff {
+            Some(self.get_json().and_then(|json| {
+                serde_json::from_value::<EffectsDiff>(json).map_err(|e| {
+                    anyhow!(
+                        "Failed to deserialize effects diff from {}: {e}",
+                        self.artifact_path.display()
+                    )
+                })
+            }))
+        } else {
+            None
+        }
+    }
 }
 
 /// Serialization methods for `ArtifactManager`.
@@ -2,9 +2,11 @@
 // SPDX-License-Identifier: Apache-2.0
 
 mod cache_summary;
+mod effects_diff;
 mod manager;
 mod move_call_info;
 
 pub use cache_summary::*;
+pub use effects_diff::*;
 pub use manager::*;
 pub use move_call_info::*;
@@ -0,0 +1,430 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Batch replay support: how the transactions of a run are split between workers, and the
+//! machine-readable report of a run.
+//! The report records for every transaction whether the replay matched the on-chain effects
+//! (`pass`), produced different effects (`fork`) or failed (`error`), together with the gas
+//! cost of both executions and the diff of the effects. It can be written as JSON or as a
+//! JUnit XML file, so a replay run can be used as a regression gate in CI.
+
+use crate::{artifacts::EffectsDiff, replay_txn::ReplayOutcome};
+use anyhow::{Result, anyhow};
+use clap::ValueEnum;
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, VecDeque},
+    fmt::Write as _,
+    path::Path,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+use sui_data_store::TransactionStore;
+use sui_types::{effects::TransactionEffectsAPI, gas::GasCostSummary};
+use tracing::debug;
+
+/// Number of work items per worker, so that workers finishing early can pick up more work.
+const CHUNKS_PER_JOB: usize = 4;
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum ReportFormat {
+    #[default]
+    #[value(name = "json")]
+    Json,
+    #[value(name = "junit")]
+    Junit,
+}
+
+/// Outcome of the replay of a transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReplayStatus {
+    /// The replayed effects are the on-chain effects.
+    Pass,
+    /// The replayed effects differ from the on-chain effects. In a what-if replay this means
+    /// the overrides changed the outcome of the transaction.
+    Fork,
+    /// The transaction could not be replayed.
+    Error,
+}
+
+/// Gas cost of the on-chain and of the replayed execution.
+#[derive(Debug, Clone, Serialize)]
+pub struct GasDelta {
+    pub expected: GasCostSummary,
+    pub actual: GasCostSummary,
+    /// Replayed minus on-chain net gas usage.
+    pub net_gas_usage_delta: i64,
+}
+
+/// Report entry for a single transaction.
+#[derive(Debug, Clone, Serialize)]
+pub struct TransactionReport {
+    pub digest: String,
+    pub status: ReplayStatus,
+    pub epoch: Option<u64>,
+    pub checkpoint: Option<u64>,
+    pub exec_ms: u128,
+    pub total_ms: u128,
+    pub gas: Option<GasDelta>,
+    pub effects_diff: Option<EffectsDiff>,
+    pub error: Option<String>,
+}
+
+impl TransactionReport {
+    /// Build the entry for a transaction from the result of its replay. `epoch` is the epoch
+    /// of the transaction if known before the replay, reported when the replay failed.
+    pub fn new(
+        digest: &str,
+        epoch: Option<u64>,
+        result: &Result<ReplayOutcome>,
+        total_ms: u128,
+    ) -> Self {
+        match result {
+            Ok(outcome) => {
+                let expected = outcome.expected_effects.gas_cost_summary();
+                let actual = outcome.effects.gas_cost_summary();
+                // The diff only covers what is reported, so compare the whole effects.
+                let (status, effects_diff) = if outcome.effects == outcome.expected_effects {
+                    (ReplayStatus::Pass, None)
+                } else {
+                    let diff = EffectsDiff::new(&outcome.expected_effects, &outcome.effects);
+                    (ReplayStatus::Fork, Some(diff))
+                };
+                Self {
+                    digest: digest.to_string(),
+                    status,
+                    epoch: Some(outcome.expected_effects.executed_epoch()),
+                    checkpoint: Some(outcome.checkpoint),
+                    exec_ms: outcome.exec_ms,
+                    total_ms,
+                    gas: Some(GasDelta {
+                        expected: expected.clone(),
+                        actual: actual.clone(),
+                        net_gas_usage_delta: actual.net_gas_usage() - expected.net_gas_usage(),
+                    }),
+                    effects_diff,
+                    error: None,
+                }
+            }
+            Err(e) => Self {
+                digest: digest.to_string(),
+                status: ReplayStatus::Error,
+                epoch,
+                checkpoint: None,
+                exec_ms: 0,
+                total_ms,
+                gas: None,
+                effects_diff: None,
+                error: Some(format!("{e:?}")),
+            },
+        }
+    }
+}
+
+/// Report of a replay run.
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchReport {
+    pub network: String,
+    pub tx_count: u64,
+    pub passed: u64,
+    pub forked: u64,
+    pub failed: u64,
+    pub exec_ms: u128,
+    pub total_ms: u128,
+    pub transactions: Vec<TransactionReport>,
+}
+
+impl BatchReport {
+    pub fn new(network: String, transactions: Vec<TransactionReport>) -> Self {
+        let count = |status| {
+            transactions
+                .iter()
+                .filter(|report| report.status == status)
+                .count() as u64
+        };
+        Self {
+            network,
+            tx_count: transactions.len() as u64,
+            passed: count(ReplayStatus::Pass),
+            forked: count(ReplayStatus::Fork),
+            failed: count(ReplayStatus::Error),
+            exec_ms: transactions.iter().map(|report| report.exec_ms).sum(),
+            total_ms: transactions.iter().map(|report| report.total_ms).sum(),
+            transactions,
+        }
+    }
+
+    /// Write the report to `path` in the given format.
+    pub fn save(&self, path: &Path, format: ReportFormat) -> Result<()> {
+        let contents = match format {
+            ReportFormat::Json => serde_json::to_string_pretty(self)?,
+            ReportFormat::Junit => self.to_junit()?,
+        };
+        std::fs::write(path, contents)
+            .map_err(|e| anyhow!("Failed to write report {}: {e}", path.display()))
+    }
+
+    // One test case per transaction, classified by epoch. Forks are reported as failures
+    // with the effects diff, replay errors as errors.
+    fn to_junit(&self) -> Result<String> {
+        let mut out = String::new();
+        writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        writeln!(
+            out,
+            r#"<testsuite name="replay.{}" tests="{}" failures="{}" errors="{}" time="{}">"#,
+            escape_xml(&self.network),
+            self.tx_count,
+            self.forked,
+            self.failed,
+            seconds(self.total_ms),
+        )?;
+        for report in &self.transactions {
+            let classname = match report.epoch {
+                Some(epoch) => format!("epoch_{epoch}"),
+                None => "unknown_epoch".to_string(),
+            };
+            write!(
+                out,
+                r#"  <testcase classname="{classname}" name="{}" time="{}""#,
+                escape_xml(&report.digest),
+                seconds(report.total_ms),
+            )?;
+            match report.status {
+                ReplayStatus::Pass => writeln!(out, "/>")?,
+                ReplayStatus::Fork => {
+                    let diff = serde_json::to_string_pretty(&report.effects_diff)?;
+                    writeln!(out, ">")?;
+                    writeln!(
+                        out,
+                        r#"    <failure type="fork" message="Replayed effects differ from on-chain effects">{}</failure>"#,
+                        escape_xml(&diff),
+                    )?;
+                    writeln!(out, "  </testcase>")?;
+                }
+                ReplayStatus::Error => {
+                    let error = report.error.as_deref().unwrap_or_default();
+                    let message = error.lines().next().unwrap_or_default();
+                    writeln!(out, ">")?;
+                    writeln!(
+                        out,
+                        r#"    <error type="error" message="{}">{}</error>"#,
+                        escape_xml(message),
+                        escape_xml(error),
+                    )?;
+                    writeln!(out, "  </testcase>")?;
+                }
+            }
+        }
+        writeln!(out, "</testsuite>")?;
+        Ok(out)
+    }
+}
+
+/// Load the epoch of every transaction of a run, `None` if it could not be loaded.
+/// The transactions are loaded by `jobs` threads, each picking up the next transaction left.
+pub(crate) fn load_epochs<S: TransactionStore + Sync>(
+    digests: &[String],
+    txn_store: &S,
+    jobs: usize,
+) -> Result<Vec<Option<u64>>> {
+    let next = AtomicUsize::new(0);
+    let load = || {
+        let mut loaded = vec![];
+        loop {
+            let idx = next.fetch_add(1, Ordering::Relaxed);
+            let Some(digest) = digests.get(idx) else {
+                return loaded;
+            };
+            let epoch = match txn_store.transaction_data_and_effects(digest) {
+                Ok(Some(info)) => Some(info.effects.executed_epoch()),
+                Ok(None) => None,
+                Err(e) => {
+                    debug!(tx_digest = %digest, error = ?e, "Failed to load transaction epoch");
+                    None
+                }
+            };
+            loaded.push((idx, epoch));
+        }
+    };
+
+    let mut epochs = vec![None; digests.len()];
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..jobs.min(digests.len()))
+            .map(|_| scope.spawn(load))
+            .collect();
+        for handle in handles {
+            let loaded = handle
+                .join()
+                .map_err(|_| anyhow!("Transaction loading panicked"))?;
+            for (idx, epoch) in loaded {
+                epochs[idx] = epoch;
+            }
+        }
+        Ok::<_, anyhow::Error>(())
+    })?;
+    Ok(epochs)
+}
+
+/// Split the transactions of a run into work items of consecutive transactions of the same
+/// epoch, so that a worker replaying a work item keeps using the same executor.
+/// `epochs` holds the epoch of every transaction, and the work items are indices into it.
+/// Transactions whose epoch is unknown are replayed last.
+pub(crate) fn plan_work(epochs: &[Option<u64>], jobs: usize) -> VecDeque<Vec<usize>> {
+    let mut by_epoch: BTreeMap<Option<u64>, Vec<usize>> = BTreeMap::new();
+    for (idx, epoch) in epochs.iter().enumerate() {
+        by_epoch.entry(*epoch).or_default().push(idx);
+    }
+    let unknown = by_epoch.remove(&None).unwrap_or_default();
+
+    let chunk_size = epochs.len().div_ceil(jobs * CHUNKS_PER_JOB).max(1);
+    by_epoch
+        .into_values()
+        .chain(std::iter::once(unknown))
+        .flat_map(|indices| {
+            indices
+                .chunks(chunk_size)
+                .map(|chunk| chunk.to_vec())
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+fn seconds(ms: u128) -> String {
+    format!("{:.3}", ms as f64 / 1000.0)
+}
+
+fn escape_xml(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(digest: &str, status: ReplayStatus, epoch: Option<u64>) -> TransactionReport {
+        TransactionReport {
+            digest: digest.to_string(),
+            status,
+            epoch,
+            checkpoint: None,
+            exec_ms: 10,
+            total_ms: 1500,
+            gas: None,
+            effects_diff: None,
+            error: (status == ReplayStatus::Error).then(|| "first line\n<second> & line".into()),
+        }
+    }
+
+    #[test]
+    fn test_plan_work() {
+        let epochs = [
+            Some(2),
+            None,
+            Some(1),
+            Some(2),
+            Some(1),
+            Some(2),
+            None,
+            Some(1),
+        ];
+
+        // 8 transactions for 1 job are split in items of 2 transactions, per epoch.
+        let work: Vec<_> = plan_work(&epochs, 1).into();
+        assert_eq!(
+            work,
+            vec![vec![2, 4], vec![7], vec![0, 3], vec![5], vec![1, 6]]
+        );
+
+        // With more jobs than transactions every item holds a single transaction.
+        let work = plan_work(&epochs, 4);
+        assert_eq!(work.len(), epochs.len());
+        assert!(work.iter().all(|item| item.len() == 1));
+        // Transactions with an unknown epoch are replayed last.
+        assert_eq!(
+            work.iter().rev().take(2).flatten().collect::<Vec<_>>(),
+            [&6, &1]
+        );
+
+        assert!(plan_work(&[], 4).is_empty());
+    }
+
+    #[test]
+    fn test_escape_xml() {
+        assert_eq!(escape_xml("plain"), "plain");
+        assert_eq!(
+            escape_xml(r#"<a b="c">'d' & e</a>"#),
+            "&lt;a b=&quot;c&quot;&gt;&apos;d&apos; &amp; e&lt;/a&gt;"
+        );
+    }
+
+    #[test]
+    fn test_batch_report() {
+        let report = BatchReport::new(
+            "mainnet".to_string(),
+            vec![
+                report("pass", ReplayStatus::Pass, Some(1)),
+                report("fork", ReplayStatus::Fork, Some(2)),
+                report("error", ReplayStatus::Error, None),
+                report("pass2", ReplayStatus::Pass, Some(1)),
+            ],
+        );
+        assert_eq!(report.tx_count, 4);
+        assert_eq!(report.passed, 2);
+        assert_eq!(report.forked, 1);
+        assert_eq!(report.failed, 1);
+        assert_eq!(report.exec_ms, 40);
+        assert_eq!(report.total_ms, 6000);
+        assert_eq!(report.transactions.len(), 4);
+    }
+
+    #[test]
+    fn test_to_junit() {
+        let report = BatchReport::new(
+            "mainnet<&>".to_string(),
+            vec![
+                report("pass", ReplayStatus::Pass, Some(1)),
+                report("fork", ReplayStatus::Fork, Some(2)),
+                report("error", ReplayStatus::Error, None),
+            ],
+        );
+        let junit = report.to_junit().unwrap();
+        let lines: Vec<_> = junit.lines().collect();
+        assert_eq!(lines[0], r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+        assert_eq!(
+            lines[1],
+            r#"<testsuite name="replay.mainnet&lt;&amp;&gt;" tests="3" failures="1" errors="1" time="4.500">"#
+        );
+        assert_eq!(
+            lines[2],
+            r#"  <testcase classname="epoch_1" name="pass" time="1.500"/>"#
+        );
+        assert_eq!(
+            lines[3],
+            r#"  <testcase classname="epoch_2" name="fork" time="1.500">"#
+        );
+        assert!(lines[4].starts_with(r#"    <failure type="fork""#));
+        assert_eq!(
+            lines[6],
+            r#"  <testcase classname="unknown_epoch" name="error" time="1.500">"#
+        );
+        // The first line of the error is the message, the whole error is the body.
+        assert_eq!(
+            lines[7],
+            r#"    <error type="error" message="first line">first line"#
+        );
+        assert_eq!(lines[8], "&lt;second&gt; &amp; line</error>");
+        assert_eq!(lines[9], "  </testcase>");
+        assert_eq!(lines.last(), Some(&"</testsuite>"));
+    }
+}
@@ -0,0 +1,114 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    artifacts::{EffectsDiff, ObjectChangeSummary},
+    displays::Pretty,
+};
+use std::fmt::{Display, Formatter};
+use tabled::{
+    builder::Builder as TableBuilder,
+    settings::{Style as TableStyle, style::HorizontalLine},
+};
+
+impl Display for Pretty<'_, EffectsDiff> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let Pretty(diff) = self;
+        if diff.is_empty() {
+            return writeln!(
+                f,
+                "No difference with on-chain effects for {}",
+                diff.transaction_digest
+            );
+        }
+        summary_table(f, diff)?;
+        if !diff.object_changes.is_empty() {
+            object_changes_table(f, diff)?;
+        }
+        Ok(())
+    }
+}
+
+fn summary_table(f: &mut Formatter<'_>, diff: &EffectsDiff) -> std::fmt::Result {
+    let mut builder = TableBuilder::default();
+    builder.push_record(vec!["Field", "On-chain", "Replayed"]);
+    if let Some(status) = &diff.status {
+        builder.push_record(vec![
+            "Status".to_string(),
+            format!("{:?}", status.expected),
+            format!("{:?}", status.actual),
+        ]);
+    }
+    if let Some(gas) = &diff.gas_cost_summary {
+        let (expected, actual) = (&gas.expected, &gas.actual);
+        for (name, expected, actual) in [
+            (
+                "Computation Cost",
+                expected.computation_cost,
+                actual.computation_cost,
+            ),
+            ("Storage Cost", expected.storage_cost, actual.storage_cost),
+            (
+                "Storage Rebate",
+                expected.storage_rebate,
+                actual.storage_rebate,
+            ),
+            (
+                "Non-Refundable Storage Fee",
+                expected.non_refundable_storage_fee,
+                actual.non_refundable_storage_fee,
+            ),
+        ] {
+            builder.push_record(vec![
+                name.to_string(),
+                expected.to_string(),
+                actual.to_string(),
+            ]);
+        }
+    }
+    if let Some(events) = &diff.events_digest {
+        let digest = |d: &Option<_>| d.as_ref().map_or("-".to_string(), |d| format!("{d}"));
+        builder.push_record(vec![
+            "Events Digest".to_string(),
+            digest(&events.expected),
+            digest(&events.actual),
+        ]);
+    }
+    write_table(f, builder)
+}
+
+fn object_changes_table(f: &mut Formatter<'_>, diff: &EffectsDiff) -> std::fmt::Result {
+    let mut builder = TableBuilder::default();
+    builder.push_record(vec!["Object ID", "On-chain", "Replayed"]);
+    for change in &diff.object_changes {
+        builder.push_record(vec![
+            change.object_id.to_string(),
+            object_change(&change.expected),
+            object_change(&change.actual),
+        ]);
+    }
+    write_table(f, builder)
+}
+
+fn object_change(change: &Option<ObjectChangeSummary>) -> String {
+    let Some(change) = change else {
+        return "unchanged".to_string();
+    };
+    let mut out = format!("{:?}", change.operation);
+    if let Some(version) = change.output_version {
+        out.push_str(&format!(" v{}", version.value()));
+    }
+    if let Some(digest) = change.output_digest {
+        out.push_str(&format!(" {digest}"));
+    }
+    out
+}
+
+fn write_table(f: &mut Formatter<'_>, builder: TableBuilder) -> std::fmt::Result {
+    let mut table = builder.build();
+    table.with(TableStyle::rounded().horizontals([HorizontalLine::new(
+        1,
+        TableStyle::modern().get_horizontal(),
+    )]));
+    write!(f, "\n{}\n", table)
+}
@@ -1,6 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+mod effects_diff;
 mod gas_report;
 
 pub struct Pretty<'a, T>(pub &'a T);
@@ -3,9 +3,11 @@
 
 use crate::{
     artifacts::{Artifact, ArtifactManager},
+    batch::{BatchReport, ReplayStatus, ReportFormat, TransactionReport, load_epochs, plan_work},
     displays::Pretty,
     replay_txn::replay_transaction,
     summary_metrics::TotalMetrics,
+    what_if::{WhatIf, WhatIfConfig},
 };
 use anyhow::{Result, anyhow, bail};
 use clap::{Parser, ValueEnum};
@@ -13,28 +15,35 @@ use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
 use serde::Deserialize;
 use similar::{ChangeTag, TextDiff};
 use std::{
+    collections::VecDeque,
     fs,
     io::Write,
     path::{Path, PathBuf},
+    sync::{
+        Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
     time::Duration,
 };
 use sui_config::sui_config_dir;
 use sui_data_store::{
     Node, ReadDataStore, SetupStore, StoreSummary,
-    stores::{DataStore, FileSystemStore, InMemoryStore, ReadThroughStore},
+    stores::{CheckpointArchiveStore, DataStore, FileSystemStore, InMemoryStore, ReadThroughStore},
 };
 use sui_json_rpc_types::SuiTransactionBlockEffects;
 use sui_types::effects::TransactionEffects;
 // Disambiguate external tracing crate from local `crate::tracing` module using absolute path.
-use ::tracing::{Instrument, debug, error, info_span, warn};
+use ::tracing::{debug, error, info_span, warn};
 
 pub mod artifacts;
+pub mod batch;
 pub mod displays;
 pub mod execution;
 pub mod package_tools;
 pub mod replay_txn;
 pub mod summary_metrics;
 pub mod tracing;
+pub mod what_if;
 
 const DEFAULT_OUTPUT_DIR: &str = ".replay";
 const CONFIG_FILE_NAME: &str = "replay.toml";
@@ -202,9 +211,25 @@ pub struct ReplayConfigExperimental {
     /// - fs-only: FileSystem only
     /// - inmem-fs: InMemory -> FileSystem
     /// - inmem-fs-gql: InMemory -> FileSystem -> GraphQL (default)
+    /// - archive-only: checkpoint files in `--checkpoint-dir` only
+    /// - fs-then-archive: FileSystem primary with checkpoint files fallback
     #[arg(long = "store-mode", value_enum, default_value_t = StoreMode::GqlOnly)]
     pub store_mode: StoreMode,
 
+    /// Directory of checkpoint files (`<sequence_number>.chk`) for the archive store modes.
+    /// Without `--digest` or `--digests-path`, every transaction of the indexed checkpoints
+    /// is replayed.
+    #[arg(long = "checkpoint-dir")]
+    pub checkpoint_dir: Option<PathBuf>,
+
+    /// First checkpoint to index from `--checkpoint-dir`. Defaults to the first file found.
+    #[arg(long = "first-checkpoint", requires = "last_checkpoint")]
+    pub first_checkpoint: Option<u64>,
+
+    /// Last checkpoint to index from `--checkpoint-dir`. Defaults to the last file found.
+    #[arg(long = "last-checkpoint", requires = "first_checkpoint")]
+    pub last_checkpoint: Option<u64>,
+
     /// Include execution and total time in transaction output.
     #[arg(long = "track-time", default_value = "false")]
     pub track_time: bool,
@@ -212,6 +237,24 @@ pub struct ReplayConfigExperimental {
     /// Cache executors across transactions within the same epoch.
     #[arg(long = "cache-executor", default_value = "false")]
     pub cache_executor: bool,
+
+    /// Number of transactions replayed concurrently. With more than one job transactions
+    /// are grouped by epoch, so that each job reuses its executors with `--cache-executor`.
+    #[arg(long = "jobs", short = 'j', default_value = "1")]
+    pub jobs: usize,
+
+    /// Write a report with the status (pass, fork or error), gas costs and effects diff of
+    /// every replayed transaction to this file.
+    #[arg(long = "report")]
+    pub report: Option<PathBuf>,
+
+    /// Format of the `--report` file.
+    #[arg(long = "report-format", value_enum, default_value_t = ReportFormat::Json)]
+    pub report_format: ReportFormat,
+
+    /// Overrides for a what-if replay.
+    #[command(flatten)]
+    pub what_if: WhatIfConfig,
 }
 
 impl Default for ReplayConfigExperimental {
@@ -220,8 +263,15 @@ impl Default for ReplayConfigExperimental {
             node: Node::Mainnet,
             verbose: false,
             store_mode: StoreMode::GqlOnly,
+            checkpoint_dir: None,
+            first_checkpoint: None,
+            last_checkpoint: None,
             track_time: false,
             cache_executor: false,
+            jobs: 1,
+            report: None,
+            report_format: ReportFormat::Json,
+            what_if: WhatIfConfig::default(),
         }
     }
 }
@@ -238,6 +288,10 @@ pub enum StoreMode {
     InmemFs,
     #[value(name = "inmem-fs-gql")]
     InmemFsGql,
+    #[value(name = "archive-only")]
+    ArchiveOnly,
+    #[value(name = "fs-then-archive")]
+    FsThenArchive,
 }
 
 /// Load replay configuration from ~/.sui/sui_config/replay.toml file.
@@ -333,11 +387,7 @@ pub async fn handle_replay_config(
     let mut terminate_early = *terminate_early;
 
     let ReplayConfigExperimental {
-        node,
-        verbose,
-        store_mode,
-        track_time,
-        cache_executor,
+        store_mode, jobs, ..
     } = experimental_config;
 
     let output_root_dir = if let Some(dir) = output_dir {
@@ -358,49 +408,106 @@ pub async fn handle_replay_config(
         );
     }
 
+    if *jobs == 0 {
+        bail!("--jobs must be at least 1");
+    }
+
     // If a file is specified it is read and the digest ignored.
     // Once we decide on the options we want this is likely to change.
+    // With the archive store modes and neither option, all the transactions in the
+    // checkpoint range are replayed.
     let digests = if let Some(digests_path) = digests_path {
         // read digests from file
-        std::fs::read_to_string(digests_path.clone())
-            .map_err(|e| {
-                anyhow!(
-                    "Failed to read digests file {}: {e}",
-                    digests_path.display(),
-                )
-            })?
-            .lines()
-            .map(|s| s.trim().to_string())
-            .collect::<Vec<_>>()
+        Some(
+            std::fs::read_to_string(digests_path.clone())
+                .map_err(|e| {
+                    anyhow!(
+                        "Failed to read digests file {}: {e}",
+                        digests_path.display(),
+                    )
+                })?
+                .lines()
+                .map(|s| s.trim().to_string())
+                .collect::<Vec<_>>(),
+        )
     } else if let Some(tx_digest) = digest {
         // terminate early if a single digest is provided this way we get proper error messages from
         terminate_early = true;
         // single digest provided
-        vec![tx_digest.clone()]
+        Some(vec![tx_digest.clone()])
+    } else if matches!(
+        store_mode,
+        StoreMode::ArchiveOnly | StoreMode::FsThenArchive
+    ) {
+        None
     } else {
         bail!("either --digest or --digests-path must be provided");
     };
 
     debug!("Binary version: {version}");
 
+    // The replay is synchronous and runs its workers on threads of its own, so keep it off
+    // the runtime's worker threads.
+    let experimental_config = experimental_config.clone();
+    let output_root = output_root_dir.clone();
+    let (trace, overwrite_existing, version) = (*trace, *overwrite_existing, version.to_string());
+    tokio::task::spawn_blocking(move || {
+        let what_if = if experimental_config.what_if.is_enabled() {
+            Some(WhatIf::new(
+                &experimental_config.what_if,
+                &experimental_config.node,
+            )?)
+        } else {
+            None
+        };
+        let run = ReplayRun {
+            output_root_dir: &output_root,
+            node: &experimental_config.node,
+            overwrite_existing,
+            trace,
+            verbose: experimental_config.verbose,
+            terminate_early,
+            track_time: experimental_config.track_time,
+            cache_executor: experimental_config.cache_executor,
+            jobs: experimental_config.jobs,
+            report: experimental_config
+                .report
+                .as_deref()
+                .map(|path| (path, experimental_config.report_format)),
+            what_if: what_if.as_ref(),
+        };
+        replay_with_store(&experimental_config, digests, &run, &version)
+    })
+    .await
+    .map_err(|e| anyhow!("Replay task failed: {e}"))??;
+
+    Ok(output_root_dir)
+}
+
+// Build the data store of the store mode selected in `config` and replay the transactions.
+// With the archive store modes and no `digests`, all the transactions in the checkpoint range
+// are replayed.
+fn replay_with_store(
+    config: &ReplayConfigExperimental,
+    digests: Option<Vec<String>>,
+    run: &ReplayRun<'_>,
+    version: &str,
+) -> Result<()> {
+    let ReplayConfigExperimental {
+        node,
+        store_mode,
+        checkpoint_dir,
+        first_checkpoint,
+        last_checkpoint,
+        ..
+    } = config;
+
     // Build the selected data store and run replay
     match store_mode {
         StoreMode::GqlOnly => {
             let gql_store = DataStore::new(node.clone(), version)
                 .map_err(|e| anyhow!("Failed to create data store: {:?}", e))?;
-            run_replay(
-                &gql_store,
-                &output_root_dir,
-                &digests,
-                node,
-                *overwrite_existing,
-                *trace,
-                *verbose,
-                terminate_early,
-                *track_time,
-                *cache_executor,
-            )
-            .await?;
+            run_replay(&gql_store, &digests.unwrap_or_default(), run)?;
         }
         StoreMode::FsThenGql => {
             let fs_store = FileSystemStore::new(node.clone())
@@ -408,55 +515,19 @@ pub async fn handle_replay_config(
             let gql_store = DataStore::new(node.clone(), version)
                 .map_err(|e| anyhow!("Failed to create data store: {:?}", e))?;
             let store = ReadThroughStore::new(fs_store, gql_store);
-            run_replay(
-                &store,
-                &output_root_dir,
-                &digests,
-                node,
-                *overwrite_existing,
-                *trace,
-                *verbose,
-                terminate_early,
-                *track_time,
-                *cache_executor,
-            )
-            .await?;
+            run_replay(&store, &digests.unwrap_or_default(), run)?;
         }
         StoreMode::FsOnly => {
             let fs_store = FileSystemStore::new(node.clone())
                 .map_err(|e| anyhow!("Failed to create file system store: {:?}", e))?;
-            run_replay(
-                &fs_store,
-                &output_root_dir,
-                &digests,
-                node,
-                *overwrite_existing,
-                *trace,
-                *verbose,
-                terminate_early,
-                *track_time,
-                *cache_executor,
-            )
-            .await?;
+            run_replay(&fs_store, &digests.unwrap_or_default(), run)?;
         }
         StoreMode::InmemFs => {
             let fs_store = FileSystemStore::new(node.clone())
                 .map_err(|e| anyhow!("Failed to create file system store: {:?}", e))?;
             let in_memory_store = InMemoryStore::new(node.clone());
             let store = ReadThroughStore::new(in_memory_store, fs_store);
-            run_replay(
-                &store,
-                &output_root_dir,
-                &digests,
-                node,
-                *overwrite_existing,
-                *trace,
-                *verbose,
-                terminate_early,
-                *track_time,
-                *cache_executor,
-            )
-            .await?;
+            run_replay(&store, &digests.unwrap_or_default(), run)?;
         }
         StoreMode::InmemFsGql => {
             let fs_store = FileSystemStore::new(node.clone())
@@ -466,46 +537,188 @@ pub async fn handle_replay_config(
             let secondary_store = ReadThroughStore::new(fs_store, gql_store);
             let in_memory_store = InMemoryStore::new(node.clone());
             let store = ReadThroughStore::new(in_memory_store, secondary_store);
-            run_replay(
-                &store,
-                &output_root_dir,
-                &digests,
+            run_replay(&store, &digests.unwrap_or_default(), run)?;
+        }
+        StoreMode::ArchiveOnly => {
+            let archive_store = checkpoint_archive_store(
+                node,
+                checkpoint_dir.as_deref(),
+                *first_checkpoint,
+                *last_checkpoint,
+            )?;
+            let digests = match digests {
+                Some(digests) => digests,
+                None => archive_store.transaction_digests()?,
+            };
+            run_replay(&archive_store, &digests, run)?;
+        }
+        StoreMode::FsThenArchive => {
+            let fs_store = FileSystemStore::new(node.clone())
+                .map_err(|e| anyhow!("Failed to create file system store: {:?}", e))?;
+            let archive_store = checkpoint_archive_store(
                 node,
-                *overwrite_existing,
-                *trace,
-                *verbose,
-                terminate_early,
-                *track_time,
-                *cache_executor,
-            )
-            .await?;
+                checkpoint_dir.as_deref(),
+                *first_checkpoint,
+                *last_checkpoint,
+            )?;
+            let digests = match digests {
+                Some(digests) => digests,
+                None => archive_store.transaction_digests()?,
+            };
+            let store = ReadThroughStore::new(fs_store, archive_store);
+            run_replay(&store, &digests, run)?;
         }
     }
 
-    Ok(output_root_dir)
+    Ok(())
 }
 
-async fn run_replay<S>(
-    data_store: &S,
-    output_root_dir: &Path,
-    digests: &[String],
+fn checkpoint_archive_store(
     node: &Node,
+    checkpoint_dir: Option<&Path>,
+    first_checkpoint: Option<u64>,
+    last_checkpoint: Option<u64>,
+) -> Result<CheckpointArchiveStore> {
+    let Some(checkpoint_dir) = checkpoint_dir else {
+        bail!("--checkpoint-dir must be provided with the archive store modes");
+    };
+    let range = first_checkpoint
+        .zip(last_checkpoint)
+        .map(|(first, last)| first..=last);
+    CheckpointArchiveStore::new(node.clone(), checkpoint_dir, range)
+        .map_err(|e| anyhow!("Failed to create checkpoint archive store: {:?}", e))
+}
+
+/// Settings of a replay run, shared by all the data store modes.
+struct ReplayRun<'a> {
+    output_root_dir: &'a Path,
+    node: &'a Node,
     overwrite_existing: bool,
     trace: bool,
     verbose: bool,
     terminate_early: bool,
     track_time: bool,
     cache_executor: bool,
-) -> Result<()>
+    jobs: usize,
+    report: Option<(&'a Path, ReportFormat)>,
+    what_if: Option<&'a WhatIf>,
+}
+
+/// State shared by the workers of a replay run.
+struct ReplayWorkers<'a, S> {
+    data_store: &'a S,
+    digests: &'a [String],
+    epochs: Vec<Option<u64>>,
+    run: &'a ReplayRun<'a>,
+    work: Mutex<VecDeque<Vec<usize>>>,
+    reports: Mutex<Vec<Option<TransactionReport>>>,
+    stop: AtomicBool,
+    tx_spinner: ProgressBar,
+    progress_bar: ProgressBar,
+}
+
+impl<S: ReadDataStore> ReplayWorkers<'_, S> {
+    // Replay work items until there are none left. Each worker has its own executors.
+    fn run_worker(&self) -> Result<()> {
+        use crate::replay_txn::ExecutorProvider;
+        use std::time::Instant;
+
+        let mut executor_provider = ExecutorProvider::new(self.run.cache_executor)
+            .with_protocol_config_override(
+                self.run
+                    .what_if
+                    .and_then(|what_if| what_if.protocol_config_override().cloned()),
+            );
+
+        loop {
+            let Some(indices) = self.work.lock().unwrap().pop_front() else {
+                return Ok(());
+            };
+            for idx in indices {
+                if self.stop.load(Ordering::Relaxed) {
+                    return Ok(());
+                }
+                let tx_digest = &self.digests[idx];
+                let tx_dir = self.run.output_root_dir.join(tx_digest);
+                let artifact_manager = ArtifactManager::new(&tx_dir, self.run.overwrite_existing)?;
+                let span = info_span!("replay", tx_digest = %tx_digest);
+
+                self.tx_spinner
+                    .set_message(format!("Executing transaction {}", tx_digest));
+
+                let tx_start = Instant::now();
+                let result = span.in_scope(|| {
+                    replay_transaction(
+                        &artifact_manager,
+                        tx_digest,
+                        self.data_store,
+                        self.run.node.network_name(),
+                        self.run.trace,
+                        &mut executor_provider,
+                        self.run.what_if,
+                    )
+                });
+                let tx_total_ms = tx_start.elapsed().as_millis();
+                let report =
+                    TransactionReport::new(tx_digest, self.epochs[idx], &result, tx_total_ms);
+
+                // Print per-transaction result
+                let status = match report.status {
+                    ReplayStatus::Pass => "OK",
+                    ReplayStatus::Fork => "FORKED",
+                    ReplayStatus::Error => "FAILED",
+                };
+
+                let time_info = if self.run.track_time {
+                    format!(
+                        " ({}): exec_ms={}, total_ms={}",
+                        status, report.exec_ms, tx_total_ms
+                    )
+                } else {
+                    "".to_owned()
+                };
+
+                self.tx_spinner
+                    .println(format!("Executed transaction {}{}", tx_digest, time_info));
+                self.reports.lock().unwrap()[idx] = Some(report);
+                self.progress_bar.inc(1);
+
+                match result {
+                    Err(e) if self.run.terminate_early => {
+                        error!(tx_digest = %tx_digest, error = ?e, "Replay error; terminating early");
+                        self.stop.store(true, Ordering::Relaxed);
+                        bail!("Replay terminated due to error: {}", e);
+                    }
+                    Err(e) => {
+                        error!(tx_digest = %tx_digest, error = ?e, "Replay failed");
+                    }
+                    Ok(_) => {}
+                }
+            }
+        }
+    }
+}
+
+// Replay the transactions with `run.jobs` workers. With more than one worker the
+// transactions are grouped by epoch so that workers can reuse their executors, and are
+// replayed out of order. To group them the workers load the transactions concurrently
+// before the replay.
+fn run_replay<S>(data_store: &S, digests: &[String], run: &ReplayRun<'_>) -> Result<()>
 where
-    S: ReadDataStore + StoreSummary + SetupStore,
+    S: ReadDataStore + StoreSummary + SetupStore + Sync,
 {
-    use crate::replay_txn::ExecutorProvider;
-    use std::time::Instant;
-
     data_store.setup(None)?;
-    let mut total_metrics = TotalMetrics::new();
-    let mut executor_provider = ExecutorProvider::new(cache_executor);
+
+    let (epochs, work) = if run.jobs > 1 {
+        let epochs = load_epochs(digests, data_store, run.jobs)?;
+        let work = plan_work(&epochs, run.jobs);
+        (epochs, work)
+    } else {
+        (
+            vec![None; digests.len()],
+            VecDeque::from([(0..digests.len()).collect()]),
+        )
+    };
 
     let mp = MultiProgress::new();
     let tx_spinner = mp.add(ProgressBar::new_spinner());
@@ -514,61 +727,55 @@ where
     tx_spinner.set_style(ProgressStyle::with_template("{spinner}: {msg}").unwrap());
     tx_spinner.enable_steady_tick(Duration::from_millis(80));
 
-    for tx_digest in digests {
-        let tx_dir = output_root_dir.join(tx_digest);
-        let artifact_manager = ArtifactManager::new(&tx_dir, overwrite_existing)?;
-        let span = info_span!("replay", tx_digest = %tx_digest);
-
-        tx_spinner.set_message(format!("Executing transaction {}", tx_digest));
-
-        let tx_start = Instant::now();
-        let result = replay_transaction(
-            &artifact_manager,
-            tx_digest,
-            data_store,
-            node.network_name(),
-            trace,
-            &mut executor_provider,
-        )
-        .instrument(span)
-        .await;
-        let tx_total_ms = tx_start.elapsed().as_millis();
-
-        let success = result.is_ok();
-        let exec_ms = result.as_ref().ok().copied().unwrap_or(0);
-
-        total_metrics.add_transaction(success, tx_total_ms, exec_ms);
-
-        // Print per-transaction result
-        let status = if success { "OK" } else { "FAILED" };
-
-        let time_info = if track_time {
-            format!(
-                " ({}): exec_ms={}, total_ms={}",
-                status, exec_ms, tx_total_ms
-            )
-        } else {
-            "".to_owned()
-        };
-
-        tx_spinner.println(format!("Executed transaction {}{}", tx_digest, time_info));
-
-        match result {
-            Err(e) if terminate_early => {
-                error!(tx_digest = %tx_digest, error = ?e, "Replay error; terminating early");
-                bail!("Replay terminated due to error: {}", e);
-            }
-            Err(e) => {
-                error!(tx_digest = %tx_digest, error = ?e, "Replay failed");
-            }
-            Ok(_) => {}
-        }
-        progress_bar.inc(1);
+    let workers = ReplayWorkers {
+        data_store,
+        digests,
+        epochs,
+        run,
+        work: Mutex::new(work),
+        reports: Mutex::new(vec![None; digests.len()]),
+        stop: AtomicBool::new(false),
+        tx_spinner,
+        progress_bar,
+    };
+    let result = std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..run.jobs.min(digests.len()).max(1))
+            .map(|_| scope.spawn(|| workers.run_worker()))
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| {
+                handle
+                    .join()
+                    .unwrap_or_else(|_| bail!("Replay worker panicked"))
+            })
+            .collect::<Result<Vec<_>>>()
+    });
+
+    workers.tx_spinner.finish_and_clear();
+
+    let reports: Vec<_> = workers
+        .reports
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .flatten()
+        .collect();
+    let mut total_metrics = TotalMetrics::new();
+    for report in &reports {
+        total_metrics.add_transaction(
+            report.status != ReplayStatus::Error,
+            report.total_ms,
+            report.exec_ms,
+        );
     }
+    let batch_report = BatchReport::new(run.node.network_name(), reports);
+    if let Some((path, format)) = run.report {
+        batch_report.save(path, format)?;
+    }
+    result?;
 
-    tx_spinner.finish_and_clear();
-
-    if verbose {
+    if run.verbose {
         let mut out = std::io::stdout().lock();
         let _ = writeln!(out, "\nData store summary:");
         if let Err(e) = data_store.summary(&mut out) {
@@ -578,10 +785,11 @@ where
 
     if digests.len() > 1 {
         println!(
-            "Replay run: tx_count={} success={} failure={} - exec_ms={}, total_ms={}",
+            "Replay run: tx_count={} success={} failure={} forked={} - exec_ms={}, total_ms={}",
             total_metrics.tx_count,
             total_metrics.success_count,
             total_metrics.failure_count,
+            batch_report.forked,
             total_metrics.exec_ms,
             total_metrics.total_ms
         );
@@ -598,7 +806,18 @@ pub fn print_effects_or_fork<W: Write>(
 ) -> Result<()> {
     let output_dir = output_root.join(digest);
     let manager = ArtifactManager::new(&output_dir, false)?;
-    if manager.member(Artifact::ForkedTransactionEffects).exists() {
+    if manager.member(Artifact::EffectsDiff).exists() {
+        let effects_diff = manager
+            .member(Artifact::EffectsDiff)
+            .try_get_effects_diff()
+            .transpose()?
+            .unwrap();
+        writeln!(
+            w,
+            "What-if Effects Diff for {digest}\n{}",
+            Pretty(&effects_diff)
+        )?;
+    } else if manager.member(Artifact::ForkedTransactionEffects).exists() {
         writeln!(w, "Transaction {digest} forked")?;
         let forked_effects = manager
             .member(Artifact::ForkedTransactionEffects)
@@ -272,6 +272,25 @@ impl PackageRebuilder {
         Ok(())
     }
 
+    /// Compile the package source with the package ID being rebuilt.
+    /// The modules can be reused across calls to `rebuild_object`.
+    pub fn compile(&self) -> Result<Vec<CompiledModule>> {
+        self.compile_package_with_id()
+    }
+
+    /// Rebuild the given on-chain package object with the compiled modules, preserving
+    /// its version, type origins and linkage. Nothing is written to disk or to the cache.
+    pub fn rebuild_object(
+        &self,
+        original_object: &Object,
+        compiled_modules: Vec<CompiledModule>,
+    ) -> Result<Object> {
+        let (original_package, tx_digest, version) =
+            self.extract_package_metadata(original_object)?;
+        let rebuilt_package = self.rebuild_package(original_package, compiled_modules, version)?;
+        Ok(Object::new_from_package(rebuilt_package, tx_digest))
+    }
+
     /// Extract metadata from the original package object
     fn extract_package_metadata(
         &self,
@@ -11,9 +11,10 @@
 //! in this module and saved in the `ReplayTransaction` instance.
 
 use crate::{
-    artifacts::{Artifact, ArtifactManager, MoveCallInfo, ReplayCacheSummary},
+    artifacts::{Artifact, ArtifactManager, EffectsDiff, MoveCallInfo, ReplayCacheSummary},
     execution::{ReplayExecutor, execute_transaction_to_effects},
     tracing::save_trace_output,
+    what_if::{ProtocolConfigOverride, WhatIf},
 };
 use anyhow::{Context, Error, Result, anyhow, bail};
 use move_trace_format::format::MoveTraceBuilder;
@@ -39,7 +40,7 @@ use sui_types::{
     gas::SuiGasStatusAPI,
     transaction::{InputObjectKind, ObjectReadResult, ObjectReadResultKind},
 };
-use tracing::{debug, error, info_span, trace, warn};
+use tracing::{debug, error, info, info_span, trace, warn};
 
 pub type ObjectVersion = u64;
 pub type PackageVersion = u64;
@@ -49,9 +50,11 @@ pub type PackageVersion = u64;
 /// Provides executors for transaction replay, with optional caching.
 /// When caching is enabled, executors are cached per protocol version to avoid recreation.
 /// When caching is disabled, a fresh executor is created for each transaction.
+/// A protocol config override, if any, is applied to the protocol config of every epoch.
 pub struct ExecutorProvider {
     cache: BTreeMap<u64, ReplayExecutor>, // u64 is protocol version
     cache_enabled: bool,
+    protocol_config_override: Option<ProtocolConfigOverride>,
 }
 
 impl ExecutorProvider {
@@ -59,9 +62,18 @@ impl ExecutorProvider {
         Self {
             cache: BTreeMap::new(),
             cache_enabled,
+            protocol_config_override: None,
         }
     }
 
+    pub fn with_protocol_config_override(
+        mut self,
+        protocol_config_override: Option<ProtocolConfigOverride>,
+    ) -> Self {
+        self.protocol_config_override = protocol_config_override;
+        self
+    }
+
     /// Get or create an executor for the given epoch.
     /// If caching is disabled, always creates a new executor.
     /// If caching is enabled, reuses cached executors (by protocol version) or creates and caches new ones.
@@ -73,6 +85,10 @@ impl ExecutorProvider {
         let protocol_config = epoch_store
             .protocol_config(epoch)?
             .ok_or_else(|| anyhow!("Protocol config missing for epoch {}", epoch))?;
+        let protocol_config = match &self.protocol_config_override {
+            Some(protocol_config_override) => protocol_config_override.apply(protocol_config)?,
+            None => protocol_config,
+        };
 
         if !self.cache_enabled {
             return ReplayExecutor::new(protocol_config);
@@ -102,20 +118,30 @@ pub struct ReplayTransaction {
     pub object_cache: BTreeMap<ObjectID, BTreeMap<ObjectVersion, Object>>,
 }
 
+/// Result of a replay: the on-chain and the replayed effects of the transaction and how long
+/// execution took.
+pub struct ReplayOutcome {
+    pub exec_ms: u128,
+    pub checkpoint: u64,
+    pub expected_effects: TransactionEffects,
+    pub effects: TransactionEffects,
+}
+
 //
-// Run a single transaction and print results to stdout
+// Run a single transaction and save the results in the artifact directory
 //
-pub(crate) async fn replay_transaction<S: ReadDataStore>(
+pub(crate) fn replay_transaction<S: ReadDataStore>(
     artifact_manager: &ArtifactManager<'_>,
     tx_digest: &str,
     data_store: &S,
     network: String,
     trace: bool,
     executor_provider: &mut ExecutorProvider,
-) -> Result<u128> {
+    what_if: Option<&WhatIf>,
+) -> Result<ReplayOutcome> {
     let _span = info_span!("replay_tx", tx_digest = %tx_digest).entered();
     // load a `ReplayTransaction`
-    let replay_txn = match ReplayTransaction::load(
+    let mut replay_txn = match ReplayTransaction::load(
         tx_digest,
         data_store,
         data_store,
@@ -128,6 +154,14 @@ pub(crate) async fn replay_transaction<S: ReadDataStore>(
         }
     };
 
+    // swap in the objects and packages of a what-if replay
+    if let Some(what_if) = what_if {
+        what_if.apply(&mut replay_txn, data_store).context(format!(
+            "Failed to apply overrides to transaction {}",
+            tx_digest
+        ))?;
+    }
+
     // replay the transaction
     let mut trace_builder_opt = trace.then(MoveTraceBuilder::new);
 
@@ -214,13 +248,52 @@ pub(crate) async fn replay_transaction<S: ReadDataStore>(
         }
     }
 
-    verify_txn_and_save_effects(
-        artifact_manager,
-        &context_and_effects.expected_effects,
-        &context_and_effects.execution_effects,
-    )?;
+    if what_if.is_some() {
+        save_effects_diff(
+            artifact_manager,
+            &context_and_effects.expected_effects,
+            &context_and_effects.execution_effects,
+        )?;
+    } else {
+        verify_txn_and_save_effects(
+            artifact_manager,
+            &context_and_effects.expected_effects,
+            &context_and_effects.execution_effects,
+        )?;
+    }
 
-    Ok(exec_ms)
+    Ok(ReplayOutcome {
+        exec_ms,
+        checkpoint: context_and_effects.checkpoint,
+        expected_effects: context_and_effects.expected_effects,
+        effects: context_and_effects.execution_effects,
+    })
+}
+
+// Effects of a what-if replay are expected to differ from the on-chain ones, so rather
+// than saving them as forked effects save the structured diff of the two.
+fn save_effects_diff(
+    artifact_manager: &ArtifactManager<'_>,
+    expected_effects: &TransactionEffects,
+    effects: &TransactionEffects,
+) -> Result<()> {
+    let diff = EffectsDiff::new(expected_effects, effects);
+    if !diff.is_empty() {
+        info!(
+            tx_digest = %effects.transaction_digest(),
+            changed_objects = diff.object_changes.len(),
+            "What-if effects differ from on-chain effects",
+        );
+    }
+    artifact_manager
+        .member(Artifact::EffectsDiff)
+        .serialize_artifact(&diff)
+        .transpose()?
+        .unwrap();
+    artifact_manager
+        .member(Artifact::ForkedTransactionEffects)
+        .try_remove_artifact()?;
+    Ok(())
 }
 
 fn verify_txn_and_save_effects(
@@ -234,6 +307,10 @@ fn verify_txn_and_save_effects(
     // If replayed and expected effects are the same, save the replayed effects
     // and try removing the forked effects (if any) so that the output just shows
     // the replayed effects rather than (now spurious) effects diff.
+    // The diff of a previous what-if replay (if any) is removed as well.
+    artifact_manager
+        .member(Artifact::EffectsDiff)
+        .try_remove_artifact()?;
     if effects != expected_effects {
         error!(
             tx_digest = %effects.transaction_digest(),
@@ -0,0 +1,357 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! What-if replay: replay a transaction with some of its inputs changed and compare
+//! the resulting effects with the on-chain effects.
+//! Objects and packages loaded for the transaction can be replaced with local objects
+//! (BCS or JSON encoded `Object`s), packages can be rebuilt from local sources, and the
+//! protocol config used for execution can be forced to another version and/or have
+//! individual attributes overridden.
+//! Overrides are applied to the `ReplayTransaction` object cache after it is loaded, so
+//! dynamic fields (child objects) that are read through the store during execution are
+//! not overridden; overriding an object the transaction does not load logs a warning.
+
+use crate::{package_tools::PackageRebuilder, replay_txn::ReplayTransaction};
+use anyhow::{Context, Result, anyhow, bail};
+use clap::Parser;
+use move_binary_format::CompiledModule;
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+use sui_data_store::{Node, ObjectKey, ObjectStore, VersionQuery};
+use sui_protocol_config::{Chain, ProtocolConfig, ProtocolConfigOptional, ProtocolVersion};
+use sui_types::{
+    base_types::{ObjectID, SequenceNumber},
+    object::Object,
+};
+use tracing::{debug, warn};
+
+/// Arguments for a what-if replay. When any is provided, the replayed effects are
+/// diffed against the on-chain effects instead of being checked for a fork.
+#[derive(Parser, Clone, Debug, Default)]
+pub struct WhatIfConfig {
+    /// Replace an object loaded for the transaction with a local object. The file holds a
+    /// BCS encoded `Object`, or a JSON encoded one if it has a `.json` extension.
+    /// The object keeps the version(s) the transaction loaded. Can be repeated.
+    #[arg(long = "override-object", value_name = "PATH")]
+    pub override_objects: Vec<PathBuf>,
+
+    /// Replace a package with a local package object, e.g. the output of
+    /// `rebuild-package --output`. Same encoding as `--override-object`. Can be repeated.
+    #[arg(long = "override-package", value_name = "PATH")]
+    pub override_packages: Vec<PathBuf>,
+
+    /// Replace a package with one built from local sources, as `<PKG_ID>=<PKG_SRC>`.
+    /// The on-chain package is rebuilt with the compiled modules as `rebuild-package` does,
+    /// without touching the cache. Can be repeated.
+    #[arg(long = "override-package-src", value_name = "PKG_ID=PKG_SRC")]
+    pub override_package_sources: Vec<String>,
+
+    /// Execute with the protocol config of this version instead of the one of the epoch
+    /// the transaction was executed in.
+    #[arg(long = "protocol-version")]
+    pub protocol_version: Option<u64>,
+
+    /// Override a protocol config attribute, as `<NAME>=<VALUE>`. Can be repeated.
+    #[arg(long = "protocol-config-override", value_name = "NAME=VALUE")]
+    pub protocol_config_overrides: Vec<String>,
+}
+
+impl WhatIfConfig {
+    pub fn is_enabled(&self) -> bool {
+        !self.override_objects.is_empty()
+            || !self.override_packages.is_empty()
+            || !self.override_package_sources.is_empty()
+            || self.protocol_version.is_some()
+            || !self.protocol_config_overrides.is_empty()
+    }
+}
+
+/// Changes to the protocol config a transaction is executed with.
+#[derive(Clone, Debug)]
+pub struct ProtocolConfigOverride {
+    chain: Chain,
+    version: Option<ProtocolVersion>,
+    attributes: serde_json::Map<String, serde_json::Value>,
+}
+
+impl ProtocolConfigOverride {
+    fn new(config: &WhatIfConfig, chain: Chain) -> Result<Option<Self>> {
+        if config.protocol_version.is_none() && config.protocol_config_overrides.is_empty() {
+            return Ok(None);
+        }
+
+        let version = config.protocol_version.map(ProtocolVersion::new);
+        if let Some(version) = version
+            && ProtocolConfig::get_for_version_if_supported(version, chain).is_none()
+        {
+            bail!("Protocol version {} is not supported", version.as_u64());
+        }
+
+        let known_attributes = ProtocolConfig::get_for_max_version_UNSAFE().attr_map();
+        let mut attributes = serde_json::Map::new();
+        for attribute in &config.protocol_config_overrides {
+            let (name, value) = attribute.split_once('=').ok_or_else(|| {
+                anyhow!("Invalid protocol config override '{attribute}', expected <NAME>=<VALUE>")
+            })?;
+            let (name, value) = (name.trim(), value.trim());
+            if !known_attributes.contains_key(name) {
+                bail!("Unknown protocol config attribute '{name}'");
+            }
+            // Attributes are numbers or booleans, which parse as JSON.
+            let value = serde_json::from_str(value)
+                .unwrap_or_else(|_| serde_json::Value::String(value.to_string()));
+            attributes.insert(name.to_string(), value);
+        }
+
+        let protocol_override = Self {
+            chain,
+            version,
+            attributes,
+        };
+        // Report ill-typed values before anything is replayed.
+        protocol_override.apply(ProtocolConfig::get_for_max_version_UNSAFE())?;
+        Ok(Some(protocol_override))
+    }
+
+    /// Apply the overrides to the protocol config of the epoch a transaction was executed in.
+    pub fn apply(&self, protocol_config: ProtocolConfig) -> Result<ProtocolConfig> {
+        let mut protocol_config = match self.version {
+            Some(version) => ProtocolConfig::get_for_version(version, self.chain),
+            None => protocol_config,
+        };
+        if !self.attributes.is_empty() {
+            let overrides: ProtocolConfigOptional =
+                serde_json::from_value(serde_json::Value::Object(self.attributes.clone()))
+                    .map_err(|e| anyhow!("Invalid protocol config override: {e}"))?;
+            overrides.apply_to(&mut protocol_config);
+        }
+        Ok(protocol_config)
+    }
+}
+
+/// A package rebuilt from local sources for every transaction that uses it.
+struct PackageSource {
+    package_id: ObjectID,
+    rebuilder: PackageRebuilder,
+    modules: Vec<CompiledModule>,
+}
+
+/// Overrides for a what-if replay, loaded once and applied to every transaction replayed.
+pub struct WhatIf {
+    objects: BTreeMap<ObjectID, Object>,
+    packages: BTreeMap<ObjectID, Object>,
+    package_sources: Vec<PackageSource>,
+    protocol_config_override: Option<ProtocolConfigOverride>,
+}
+
+impl WhatIf {
+    /// Read the override objects and packages and compile the package sources.
+    pub fn new(config: &WhatIfConfig, node: &Node) -> Result<Self> {
+        let mut objects = BTreeMap::new();
+        for path in &config.override_objects {
+            let object = read_object(path)?;
+            if object.is_package() {
+                bail!(
+                    "{} holds a package, use --override-package instead",
+                    path.display()
+                );
+            }
+            objects.insert(object.id(), object);
+        }
+
+        let mut packages = BTreeMap::new();
+        for path in &config.override_packages {
+            let package = read_object(path)?;
+            if !package.is_package() {
+                bail!("{} does not hold a package", path.display());
+            }
+            packages.insert(package.id(), package);
+        }
+
+        let mut package_sources = vec![];
+        for source in &config.override_package_sources {
+            let (package_id, source_path) = source.split_once('=').ok_or_else(|| {
+                anyhow!("Invalid package source override '{source}', expected <PKG_ID>=<PKG_SRC>")
+            })?;
+            let package_id = ObjectID::from_str(package_id.trim())
+                .map_err(|e| anyhow!("Invalid package ID: {}", e))?;
+            let rebuilder =
+                PackageRebuilder::new(node.clone(), package_id, source_path.into(), None);
+            let modules = rebuilder.compile().context(format!(
+                "Failed to build package {package_id} from {source_path}"
+            ))?;
+            package_sources.push(PackageSource {
+                package_id,
+                rebuilder,
+                modules,
+            });
+        }
+
+        Ok(Self {
+            objects,
+            packages,
+            package_sources,
+            protocol_config_override: ProtocolConfigOverride::new(config, node.chain())?,
+        })
+    }
+
+    pub fn protocol_config_override(&self) -> Option<&ProtocolConfigOverride> {
+        self.protocol_config_override.as_ref()
+    }
+
+    /// Replace the objects and packages loaded for the transaction with the overrides.
+    pub fn apply(&self, txn: &mut ReplayTransaction, object_store: &dyn ObjectStore) -> Result<()> {
+        for (object_id, object) in &self.objects {
+            debug!(object_id = %object_id, "override object");
+            let versions = txn.object_cache.entry(*object_id).or_default();
+            if versions.is_empty() {
+                // Child objects and dynamic fields are read from the store during execution,
+                // so an override for one is never seen.
+                warn!(
+                    tx_digest = %txn.digest,
+                    object_id = %object_id,
+                    "Override object is not an object loaded for the transaction, and is ignored \
+                     if it is a child object or dynamic field",
+                );
+                versions.insert(object.version().value(), object.clone());
+            }
+            // Keep the versions the transaction refers to so the inputs still line up.
+            for (version, cached) in versions.iter_mut() {
+                *cached = with_version(object, SequenceNumber::from_u64(*version));
+            }
+        }
+
+        for (package_id, package) in &self.packages {
+            debug!(package_id = %package_id, "override package");
+            txn.object_cache.insert(
+                *package_id,
+                BTreeMap::from([(package.version().value(), package.clone())]),
+            );
+        }
+
+        for source in &self.package_sources {
+            debug!(package_id = %source.package_id, "rebuild package");
+            let original = match txn
+                .object_cache
+                .get(&source.package_id)
+                .and_then(|versions| versions.values().next())
+            {
+                Some(package) => package.clone(),
+                None => object_store
+                    .get_objects(&[ObjectKey {
+                        object_id: source.package_id,
+                        version_query: VersionQuery::AtCheckpoint(txn.checkpoint),
+                    }])?
+                    .into_iter()
+                    .next()
+                    .flatten()
+                    .map(|(package, _version)| package)
+                    .ok_or_else(|| anyhow!("Package {} not found", source.package_id))?,
+            };
+            let package = source
+                .rebuilder
+                .rebuild_object(&original, source.modules.clone())
+                .context(format!("Failed to rebuild package {}", source.package_id))?;
+            txn.object_cache.insert(
+                source.package_id,
+                BTreeMap::from([(package.version().value(), package)]),
+            );
+        }
+
+        Ok(())
+    }
+}
+
+// Read an `Object` from a JSON file if it has a `.json` extension, or from a BCS file otherwise.
+fn read_object(path: &Path) -> Result<Object> {
+    let bytes =
+        std::fs::read(path).context(format!("Failed to read object file {}", path.display()))?;
+    if path.extension().is_some_and(|ext| ext == "json") {
+        serde_json::from_slice(&bytes)
+            .context(format!("Invalid JSON encoded object in {}", path.display()))
+    } else {
+        bcs::from_bytes(&bytes).context(format!("Invalid BCS encoded object in {}", path.display()))
+    }
+}
+
+fn with_version(object: &Object, version: SequenceNumber) -> Object {
+    if object.version() == version {
+        return object.clone();
+    }
+    let mut inner = object.clone().into_inner();
+    if let Some(move_object) = inner.data.try_as_move_mut() {
+        if version > move_object.version() {
+            move_object.increment_version_to(version);
+        } else {
+            move_object.decrement_version_to(version);
+        }
+    }
+    inner.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn protocol_override(
+        protocol_version: Option<u64>,
+        overrides: &[&str],
+    ) -> Result<Option<ProtocolConfigOverride>> {
+        let config = WhatIfConfig {
+            protocol_version,
+            protocol_config_overrides: overrides.iter().map(|o| o.to_string()).collect(),
+            ..Default::default()
+        };
+        ProtocolConfigOverride::new(&config, Chain::Unknown)
+    }
+
+    #[test]
+    fn test_protocol_config_override_attributes() {
+        let config_override = protocol_override(None, &["max_tx_gas = 42", "max_arguments=7"])
+            .unwrap()
+            .unwrap();
+
+        let base = ProtocolConfig::get_for_version(ProtocolVersion::MIN, Chain::Unknown);
+        let config = config_override.apply(base.clone()).unwrap();
+        assert_eq!(config.version, ProtocolVersion::MIN);
+        assert_eq!(config.max_tx_gas(), 42);
+        assert_eq!(config.max_arguments(), 7);
+        // Attributes that are not overridden keep the value of the epoch's config.
+        assert_eq!(config.max_gas_price(), base.max_gas_price());
+    }
+
+    #[test]
+    fn test_protocol_config_override_version() {
+        let config_override = protocol_override(Some(ProtocolVersion::MIN.as_u64()), &[])
+            .unwrap()
+            .unwrap();
+
+        let config = config_override
+            .apply(ProtocolConfig::get_for_max_version_UNSAFE())
+            .unwrap();
+        assert_eq!(config.version, ProtocolVersion::MIN);
+
+        // Attributes are applied on top of the forced version.
+        let config_override =
+            protocol_override(Some(ProtocolVersion::MIN.as_u64()), &["max_tx_gas=42"])
+                .unwrap()
+                .unwrap();
+        let config = config_override
+            .apply(ProtocolConfig::get_for_max_version_UNSAFE())
+            .unwrap();
+        assert_eq!(config.version, ProtocolVersion::MIN);
+        assert_eq!(config.max_tx_gas(), 42);
+    }
+
+    #[test]
+    fn test_protocol_config_override_invalid() {
+        assert!(protocol_override(None, &[]).unwrap().is_none());
+        assert!(protocol_override(Some(u64::MAX), &[]).is_err());
+        assert!(protocol_override(None, &["max_tx_gas"]).is_err());
+        assert!(protocol_override(None, &["not_an_attribute=1"]).is_err());
+        assert!(protocol_override(None, &["max_tx_gas=not_a_number"]).is_err());
+    }
+}
@@ -7,6 +7,7 @@ use axum_extra::extract::WithRejection;
 use prost_types::FieldMask;
 use sui_rpc::field::FieldMaskUtil;
 use sui_rpc::proto::sui::rpc::v2::GetTransactionRequest;
+use sui_types::base_types::TransactionDigest;
 use tracing::debug;
 
 use crate::operations::Operations;
@@ -45,12 +46,21 @@ pub async fn transaction(
     WithRejection(Json(request), _): WithRejection<Json<BlockTransactionRequest>, Error>,
 ) -> Result<BlockTransactionResponse, Error> {
     env.check_network_identifier(&request.network_identifier)?;
-    let digest = request.transaction_identifier.hash;
+    let (transaction, _) = get_transaction(&context, request.transaction_identifier.hash).await?;
+    Ok(BlockTransactionResponse { transaction })
+}
 
+/// Fetch a transaction and convert it into Rosetta operations, along with the sequence number of
+/// the checkpoint it was included in, if it is in one yet.
+pub(crate) async fn get_transaction(
+    context: &OnlineServerContext,
+    digest: TransactionDigest,
+) -> Result<(Transaction, Option<u64>), Error> {
     let request = GetTransactionRequest::default()
         .with_digest(digest.to_string())
         .with_read_mask(FieldMask::from_paths([
             "digest",
+            "checkpoint",
             "transaction.sender",
             "transaction.gas_payment",
             "transaction.kind",
@@ -70,13 +80,14 @@ pub async fn transaction(
         .await?
         .into_inner();
 
-    let operations = Operations::try_from_executed_transaction(
-        response
-            .transaction
-            .ok_or_else(|| Error::DataError("Response missing transaction".to_string()))?,
-        &context.coin_metadata_cache,
-    )
-    .await?;
+    let executed_tx = response
+        .transaction
+        .ok_or_else(|| Error::DataError("Response missing transaction".to_string()))?;
+    let checkpoint = executed_tx.checkpoint;
+
+    let operations =
+        Operations::try_from_executed_transaction(executed_tx, &context.coin_metadata_cache)
+            .await?;
 
     let transaction = Transaction {
         transaction_identifier: TransactionIdentifier { hash: digest },
@@ -85,5 +96,5 @@ pub async fn transaction(
         metadata: None,
     };
 
-    Ok(BlockTransactionResponse { transaction })
+    Ok((transaction, checkpoint))
 }
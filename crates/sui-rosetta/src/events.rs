@@ -0,0 +1,61 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use axum::extract::State;
+use axum::{Extension, Json};
+use axum_extra::extract::WithRejection;
+use futures::stream::{self, StreamExt, TryStreamExt};
+
+use crate::types::{BlockEvent, BlockEventType, EventsBlocksRequest, EventsBlocksResponse};
+use crate::{Error, OnlineServerContext, SuiEnv};
+
+// This module implements the [Mesh Events API](https://docs.cdp.coinbase.com/mesh/mesh-api-spec/api-reference#events)
+
+const DEFAULT_LIMIT: u64 = 100;
+const MAX_LIMIT: u64 = 1000;
+
+/// Get the sequence of block events, starting at `offset`, or if `offset` is not set, the last
+/// `limit` events up to the current block.
+///
+/// Checkpoints are final, so blocks are never removed, and the event with sequence number `n` is
+/// always the addition of the block with index `n`.
+///
+/// [Mesh API Spec](https://docs.cdp.coinbase.com/api-reference/mesh/events/get-a-range-of-blockevents)
+pub async fn blocks(
+    State(context): State<OnlineServerContext>,
+    Extension(env): Extension<SuiEnv>,
+    WithRejection(Json(request), _): WithRejection<Json<EventsBlocksRequest>, Error>,
+) -> Result<EventsBlocksResponse, Error> {
+    env.check_network_identifier(&request.network_identifier)?;
+
+    let limit = request.limit.unwrap_or(DEFAULT_LIMIT);
+    if limit == 0 || limit > MAX_LIMIT {
+        return Err(Error::InvalidInput(format!(
+            "limit must be between 1 and {MAX_LIMIT}"
+        )));
+    }
+
+    let blocks = context.blocks();
+    let max_sequence = blocks.current_block_identifier().await?.index;
+    let start = request
+        .offset
+        .unwrap_or_else(|| (max_sequence + 1).saturating_sub(limit));
+    let end = max_sequence.min(start.saturating_add(limit - 1));
+
+    let events = stream::iter(start..=end)
+        .map(|sequence| async move {
+            Ok::<_, Error>(BlockEvent {
+                sequence,
+                block_identifier: blocks.create_block_identifier(sequence).await?,
+                type_: BlockEventType::BlockAdded,
+            })
+        })
+        .buffered(10)
+        .try_collect()
+        .await?;
+
+    Ok(EventsBlocksResponse {
+        max_sequence,
+        events,
+    })
+}
@@ -30,8 +30,11 @@ mod account;
 mod block;
 mod construction;
 pub mod errors;
+mod events;
+mod mempool;
 mod network;
 pub mod operations;
+mod search;
 mod state;
 pub mod types;
 
@@ -73,6 +76,9 @@ impl RosettaOnlineServer {
             .route("/network/status", post(network::status))
             .route("/network/list", post(network::list))
             .route("/network/options", post(network::options))
+            .route("/search/transactions", post(search::transactions))
+            .route("/events/blocks", post(events::blocks))
+            .route("/mempool", post(mempool::mempool))
             .layer(Extension(self.env))
             .with_state(self.context);
 
@@ -0,0 +1,27 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use axum::{Extension, Json};
+use axum_extra::extract::WithRejection;
+
+use crate::types::{MempoolRequest, MempoolResponse};
+use crate::{Error, SuiEnv};
+
+// This module implements the [Mesh Mempool API](https://docs.cdp.coinbase.com/mesh/mesh-api-spec/api-reference#mempool)
+
+/// Get all transaction identifiers in the mempool.
+///
+/// Transactions submitted through `/construction/submit` are executed before the call returns,
+/// and there is no view into the pending transactions of the network, so the mempool is always
+/// empty.
+///
+/// [Mesh API Spec](https://docs.cdp.coinbase.com/api-reference/mesh/mempool/get-all-mempool-transactions)
+pub async fn mempool(
+    Extension(env): Extension<SuiEnv>,
+    WithRejection(Json(request), _): WithRejection<Json<MempoolRequest>, Error>,
+) -> Result<MempoolResponse, Error> {
+    env.check_network_identifier(&request.network_identifier)?;
+    Ok(MempoolResponse {
+        transaction_identifiers: vec![],
+    })
+}
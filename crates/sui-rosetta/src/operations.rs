@@ -90,6 +90,10 @@ impl Operations {
         self
     }
 
+    pub fn iter(&self) -> impl Iterator<Item = &Operation> {
+        self.0.iter()
+    }
+
     pub fn type_(&self) -> Option<OperationType> {
         self.0.first().map(|op| op.type_)
     }
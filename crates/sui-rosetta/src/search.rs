@@ -0,0 +1,189 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use axum::extract::State;
+use axum::{Extension, Json};
+use axum_extra::extract::WithRejection;
+use futures::stream::{self, StreamExt, TryStreamExt};
+use tracing::debug;
+
+use crate::block::get_transaction;
+use crate::types::{
+    BlockTransaction, OperationStatus, Operator, SearchTransactionsRequest,
+    SearchTransactionsResponse, Transaction,
+};
+use crate::{Error, OnlineServerContext, SuiEnv};
+
+// This module implements the [Mesh Search API](https://docs.cdp.coinbase.com/mesh/mesh-api-spec/api-reference#search)
+
+const DEFAULT_LIMIT: u64 = 100;
+const MAX_LIMIT: u64 = 1000;
+
+/// The maximum number of blocks (checkpoints) a single search request scans.
+const MAX_BLOCKS_PER_SEARCH: u64 = 100;
+
+/// Search for transactions matching a set of conditions.
+///
+/// There is no transaction index to query, so unless the search is for a specific transaction,
+/// blocks are scanned backwards from `max_block` (default: the current block), and their
+/// transactions are matched against the conditions. The conditions are checked against each
+/// transaction's operations, using the same conversion as `/block/transaction`.
+///
+/// Pagination is by block: `offset` is the number of blocks below `max_block` to skip, and
+/// `next_offset` is where the next page starts. Pages always contain whole blocks, and
+/// `total_count` is the number of matching transactions in the returned page. To page through a
+/// stable result set, set `max_block` explicitly.
+///
+/// [Mesh API Spec](https://docs.cdp.coinbase.com/api-reference/mesh/search/search-for-transactions)
+pub async fn transactions(
+    State(context): State<OnlineServerContext>,
+    Extension(env): Extension<SuiEnv>,
+    WithRejection(Json(request), _): WithRejection<Json<SearchTransactionsRequest>, Error>,
+) -> Result<SearchTransactionsResponse, Error> {
+    debug!("Called /search/transactions endpoint: {:?}", request);
+    env.check_network_identifier(&request.network_identifier)?;
+
+    let limit = request.limit.unwrap_or(DEFAULT_LIMIT);
+    if limit == 0 || limit > MAX_LIMIT {
+        return Err(Error::InvalidInput(format!(
+            "limit must be between 1 and {MAX_LIMIT}"
+        )));
+    }
+
+    // Looking up a transaction directly is much cheaper than scanning blocks for it.
+    if let Some(identifier) = &request.transaction_identifier {
+        let (transaction, checkpoint) = get_transaction(&context, identifier.hash).await?;
+        let checkpoint = checkpoint.ok_or_else(|| {
+            Error::DataError("Transaction is not in a checkpoint yet".to_string())
+        })?;
+        let in_page = request.max_block.is_none_or(|max| checkpoint <= max)
+            && request.offset.unwrap_or(0) == 0;
+
+        let mut transactions = vec![];
+        if in_page && matches(&request, &transaction) {
+            let blocks = context.blocks();
+            transactions.push(BlockTransaction {
+                block_identifier: blocks.create_block_identifier(checkpoint).await?,
+                transaction,
+            });
+        }
+
+        return Ok(SearchTransactionsResponse {
+            total_count: transactions.len() as u64,
+            transactions,
+            next_offset: None,
+        });
+    }
+
+    let blocks = context.blocks();
+    let max_block = match request.max_block {
+        Some(max_block) => max_block,
+        None => blocks.current_block_identifier().await?.index,
+    };
+
+    let offset = request.offset.unwrap_or(0);
+    let Some(start) = max_block.checked_sub(offset) else {
+        return Ok(SearchTransactionsResponse {
+            transactions: vec![],
+            total_count: 0,
+            next_offset: None,
+        });
+    };
+
+    let end = start.saturating_sub(MAX_BLOCKS_PER_SEARCH - 1);
+    let mut block_stream = stream::iter((end..=start).rev())
+        .map(|index| blocks.get_block_by_index(index))
+        .buffered(10);
+
+    let mut transactions = vec![];
+    let mut scanned = 0;
+    let mut exhausted = false;
+    while let Some(response) = block_stream.try_next().await? {
+        let block = response.block;
+        let block_identifier = block.block_identifier;
+        let matching: Vec<_> = block
+            .transactions
+            .into_iter()
+            .filter(|tx| matches(&request, tx))
+            .map(|transaction| BlockTransaction {
+                block_identifier,
+                transaction,
+            })
+            .collect();
+
+        // Leave the block for the next page if it would take this page over the limit.
+        if !transactions.is_empty() && transactions.len() + matching.len() > limit as usize {
+            break;
+        }
+
+        transactions.extend(matching);
+        scanned += 1;
+
+        if block_identifier.index == 0 {
+            exhausted = true;
+            break;
+        }
+
+        if transactions.len() >= limit as usize {
+            break;
+        }
+    }
+
+    Ok(SearchTransactionsResponse {
+        total_count: transactions.len() as u64,
+        transactions,
+        next_offset: (!exhausted).then_some(offset + scanned),
+    })
+}
+
+/// Whether `tx` satisfies the conditions in `request`. Each condition is satisfied if any of the
+/// transaction's operations satisfies it, and conditions are combined using the request's
+/// operator. A request with no conditions matches every transaction.
+fn matches(request: &SearchTransactionsRequest, tx: &Transaction) -> bool {
+    let ops = &tx.operations;
+    let conditions = [
+        request
+            .transaction_identifier
+            .as_ref()
+            .map(|id| id.hash == tx.transaction_identifier.hash),
+        request
+            .account_identifier
+            .as_ref()
+            .map(|account| ops.iter().any(|op| op.account.as_ref() == Some(account))),
+        request.address.map(|address| {
+            ops.iter()
+                .any(|op| op.account.as_ref().is_some_and(|a| a.address == address))
+        }),
+        request.coin_identifier.as_ref().map(|coin| {
+            ops.iter().any(|op| {
+                op.coin_change
+                    .as_ref()
+                    .is_some_and(|c| &c.coin_identifier == coin)
+            })
+        }),
+        request.currency.as_ref().map(|currency| {
+            ops.iter()
+                .any(|op| op.amount.as_ref().is_some_and(|a| &a.currency == currency))
+        }),
+        request
+            .status
+            .map(|status| ops.iter().any(|op| op.status == Some(status))),
+        request
+            .type_
+            .map(|type_| ops.iter().any(|op| op.type_ == type_)),
+        request.success.map(|success| {
+            ops.iter()
+                .any(|op| op.status.map(|s| s == OperationStatus::Success) == Some(success))
+        }),
+    ];
+
+    let mut conditions = conditions.into_iter().flatten().peekable();
+    if conditions.peek().is_none() {
+        return true;
+    }
+
+    match request.operator {
+        Operator::And => conditions.all(|c| c),
+        Operator::Or => conditions.any(|c| c),
+    }
+}
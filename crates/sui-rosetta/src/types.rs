@@ -891,6 +891,119 @@ impl IntoResponse for BlockTransactionResponse {
     }
 }
 
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Default, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Operator {
+    Or,
+    #[default]
+    And,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SearchTransactionsRequest {
+    pub network_identifier: NetworkIdentifier,
+    #[serde(default)]
+    pub operator: Operator,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_block: Option<u64>,
+    /// Number of blocks below `max_block` to skip before searching.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub offset: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transaction_identifier: Option<TransactionIdentifier>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub account_identifier: Option<AccountIdentifier>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub coin_identifier: Option<CoinIdentifier>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub currency: Option<Currency>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<OperationStatus>,
+    #[serde(default, rename = "type", skip_serializing_if = "Option::is_none")]
+    pub type_: Option<OperationType>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<SuiAddress>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub success: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SearchTransactionsResponse {
+    pub transactions: Vec<BlockTransaction>,
+    pub total_count: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_offset: Option<u64>,
+}
+
+impl IntoResponse for SearchTransactionsResponse {
+    fn into_response(self) -> Response {
+        Json(self).into_response()
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct BlockTransaction {
+    pub block_identifier: BlockIdentifier,
+    pub transaction: Transaction,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct EventsBlocksRequest {
+    pub network_identifier: NetworkIdentifier,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub offset: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct EventsBlocksResponse {
+    pub max_sequence: u64,
+    pub events: Vec<BlockEvent>,
+}
+
+impl IntoResponse for EventsBlocksResponse {
+    fn into_response(self) -> Response {
+        Json(self).into_response()
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct BlockEvent {
+    pub sequence: u64,
+    pub block_identifier: BlockIdentifier,
+    #[serde(rename = "type")]
+    pub type_: BlockEventType,
+}
+
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+#[allow(dead_code)]
+pub enum BlockEventType {
+    BlockAdded,
+    BlockRemoved,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct MempoolRequest {
+    pub network_identifier: NetworkIdentifier,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct MempoolResponse {
+    pub transaction_identifiers: Vec<TransactionIdentifier>,
+}
+
+impl IntoResponse for MempoolResponse {
+    fn into_response(self) -> Response {
+        Json(self).into_response()
+    }
+}
+
 #[derive(Serialize, Clone)]
 pub struct PrefundedAccount {
     pub privkey: String,
@@ -1785,3 +1785,177 @@ async fn test_block_transaction() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_search_transactions() -> Result<()> {
+    let test_cluster = TestClusterBuilder::new().build().await;
+    let mut client = GrpcClient::new(test_cluster.rpc_url())?;
+    let keystore = &test_cluster.wallet.config.keystore;
+
+    let sender = test_cluster.get_address_0();
+    let recipient = test_cluster.get_address_1();
+    let coins = get_all_coins(&mut client, sender).await?;
+    let gas_object = coins.first().unwrap().compute_object_reference();
+
+    let tx_data = TransactionData::new_transfer_sui(
+        recipient,
+        sender,
+        Some(1),
+        gas_object,
+        1_000_000,
+        test_cluster.get_reference_gas_price().await,
+    );
+    let tx = to_sender_signed_transaction(tx_data, keystore.export(&sender)?);
+    let executed_tx = execute_transaction(&mut client, &tx).await?;
+    let tx_digest = executed_tx.transaction().digest().to_string();
+    let checkpoint = client
+        .ledger_client()
+        .get_transaction(
+            GetTransactionRequest::default()
+                .with_digest(tx_digest.clone())
+                .with_read_mask(FieldMask::from_paths(["checkpoint"])),
+        )
+        .await?
+        .into_inner()
+        .transaction()
+        .checkpoint();
+
+    let (rosetta_client, _handle) = start_rosetta_test_server(client).await;
+
+    // Scanning blocks for the recipient's transactions.
+    let request = serde_json::json!({
+        "network_identifier": {
+            "blockchain": "sui",
+            "network": "localnet"
+        },
+        "max_block": checkpoint,
+        "address": recipient.to_string(),
+        "type": "SuiBalanceChange",
+        "success": true
+    });
+
+    let response: serde_json::Value = rosetta_client
+        .call(RosettaEndpoint::SearchTransactions, &request)
+        .await
+        .unwrap();
+
+    let transactions = response["transactions"].as_array().unwrap();
+    assert_eq!(
+        response["total_count"].as_u64().unwrap(),
+        transactions.len() as u64
+    );
+    let found = transactions
+        .iter()
+        .find(|t| t["transaction"]["transaction_identifier"]["hash"].as_str() == Some(&tx_digest))
+        .expect("transfer should match the search");
+    assert_eq!(
+        found["block_identifier"]["index"].as_u64(),
+        Some(checkpoint)
+    );
+
+    // Looking up the transaction directly.
+    let request = serde_json::json!({
+        "network_identifier": {
+            "blockchain": "sui",
+            "network": "localnet"
+        },
+        "transaction_identifier": {
+            "hash": tx_digest
+        }
+    });
+
+    let response: serde_json::Value = rosetta_client
+        .call(RosettaEndpoint::SearchTransactions, &request)
+        .await
+        .unwrap();
+
+    let transactions = response["transactions"].as_array().unwrap();
+    assert_eq!(transactions.len(), 1);
+    assert_eq!(
+        transactions[0]["block_identifier"]["index"].as_u64(),
+        Some(checkpoint)
+    );
+
+    // The same operations as `/block/transaction`.
+    let request = serde_json::json!({
+        "network_identifier": {
+            "blockchain": "sui",
+            "network": "localnet"
+        },
+        "block_identifier": transactions[0]["block_identifier"],
+        "transaction_identifier": {
+            "hash": tx_digest
+        }
+    });
+
+    let block_transaction: serde_json::Value = rosetta_client
+        .call(RosettaEndpoint::Transaction, &request)
+        .await
+        .unwrap();
+    assert_eq!(
+        block_transaction["transaction"]["operations"],
+        transactions[0]["transaction"]["operations"]
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_events_blocks() -> Result<()> {
+    let test_cluster = TestClusterBuilder::new().build().await;
+    let client = GrpcClient::new(test_cluster.rpc_url())?;
+    let (rosetta_client, _handle) = start_rosetta_test_server(client).await;
+
+    let request = serde_json::json!({
+        "network_identifier": {
+            "blockchain": "sui",
+            "network": "localnet"
+        },
+        "offset": 0,
+        "limit": 2
+    });
+
+    let response: serde_json::Value = rosetta_client
+        .call(RosettaEndpoint::EventsBlocks, &request)
+        .await
+        .unwrap();
+
+    let events = response["events"].as_array().unwrap();
+    assert_eq!(events.len(), 2);
+    for (i, event) in events.iter().enumerate() {
+        assert_eq!(event["sequence"].as_u64(), Some(i as u64));
+        assert_eq!(event["block_identifier"]["index"].as_u64(), Some(i as u64));
+        assert_eq!(event["type"].as_str(), Some("block_added"));
+    }
+    assert!(response["max_sequence"].as_u64().unwrap() >= 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_mempool() -> Result<()> {
+    let test_cluster = TestClusterBuilder::new().build().await;
+    let client = GrpcClient::new(test_cluster.rpc_url())?;
+    let (rosetta_client, _handle) = start_rosetta_test_server(client).await;
+
+    let request = serde_json::json!({
+        "network_identifier": {
+            "blockchain": "sui",
+            "network": "localnet"
+        }
+    });
+
+    let response: serde_json::Value = rosetta_client
+        .call(RosettaEndpoint::Mempool, &request)
+        .await
+        .unwrap();
+
+    assert!(
+        response["transaction_identifiers"]
+            .as_array()
+            .unwrap()
+            .is_empty()
+    );
+
+    Ok(())
+}
@@ -268,6 +268,9 @@ pub enum RosettaEndpoint {
     Submit,
     Metadata,
     Status,
+    SearchTransactions,
+    EventsBlocks,
+    Mempool,
 }
 
 impl RosettaEndpoint {
@@ -288,6 +291,9 @@ impl RosettaEndpoint {
             RosettaEndpoint::Submit => "construction/submit",
             RosettaEndpoint::Metadata => "construction/metadata",
             RosettaEndpoint::Status => "network/status",
+            RosettaEndpoint::SearchTransactions => "search/transactions",
+            RosettaEndpoint::EventsBlocks => "events/blocks",
+            RosettaEndpoint::Mempool => "mempool",
         }
     }
 
@@ -307,7 +313,10 @@ impl RosettaEndpoint {
             | RosettaEndpoint::Transaction
             | RosettaEndpoint::Submit
             | RosettaEndpoint::Metadata
-            | RosettaEndpoint::Status => true,
+            | RosettaEndpoint::Status
+            | RosettaEndpoint::SearchTransactions
+            | RosettaEndpoint::EventsBlocks
+            | RosettaEndpoint::Mempool => true,
         }
     }
 }
@@ -0,0 +1,133 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+#![allow(clippy::field_reassign_with_default)]
+
+use crate::ErrorReason;
+use crate::Result;
+use crate::RpcError;
+use crate::RpcService;
+use crate::grpc::alpha::gas_price_service_proto::{
+    GasPriceConfidence, ObjectCongestion, SuggestGasPriceRequest, SuggestGasPriceResponse,
+    gas_price_service_server::GasPriceService,
+};
+use sui_rpc::proto::google::rpc::bad_request::FieldViolation;
+use sui_rpc::proto::timestamp_ms_to_proto;
+use sui_types::base_types::ObjectID;
+use sui_types::storage::{MAX_GAS_PRICE_SUGGESTION_OBJECTS, ObjectCongestionInfo};
+use sui_types::transaction::TransactionData;
+
+pub struct GasPriceServiceImpl {
+    service: RpcService,
+}
+
+impl GasPriceServiceImpl {
+    pub fn new(service: RpcService) -> Self {
+        Self { service }
+    }
+}
+
+#[tonic::async_trait]
+impl GasPriceService for GasPriceServiceImpl {
+    async fn suggest_gas_price(
+        &self,
+        request: tonic::Request<SuggestGasPriceRequest>,
+    ) -> Result<tonic::Response<SuggestGasPriceResponse>, tonic::Status> {
+        suggest_gas_price(&self.service, request.into_inner())
+            .map(tonic::Response::new)
+            .map_err(Into::into)
+    }
+}
+
+#[tracing::instrument(skip(service))]
+fn suggest_gas_price(
+    service: &RpcService,
+    request: SuggestGasPriceRequest,
+) -> Result<SuggestGasPriceResponse> {
+    if request.transaction.is_none() && request.object_ids.is_empty() {
+        return Err(FieldViolation::new("object_ids")
+            .with_description("one of transaction or object_ids must be set")
+            .with_reason(ErrorReason::FieldMissing)
+            .into());
+    }
+
+    if request.object_ids.len() > MAX_GAS_PRICE_SUGGESTION_OBJECTS {
+        return Err(FieldViolation::new("object_ids")
+            .with_description(format!(
+                "at most {MAX_GAS_PRICE_SUGGESTION_OBJECTS} objects can be requested"
+            ))
+            .with_reason(ErrorReason::FieldInvalid)
+            .into());
+    }
+
+    let transaction = request
+        .transaction
+        .as_ref()
+        .map(|bcs| {
+            bcs.deserialize::<TransactionData>().map_err(|e| {
+                FieldViolation::new("transaction")
+                    .with_description(format!("invalid transaction: {e}"))
+                    .with_reason(ErrorReason::FieldInvalid)
+            })
+        })
+        .transpose()?;
+
+    let object_ids = request
+        .object_ids
+        .iter()
+        .enumerate()
+        .map(|(i, object_id)| {
+            object_id.parse::<ObjectID>().map_err(|e| {
+                FieldViolation::new_at("object_ids", i)
+                    .with_description(format!("invalid object_id: {e}"))
+                    .with_reason(ErrorReason::FieldInvalid)
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let suggestion = service
+        .reader
+        .inner()
+        .get_gas_price_suggestion(transaction.as_ref(), &object_ids)?
+        .ok_or_else(|| {
+            RpcError::new(
+                tonic::Code::Unimplemented,
+                "gas price suggestions are not supported by this node",
+            )
+        })?;
+
+    let mut message = SuggestGasPriceResponse::default();
+    message.gas_price = Some(suggestion.gas_price);
+    message.reference_gas_price = Some(suggestion.reference_gas_price);
+    message.set_confidence(confidence_to_proto(suggestion.confidence));
+    message.objects = suggestion
+        .objects
+        .into_iter()
+        .map(object_congestion_to_proto)
+        .collect();
+    Ok(message)
+}
+
+fn object_congestion_to_proto(info: ObjectCongestionInfo) -> ObjectCongestion {
+    let mut message = ObjectCongestion::default();
+    message.object_id = Some(info.object_id.to_string());
+    message.suggested_gas_price = info.suggested_gas_price;
+    message.set_confidence(confidence_to_proto(info.confidence));
+    message.last_cancellation = info
+        .last_cancellation_timestamp_ms
+        .map(timestamp_ms_to_proto);
+    message.highest_cancelled_gas_price = info.highest_cancelled_gas_price;
+    message.last_success = info.last_success_timestamp_ms.map(timestamp_ms_to_proto);
+    message.lowest_executed_gas_price = info.lowest_executed_gas_price;
+    message
+}
+
+fn confidence_to_proto(confidence: sui_types::storage::GasPriceConfidence) -> GasPriceConfidence {
+    use sui_types::storage::GasPriceConfidence as Confidence;
+
+    match confidence {
+        Confidence::Low => GasPriceConfidence::Low,
+        Confidence::Medium => GasPriceConfidence::Medium,
+        Confidence::High => GasPriceConfidence::High,
+    }
+}
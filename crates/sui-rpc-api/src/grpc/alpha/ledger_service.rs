@@ -0,0 +1,41 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::RpcService;
+use crate::grpc::alpha::ledger_service_proto::ledger_service_server::LedgerService;
+use crate::grpc::alpha::ledger_service_proto::{
+    ListTransactionsRequest, ListTransactionsResponse, QueryEventsRequest, QueryEventsResponse,
+};
+use crate::grpc::alpha::list_transactions::list_transactions;
+use crate::grpc::alpha::query_events::query_events;
+
+pub struct LedgerServiceImpl {
+    service: RpcService,
+}
+
+impl LedgerServiceImpl {
+    pub fn new(service: RpcService) -> Self {
+        Self { service }
+    }
+}
+
+#[tonic::async_trait]
+impl LedgerService for LedgerServiceImpl {
+    async fn list_transactions(
+        &self,
+        request: tonic::Request<ListTransactionsRequest>,
+    ) -> Result<tonic::Response<ListTransactionsResponse>, tonic::Status> {
+        list_transactions(&self.service, request.into_inner())
+            .map(tonic::Response::new)
+            .map_err(Into::into)
+    }
+
+    async fn query_events(
+        &self,
+        request: tonic::Request<QueryEventsRequest>,
+    ) -> Result<tonic::Response<QueryEventsResponse>, tonic::Status> {
+        query_events(&self.service, request.into_inner())
+            .map(tonic::Response::new)
+            .map_err(Into::into)
+    }
+}
@@ -0,0 +1,165 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+#![allow(clippy::field_reassign_with_default)]
+
+use crate::ErrorReason;
+use crate::RpcError;
+use crate::RpcService;
+use crate::grpc::alpha::ledger_service_proto::{ListTransactionsRequest, ListTransactionsResponse};
+use crate::grpc::alpha::subscription_service::transaction_filter_from_proto;
+use crate::grpc::v2::ledger_service::transaction_to_response;
+use prost::Message;
+use prost_types::FieldMask;
+use sui_rpc::field::FieldMaskTree;
+use sui_rpc::field::FieldMaskUtil;
+use sui_rpc::proto::google::rpc::bad_request::FieldViolation;
+use sui_rpc::proto::sui::rpc::v2::ExecutedTransaction;
+use sui_types::storage::TransactionIndexFilter;
+
+const MAX_PAGE_SIZE: u32 = 1000;
+const DEFAULT_PAGE_SIZE: u32 = 50;
+const MAX_PAGE_SIZE_BYTES: usize = 512 * 1024; // 512KiB
+const READ_MASK_DEFAULT: &str = "digest";
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct PageToken {
+    filter: TransactionIndexFilter,
+    descending: bool,
+    next_checkpoint: u64,
+    next_transaction_idx: u32,
+}
+
+fn decode_page_token(page_token: &[u8]) -> Result<PageToken, RpcError> {
+    bcs::from_bytes(page_token).map_err(|_| {
+        RpcError::new(
+            tonic::Code::InvalidArgument,
+            "invalid page_token".to_string(),
+        )
+    })
+}
+
+fn encode_page_token(page_token: PageToken) -> Vec<u8> {
+    bcs::to_bytes(&page_token).unwrap()
+}
+
+#[tracing::instrument(skip(service))]
+pub fn list_transactions(
+    service: &RpcService,
+    request: ListTransactionsRequest,
+) -> Result<ListTransactionsResponse, RpcError> {
+    if !service.config.transaction_and_event_indexing() {
+        return Err(RpcError::new(
+            tonic::Code::Unimplemented,
+            "Transaction and event indexing is disabled".to_string(),
+        ));
+    }
+
+    let filter = request.filter.ok_or_else(|| {
+        FieldViolation::new("filter")
+            .with_description("missing filter")
+            .with_reason(ErrorReason::FieldMissing)
+    })?;
+    let filter = transaction_filter_from_proto(filter.filter).map_err(|description| {
+        FieldViolation::new("filter")
+            .with_description(description)
+            .with_reason(ErrorReason::FieldInvalid)
+    })?;
+
+    let descending = request.descending.unwrap_or(false);
+    let page_size = request
+        .page_size
+        .map(|s| s.clamp(1, MAX_PAGE_SIZE))
+        .unwrap_or(DEFAULT_PAGE_SIZE);
+
+    let page_token = request
+        .page_token
+        .as_ref()
+        .map(|token| decode_page_token(token))
+        .transpose()?;
+
+    if let Some(token) = &page_token
+        && (token.filter != filter || token.descending != descending)
+    {
+        return Err(RpcError::new(
+            tonic::Code::InvalidArgument,
+            "page_token does not match request".to_string(),
+        ));
+    }
+
+    let read_mask = {
+        let read_mask = request
+            .read_mask
+            .unwrap_or_else(|| FieldMask::from_str(READ_MASK_DEFAULT));
+        read_mask
+            .validate::<ExecutedTransaction>()
+            .map_err(|path| {
+                FieldViolation::new("read_mask")
+                    .with_description(format!("invalid read_mask path: {path}"))
+                    .with_reason(ErrorReason::FieldInvalid)
+            })?;
+        FieldMaskTree::from(read_mask)
+    };
+
+    let reader = service.reader.inner();
+    let indexes = reader.indexes().ok_or_else(RpcError::not_found)?;
+
+    // Transactions from pruned checkpoints are still in the index until it is compacted, but
+    // can no longer be loaded.
+    let lowest_available = reader.get_lowest_available_checkpoint()?;
+    let mut cursor = page_token.map(|t| (t.next_checkpoint, t.next_transaction_idx));
+    if !descending {
+        cursor = cursor.max(Some((lowest_available, 0)));
+    }
+
+    let iter = indexes
+        .transactions_iter(filter.clone(), cursor, descending)
+        .map_err(|e| RpcError::new(tonic::Code::Internal, e.to_string()))?;
+
+    let mut transactions = Vec::new();
+    let mut size_bytes = 0;
+    let mut next_page_token = None;
+
+    for result in iter {
+        let (checkpoint, transaction_idx, digest) =
+            result.map_err(|e| RpcError::new(tonic::Code::Internal, e.to_string()))?;
+
+        if checkpoint < lowest_available {
+            break;
+        }
+
+        let token = || {
+            encode_page_token(PageToken {
+                filter: filter.clone(),
+                descending,
+                next_checkpoint: checkpoint,
+                next_transaction_idx: transaction_idx,
+            })
+        };
+
+        if transactions.len() >= page_size as usize {
+            next_page_token = Some(token());
+            break;
+        }
+
+        let transaction = transaction_to_response(
+            service,
+            service.reader.get_transaction_read(digest.into())?,
+            &read_mask,
+        );
+        let transaction_size = transaction.encoded_len();
+
+        if !transactions.is_empty() && size_bytes + transaction_size > MAX_PAGE_SIZE_BYTES {
+            next_page_token = Some(token());
+            break;
+        }
+
+        size_bytes += transaction_size;
+        transactions.push(transaction);
+    }
+
+    let mut response = ListTransactionsResponse::default();
+    response.transactions = transactions;
+    response.next_page_token = next_page_token;
+    Ok(response)
+}
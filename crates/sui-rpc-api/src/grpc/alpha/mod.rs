@@ -2,13 +2,30 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod event_service;
+pub mod gas_price_service;
+pub mod ledger_service;
 pub mod list_authenticated_events;
+pub mod list_transactions;
 pub mod proof_service;
+pub mod query_events;
+pub mod subscription_service;
 
 pub mod event_service_proto {
     include!("../../proto/generated/sui.rpc.alpha.rs");
 }
 
+pub mod gas_price_service_proto {
+    include!("../../proto/generated/sui.rpc.alpha.rs");
+}
+
+pub mod ledger_service_proto {
+    include!("../../proto/generated/sui.rpc.alpha.rs");
+}
+
 pub mod proof_service_proto {
     include!("../../proto/generated/sui.rpc.alpha.rs");
 }
+
+pub mod subscription_service_proto {
+    include!("../../proto/generated/sui.rpc.alpha.rs");
+}
@@ -0,0 +1,219 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+#![allow(clippy::field_reassign_with_default)]
+
+use crate::ErrorReason;
+use crate::RpcError;
+use crate::RpcService;
+use crate::grpc::alpha::ledger_service_proto::event_filter::Filter;
+use crate::grpc::alpha::ledger_service_proto::{
+    IndexedEvent, MoveModuleFilter, QueryEventsRequest, QueryEventsResponse,
+};
+use prost::Message;
+use prost_types::FieldMask;
+use std::str::FromStr;
+use sui_rpc::field::FieldMaskTree;
+use sui_rpc::field::FieldMaskUtil;
+use sui_rpc::merge::Merge;
+use sui_rpc::proto::google::rpc::bad_request::FieldViolation;
+use sui_rpc::proto::sui::rpc::v2::Event;
+use sui_types::base_types::{ObjectID, SuiAddress};
+use sui_types::storage::EventIndexFilter;
+
+const MAX_PAGE_SIZE: u32 = 1000;
+const DEFAULT_PAGE_SIZE: u32 = 50;
+const MAX_PAGE_SIZE_BYTES: usize = 512 * 1024; // 512KiB
+const READ_MASK_DEFAULT: &str = "package_id,module,sender,event_type";
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct PageToken {
+    filter: EventIndexFilter,
+    descending: bool,
+    next_checkpoint: u64,
+    next_transaction_idx: u32,
+    next_event_idx: u32,
+}
+
+fn decode_page_token(page_token: &[u8]) -> Result<PageToken, RpcError> {
+    bcs::from_bytes(page_token).map_err(|_| {
+        RpcError::new(
+            tonic::Code::InvalidArgument,
+            "invalid page_token".to_string(),
+        )
+    })
+}
+
+fn encode_page_token(page_token: PageToken) -> Vec<u8> {
+    bcs::to_bytes(&page_token).unwrap()
+}
+
+/// Parse an `EventFilter` (see the proto definition), describing what is wrong with it if it is
+/// invalid.
+fn event_filter_from_proto(filter: Option<Filter>) -> Result<EventIndexFilter, String> {
+    Ok(match filter {
+        None => return Err("filter is required".to_owned()),
+        Some(Filter::Sender(a)) => EventIndexFilter::Sender(
+            SuiAddress::from_str(&a).map_err(|e| format!("invalid address {a:?}: {e}"))?,
+        ),
+        Some(Filter::EmittingModule(MoveModuleFilter { package, module })) => {
+            let package = package.ok_or("emitting_module.package is required")?;
+            let package = ObjectID::from_str(&package)
+                .map_err(|e| format!("invalid package {package:?}: {e}"))?;
+            EventIndexFilter::EmittingModule { package, module }
+        }
+        Some(Filter::EventType(t)) => EventIndexFilter::EventType(
+            sui_types::parse_sui_struct_tag(&t)
+                .map_err(|e| format!("invalid event_type {t:?}: {e}"))?,
+        ),
+    })
+}
+
+fn to_indexed_event(
+    service: &RpcService,
+    checkpoint: u64,
+    transaction_idx: u32,
+    event_idx: u32,
+    digest: &sui_types::digests::TransactionDigest,
+    event: &sui_types::event::Event,
+    mask: &FieldMaskTree,
+) -> IndexedEvent {
+    let mut message = Event::merge_from(event, mask);
+    if mask.contains(Event::JSON_FIELD.name) {
+        message.json =
+            crate::grpc::v2::render_json(service, &event.type_, &event.contents).map(Box::new);
+    }
+
+    let mut indexed_event = IndexedEvent::default();
+    indexed_event.checkpoint = Some(checkpoint);
+    indexed_event.transaction_idx = Some(transaction_idx);
+    indexed_event.event_idx = Some(event_idx);
+    indexed_event.transaction_digest = Some(digest.to_string());
+    indexed_event.event = Some(message);
+    indexed_event
+}
+
+#[tracing::instrument(skip(service))]
+pub fn query_events(
+    service: &RpcService,
+    request: QueryEventsRequest,
+) -> Result<QueryEventsResponse, RpcError> {
+    if !service.config.transaction_and_event_indexing() {
+        return Err(RpcError::new(
+            tonic::Code::Unimplemented,
+            "Transaction and event indexing is disabled".to_string(),
+        ));
+    }
+
+    let filter = request.filter.ok_or_else(|| {
+        FieldViolation::new("filter")
+            .with_description("missing filter")
+            .with_reason(ErrorReason::FieldMissing)
+    })?;
+    let filter = event_filter_from_proto(filter.filter).map_err(|description| {
+        FieldViolation::new("filter")
+            .with_description(description)
+            .with_reason(ErrorReason::FieldInvalid)
+    })?;
+
+    let descending = request.descending.unwrap_or(false);
+    let page_size = request
+        .page_size
+        .map(|s| s.clamp(1, MAX_PAGE_SIZE))
+        .unwrap_or(DEFAULT_PAGE_SIZE);
+
+    let page_token = request
+        .page_token
+        .as_ref()
+        .map(|token| decode_page_token(token))
+        .transpose()?;
+
+    if let Some(token) = &page_token
+        && (token.filter != filter || token.descending != descending)
+    {
+        return Err(RpcError::new(
+            tonic::Code::InvalidArgument,
+            "page_token does not match request".to_string(),
+        ));
+    }
+
+    let read_mask = {
+        let read_mask = request
+            .read_mask
+            .unwrap_or_else(|| FieldMask::from_str(READ_MASK_DEFAULT));
+        read_mask.validate::<Event>().map_err(|path| {
+            FieldViolation::new("read_mask")
+                .with_description(format!("invalid read_mask path: {path}"))
+                .with_reason(ErrorReason::FieldInvalid)
+        })?;
+        FieldMaskTree::from(read_mask)
+    };
+
+    let reader = service.reader.inner();
+    let indexes = reader.indexes().ok_or_else(RpcError::not_found)?;
+
+    // Events from pruned checkpoints are still in the index until it is compacted, but can no
+    // longer be loaded.
+    let lowest_available = reader.get_lowest_available_checkpoint()?;
+    let mut cursor =
+        page_token.map(|t| (t.next_checkpoint, t.next_transaction_idx, t.next_event_idx));
+    if !descending {
+        cursor = cursor.max(Some((lowest_available, 0, 0)));
+    }
+
+    let iter = indexes
+        .events_iter(filter.clone(), cursor, descending)
+        .map_err(|e| RpcError::new(tonic::Code::Internal, e.to_string()))?;
+
+    let mut events = Vec::new();
+    let mut size_bytes = 0;
+    let mut next_page_token = None;
+
+    for result in iter {
+        let (checkpoint, transaction_idx, event_idx, digest, event) =
+            result.map_err(|e| RpcError::new(tonic::Code::Internal, e.to_string()))?;
+
+        if checkpoint < lowest_available {
+            break;
+        }
+
+        let token = || {
+            encode_page_token(PageToken {
+                filter: filter.clone(),
+                descending,
+                next_checkpoint: checkpoint,
+                next_transaction_idx: transaction_idx,
+                next_event_idx: event_idx,
+            })
+        };
+
+        if events.len() >= page_size as usize {
+            next_page_token = Some(token());
+            break;
+        }
+
+        let indexed_event = to_indexed_event(
+            service,
+            checkpoint,
+            transaction_idx,
+            event_idx,
+            &digest,
+            &event,
+            &read_mask,
+        );
+        let event_size = indexed_event.encoded_len();
+
+        if !events.is_empty() && size_bytes + event_size > MAX_PAGE_SIZE_BYTES {
+            next_page_token = Some(token());
+            break;
+        }
+
+        size_bytes += event_size;
+        events.push(indexed_event);
+    }
+
+    let mut response = QueryEventsResponse::default();
+    response.events = events;
+    response.next_page_token = next_page_token;
+    Ok(response)
+}
@@ -0,0 +1,472 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeSet;
+use std::pin::Pin;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::ErrorReason;
+use crate::RpcError;
+use crate::RpcService;
+use crate::error::CheckpointNotFoundError;
+use crate::grpc::alpha::subscription_service_proto::subscription_service_server::SubscriptionService;
+use crate::grpc::alpha::subscription_service_proto::transaction_filter::Filter;
+use crate::grpc::alpha::subscription_service_proto::{
+    MoveCallFilter, SubscribeCheckpointsRequest, SubscribeCheckpointsResponse,
+};
+use crate::grpc::v2::ledger_service::get_checkpoint::{
+    READ_MASK_DEFAULT, executed_transaction_to_proto,
+};
+use crate::subscription::SubscriptionServiceHandle;
+use prost_types::FieldMask;
+use sui_rpc::field::FieldMaskTree;
+use sui_rpc::field::FieldMaskUtil;
+use sui_rpc::merge::Merge;
+use sui_rpc::proto::google::rpc::bad_request::FieldViolation;
+use sui_rpc::proto::sui::rpc::v2::Checkpoint;
+use sui_rpc::proto::sui::rpc::v2::ObjectSet;
+use sui_types::base_types::{ObjectID, SuiAddress};
+use sui_types::effects::TransactionEffectsAPI;
+use sui_types::full_checkpoint_content::{
+    Checkpoint as CheckpointData, ExecutedTransaction, ObjectSet as ObjectSetData,
+};
+use sui_types::object::Owner;
+use sui_types::storage::RpcStateReader;
+use sui_types::storage::TransactionIndexFilter;
+use sui_types::transaction::TransactionDataAPI;
+use tokio::sync::mpsc;
+use tokio::time::{Instant, Interval, MissedTickBehavior};
+
+pub struct SubscriptionServiceImpl {
+    service: RpcService,
+}
+
+/// A validated `SubscribeCheckpointsRequest`.
+struct Subscription {
+    read_mask: FieldMaskTree,
+    start_cursor: Option<u64>,
+    filters: Vec<TransactionIndexFilter>,
+    heartbeat_interval: Option<Duration>,
+}
+
+impl SubscriptionServiceImpl {
+    pub fn new(service: RpcService) -> Self {
+        Self { service }
+    }
+}
+
+#[tonic::async_trait]
+impl SubscriptionService for SubscriptionServiceImpl {
+    /// Server streaming response type for the SubscribeCheckpoints method.
+    type SubscribeCheckpointsStream = Pin<
+        Box<
+            dyn tokio_stream::Stream<Item = Result<SubscribeCheckpointsResponse, tonic::Status>>
+                + Send,
+        >,
+    >;
+
+    async fn subscribe_checkpoints(
+        &self,
+        request: tonic::Request<SubscribeCheckpointsRequest>,
+    ) -> Result<tonic::Response<Self::SubscribeCheckpointsStream>, tonic::Status> {
+        let handle = self
+            .service
+            .subscription_service_handle
+            .clone()
+            .ok_or_else(|| tonic::Status::unimplemented("subscription service not enabled"))?;
+
+        let subscription = Subscription::new(&self.service, request.into_inner())?;
+
+        // Register for live checkpoints before looking at the store, so that every checkpoint
+        // executed from this point on is either in the store or delivered to the receiver.
+        let receiver = register(&handle).await?;
+
+        Ok(tonic::Response::new(Box::pin(subscribe(
+            self.service.clone(),
+            handle,
+            receiver,
+            subscription,
+        ))))
+    }
+}
+
+/// Stream checkpoints for `subscription`, starting with checkpoints from the store (if the
+/// subscription has a start cursor), and then switching to the live checkpoints delivered to
+/// `receiver`.
+///
+/// The subscription service drops subscribers that fall too far behind. When that happens, the
+/// stream re-registers, and catches up from the store again, so clients never see gaps.
+fn subscribe(
+    service: RpcService,
+    handle: SubscriptionServiceHandle,
+    mut receiver: mpsc::Receiver<Arc<CheckpointData>>,
+    subscription: Subscription,
+) -> impl tokio_stream::Stream<Item = Result<SubscribeCheckpointsResponse, tonic::Status>> + Send {
+    async_stream::try_stream! {
+        let reader = service.reader.inner().clone();
+        let mut next = match subscription.start_cursor {
+            Some(cursor) => cursor,
+            None => latest_checkpoint(&service)? + 1,
+        };
+
+        let mut heartbeat = subscription.heartbeat_interval.map(|period| {
+            let mut interval = tokio::time::interval_at(Instant::now() + period, period);
+            interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            interval
+        });
+
+        let mut catch_up = true;
+        loop {
+            if catch_up {
+                let latest = latest_checkpoint(&service)?;
+                while next <= latest {
+                    let checkpoint = stored_checkpoint(&reader, next).await?;
+                    yield subscription.response(&service, &checkpoint)?;
+                    next += 1;
+                }
+
+                catch_up = false;
+                reset(&mut heartbeat);
+            }
+
+            let live = tokio::select! {
+                checkpoint = receiver.recv() => Some(checkpoint),
+                _ = tick(&mut heartbeat) => None,
+            };
+
+            let Some(live) = live else {
+                yield subscription.heartbeat(&service, next)?;
+                continue;
+            };
+
+            let Some(checkpoint) = live else {
+                // The subscription was dropped for falling behind.
+                receiver = register(&handle).await?;
+                catch_up = true;
+                continue;
+            };
+
+            // Checkpoints at or before the ones streamed from the store, while catching up.
+            let sequence_number = checkpoint.summary.sequence_number;
+            if sequence_number < next {
+                continue;
+            }
+
+            // Checkpoints executed after the store was last read, but before the subscription was
+            // registered.
+            while next < sequence_number {
+                let checkpoint = stored_checkpoint(&reader, next).await?;
+                yield subscription.response(&service, &checkpoint)?;
+                next += 1;
+            }
+
+            yield subscription.response(&service, &checkpoint)?;
+            next = sequence_number + 1;
+            reset(&mut heartbeat);
+        }
+    }
+}
+
+impl Subscription {
+    fn new(service: &RpcService, request: SubscribeCheckpointsRequest) -> Result<Self, RpcError> {
+        let read_mask = {
+            let read_mask = request
+                .read_mask
+                .unwrap_or_else(|| FieldMask::from_str(READ_MASK_DEFAULT));
+            read_mask.validate::<Checkpoint>().map_err(|path| {
+                FieldViolation::new("read_mask")
+                    .with_description(format!("invalid read_mask path: {path}"))
+                    .with_reason(ErrorReason::FieldInvalid)
+            })?;
+            FieldMaskTree::from(read_mask)
+        };
+
+        if let Some(cursor) = request.start_cursor {
+            let reader = service.reader.inner();
+            let lowest = reader.get_lowest_available_checkpoint()?;
+            let latest = reader.get_latest_checkpoint_sequence_number()?;
+            if cursor < lowest || cursor > latest + 1 {
+                return Err(FieldViolation::new("start_cursor")
+                    .with_description(format!(
+                        "start_cursor {cursor} is outside the available range [{lowest}, {}]",
+                        latest + 1,
+                    ))
+                    .with_reason(ErrorReason::FieldInvalid)
+                    .into());
+            }
+        }
+
+        let filters = request
+            .filters
+            .into_iter()
+            .enumerate()
+            .map(|(i, filter)| {
+                transaction_filter_from_proto(filter.filter).map_err(|description| {
+                    FieldViolation::new_at("filters", i)
+                        .with_description(description)
+                        .with_reason(ErrorReason::FieldInvalid)
+                })
+            })
+            .collect::<Result<_, _>>()?;
+
+        let heartbeat_interval = match request.heartbeat_interval_ms {
+            None => None,
+            Some(0) => {
+                return Err(FieldViolation::new("heartbeat_interval_ms")
+                    .with_description("heartbeat_interval_ms must be positive")
+                    .with_reason(ErrorReason::FieldInvalid)
+                    .into());
+            }
+            Some(ms) => Some(Duration::from_millis(ms.into())),
+        };
+
+        Ok(Self {
+            read_mask,
+            start_cursor: request.start_cursor,
+            filters,
+            heartbeat_interval,
+        })
+    }
+
+    /// Whether `tx` should be included in the stream. Transactions are included if there are no
+    /// filters, or if they match any of the filters.
+    fn includes(&self, tx: &ExecutedTransaction, objects: &ObjectSetData) -> bool {
+        self.filters.is_empty()
+            || self
+                .filters
+                .iter()
+                .any(|f| transaction_matches(f, tx, objects))
+    }
+
+    fn response(
+        &self,
+        service: &RpcService,
+        checkpoint: &CheckpointData,
+    ) -> Result<SubscribeCheckpointsResponse, RpcError> {
+        let read_mask = &self.read_mask;
+        let summary = checkpoint.summary.data();
+        let sequence_number = summary.sequence_number;
+        let timestamp_ms = summary.timestamp_ms;
+
+        let mut message = Checkpoint::default();
+        message.merge(summary, read_mask);
+        message.merge(checkpoint.summary.auth_sig().clone(), read_mask);
+
+        if read_mask.contains(Checkpoint::CONTENTS_FIELD.name) {
+            message.merge(checkpoint.contents.clone(), read_mask);
+        }
+
+        let transactions: Vec<_> = checkpoint
+            .transactions
+            .iter()
+            .filter(|tx| self.includes(tx, &checkpoint.object_set))
+            .collect();
+
+        if let Some(submask) = read_mask
+            .subtree(Checkpoint::OBJECTS_FIELD)
+            .and_then(|submask| submask.subtree(ObjectSet::OBJECTS_FIELD))
+        {
+            // Only keep the objects that the included transactions touched.
+            let ids: Option<BTreeSet<_>> = (!self.filters.is_empty()).then(|| {
+                transactions
+                    .iter()
+                    .flat_map(|tx| touched_object_ids(tx))
+                    .collect()
+            });
+
+            let set = checkpoint
+                .object_set
+                .iter()
+                .filter(|o| ids.as_ref().is_none_or(|ids| ids.contains(&o.id())))
+                .map(|o| sui_rpc::proto::sui::rpc::v2::Object::merge_from(o, &submask))
+                .collect();
+            message.objects = Some(ObjectSet::default().with_objects(set));
+        }
+
+        if let Some(submask) = read_mask.subtree(Checkpoint::TRANSACTIONS_FIELD.name) {
+            message.transactions = transactions
+                .into_iter()
+                .map(|t| {
+                    executed_transaction_to_proto(
+                        service,
+                        t,
+                        sequence_number,
+                        timestamp_ms,
+                        &submask,
+                    )
+                })
+                .collect();
+        }
+
+        let mut response = SubscribeCheckpointsResponse::default();
+        response.cursor = Some(sequence_number);
+        response.checkpoint = Some(message);
+        response.latest_checkpoint = Some(latest_checkpoint(service)?);
+        Ok(response)
+    }
+
+    /// A response without a checkpoint, sent while waiting for checkpoint `next`.
+    fn heartbeat(
+        &self,
+        service: &RpcService,
+        next: u64,
+    ) -> Result<SubscribeCheckpointsResponse, RpcError> {
+        let mut response = SubscribeCheckpointsResponse::default();
+        response.cursor = next.checked_sub(1);
+        response.latest_checkpoint = Some(latest_checkpoint(service)?);
+        Ok(response)
+    }
+}
+
+/// Parse a `TransactionFilter` (see the proto definition), describing what is wrong with it if it
+/// is invalid.
+pub(crate) fn transaction_filter_from_proto(
+    filter: Option<Filter>,
+) -> Result<TransactionIndexFilter, String> {
+    let address =
+        |a: &str| SuiAddress::from_str(a).map_err(|e| format!("invalid address {a:?}: {e}"));
+
+    Ok(match filter {
+        None => return Err("filter is required".to_owned()),
+        Some(Filter::Sender(a)) => TransactionIndexFilter::Sender(address(&a)?),
+        Some(Filter::AffectedAddress(a)) => TransactionIndexFilter::AffectedAddress(address(&a)?),
+        Some(Filter::MoveCall(MoveCallFilter {
+            package,
+            module,
+            function,
+        })) => {
+            let package = package.ok_or("move_call.package is required")?;
+            let package = ObjectID::from_str(&package)
+                .map_err(|e| format!("invalid package {package:?}: {e}"))?;
+            if module.is_none() && function.is_some() {
+                return Err("move_call.function requires move_call.module".to_owned());
+            }
+
+            TransactionIndexFilter::MoveCall {
+                package,
+                module,
+                function,
+            }
+        }
+        Some(Filter::EventType(t)) => TransactionIndexFilter::EventType(
+            sui_types::parse_sui_struct_tag(&t)
+                .map_err(|e| format!("invalid event_type {t:?}: {e}"))?,
+        ),
+        Some(Filter::AffectedObject(id)) => TransactionIndexFilter::AffectedObject(
+            ObjectID::from_str(&id).map_err(|e| format!("invalid object id {id:?}: {e}"))?,
+        ),
+    })
+}
+
+/// Whether the transaction `tx`, whose objects are in `objects`, matches `filter`.
+fn transaction_matches(
+    filter: &TransactionIndexFilter,
+    tx: &ExecutedTransaction,
+    objects: &ObjectSetData,
+) -> bool {
+    match filter {
+        TransactionIndexFilter::Sender(address) => tx.transaction.sender() == *address,
+
+        TransactionIndexFilter::AffectedAddress(address) => {
+            tx.transaction.sender() == *address
+                || tx.transaction.gas_owner() == *address
+                || tx
+                    .input_objects(objects)
+                    .chain(tx.output_objects(objects))
+                    .any(|o| match o.owner() {
+                        Owner::AddressOwner(owner) | Owner::ConsensusAddressOwner { owner, .. } => {
+                            owner == address
+                        }
+                        _ => false,
+                    })
+        }
+
+        TransactionIndexFilter::AffectedObject(id) => {
+            tx.effects.object_changes().iter().any(|c| c.id == *id)
+        }
+
+        TransactionIndexFilter::MoveCall {
+            package,
+            module,
+            function,
+        } => tx.transaction.move_calls().into_iter().any(|(p, m, f)| {
+            p == package
+                && module.as_ref().is_none_or(|module| module == m)
+                && function.as_ref().is_none_or(|function| function == f)
+        }),
+
+        TransactionIndexFilter::EventType(type_) => tx.events.as_ref().is_some_and(|events| {
+            events.data.iter().any(|e| {
+                type_.address == e.type_.address
+                    && type_.module == e.type_.module
+                    && type_.name == e.type_.name
+                    && (type_.type_params.is_empty() || type_.type_params == e.type_.type_params)
+            })
+        }),
+    }
+}
+
+async fn register(
+    handle: &SubscriptionServiceHandle,
+) -> Result<mpsc::Receiver<Arc<CheckpointData>>, tonic::Status> {
+    handle
+        .register_subscription()
+        .await
+        .ok_or_else(|| tonic::Status::unavailable("too many existing subscriptions"))
+}
+
+/// Load checkpoint `sequence_number` and all its data from the store. The store is read on a
+/// blocking thread, so that catching up does not hold up the runtime.
+async fn stored_checkpoint(
+    reader: &Arc<dyn RpcStateReader>,
+    sequence_number: u64,
+) -> Result<CheckpointData, RpcError> {
+    let reader = reader.clone();
+    tokio::task::spawn_blocking(move || -> Result<CheckpointData, RpcError> {
+        let summary = reader
+            .get_checkpoint_by_sequence_number(sequence_number)
+            .ok_or(CheckpointNotFoundError::sequence_number(sequence_number))?;
+        let contents = reader
+            .get_checkpoint_contents_by_sequence_number(sequence_number)
+            .ok_or(CheckpointNotFoundError::sequence_number(sequence_number))?;
+        Ok(reader.get_checkpoint_data(summary, contents)?)
+    })
+    .await
+    .map_err(|e| anyhow::anyhow!("failed to load checkpoint {sequence_number}: {e}"))?
+}
+
+fn latest_checkpoint(service: &RpcService) -> Result<u64, RpcError> {
+    Ok(service
+        .reader
+        .inner()
+        .get_latest_checkpoint_sequence_number()?)
+}
+
+/// IDs of the objects that `tx` read or wrote.
+fn touched_object_ids(tx: &ExecutedTransaction) -> impl Iterator<Item = ObjectID> + '_ {
+    let inputs = tx.transaction.input_objects().unwrap_or_default();
+    tx.effects
+        .object_changes()
+        .into_iter()
+        .map(|c| c.id)
+        .chain(tx.unchanged_loaded_runtime_objects.iter().map(|k| k.0))
+        .chain(inputs.into_iter().map(|i| i.object_id()))
+}
+
+/// Wait for the next heartbeat, or forever if heartbeats are disabled.
+async fn tick(heartbeat: &mut Option<Interval>) {
+    match heartbeat {
+        Some(interval) => {
+            interval.tick().await;
+        }
+        None => std::future::pending().await,
+    }
+}
+
+fn reset(heartbeat: &mut Option<Interval>) {
+    if let Some(interval) = heartbeat {
+        interval.reset();
+    }
+}
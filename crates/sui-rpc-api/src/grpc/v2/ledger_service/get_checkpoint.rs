@@ -109,51 +109,15 @@ pub fn get_checkpoint(
             if let Some(submask) = read_mask.subtree(Checkpoint::TRANSACTIONS_FIELD.name) {
                 checkpoint.transactions = checkpoint_data
                     .transactions
-                    .into_iter()
+                    .iter()
                     .map(|t| {
-                        let balance_changes = submask
-                            .contains(ExecutedTransaction::BALANCE_CHANGES_FIELD)
-                            .then(|| {
-                                service
-                                    .reader
-                                    .get_transaction_info(&t.transaction.digest())
-                                    .map(|info| {
-                                        info.balance_changes
-                                            .into_iter()
-                                            .map(sui_rpc::proto::sui::rpc::v2::BalanceChange::from)
-                                            .collect::<Vec<_>>()
-                                    })
-                            })
-                            .flatten()
-                            .unwrap_or_default();
-                        let mut transaction = ExecutedTransaction::merge_from(&t, &submask);
-                        transaction.checkpoint = submask
-                            .contains(ExecutedTransaction::CHECKPOINT_FIELD)
-                            .then_some(sequence_number);
-                        transaction.timestamp = submask
-                            .contains(ExecutedTransaction::TIMESTAMP_FIELD)
-                            .then(|| sui_rpc::proto::timestamp_ms_to_proto(timestamp_ms));
-                        transaction.balance_changes = balance_changes;
-
-                        if let Some(events_mask) =
-                            submask.subtree(ExecutedTransaction::EVENTS_FIELD.name)
-                            && let Some(event_mask) =
-                                events_mask.subtree(TransactionEvents::EVENTS_FIELD.name)
-                            && event_mask.contains(Event::JSON_FIELD.name)
-                            && let Some(events) = transaction.events.as_mut()
-                            && let Some(sdk_events) = &t.events
-                        {
-                            for (message, event) in events.events.iter_mut().zip(&sdk_events.data) {
-                                message.json = crate::grpc::v2::render_json(
-                                    service,
-                                    &event.type_,
-                                    &event.contents,
-                                )
-                                .map(Box::new);
-                            }
-                        }
-
-                        transaction
+                        executed_transaction_to_proto(
+                            service,
+                            t,
+                            sequence_number,
+                            timestamp_ms,
+                            &submask,
+                        )
                     })
                     .collect();
             }
@@ -162,3 +126,51 @@ pub fn get_checkpoint(
 
     Ok(GetCheckpointResponse::new(checkpoint))
 }
+
+/// Convert a transaction from checkpoint `sequence_number` into its proto representation,
+/// according to `submask` (a mask relative to `ExecutedTransaction`).
+pub(crate) fn executed_transaction_to_proto(
+    service: &RpcService,
+    t: &sui_types::full_checkpoint_content::ExecutedTransaction,
+    sequence_number: u64,
+    timestamp_ms: u64,
+    submask: &FieldMaskTree,
+) -> ExecutedTransaction {
+    let balance_changes = submask
+        .contains(ExecutedTransaction::BALANCE_CHANGES_FIELD)
+        .then(|| {
+            service
+                .reader
+                .get_transaction_info(&t.transaction.digest())
+                .map(|info| {
+                    info.balance_changes
+                        .into_iter()
+                        .map(sui_rpc::proto::sui::rpc::v2::BalanceChange::from)
+                        .collect::<Vec<_>>()
+                })
+        })
+        .flatten()
+        .unwrap_or_default();
+    let mut transaction = ExecutedTransaction::merge_from(t, submask);
+    transaction.checkpoint = submask
+        .contains(ExecutedTransaction::CHECKPOINT_FIELD)
+        .then_some(sequence_number);
+    transaction.timestamp = submask
+        .contains(ExecutedTransaction::TIMESTAMP_FIELD)
+        .then(|| sui_rpc::proto::timestamp_ms_to_proto(timestamp_ms));
+    transaction.balance_changes = balance_changes;
+
+    if let Some(events_mask) = submask.subtree(ExecutedTransaction::EVENTS_FIELD.name)
+        && let Some(event_mask) = events_mask.subtree(TransactionEvents::EVENTS_FIELD.name)
+        && event_mask.contains(Event::JSON_FIELD.name)
+        && let Some(events) = transaction.events.as_mut()
+        && let Some(sdk_events) = &t.events
+    {
+        for (message, event) in events.events.iter_mut().zip(&sdk_events.data) {
+            message.json =
+                crate::grpc::v2::render_json(service, &event.type_, &event.contents).map(Box::new);
+        }
+    }
+
+    transaction
+}
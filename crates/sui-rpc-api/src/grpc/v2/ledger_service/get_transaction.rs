@@ -111,7 +111,7 @@ pub fn batch_get_transactions(
     Ok(BatchGetTransactionsResponse::new(transactions))
 }
 
-fn transaction_to_response(
+pub(crate) fn transaction_to_response(
     service: &RpcService,
     source: crate::reader::TransactionRead,
     mask: &FieldMaskTree,
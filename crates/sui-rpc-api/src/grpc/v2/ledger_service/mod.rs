@@ -26,6 +26,7 @@ mod get_transaction;
 pub use get_epoch::protocol_config_to_proto;
 pub use get_object::validate_get_object_requests;
 pub(crate) use get_transaction::render_clever_error;
+pub(crate) use get_transaction::transaction_to_response;
 
 #[tonic::async_trait]
 impl LedgerService for RpcService {
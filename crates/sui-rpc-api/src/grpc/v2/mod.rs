@@ -10,7 +10,7 @@ mod subscription_service;
 mod transaction_execution_service;
 pub use ledger_service::protocol_config_to_proto;
 
-fn render_json(
+pub(crate) fn render_json(
     service: &crate::RpcService,
     struct_tag: &move_core_types::language_storage::StructTag,
     contents: &[u8],
@@ -139,6 +139,14 @@ impl RpcService {
                 crate::grpc::alpha::proof_service_proto::proof_service_server::ProofServiceServer::new(
                     crate::grpc::alpha::proof_service::ProofServiceImpl::new(self.clone()),
                 );
+            let gas_price_service_alpha =
+                crate::grpc::alpha::gas_price_service_proto::gas_price_service_server::GasPriceServiceServer::new(
+                    crate::grpc::alpha::gas_price_service::GasPriceServiceImpl::new(self.clone()),
+                );
+            let ledger_service_alpha =
+                crate::grpc::alpha::ledger_service_proto::ledger_service_server::LedgerServiceServer::new(
+                    crate::grpc::alpha::ledger_service::LedgerServiceImpl::new(self.clone()),
+                );
 
             let (health_reporter, health_service) = tonic_health::server::health_reporter();
 
@@ -183,6 +191,8 @@ impl RpcService {
                 service_name(&name_service),
                 service_name(&event_service_alpha),
                 service_name(&proof_service_alpha),
+                service_name(&gas_price_service_alpha),
+                service_name(&ledger_service_alpha),
                 service_name(&reflection_v1),
                 service_name(&reflection_v1alpha),
             ] {
@@ -202,6 +212,8 @@ impl RpcService {
                 // alpha
                 .add_service(event_service_alpha)
                 .add_service(proof_service_alpha)
+                .add_service(gas_price_service_alpha)
+                .add_service(ledger_service_alpha)
                 // Reflection
                 .add_service(reflection_v1)
                 .add_service(reflection_v1alpha);
@@ -216,7 +228,20 @@ sui_rpc::proto::sui::rpc::v2::subscription_service_server::SubscriptionServiceSe
                     )
                     .await;
 
-                services = services.add_service(subscription_service);
+                let subscription_service_alpha =
+                    crate::grpc::alpha::subscription_service_proto::subscription_service_server::SubscriptionServiceServer::new(
+                        crate::grpc::alpha::subscription_service::SubscriptionServiceImpl::new(self.clone()),
+                    );
+                health_reporter
+                    .set_service_status(
+                        service_name(&subscription_service_alpha),
+                        tonic_health::ServingStatus::Serving,
+                    )
+                    .await;
+
+                services = services
+                    .add_service(subscription_service)
+                    .add_service(subscription_service_alpha);
             }
 
             services.add_service(health_service).into_router()
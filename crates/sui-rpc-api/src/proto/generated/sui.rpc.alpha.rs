@@ -366,49 +366,104 @@ pub mod event_service_server {
         const NAME: &'static str = SERVICE_NAME;
     }
 }
-/// Object Checkpoint State inclusion proof.
+/// Request for a gas price suggestion. At least one of `transaction` and `object_ids` must be set.
 #[non_exhaustive]
 #[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
-pub struct OcsInclusionProof {
-    /// BCS-encoded merkle proof nodes.
-    #[prost(bytes = "vec", optional, tag = "1")]
-    pub merkle_proof: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
-    /// Leaf index in the merkle tree.
+pub struct SuggestGasPriceRequest {
+    /// Optional. BCS-encoded `TransactionData` of the transaction to suggest a gas price for. The
+    /// shared objects it accesses mutably are taken into account.
+    #[prost(message, optional, tag = "1")]
+    pub transaction: ::core::option::Option<::sui_rpc::proto::sui::rpc::v2::Bcs>,
+    /// Optional. IDs of shared objects to take into account, in addition to the ones accessed by
+    /// `transaction`.
+    #[prost(string, repeated, tag = "2")]
+    pub object_ids: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+/// A gas price suggestion, and the congestion information it is based on.
+#[non_exhaustive]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SuggestGasPriceResponse {
+    /// The suggested gas price.
+    #[prost(uint64, optional, tag = "1")]
+    pub gas_price: ::core::option::Option<u64>,
+    /// The reference gas price of the current epoch.
     #[prost(uint64, optional, tag = "2")]
-    pub leaf_index: ::core::option::Option<u64>,
-    /// Tree root digest (32 bytes).
-    #[prost(bytes = "vec", optional, tag = "3")]
-    pub tree_root: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
+    pub reference_gas_price: ::core::option::Option<u64>,
+    /// How likely a transaction paying `gas_price` is to avoid being cancelled. This is the lowest
+    /// confidence across all the objects.
+    #[prost(enumeration = "GasPriceConfidence", optional, tag = "3")]
+    pub confidence: ::core::option::Option<i32>,
+    /// Congestion information for each of the objects that were taken into account.
+    #[prost(message, repeated, tag = "4")]
+    pub objects: ::prost::alloc::vec::Vec<ObjectCongestion>,
 }
-/// Request for object inclusion proof at a checkpoint.
+/// Recent congestion on a single shared object.
 #[non_exhaustive]
 #[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
-pub struct GetObjectInclusionProofRequest {
-    /// Required. Object ID to get inclusion proof for.
+pub struct ObjectCongestion {
+    /// ID of the shared object.
     #[prost(string, optional, tag = "1")]
     pub object_id: ::core::option::Option<::prost::alloc::string::String>,
-    /// Required. Checkpoint sequence number.
+    /// The gas price needed to access this object mutably, if it is congested.
     #[prost(uint64, optional, tag = "2")]
-    pub checkpoint: ::core::option::Option<u64>,
+    pub suggested_gas_price: ::core::option::Option<u64>,
+    /// How likely a transaction paying `suggested_gas_price` is to avoid being cancelled on this
+    /// object.
+    #[prost(enumeration = "GasPriceConfidence", optional, tag = "3")]
+    pub confidence: ::core::option::Option<i32>,
+    /// Timestamp of the most recent checkpoint with a transaction cancelled on this object.
+    #[prost(message, optional, tag = "4")]
+    pub last_cancellation: ::core::option::Option<::prost_types::Timestamp>,
+    /// Highest gas price of the transactions cancelled in that checkpoint.
+    #[prost(uint64, optional, tag = "5")]
+    pub highest_cancelled_gas_price: ::core::option::Option<u64>,
+    /// Timestamp of the most recent checkpoint with a transaction that accessed this object mutably
+    /// while it was congested.
+    #[prost(message, optional, tag = "6")]
+    pub last_success: ::core::option::Option<::prost_types::Timestamp>,
+    /// Lowest gas price of the transactions executed on this object in that checkpoint.
+    #[prost(uint64, optional, tag = "7")]
+    pub lowest_executed_gas_price: ::core::option::Option<u64>,
 }
-/// Response containing object inclusion proof and object data.
 #[non_exhaustive]
-#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
-pub struct GetObjectInclusionProofResponse {
-    /// Object reference being proven (object_id, version, digest).
-    #[prost(message, optional, tag = "1")]
-    pub object_ref: ::core::option::Option<
-        ::sui_rpc::proto::sui::rpc::v2::ObjectReference,
-    >,
-    /// Object inclusion proof.
-    #[prost(message, optional, tag = "2")]
-    pub inclusion_proof: ::core::option::Option<OcsInclusionProof>,
-    /// BCS-encoded object data.
-    #[prost(bytes = "vec", optional, tag = "3")]
-    pub object_data: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum GasPriceConfidence {
+    Unknown = 0,
+    /// The object has only seen cancellations recently, so the price needed to get through is not
+    /// known. The suggestion is a lower bound.
+    Low = 1,
+    /// The object has seen both cancellations and successful transactions recently.
+    Medium = 2,
+    /// The object is not congested.
+    High = 3,
+}
+impl GasPriceConfidence {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::Unknown => "GAS_PRICE_CONFIDENCE_UNKNOWN",
+            Self::Low => "GAS_PRICE_CONFIDENCE_LOW",
+            Self::Medium => "GAS_PRICE_CONFIDENCE_MEDIUM",
+            Self::High => "GAS_PRICE_CONFIDENCE_HIGH",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "GAS_PRICE_CONFIDENCE_UNKNOWN" => Some(Self::Unknown),
+            "GAS_PRICE_CONFIDENCE_LOW" => Some(Self::Low),
+            "GAS_PRICE_CONFIDENCE_MEDIUM" => Some(Self::Medium),
+            "GAS_PRICE_CONFIDENCE_HIGH" => Some(Self::High),
+            _ => None,
+        }
+    }
 }
 /// Generated client implementations.
-pub mod proof_service_client {
+pub mod gas_price_service_client {
     #![allow(
         unused_variables,
         dead_code,
@@ -418,12 +473,12 @@ pub mod proof_service_client {
     )]
     use tonic::codegen::*;
     use tonic::codegen::http::Uri;
-    /// ProofService provides cryptographic proofs for blockchain objects.
+    /// GasPriceService suggests gas prices based on recent congestion on shared objects.
     #[derive(Debug, Clone)]
-    pub struct ProofServiceClient<T> {
+    pub struct GasPriceServiceClient<T> {
         inner: tonic::client::Grpc<T>,
     }
-    impl ProofServiceClient<tonic::transport::Channel> {
+    impl GasPriceServiceClient<tonic::transport::Channel> {
         /// Attempt to create a new client by connecting to a given endpoint.
         pub async fn connect<D>(dst: D) -> Result<Self, tonic::transport::Error>
         where
@@ -434,7 +489,7 @@ pub mod proof_service_client {
             Ok(Self::new(conn))
         }
     }
-    impl<T> ProofServiceClient<T>
+    impl<T> GasPriceServiceClient<T>
     where
         T: tonic::client::GrpcService<tonic::body::Body>,
         T::Error: Into<StdError>,
@@ -452,7 +507,7 @@ pub mod proof_service_client {
         pub fn with_interceptor<F>(
             inner: T,
             interceptor: F,
-        ) -> ProofServiceClient<InterceptedService<T, F>>
+        ) -> GasPriceServiceClient<InterceptedService<T, F>>
         where
             F: tonic::service::Interceptor,
             T::ResponseBody: Default,
@@ -466,7 +521,7 @@ pub mod proof_service_client {
                 http::Request<tonic::body::Body>,
             >>::Error: Into<StdError> + std::marker::Send + std::marker::Sync,
         {
-            ProofServiceClient::new(InterceptedService::new(inner, interceptor))
+            GasPriceServiceClient::new(InterceptedService::new(inner, interceptor))
         }
         /// Compress requests with the given encoding.
         ///
@@ -499,12 +554,17 @@ pub mod proof_service_client {
             self.inner = self.inner.max_encoding_message_size(limit);
             self
         }
-        /// Returns an inclusion proof if a specified object id was written in a specified checkpoint.
-        pub async fn get_object_inclusion_proof(
+        /// Suggest a gas price for a transaction that accesses a set of shared objects mutably.
+        ///
+        /// Transactions that access a congested shared object mutably are ordered by gas price, and
+        /// those that do not fit in a checkpoint are cancelled. The suggestion is based on the prices of
+        /// the transactions that were recently executed and cancelled on each object, and is never below
+        /// the reference gas price.
+        pub async fn suggest_gas_price(
             &mut self,
-            request: impl tonic::IntoRequest<super::GetObjectInclusionProofRequest>,
+            request: impl tonic::IntoRequest<super::SuggestGasPriceRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::GetObjectInclusionProofResponse>,
+            tonic::Response<super::SuggestGasPriceResponse>,
             tonic::Status,
         > {
             self.inner
@@ -517,14 +577,14 @@ pub mod proof_service_client {
                 })?;
             let codec = tonic_prost::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/sui.rpc.alpha.ProofService/GetObjectInclusionProof",
+                "/sui.rpc.alpha.GasPriceService/SuggestGasPrice",
             );
             let mut req = request.into_request();
             req.extensions_mut()
                 .insert(
                     GrpcMethod::new(
-                        "sui.rpc.alpha.ProofService",
-                        "GetObjectInclusionProof",
+                        "sui.rpc.alpha.GasPriceService",
+                        "SuggestGasPrice",
                     ),
                 );
             self.inner.unary(req, path, codec).await
@@ -532,7 +592,7 @@ pub mod proof_service_client {
     }
 }
 /// Generated server implementations.
-pub mod proof_service_server {
+pub mod gas_price_service_server {
     #![allow(
         unused_variables,
         dead_code,
@@ -541,28 +601,33 @@ pub mod proof_service_server {
         clippy::let_unit_value,
     )]
     use tonic::codegen::*;
-    /// Generated trait containing gRPC methods that should be implemented for use with ProofServiceServer.
+    /// Generated trait containing gRPC methods that should be implemented for use with GasPriceServiceServer.
     #[async_trait]
-    pub trait ProofService: std::marker::Send + std::marker::Sync + 'static {
-        /// Returns an inclusion proof if a specified object id was written in a specified checkpoint.
-        async fn get_object_inclusion_proof(
+    pub trait GasPriceService: std::marker::Send + std::marker::Sync + 'static {
+        /// Suggest a gas price for a transaction that accesses a set of shared objects mutably.
+        ///
+        /// Transactions that access a congested shared object mutably are ordered by gas price, and
+        /// those that do not fit in a checkpoint are cancelled. The suggestion is based on the prices of
+        /// the transactions that were recently executed and cancelled on each object, and is never below
+        /// the reference gas price.
+        async fn suggest_gas_price(
             &self,
-            request: tonic::Request<super::GetObjectInclusionProofRequest>,
+            request: tonic::Request<super::SuggestGasPriceRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::GetObjectInclusionProofResponse>,
+            tonic::Response<super::SuggestGasPriceResponse>,
             tonic::Status,
         >;
     }
-    /// ProofService provides cryptographic proofs for blockchain objects.
+    /// GasPriceService suggests gas prices based on recent congestion on shared objects.
     #[derive(Debug)]
-    pub struct ProofServiceServer<T> {
+    pub struct GasPriceServiceServer<T> {
         inner: Arc<T>,
         accept_compression_encodings: EnabledCompressionEncodings,
         send_compression_encodings: EnabledCompressionEncodings,
         max_decoding_message_size: Option<usize>,
         max_encoding_message_size: Option<usize>,
     }
-    impl<T> ProofServiceServer<T> {
+    impl<T> GasPriceServiceServer<T> {
         pub fn new(inner: T) -> Self {
             Self::from_arc(Arc::new(inner))
         }
@@ -613,9 +678,9 @@ pub mod proof_service_server {
             self
         }
     }
-    impl<T, B> tonic::codegen::Service<http::Request<B>> for ProofServiceServer<T>
+    impl<T, B> tonic::codegen::Service<http::Request<B>> for GasPriceServiceServer<T>
     where
-        T: ProofService,
+        T: GasPriceService,
         B: Body + std::marker::Send + 'static,
         B::Error: Into<StdError> + std::marker::Send + 'static,
     {
@@ -630,14 +695,14 @@ pub mod proof_service_server {
         }
         fn call(&mut self, req: http::Request<B>) -> Self::Future {
             match req.uri().path() {
-                "/sui.rpc.alpha.ProofService/GetObjectInclusionProof" => {
+                "/sui.rpc.alpha.GasPriceService/SuggestGasPrice" => {
                     #[allow(non_camel_case_types)]
-                    struct GetObjectInclusionProofSvc<T: ProofService>(pub Arc<T>);
+                    struct SuggestGasPriceSvc<T: GasPriceService>(pub Arc<T>);
                     impl<
-                        T: ProofService,
-                    > tonic::server::UnaryService<super::GetObjectInclusionProofRequest>
-                    for GetObjectInclusionProofSvc<T> {
-                        type Response = super::GetObjectInclusionProofResponse;
+                        T: GasPriceService,
+                    > tonic::server::UnaryService<super::SuggestGasPriceRequest>
+                    for SuggestGasPriceSvc<T> {
+                        type Response = super::SuggestGasPriceResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
@@ -645,12 +710,12 @@ pub mod proof_service_server {
                         fn call(
                             &mut self,
                             request: tonic::Request<
-                                super::GetObjectInclusionProofRequest,
+                                super::SuggestGasPriceRequest,
                             >,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as ProofService>::get_object_inclusion_proof(
+                                <T as GasPriceService>::suggest_gas_price(
                                         &inner,
                                         request,
                                     )
@@ -665,7 +730,7 @@ pub mod proof_service_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = GetObjectInclusionProofSvc(inner);
+                        let method = SuggestGasPriceSvc(inner);
                         let codec = tonic_prost::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -703,7 +768,7 @@ pub mod proof_service_server {
             }
         }
     }
-    impl<T> Clone for ProofServiceServer<T> {
+    impl<T> Clone for GasPriceServiceServer<T> {
         fn clone(&self) -> Self {
             let inner = self.inner.clone();
             Self {
@@ -716,8 +781,1331 @@ pub mod proof_service_server {
         }
     }
     /// Generated gRPC service name
-    pub const SERVICE_NAME: &str = "sui.rpc.alpha.ProofService";
-    impl<T> tonic::server::NamedService for ProofServiceServer<T> {
+    pub const SERVICE_NAME: &str = "sui.rpc.alpha.GasPriceService";
+    impl<T> tonic::server::NamedService for GasPriceServiceServer<T> {
+        const NAME: &'static str = SERVICE_NAME;
+    }
+}
+/// Request to list the transactions that match a filter.
+#[non_exhaustive]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListTransactionsRequest {
+    /// Required. The condition transactions must match.
+    #[prost(message, optional, tag = "1")]
+    pub filter: ::core::option::Option<TransactionFilter>,
+    /// Optional. The maximum number of transactions to return. The service may return fewer than
+    /// this value. (default: 50, max: 1000)
+    #[prost(uint32, optional, tag = "2")]
+    pub page_size: ::core::option::Option<u32>,
+    /// Optional. A page token, received from a previous `ListTransactions` call. Provide this to
+    /// retrieve the subsequent page. When paginating, all other parameters provided to
+    /// `ListTransactions` must match the call that provided the page token.
+    #[prost(bytes = "vec", optional, tag = "3")]
+    pub page_token: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
+    /// Optional. Mask for specifying which parts of each transaction should be returned.
+    /// (default: `digest`)
+    #[prost(message, optional, tag = "4")]
+    pub read_mask: ::core::option::Option<::prost_types::FieldMask>,
+    /// Optional. If set, transactions are listed from the most recent to the oldest.
+    #[prost(bool, optional, tag = "5")]
+    pub descending: ::core::option::Option<bool>,
+}
+/// A page of transactions matching a filter.
+#[non_exhaustive]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListTransactionsResponse {
+    /// The matching transactions, in checkpoint order (or reverse checkpoint order, if `descending`
+    /// was set).
+    #[prost(message, repeated, tag = "1")]
+    pub transactions: ::prost::alloc::vec::Vec<
+        ::sui_rpc::proto::sui::rpc::v2::ExecutedTransaction,
+    >,
+    /// A token, which can be sent as `page_token` to retrieve the next page. If this field is
+    /// omitted, there are no subsequent pages.
+    #[prost(bytes = "vec", optional, tag = "2")]
+    pub next_page_token: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
+}
+/// Request to list the events that match a filter.
+#[non_exhaustive]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QueryEventsRequest {
+    /// Required. The condition events must match.
+    #[prost(message, optional, tag = "1")]
+    pub filter: ::core::option::Option<EventFilter>,
+    /// Optional. The maximum number of events to return. The service may return fewer than this
+    /// value. (default: 50, max: 1000)
+    #[prost(uint32, optional, tag = "2")]
+    pub page_size: ::core::option::Option<u32>,
+    /// Optional. A page token, received from a previous `QueryEvents` call. Provide this to
+    /// retrieve the subsequent page. When paginating, all other parameters provided to
+    /// `QueryEvents` must match the call that provided the page token.
+    #[prost(bytes = "vec", optional, tag = "3")]
+    pub page_token: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
+    /// Optional. Mask for specifying which parts of each event should be returned.
+    /// (default: `package_id,module,sender,event_type`)
+    #[prost(message, optional, tag = "4")]
+    pub read_mask: ::core::option::Option<::prost_types::FieldMask>,
+    /// Optional. If set, events are listed from the most recent to the oldest.
+    #[prost(bool, optional, tag = "5")]
+    pub descending: ::core::option::Option<bool>,
+}
+/// A condition on a single event.
+#[non_exhaustive]
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct EventFilter {
+    #[prost(oneof = "event_filter::Filter", tags = "1, 2, 3")]
+    pub filter: ::core::option::Option<event_filter::Filter>,
+}
+/// Nested message and enum types in `EventFilter`.
+pub mod event_filter {
+    #[non_exhaustive]
+    #[derive(Clone, PartialEq, Eq, Hash, ::prost::Oneof)]
+    pub enum Filter {
+        /// The event was emitted by a transaction sent by this address.
+        #[prost(string, tag = "1")]
+        Sender(::prost::alloc::string::String),
+        /// The event was emitted by a module matching this filter.
+        #[prost(message, tag = "2")]
+        EmittingModule(super::MoveModuleFilter),
+        /// The event is of this type. If the type has no type parameters, events with any
+        /// instantiation of the type match.
+        #[prost(string, tag = "3")]
+        EventType(::prost::alloc::string::String),
+    }
+}
+/// Matches modules in a package, and optionally a single module within it.
+#[non_exhaustive]
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct MoveModuleFilter {
+    /// Required. ID of the package the module belongs to.
+    #[prost(string, optional, tag = "1")]
+    pub package: ::core::option::Option<::prost::alloc::string::String>,
+    /// Optional. Name of the module.
+    #[prost(string, optional, tag = "2")]
+    pub module: ::core::option::Option<::prost::alloc::string::String>,
+}
+/// A page of events matching a filter.
+#[non_exhaustive]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QueryEventsResponse {
+    /// The matching events, in checkpoint order (or reverse checkpoint order, if `descending` was
+    /// set).
+    #[prost(message, repeated, tag = "1")]
+    pub events: ::prost::alloc::vec::Vec<IndexedEvent>,
+    /// A token, which can be sent as `page_token` to retrieve the next page. If this field is
+    /// omitted, there are no subsequent pages.
+    #[prost(bytes = "vec", optional, tag = "2")]
+    pub next_page_token: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
+}
+/// An event, and where it was emitted.
+#[non_exhaustive]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct IndexedEvent {
+    /// Sequence number of the checkpoint the event was emitted in.
+    #[prost(uint64, optional, tag = "1")]
+    pub checkpoint: ::core::option::Option<u64>,
+    /// Index of the transaction that emitted the event, within its checkpoint.
+    #[prost(uint32, optional, tag = "2")]
+    pub transaction_idx: ::core::option::Option<u32>,
+    /// Index of the event, within its transaction's events.
+    #[prost(uint32, optional, tag = "3")]
+    pub event_idx: ::core::option::Option<u32>,
+    /// Digest of the transaction that emitted the event.
+    #[prost(string, optional, tag = "4")]
+    pub transaction_digest: ::core::option::Option<::prost::alloc::string::String>,
+    /// The event, masked according to the request.
+    #[prost(message, optional, tag = "5")]
+    pub event: ::core::option::Option<::sui_rpc::proto::sui::rpc::v2::Event>,
+}
+/// Generated client implementations.
+pub mod ledger_service_client {
+    #![allow(
+        unused_variables,
+        dead_code,
+        missing_docs,
+        clippy::wildcard_imports,
+        clippy::let_unit_value,
+    )]
+    use tonic::codegen::*;
+    use tonic::codegen::http::Uri;
+    /// LedgerService lists transactions and events by the conditions they match, using indexes
+    /// maintained by the node.
+    ///
+    /// Requires the node to have `transaction-and-event-indexing` enabled. Only transactions and
+    /// events from checkpoints that have not been pruned are returned.
+    #[derive(Debug, Clone)]
+    pub struct LedgerServiceClient<T> {
+        inner: tonic::client::Grpc<T>,
+    }
+    impl LedgerServiceClient<tonic::transport::Channel> {
+        /// Attempt to create a new client by connecting to a given endpoint.
+        pub async fn connect<D>(dst: D) -> Result<Self, tonic::transport::Error>
+        where
+            D: TryInto<tonic::transport::Endpoint>,
+            D::Error: Into<StdError>,
+        {
+            let conn = tonic::transport::Endpoint::new(dst)?.connect().await?;
+            Ok(Self::new(conn))
+        }
+    }
+    impl<T> LedgerServiceClient<T>
+    where
+        T: tonic::client::GrpcService<tonic::body::Body>,
+        T::Error: Into<StdError>,
+        T::ResponseBody: Body<Data = Bytes> + std::marker::Send + 'static,
+        <T::ResponseBody as Body>::Error: Into<StdError> + std::marker::Send,
+    {
+        pub fn new(inner: T) -> Self {
+            let inner = tonic::client::Grpc::new(inner);
+            Self { inner }
+        }
+        pub fn with_origin(inner: T, origin: Uri) -> Self {
+            let inner = tonic::client::Grpc::with_origin(inner, origin);
+            Self { inner }
+        }
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> LedgerServiceClient<InterceptedService<T, F>>
+        where
+            F: tonic::service::Interceptor,
+            T::ResponseBody: Default,
+            T: tonic::codegen::Service<
+                http::Request<tonic::body::Body>,
+                Response = http::Response<
+                    <T as tonic::client::GrpcService<tonic::body::Body>>::ResponseBody,
+                >,
+            >,
+            <T as tonic::codegen::Service<
+                http::Request<tonic::body::Body>,
+            >>::Error: Into<StdError> + std::marker::Send + std::marker::Sync,
+        {
+            LedgerServiceClient::new(InterceptedService::new(inner, interceptor))
+        }
+        /// Compress requests with the given encoding.
+        ///
+        /// This requires the server to support it otherwise it might respond with an
+        /// error.
+        #[must_use]
+        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.send_compressed(encoding);
+            self
+        }
+        /// Enable decompressing responses.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.accept_compressed(encoding);
+            self
+        }
+        /// Limits the maximum size of a decoded message.
+        ///
+        /// Default: `4MB`
+        #[must_use]
+        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
+            self.inner = self.inner.max_decoding_message_size(limit);
+            self
+        }
+        /// Limits the maximum size of an encoded message.
+        ///
+        /// Default: `usize::MAX`
+        #[must_use]
+        pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
+            self.inner = self.inner.max_encoding_message_size(limit);
+            self
+        }
+        /// List the transactions that match a filter, in checkpoint order.
+        pub async fn list_transactions(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ListTransactionsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListTransactionsResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic_prost::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/sui.rpc.alpha.LedgerService/ListTransactions",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "sui.rpc.alpha.LedgerService",
+                        "ListTransactions",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+        /// List the events that match a filter, in checkpoint order.
+        pub async fn query_events(
+            &mut self,
+            request: impl tonic::IntoRequest<super::QueryEventsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::QueryEventsResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic_prost::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/sui.rpc.alpha.LedgerService/QueryEvents",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "sui.rpc.alpha.LedgerService",
+                        "QueryEvents",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+    }
+}
+/// Generated server implementations.
+pub mod ledger_service_server {
+    #![allow(
+        unused_variables,
+        dead_code,
+        missing_docs,
+        clippy::wildcard_imports,
+        clippy::let_unit_value,
+    )]
+    use tonic::codegen::*;
+    /// Generated trait containing gRPC methods that should be implemented for use with LedgerServiceServer.
+    #[async_trait]
+    pub trait LedgerService: std::marker::Send + std::marker::Sync + 'static {
+        /// List the transactions that match a filter, in checkpoint order.
+        async fn list_transactions(
+            &self,
+            request: tonic::Request<super::ListTransactionsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListTransactionsResponse>,
+            tonic::Status,
+        >;
+        /// List the events that match a filter, in checkpoint order.
+        async fn query_events(
+            &self,
+            request: tonic::Request<super::QueryEventsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::QueryEventsResponse>,
+            tonic::Status,
+        >;
+    }
+    /// LedgerService lists transactions and events by the conditions they match, using indexes
+    /// maintained by the node.
+    ///
+    /// Requires the node to have `transaction-and-event-indexing` enabled. Only transactions and
+    /// events from checkpoints that have not been pruned are returned.
+    #[derive(Debug)]
+    pub struct LedgerServiceServer<T> {
+        inner: Arc<T>,
+        accept_compression_encodings: EnabledCompressionEncodings,
+        send_compression_encodings: EnabledCompressionEncodings,
+        max_decoding_message_size: Option<usize>,
+        max_encoding_message_size: Option<usize>,
+    }
+    impl<T> LedgerServiceServer<T> {
+        pub fn new(inner: T) -> Self {
+            Self::from_arc(Arc::new(inner))
+        }
+        pub fn from_arc(inner: Arc<T>) -> Self {
+            Self {
+                inner,
+                accept_compression_encodings: Default::default(),
+                send_compression_encodings: Default::default(),
+                max_decoding_message_size: None,
+                max_encoding_message_size: None,
+            }
+        }
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> InterceptedService<Self, F>
+        where
+            F: tonic::service::Interceptor,
+        {
+            InterceptedService::new(Self::new(inner), interceptor)
+        }
+        /// Enable decompressing requests with the given encoding.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.accept_compression_encodings.enable(encoding);
+            self
+        }
+        /// Compress responses with the given encoding, if the client supports it.
+        #[must_use]
+        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.send_compression_encodings.enable(encoding);
+            self
+        }
+        /// Limits the maximum size of a decoded message.
+        ///
+        /// Default: `4MB`
+        #[must_use]
+        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
+            self.max_decoding_message_size = Some(limit);
+            self
+        }
+        /// Limits the maximum size of an encoded message.
+        ///
+        /// Default: `usize::MAX`
+        #[must_use]
+        pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
+            self.max_encoding_message_size = Some(limit);
+            self
+        }
+    }
+    impl<T, B> tonic::codegen::Service<http::Request<B>> for LedgerServiceServer<T>
+    where
+        T: LedgerService,
+        B: Body + std::marker::Send + 'static,
+        B::Error: Into<StdError> + std::marker::Send + 'static,
+    {
+        type Response = http::Response<tonic::body::Body>;
+        type Error = std::convert::Infallible;
+        type Future = BoxFuture<Self::Response, Self::Error>;
+        fn poll_ready(
+            &mut self,
+            _cx: &mut Context<'_>,
+        ) -> Poll<std::result::Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+        fn call(&mut self, req: http::Request<B>) -> Self::Future {
+            match req.uri().path() {
+                "/sui.rpc.alpha.LedgerService/ListTransactions" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListTransactionsSvc<T: LedgerService>(pub Arc<T>);
+                    impl<
+                        T: LedgerService,
+                    > tonic::server::UnaryService<super::ListTransactionsRequest>
+                    for ListTransactionsSvc<T> {
+                        type Response = super::ListTransactionsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::ListTransactionsRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as LedgerService>::list_transactions(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ListTransactionsSvc(inner);
+                        let codec = tonic_prost::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/sui.rpc.alpha.LedgerService/QueryEvents" => {
+                    #[allow(non_camel_case_types)]
+                    struct QueryEventsSvc<T: LedgerService>(pub Arc<T>);
+                    impl<
+                        T: LedgerService,
+                    > tonic::server::UnaryService<super::QueryEventsRequest>
+                    for QueryEventsSvc<T> {
+                        type Response = super::QueryEventsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::QueryEventsRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as LedgerService>::query_events(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = QueryEventsSvc(inner);
+                        let codec = tonic_prost::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                _ => {
+                    Box::pin(async move {
+                        let mut response = http::Response::new(
+                            tonic::body::Body::default(),
+                        );
+                        let headers = response.headers_mut();
+                        headers
+                            .insert(
+                                tonic::Status::GRPC_STATUS,
+                                (tonic::Code::Unimplemented as i32).into(),
+                            );
+                        headers
+                            .insert(
+                                http::header::CONTENT_TYPE,
+                                tonic::metadata::GRPC_CONTENT_TYPE,
+                            );
+                        Ok(response)
+                    })
+                }
+            }
+        }
+    }
+    impl<T> Clone for LedgerServiceServer<T> {
+        fn clone(&self) -> Self {
+            let inner = self.inner.clone();
+            Self {
+                inner,
+                accept_compression_encodings: self.accept_compression_encodings,
+                send_compression_encodings: self.send_compression_encodings,
+                max_decoding_message_size: self.max_decoding_message_size,
+                max_encoding_message_size: self.max_encoding_message_size,
+            }
+        }
+    }
+    /// Generated gRPC service name
+    pub const SERVICE_NAME: &str = "sui.rpc.alpha.LedgerService";
+    impl<T> tonic::server::NamedService for LedgerServiceServer<T> {
+        const NAME: &'static str = SERVICE_NAME;
+    }
+}
+/// Object Checkpoint State inclusion proof.
+#[non_exhaustive]
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct OcsInclusionProof {
+    /// BCS-encoded merkle proof nodes.
+    #[prost(bytes = "vec", optional, tag = "1")]
+    pub merkle_proof: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
+    /// Leaf index in the merkle tree.
+    #[prost(uint64, optional, tag = "2")]
+    pub leaf_index: ::core::option::Option<u64>,
+    /// Tree root digest (32 bytes).
+    #[prost(bytes = "vec", optional, tag = "3")]
+    pub tree_root: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
+}
+/// Request for object inclusion proof at a checkpoint.
+#[non_exhaustive]
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct GetObjectInclusionProofRequest {
+    /// Required. Object ID to get inclusion proof for.
+    #[prost(string, optional, tag = "1")]
+    pub object_id: ::core::option::Option<::prost::alloc::string::String>,
+    /// Required. Checkpoint sequence number.
+    #[prost(uint64, optional, tag = "2")]
+    pub checkpoint: ::core::option::Option<u64>,
+}
+/// Response containing object inclusion proof and object data.
+#[non_exhaustive]
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct GetObjectInclusionProofResponse {
+    /// Object reference being proven (object_id, version, digest).
+    #[prost(message, optional, tag = "1")]
+    pub object_ref: ::core::option::Option<
+        ::sui_rpc::proto::sui::rpc::v2::ObjectReference,
+    >,
+    /// Object inclusion proof.
+    #[prost(message, optional, tag = "2")]
+    pub inclusion_proof: ::core::option::Option<OcsInclusionProof>,
+    /// BCS-encoded object data.
+    #[prost(bytes = "vec", optional, tag = "3")]
+    pub object_data: ::core::option::Option<::prost::alloc::vec::Vec<u8>>,
+}
+/// Generated client implementations.
+pub mod proof_service_client {
+    #![allow(
+        unused_variables,
+        dead_code,
+        missing_docs,
+        clippy::wildcard_imports,
+        clippy::let_unit_value,
+    )]
+    use tonic::codegen::*;
+    use tonic::codegen::http::Uri;
+    /// ProofService provides cryptographic proofs for blockchain objects.
+    #[derive(Debug, Clone)]
+    pub struct ProofServiceClient<T> {
+        inner: tonic::client::Grpc<T>,
+    }
+    impl ProofServiceClient<tonic::transport::Channel> {
+        /// Attempt to create a new client by connecting to a given endpoint.
+        pub async fn connect<D>(dst: D) -> Result<Self, tonic::transport::Error>
+        where
+            D: TryInto<tonic::transport::Endpoint>,
+            D::Error: Into<StdError>,
+        {
+            let conn = tonic::transport::Endpoint::new(dst)?.connect().await?;
+            Ok(Self::new(conn))
+        }
+    }
+    impl<T> ProofServiceClient<T>
+    where
+        T: tonic::client::GrpcService<tonic::body::Body>,
+        T::Error: Into<StdError>,
+        T::ResponseBody: Body<Data = Bytes> + std::marker::Send + 'static,
+        <T::ResponseBody as Body>::Error: Into<StdError> + std::marker::Send,
+    {
+        pub fn new(inner: T) -> Self {
+            let inner = tonic::client::Grpc::new(inner);
+            Self { inner }
+        }
+        pub fn with_origin(inner: T, origin: Uri) -> Self {
+            let inner = tonic::client::Grpc::with_origin(inner, origin);
+            Self { inner }
+        }
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> ProofServiceClient<InterceptedService<T, F>>
+        where
+            F: tonic::service::Interceptor,
+            T::ResponseBody: Default,
+            T: tonic::codegen::Service<
+                http::Request<tonic::body::Body>,
+                Response = http::Response<
+                    <T as tonic::client::GrpcService<tonic::body::Body>>::ResponseBody,
+                >,
+            >,
+            <T as tonic::codegen::Service<
+                http::Request<tonic::body::Body>,
+            >>::Error: Into<StdError> + std::marker::Send + std::marker::Sync,
+        {
+            ProofServiceClient::new(InterceptedService::new(inner, interceptor))
+        }
+        /// Compress requests with the given encoding.
+        ///
+        /// This requires the server to support it otherwise it might respond with an
+        /// error.
+        #[must_use]
+        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.send_compressed(encoding);
+            self
+        }
+        /// Enable decompressing responses.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.accept_compressed(encoding);
+            self
+        }
+        /// Limits the maximum size of a decoded message.
+        ///
+        /// Default: `4MB`
+        #[must_use]
+        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
+            self.inner = self.inner.max_decoding_message_size(limit);
+            self
+        }
+        /// Limits the maximum size of an encoded message.
+        ///
+        /// Default: `usize::MAX`
+        #[must_use]
+        pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
+            self.inner = self.inner.max_encoding_message_size(limit);
+            self
+        }
+        /// Returns an inclusion proof if a specified object id was written in a specified checkpoint.
+        pub async fn get_object_inclusion_proof(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetObjectInclusionProofRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetObjectInclusionProofResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic_prost::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/sui.rpc.alpha.ProofService/GetObjectInclusionProof",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "sui.rpc.alpha.ProofService",
+                        "GetObjectInclusionProof",
+                    ),
+                );
+            self.inner.unary(req, path, codec).await
+        }
+    }
+}
+/// Generated server implementations.
+pub mod proof_service_server {
+    #![allow(
+        unused_variables,
+        dead_code,
+        missing_docs,
+        clippy::wildcard_imports,
+        clippy::let_unit_value,
+    )]
+    use tonic::codegen::*;
+    /// Generated trait containing gRPC methods that should be implemented for use with ProofServiceServer.
+    #[async_trait]
+    pub trait ProofService: std::marker::Send + std::marker::Sync + 'static {
+        /// Returns an inclusion proof if a specified object id was written in a specified checkpoint.
+        async fn get_object_inclusion_proof(
+            &self,
+            request: tonic::Request<super::GetObjectInclusionProofRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetObjectInclusionProofResponse>,
+            tonic::Status,
+        >;
+    }
+    /// ProofService provides cryptographic proofs for blockchain objects.
+    #[derive(Debug)]
+    pub struct ProofServiceServer<T> {
+        inner: Arc<T>,
+        accept_compression_encodings: EnabledCompressionEncodings,
+        send_compression_encodings: EnabledCompressionEncodings,
+        max_decoding_message_size: Option<usize>,
+        max_encoding_message_size: Option<usize>,
+    }
+    impl<T> ProofServiceServer<T> {
+        pub fn new(inner: T) -> Self {
+            Self::from_arc(Arc::new(inner))
+        }
+        pub fn from_arc(inner: Arc<T>) -> Self {
+            Self {
+                inner,
+                accept_compression_encodings: Default::default(),
+                send_compression_encodings: Default::default(),
+                max_decoding_message_size: None,
+                max_encoding_message_size: None,
+            }
+        }
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> InterceptedService<Self, F>
+        where
+            F: tonic::service::Interceptor,
+        {
+            InterceptedService::new(Self::new(inner), interceptor)
+        }
+        /// Enable decompressing requests with the given encoding.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.accept_compression_encodings.enable(encoding);
+            self
+        }
+        /// Compress responses with the given encoding, if the client supports it.
+        #[must_use]
+        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.send_compression_encodings.enable(encoding);
+            self
+        }
+        /// Limits the maximum size of a decoded message.
+        ///
+        /// Default: `4MB`
+        #[must_use]
+        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
+            self.max_decoding_message_size = Some(limit);
+            self
+        }
+        /// Limits the maximum size of an encoded message.
+        ///
+        /// Default: `usize::MAX`
+        #[must_use]
+        pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
+            self.max_encoding_message_size = Some(limit);
+            self
+        }
+    }
+    impl<T, B> tonic::codegen::Service<http::Request<B>> for ProofServiceServer<T>
+    where
+        T: ProofService,
+        B: Body + std::marker::Send + 'static,
+        B::Error: Into<StdError> + std::marker::Send + 'static,
+    {
+        type Response = http::Response<tonic::body::Body>;
+        type Error = std::convert::Infallible;
+        type Future = BoxFuture<Self::Response, Self::Error>;
+        fn poll_ready(
+            &mut self,
+            _cx: &mut Context<'_>,
+        ) -> Poll<std::result::Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+        fn call(&mut self, req: http::Request<B>) -> Self::Future {
+            match req.uri().path() {
+                "/sui.rpc.alpha.ProofService/GetObjectInclusionProof" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetObjectInclusionProofSvc<T: ProofService>(pub Arc<T>);
+                    impl<
+                        T: ProofService,
+                    > tonic::server::UnaryService<super::GetObjectInclusionProofRequest>
+                    for GetObjectInclusionProofSvc<T> {
+                        type Response = super::GetObjectInclusionProofResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::GetObjectInclusionProofRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as ProofService>::get_object_inclusion_proof(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetObjectInclusionProofSvc(inner);
+                        let codec = tonic_prost::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                _ => {
+                    Box::pin(async move {
+                        let mut response = http::Response::new(
+                            tonic::body::Body::default(),
+                        );
+                        let headers = response.headers_mut();
+                        headers
+                            .insert(
+                                tonic::Status::GRPC_STATUS,
+                                (tonic::Code::Unimplemented as i32).into(),
+                            );
+                        headers
+                            .insert(
+                                http::header::CONTENT_TYPE,
+                                tonic::metadata::GRPC_CONTENT_TYPE,
+                            );
+                        Ok(response)
+                    })
+                }
+            }
+        }
+    }
+    impl<T> Clone for ProofServiceServer<T> {
+        fn clone(&self) -> Self {
+            let inner = self.inner.clone();
+            Self {
+                inner,
+                accept_compression_encodings: self.accept_compression_encodings,
+                send_compression_encodings: self.send_compression_encodings,
+                max_decoding_message_size: self.max_decoding_message_size,
+                max_encoding_message_size: self.max_encoding_message_size,
+            }
+        }
+    }
+    /// Generated gRPC service name
+    pub const SERVICE_NAME: &str = "sui.rpc.alpha.ProofService";
+    impl<T> tonic::server::NamedService for ProofServiceServer<T> {
+        const NAME: &'static str = SERVICE_NAME;
+    }
+}
+/// Request to subscribe to a stream of checkpoints.
+#[non_exhaustive]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SubscribeCheckpointsRequest {
+    /// Optional. Mask for specifying which parts of each checkpoint should be returned.
+    #[prost(message, optional, tag = "1")]
+    pub read_mask: ::core::option::Option<::prost_types::FieldMask>,
+    /// Optional. Sequence number of the first checkpoint to stream. Must not be pruned from the
+    /// node's store, and must be at most one past the node's latest checkpoint. (default: the
+    /// next checkpoint to be executed)
+    #[prost(uint64, optional, tag = "2")]
+    pub start_cursor: ::core::option::Option<u64>,
+    /// Optional. Only transactions that match at least one of these filters are included in each
+    /// checkpoint's `transactions`. (default: no filtering)
+    #[prost(message, repeated, tag = "3")]
+    pub filters: ::prost::alloc::vec::Vec<TransactionFilter>,
+    /// Optional. If set, a heartbeat (a response without a checkpoint) is sent whenever this many
+    /// milliseconds pass without a checkpoint being sent.
+    #[prost(uint32, optional, tag = "4")]
+    pub heartbeat_interval_ms: ::core::option::Option<u32>,
+}
+/// A condition on a single transaction.
+#[non_exhaustive]
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct TransactionFilter {
+    #[prost(oneof = "transaction_filter::Filter", tags = "1, 2, 3, 4, 5")]
+    pub filter: ::core::option::Option<transaction_filter::Filter>,
+}
+/// Nested message and enum types in `TransactionFilter`.
+pub mod transaction_filter {
+    #[non_exhaustive]
+    #[derive(Clone, PartialEq, Eq, Hash, ::prost::Oneof)]
+    pub enum Filter {
+        /// The transaction was sent by this address.
+        #[prost(string, tag = "1")]
+        Sender(::prost::alloc::string::String),
+        /// The transaction was sent or sponsored by this address, or touched an object owned by this
+        /// address (before or after the transaction).
+        #[prost(string, tag = "2")]
+        AffectedAddress(::prost::alloc::string::String),
+        /// The transaction calls a Move function matching this filter.
+        #[prost(message, tag = "3")]
+        MoveCall(super::MoveCallFilter),
+        /// The transaction emitted an event of this type. If the type has no type parameters, events
+        /// with any instantiation of the type match.
+        #[prost(string, tag = "4")]
+        EventType(::prost::alloc::string::String),
+        /// The transaction created, mutated, wrapped, unwrapped or deleted the object with this ID.
+        #[prost(string, tag = "5")]
+        AffectedObject(::prost::alloc::string::String),
+    }
+}
+/// Matches calls to functions in a package, and optionally a module and function within it.
+#[non_exhaustive]
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct MoveCallFilter {
+    /// Required. ID of the package the function belongs to.
+    #[prost(string, optional, tag = "1")]
+    pub package: ::core::option::Option<::prost::alloc::string::String>,
+    /// Optional. Name of the module the function belongs to.
+    #[prost(string, optional, tag = "2")]
+    pub module: ::core::option::Option<::prost::alloc::string::String>,
+    /// Optional. Name of the function. Requires `module` to be set.
+    #[prost(string, optional, tag = "3")]
+    pub function: ::core::option::Option<::prost::alloc::string::String>,
+}
+/// A single message in the checkpoint stream.
+#[non_exhaustive]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SubscribeCheckpointsResponse {
+    /// Sequence number of the checkpoint in this response, or for heartbeats, of the checkpoint
+    /// before the next one to be streamed. In either case, the subscription can be resumed by
+    /// setting its `start_cursor` to one past this value.
+    #[prost(uint64, optional, tag = "1")]
+    pub cursor: ::core::option::Option<u64>,
+    /// The checkpoint, filtered and masked according to the request. Unset for heartbeats.
+    #[prost(message, optional, tag = "2")]
+    pub checkpoint: ::core::option::Option<::sui_rpc::proto::sui::rpc::v2::Checkpoint>,
+    /// The latest checkpoint executed by the node when this response was produced. The difference
+    /// between this and `cursor` is how far the subscription is lagging behind.
+    #[prost(uint64, optional, tag = "3")]
+    pub latest_checkpoint: ::core::option::Option<u64>,
+}
+/// Generated client implementations.
+pub mod subscription_service_client {
+    #![allow(
+        unused_variables,
+        dead_code,
+        missing_docs,
+        clippy::wildcard_imports,
+        clippy::let_unit_value,
+    )]
+    use tonic::codegen::*;
+    use tonic::codegen::http::Uri;
+    /// SubscriptionService streams checkpoints, optionally resuming from a checkpoint in the past, and
+    /// filtering the transactions that are included.
+    #[derive(Debug, Clone)]
+    pub struct SubscriptionServiceClient<T> {
+        inner: tonic::client::Grpc<T>,
+    }
+    impl SubscriptionServiceClient<tonic::transport::Channel> {
+        /// Attempt to create a new client by connecting to a given endpoint.
+        pub async fn connect<D>(dst: D) -> Result<Self, tonic::transport::Error>
+        where
+            D: TryInto<tonic::transport::Endpoint>,
+            D::Error: Into<StdError>,
+        {
+            let conn = tonic::transport::Endpoint::new(dst)?.connect().await?;
+            Ok(Self::new(conn))
+        }
+    }
+    impl<T> SubscriptionServiceClient<T>
+    where
+        T: tonic::client::GrpcService<tonic::body::Body>,
+        T::Error: Into<StdError>,
+        T::ResponseBody: Body<Data = Bytes> + std::marker::Send + 'static,
+        <T::ResponseBody as Body>::Error: Into<StdError> + std::marker::Send,
+    {
+        pub fn new(inner: T) -> Self {
+            let inner = tonic::client::Grpc::new(inner);
+            Self { inner }
+        }
+        pub fn with_origin(inner: T, origin: Uri) -> Self {
+            let inner = tonic::client::Grpc::with_origin(inner, origin);
+            Self { inner }
+        }
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> SubscriptionServiceClient<InterceptedService<T, F>>
+        where
+            F: tonic::service::Interceptor,
+            T::ResponseBody: Default,
+            T: tonic::codegen::Service<
+                http::Request<tonic::body::Body>,
+                Response = http::Response<
+                    <T as tonic::client::GrpcService<tonic::body::Body>>::ResponseBody,
+                >,
+            >,
+            <T as tonic::codegen::Service<
+                http::Request<tonic::body::Body>,
+            >>::Error: Into<StdError> + std::marker::Send + std::marker::Sync,
+        {
+            SubscriptionServiceClient::new(InterceptedService::new(inner, interceptor))
+        }
+        /// Compress requests with the given encoding.
+        ///
+        /// This requires the server to support it otherwise it might respond with an
+        /// error.
+        #[must_use]
+        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.send_compressed(encoding);
+            self
+        }
+        /// Enable decompressing responses.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.accept_compressed(encoding);
+            self
+        }
+        /// Limits the maximum size of a decoded message.
+        ///
+        /// Default: `4MB`
+        #[must_use]
+        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
+            self.inner = self.inner.max_decoding_message_size(limit);
+            self
+        }
+        /// Limits the maximum size of an encoded message.
+        ///
+        /// Default: `usize::MAX`
+        #[must_use]
+        pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
+            self.inner = self.inner.max_encoding_message_size(limit);
+            self
+        }
+        /// Subscribe to the stream of checkpoints.
+        ///
+        /// If `start_cursor` is set, checkpoints from `start_cursor` onwards are first replayed from the
+        /// node's store, before the stream switches over to checkpoints as they are executed, without
+        /// any gaps or duplicates. Otherwise the stream starts from the next checkpoint to be executed.
+        ///
+        /// Checkpoints are always streamed in order, and every checkpoint is streamed, even if none of
+        /// its transactions match the subscription's filters.
+        pub async fn subscribe_checkpoints(
+            &mut self,
+            request: impl tonic::IntoRequest<super::SubscribeCheckpointsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<
+                tonic::codec::Streaming<super::SubscribeCheckpointsResponse>,
+            >,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic_prost::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/sui.rpc.alpha.SubscriptionService/SubscribeCheckpoints",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(
+                    GrpcMethod::new(
+                        "sui.rpc.alpha.SubscriptionService",
+                        "SubscribeCheckpoints",
+                    ),
+                );
+            self.inner.server_streaming(req, path, codec).await
+        }
+    }
+}
+/// Generated server implementations.
+pub mod subscription_service_server {
+    #![allow(
+        unused_variables,
+        dead_code,
+        missing_docs,
+        clippy::wildcard_imports,
+        clippy::let_unit_value,
+    )]
+    use tonic::codegen::*;
+    /// Generated trait containing gRPC methods that should be implemented for use with SubscriptionServiceServer.
+    #[async_trait]
+    pub trait SubscriptionService: std::marker::Send + std::marker::Sync + 'static {
+        /// Server streaming response type for the SubscribeCheckpoints method.
+        type SubscribeCheckpointsStream: tonic::codegen::tokio_stream::Stream<
+                Item = std::result::Result<
+                    super::SubscribeCheckpointsResponse,
+                    tonic::Status,
+                >,
+            >
+            + std::marker::Send
+            + 'static;
+        /// Subscribe to the stream of checkpoints.
+        ///
+        /// If `start_cursor` is set, checkpoints from `start_cursor` onwards are first replayed from the
+        /// node's store, before the stream switches over to checkpoints as they are executed, without
+        /// any gaps or duplicates. Otherwise the stream starts from the next checkpoint to be executed.
+        ///
+        /// Checkpoints are always streamed in order, and every checkpoint is streamed, even if none of
+        /// its transactions match the subscription's filters.
+        async fn subscribe_checkpoints(
+            &self,
+            request: tonic::Request<super::SubscribeCheckpointsRequest>,
+        ) -> std::result::Result<
+            tonic::Response<Self::SubscribeCheckpointsStream>,
+            tonic::Status,
+        >;
+    }
+    /// SubscriptionService streams checkpoints, optionally resuming from a checkpoint in the past, and
+    /// filtering the transactions that are included.
+    #[derive(Debug)]
+    pub struct SubscriptionServiceServer<T> {
+        inner: Arc<T>,
+        accept_compression_encodings: EnabledCompressionEncodings,
+        send_compression_encodings: EnabledCompressionEncodings,
+        max_decoding_message_size: Option<usize>,
+        max_encoding_message_size: Option<usize>,
+    }
+    impl<T> SubscriptionServiceServer<T> {
+        pub fn new(inner: T) -> Self {
+            Self::from_arc(Arc::new(inner))
+        }
+        pub fn from_arc(inner: Arc<T>) -> Self {
+            Self {
+                inner,
+                accept_compression_encodings: Default::default(),
+                send_compression_encodings: Default::default(),
+                max_decoding_message_size: None,
+                max_encoding_message_size: None,
+            }
+        }
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> InterceptedService<Self, F>
+        where
+            F: tonic::service::Interceptor,
+        {
+            InterceptedService::new(Self::new(inner), interceptor)
+        }
+        /// Enable decompressing requests with the given encoding.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.accept_compression_encodings.enable(encoding);
+            self
+        }
+        /// Compress responses with the given encoding, if the client supports it.
+        #[must_use]
+        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.send_compression_encodings.enable(encoding);
+            self
+        }
+        /// Limits the maximum size of a decoded message.
+        ///
+        /// Default: `4MB`
+        #[must_use]
+        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
+            self.max_decoding_message_size = Some(limit);
+            self
+        }
+        /// Limits the maximum size of an encoded message.
+        ///
+        /// Default: `usize::MAX`
+        #[must_use]
+        pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
+            self.max_encoding_message_size = Some(limit);
+            self
+        }
+    }
+    impl<T, B> tonic::codegen::Service<http::Request<B>> for SubscriptionServiceServer<T>
+    where
+        T: SubscriptionService,
+        B: Body + std::marker::Send + 'static,
+        B::Error: Into<StdError> + std::marker::Send + 'static,
+    {
+        type Response = http::Response<tonic::body::Body>;
+        type Error = std::convert::Infallible;
+        type Future = BoxFuture<Self::Response, Self::Error>;
+        fn poll_ready(
+            &mut self,
+            _cx: &mut Context<'_>,
+        ) -> Poll<std::result::Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+        fn call(&mut self, req: http::Request<B>) -> Self::Future {
+            match req.uri().path() {
+                "/sui.rpc.alpha.SubscriptionService/SubscribeCheckpoints" => {
+                    #[allow(non_camel_case_types)]
+                    struct SubscribeCheckpointsSvc<T: SubscriptionService>(pub Arc<T>);
+                    impl<
+                        T: SubscriptionService,
+                    > tonic::server::ServerStreamingService<
+                        super::SubscribeCheckpointsRequest,
+                    > for SubscribeCheckpointsSvc<T> {
+                        type Response = super::SubscribeCheckpointsResponse;
+                        type ResponseStream = T::SubscribeCheckpointsStream;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::ResponseStream>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<
+                                super::SubscribeCheckpointsRequest,
+                            >,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as SubscriptionService>::subscribe_checkpoints(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = SubscribeCheckpointsSvc(inner);
+                        let codec = tonic_prost::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.server_streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                _ => {
+                    Box::pin(async move {
+                        let mut response = http::Response::new(
+                            tonic::body::Body::default(),
+                        );
+                        let headers = response.headers_mut();
+                        headers
+                            .insert(
+                                tonic::Status::GRPC_STATUS,
+                                (tonic::Code::Unimplemented as i32).into(),
+                            );
+                        headers
+                            .insert(
+                                http::header::CONTENT_TYPE,
+                                tonic::metadata::GRPC_CONTENT_TYPE,
+                            );
+                        Ok(response)
+                    })
+                }
+            }
+        }
+    }
+    impl<T> Clone for SubscriptionServiceServer<T> {
+        fn clone(&self) -> Self {
+            let inner = self.inner.clone();
+            Self {
+                inner,
+                accept_compression_encodings: self.accept_compression_encodings,
+                send_compression_encodings: self.send_compression_encodings,
+                max_decoding_message_size: self.max_decoding_message_size,
+                max_encoding_message_size: self.max_encoding_message_size,
+            }
+        }
+    }
+    /// Generated gRPC service name
+    pub const SERVICE_NAME: &str = "sui.rpc.alpha.SubscriptionService";
+    impl<T> tonic::server::NamedService for SubscriptionServiceServer<T> {
         const NAME: &'static str = SERVICE_NAME;
     }
 }
@@ -0,0 +1,16 @@
+CREATE TABLE IF NOT EXISTS dead_letters
+(
+    -- The pipeline that failed to process the checkpoint.
+    pipeline                    TEXT          NOT NULL,
+    -- The checkpoint that the pipeline skipped after a permanent error.
+    checkpoint                  BIGINT        NOT NULL,
+    -- The error from the latest attempt at processing the checkpoint.
+    error                       TEXT          NOT NULL,
+    -- The number of times processing the checkpoint has failed, including
+    -- failed attempts at re-driving it.
+    attempts                    BIGINT        NOT NULL,
+    -- When the latest failure was recorded, in milliseconds since the Unix
+    -- epoch.
+    updated_at_ms               BIGINT        NOT NULL,
+    PRIMARY KEY (pipeline, checkpoint)
+);
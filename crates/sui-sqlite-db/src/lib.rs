@@ -0,0 +1,371 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! An embedded SQLite implementation of the indexer framework's `Store` and `TransactionalStore`,
+//! for indexers (and their tests) that should run as a single binary, without provisioning a
+//! Postgres database.
+//!
+//! The database lives in a single file, shared by a pool of connections. Connections are put in
+//! WAL mode so that reads are not blocked by the (single) writer, and wait for the database to
+//! become available when another connection is writing to it, rather than failing immediately.
+
+use std::ops::{Deref, DerefMut};
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::anyhow;
+use diesel::ConnectionError;
+use diesel::migration::{Migration, MigrationSource, MigrationVersion};
+use diesel::sqlite::Sqlite;
+use diesel::{QueryableByName, SqliteConnection};
+use diesel_async::async_connection_wrapper::AsyncConnectionWrapper;
+use diesel_async::pooled_connection::ManagerConfig;
+use diesel_async::sync_connection_wrapper::SyncConnectionWrapper;
+use diesel_async::{
+    AsyncConnection, RunQueryDsl, SimpleAsyncConnection,
+    pooled_connection::{
+        AsyncDieselConnectionManager,
+        bb8::{Pool, PooledConnection},
+    },
+};
+use futures::FutureExt;
+use tracing::info;
+
+mod model;
+
+pub use sui_field_count::FieldCount;
+
+pub mod schema;
+pub mod store;
+
+use diesel_migrations::{EmbeddedMigrations, embed_migrations};
+
+pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations");
+
+/// An async SQLite connection. SQLite connections are synchronous, so queries are run on tokio's
+/// blocking thread pool.
+pub type AsyncSqliteConnection = SyncConnectionWrapper<SqliteConnection>;
+
+#[derive(clap::Args, Debug, Clone)]
+pub struct DbArgs {
+    /// Number of connections to keep in the pool.
+    #[arg(long, default_value_t = Self::default().db_connection_pool_size)]
+    pub db_connection_pool_size: u32,
+
+    /// Time spent waiting for a connection from the pool to become available, in milliseconds.
+    #[arg(long, default_value_t = Self::default().db_connection_timeout_ms)]
+    pub db_connection_timeout_ms: u64,
+
+    /// Time spent waiting for the database to be unlocked by another connection, before a
+    /// statement fails, in milliseconds.
+    #[arg(long, default_value_t = Self::default().db_busy_timeout_ms)]
+    pub db_busy_timeout_ms: u64,
+}
+
+#[derive(Clone)]
+pub struct Db(Pool<AsyncSqliteConnection>);
+
+/// Wrapper struct over the remote `PooledConnection` type for dealing with the `Store` trait.
+pub struct Connection<'a>(PooledConnection<'a, AsyncSqliteConnection>);
+
+impl DbArgs {
+    pub fn connection_timeout(&self) -> Duration {
+        Duration::from_millis(self.db_connection_timeout_ms)
+    }
+
+    pub fn busy_timeout(&self) -> Duration {
+        Duration::from_millis(self.db_busy_timeout_ms)
+    }
+}
+
+impl Db {
+    /// Construct a new DB connection pool for the database in the file at `path`, that supports
+    /// writes and reads. The file is created if it does not exist yet. Instances of [Db] can be
+    /// cloned to share access to the same pool.
+    pub async fn for_write(path: impl AsRef<Path>, config: DbArgs) -> anyhow::Result<Self> {
+        Ok(Self(pool(path.as_ref(), config, false).await?))
+    }
+
+    /// Construct a new DB connection pool for the database in the file at `path`, that only
+    /// supports reads. Instances of [Db] can be cloned to share access to the same pool.
+    pub async fn for_read(path: impl AsRef<Path>, config: DbArgs) -> anyhow::Result<Self> {
+        Ok(Self(pool(path.as_ref(), config, true).await?))
+    }
+
+    /// Retrieves a connection from the pool. Can fail with a timeout if a connection cannot be
+    /// established before the [DbArgs::connection_timeout] has elapsed.
+    pub async fn connect(&self) -> anyhow::Result<Connection<'_>> {
+        Ok(Connection(self.0.get().await?))
+    }
+
+    /// Statistics about the connection pool
+    pub fn state(&self) -> bb8::State {
+        self.0.state()
+    }
+
+    async fn clear_database(&self) -> anyhow::Result<()> {
+        #[derive(QueryableByName)]
+        struct Table {
+            #[diesel(sql_type = diesel::sql_types::Text)]
+            name: String,
+        }
+
+        info!("Clearing the database...");
+        let mut conn = self.connect().await?;
+        let tables: Vec<Table> = diesel::sql_query(
+            "SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%'",
+        )
+        .load(&mut conn)
+        .await?;
+
+        for Table { name } in tables {
+            conn.batch_execute(&format!("DROP TABLE IF EXISTS \"{name}\""))
+                .await?;
+        }
+
+        info!("Database cleared.");
+        Ok(())
+    }
+
+    /// Run migrations on the database. Use Diesel's `embed_migrations!` macro to generate the
+    /// `migrations` parameter for your indexer.
+    pub async fn run_migrations(
+        &self,
+        migrations: Option<&'static EmbeddedMigrations>,
+    ) -> anyhow::Result<Vec<MigrationVersion<'static>>> {
+        use diesel_migrations::MigrationHarness;
+
+        let merged_migrations = merge_migrations(migrations);
+
+        info!("Running migrations ...");
+        let conn = self.0.dedicated_connection().await?;
+        let mut wrapper: AsyncConnectionWrapper<AsyncSqliteConnection> =
+            AsyncConnectionWrapper::from(conn);
+
+        let finished_migrations = tokio::task::spawn_blocking(move || {
+            wrapper
+                .run_pending_migrations(merged_migrations)
+                .map(|versions| versions.iter().map(MigrationVersion::as_owned).collect())
+        })
+        .await?
+        .map_err(|e| anyhow!("Failed to run migrations: {:?}", e))?;
+
+        info!("Migrations complete.");
+        Ok(finished_migrations)
+    }
+}
+
+impl Default for DbArgs {
+    fn default() -> Self {
+        Self {
+            db_connection_pool_size: 10,
+            db_connection_timeout_ms: 60_000,
+            db_busy_timeout_ms: 5_000,
+        }
+    }
+}
+
+/// Drop all tables, and re-run migrations if supplied.
+pub async fn reset_database(
+    path: impl AsRef<Path>,
+    db_config: DbArgs,
+    migrations: Option<&'static EmbeddedMigrations>,
+) -> anyhow::Result<()> {
+    let db = Db::for_write(path, db_config).await?;
+    db.clear_database().await?;
+    if let Some(migrations) = migrations {
+        db.run_migrations(Some(migrations)).await?;
+    }
+
+    Ok(())
+}
+
+impl<'a> Deref for Connection<'a> {
+    type Target = PooledConnection<'a, AsyncSqliteConnection>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for Connection<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+async fn pool(
+    path: &Path,
+    args: DbArgs,
+    read_only: bool,
+) -> anyhow::Result<Pool<AsyncSqliteConnection>> {
+    let database_url = path
+        .to_str()
+        .ok_or_else(|| anyhow!("Database path is not valid UTF-8: {}", path.display()))?
+        .to_owned();
+
+    let busy_timeout = args.busy_timeout();
+
+    let mut config = ManagerConfig::default();
+
+    config.custom_setup = Box::new(move |url| {
+        let url = url.to_owned();
+
+        async move {
+            let mut conn = AsyncSqliteConnection::establish(&url).await?;
+
+            // WAL mode allows reads to proceed while a write is in progress, and the busy timeout
+            // makes writers queue up behind each other instead of failing with `SQLITE_BUSY`.
+            let mut setup = format!(
+                "PRAGMA journal_mode = WAL; \
+                 PRAGMA synchronous = NORMAL; \
+                 PRAGMA busy_timeout = {};",
+                busy_timeout.as_millis(),
+            );
+
+            if read_only {
+                setup.push_str(" PRAGMA query_only = ON;");
+            }
+
+            conn.batch_execute(&setup)
+                .await
+                .map_err(ConnectionError::CouldntSetupConfiguration)?;
+
+            Ok(conn)
+        }
+        .boxed()
+    });
+
+    let manager = AsyncDieselConnectionManager::new_with_config(database_url, config);
+
+    Ok(Pool::builder()
+        .max_size(args.db_connection_pool_size)
+        .connection_timeout(args.connection_timeout())
+        .build(manager)
+        .await?)
+}
+
+/// Returns new migrations derived from the combination of provided migrations and migrations
+/// defined in this crate.
+pub fn merge_migrations(
+    migrations: Option<&'static EmbeddedMigrations>,
+) -> impl MigrationSource<Sqlite> + Send + Sync + 'static {
+    struct Migrations(Option<&'static EmbeddedMigrations>);
+    impl MigrationSource<Sqlite> for Migrations {
+        fn migrations(&self) -> diesel::migration::Result<Vec<Box<dyn Migration<Sqlite>>>> {
+            let mut migrations = MIGRATIONS.migrations()?;
+            if let Some(more_migrations) = self.0 {
+                migrations.extend(more_migrations.migrations()?);
+            }
+            Ok(migrations)
+        }
+    }
+
+    Migrations(migrations)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, QueryableByName)]
+    struct CountResult {
+        #[diesel(sql_type = diesel::sql_types::BigInt)]
+        cnt: i64,
+    }
+
+    const COUNT_TEST_TABLE: &str =
+        "SELECT COUNT(*) AS cnt FROM sqlite_master WHERE type = 'table' AND name = 'test_table'";
+
+    #[tokio::test]
+    async fn test_reset_database_skip_migrations() {
+        telemetry_subscribers::init_for_testing();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("test.db");
+
+        let db = Db::for_write(&path, DbArgs::default()).await.unwrap();
+        let mut conn = db.connect().await.unwrap();
+        diesel::sql_query("CREATE TABLE test_table (id INTEGER PRIMARY KEY)")
+            .execute(&mut conn)
+            .await
+            .unwrap();
+        let cnt: CountResult = diesel::sql_query(COUNT_TEST_TABLE)
+            .get_result(&mut conn)
+            .await
+            .unwrap();
+        assert_eq!(cnt.cnt, 1);
+        drop(conn);
+
+        reset_database(&path, DbArgs::default(), None)
+            .await
+            .unwrap();
+
+        let mut conn = db.connect().await.unwrap();
+        let cnt: CountResult = diesel::sql_query(COUNT_TEST_TABLE)
+            .get_result(&mut conn)
+            .await
+            .unwrap();
+        assert_eq!(cnt.cnt, 0);
+    }
+
+    #[tokio::test]
+    async fn test_read_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("test.db");
+
+        let writer = Db::for_write(&path, DbArgs::default()).await.unwrap();
+        let reader = Db::for_read(&path, DbArgs::default()).await.unwrap();
+
+        {
+            // Create a table
+            let mut conn = writer.connect().await.unwrap();
+            diesel::sql_query("CREATE TABLE test_table (id INTEGER PRIMARY KEY)")
+                .execute(&mut conn)
+                .await
+                .unwrap();
+        }
+
+        {
+            // Try an insert into it using the read-only connection, which should fail
+            let mut conn = reader.connect().await.unwrap();
+            let result = diesel::sql_query("INSERT INTO test_table (id) VALUES (1)")
+                .execute(&mut conn)
+                .await;
+            assert!(result.is_err());
+        }
+
+        {
+            // Then try to write to it using the write connection, which should succeed
+            let mut conn = writer.connect().await.unwrap();
+            diesel::sql_query("INSERT INTO test_table (id) VALUES (1)")
+                .execute(&mut conn)
+                .await
+                .unwrap();
+        }
+
+        {
+            // Finally, try to read from it using the read-only connection, which should see the
+            // write.
+            let mut conn = reader.connect().await.unwrap();
+            let cnt: CountResult = diesel::sql_query("SELECT COUNT(*) AS cnt FROM test_table")
+                .get_result(&mut conn)
+                .await
+                .unwrap();
+            assert_eq!(cnt.cnt, 1);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_migrations() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("test.db");
+
+        let db = Db::for_write(&path, DbArgs::default()).await.unwrap();
+        let applied = db.run_migrations(None).await.unwrap();
+        assert_eq!(applied.len(), 2);
+
+        // Migrations that have already been run are skipped.
+        let applied = db.run_migrations(None).await.unwrap();
+        assert!(applied.is_empty());
+    }
+}
@@ -0,0 +1,30 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use diesel::prelude::*;
+use sui_field_count::FieldCount;
+
+use crate::schema::{dead_letters, watermarks};
+
+#[derive(Insertable, Selectable, Queryable, Debug, Clone, FieldCount)]
+#[diesel(table_name = watermarks)]
+pub struct StoredWatermark {
+    pub pipeline: String,
+    pub epoch_hi_inclusive: i64,
+    pub checkpoint_hi_inclusive: i64,
+    pub tx_hi: i64,
+    pub timestamp_ms_hi_inclusive: i64,
+    pub reader_lo: i64,
+    pub pruner_timestamp_ms: i64,
+    pub pruner_hi: i64,
+}
+
+#[derive(Selectable, Queryable, Debug, Clone)]
+#[diesel(table_name = dead_letters)]
+pub struct StoredDeadLetter {
+    pub pipeline: String,
+    pub checkpoint: i64,
+    pub error: String,
+    pub attempts: i64,
+    pub updated_at_ms: i64,
+}
@@ -0,0 +1,431 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use diesel::ExpressionMethods;
+use diesel::OptionalExtension;
+use diesel::dsl::sql;
+use diesel::expression::SqlLiteral;
+use diesel::prelude::*;
+use diesel::sql_types::BigInt;
+use diesel_async::{AsyncConnection, RunQueryDsl};
+use scoped_futures::ScopedBoxFuture;
+use sui_indexer_alt_framework_store_traits as store;
+
+use crate::model::{StoredDeadLetter, StoredWatermark};
+use crate::schema::{dead_letters, watermarks};
+use crate::{Connection, Db};
+
+pub use sui_indexer_alt_framework_store_traits::Store;
+
+/// The database's current time, in milliseconds since the Unix epoch. SQLite's `CURRENT_TIMESTAMP`
+/// only has second precision, but its Julian day numbers are fractional.
+const NOW_MS: &str = "CAST((julianday('now') - 2440587.5) * 86400000 AS BIGINT)";
+
+fn now_ms() -> SqlLiteral<BigInt> {
+    sql(NOW_MS)
+}
+
+#[async_trait]
+impl store::Connection for Connection<'_> {
+    async fn committer_watermark(
+        &mut self,
+        pipeline_task: &str,
+    ) -> anyhow::Result<Option<store::CommitterWatermark>> {
+        let watermark: Option<(i64, i64, i64, i64)> = watermarks::table
+            .select((
+                watermarks::epoch_hi_inclusive,
+                watermarks::checkpoint_hi_inclusive,
+                watermarks::tx_hi,
+                watermarks::timestamp_ms_hi_inclusive,
+            ))
+            .filter(watermarks::pipeline.eq(pipeline_task))
+            .first(self)
+            .await
+            .optional()?;
+
+        if let Some(watermark) = watermark {
+            Ok(Some(store::CommitterWatermark {
+                epoch_hi_inclusive: watermark.0 as u64,
+                checkpoint_hi_inclusive: watermark.1 as u64,
+                tx_hi: watermark.2 as u64,
+                timestamp_ms_hi_inclusive: watermark.3 as u64,
+            }))
+        } else {
+            Ok(None)
+        }
+    }
+
+    async fn reader_watermark(
+        &mut self,
+        pipeline: &'static str,
+    ) -> anyhow::Result<Option<store::ReaderWatermark>> {
+        let watermark: Option<(i64, i64)> = watermarks::table
+            .select((watermarks::checkpoint_hi_inclusive, watermarks::reader_lo))
+            .filter(watermarks::pipeline.eq(pipeline))
+            .first(self)
+            .await
+            .optional()?;
+
+        if let Some(watermark) = watermark {
+            Ok(Some(store::ReaderWatermark {
+                checkpoint_hi_inclusive: watermark.0 as u64,
+                reader_lo: watermark.1 as u64,
+            }))
+        } else {
+            Ok(None)
+        }
+    }
+
+    async fn pruner_watermark(
+        &mut self,
+        pipeline: &'static str,
+        delay: Duration,
+    ) -> anyhow::Result<Option<store::PrunerWatermark>> {
+        //     |---------- + delay ---------------------|
+        //                             |--- wait_for ---|
+        //     |-----------------------|----------------|
+        //     ^                       ^
+        //     pruner_timestamp        NOW()
+        let wait_for = sql::<BigInt>("(")
+            .bind::<BigInt, _>(delay.as_millis() as i64)
+            .sql(" + pruner_timestamp_ms - ")
+            .sql(NOW_MS)
+            .sql(")");
+
+        let watermark: Option<(i64, i64, i64)> = watermarks::table
+            .select((wait_for, watermarks::pruner_hi, watermarks::reader_lo))
+            .filter(watermarks::pipeline.eq(pipeline))
+            .first(self)
+            .await
+            .optional()?;
+
+        if let Some(watermark) = watermark {
+            Ok(Some(store::PrunerWatermark {
+                wait_for_ms: watermark.0,
+                pruner_hi: watermark.1 as u64,
+                reader_lo: watermark.2 as u64,
+            }))
+        } else {
+            Ok(None)
+        }
+    }
+
+    async fn set_committer_watermark(
+        &mut self,
+        pipeline_task: &str,
+        watermark: store::CommitterWatermark,
+    ) -> anyhow::Result<bool> {
+        // Create a StoredWatermark directly from CommitterWatermark
+        let stored_watermark = StoredWatermark {
+            pipeline: pipeline_task.to_string(),
+            epoch_hi_inclusive: watermark.epoch_hi_inclusive as i64,
+            checkpoint_hi_inclusive: watermark.checkpoint_hi_inclusive as i64,
+            tx_hi: watermark.tx_hi as i64,
+            timestamp_ms_hi_inclusive: watermark.timestamp_ms_hi_inclusive as i64,
+            reader_lo: 0,
+            pruner_timestamp_ms: 0,
+            pruner_hi: 0,
+        };
+
+        use diesel::query_dsl::methods::FilterDsl;
+        Ok(diesel::insert_into(watermarks::table)
+            .values(&stored_watermark)
+            // There is an existing entry, so only write the new `hi` values
+            .on_conflict(watermarks::pipeline)
+            .do_update()
+            .set((
+                watermarks::epoch_hi_inclusive.eq(stored_watermark.epoch_hi_inclusive),
+                watermarks::checkpoint_hi_inclusive.eq(stored_watermark.checkpoint_hi_inclusive),
+                watermarks::tx_hi.eq(stored_watermark.tx_hi),
+                watermarks::timestamp_ms_hi_inclusive
+                    .eq(stored_watermark.timestamp_ms_hi_inclusive),
+            ))
+            .filter(
+                watermarks::checkpoint_hi_inclusive.lt(stored_watermark.checkpoint_hi_inclusive),
+            )
+            .execute(self)
+            .await?
+            > 0)
+    }
+
+    async fn set_reader_watermark(
+        &mut self,
+        pipeline: &'static str,
+        reader_lo: u64,
+    ) -> anyhow::Result<bool> {
+        Ok(diesel::update(watermarks::table)
+            .set((
+                watermarks::reader_lo.eq(reader_lo as i64),
+                watermarks::pruner_timestamp_ms.eq(now_ms()),
+            ))
+            .filter(watermarks::pipeline.eq(pipeline))
+            .filter(watermarks::reader_lo.lt(reader_lo as i64))
+            .execute(self)
+            .await?
+            > 0)
+    }
+
+    async fn set_pruner_watermark(
+        &mut self,
+        pipeline: &'static str,
+        pruner_hi: u64,
+    ) -> anyhow::Result<bool> {
+        Ok(diesel::update(watermarks::table)
+            .set(watermarks::pruner_hi.eq(pruner_hi as i64))
+            .filter(watermarks::pipeline.eq(pipeline))
+            .execute(self)
+            .await?
+            > 0)
+    }
+
+    async fn record_dead_letter(
+        &mut self,
+        pipeline: &str,
+        checkpoint: u64,
+        error: &str,
+    ) -> anyhow::Result<()> {
+        diesel::insert_into(dead_letters::table)
+            .values((
+                dead_letters::pipeline.eq(pipeline),
+                dead_letters::checkpoint.eq(checkpoint as i64),
+                dead_letters::error.eq(error),
+                dead_letters::attempts.eq(1),
+                dead_letters::updated_at_ms.eq(now_ms()),
+            ))
+            // The checkpoint was dead-lettered before, so this is a failed attempt to re-drive it.
+            .on_conflict((dead_letters::pipeline, dead_letters::checkpoint))
+            .do_update()
+            .set((
+                dead_letters::error.eq(error),
+                dead_letters::attempts.eq(dead_letters::attempts + 1),
+                dead_letters::updated_at_ms.eq(now_ms()),
+            ))
+            .execute(self)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn dead_letters(&mut self, pipeline: &str) -> anyhow::Result<Vec<store::DeadLetter>> {
+        let stored: Vec<StoredDeadLetter> = dead_letters::table
+            .select(StoredDeadLetter::as_select())
+            .filter(dead_letters::pipeline.eq(pipeline))
+            .order_by(dead_letters::checkpoint)
+            .load(self)
+            .await?;
+
+        Ok(stored
+            .into_iter()
+            .map(|d| store::DeadLetter {
+                pipeline: d.pipeline,
+                checkpoint: d.checkpoint as u64,
+                error: d.error,
+                attempts: d.attempts as u64,
+            })
+            .collect())
+    }
+
+    async fn delete_dead_letter(
+        &mut self,
+        pipeline: &str,
+        checkpoint: u64,
+    ) -> anyhow::Result<bool> {
+        Ok(diesel::delete(dead_letters::table)
+            .filter(dead_letters::pipeline.eq(pipeline))
+            .filter(dead_letters::checkpoint.eq(checkpoint as i64))
+            .execute(self)
+            .await?
+            > 0)
+    }
+}
+
+#[async_trait]
+impl store::Store for Db {
+    type Connection<'c> = Connection<'c>;
+
+    const SUPPORTS_DEAD_LETTERS: bool = true;
+
+    async fn connect<'c>(&'c self) -> anyhow::Result<Self::Connection<'c>> {
+        Ok(Connection(self.0.get().await?))
+    }
+}
+
+#[async_trait]
+impl store::TransactionalStore for Db {
+    async fn transaction<'a, R, F>(&self, f: F) -> anyhow::Result<R>
+    where
+        R: Send + 'a,
+        F: Send + 'a,
+        F: for<'r> FnOnce(
+            &'r mut Self::Connection<'_>,
+        ) -> ScopedBoxFuture<'a, 'r, anyhow::Result<R>>,
+    {
+        let mut conn = self.connect().await?;
+        AsyncConnection::transaction(&mut conn, |conn| f(conn)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use scoped_futures::ScopedFutureExt;
+    use store::{Connection as _, TransactionalStore};
+    use tempfile::TempDir;
+
+    use crate::DbArgs;
+
+    use super::*;
+
+    async fn setup() -> (TempDir, Db) {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Db::for_write(dir.path().join("test.db"), DbArgs::default())
+            .await
+            .unwrap();
+        db.run_migrations(None).await.unwrap();
+        (dir, db)
+    }
+
+    fn committer_watermark(checkpoint_hi_inclusive: u64) -> store::CommitterWatermark {
+        store::CommitterWatermark {
+            epoch_hi_inclusive: 0,
+            checkpoint_hi_inclusive,
+            tx_hi: checkpoint_hi_inclusive * 10,
+            timestamp_ms_hi_inclusive: checkpoint_hi_inclusive * 1000,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_committer_watermark_only_moves_forward() {
+        let (_dir, db) = setup().await;
+        let mut conn = db.connect().await.unwrap();
+
+        assert!(
+            conn.committer_watermark("pipeline")
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        assert!(
+            conn.set_committer_watermark("pipeline", committer_watermark(10))
+                .await
+                .unwrap()
+        );
+        assert!(
+            !conn
+                .set_committer_watermark("pipeline", committer_watermark(5))
+                .await
+                .unwrap()
+        );
+
+        let watermark = conn.committer_watermark("pipeline").await.unwrap().unwrap();
+        assert_eq!(watermark.checkpoint_hi_inclusive, 10);
+        assert_eq!(watermark.tx_hi, 100);
+        assert_eq!(watermark.timestamp_ms_hi_inclusive, 10000);
+    }
+
+    #[tokio::test]
+    async fn test_reader_and_pruner_watermarks() {
+        let (_dir, db) = setup().await;
+        let mut conn = db.connect().await.unwrap();
+
+        conn.set_committer_watermark("pipeline", committer_watermark(10))
+            .await
+            .unwrap();
+
+        assert!(conn.set_reader_watermark("pipeline", 5).await.unwrap());
+        assert!(!conn.set_reader_watermark("pipeline", 3).await.unwrap());
+
+        let reader = conn.reader_watermark("pipeline").await.unwrap().unwrap();
+        assert_eq!(reader.checkpoint_hi_inclusive, 10);
+        assert_eq!(reader.reader_lo, 5);
+
+        // The reader watermark was just updated, so the pruner has to wait for most of the delay.
+        let delay = Duration::from_secs(60);
+        let pruner = conn
+            .pruner_watermark("pipeline", delay)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(pruner.wait_for_ms > 50_000 && pruner.wait_for_ms <= 60_000);
+        assert_eq!(pruner.pruner_hi, 0);
+        assert_eq!(pruner.reader_lo, 5);
+
+        assert!(conn.set_pruner_watermark("pipeline", 5).await.unwrap());
+        let pruner = conn
+            .pruner_watermark("pipeline", Duration::ZERO)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(pruner.wait_for_ms <= 0);
+        assert_eq!(pruner.pruner_hi, 5);
+    }
+
+    #[tokio::test]
+    async fn test_dead_letters() {
+        let (_dir, db) = setup().await;
+        let mut conn = db.connect().await.unwrap();
+
+        conn.record_dead_letter("pipeline", 7, "first")
+            .await
+            .unwrap();
+        conn.record_dead_letter("pipeline", 3, "other")
+            .await
+            .unwrap();
+        conn.record_dead_letter("pipeline", 7, "second")
+            .await
+            .unwrap();
+
+        let dead_letters = conn.dead_letters("pipeline").await.unwrap();
+        let summary: Vec<_> = dead_letters
+            .iter()
+            .map(|d| (d.checkpoint, d.error.as_str(), d.attempts))
+            .collect();
+        assert_eq!(summary, vec![(3, "other", 1), (7, "second", 2)]);
+
+        assert!(conn.delete_dead_letter("pipeline", 7).await.unwrap());
+        assert!(!conn.delete_dead_letter("pipeline", 7).await.unwrap());
+        assert_eq!(conn.dead_letters("pipeline").await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_transaction_rolls_back_on_error() {
+        let (_dir, db) = setup().await;
+
+        let result: anyhow::Result<()> = db
+            .transaction(|conn| {
+                async move {
+                    conn.set_committer_watermark("pipeline", committer_watermark(10))
+                        .await?;
+                    anyhow::bail!("Failure after write");
+                }
+                .scope_boxed()
+            })
+            .await;
+        assert!(result.is_err());
+
+        let mut conn = db.connect().await.unwrap();
+        assert!(
+            conn.committer_watermark("pipeline")
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        db.transaction(|conn| {
+            async move {
+                conn.set_committer_watermark("pipeline", committer_watermark(10))
+                    .await?;
+                Ok(())
+            }
+            .scope_boxed()
+        })
+        .await
+        .unwrap();
+
+        let watermark = conn.committer_watermark("pipeline").await.unwrap().unwrap();
+        assert_eq!(watermark.checkpoint_hi_inclusive, 10);
+    }
+}
@@ -1,6 +1,8 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+#[cfg(not(tidehunter))]
+use crate::consensus_store_tool::{ConsensusStoreCommand, execute_consensus_store_command};
 #[cfg(not(tidehunter))]
 use crate::db_tool::{DbToolCommand, execute_db_tool_command, print_db_all_tables};
 use crate::{
@@ -10,8 +12,10 @@ use crate::{
     restore_from_db_checkpoint,
 };
 use anyhow::Result;
+#[cfg(not(tidehunter))]
+use consensus_core::DagWindowEpoch;
 use consensus_core::storage::{Store, rocksdb_store::RocksDBStore};
-use consensus_core::{BlockAPI, CommitAPI, CommitRange};
+use consensus_core::{BlockAPI, CommitAPI, CommitRange, DagWindow};
 use futures::{StreamExt, future::join_all};
 use std::path::PathBuf;
 use std::{collections::BTreeMap, env, sync::Arc};
@@ -56,6 +60,48 @@ pub enum ToolCommand {
         end_commit: Option<u32>,
     },
 
+    /// Inspect blocks, commits, commit votes and rejected transactions in a consensus db.
+    /// The db is opened read only. Output is JSON, or a Graphviz DOT rendering of the DAG.
+    #[cfg(not(tidehunter))]
+    #[command(name = "inspect-consensus-store")]
+    InspectConsensusStore {
+        #[arg(long = "db-path")]
+        db_path: String,
+        #[command(subcommand)]
+        cmd: ConsensusStoreCommand,
+    },
+
+    /// Record a range of consensus commits, with the DAG they were sequenced from, into a file
+    /// that can be replayed offline with `replay-consensus-dag-window`. The db is opened read only.
+    #[cfg(not(tidehunter))]
+    #[command(name = "record-consensus-dag-window")]
+    RecordConsensusDagWindow {
+        #[arg(long = "db-path")]
+        db_path: String,
+        /// JSON file with the committee, own authority index, protocol version, chain and
+        /// leader scoring strategy of the epoch of the consensus db.
+        #[arg(long = "epoch-path")]
+        epoch_path: PathBuf,
+        #[arg(long = "start-commit")]
+        start_commit: u32,
+        #[arg(long = "end-commit")]
+        end_commit: u32,
+        /// Number of rounds above the last commit's leader to record, so that the replay
+        /// can decide the last leaders of the range.
+        #[arg(long = "extra-rounds", default_value_t = 10)]
+        extra_rounds: u32,
+        #[arg(long = "output")]
+        output: PathBuf,
+    },
+
+    /// Replay a recorded consensus DAG window through the committer, linearizer and
+    /// commit finalizer, and report where the replayed commits differ from the recorded ones.
+    #[command(name = "replay-consensus-dag-window")]
+    ReplayConsensusDagWindow {
+        #[arg(long = "input")]
+        input: PathBuf,
+    },
+
     /// Inspect if a specific object is or all gas objects owned by an address are locked by validators
     #[command(name = "locked-object")]
     LockedObject {
@@ -486,6 +532,52 @@ impl ToolCommand {
                     }
                 }
             }
+            #[cfg(not(tidehunter))]
+            ToolCommand::InspectConsensusStore { db_path, cmd } => {
+                execute_consensus_store_command(&db_path, cmd)?;
+            }
+            #[cfg(not(tidehunter))]
+            ToolCommand::RecordConsensusDagWindow {
+                db_path,
+                epoch_path,
+                start_commit,
+                end_commit,
+                extra_rounds,
+                output,
+            } => {
+                if end_commit < start_commit {
+                    anyhow::bail!("End commit {end_commit} is before start commit {start_commit}");
+                }
+                let epoch: DagWindowEpoch = serde_json::from_slice(&std::fs::read(&epoch_path)?)?;
+                let rocks_db_store = RocksDBStore::new_read_only(&db_path);
+                let window = DagWindow::record(
+                    &rocks_db_store,
+                    epoch,
+                    CommitRange::new(start_commit..=end_commit),
+                    extra_rounds,
+                )?;
+                std::fs::write(&output, window.serialize()?)?;
+                println!(
+                    "Recorded commits {start_commit}..={end_commit} and {} blocks to {:?}",
+                    window.num_blocks(),
+                    output
+                );
+            }
+            ToolCommand::ReplayConsensusDagWindow { input } => {
+                let window = DagWindow::deserialize(&std::fs::read(&input)?)?;
+                let range = window.commit_range()?;
+                let report = window.replay().await?;
+                println!(
+                    "Replayed commits {}..={}: {} matched, {} produced",
+                    range.start(),
+                    range.end(),
+                    report.matched_commits,
+                    report.produced_commits
+                );
+                if let Some(divergence) = report.divergence {
+                    anyhow::bail!("Replay diverged. {divergence}");
+                }
+            }
             ToolCommand::LockedObject {
                 id,
                 fullnode_rpc_url,
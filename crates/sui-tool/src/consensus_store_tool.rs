@@ -0,0 +1,316 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Inspection of the consensus store of a validator. The store is opened read only, so it
+//! can be inspected while the validator is running.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write as _;
+
+use anyhow::{anyhow, bail};
+use clap::{Parser, ValueEnum};
+use consensus_config::AuthorityIndex;
+use consensus_core::storage::{Store, rocksdb_store::RocksDBStore};
+use consensus_core::{BlockAPI, CommitAPI, CommitIndex, CommitRange, VerifiedBlock};
+use consensus_types::block::{BlockRef, Round, TransactionIndex};
+use serde::Serialize;
+
+/// Number of rounds listed when no end round is given.
+const DEFAULT_NUM_ROUNDS: Round = 100;
+
+/// Number of commits printed when no end commit is given.
+const DEFAULT_NUM_COMMITS: CommitIndex = 100;
+
+#[derive(Clone, Copy, Debug, Default, ValueEnum)]
+pub enum OutputFormat {
+    #[default]
+    Json,
+    /// Graphviz DOT rendering of the DAG formed by the blocks.
+    Dot,
+}
+
+#[derive(Parser)]
+#[command(rename_all = "kebab-case")]
+pub enum ConsensusStoreCommand {
+    /// List blocks by author and round.
+    Blocks(BlocksOptions),
+    /// Print commits with their leader and sub-DAG.
+    Commits(CommitsOptions),
+    /// Show the votes received by a commit.
+    CommitVotes(CommitIndexOptions),
+    /// Show the transactions rejected in a finalized commit.
+    RejectedTransactions(CommitIndexOptions),
+}
+
+#[derive(Parser)]
+#[command(rename_all = "kebab-case")]
+pub struct BlocksOptions {
+    /// Only list the blocks of this authority.
+    #[arg(long = "author")]
+    author: Option<u32>,
+    #[arg(long = "start-round", default_value_t = 0)]
+    start_round: Round,
+    /// Last round to list, inclusive. Defaults to listing 100 rounds.
+    #[arg(long = "end-round")]
+    end_round: Option<Round>,
+    #[arg(long = "format", value_enum, default_value_t)]
+    format: OutputFormat,
+}
+
+#[derive(Parser)]
+#[command(rename_all = "kebab-case")]
+pub struct CommitsOptions {
+    #[arg(long = "start-commit", default_value_t = 0)]
+    start_commit: CommitIndex,
+    /// Last commit to print, inclusive. Defaults to printing 100 commits.
+    #[arg(long = "end-commit")]
+    end_commit: Option<CommitIndex>,
+    #[arg(long = "format", value_enum, default_value_t)]
+    format: OutputFormat,
+}
+
+#[derive(Parser)]
+#[command(rename_all = "kebab-case")]
+pub struct CommitIndexOptions {
+    #[arg(long = "commit-index")]
+    commit_index: CommitIndex,
+}
+
+#[derive(Serialize)]
+struct BlockOutput {
+    reference: String,
+    round: Round,
+    author: u32,
+    timestamp_ms: u64,
+    ancestors: Vec<String>,
+    num_transactions: usize,
+    commit_votes: Vec<String>,
+}
+
+impl From<&VerifiedBlock> for BlockOutput {
+    fn from(block: &VerifiedBlock) -> Self {
+        Self {
+            reference: block.reference().to_string(),
+            round: block.round(),
+            author: block.author().value() as u32,
+            timestamp_ms: block.timestamp_ms(),
+            ancestors: block.ancestors().iter().map(|a| a.to_string()).collect(),
+            num_transactions: block.transactions().len(),
+            commit_votes: block
+                .commit_votes()
+                .iter()
+                .map(|vote| vote.to_string())
+                .collect(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct CommitOutput {
+    reference: String,
+    round: Round,
+    previous_digest: String,
+    timestamp_ms: u64,
+    leader: String,
+    sub_dag: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct CommitVotesOutput {
+    commit_index: CommitIndex,
+    votes: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct RejectedTransactionsOutput {
+    commit: String,
+    /// Indices of the rejected transactions, by block.
+    rejected_transactions: BTreeMap<String, Vec<TransactionIndex>>,
+}
+
+pub fn execute_consensus_store_command(
+    db_path: &str,
+    cmd: ConsensusStoreCommand,
+) -> anyhow::Result<()> {
+    let store = RocksDBStore::new_read_only(db_path);
+    let output = match cmd {
+        ConsensusStoreCommand::Blocks(options) => print_blocks(&store, options)?,
+        ConsensusStoreCommand::Commits(options) => print_commits(&store, options)?,
+        ConsensusStoreCommand::CommitVotes(options) => {
+            let votes = store.read_commit_votes(options.commit_index)?;
+            to_json(&CommitVotesOutput {
+                commit_index: options.commit_index,
+                votes: votes.iter().map(|vote| vote.to_string()).collect(),
+            })?
+        }
+        ConsensusStoreCommand::RejectedTransactions(options) => {
+            let commit = store
+                .scan_commits(CommitRange::new(
+                    options.commit_index..=options.commit_index,
+                ))?
+                .pop()
+                .ok_or_else(|| anyhow!("Commit {} not found", options.commit_index))?;
+            let commit_ref = commit.reference();
+            let Some(rejected) = store.read_rejected_transactions(commit_ref)? else {
+                bail!("Commit {commit_ref} is not finalized");
+            };
+            to_json(&RejectedTransactionsOutput {
+                commit: commit_ref.to_string(),
+                rejected_transactions: rejected
+                    .into_iter()
+                    .map(|(block_ref, indices)| (block_ref.to_string(), indices))
+                    .collect(),
+            })?
+        }
+    };
+    println!("{output}");
+    Ok(())
+}
+
+fn print_blocks(store: &RocksDBStore, options: BlocksOptions) -> anyhow::Result<String> {
+    let start_round = options.start_round;
+    let end_round = options
+        .end_round
+        .unwrap_or_else(|| start_round.saturating_add(DEFAULT_NUM_ROUNDS - 1));
+    if end_round < start_round {
+        bail!("End round {end_round} is before start round {start_round}");
+    }
+
+    let blocks = match options.author {
+        Some(author) => store.scan_blocks_by_author_in_range(
+            AuthorityIndex::for_scan(author),
+            start_round,
+            end_round,
+        )?,
+        None => store.scan_blocks_by_rounds(start_round, end_round)?,
+    };
+
+    match options.format {
+        OutputFormat::Json => to_json(&blocks.iter().map(BlockOutput::from).collect::<Vec<_>>()),
+        OutputFormat::Dot => Ok(render_dot(&blocks, &BTreeSet::new())),
+    }
+}
+
+fn print_commits(store: &RocksDBStore, options: CommitsOptions) -> anyhow::Result<String> {
+    let start_commit = options.start_commit;
+    let end_commit = options
+        .end_commit
+        .unwrap_or_else(|| start_commit.saturating_add(DEFAULT_NUM_COMMITS - 1));
+    if end_commit < start_commit {
+        bail!("End commit {end_commit} is before start commit {start_commit}");
+    }
+    let commits = store.scan_commits(CommitRange::new(start_commit..=end_commit))?;
+
+    match options.format {
+        OutputFormat::Json => to_json(
+            &commits
+                .iter()
+                .map(|commit| CommitOutput {
+                    reference: commit.reference().to_string(),
+                    round: commit.round(),
+                    previous_digest: commit.previous_digest().to_string(),
+                    timestamp_ms: commit.timestamp_ms(),
+                    leader: commit.leader().to_string(),
+                    sub_dag: commit.blocks().iter().map(|b| b.to_string()).collect(),
+                })
+                .collect::<Vec<_>>(),
+        ),
+        OutputFormat::Dot => {
+            let leaders = commits.iter().map(|commit| commit.leader()).collect();
+            let refs: Vec<BlockRef> = commits
+                .iter()
+                .flat_map(|commit| commit.blocks().iter().copied())
+                .collect();
+            let blocks = store
+                .read_blocks(&refs)?
+                .into_iter()
+                .zip(refs.iter())
+                .map(|(block, block_ref)| {
+                    block.ok_or_else(|| anyhow!("Committed block {block_ref} not found"))
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            Ok(render_dot(&blocks, &leaders))
+        }
+    }
+}
+
+fn to_json<T: Serialize>(value: &T) -> anyhow::Result<String> {
+    Ok(serde_json::to_string_pretty(value)?)
+}
+
+/// Renders the blocks as a Graphviz DOT graph, with blocks of the same round on the same
+/// rank and an edge from each block to its ancestors. Ancestors outside of the given blocks
+/// are not rendered. Leaders are highlighted.
+fn render_dot(blocks: &[VerifiedBlock], leaders: &BTreeSet<BlockRef>) -> String {
+    let included: BTreeSet<BlockRef> = blocks.iter().map(|block| block.reference()).collect();
+    let mut rounds: BTreeMap<Round, Vec<BlockRef>> = BTreeMap::new();
+    for block_ref in &included {
+        rounds.entry(block_ref.round).or_default().push(*block_ref);
+    }
+
+    let mut dot = String::from("digraph dag {\n    rankdir=BT;\n    node [shape=box];\n");
+    for (round, block_refs) in &rounds {
+        let _ = writeln!(dot, "    subgraph round_{round} {{\n        rank=same;");
+        for block_ref in block_refs {
+            let style = if leaders.contains(block_ref) {
+                ", style=filled, fillcolor=gold"
+            } else {
+                ""
+            };
+            let _ = writeln!(
+                dot,
+                "        \"{block_ref}\" [label=\"R{} A{}\\n{}\"{style}];",
+                block_ref.round,
+                block_ref.author,
+                short_digest(block_ref),
+            );
+        }
+        dot.push_str("    }\n");
+    }
+    for block in blocks {
+        for ancestor in block.ancestors() {
+            if included.contains(ancestor) {
+                let _ = writeln!(dot, "    \"{}\" -> \"{ancestor}\";", block.reference());
+            }
+        }
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+fn short_digest(block_ref: &BlockRef) -> String {
+    block_ref.digest.to_string().chars().take(8).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use consensus_core::TestBlock;
+
+    #[test]
+    fn test_render_dot() {
+        let genesis: Vec<VerifiedBlock> = (0..2)
+            .map(|author| VerifiedBlock::new_for_test(TestBlock::new(0, author).build()))
+            .collect();
+        let ancestors: Vec<BlockRef> = genesis.iter().map(|block| block.reference()).collect();
+        let block = VerifiedBlock::new_for_test(
+            TestBlock::new(1, 0)
+                .set_ancestors(ancestors.clone())
+                .build(),
+        );
+        // The second genesis block is not rendered, so the edge to it is dropped.
+        let blocks = vec![genesis[0].clone(), block.clone()];
+        let leaders = BTreeSet::from([block.reference()]);
+
+        let dot = render_dot(&blocks, &leaders);
+        assert!(dot.starts_with("digraph dag {"));
+        assert!(dot.contains(&format!(
+            "\"{}\" -> \"{}\";",
+            block.reference(),
+            ancestors[0]
+        )));
+        assert!(!dot.contains(&format!("-> \"{}\"", ancestors[1])));
+        assert_eq!(dot.matches("fillcolor=gold").count(), 1);
+        assert_eq!(dot.matches("rank=same").count(), 2);
+    }
+}
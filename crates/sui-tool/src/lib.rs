@@ -68,6 +68,8 @@ use typed_store::DBMetrics;
 
 pub mod commands;
 #[cfg(not(tidehunter))]
+pub mod consensus_store_tool;
+#[cfg(not(tidehunter))]
 pub mod db_tool;
 mod formal_snapshot_util;
 
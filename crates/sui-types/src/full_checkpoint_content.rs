@@ -1,9 +1,10 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
 
 use crate::base_types::{ExecutionData, ObjectID, ObjectRef};
+use crate::digests::TransactionDigest;
 use crate::effects::{TransactionEffects, TransactionEffectsAPI, TransactionEvents};
 use crate::messages_checkpoint::{CertifiedCheckpointSummary, CheckpointContents};
 use crate::object::Object;
@@ -284,6 +285,53 @@ impl Checkpoint {
         }
         eventually_removed_object_refs.into_values().collect()
     }
+
+    /// The sequence number of the first transaction in this checkpoint. This is derived from the
+    /// checkpoint's contents, rather than `transactions`, so it is correct even if `transactions`
+    /// only holds some of the checkpoint's transactions.
+    pub fn first_tx_sequence_number(&self) -> u64 {
+        self.summary.network_total_transactions - self.contents.size() as u64
+    }
+
+    /// The transactions in `transactions`, paired with their sequence numbers. If `transactions`
+    /// only holds some of the checkpoint's transactions, their positions are looked up in the
+    /// checkpoint's contents. Fails if a transaction is not in the contents.
+    pub fn transactions_with_sequence_numbers(
+        &self,
+    ) -> Result<impl Iterator<Item = (u64, &ExecutedTransaction)> + '_, StorageError> {
+        let first_tx = self.first_tx_sequence_number();
+        let offsets: Option<Vec<u64>> = if self.transactions.len() != self.contents.size() {
+            let positions: HashMap<TransactionDigest, u64> = self
+                .contents
+                .iter()
+                .enumerate()
+                .map(|(i, digests)| (digests.transaction, i as u64))
+                .collect();
+
+            let offsets = self
+                .transactions
+                .iter()
+                .map(|tx| {
+                    let digest = tx.effects.transaction_digest();
+                    positions.get(digest).copied().ok_or_else(|| {
+                        StorageError::custom(format!(
+                            "transaction {digest} is not in the contents of checkpoint {}",
+                            self.summary.sequence_number
+                        ))
+                    })
+                })
+                .collect::<Result<_, _>>()?;
+
+            Some(offsets)
+        } else {
+            None
+        };
+
+        Ok(self.transactions.iter().enumerate().map(move |(i, tx)| {
+            let offset = offsets.as_ref().map_or(i as u64, |offsets| offsets[i]);
+            (first_tx + offset, tx)
+        }))
+    }
 }
 
 impl ExecutedTransaction {
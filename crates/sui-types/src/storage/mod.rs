@@ -36,10 +36,17 @@ pub use read_store::CoinInfo;
 pub use read_store::DynamicFieldIndexInfo;
 pub use read_store::DynamicFieldKey;
 pub use read_store::EpochInfo;
+pub use read_store::EventIndexFilter;
+pub use read_store::GasPriceConfidence;
+pub use read_store::GasPriceSuggestion;
+pub use read_store::IndexedEventRecord;
+pub use read_store::IndexedTransactionRecord;
+pub use read_store::ObjectCongestionInfo;
 pub use read_store::OwnedObjectInfo;
 pub use read_store::ReadStore;
 pub use read_store::RpcIndexes;
 pub use read_store::RpcStateReader;
+pub use read_store::TransactionIndexFilter;
 pub use read_store::TransactionInfo;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
@@ -31,6 +31,13 @@ pub type BalanceIterator<'a> = Box<dyn Iterator<Item = Result<(StructTag, Balanc
 pub type PackageVersionsIterator<'a> =
     Box<dyn Iterator<Item = Result<(u64, ObjectID), TypedStoreError>> + 'a>;
 pub type AuthenticatedEventRecord = (u64, u64, u32, u32, crate::event::Event);
+/// A transaction found by [RpcIndexes::transactions_iter]: its checkpoint, its index in the
+/// checkpoint, and its digest.
+pub type IndexedTransactionRecord = (u64, u32, TransactionDigest);
+/// An event found by [RpcIndexes::events_iter]: the checkpoint and index in the checkpoint of the
+/// transaction that emitted it, its index in the transaction's events, the transaction's digest,
+/// and the event itself.
+pub type IndexedEventRecord = (u64, u32, u32, TransactionDigest, crate::event::Event);
 
 pub trait ReadStore: ObjectStore {
     //
@@ -652,6 +659,17 @@ pub trait RpcStateReader: ObjectStore + ReadStore + Send + Sync {
         }
     }
     fn get_struct_layout(&self, type_tag: &StructTag) -> Result<Option<MoveTypeLayout>>;
+
+    /// Suggest a gas price for `transaction`, if provided, that also accesses `object_ids`
+    /// mutably, based on recent congestion on those objects. Returns `None` if congestion is not
+    /// tracked by this reader.
+    fn get_gas_price_suggestion(
+        &self,
+        _transaction: Option<&TransactionData>,
+        _object_ids: &[ObjectID],
+    ) -> Result<Option<GasPriceSuggestion>> {
+        Ok(None)
+    }
 }
 
 pub type DynamicFieldIteratorItem = Result<DynamicFieldKey, TypedStoreError>;
@@ -703,6 +721,63 @@ pub trait RpcIndexes: Send + Sync {
         end_checkpoint: u64,
         limit: u32,
     ) -> Result<Box<dyn Iterator<Item = Result<AuthenticatedEventRecord, TypedStoreError>> + '_>>;
+
+    /// Iterate over the transactions matching `filter`, in checkpoint order (or reverse checkpoint
+    /// order if `descending`), starting from the transaction at `cursor` (a checkpoint and an
+    /// index in that checkpoint), inclusive.
+    fn transactions_iter(
+        &self,
+        filter: TransactionIndexFilter,
+        cursor: Option<(u64, u32)>,
+        descending: bool,
+    ) -> Result<Box<dyn Iterator<Item = Result<IndexedTransactionRecord, TypedStoreError>> + '_>>;
+
+    /// Iterate over the events matching `filter`, in checkpoint order (or reverse checkpoint order
+    /// if `descending`), starting from the event at `cursor` (a checkpoint, an index in that
+    /// checkpoint, and an index in that transaction's events), inclusive.
+    fn events_iter(
+        &self,
+        filter: EventIndexFilter,
+        cursor: Option<(u64, u32, u32)>,
+        descending: bool,
+    ) -> Result<Box<dyn Iterator<Item = Result<IndexedEventRecord, TypedStoreError>> + '_>>;
+}
+
+/// A condition that transactions are indexed by.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum TransactionIndexFilter {
+    /// The transaction was sent by this address.
+    Sender(SuiAddress),
+    /// The transaction was sent or sponsored by this address, or touched an object owned by this
+    /// address (before or after the transaction).
+    AffectedAddress(SuiAddress),
+    /// The transaction created, mutated, wrapped, unwrapped or deleted this object.
+    AffectedObject(ObjectID),
+    /// The transaction calls a function in this package, and in `module` and `function`, if they
+    /// are set. `function` is only set if `module` is.
+    MoveCall {
+        package: ObjectID,
+        module: Option<String>,
+        function: Option<String>,
+    },
+    /// The transaction emitted an event of this type. If the type has no type parameters, events
+    /// with any instantiation of the type match.
+    EventType(StructTag),
+}
+
+/// A condition that events are indexed by.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum EventIndexFilter {
+    /// The event was emitted by a transaction sent by this address.
+    Sender(SuiAddress),
+    /// The event was emitted by a function in this package, and in `module`, if it is set.
+    EmittingModule {
+        package: ObjectID,
+        module: Option<String>,
+    },
+    /// The event is of this type. If the type has no type parameters, events with any
+    /// instantiation of the type match.
+    EventType(StructTag),
 }
 
 #[derive(Clone, Serialize, Deserialize, Debug)]
@@ -799,3 +874,46 @@ pub struct EpochInfo {
     // pub end_of_epoch_transaction: Option<TransactionDigest>,
     // pub epoch_commitments: Vec<sui_types::messages_checkpoint::CheckpointCommitment>,
 }
+
+/// The most object IDs that a gas price suggestion can be requested for, on top of the shared
+/// objects accessed by the transaction it is requested for.
+pub const MAX_GAS_PRICE_SUGGESTION_OBJECTS: usize = 100;
+
+/// How likely a suggested gas price is to get a transaction executed without being cancelled due
+/// to congestion, ordered from least to most confident.
+#[derive(Clone, Copy, Serialize, Deserialize, Eq, PartialEq, Ord, PartialOrd, Debug)]
+pub enum GasPriceConfidence {
+    /// An object only saw cancellations in the last checkpoint it was congested in, so the
+    /// suggestion (just above the highest cancelled price) is a lower bound on the clearing
+    /// price.
+    Low,
+    /// An object saw both cancellations and executions in the last checkpoint it was congested
+    /// in, so the suggestion is a price that recently cleared.
+    Medium,
+    /// None of the objects are congested, so the reference gas price is enough.
+    High,
+}
+
+/// Recent congestion on a shared object, as observed in executed checkpoints.
+#[derive(Clone, Serialize, Deserialize, Eq, PartialEq, Debug)]
+pub struct ObjectCongestionInfo {
+    pub object_id: ObjectID,
+    /// The gas price a transaction accessing this object mutably should pay, if the object is
+    /// currently congested.
+    pub suggested_gas_price: Option<u64>,
+    pub confidence: GasPriceConfidence,
+    pub last_cancellation_timestamp_ms: Option<u64>,
+    pub highest_cancelled_gas_price: Option<u64>,
+    pub last_success_timestamp_ms: Option<u64>,
+    pub lowest_executed_gas_price: Option<u64>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Eq, PartialEq, Debug)]
+pub struct GasPriceSuggestion {
+    /// The suggested gas price, which is never below the reference gas price.
+    pub gas_price: u64,
+    pub reference_gas_price: u64,
+    /// The lowest confidence across all objects.
+    pub confidence: GasPriceConfidence,
+    pub objects: Vec<ObjectCongestionInfo>,
+}
@@ -3,6 +3,7 @@
 
 use serde::{Deserialize, Serialize, de::Deserializer};
 use serde_with::serde_as;
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
 // These values set to loosely attempt to limit
@@ -76,6 +77,12 @@ pub struct TrafficControlReconfigParams {
     pub error_threshold: Option<u64>,
     pub spam_threshold: Option<u64>,
     pub dry_run: Option<bool>,
+    /// If set, replaces the spam and error policies (discarding their
+    /// state), blocklist TTLs, spam sample rate and dry run mode before
+    /// the fields above are applied. The remaining fields of the config
+    /// only take effect on restart.
+    #[serde(default)]
+    pub policy_config: Option<PolicyConfig>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -218,6 +225,167 @@ fn default_sketch_tolerance() -> f64 {
     DEFAULT_SKETCH_TOLERANCE
 }
 
+/// Relative cost of a tally, keyed by request type (the handler name on
+/// validators, e.g. `object_info_impl`, or the JSON-RPC method name on
+/// fullnodes) and by error type (the `String` in `TrafficTally::error_info`).
+/// The cost of a tally is the product of the weight for its request type
+/// and, if the request errored, the weight for its error type. Types that
+/// are not listed default to a weight of 1.0.
+#[serde_as]
+#[derive(Clone, Debug, Deserialize, Serialize, Default, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub struct TallyWeights {
+    #[serde(default)]
+    pub request_types: BTreeMap<String, f64>,
+    #[serde(default)]
+    pub error_types: BTreeMap<String, f64>,
+}
+
+impl TallyWeights {
+    pub fn cost(&self, request_type: Option<&str>, error_type: Option<&str>) -> f64 {
+        let request_weight = request_type
+            .and_then(|request_type| self.request_types.get(request_type))
+            .copied()
+            .unwrap_or(1.0);
+        let error_weight = error_type
+            .and_then(|error_type| self.error_types.get(error_type))
+            .copied()
+            .unwrap_or(1.0);
+        request_weight * error_weight
+    }
+}
+
+#[serde_as]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct TokenBucketConfig {
+    /// Maximum number of tokens a direct client may accumulate,
+    /// i.e. the largest burst that is allowed after a quiet period
+    #[serde(default = "default_bucket_capacity")]
+    pub client_capacity: f64,
+    /// Tokens added to a direct client's bucket per second
+    #[serde(default = "default_bucket_refill_rate")]
+    pub client_refill_rate: f64,
+    #[serde(default = "default_proxied_bucket_capacity")]
+    pub proxied_client_capacity: f64,
+    #[serde(default = "default_proxied_bucket_refill_rate")]
+    pub proxied_client_refill_rate: f64,
+    /// Maximum number of clients (of each type) for which buckets
+    /// are kept. When exceeded, the buckets of the clients charged least
+    /// recently are evicted first.
+    #[serde(default = "default_max_tracked_clients")]
+    pub max_tracked_clients: usize,
+    #[serde(default)]
+    pub weights: TallyWeights,
+}
+
+impl Default for TokenBucketConfig {
+    fn default() -> Self {
+        Self {
+            client_capacity: default_bucket_capacity(),
+            client_refill_rate: default_bucket_refill_rate(),
+            proxied_client_capacity: default_proxied_bucket_capacity(),
+            proxied_client_refill_rate: default_proxied_bucket_refill_rate(),
+            max_tracked_clients: default_max_tracked_clients(),
+            weights: TallyWeights::default(),
+        }
+    }
+}
+
+fn default_bucket_capacity() -> f64 {
+    // See `default_client_threshold`. Direct clients may be fullnodes
+    // proxying many well behaved clients, so default to a very high limit.
+    1_000_000.0
+}
+
+fn default_bucket_refill_rate() -> f64 {
+    100_000.0
+}
+
+fn default_proxied_bucket_capacity() -> f64 {
+    100.0
+}
+
+fn default_proxied_bucket_refill_rate() -> f64 {
+    10.0
+}
+
+fn default_max_tracked_clients() -> usize {
+    100_000
+}
+
+#[serde_as]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct SlidingWindowLogConfig {
+    /// Maximum total cost of tallies a direct client may accrue
+    /// within any window of `window_size_secs`
+    #[serde(default = "default_client_threshold")]
+    pub client_threshold: u64,
+    #[serde(default = "default_proxied_client_threshold")]
+    pub proxied_client_threshold: u64,
+    #[serde(default = "default_window_size_secs")]
+    pub window_size_secs: u64,
+    /// Maximum number of clients (of each type) for which logs are
+    /// kept. When exceeded, the clients with the oldest most recent
+    /// tally are evicted first.
+    #[serde(default = "default_max_tracked_clients")]
+    pub max_tracked_clients: usize,
+    #[serde(default)]
+    pub weights: TallyWeights,
+}
+
+impl Default for SlidingWindowLogConfig {
+    fn default() -> Self {
+        Self {
+            client_threshold: default_client_threshold(),
+            proxied_client_threshold: default_proxied_client_threshold(),
+            window_size_secs: default_window_size_secs(),
+            max_tracked_clients: default_max_tracked_clients(),
+            weights: TallyWeights::default(),
+        }
+    }
+}
+
+/// How a composite policy combines the responses of its enforcing
+/// (i.e. non dry-run) child policies.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum CompositeMode {
+    /// Block a client only if every enforcing child policy blocks it
+    All,
+    /// Block a client if any enforcing child policy blocks it
+    #[default]
+    Any,
+    /// Use the response of the first enforcing child policy, in
+    /// declaration order, that blocks anything
+    FirstMatch,
+}
+
+#[serde_as]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CompositeChildConfig {
+    /// Used to label the metrics of this child policy, and
+    /// must therefore be unique within the composite policy
+    pub name: String,
+    pub policy_type: PolicyType,
+    /// If set, the child policy sees all tallies and reports what it
+    /// would block through metrics, but its responses are not used
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[serde_as]
+#[derive(Clone, Debug, Deserialize, Serialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct CompositeConfig {
+    #[serde(default)]
+    pub mode: CompositeMode,
+    #[serde(default)]
+    pub policies: Vec<CompositeChildConfig>,
+}
+
 // Serializable representation of policy types, used in config
 // in order to easily change in tests or to killswitch
 #[derive(Clone, Serialize, Deserialize, Debug, Default)]
@@ -232,6 +400,25 @@ pub enum PolicyType {
     #[serde(rename = "freq-threshold", alias = "FreqThreshold")]
     FreqThreshold(FreqThresholdConfig),
 
+    /// Keeps a token bucket per client which is refilled at a constant rate
+    /// and drained by the weighted cost of each tally. Blocks the client once
+    /// its bucket is empty. Tolerates bursts up to the bucket capacity.
+    #[serde(rename = "token-bucket", alias = "TokenBucket")]
+    TokenBucket(TokenBucketConfig),
+
+    /// Keeps an exact log of weighted tallies per client and blocks the client
+    /// once the total cost within the trailing `window_size_secs` reaches the
+    /// threshold. Catches slow, steady clients that stay under sketch noise.
+    #[serde(rename = "sliding-window-log", alias = "SlidingWindowLog")]
+    SlidingWindowLog(SlidingWindowLogConfig),
+
+    /// Evaluates each child policy, in order, on every tally and combines
+    /// their responses according to `mode`. Child policies may individually
+    /// be run in dry-run mode, e.g. to trial a new policy alongside the one
+    /// currently enforcing.
+    #[serde(rename = "composite", alias = "Composite")]
+    Composite(CompositeConfig),
+
     /* Below this point are test policies, and thus should not be used in production */
     ///
     /// Simple policy that adds connection_ip to blocklist when the same connection_ip
@@ -276,6 +463,35 @@ pub struct PolicyConfig {
     /// and any blocklist related configuration will be ignored.
     #[serde(default)]
     pub allow_list: Option<Vec<String>>,
+    /// If set, blocklists are snapshotted to disk and restored on
+    /// startup, and optionally shared with other nodes.
+    #[serde(default)]
+    pub blocklist_persistence: Option<BlocklistPersistenceConfig>,
+}
+
+#[serde_as]
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct BlocklistPersistenceConfig {
+    /// File to which the blocklists are periodically snapshotted,
+    /// and from which they are restored on startup
+    pub snapshot_path: PathBuf,
+    #[serde(default = "default_blocklist_snapshot_interval_secs")]
+    pub snapshot_interval_secs: u64,
+    /// Directory shared between a fleet of nodes, e.g. one that operators
+    /// keep in sync. Every `*.json` file in it is periodically imported
+    /// into the blocklists.
+    #[serde(default)]
+    pub shared_dir: Option<PathBuf>,
+    /// If set along with `shared_dir`, the blocklists of this node are
+    /// exported to `<shared_dir>/<export_name>.json`. Must be unique
+    /// within the fleet.
+    #[serde(default)]
+    pub export_name: Option<String>,
+}
+
+fn default_blocklist_snapshot_interval_secs() -> u64 {
+    30
 }
 
 impl Default for PolicyConfig {
@@ -290,6 +506,7 @@ impl Default for PolicyConfig {
             spam_sample_rate: default_spam_sample_rate(),
             dry_run: default_dry_run(),
             allow_list: None,
+            blocklist_persistence: None,
         }
     }
 }
@@ -0,0 +1,198 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Call tree of a trace with the number of instructions executed by each function, rendered
+//! either as an indented summary or as folded stacks for `flamegraph.pl`.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+
+use move_trace_format::format::TraceEvent;
+
+use super::function_name;
+
+const ROOT: usize = 0;
+
+#[derive(Default)]
+struct CallNode {
+    name: String,
+    parent: usize,
+    calls: u64,
+    self_instructions: u64,
+    children: Vec<usize>,
+}
+
+pub(crate) struct CallTree {
+    /// Call paths as a tree, the root is a placeholder for the transaction.
+    nodes: Vec<CallNode>,
+    child_index: BTreeMap<(usize, String), usize>,
+    stack: Vec<usize>,
+    use_long_function_name: bool,
+}
+
+#[derive(Default)]
+struct FunctionTotals {
+    calls: u64,
+    self_instructions: u64,
+}
+
+impl CallTree {
+    pub(crate) fn new(use_long_function_name: bool) -> Self {
+        Self {
+            nodes: vec![CallNode::default()],
+            child_index: BTreeMap::new(),
+            stack: vec![],
+            use_long_function_name,
+        }
+    }
+
+    pub(crate) fn process(&mut self, event: &TraceEvent) {
+        match event {
+            TraceEvent::OpenFrame { frame, .. } => {
+                let name = function_name(frame, self.use_long_function_name);
+                let parent = self.current();
+                let node = match self.child_index.get(&(parent, name.clone())) {
+                    Some(node) => *node,
+                    None => {
+                        let node = self.nodes.len();
+                        self.nodes.push(CallNode {
+                            name: name.clone(),
+                            parent,
+                            ..Default::default()
+                        });
+                        self.nodes[parent].children.push(node);
+                        self.child_index.insert((parent, name), node);
+                        node
+                    }
+                };
+                self.nodes[node].calls += 1;
+                self.stack.push(node);
+            }
+            TraceEvent::CloseFrame { .. } => {
+                self.stack.pop();
+            }
+            TraceEvent::Instruction { .. } => {
+                let node = self.current();
+                self.nodes[node].self_instructions += 1;
+            }
+            TraceEvent::Effect(_) | TraceEvent::External(_) => (),
+        }
+    }
+
+    fn current(&self) -> usize {
+        self.stack.last().copied().unwrap_or(ROOT)
+    }
+
+    /// Instructions executed by each node and its callees.
+    fn inclusive_instructions(&self) -> Vec<u64> {
+        let mut inclusive: Vec<u64> = self.nodes.iter().map(|n| n.self_instructions).collect();
+        // Children are always created after their parent.
+        for node in (1..self.nodes.len()).rev() {
+            inclusive[self.nodes[node].parent] += inclusive[node];
+        }
+        inclusive
+    }
+
+    /// Renders the call tree, followed by the functions sorted by the number of instructions
+    /// they executed themselves.
+    pub(crate) fn render_summary(&self) -> String {
+        let inclusive = self.inclusive_instructions();
+        let mut out = format!("Total instructions: {}\n\n", inclusive[ROOT]);
+        let mut to_visit: Vec<(usize, usize)> = self.nodes[ROOT]
+            .children
+            .iter()
+            .rev()
+            .map(|child| (*child, 0))
+            .collect();
+        while let Some((node, depth)) = to_visit.pop() {
+            let CallNode {
+                name,
+                calls,
+                self_instructions,
+                children,
+                ..
+            } = &self.nodes[node];
+            let _ = writeln!(
+                out,
+                "{:indent$}{name} [calls: {calls}, instructions: {}, self: {self_instructions}]",
+                "",
+                inclusive[node],
+                indent = depth * 2,
+            );
+            to_visit.extend(children.iter().rev().map(|child| (*child, depth + 1)));
+        }
+
+        let mut totals: BTreeMap<&str, FunctionTotals> = BTreeMap::new();
+        for node in &self.nodes[1..] {
+            let entry = totals.entry(node.name.as_str()).or_default();
+            entry.calls += node.calls;
+            entry.self_instructions += node.self_instructions;
+        }
+        let mut totals: Vec<_> = totals.into_iter().collect();
+        totals.sort_by(|(_, a), (_, b)| b.self_instructions.cmp(&a.self_instructions));
+
+        let _ = writeln!(out, "\n{:>12} {:>8}  function", "self", "calls");
+        for (name, totals) in totals {
+            let _ = writeln!(
+                out,
+                "{:>12} {:>8}  {name}",
+                totals.self_instructions, totals.calls
+            );
+        }
+        out
+    }
+
+    /// Renders one line per call path with the instructions executed at its top, the input
+    /// format of `flamegraph.pl`.
+    pub(crate) fn render_folded(&self) -> String {
+        let mut out = String::new();
+        for (node, call) in self.nodes.iter().enumerate().skip(1) {
+            if call.self_instructions == 0 {
+                continue;
+            }
+            let mut path = vec![];
+            let mut current = node;
+            while current != ROOT {
+                path.push(self.nodes[current].name.as_str());
+                current = self.nodes[current].parent;
+            }
+            path.reverse();
+            let _ = writeln!(out, "{} {}", path.join(";"), call.self_instructions);
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trace_analysis_commands::tests::{close_frame, instruction, open_frame};
+
+    #[test]
+    fn test_call_tree() {
+        let mut tree = CallTree::new(false);
+        let events = [
+            open_frame(0, "m", "entry"),
+            instruction(0),
+            open_frame(2, "m", "helper"),
+            instruction(0),
+            instruction(1),
+            close_frame(2),
+            open_frame(6, "m", "helper"),
+            instruction(0),
+            instruction(1),
+            close_frame(6),
+            instruction(1),
+            close_frame(0),
+        ];
+        for event in &events {
+            tree.process(event);
+        }
+
+        assert_eq!(tree.render_folded(), "m::entry 2\nm::entry;m::helper 4\n");
+        let summary = tree.render_summary();
+        assert!(summary.starts_with("Total instructions: 6\n"));
+        assert!(summary.contains("m::entry [calls: 1, instructions: 6, self: 2]\n"));
+        assert!(summary.contains("  m::helper [calls: 2, instructions: 4, self: 4]\n"));
+    }
+}
@@ -0,0 +1,314 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Line and instruction coverage of a trace. Executed instructions are mapped to source lines
+//! with the source maps of a built package. Line coverage is rendered in the LCOV format, and
+//! instruction coverage as JSON.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use anyhow::anyhow;
+use move_binary_format::CompiledModule;
+use move_binary_format::file_format::FunctionDefinitionIndex;
+use move_bytecode_source_map::source_map::SourceMap;
+use move_bytecode_source_map::utils::source_map_from_file;
+use move_command_line_common::files::FileHash;
+use move_core_types::account_address::AccountAddress;
+use move_core_types::language_storage::ModuleId;
+use move_trace_format::format::TraceEvent;
+use serde::Serialize;
+
+/// The modules, source maps and sources found in the build output of a package.
+pub(crate) struct PackageSources {
+    modules: Vec<ModuleSources>,
+    files: BTreeMap<FileHash, SourceFile>,
+}
+
+struct ModuleSources {
+    address: AccountAddress,
+    name: String,
+    source_map: SourceMap,
+    module: Option<CompiledModule>,
+}
+
+struct SourceFile {
+    path: PathBuf,
+    /// Byte offset of the start of each line.
+    line_starts: Vec<usize>,
+}
+
+impl SourceFile {
+    fn new(path: PathBuf, contents: &str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(contents.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        Self { path, line_starts }
+    }
+
+    /// The 1-based line of a byte offset.
+    fn line(&self, offset: usize) -> usize {
+        self.line_starts.partition_point(|start| *start <= offset)
+    }
+}
+
+impl PackageSources {
+    /// Loads all compiled modules (`bytecode_modules`), source maps (`source_maps`) and Move
+    /// sources found under the directory, including those of dependencies.
+    pub(crate) fn load(build_dir: &Path) -> anyhow::Result<Self> {
+        let mut source_maps = BTreeMap::new();
+        let mut modules = BTreeMap::new();
+        let mut files = BTreeMap::new();
+        for path in files_in(build_dir)? {
+            let in_dir = |name: &str| path.components().any(|c| c.as_os_str() == name);
+            match path.extension().and_then(|ext| ext.to_str()) {
+                Some("mvsm" | "json") if in_dir("source_maps") => {
+                    let source_map = source_map_from_file(&path)?;
+                    let (address, name) = &source_map.module_name;
+                    source_maps.insert((*address, name.to_string()), source_map);
+                }
+                Some("mv") if in_dir("bytecode_modules") => {
+                    let module = CompiledModule::deserialize_with_defaults(&std::fs::read(&path)?)
+                        .map_err(|e| anyhow!("Failed to read module {}: {e}", path.display()))?;
+                    let id = module.self_id();
+                    modules.insert((*id.address(), id.name().to_string()), module);
+                }
+                Some("move") => {
+                    let contents = std::fs::read_to_string(&path)?;
+                    files.insert(FileHash::new(&contents), SourceFile::new(path, &contents));
+                }
+                _ => (),
+            }
+        }
+        if source_maps.is_empty() {
+            anyhow::bail!("No source maps found in {}", build_dir.display());
+        }
+
+        let modules = source_maps
+            .into_iter()
+            .map(|((address, name), source_map)| {
+                let module = modules.remove(&(address, name.clone()));
+                ModuleSources {
+                    address,
+                    name,
+                    source_map,
+                    module,
+                }
+            })
+            .collect();
+        Ok(Self { modules, files })
+    }
+
+    /// Finds a module by address and name. Published packages are usually built at a different
+    /// address than the one they run at, so a module whose name is unique in the build is
+    /// matched by name only.
+    fn find(&self, id: &ModuleId) -> Option<&ModuleSources> {
+        let name = id.name().as_str();
+        self.modules
+            .iter()
+            .find(|m| m.address == *id.address() && m.name == name)
+            .or_else(|| {
+                let mut by_name = self.modules.iter().filter(|m| m.name == name);
+                let module = by_name.next()?;
+                by_name.next().is_none().then_some(module)
+            })
+    }
+}
+
+fn files_in(dir: &Path) -> anyhow::Result<Vec<PathBuf>> {
+    let mut files = vec![];
+    let mut dirs = vec![dir.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        for entry in std::fs::read_dir(&dir)
+            .map_err(|e| anyhow!("Failed to read directory {}: {e}", dir.display()))?
+        {
+            let path = entry?.path();
+            if path.is_dir() {
+                dirs.push(path);
+            } else {
+                files.push(path);
+            }
+        }
+    }
+    Ok(files)
+}
+
+#[derive(Serialize)]
+pub(crate) struct FunctionCoverage {
+    module: String,
+    function: String,
+    /// Number of distinct instructions executed.
+    covered_instructions: usize,
+    /// Number of instructions of the function, if its compiled module was found.
+    total_instructions: Option<usize>,
+    /// Number of instructions executed, counting repeated executions.
+    executed_instructions: u64,
+}
+
+/// Execution counts of each instruction of the functions executed in a trace.
+#[derive(Default)]
+pub(crate) struct TraceCoverage {
+    stack: Vec<(ModuleId, u16, String)>,
+    executed: BTreeMap<(ModuleId, u16), (String, BTreeMap<u16, u64>)>,
+}
+
+impl TraceCoverage {
+    pub(crate) fn process(&mut self, event: &TraceEvent) {
+        match event {
+            TraceEvent::OpenFrame { frame, .. } => self.stack.push((
+                frame.module.clone(),
+                frame.binary_member_index,
+                frame.function_name.clone(),
+            )),
+            TraceEvent::CloseFrame { .. } => {
+                self.stack.pop();
+            }
+            TraceEvent::Instruction { pc, .. } => {
+                if let Some((module, index, name)) = self.stack.last() {
+                    let (_, pcs) = self
+                        .executed
+                        .entry((module.clone(), *index))
+                        .or_insert_with(|| (name.clone(), BTreeMap::new()));
+                    *pcs.entry(*pc).or_default() += 1;
+                }
+            }
+            TraceEvent::Effect(_) | TraceEvent::External(_) => (),
+        }
+    }
+
+    fn executed_modules(&self) -> Vec<&ModuleId> {
+        let mut modules: Vec<_> = self.executed.keys().map(|(module, _)| module).collect();
+        modules.dedup();
+        modules
+    }
+
+    /// The functions of a module executed in the trace, by function definition index.
+    fn executed_functions<'a>(
+        &'a self,
+        id: &ModuleId,
+    ) -> impl Iterator<Item = (u16, &'a (String, BTreeMap<u16, u64>))> {
+        self.executed
+            .range((id.clone(), 0)..=(id.clone(), u16::MAX))
+            .map(|((_, index), function)| (*index, function))
+    }
+
+    /// Instruction coverage of every function of the modules executed in the trace.
+    pub(crate) fn instruction_coverage(&self, sources: &PackageSources) -> Vec<FunctionCoverage> {
+        let mut coverage = vec![];
+        for id in self.executed_modules() {
+            let module_name = id.to_canonical_string(/* with_prefix */ true);
+            let compiled = sources.find(id).and_then(|m| m.module.as_ref());
+            if let Some(compiled) = compiled {
+                for (index, def) in compiled.function_defs().iter().enumerate() {
+                    let pcs = self.executed.get(&(id.clone(), index as u16));
+                    let handle = compiled.function_handle_at(def.function);
+                    coverage.push(FunctionCoverage {
+                        module: module_name.clone(),
+                        function: compiled.identifier_at(handle.name).to_string(),
+                        covered_instructions: pcs.map_or(0, |(_, pcs)| pcs.len()),
+                        total_instructions: def.code.as_ref().map(|code| code.code.len()),
+                        executed_instructions: pcs.map_or(0, |(_, pcs)| pcs.values().sum()),
+                    });
+                }
+            } else {
+                for (_, (function, pcs)) in self.executed_functions(id) {
+                    coverage.push(FunctionCoverage {
+                        module: module_name.clone(),
+                        function: function.clone(),
+                        covered_instructions: pcs.len(),
+                        total_instructions: None,
+                        executed_instructions: pcs.values().sum(),
+                    });
+                }
+            }
+        }
+        coverage
+    }
+
+    /// Line coverage of the modules executed in the trace, in the LCOV format. The hit count of
+    /// a line is the highest execution count of its instructions.
+    pub(crate) fn render_lcov(&self, sources: &PackageSources) -> String {
+        let mut lines: BTreeMap<&Path, BTreeMap<usize, u64>> = BTreeMap::new();
+        let mut record = |source_map: &SourceMap, index: u16, pc: u16, count: u64| {
+            let Ok(loc) = source_map.get_code_location(FunctionDefinitionIndex(index), pc) else {
+                return;
+            };
+            let Some(file) = sources.files.get(&loc.file_hash()) else {
+                return;
+            };
+            let hits = lines
+                .entry(file.path.as_path())
+                .or_default()
+                .entry(file.line(loc.start() as usize))
+                .or_default();
+            *hits = (*hits).max(count);
+        };
+
+        for id in self.executed_modules() {
+            let Some(module) = sources.find(id) else {
+                continue;
+            };
+            // Lines of instructions that were not executed are reported with no hits.
+            if let Some(compiled) = &module.module {
+                for (index, def) in compiled.function_defs().iter().enumerate() {
+                    let Some(code) = &def.code else {
+                        continue;
+                    };
+                    for pc in 0..code.code.len() {
+                        record(&module.source_map, index as u16, pc as u16, 0);
+                    }
+                }
+            }
+            for (index, (_, pcs)) in self.executed_functions(id) {
+                for (pc, count) in pcs {
+                    record(&module.source_map, index, *pc, *count);
+                }
+            }
+        }
+
+        let mut out = String::from("TN:\n");
+        for (path, lines) in lines {
+            let _ = writeln!(out, "SF:{}", path.display());
+            for (line, hits) in &lines {
+                let _ = writeln!(out, "DA:{line},{hits}");
+            }
+            let hit = lines.values().filter(|hits| **hits > 0).count();
+            let _ = writeln!(out, "LF:{}\nLH:{hit}\nend_of_record", lines.len());
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trace_analysis_commands::tests::{close_frame, instruction, open_frame};
+
+    #[test]
+    fn test_source_file_lines() {
+        let file = SourceFile::new(PathBuf::from("a.move"), "module a::a;\n\nfun f() {}\n");
+        assert_eq!(file.line(0), 1);
+        assert_eq!(file.line(12), 1);
+        assert_eq!(file.line(13), 2);
+        assert_eq!(file.line(14), 3);
+    }
+
+    #[test]
+    fn test_instruction_counts() {
+        let mut coverage = TraceCoverage::default();
+        let events = [
+            open_frame(0, "m", "f"),
+            instruction(0),
+            instruction(1),
+            instruction(0),
+            close_frame(0),
+        ];
+        for event in &events {
+            coverage.process(event);
+        }
+        let ((_, index), (name, pcs)) = coverage.executed.first_key_value().unwrap();
+        assert_eq!((*index, name.as_str()), (0, "f"));
+        assert_eq!(pcs, &BTreeMap::from([(0, 2), (1, 1)]));
+    }
+}
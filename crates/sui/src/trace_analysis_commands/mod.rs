@@ -0,0 +1,222 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+mod call_tree;
+mod coverage;
+mod object_access;
+
+use anyhow::anyhow;
+use clap::*;
+use move_trace_format::format::{Frame, MoveTraceReader};
+use move_vm_profiler::trace_converter::{GasProfiler, ProfilerConfig};
+use std::path::{Path, PathBuf};
+
+use call_tree::CallTree;
+use coverage::{PackageSources, TraceCoverage};
+use object_access::ObjectAccessReport;
+
+#[derive(Parser)]
+#[clap(rename_all = "kebab-case")]
+pub enum AnalyzeTraceCommand {
+    /// Generate a gas profile for the trace file compatible with the `speedscope.app` profiler.
+    GasProfile {
+        /// Whether function names should be fully qualified with their module and package
+        /// addresses or if only the function name should be used.
+        #[arg(long, short)]
+        use_long_function_name: bool,
+    },
+    /// Generate line coverage (LCOV) and instruction coverage (JSON) of the functions executed
+    /// in the trace, mapped to source with the source maps of a built package.
+    Coverage {
+        /// The build output of the package, e.g. `<package>/build`. The compiled modules, source
+        /// maps and sources of the package and its dependencies are read from it.
+        #[arg(long, short)]
+        build_dir: PathBuf,
+    },
+    /// Summarize the call tree of the trace with the number of instructions executed by each
+    /// function.
+    CallTree {
+        /// Whether function names should be fully qualified with their package address.
+        #[arg(long, short)]
+        use_long_function_name: bool,
+        /// Output folded stacks for `flamegraph.pl` instead of the summary.
+        #[arg(long)]
+        folded: bool,
+    },
+    /// Report the objects and dynamic fields that were loaded, read or written, and how often.
+    ObjectAccess,
+}
+
+impl AnalyzeTraceCommand {
+    pub async fn execute(
+        self,
+        path: PathBuf,
+        output_dir: Option<PathBuf>,
+    ) -> Result<(), anyhow::Error> {
+        let trace_file = std::fs::File::open(&path).map_err(|e| {
+            anyhow!(
+                "Failed to open trace file at {}: {e}",
+                path.to_string_lossy()
+            )
+        })?;
+        let trace_reader = MoveTraceReader::new(trace_file)
+            .map_err(|e| anyhow!("Failed to read trace file: {e}"))?;
+
+        match self {
+            AnalyzeTraceCommand::GasProfile {
+                use_long_function_name,
+            } => {
+                let mut profiler = GasProfiler::init(
+                    ProfilerConfig {
+                        output_dir,
+                        use_long_function_name,
+                    },
+                    path.to_string_lossy().to_string(),
+                );
+                profiler.generate_from_trace(trace_reader);
+                profiler.save_profile();
+            }
+            AnalyzeTraceCommand::Coverage { build_dir } => {
+                let sources = PackageSources::load(&build_dir)?;
+                let mut coverage = TraceCoverage::default();
+                for event in trace_reader {
+                    coverage.process(&event?);
+                }
+                save_output(
+                    &output_dir,
+                    &path,
+                    "line_coverage",
+                    "lcov",
+                    &coverage.render_lcov(&sources),
+                )?;
+                save_output(
+                    &output_dir,
+                    &path,
+                    "instruction_coverage",
+                    "json",
+                    &serde_json::to_string_pretty(&coverage.instruction_coverage(&sources))?,
+                )?;
+            }
+            AnalyzeTraceCommand::CallTree {
+                use_long_function_name,
+                folded,
+            } => {
+                let mut tree = CallTree::new(use_long_function_name);
+                for event in trace_reader {
+                    tree.process(&event?);
+                }
+                if folded {
+                    save_output(
+                        &output_dir,
+                        &path,
+                        "folded_stacks",
+                        "txt",
+                        &tree.render_folded(),
+                    )?;
+                } else {
+                    save_output(
+                        &output_dir,
+                        &path,
+                        "call_tree",
+                        "txt",
+                        &tree.render_summary(),
+                    )?;
+                }
+            }
+            AnalyzeTraceCommand::ObjectAccess => {
+                let mut report = ObjectAccessReport::default();
+                for event in trace_reader {
+                    report.process(&event?);
+                }
+                save_output(
+                    &output_dir,
+                    &path,
+                    "object_access",
+                    "json",
+                    &serde_json::to_string_pretty(&report.into_accesses())?,
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Name of the function of a frame, either `module::function` or qualified with the address of
+/// the package version that was executed.
+fn function_name(frame: &Frame, use_long_function_name: bool) -> String {
+    if use_long_function_name {
+        format!(
+            "{}::{}::{}",
+            frame.version_id.to_canonical_display(true),
+            frame.module.name(),
+            frame.function_name
+        )
+    } else {
+        format!("{}::{}", frame.module.name(), frame.function_name)
+    }
+}
+
+/// Writes the output of an analysis to `<output_dir>/<prefix>_<trace file name>.<extension>`.
+fn save_output(
+    output_dir: &Option<PathBuf>,
+    trace_path: &Path,
+    prefix: &str,
+    extension: &str,
+    contents: &str,
+) -> Result<(), anyhow::Error> {
+    let trace_name = trace_path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .map(|name| name.split_once('.').map_or(name, |(base, _)| base))
+        .unwrap_or("trace");
+    let mut path = output_dir.clone().unwrap_or_else(|| PathBuf::from("."));
+    std::fs::create_dir_all(&path)?;
+    path.push(format!("{prefix}_{trace_name}.{extension}"));
+
+    println!("Saving {prefix} to: {}", path.display());
+    std::fs::write(&path, contents).map_err(|e| anyhow!("Failed to write {}: {e}", path.display()))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use move_core_types::account_address::AccountAddress;
+    use move_core_types::identifier::Identifier;
+    use move_core_types::language_storage::ModuleId;
+    use move_trace_format::format::{Frame, TraceEvent, TraceIndex};
+
+    pub(crate) fn open_frame(frame_id: TraceIndex, module: &str, function: &str) -> TraceEvent {
+        TraceEvent::OpenFrame {
+            frame: Box::new(Frame {
+                frame_id,
+                function_name: function.to_string(),
+                module: ModuleId::new(AccountAddress::TWO, Identifier::new(module).unwrap()),
+                version_id: AccountAddress::TWO,
+                binary_member_index: 0,
+                type_instantiation: vec![],
+                parameters: vec![],
+                return_types: vec![],
+                locals_types: vec![],
+                is_native: false,
+            }),
+            gas_left: 0,
+        }
+    }
+
+    pub(crate) fn close_frame(frame_id: TraceIndex) -> TraceEvent {
+        TraceEvent::CloseFrame {
+            frame_id,
+            return_: vec![],
+            gas_left: 0,
+        }
+    }
+
+    pub(crate) fn instruction(pc: u16) -> TraceEvent {
+        TraceEvent::Instruction {
+            type_parameters: vec![],
+            pc,
+            gas_left: 0,
+            instruction: Box::new("NOP".to_string()),
+        }
+    }
+}
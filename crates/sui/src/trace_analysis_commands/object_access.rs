@@ -0,0 +1,211 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Report of the objects and dynamic fields accessed in a trace. An access is attributed to an
+//! object when the root value of the read or written location is the object, and a load is a
+//! reference to an object handed out by a native function, e.g. a dynamic field borrow.
+
+use std::collections::BTreeMap;
+
+use move_core_types::account_address::AccountAddress;
+use move_core_types::language_storage::StructTag;
+use move_trace_format::format::{Effect, TraceEvent};
+use move_trace_format::value::SerializableMoveValue;
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub(crate) struct ObjectAccess {
+    object_id: AccountAddress,
+    #[serde(rename = "type")]
+    type_: String,
+    dynamic_field: bool,
+    loads: u64,
+    reads: u64,
+    writes: u64,
+}
+
+#[derive(Default)]
+pub(crate) struct ObjectAccessReport {
+    objects: BTreeMap<AccountAddress, ObjectAccess>,
+}
+
+enum Access {
+    Load,
+    Read,
+    Write,
+}
+
+impl ObjectAccessReport {
+    pub(crate) fn process(&mut self, event: &TraceEvent) {
+        let TraceEvent::Effect(effect) = event else {
+            return;
+        };
+        match effect.as_ref() {
+            Effect::DataLoad(load) => self.record(&load.snapshot, Access::Load),
+            Effect::Read(read) => self.record(read.root_value_read.snapshot(), Access::Read),
+            Effect::Write(write) => {
+                self.record(write.root_value_after_write.snapshot(), Access::Write)
+            }
+            Effect::Pop(_) | Effect::Push(_) | Effect::ExecutionError(_) => (),
+        }
+    }
+
+    fn record(&mut self, value: &SerializableMoveValue, access: Access) {
+        let Some((object_id, type_)) = object_id(value) else {
+            return;
+        };
+        let entry = self
+            .objects
+            .entry(object_id)
+            .or_insert_with(|| ObjectAccess {
+                object_id,
+                type_: type_.to_canonical_string(/* with_prefix */ true),
+                dynamic_field: is_dynamic_field(type_),
+                loads: 0,
+                reads: 0,
+                writes: 0,
+            });
+        match access {
+            Access::Load => entry.loads += 1,
+            Access::Read => entry.reads += 1,
+            Access::Write => entry.writes += 1,
+        }
+    }
+
+    /// The accessed objects, most accessed first.
+    pub(crate) fn into_accesses(self) -> Vec<ObjectAccess> {
+        let mut accesses: Vec<_> = self.objects.into_values().collect();
+        accesses.sort_by_key(|a| std::cmp::Reverse(a.loads + a.reads + a.writes));
+        accesses
+    }
+}
+
+/// Returns the id and type of the value if it is an object, i.e. a struct with a `UID` as its
+/// first field.
+fn object_id(value: &SerializableMoveValue) -> Option<(AccountAddress, &StructTag)> {
+    let SerializableMoveValue::Struct(object) = value else {
+        return None;
+    };
+    let (name, SerializableMoveValue::Struct(uid)) = object.fields.first()? else {
+        return None;
+    };
+    if name.as_str() != "id" || !is_framework_type(&uid.type_, "object", "UID") {
+        return None;
+    }
+    let (_, SerializableMoveValue::Struct(id)) = uid.fields.first()? else {
+        return None;
+    };
+    let (_, SerializableMoveValue::Address(bytes)) = id.fields.first()? else {
+        return None;
+    };
+    Some((*bytes, &object.type_))
+}
+
+fn is_dynamic_field(type_: &StructTag) -> bool {
+    is_framework_type(type_, "dynamic_field", "Field")
+}
+
+fn is_framework_type(type_: &StructTag, module: &str, name: &str) -> bool {
+    type_.address == AccountAddress::TWO
+        && type_.module.as_str() == module
+        && type_.name.as_str() == name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use move_core_types::identifier::Identifier;
+    use move_trace_format::format::{DataLoad, Location, Read, RefType, TraceValue, Write};
+    use move_trace_format::value::SimplifiedMoveStruct;
+
+    fn struct_tag(address: AccountAddress, module: &str, name: &str) -> StructTag {
+        StructTag {
+            address,
+            module: Identifier::new(module).unwrap(),
+            name: Identifier::new(name).unwrap(),
+            type_params: vec![],
+        }
+    }
+
+    fn new_struct(
+        type_: StructTag,
+        fields: Vec<(&str, SerializableMoveValue)>,
+    ) -> SerializableMoveValue {
+        SerializableMoveValue::Struct(SimplifiedMoveStruct {
+            type_,
+            fields: fields
+                .into_iter()
+                .map(|(name, value)| (Identifier::new(name).unwrap(), value))
+                .collect(),
+        })
+    }
+
+    fn object(id: AccountAddress, type_: StructTag) -> SerializableMoveValue {
+        let id_value = new_struct(
+            struct_tag(AccountAddress::TWO, "object", "ID"),
+            vec![("bytes", SerializableMoveValue::Address(id))],
+        );
+        let uid = new_struct(
+            struct_tag(AccountAddress::TWO, "object", "UID"),
+            vec![("id", id_value)],
+        );
+        new_struct(
+            type_,
+            vec![("id", uid), ("value", SerializableMoveValue::U64(0))],
+        )
+    }
+
+    #[test]
+    fn test_object_access() {
+        let counter = object(
+            AccountAddress::from_suffix(0x10),
+            struct_tag(AccountAddress::from_suffix(0x1234), "counter", "Counter"),
+        );
+        let field = object(
+            AccountAddress::from_suffix(0x20),
+            struct_tag(AccountAddress::TWO, "dynamic_field", "Field"),
+        );
+        let read = |value: &SerializableMoveValue| {
+            TraceEvent::Effect(Box::new(Effect::Read(Read {
+                location: Location::Local(0, 0),
+                root_value_read: TraceValue::RuntimeValue {
+                    value: value.clone(),
+                },
+                moved: false,
+            })))
+        };
+        let events = [
+            read(&counter),
+            read(&counter),
+            TraceEvent::Effect(Box::new(Effect::Write(Write {
+                location: Location::Local(0, 0),
+                root_value_after_write: TraceValue::RuntimeValue {
+                    value: counter.clone(),
+                },
+            }))),
+            TraceEvent::Effect(Box::new(Effect::DataLoad(DataLoad {
+                ref_type: RefType::Imm,
+                location: Location::Global(3),
+                snapshot: field.clone(),
+            }))),
+            // Values that are not objects are ignored.
+            read(&SerializableMoveValue::U64(1)),
+        ];
+
+        let mut report = ObjectAccessReport::default();
+        for event in &events {
+            report.process(event);
+        }
+        let accesses = report.into_accesses();
+        assert_eq!(accesses.len(), 2);
+        assert_eq!(accesses[0].object_id, AccountAddress::from_suffix(0x10));
+        assert!(!accesses[0].dynamic_field);
+        assert_eq!(
+            (accesses[0].loads, accesses[0].reads, accesses[0].writes),
+            (0, 2, 1)
+        );
+        assert_eq!(accesses[1].object_id, AccountAddress::from_suffix(0x20));
+        assert!(accesses[1].dynamic_field);
+        assert_eq!(accesses[1].loads, 1);
+    }
+}
@@ -21,6 +21,8 @@ const DB_OPTIONS_CUSTOM_FUNCTION: &str = "default_options_override_fn";
 const DB_OPTIONS_RENAME: &str = "rename";
 // Deprecate a column family
 const DB_OPTIONS_DEPRECATE: &str = "deprecated";
+// Schema version of the table, used by typed_store::migration
+const DB_OPTIONS_SCHEMA_VERSION: &str = "schema_version";
 
 /// Options can either be simplified form or
 enum GeneralTableOptions {
@@ -45,6 +47,7 @@ fn extract_struct_info(input: ItemStruct) -> ExtractedStructInfo {
                 a.path.is_ident(DB_OPTIONS_CUSTOM_FUNCTION)
                     || a.path.is_ident(DB_OPTIONS_RENAME)
                     || a.path.is_ident(DB_OPTIONS_DEPRECATE)
+                    || a.path.is_ident(DB_OPTIONS_SCHEMA_VERSION)
             })
             .map(|a| (a.path.get_ident().unwrap().to_string(), a))
             .collect();
@@ -86,8 +89,29 @@ fn extract_struct_info(input: ItemStruct) -> ExtractedStructInfo {
                 if attrs.contains_key(DB_OPTIONS_DEPRECATE) {
                     deprecated_cfs.push(field_name.clone());
                 }
+                let schema_version = if let Some(version) = attrs.get(DB_OPTIONS_SCHEMA_VERSION) {
+                    match version
+                        .parse_meta()
+                        .expect("Cannot parse meta of attribute")
+                    {
+                        Meta::NameValue(val) => {
+                            if let Lit::Int(i) = val.lit {
+                                i.base10_parse::<u32>()
+                                    .expect("Schema version must be a u32")
+                            } else {
+                                panic!("Expected integer value for schema_version")
+                            }
+                        }
+                        _ => panic!("Expected integer value for schema_version"),
+                    }
+                } else {
+                    0
+                };
 
-                return ((field_name, cf_name, type_str), (inner_type, options));
+                return (
+                    (field_name, cf_name, type_str),
+                    (inner_type, options, schema_version),
+                );
             } else {
                 panic!("All struct members must be of type DBMap");
             }
@@ -110,7 +134,8 @@ fn extract_struct_info(input: ItemStruct) -> ExtractedStructInfo {
         panic!("Cannot derive on empty struct");
     };
 
-    let (inner_types, options): (Vec<_>, Vec<_>) = inner_types_with_opts.into_iter().unzip();
+    let (inner_types, options, schema_versions): (Vec<_>, Vec<_>, Vec<_>) =
+        inner_types_with_opts.into_iter().multiunzip();
 
     ExtractedStructInfo {
         field_names,
@@ -118,6 +143,7 @@ fn extract_struct_info(input: ItemStruct) -> ExtractedStructInfo {
         inner_types,
         derived_table_options: options,
         deprecated_cfs,
+        schema_versions,
     }
 }
 
@@ -178,11 +204,12 @@ struct ExtractedStructInfo {
     inner_types: Vec<AngleBracketedGenericArguments>,
     derived_table_options: Vec<GeneralTableOptions>,
     deprecated_cfs: Vec<Ident>,
+    schema_versions: Vec<u32>,
 }
 
 #[proc_macro_derive(
     DBMapUtils,
-    attributes(default_options_override_fn, rename, tidehunter)
+    attributes(default_options_override_fn, rename, schema_version, tidehunter)
 )]
 pub fn derive_dbmap_utils_general(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as ItemStruct);
@@ -201,6 +228,7 @@ pub fn derive_dbmap_utils_general(input: TokenStream) -> TokenStream {
         inner_types,
         derived_table_options,
         deprecated_cfs,
+        schema_versions,
     } = extract_struct_info(input.clone());
 
     let (key_names, value_names): (Vec<_>, Vec<_>) = inner_types
@@ -248,6 +276,16 @@ pub fn derive_dbmap_utils_general(input: TokenStream) -> TokenStream {
                     (stringify!(#cf_names).to_owned(), (stringify!(#key_names).to_owned(), stringify!(#value_names).to_owned())),
                 )*].into_iter().collect()
             }
+
+            /// Returns the schema version of each table, see `typed_store::migration`
+            pub fn table_descriptors() -> Vec<typed_store::migration::TableDescriptor> {
+                vec![#(
+                    typed_store::migration::TableDescriptor {
+                        name: stringify!(#cf_names),
+                        version: #schema_versions,
+                    },
+                )*]
+            }
         }
     };
 
@@ -530,6 +568,25 @@ pub fn derive_dbmap_utils_general(input: TokenStream) -> TokenStream {
                     }
                 }
 
+                /// Opens the tables in a new in-memory database, which has the consistency semantics
+                /// of RocksDB. Intended for tests.
+                #[allow(unused_parens)]
+                pub fn open_tables_in_memory(metric_conf: typed_store::rocks::MetricConf) -> Self {
+                    let db = typed_store::rocks::open_in_memory(metric_conf);
+                    let (
+                            #(
+                                #field_names
+                            ),*
+                    ) = (#(
+                            DBMap::#inner_types::reopen(&db, Some(stringify!(#cf_names)), &typed_store::rocks::ReadWriteOptions::default(), false).expect(&format!("Cannot open {} CF.", stringify!(#cf_names))[..])
+                        ),*);
+                    Self {
+                        #(
+                            #field_names,
+                        )*
+                    }
+                }
+
                 #[allow(unused_parens)]
                 pub fn open_tables_read_write_with_deprecation_option(
                     path: std::path::PathBuf,
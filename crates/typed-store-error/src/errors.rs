@@ -19,4 +19,6 @@ pub enum TypedStoreError {
     MetricsReporting,
     #[error("Transaction should be retried")]
     RetryableTransactionError,
+    #[error("schema migration error: {0}")]
+    SchemaMigrationError(String),
 }
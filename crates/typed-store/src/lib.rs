@@ -15,6 +15,7 @@ pub mod traits;
 pub use traits::{DbIterator, Map};
 pub mod memstore;
 pub mod metrics;
+pub mod migration;
 pub mod rocks;
 #[cfg(tidehunter)]
 pub mod tidehunter_util;
@@ -121,6 +122,8 @@ pub type StoreError = typed_store_error::TypedStoreError;
 ///
 /// 5. Other convenience features
 ///    `Tables::describe_tables` is used to get a list of the table names and key-value types as string in a BTreeMap
+///    `Tables::table_descriptors` returns the schema version of each table, set with `#[schema_version = N]`.
+///    See the `migration` module for changing the types of a table
 ///
 /// // Bad usage example
 /// // Structs fields most only be of type Store<K, V> or DMBap<K, V>
@@ -1,23 +1,94 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+//! In-memory storage backend, with the consistency semantics of the RocksDB backend:
+//! batches are written atomically, iterators and snapshots observe a point-in-time view
+//! of the data, and optimistic transactions fail to commit on conflicting writes.
+//!
+//! Column families are copy-on-write. Snapshots and iterators hold a reference to the
+//! column families at the time they were created, and a write only copies a column
+//! family while such a reference is alive.
+//!
+//! Like RocksDB, every write is assigned a sequence number. While transactions are open,
+//! the sequence number of the last write to each key is kept, so that a commit detects
+//! any write after its snapshot, even one that restored the value the transaction read.
+
 use bincode::Options;
 use serde::de::DeserializeOwned;
-use std::collections::{BTreeMap, Bound, HashMap};
+use std::collections::{BTreeMap, BTreeSet, Bound, HashMap};
 use std::sync::{Arc, RwLock};
 use typed_store_error::TypedStoreError;
 
-type InMemoryStoreInternal = Arc<RwLock<HashMap<String, BTreeMap<Vec<u8>, Vec<u8>>>>>;
+type ColumnFamilyData = Arc<BTreeMap<Vec<u8>, Vec<u8>>>;
+type InMemoryStoreInternal = Arc<RwLock<InMemoryState>>;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default)]
 pub struct InMemoryDB {
     data: InMemoryStoreInternal,
 }
 
+#[derive(Debug, Default)]
+struct InMemoryState {
+    column_families: HashMap<String, ColumnFamilyData>,
+    /// The sequence number of the last write.
+    sequence: u64,
+    /// The sequence number of the last write to each key, kept while transactions are open.
+    versions: HashMap<(String, Vec<u8>), u64>,
+    open_transactions: usize,
+}
+
+impl InMemoryState {
+    /// Starts a write, returning its sequence number.
+    fn next_sequence(&mut self) -> u64 {
+        self.sequence += 1;
+        self.sequence
+    }
+
+    /// Records that `key` was written at `sequence`, if an open transaction may need to know.
+    fn touch(&mut self, cf_name: &str, key: Vec<u8>, sequence: u64) {
+        if self.open_transactions > 0 {
+            self.versions.insert((cf_name.to_string(), key), sequence);
+        }
+    }
+
+    fn cf_mut(&mut self, cf_name: String) -> &mut BTreeMap<Vec<u8>, Vec<u8>> {
+        Arc::make_mut(self.column_families.entry(cf_name).or_default())
+    }
+}
+
 #[derive(Clone, Debug)]
 enum InMemoryChange {
     Delete((String, Vec<u8>)),
     Put((String, Vec<u8>, Vec<u8>)),
+    /// Deletes keys in `[from, to)`.
+    DeleteRange((String, Vec<u8>, Vec<u8>)),
+}
+
+impl InMemoryChange {
+    fn apply(self, state: &mut InMemoryState, sequence: u64) {
+        match self {
+            InMemoryChange::Delete((cf_name, key)) => {
+                state.touch(&cf_name, key.clone(), sequence);
+                state.cf_mut(cf_name).remove(&key);
+            }
+            InMemoryChange::Put((cf_name, key, value)) => {
+                state.touch(&cf_name, key.clone(), sequence);
+                state.cf_mut(cf_name).insert(key, value);
+            }
+            InMemoryChange::DeleteRange((cf_name, from, to)) => {
+                if from >= to {
+                    return;
+                }
+                let cf = state.cf_mut(cf_name.clone());
+                let mut deleted = cf.split_off(&from);
+                let mut kept = deleted.split_off(&to);
+                cf.append(&mut kept);
+                for key in deleted.into_keys() {
+                    state.touch(&cf_name, key, sequence);
+                }
+            }
+        }
+    }
 }
 
 #[derive(Clone, Debug, Default)]
@@ -44,12 +115,29 @@ impl InMemoryBatch {
             value.as_ref().to_vec(),
         )));
     }
+
+    /// Deletes keys in `[from, to)`, like a RocksDB range tombstone.
+    pub fn delete_range_cf<K: AsRef<[u8]>>(&mut self, cf_name: &str, from: K, to: K) {
+        self.data.push(InMemoryChange::DeleteRange((
+            cf_name.to_string(),
+            from.as_ref().to_vec(),
+            to.as_ref().to_vec(),
+        )));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
 }
 
 impl InMemoryDB {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
     pub fn get<K: AsRef<[u8]>>(&self, cf_name: &str, key: K) -> Option<Vec<u8>> {
         let data = self.data.read().expect("can't read data");
-        match data.get(cf_name) {
+        match data.column_families.get(cf_name) {
             Some(cf) => cf.get(key.as_ref()).cloned(),
             None => None,
         }
@@ -61,71 +149,429 @@ impl InMemoryDB {
         K: AsRef<[u8]>,
     {
         let data = self.data.read().expect("can't read data");
-        match data.get(cf_name) {
-            Some(cf) => keys
-                .into_iter()
-                .map(|k| cf.get(k.as_ref()).cloned())
-                .collect(),
-            None => vec![],
-        }
+        let cf = data.column_families.get(cf_name);
+        keys.into_iter()
+            .map(|k| cf.and_then(|cf| cf.get(k.as_ref()).cloned()))
+            .collect()
     }
 
     pub fn delete(&self, cf_name: &str, key: &[u8]) {
-        let mut data = self.data.write().expect("can't write data");
-        data.entry(cf_name.to_string()).or_default().remove(key);
+        self.apply(vec![InMemoryChange::Delete((
+            cf_name.to_string(),
+            key.to_vec(),
+        ))]);
     }
 
     pub fn put(&self, cf_name: &str, key: Vec<u8>, value: Vec<u8>) {
-        let mut data = self.data.write().expect("can't write data");
-        data.entry(cf_name.to_string())
-            .or_default()
-            .insert(key, value);
+        self.apply(vec![InMemoryChange::Put((cf_name.to_string(), key, value))]);
     }
 
+    /// Writes all changes of the batch atomically.
     pub fn write(&self, batch: InMemoryBatch) {
-        for change in batch.data {
-            match change {
-                InMemoryChange::Delete((cf_name, key)) => self.delete(&cf_name, &key),
-                InMemoryChange::Put((cf_name, key, value)) => self.put(&cf_name, key, value),
-            }
+        self.apply(batch.data);
+    }
+
+    fn apply(&self, changes: Vec<InMemoryChange>) {
+        let mut data = self.data.write().expect("can't write data");
+        let sequence = data.next_sequence();
+        for change in changes {
+            change.apply(&mut data, sequence);
         }
     }
 
     pub fn drop_cf(&self, name: &str) {
-        self.data.write().expect("can't write data").remove(name);
+        let mut data = self.data.write().expect("can't write data");
+        let sequence = data.next_sequence();
+        if let Some(cf) = data.column_families.remove(name) {
+            for key in cf.keys() {
+                data.touch(name, key.clone(), sequence);
+            }
+        }
+    }
+
+    /// Creates the column family if it does not exist yet.
+    pub fn create_cf(&self, name: &str) {
+        self.data
+            .write()
+            .expect("can't write data")
+            .column_families
+            .entry(name.to_string())
+            .or_default();
+    }
+
+    /// Returns true if the column family was created, or written to, since it was last dropped.
+    pub fn has_cf(&self, name: &str) -> bool {
+        self.data
+            .read()
+            .expect("can't read data")
+            .column_families
+            .contains_key(name)
+    }
+
+    /// Returns a point-in-time view of all column families.
+    pub fn snapshot(&self) -> InMemorySnapshot {
+        InMemorySnapshot::new(&self.data.read().expect("can't read data"))
+    }
+
+    /// Starts an optimistic transaction reading from a snapshot taken now.
+    pub fn transaction(&self) -> InMemoryTransaction {
+        let mut data = self.data.write().expect("can't write data");
+        data.open_transactions += 1;
+        InMemoryTransaction {
+            db: self.clone(),
+            snapshot: InMemorySnapshot::new(&data),
+            tracked_keys: BTreeSet::new(),
+            batch: InMemoryBatch::default(),
+        }
+    }
+
+    /// Iterates over keys in `[lower_bound, upper_bound)`, the same bounds as RocksDB
+    /// iterate bounds. The iterator observes the column family at the time of the call.
+    pub fn iterator<'a, K, V>(
+        &'a self,
+        cf_name: &str,
+        lower_bound: Option<Vec<u8>>,
+        upper_bound: Option<Vec<u8>>,
+        reverse: bool,
+    ) -> Box<dyn Iterator<Item = Result<(K, V), TypedStoreError>> + 'a>
+    where
+        K: DeserializeOwned + 'a,
+        V: DeserializeOwned + 'a,
+    {
+        self.snapshot()
+            .iterator(cf_name, lower_bound, upper_bound, reverse)
+    }
+}
+
+/// A point-in-time view of an `InMemoryDB`. Holding a snapshot does not block writes.
+#[derive(Clone, Debug)]
+pub struct InMemorySnapshot {
+    data: HashMap<String, ColumnFamilyData>,
+    /// The sequence number of the last write visible in the snapshot.
+    sequence: u64,
+}
+
+impl InMemorySnapshot {
+    fn new(state: &InMemoryState) -> Self {
+        Self {
+            data: state.column_families.clone(),
+            sequence: state.sequence,
+        }
+    }
+
+    pub fn get<K: AsRef<[u8]>>(&self, cf_name: &str, key: K) -> Option<Vec<u8>> {
+        self.data
+            .get(cf_name)
+            .and_then(|cf| cf.get(key.as_ref()).cloned())
+    }
+
+    pub fn multi_get<I, K>(&self, cf_name: &str, keys: I) -> Vec<Option<Vec<u8>>>
+    where
+        I: IntoIterator<Item = K>,
+        K: AsRef<[u8]>,
+    {
+        keys.into_iter().map(|k| self.get(cf_name, k)).collect()
     }
 
-    pub fn iterator<K, V>(
+    /// Iterates over keys in `[lower_bound, upper_bound)` of the snapshot.
+    pub fn iterator<'a, K, V>(
         &self,
         cf_name: &str,
         lower_bound: Option<Vec<u8>>,
         upper_bound: Option<Vec<u8>>,
         reverse: bool,
-    ) -> Box<dyn Iterator<Item = Result<(K, V), TypedStoreError>> + '_>
+    ) -> Box<dyn Iterator<Item = Result<(K, V), TypedStoreError>> + 'a>
     where
-        K: DeserializeOwned,
-        V: DeserializeOwned,
+        K: DeserializeOwned + 'a,
+        V: DeserializeOwned + 'a,
     {
         let config = bincode::DefaultOptions::new()
             .with_big_endian()
             .with_fixint_encoding();
-        let lower_bound = lower_bound.map(Bound::Included).unwrap_or(Bound::Unbounded);
-        let upper_bound = upper_bound.map(Bound::Included).unwrap_or(Bound::Unbounded);
+        let iter = InMemoryIter {
+            data: self.data.get(cf_name).cloned().unwrap_or_default(),
+            lower_bound: lower_bound.map(Bound::Included).unwrap_or(Bound::Unbounded),
+            upper_bound: upper_bound.map(Bound::Excluded).unwrap_or(Bound::Unbounded),
+            reverse,
+        };
+        // Like `SafeIter`, iteration stops at the first entry that fails to deserialize.
+        Box::new(iter.map_while(move |(raw_key, raw_value)| {
+            let key = config.deserialize(&raw_key).ok()?;
+            let value = bcs::from_bytes(&raw_value).ok()?;
+            Some(Ok((key, value)))
+        }))
+    }
+}
 
-        let data = self.data.read().expect("can't read data");
-        let mut section: Vec<_> = data
-            .get(cf_name)
-            .unwrap_or(&BTreeMap::new())
-            .range((lower_bound, upper_bound))
-            .map(|(k, v)| (k.clone(), v.clone()))
-            .collect();
-        if reverse {
-            section.reverse();
+/// Iterates lazily over a frozen column family. The position is kept as a bound, so
+/// that the iterator does not borrow from the column family.
+struct InMemoryIter {
+    data: ColumnFamilyData,
+    lower_bound: Bound<Vec<u8>>,
+    upper_bound: Bound<Vec<u8>>,
+    reverse: bool,
+}
+
+impl Iterator for InMemoryIter {
+    type Item = (Vec<u8>, Vec<u8>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let (Bound::Included(lower) | Bound::Excluded(lower), Bound::Excluded(upper)) =
+            (&self.lower_bound, &self.upper_bound)
+            && lower >= upper
+        {
+            return None;
         }
-        Box::new(section.into_iter().map(move |(raw_key, raw_value)| {
-            let key = config.deserialize(&raw_key).unwrap();
-            let value = bcs::from_bytes(&raw_value).unwrap();
-            Ok((key, value))
-        }))
+        let mut range = self
+            .data
+            .range((self.lower_bound.clone(), self.upper_bound.clone()));
+        let (key, value) = if self.reverse {
+            range.next_back()?
+        } else {
+            range.next()?
+        };
+        let item = (key.clone(), value.clone());
+        if self.reverse {
+            self.upper_bound = Bound::Excluded(item.0.clone());
+        } else {
+            self.lower_bound = Bound::Excluded(item.0.clone());
+        }
+        Some(item)
+    }
+}
+
+/// An optimistic transaction over an `InMemoryDB`. Reads observe the snapshot taken when
+/// the transaction started, writes are buffered until commit. The commit fails with
+/// `TypedStoreError::RetryableTransactionError` if any key read through `get_for_update`
+/// or written by the transaction was written by someone else after the snapshot, whatever
+/// the value written.
+pub struct InMemoryTransaction {
+    db: InMemoryDB,
+    snapshot: InMemorySnapshot,
+    tracked_keys: BTreeSet<(String, Vec<u8>)>,
+    batch: InMemoryBatch,
+}
+
+impl InMemoryTransaction {
+    pub fn snapshot(&self) -> &InMemorySnapshot {
+        &self.snapshot
+    }
+
+    /// Reads a key from the snapshot, without tracking it for conflicts.
+    pub fn get<K: AsRef<[u8]>>(&self, cf_name: &str, key: K) -> Option<Vec<u8>> {
+        self.snapshot.get(cf_name, key)
+    }
+
+    /// Reads a key from the snapshot, and fails the commit if the key is changed by
+    /// another writer before the transaction commits.
+    pub fn get_for_update<K: AsRef<[u8]>>(&mut self, cf_name: &str, key: K) -> Option<Vec<u8>> {
+        self.track(cf_name, key.as_ref());
+        self.snapshot.get(cf_name, key)
+    }
+
+    pub fn put_cf<K, V>(&mut self, cf_name: &str, key: K, value: V)
+    where
+        K: AsRef<[u8]>,
+        V: AsRef<[u8]>,
+    {
+        self.track(cf_name, key.as_ref());
+        self.batch.put_cf(cf_name, key, value);
+    }
+
+    pub fn delete_cf<K: AsRef<[u8]>>(&mut self, cf_name: &str, key: K) {
+        self.track(cf_name, key.as_ref());
+        self.batch.delete_cf(cf_name, key);
+    }
+
+    fn track(&mut self, cf_name: &str, key: &[u8]) {
+        self.tracked_keys
+            .insert((cf_name.to_string(), key.to_vec()));
+    }
+
+    /// Validates the tracked keys and writes the buffered changes atomically.
+    pub fn commit(mut self) -> Result<(), TypedStoreError> {
+        let mut data = self.db.data.write().expect("can't write data");
+        for key in std::mem::take(&mut self.tracked_keys) {
+            if data
+                .versions
+                .get(&key)
+                .is_some_and(|&written| written > self.snapshot.sequence)
+            {
+                return Err(TypedStoreError::RetryableTransactionError);
+            }
+        }
+        let sequence = data.next_sequence();
+        for change in std::mem::take(&mut self.batch.data) {
+            change.apply(&mut data, sequence);
+        }
+        Ok(())
+    }
+}
+
+impl Drop for InMemoryTransaction {
+    fn drop(&mut self) {
+        let mut data = self.db.data.write().expect("can't write data");
+        data.open_transactions -= 1;
+        // Versions are only needed to validate open transactions.
+        if data.open_transactions == 0 {
+            data.versions.clear();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::be_fix_int_ser;
+
+    const CF: &str = "cf";
+
+    fn key(i: u32) -> Vec<u8> {
+        be_fix_int_ser(&i)
+    }
+
+    fn value(s: &str) -> Vec<u8> {
+        bcs::to_bytes(s).unwrap()
+    }
+
+    fn collect(
+        iter: Box<dyn Iterator<Item = Result<(u32, String), TypedStoreError>> + '_>,
+    ) -> Vec<u32> {
+        iter.map(|item| item.unwrap().0).collect()
+    }
+
+    #[test]
+    fn test_iterator_bounds() {
+        let db = InMemoryDB::new();
+        for i in 1..10 {
+            db.put(CF, key(i), value(&i.to_string()));
+        }
+        // The upper bound is exclusive, like RocksDB iterate bounds.
+        let iter = db.iterator(CF, Some(key(3)), Some(key(6)), false);
+        assert_eq!(collect(iter), vec![3, 4, 5]);
+        let iter = db.iterator(CF, Some(key(3)), Some(key(6)), true);
+        assert_eq!(collect(iter), vec![5, 4, 3]);
+        let iter = db.iterator(CF, None, None, true);
+        assert_eq!(collect(iter), (1..10).rev().collect::<Vec<_>>());
+        let iter = db.iterator(CF, Some(key(6)), Some(key(3)), false);
+        assert!(collect(iter).is_empty());
+    }
+
+    #[test]
+    fn test_snapshot_consistency() {
+        let db = InMemoryDB::new();
+        for i in 1..5 {
+            db.put(CF, key(i), value("old"));
+        }
+        let snapshot = db.snapshot();
+        let mut iter = db.iterator::<u32, String>(CF, None, None, false);
+        assert_eq!(iter.next().unwrap().unwrap().0, 1);
+
+        let mut batch = InMemoryBatch::default();
+        batch.put_cf(CF, key(2), value("new"));
+        batch.put_cf(CF, key(10), value("new"));
+        batch.delete_range_cf(CF, key(3), key(5));
+        db.write(batch);
+
+        // Neither the snapshot nor the open iterator observe the batch.
+        assert_eq!(snapshot.get(CF, key(2)), Some(value("old")));
+        assert_eq!(snapshot.get(CF, key(10)), None);
+        assert_eq!(
+            iter.map(|item| item.unwrap()).collect::<Vec<_>>(),
+            vec![
+                (2, "old".to_string()),
+                (3, "old".to_string()),
+                (4, "old".to_string())
+            ]
+        );
+        let iter = db.iterator(CF, None, None, false);
+        assert_eq!(collect(iter), vec![1, 2, 10]);
+        assert_eq!(db.get(CF, key(2)), Some(value("new")));
+    }
+
+    #[test]
+    fn test_transaction_conflicts() {
+        let db = InMemoryDB::new();
+        db.put(CF, key(1), value("a"));
+
+        // Committing a transaction without conflicts writes all its changes.
+        let mut txn = db.transaction();
+        assert_eq!(txn.get_for_update(CF, key(1)), Some(value("a")));
+        txn.put_cf(CF, key(1), value("b"));
+        txn.put_cf(CF, key(2), value("b"));
+        // Writes are not visible before the commit.
+        assert_eq!(db.get(CF, key(2)), None);
+        txn.commit().unwrap();
+        assert_eq!(db.get(CF, key(1)), Some(value("b")));
+        assert_eq!(db.get(CF, key(2)), Some(value("b")));
+
+        // A key read for update and changed concurrently fails the commit.
+        let mut txn = db.transaction();
+        txn.get_for_update(CF, key(1));
+        txn.put_cf(CF, key(3), value("c"));
+        db.put(CF, key(1), value("c"));
+        assert_eq!(
+            txn.commit(),
+            Err(TypedStoreError::RetryableTransactionError)
+        );
+        assert_eq!(db.get(CF, key(3)), None);
+
+        // Concurrent writes to the same key conflict, including deletes.
+        let mut first = db.transaction();
+        let mut second = db.transaction();
+        first.delete_cf(CF, key(2));
+        second.put_cf(CF, key(2), value("d"));
+        first.commit().unwrap();
+        assert_eq!(
+            second.commit(),
+            Err(TypedStoreError::RetryableTransactionError)
+        );
+        assert_eq!(db.get(CF, key(2)), None);
+
+        // Plain reads are not tracked.
+        let mut txn = db.transaction();
+        assert_eq!(txn.get(CF, key(1)), Some(value("c")));
+        txn.put_cf(CF, key(4), value("e"));
+        db.put(CF, key(1), value("e"));
+        txn.commit().unwrap();
+    }
+
+    #[test]
+    fn test_transaction_conflicts_on_restored_values() {
+        let db = InMemoryDB::new();
+        db.put(CF, key(1), value("a"));
+
+        // A key changed and changed back after the snapshot still conflicts.
+        let mut txn = db.transaction();
+        assert_eq!(txn.get_for_update(CF, key(1)), Some(value("a")));
+        txn.put_cf(CF, key(2), value("b"));
+        db.put(CF, key(1), value("x"));
+        db.put(CF, key(1), value("a"));
+        assert_eq!(
+            txn.commit(),
+            Err(TypedStoreError::RetryableTransactionError)
+        );
+
+        // So does a missing key that is created and deleted again, including by a range delete.
+        let mut txn = db.transaction();
+        assert_eq!(txn.get_for_update(CF, key(3)), None);
+        db.put(CF, key(3), value("x"));
+        let mut batch = InMemoryBatch::default();
+        batch.delete_range_cf(CF, key(3), key(4));
+        db.write(batch);
+        assert_eq!(db.get(CF, key(3)), None);
+        assert_eq!(
+            txn.commit(),
+            Err(TypedStoreError::RetryableTransactionError)
+        );
+
+        // Writes made before the snapshot, or once no transaction is open, do not conflict.
+        db.put(CF, key(1), value("y"));
+        let mut txn = db.transaction();
+        txn.get_for_update(CF, key(1));
+        txn.put_cf(CF, key(1), value("z"));
+        txn.commit().unwrap();
+        assert_eq!(db.get(CF, key(1)), Some(value("z")));
+        assert!(db.data.read().unwrap().versions.is_empty());
     }
 }
@@ -964,12 +964,49 @@ impl WritePerfContextMetrics {
     }
 }
 
+#[derive(Debug)]
+pub struct MigrationMetrics {
+    pub migration_keys_migrated: IntCounterVec,
+    pub migration_status: IntGaugeVec,
+    pub migration_batch_latency_seconds: HistogramVec,
+}
+
+impl MigrationMetrics {
+    pub(crate) fn new(registry: &Registry) -> Self {
+        MigrationMetrics {
+            migration_keys_migrated: register_int_counter_vec_with_registry!(
+                "typed_store_migration_keys_migrated",
+                "Number of entries copied by a schema migration into the table",
+                &["db_name", "table"],
+                registry,
+            )
+            .unwrap(),
+            migration_status: register_int_gauge_vec_with_registry!(
+                "typed_store_migration_status",
+                "Status of the schema migration of the table: 0 = up to date, 1 = running, 2 = completed with rollback available",
+                &["db_name", "table"],
+                registry,
+            )
+            .unwrap(),
+            migration_batch_latency_seconds: register_histogram_vec_with_registry!(
+                "typed_store_migration_batch_latency_seconds",
+                "Time to migrate and write one batch of entries",
+                &["db_name", "table"],
+                LATENCY_SEC_BUCKETS.to_vec(),
+                registry,
+            )
+            .unwrap(),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct DBMetrics {
     pub op_metrics: OperationMetrics,
     pub cf_metrics: ColumnFamilyMetrics,
     pub read_perf_ctx_metrics: ReadPerfContextMetrics,
     pub write_perf_ctx_metrics: WritePerfContextMetrics,
+    pub migration_metrics: MigrationMetrics,
     pub registry_serivce: RegistryService,
 }
 
@@ -983,6 +1020,7 @@ impl DBMetrics {
             cf_metrics: ColumnFamilyMetrics::new(&registry),
             read_perf_ctx_metrics: ReadPerfContextMetrics::new(&registry),
             write_perf_ctx_metrics: WritePerfContextMetrics::new(&registry),
+            migration_metrics: MigrationMetrics::new(&registry),
             registry_serivce: registry_service,
         }
     }
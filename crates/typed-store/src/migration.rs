@@ -0,0 +1,906 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Versioned tables and online schema migrations.
+//!
+//! A table declares the version of its schema with `#[schema_version = N]` on its field of a
+//! `DBMapUtils` struct, and the version of each table is recorded in the default column family
+//! of the database. To change the value type of a table, bump its version and move it to a new
+//! column family with `#[rename = "..."]`, keeping the field name:
+//!
+//! ```ignore
+//! #[derive(DBMapUtils)]
+//! struct Tables {
+//!     #[schema_version = 2]
+//!     #[rename = "objects_v2"]
+//!     objects: DBMap<ObjectKey, ObjectV2>,
+//! }
+//! ```
+//!
+//! The migration registered for the bump copies the entries of the old column family into the
+//! new one, converting each value. [SchemaMigrator::start] runs it in a background thread, in
+//! batches, and the progress is written atomically with every batch, so a restarted node resumes
+//! where it stopped.
+//!
+//! Other tables can be used while a migration runs, but a migrated table must not be read or
+//! written until [MigrationHandle::wait_migrated] returns for it. Entries are copied into the new
+//! column family as they are, so writes made to it while it is being filled would be overwritten,
+//! and reads would miss the entries that were not copied yet.
+//!
+//! The old column family is retained along with a rollback marker until the migration is
+//! finalized, so that a node can be downgraded to the previous binary, which drops the new
+//! column family on startup. This is only possible until the migrated table is released for use
+//! by `wait_migrated`, as writes made to it afterwards would be lost: the previous binary refuses
+//! to start instead, and the migration must be finalized with the newer binary.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::marker::PhantomData;
+use std::ops::Bound;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use bincode::Options;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use tokio::sync::watch;
+use tracing::{error, info};
+
+use crate::TypedStoreError;
+use crate::metrics::DBMetrics;
+use crate::rocks::errors::typed_store_err_from_rocks_err;
+use crate::rocks::{DBBatch, DBMap, Database, ReadWriteOptions};
+use crate::traits::Map;
+use crate::util::be_fix_int_ser;
+
+const DEFAULT_BATCH_SIZE: usize = 10_000;
+
+const STATUS_UP_TO_DATE: i64 = 0;
+const STATUS_RUNNING: i64 = 1;
+const STATUS_ROLLBACK_AVAILABLE: i64 = 2;
+
+/// The column family name and schema version of a table, as declared in a `DBMapUtils`
+/// struct. Returned by the generated `table_descriptors()`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TableDescriptor {
+    pub name: &'static str,
+    pub version: u32,
+}
+
+/// The schema state of a table, as recorded in the database.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TableSchemaState {
+    pub version: u32,
+    /// Set while entries are being migrated into the table.
+    pub migration: Option<MigrationProgress>,
+    /// Set from the start of a migration until it is finalized.
+    pub rollback: Option<RollbackMarker>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MigrationProgress {
+    /// Serialized key of the last migrated entry of the source column family.
+    pub cursor: Option<Vec<u8>>,
+    pub keys_migrated: u64,
+}
+
+/// The table a migration copies from, which is retained until the migration is finalized.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RollbackMarker {
+    pub cf_name: String,
+    pub version: u32,
+    /// Set once the migrated table is released for use, after which the migration can no
+    /// longer be rolled back.
+    pub released: bool,
+}
+
+/// A migration between two versions of a table, with the types erased.
+trait TableMigration: Send + Sync {
+    fn source_cf(&self) -> &str;
+
+    fn from_version(&self) -> u32;
+
+    fn to_version(&self) -> u32;
+
+    /// Adds to `batch` the migration of at most `limit` entries of the source column family
+    /// that follow `cursor`. Returns the number of entries and the key of the last one.
+    fn migrate_batch(
+        &self,
+        db: &Arc<Database>,
+        target_cf: &str,
+        cursor: Option<&[u8]>,
+        limit: usize,
+        batch: &mut DBBatch,
+    ) -> Result<(usize, Option<Vec<u8>>), TypedStoreError>;
+}
+
+struct ValueMigration<K, OldV, NewV> {
+    source_cf: String,
+    from_version: u32,
+    to_version: u32,
+    transform: Box<dyn Fn(OldV) -> NewV + Send + Sync>,
+    _phantom: PhantomData<fn(K)>,
+}
+
+impl<K, OldV, NewV> TableMigration for ValueMigration<K, OldV, NewV>
+where
+    K: Serialize + DeserializeOwned,
+    OldV: Serialize + DeserializeOwned,
+    NewV: Serialize + DeserializeOwned,
+{
+    fn source_cf(&self) -> &str {
+        &self.source_cf
+    }
+
+    fn from_version(&self) -> u32 {
+        self.from_version
+    }
+
+    fn to_version(&self) -> u32 {
+        self.to_version
+    }
+
+    fn migrate_batch(
+        &self,
+        db: &Arc<Database>,
+        target_cf: &str,
+        cursor: Option<&[u8]>,
+        limit: usize,
+        batch: &mut DBBatch,
+    ) -> Result<(usize, Option<Vec<u8>>), TypedStoreError> {
+        let source = open_handle::<K, OldV>(db, &self.source_cf)?;
+        let target = open_handle::<K, NewV>(db, target_cf)?;
+        let lower_bound = match cursor {
+            Some(cursor) => Bound::Excluded(deserialize_key::<K>(cursor)?),
+            None => Bound::Unbounded,
+        };
+
+        let mut entries = Vec::new();
+        for item in source
+            .safe_range_iter((lower_bound, Bound::Unbounded))
+            .take(limit)
+        {
+            let (key, value) = item?;
+            entries.push((key, (self.transform)(value)));
+        }
+        let last_key = entries.last().map(|(key, _)| be_fix_int_ser(key));
+        let count = entries.len();
+        batch.insert_batch(&target, entries)?;
+        Ok((count, last_key))
+    }
+}
+
+/// The migrations known to a binary, keyed by the table they migrate into.
+#[derive(Default)]
+pub struct MigrationRegistry {
+    migrations: BTreeMap<String, Arc<dyn TableMigration>>,
+}
+
+impl MigrationRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the migration of `table` to `to_version`, from the table `source_cf` at
+    /// `from_version`. Every value of the source is converted with `transform`, keys are kept.
+    pub fn register<K, OldV, NewV>(
+        mut self,
+        table: &str,
+        to_version: u32,
+        source_cf: &str,
+        from_version: u32,
+        transform: impl Fn(OldV) -> NewV + Send + Sync + 'static,
+    ) -> Self
+    where
+        K: Serialize + DeserializeOwned + 'static,
+        OldV: Serialize + DeserializeOwned + 'static,
+        NewV: Serialize + DeserializeOwned + 'static,
+    {
+        assert_ne!(
+            table, source_cf,
+            "a migrated table must be moved to a new column family"
+        );
+        let migration = ValueMigration::<K, OldV, NewV> {
+            source_cf: source_cf.to_owned(),
+            from_version,
+            to_version,
+            transform: Box::new(transform),
+            _phantom: PhantomData,
+        };
+        let previous = self
+            .migrations
+            .insert(table.to_owned(), Arc::new(migration));
+        assert!(
+            previous.is_none(),
+            "more than one migration registered into table {table}"
+        );
+        self
+    }
+
+    /// Registers the renaming of the table `source_cf` to `table`, without a change of types.
+    pub fn register_rename<K, V>(
+        self,
+        table: &str,
+        to_version: u32,
+        source_cf: &str,
+        from_version: u32,
+    ) -> Self
+    where
+        K: Serialize + DeserializeOwned + 'static,
+        V: Serialize + DeserializeOwned + 'static,
+    {
+        self.register::<K, V, V>(table, to_version, source_cf, from_version, |value| value)
+    }
+}
+
+/// Checks the recorded schema of the tables of a database against the declared one, and runs
+/// the migrations of the tables whose version was bumped.
+pub struct SchemaMigrator {
+    db: Arc<Database>,
+    metadata: DBMap<String, TableSchemaState>,
+    registry: MigrationRegistry,
+    batch_size: usize,
+}
+
+impl SchemaMigrator {
+    pub fn new(db: Arc<Database>, registry: MigrationRegistry) -> Result<Self, TypedStoreError> {
+        let metadata = open_handle(&db, rocksdb::DEFAULT_COLUMN_FAMILY_NAME)?;
+        Ok(Self {
+            db,
+            metadata,
+            registry,
+            batch_size: DEFAULT_BATCH_SIZE,
+        })
+    }
+
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        assert!(batch_size > 0, "batch size must be positive");
+        self.batch_size = batch_size;
+        self
+    }
+
+    pub fn table_state(&self, table: &str) -> Result<Option<TableSchemaState>, TypedStoreError> {
+        self.metadata.get(&table.to_owned())
+    }
+
+    /// Returns true once all entries were migrated into the table, or if it had nothing to
+    /// migrate.
+    pub fn is_migrated(&self, table: &str) -> Result<bool, TypedStoreError> {
+        Ok(self
+            .table_state(table)?
+            .is_some_and(|state| state.migration.is_none()))
+    }
+
+    /// Reconciles the recorded schema with the declared tables and returns the tables with a
+    /// migration to run:
+    /// - tables without a recorded version are recorded at their declared version, unless a
+    ///   registered migration has data to copy into them;
+    /// - tables recorded at an older version start the migration registered for the bump;
+    /// - tables unknown to this binary whose rollback marker points to a declared table are
+    ///   dropped, as the database was written by a newer binary that has been rolled back.
+    ///
+    /// Fails if a table is recorded at a newer version than declared, or if a bump has no
+    /// registered migration.
+    pub fn prepare(&self, descriptors: &[TableDescriptor]) -> Result<Vec<String>, TypedStoreError> {
+        let declared: BTreeMap<&str, u32> = descriptors
+            .iter()
+            .map(|descriptor| (descriptor.name, descriptor.version))
+            .collect();
+        let recorded = self.metadata.safe_iter().collect::<Result<Vec<_>, _>>()?;
+
+        for (table, state) in recorded {
+            if declared.contains_key(table.as_str()) {
+                continue;
+            }
+            if let Some(marker) = &state.rollback
+                && declared.get(marker.cf_name.as_str()) == Some(&marker.version)
+            {
+                self.rollback(&table, &state)?;
+            }
+        }
+
+        let mut pending = vec![];
+        for descriptor in descriptors {
+            let state = self.table_state(descriptor.name)?;
+            match state {
+                Some(state) if state.version == descriptor.version => {
+                    if state.migration.is_some() {
+                        pending.push(descriptor.name.to_owned());
+                    } else {
+                        let status = if state.rollback.is_some() {
+                            STATUS_ROLLBACK_AVAILABLE
+                        } else {
+                            STATUS_UP_TO_DATE
+                        };
+                        self.report_status(descriptor.name, status);
+                    }
+                }
+                Some(state) if state.version > descriptor.version => {
+                    return Err(migration_error(format!(
+                        "table {} is at version {}, newer than the supported version {}",
+                        descriptor.name, state.version, descriptor.version
+                    )));
+                }
+                state => {
+                    if self.start_migration(descriptor, state.map(|state| state.version))? {
+                        pending.push(descriptor.name.to_owned());
+                    }
+                }
+            }
+        }
+        Ok(pending)
+    }
+
+    /// Records the table at its declared version, with a migration to run if one is
+    /// registered. Returns true if there is a migration to run.
+    fn start_migration(
+        &self,
+        descriptor: &TableDescriptor,
+        recorded_version: Option<u32>,
+    ) -> Result<bool, TypedStoreError> {
+        let table = descriptor.name;
+        let migration = match self.registry.migrations.get(table) {
+            Some(migration) if self.db.has_cf(migration.source_cf())? => Some(migration),
+            _ => None,
+        };
+        let Some(migration) = migration else {
+            if let Some(version) = recorded_version {
+                return Err(migration_error(format!(
+                    "no migration registered for table {table} from version {version} to {}",
+                    descriptor.version
+                )));
+            }
+            self.write_state(
+                table,
+                &TableSchemaState {
+                    version: descriptor.version,
+                    ..Default::default()
+                },
+            )?;
+            self.report_status(table, STATUS_UP_TO_DATE);
+            return Ok(false);
+        };
+
+        if migration.to_version() != descriptor.version {
+            return Err(migration_error(format!(
+                "migration into table {table} targets version {}, but the table is declared at version {}",
+                migration.to_version(),
+                descriptor.version
+            )));
+        }
+        // A source without a recorded version predates versioning.
+        if let Some(source) = self.table_state(migration.source_cf())?
+            && source.version != migration.from_version()
+        {
+            return Err(migration_error(format!(
+                "migration into table {table} expects {} at version {}, found version {}",
+                migration.source_cf(),
+                migration.from_version(),
+                source.version
+            )));
+        }
+
+        info!(
+            "Starting migration of table {} version {} into table {table} version {}",
+            migration.source_cf(),
+            migration.from_version(),
+            descriptor.version
+        );
+        self.write_state(
+            table,
+            &TableSchemaState {
+                version: descriptor.version,
+                migration: Some(MigrationProgress::default()),
+                rollback: Some(RollbackMarker {
+                    cf_name: migration.source_cf().to_owned(),
+                    version: migration.from_version(),
+                    released: false,
+                }),
+            },
+        )?;
+        Ok(true)
+    }
+
+    /// Migrates the remaining entries into the table, one batch at a time.
+    pub fn migrate(&self, table: &str) -> Result<(), TypedStoreError> {
+        self.migrate_until_stopped(table, &AtomicBool::new(false))
+            .map(|_| ())
+    }
+
+    /// Like `migrate`, but returns false before the next batch once `stop` is set.
+    fn migrate_until_stopped(
+        &self,
+        table: &str,
+        stop: &AtomicBool,
+    ) -> Result<bool, TypedStoreError> {
+        let migration = self
+            .registry
+            .migrations
+            .get(table)
+            .ok_or_else(|| migration_error(format!("no migration registered into {table}")))?;
+        let metrics = &DBMetrics::get().migration_metrics;
+        let db_name = self.db.db_name();
+        self.report_status(table, STATUS_RUNNING);
+
+        loop {
+            let Some(state) = self.table_state(table)? else {
+                return Err(migration_error(format!("table {table} is not recorded")));
+            };
+            let Some(mut progress) = state.migration.clone() else {
+                break;
+            };
+            if stop.load(Ordering::Relaxed) {
+                info!("Stopped migration into table {table}");
+                return Ok(false);
+            }
+
+            let _timer = metrics
+                .migration_batch_latency_seconds
+                .with_label_values(&[db_name.as_str(), table])
+                .start_timer();
+            let mut batch = self.metadata.batch();
+            let (count, last_key) = migration.migrate_batch(
+                &self.db,
+                table,
+                progress.cursor.as_deref(),
+                self.batch_size,
+                &mut batch,
+            )?;
+            progress.keys_migrated += count as u64;
+            if last_key.is_some() {
+                progress.cursor = last_key;
+            }
+            let keys_migrated = progress.keys_migrated;
+            batch.insert_batch(
+                &self.metadata,
+                [(
+                    table.to_owned(),
+                    TableSchemaState {
+                        migration: Some(progress),
+                        ..state.clone()
+                    },
+                )],
+            )?;
+            batch.write()?;
+            metrics
+                .migration_keys_migrated
+                .with_label_values(&[db_name.as_str(), table])
+                .inc_by(count as u64);
+
+            if count < self.batch_size {
+                self.write_state(
+                    table,
+                    &TableSchemaState {
+                        migration: None,
+                        ..state
+                    },
+                )?;
+                info!("Migrated {keys_migrated} entries into table {table}");
+            }
+        }
+
+        self.report_status(table, STATUS_ROLLBACK_AVAILABLE);
+        Ok(true)
+    }
+
+    /// Prepares the tables, and runs the pending migrations one after the other in a background
+    /// thread. The tables they migrate into must not be used before they are released with
+    /// `MigrationHandle::wait_migrated`.
+    pub fn start(
+        self,
+        descriptors: &[TableDescriptor],
+    ) -> Result<MigrationHandle, TypedStoreError> {
+        let pending = self.prepare(descriptors)?;
+        let migrator = Arc::new(self);
+        let stop = Arc::new(AtomicBool::new(false));
+        let (sender, progress) = watch::channel(MigrationTaskState {
+            pending: pending.iter().cloned().collect(),
+            ..Default::default()
+        });
+
+        let task_migrator = migrator.clone();
+        let task_stop = stop.clone();
+        std::thread::Builder::new()
+            .name("schema-migration".to_string())
+            .spawn(move || task_migrator.run_pending(pending, &task_stop, &sender))
+            .map_err(|e| migration_error(format!("failed to spawn the migration thread: {e}")))?;
+        Ok(MigrationHandle {
+            migrator,
+            progress,
+            stop,
+        })
+    }
+
+    fn run_pending(
+        &self,
+        pending: Vec<String>,
+        stop: &AtomicBool,
+        sender: &watch::Sender<MigrationTaskState>,
+    ) {
+        for table in pending {
+            match self.migrate_until_stopped(&table, stop) {
+                Ok(true) => sender.send_modify(|state| {
+                    state.pending.remove(&table);
+                }),
+                Ok(false) => break,
+                Err(e) => {
+                    error!("Migration into table {table} failed: {e}");
+                    sender.send_modify(|state| {
+                        state.error = Some(migration_error(format!(
+                            "migration into table {table} failed: {e}"
+                        )))
+                    });
+                    break;
+                }
+            }
+        }
+        sender.send_modify(|state| state.finished = true);
+    }
+
+    /// Releases a table whose migration completed for use. From then on, the migration can no
+    /// longer be rolled back.
+    pub fn release(&self, table: &str) -> Result<(), TypedStoreError> {
+        let state = self
+            .table_state(table)?
+            .filter(|state| state.migration.is_none())
+            .ok_or_else(|| migration_error(format!("no completed migration into {table}")))?;
+        match &state.rollback {
+            Some(marker) if !marker.released => self.write_state(
+                table,
+                &TableSchemaState {
+                    rollback: Some(RollbackMarker {
+                        released: true,
+                        ..marker.clone()
+                    }),
+                    ..state.clone()
+                },
+            ),
+            _ => Ok(()),
+        }
+    }
+
+    /// Drops the source table of a completed migration, after which the migration can no longer
+    /// be rolled back.
+    pub fn finalize(&self, table: &str) -> Result<(), TypedStoreError> {
+        let state = self
+            .table_state(table)?
+            .filter(|state| state.migration.is_none())
+            .ok_or_else(|| migration_error(format!("no completed migration into {table}")))?;
+        let Some(marker) = state.rollback.clone() else {
+            return Ok(());
+        };
+
+        // The marker is removed first, a crash before the drop leaves an unused column family.
+        let mut batch = self.metadata.batch();
+        batch.delete_batch(&self.metadata, [marker.cf_name.clone()])?;
+        batch.insert_batch(
+            &self.metadata,
+            [(
+                table.to_owned(),
+                TableSchemaState {
+                    rollback: None,
+                    ..state
+                },
+            )],
+        )?;
+        batch.write()?;
+        if self.db.has_cf(&marker.cf_name)? {
+            self.db
+                .drop_cf(&marker.cf_name)
+                .map_err(typed_store_err_from_rocks_err)?;
+        }
+        info!(
+            "Finalized migration into table {table}, dropped {}",
+            marker.cf_name
+        );
+        self.report_status(table, STATUS_UP_TO_DATE);
+        Ok(())
+    }
+
+    /// Drops a table written by a newer binary, whose source table is still intact. Fails if the
+    /// table was released for use, as the writes made to it since would be lost.
+    fn rollback(&self, table: &str, state: &TableSchemaState) -> Result<(), TypedStoreError> {
+        if self.db.has_cf(table)? {
+            if state
+                .rollback
+                .as_ref()
+                .is_some_and(|marker| marker.released)
+            {
+                return Err(migration_error(format!(
+                    "cannot roll back the migration into table {table}: it was released for use, \
+                     and the writes made to it since would be lost. Finalize the migration with \
+                     the newer binary instead"
+                )));
+            }
+
+            info!("Rolling back the migration into table {table}");
+            self.db
+                .drop_cf(table)
+                .map_err(typed_store_err_from_rocks_err)?;
+        }
+        self.metadata.remove(&table.to_owned())
+    }
+
+    fn write_state(&self, table: &str, state: &TableSchemaState) -> Result<(), TypedStoreError> {
+        self.metadata.insert(&table.to_owned(), state)
+    }
+
+    fn report_status(&self, table: &str, status: i64) {
+        DBMetrics::get()
+            .migration_metrics
+            .migration_status
+            .with_label_values(&[self.db.db_name().as_str(), table])
+            .set(status);
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+struct MigrationTaskState {
+    /// Tables whose migration did not complete yet.
+    pending: BTreeSet<String>,
+    error: Option<TypedStoreError>,
+    /// Set once the task stopped, whether or not all migrations completed.
+    finished: bool,
+}
+
+/// Handle to the migrations run in the background by `SchemaMigrator::start`. Dropping it leaves
+/// them running.
+pub struct MigrationHandle {
+    migrator: Arc<SchemaMigrator>,
+    progress: watch::Receiver<MigrationTaskState>,
+    stop: Arc<AtomicBool>,
+}
+
+impl MigrationHandle {
+    /// Returns true once all entries were migrated into the table, or if it had nothing to
+    /// migrate.
+    pub fn is_migrated(&self, table: &str) -> bool {
+        !self.progress.borrow().pending.contains(table)
+    }
+
+    /// Waits until all entries were migrated into the table, then releases it for use, see
+    /// `SchemaMigrator::release`. Fails if the migration failed or was stopped.
+    pub async fn wait_migrated(&self, table: &str) -> Result<(), TypedStoreError> {
+        let mut progress = self.progress.clone();
+        let state = progress
+            .wait_for(|state| !state.pending.contains(table) || state.finished)
+            .await
+            .map_err(|_| migration_error("the migration thread panicked".to_string()))?
+            .clone();
+        if state.pending.contains(table) {
+            return Err(state.error.unwrap_or_else(|| {
+                migration_error(format!("the migration into table {table} was stopped"))
+            }));
+        }
+        self.migrator.release(table)
+    }
+
+    /// Stops the migrations before their next batch. They resume where they stopped on the next
+    /// start.
+    pub fn stop(&self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Opens a handle to a column family of an open database. Column family metrics are not
+/// reported for these handles.
+fn open_handle<K, V>(db: &Arc<Database>, cf_name: &str) -> Result<DBMap<K, V>, TypedStoreError> {
+    DBMap::reopen(db, Some(cf_name), &ReadWriteOptions::default(), true)
+}
+
+fn deserialize_key<K: DeserializeOwned>(bytes: &[u8]) -> Result<K, TypedStoreError> {
+    bincode::DefaultOptions::new()
+        .with_big_endian()
+        .with_fixint_encoding()
+        .deserialize(bytes)
+        .map_err(|e| TypedStoreError::SerializationError(e.to_string()))
+}
+
+fn migration_error(message: String) -> TypedStoreError {
+    TypedStoreError::SchemaMigrationError(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rocks::{MetricConf, open_in_memory};
+
+    fn descriptor(name: &'static str, version: u32) -> TableDescriptor {
+        TableDescriptor { name, version }
+    }
+
+    #[tokio::test]
+    async fn test_resume_and_finalize() {
+        let db = open_in_memory(MetricConf::default());
+        let source: DBMap<u64, String> = open_handle(&db, "source").unwrap();
+        let target: DBMap<u64, usize> = open_handle(&db, "target").unwrap();
+        source
+            .multi_insert((0..30).map(|i| (i, "x".repeat(i as usize))))
+            .unwrap();
+
+        let registry = MigrationRegistry::new().register::<u64, String, usize>(
+            "target",
+            2,
+            "source",
+            1,
+            |value| value.len(),
+        );
+        let migrator = SchemaMigrator::new(db.clone(), registry)
+            .unwrap()
+            .with_batch_size(7);
+        migrator
+            .write_state(
+                "source",
+                &TableSchemaState {
+                    version: 1,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            migrator.prepare(&[descriptor("target", 2)]).unwrap(),
+            vec!["target".to_string()]
+        );
+
+        // Resume after the first 10 entries, as recorded by an interrupted run.
+        let mut state = migrator.table_state("target").unwrap().unwrap();
+        state.migration = Some(MigrationProgress {
+            cursor: Some(be_fix_int_ser(&9u64)),
+            keys_migrated: 10,
+        });
+        migrator.write_state("target", &state).unwrap();
+        migrator.migrate("target").unwrap();
+
+        let migrated: Vec<_> = target.safe_iter().map(Result::unwrap).collect();
+        assert_eq!(
+            migrated,
+            (10..30).map(|i| (i, i as usize)).collect::<Vec<_>>()
+        );
+        let state = migrator.table_state("target").unwrap().unwrap();
+        assert_eq!(state.migration, None);
+        let marker = state.rollback.unwrap();
+        assert_eq!(marker.cf_name, "source");
+        assert_eq!(marker.version, 1);
+        assert!(!marker.released);
+
+        migrator.finalize("target").unwrap();
+        assert!(!db.has_cf("source").unwrap());
+        assert_eq!(migrator.table_state("source").unwrap(), None);
+        assert_eq!(
+            migrator.table_state("target").unwrap().unwrap().rollback,
+            None
+        );
+        // Nothing is left to migrate on the next start.
+        assert!(
+            migrator
+                .prepare(&[descriptor("target", 2)])
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rollback_refused_after_release() {
+        let db = open_in_memory(MetricConf::default());
+        let source: DBMap<u64, u64> = open_handle(&db, "source").unwrap();
+        let target: DBMap<u64, u64> = open_handle(&db, "target").unwrap();
+        source.multi_insert((0..5).map(|i| (i, i))).unwrap();
+        let registry =
+            || MigrationRegistry::new().register_rename::<u64, u64>("target", 1, "source", 0);
+
+        // A completed migration can be rolled back until the table is released for use.
+        let migrator = SchemaMigrator::new(db.clone(), registry()).unwrap();
+        migrator.prepare(&[descriptor("target", 1)]).unwrap();
+        migrator.migrate("target").unwrap();
+        assert!(migrator.is_migrated("target").unwrap());
+        let previous = SchemaMigrator::new(db.clone(), MigrationRegistry::new()).unwrap();
+        assert!(
+            previous
+                .prepare(&[descriptor("source", 0)])
+                .unwrap()
+                .is_empty()
+        );
+        assert!(!db.has_cf("target").unwrap());
+
+        // The upgrade migrates the table again, and releases it.
+        drop(target);
+        let target: DBMap<u64, u64> = open_handle(&db, "target").unwrap();
+        let migrations = SchemaMigrator::new(db.clone(), registry())
+            .unwrap()
+            .start(&[descriptor("target", 1)])
+            .unwrap();
+        migrations.wait_migrated("target").await.unwrap();
+        assert!(migrations.is_migrated("target"));
+        assert_eq!(target.get(&4).unwrap(), Some(4));
+        target.insert(&5, &5).unwrap();
+
+        // The previous binary would lose the write.
+        assert!(matches!(
+            previous.prepare(&[descriptor("source", 0)]),
+            Err(TypedStoreError::SchemaMigrationError(_))
+        ));
+        assert!(db.has_cf("target").unwrap());
+        assert_eq!(source.get(&4).unwrap(), Some(4));
+    }
+
+    #[tokio::test]
+    async fn test_stopped_migration_resumes() {
+        let db = open_in_memory(MetricConf::default());
+        let source: DBMap<u64, u64> = open_handle(&db, "source").unwrap();
+        let target: DBMap<u64, u64> = open_handle(&db, "target").unwrap();
+        source.multi_insert((0..20).map(|i| (i, i))).unwrap();
+        let registry =
+            || MigrationRegistry::new().register_rename::<u64, u64>("target", 1, "source", 0);
+
+        let migrator = SchemaMigrator::new(db.clone(), registry())
+            .unwrap()
+            .with_batch_size(3);
+        assert_eq!(
+            migrator.prepare(&[descriptor("target", 1)]).unwrap(),
+            vec!["target".to_string()]
+        );
+        let stop = AtomicBool::new(true);
+        assert!(!migrator.migrate_until_stopped("target", &stop).unwrap());
+        assert!(!migrator.is_migrated("target").unwrap());
+        // A table cannot be released before its migration completes.
+        assert!(migrator.release("target").is_err());
+
+        let migrations = SchemaMigrator::new(db.clone(), registry())
+            .unwrap()
+            .with_batch_size(3)
+            .start(&[descriptor("target", 1)])
+            .unwrap();
+        migrations.wait_migrated("target").await.unwrap();
+        assert_eq!(
+            target.safe_iter().map(Result::unwrap).collect::<Vec<_>>(),
+            (0..20).map(|i| (i, i)).collect::<Vec<_>>()
+        );
+        assert!(
+            migrations
+                .migrator
+                .table_state("target")
+                .unwrap()
+                .unwrap()
+                .rollback
+                .unwrap()
+                .released
+        );
+    }
+
+    #[tokio::test]
+    async fn test_has_cf_in_memory() {
+        let db = open_in_memory(MetricConf::default());
+        assert!(!db.has_cf("table").unwrap());
+
+        // Opening a map creates its column family, even before anything is written to it.
+        let _table: DBMap<u64, u64> = open_handle(&db, "table").unwrap();
+        assert!(db.has_cf("table").unwrap());
+
+        db.drop_cf("table").unwrap();
+        assert!(!db.has_cf("table").unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_version_checks() {
+        let db = open_in_memory(MetricConf::default());
+        let migrator = SchemaMigrator::new(db.clone(), MigrationRegistry::new()).unwrap();
+
+        // A new table is recorded at its declared version.
+        assert!(
+            migrator
+                .prepare(&[descriptor("table", 1)])
+                .unwrap()
+                .is_empty()
+        );
+        assert!(migrator.is_migrated("table").unwrap());
+
+        // A bump without a registered migration is rejected, and so is a downgrade without a
+        // rollback marker.
+        assert!(matches!(
+            migrator.prepare(&[descriptor("table", 2)]),
+            Err(TypedStoreError::SchemaMigrationError(_))
+        ));
+        assert!(matches!(
+            migrator.prepare(&[descriptor("table", 0)]),
+            Err(TypedStoreError::SchemaMigrationError(_))
+        ));
+    }
+}
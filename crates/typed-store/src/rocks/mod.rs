@@ -4,14 +4,16 @@ pub mod errors;
 mod options;
 mod rocks_util;
 pub(crate) mod safe_iter;
+mod transaction;
 
-use crate::memstore::{InMemoryBatch, InMemoryDB};
+use crate::memstore::{InMemoryBatch, InMemoryDB, InMemorySnapshot, InMemoryTransaction};
 use crate::rocks::errors::typed_store_err_from_bcs_err;
 use crate::rocks::errors::typed_store_err_from_rocks_err;
 pub use crate::rocks::options::{
     DBMapTableConfigMap, DBOptions, ReadWriteOptions, default_db_options, read_size_from_env,
 };
 use crate::rocks::safe_iter::{SafeIter, SafeRevIter};
+use crate::rocks::transaction::{CfKey, RocksTransaction, WriteTracker};
 #[cfg(tidehunter)]
 use crate::tidehunter_util::{
     apply_range_bounds, transform_th_iterator, transform_th_key, typed_store_error_from_th_error,
@@ -60,6 +62,7 @@ mod tests;
 #[derive(Debug)]
 pub struct RocksDB {
     pub underlying: rocksdb::DBWithThreadMode<MultiThreaded>,
+    write_tracker: WriteTracker,
 }
 
 impl Drop for RocksDB {
@@ -157,6 +160,37 @@ impl Database {
         }
     }
 
+    /// Starts an optimistic transaction over the database.
+    ///
+    /// RocksDB and in-memory storages behave the same. On RocksDB, writes whose keys are not
+    /// known (range deletions, file deletions, dropped column families and batches created before
+    /// the transaction started) conflict with every open transaction.
+    pub fn transaction(self: &Arc<Self>) -> Result<DBTransaction<'_>, TypedStoreError> {
+        let transaction = match &self.storage {
+            Storage::Rocks(db) => StorageTransaction::Rocks(RocksTransaction::new(db)),
+            Storage::InMemory(db) => StorageTransaction::InMemory(db.transaction()),
+            #[cfg(tidehunter)]
+            Storage::TideHunter(_) => {
+                return Err(TypedStoreError::RocksDBError(
+                    "TideHunter: optimistic transactions are not supported".to_string(),
+                ));
+            }
+        };
+        Ok(DBTransaction {
+            database: self,
+            transaction,
+        })
+    }
+
+    /// Whether writes to this database must collect the keys they write, for open transactions
+    /// to detect conflicts.
+    fn tracks_writes(&self) -> bool {
+        match &self.storage {
+            Storage::Rocks(db) => db.write_tracker.has_open_transactions(),
+            _ => false,
+        }
+    }
+
     /// Flush all memtables to SST files on disk.
     pub fn flush(&self) -> Result<(), TypedStoreError> {
         match &self.storage {
@@ -247,9 +281,27 @@ impl Database {
         }
     }
 
+    /// Returns true if the column family exists in the database, whether or not it is
+    /// opened as a table.
+    pub fn has_cf(&self, name: &str) -> Result<bool, TypedStoreError> {
+        match &self.storage {
+            Storage::Rocks(db) => Ok(db.underlying.cf_handle(name).is_some()),
+            Storage::InMemory(db) => Ok(db.has_cf(name)),
+            #[cfg(tidehunter)]
+            Storage::TideHunter(_) => Err(TypedStoreError::RocksDBError(
+                "TideHunter: listing column families is not supported".to_string(),
+            )),
+        }
+    }
+
     pub fn drop_cf(&self, name: &str) -> Result<(), rocksdb::Error> {
         match &self.storage {
-            Storage::Rocks(db) => db.underlying.drop_cf(name),
+            Storage::Rocks(db) => {
+                let write = db.write_tracker.start_write();
+                let ret = db.underlying.drop_cf(name);
+                write.finish(None);
+                ret
+            }
             Storage::InMemory(db) => {
                 db.drop_cf(name);
                 Ok(())
@@ -268,7 +320,12 @@ impl Database {
         to: K,
     ) -> Result<(), rocksdb::Error> {
         match &self.storage {
-            Storage::Rocks(rocks) => rocks.underlying.delete_file_in_range_cf(cf, from, to),
+            Storage::Rocks(rocks) => {
+                let write = rocks.write_tracker.start_write();
+                let ret = rocks.underlying.delete_file_in_range_cf(cf, from, to);
+                write.finish(None);
+                ret
+            }
             _ => unimplemented!("delete_file_in_range is only supported for rocksdb backend"),
         }
     }
@@ -276,10 +333,18 @@ impl Database {
     fn delete_cf<K: AsRef<[u8]>>(&self, cf: &ColumnFamily, key: K) -> Result<(), TypedStoreError> {
         fail_point!("delete-cf-before");
         let ret = match (&self.storage, cf) {
-            (Storage::Rocks(db), ColumnFamily::Rocks(_)) => db
-                .underlying
-                .delete_cf(&cf.rocks_cf(db), key)
-                .map_err(typed_store_err_from_rocks_err),
+            (Storage::Rocks(db), ColumnFamily::Rocks(cf_name)) => {
+                let write = db.write_tracker.start_write();
+                let keys = write
+                    .is_tracked()
+                    .then(|| vec![(cf_name.clone(), key.as_ref().to_vec())]);
+                let ret = db
+                    .underlying
+                    .delete_cf(&cf.rocks_cf(db), key)
+                    .map_err(typed_store_err_from_rocks_err);
+                write.finish(keys);
+                ret
+            }
             (Storage::InMemory(db), ColumnFamily::InMemory(cf_name)) => {
                 db.delete(cf_name, key.as_ref());
                 Ok(())
@@ -312,10 +377,18 @@ impl Database {
     ) -> Result<(), TypedStoreError> {
         fail_point!("put-cf-before");
         let ret = match (&self.storage, cf) {
-            (Storage::Rocks(db), ColumnFamily::Rocks(_)) => db
-                .underlying
-                .put_cf(&cf.rocks_cf(db), key, value)
-                .map_err(typed_store_err_from_rocks_err),
+            (Storage::Rocks(db), ColumnFamily::Rocks(cf_name)) => {
+                let write = db.write_tracker.start_write();
+                let keys = write
+                    .is_tracked()
+                    .then(|| vec![(cf_name.clone(), key.clone())]);
+                let ret = db
+                    .underlying
+                    .put_cf(&cf.rocks_cf(db), key, value)
+                    .map_err(typed_store_err_from_rocks_err);
+                write.finish(keys);
+                ret
+            }
             (Storage::InMemory(db), ColumnFamily::InMemory(cf_name)) => {
                 db.put(cf_name, key, value);
                 Ok(())
@@ -359,13 +432,29 @@ impl Database {
         &self,
         batch: StorageWriteBatch,
         write_options: &rocksdb::WriteOptions,
+    ) -> Result<(), TypedStoreError> {
+        self.write_opt_with_keys(batch, write_options, None)
+    }
+
+    /// Writes a batch, whose written keys are passed for open transactions to detect conflicts.
+    /// `None` means the keys are not known, and conflicts with every open transaction.
+    fn write_opt_with_keys(
+        &self,
+        batch: StorageWriteBatch,
+        write_options: &rocksdb::WriteOptions,
+        written_keys: Option<Vec<CfKey>>,
     ) -> Result<(), TypedStoreError> {
         fail_point!("batch-write-before");
         let ret = match (&self.storage, batch) {
-            (Storage::Rocks(rocks), StorageWriteBatch::Rocks(batch)) => rocks
-                .underlying
-                .write_opt(batch, write_options)
-                .map_err(typed_store_err_from_rocks_err),
+            (Storage::Rocks(rocks), StorageWriteBatch::Rocks(batch)) => {
+                let write = rocks.write_tracker.start_write();
+                let ret = rocks
+                    .underlying
+                    .write_opt(batch, write_options)
+                    .map_err(typed_store_err_from_rocks_err);
+                write.finish(written_keys);
+                ret
+            }
             (Storage::InMemory(db), StorageWriteBatch::InMemory(batch)) => {
                 // InMemory doesn't support write options
                 db.write(batch);
@@ -451,7 +540,7 @@ impl Database {
         self.metric_conf.iter_sample_interval.new_from_self()
     }
 
-    fn db_name(&self) -> String {
+    pub(crate) fn db_name(&self) -> String {
         let name = &self.metric_conf.db_name;
         if name.is_empty() {
             "default".to_string()
@@ -606,11 +695,18 @@ impl<K, V> DBMap<K, V> {
         let cf_key = opt_cf
             .unwrap_or(rocksdb::DEFAULT_COLUMN_FAMILY_NAME)
             .to_owned();
+        let column_family = match &db.storage {
+            Storage::InMemory(memory_db) => {
+                memory_db.create_cf(&cf_key);
+                ColumnFamily::InMemory(cf_key.clone())
+            }
+            _ => ColumnFamily::Rocks(cf_key.clone()),
+        };
         Ok(DBMap::new(
             db.clone(),
             rw_options,
             &cf_key,
-            ColumnFamily::Rocks(cf_key.to_string()),
+            column_family,
             is_deprecated,
         ))
     }
@@ -652,6 +748,25 @@ impl<K, V> DBMap<K, V> {
         )
     }
 
+    /// Takes a point-in-time snapshot of the database of this map. The snapshot pins the data it
+    /// observes until it is dropped, so it should not be held for long.
+    pub fn snapshot(&self) -> Result<DBSnapshot<'_>, TypedStoreError> {
+        let snapshot = match &self.db.storage {
+            Storage::Rocks(db) => StorageSnapshot::Rocks(db, db.underlying.snapshot()),
+            Storage::InMemory(db) => StorageSnapshot::InMemory(db.snapshot()),
+            #[cfg(tidehunter)]
+            Storage::TideHunter(_) => {
+                return Err(TypedStoreError::RocksDBError(
+                    "TideHunter: snapshots are not supported".to_string(),
+                ));
+            }
+        };
+        Ok(DBSnapshot {
+            database: &self.db,
+            snapshot,
+        })
+    }
+
     pub fn flush(&self) -> Result<(), TypedStoreError> {
         self.db.flush()
     }
@@ -1181,6 +1296,9 @@ pub struct DBBatch {
     batch: StorageWriteBatch,
     db_metrics: Arc<DBMetrics>,
     write_sample_interval: SamplingInterval,
+    /// The keys written by the batch, collected for the transactions open on RocksDB when the
+    /// batch was created. `None` if they are not known, which conflicts with open transactions.
+    written_keys: Option<Vec<CfKey>>,
 }
 
 impl DBBatch {
@@ -1198,6 +1316,7 @@ impl DBBatch {
             batch,
             db_metrics: db_metrics.clone(),
             write_sample_interval: write_sample_interval.clone(),
+            written_keys: dbref.tracks_writes().then(Vec::new),
         }
     }
 
@@ -1224,7 +1343,8 @@ impl DBBatch {
         } else {
             None
         };
-        self.database.write_opt(self.batch, write_options)?;
+        self.database
+            .write_opt_with_keys(self.batch, write_options, self.written_keys)?;
         self.db_metrics
             .op_metrics
             .rocksdb_batch_commit_bytes
@@ -1278,6 +1398,9 @@ impl DBBatch {
                 let k_buf = be_fix_int_ser(k.borrow());
                 match (&mut self.batch, &db.column_family) {
                     (StorageWriteBatch::Rocks(b), ColumnFamily::Rocks(name)) => {
+                        if let Some(keys) = &mut self.written_keys {
+                            keys.push((name.clone(), k_buf.clone()));
+                        }
                         b.delete_cf(&rocks_cf_from_db(&self.database, name)?, k_buf)
                     }
                     (StorageWriteBatch::InMemory(b), ColumnFamily::InMemory(name)) => {
@@ -1318,12 +1441,19 @@ impl DBBatch {
         let from_buf = be_fix_int_ser(from);
         let to_buf = be_fix_int_ser(to);
 
-        if let StorageWriteBatch::Rocks(b) = &mut self.batch {
-            b.delete_range_cf(
-                &rocks_cf_from_db(&self.database, db.cf_name())?,
-                from_buf,
-                to_buf,
-            );
+        match &mut self.batch {
+            StorageWriteBatch::Rocks(b) => {
+                // The deleted keys are not known, so the batch conflicts with open transactions.
+                self.written_keys = None;
+                b.delete_range_cf(
+                    &rocks_cf_from_db(&self.database, db.cf_name())?,
+                    from_buf,
+                    to_buf,
+                )
+            }
+            StorageWriteBatch::InMemory(b) => b.delete_range_cf(db.cf_name(), from_buf, to_buf),
+            #[cfg(tidehunter)]
+            StorageWriteBatch::TideHunter(_) => {}
         }
         Ok(())
     }
@@ -1356,6 +1486,9 @@ impl DBBatch {
                 }
                 match (&mut self.batch, &db.column_family) {
                     (StorageWriteBatch::Rocks(b), ColumnFamily::Rocks(name)) => {
+                        if let Some(keys) = &mut self.written_keys {
+                            keys.push((name.clone(), k_buf.clone()));
+                        }
                         b.put_cf(&rocks_cf_from_db(&self.database, name)?, k_buf, v_buf)
                     }
                     (StorageWriteBatch::InMemory(b), ColumnFamily::InMemory(name)) => {
@@ -1393,11 +1526,16 @@ impl DBBatch {
                 let k_buf = be_fix_int_ser(k.borrow());
                 let v_buf = bcs::to_bytes(v.borrow()).map_err(typed_store_err_from_bcs_err)?;
                 match &mut self.batch {
-                    StorageWriteBatch::Rocks(b) => b.merge_cf(
-                        &rocks_cf_from_db(&self.database, db.cf_name())?,
-                        k_buf,
-                        v_buf,
-                    ),
+                    StorageWriteBatch::Rocks(b) => {
+                        if let Some(keys) = &mut self.written_keys {
+                            keys.push((db.cf_name().to_string(), k_buf.clone()));
+                        }
+                        b.merge_cf(
+                            &rocks_cf_from_db(&self.database, db.cf_name())?,
+                            k_buf,
+                            v_buf,
+                        )
+                    }
                     _ => unimplemented!("merge operator is only implemented for RocksDB"),
                 }
                 Ok(())
@@ -1406,6 +1544,300 @@ impl DBBatch {
     }
 }
 
+/// An optimistic transaction over a database, created with `Database::transaction`.
+///
+/// Reads observe the snapshot taken when the transaction started, and writes are buffered
+/// until `commit`, which applies them atomically. The commit fails with
+/// `TypedStoreError::RetryableTransactionError` if a key read with `get_for_update` or written
+/// by the transaction was changed by another writer since the snapshot was taken.
+pub struct DBTransaction<'a> {
+    database: &'a Arc<Database>,
+    transaction: StorageTransaction<'a>,
+}
+
+enum StorageTransaction<'a> {
+    Rocks(RocksTransaction<'a>),
+    InMemory(InMemoryTransaction),
+}
+
+impl DBTransaction<'_> {
+    fn cf_name<K, V>(&self, db: &DBMap<K, V>) -> Result<String, TypedStoreError> {
+        if !Arc::ptr_eq(&db.db, self.database) {
+            return Err(TypedStoreError::CrossDBBatch);
+        }
+        Ok(db.cf.clone())
+    }
+
+    /// Reads a key from the snapshot of the transaction. The key is not tracked for conflicts.
+    pub fn get<K: Serialize, V: DeserializeOwned>(
+        &self,
+        db: &DBMap<K, V>,
+        key: &K,
+    ) -> Result<Option<V>, TypedStoreError> {
+        let cf_name = self.cf_name(db)?;
+        let key = be_fix_int_ser(key);
+        let value = match &self.transaction {
+            StorageTransaction::Rocks(transaction) => transaction.get(&cf_name, &key)?,
+            StorageTransaction::InMemory(transaction) => transaction.get(&cf_name, key),
+        };
+        value
+            .map(|value| bcs::from_bytes(&value).map_err(typed_store_err_from_bcs_err))
+            .transpose()
+    }
+
+    /// Reads a key from the snapshot of the transaction, and tracks it for conflicts.
+    pub fn get_for_update<K: Serialize, V: DeserializeOwned>(
+        &mut self,
+        db: &DBMap<K, V>,
+        key: &K,
+    ) -> Result<Option<V>, TypedStoreError> {
+        let cf_name = self.cf_name(db)?;
+        let key = be_fix_int_ser(key);
+        let value = match &mut self.transaction {
+            StorageTransaction::Rocks(transaction) => transaction.get_for_update(&cf_name, &key)?,
+            StorageTransaction::InMemory(transaction) => transaction.get_for_update(&cf_name, key),
+        };
+        value
+            .map(|value| bcs::from_bytes(&value).map_err(typed_store_err_from_bcs_err))
+            .transpose()
+    }
+
+    pub fn insert_batch<J: Borrow<K>, K: Serialize, U: Borrow<V>, V: Serialize>(
+        &mut self,
+        db: &DBMap<K, V>,
+        new_vals: impl IntoIterator<Item = (J, U)>,
+    ) -> Result<&mut Self, TypedStoreError> {
+        let cf_name = self.cf_name(db)?;
+        for (k, v) in new_vals {
+            let k_buf = be_fix_int_ser(k.borrow());
+            let v_buf = bcs::to_bytes(v.borrow()).map_err(typed_store_err_from_bcs_err)?;
+            match &mut self.transaction {
+                StorageTransaction::Rocks(transaction) => {
+                    transaction.put_cf(&cf_name, k_buf, v_buf)
+                }
+                StorageTransaction::InMemory(transaction) => {
+                    transaction.put_cf(&cf_name, k_buf, v_buf)
+                }
+            }
+        }
+        Ok(self)
+    }
+
+    pub fn delete_batch<J: Borrow<K>, K: Serialize, V>(
+        &mut self,
+        db: &DBMap<K, V>,
+        purged_vals: impl IntoIterator<Item = J>,
+    ) -> Result<&mut Self, TypedStoreError> {
+        let cf_name = self.cf_name(db)?;
+        for k in purged_vals {
+            let k_buf = be_fix_int_ser(k.borrow());
+            match &mut self.transaction {
+                StorageTransaction::Rocks(transaction) => transaction.delete_cf(&cf_name, k_buf),
+                StorageTransaction::InMemory(transaction) => transaction.delete_cf(&cf_name, k_buf),
+            }
+        }
+        Ok(self)
+    }
+
+    /// Iterates over the snapshot of the transaction. Writes of the transaction are not visible.
+    pub fn safe_range_iter<'b, K, V>(
+        &'b self,
+        db: &'b DBMap<K, V>,
+        range: impl RangeBounds<K>,
+    ) -> Result<DbIterator<'b, (K, V)>, TypedStoreError>
+    where
+        K: Serialize + DeserializeOwned + 'b,
+        V: DeserializeOwned + 'b,
+    {
+        let cf_name = self.cf_name(db)?;
+        let (lower_bound, upper_bound) = iterator_bounds_with_range(range);
+        Ok(match &self.transaction {
+            StorageTransaction::Rocks(transaction) => Box::new(DBSnapshot::rocks_iter(
+                db,
+                transaction.rocks,
+                &transaction.snapshot,
+                lower_bound,
+                upper_bound,
+            )),
+            StorageTransaction::InMemory(transaction) => {
+                transaction
+                    .snapshot()
+                    .iterator(&cf_name, lower_bound, upper_bound, false)
+            }
+        })
+    }
+
+    pub fn commit(self) -> Result<(), TypedStoreError> {
+        match self.transaction {
+            StorageTransaction::Rocks(transaction) => transaction.commit(),
+            StorageTransaction::InMemory(transaction) => transaction.commit(),
+        }
+    }
+}
+
+/// A point-in-time view of a database, created with `DBMap::snapshot`. Writes made after the
+/// snapshot was taken are not visible through it.
+pub struct DBSnapshot<'a> {
+    database: &'a Arc<Database>,
+    snapshot: StorageSnapshot<'a>,
+}
+
+enum StorageSnapshot<'a> {
+    Rocks(
+        &'a RocksDB,
+        rocksdb::SnapshotWithThreadMode<'a, rocksdb::DBWithThreadMode<MultiThreaded>>,
+    ),
+    InMemory(InMemorySnapshot),
+}
+
+impl DBSnapshot<'_> {
+    fn check_db<K, V>(&self, db: &DBMap<K, V>) -> Result<(), TypedStoreError> {
+        if !Arc::ptr_eq(&db.db, self.database) {
+            return Err(TypedStoreError::CrossDBBatch);
+        }
+        Ok(())
+    }
+
+    pub fn get<K: Serialize, V: DeserializeOwned>(
+        &self,
+        db: &DBMap<K, V>,
+        key: &K,
+    ) -> Result<Option<V>, TypedStoreError> {
+        Ok(self.multi_get(db, [key])?.pop().flatten())
+    }
+
+    pub fn multi_get<J: Borrow<K>, K: Serialize, V: DeserializeOwned>(
+        &self,
+        db: &DBMap<K, V>,
+        keys: impl IntoIterator<Item = J>,
+    ) -> Result<Vec<Option<V>>, TypedStoreError> {
+        self.check_db(db)?;
+        let keys: Vec<_> = keys
+            .into_iter()
+            .map(|k| be_fix_int_ser(k.borrow()))
+            .collect();
+        let values: Vec<Option<Vec<u8>>> = match &self.snapshot {
+            StorageSnapshot::Rocks(rocks, snapshot) => {
+                let mut readopts = db.opts.readopts();
+                readopts.set_snapshot(snapshot);
+                rocks
+                    .underlying
+                    .batched_multi_get_cf_opt(
+                        &rocks_cf(rocks, &db.cf),
+                        keys.iter(),
+                        /* sorted_input */ false,
+                        &readopts,
+                    )
+                    .into_iter()
+                    .map(|r| {
+                        r.map(|value| value.map(|v| v.to_vec()))
+                            .map_err(typed_store_err_from_rocks_err)
+                    })
+                    .collect::<Result<_, _>>()?
+            }
+            StorageSnapshot::InMemory(snapshot) => snapshot.multi_get(&db.cf, keys),
+        };
+        values
+            .into_iter()
+            .map(|value| {
+                value
+                    .map(|value| bcs::from_bytes(&value).map_err(typed_store_err_from_bcs_err))
+                    .transpose()
+            })
+            .collect()
+    }
+
+    pub fn safe_range_iter<'b, K, V>(
+        &'b self,
+        db: &'b DBMap<K, V>,
+        range: impl RangeBounds<K>,
+    ) -> Result<DbIterator<'b, (K, V)>, TypedStoreError>
+    where
+        K: Serialize + DeserializeOwned + 'b,
+        V: DeserializeOwned + 'b,
+    {
+        self.check_db(db)?;
+        let (lower_bound, upper_bound) = iterator_bounds_with_range(range);
+        let iter: DbIterator<'b, (K, V)> = match &self.snapshot {
+            StorageSnapshot::Rocks(rocks, snapshot) => Box::new(Self::rocks_iter(
+                db,
+                rocks,
+                snapshot,
+                lower_bound,
+                upper_bound,
+            )),
+            StorageSnapshot::InMemory(snapshot) => {
+                snapshot.iterator(&db.cf, lower_bound, upper_bound, false)
+            }
+        };
+        Ok(iter)
+    }
+
+    /// Creates a reversed iterator over the snapshot. Both bounds are included.
+    pub fn reversed_safe_iter_with_bounds<'b, K, V>(
+        &'b self,
+        db: &'b DBMap<K, V>,
+        lower_bound: Option<K>,
+        upper_bound: Option<K>,
+    ) -> Result<DbIterator<'b, (K, V)>, TypedStoreError>
+    where
+        K: Serialize + DeserializeOwned + 'b,
+        V: DeserializeOwned + 'b,
+    {
+        self.check_db(db)?;
+        let (it_lower_bound, it_upper_bound) = iterator_bounds_with_range::<K>((
+            lower_bound
+                .as_ref()
+                .map(Bound::Included)
+                .unwrap_or(Bound::Unbounded),
+            upper_bound
+                .as_ref()
+                .map(Bound::Included)
+                .unwrap_or(Bound::Unbounded),
+        ));
+        let iter: DbIterator<'b, (K, V)> = match &self.snapshot {
+            StorageSnapshot::Rocks(rocks, snapshot) => {
+                let upper_bound_key = upper_bound.as_ref().map(|k| be_fix_int_ser(&k));
+                let iter = Self::rocks_iter(db, rocks, snapshot, it_lower_bound, it_upper_bound);
+                Box::new(SafeRevIter::new(iter, upper_bound_key))
+            }
+            StorageSnapshot::InMemory(snapshot) => {
+                snapshot.iterator(&db.cf, it_lower_bound, it_upper_bound, true)
+            }
+        };
+        Ok(iter)
+    }
+
+    fn rocks_iter<'b, K, V>(
+        db: &'b DBMap<K, V>,
+        rocks: &'b RocksDB,
+        snapshot: &'b rocksdb::SnapshotWithThreadMode<'_, rocksdb::DBWithThreadMode<MultiThreaded>>,
+        lower_bound: Option<Vec<u8>>,
+        upper_bound: Option<Vec<u8>>,
+    ) -> SafeIter<'b, K, V>
+    where
+        K: DeserializeOwned,
+        V: DeserializeOwned,
+    {
+        let mut readopts =
+            rocks_util::apply_range_bounds(db.opts.readopts(), lower_bound, upper_bound);
+        readopts.set_snapshot(snapshot);
+        let db_iter = rocks
+            .underlying
+            .raw_iterator_cf_opt(&rocks_cf(rocks, &db.cf), readopts);
+        let (_timer, bytes_scanned, keys_scanned, _perf_ctx) = db.create_iter_context();
+        SafeIter::new(
+            db.cf.clone(),
+            db_iter,
+            _timer,
+            _perf_ctx,
+            bytes_scanned,
+            keys_scanned,
+            Some(db.db_metrics.clone()),
+        )
+    }
+}
+
 impl<'a, K, V> Map<'a, K, V> for DBMap<K, V>
 where
     K: Serialize + DeserializeOwned,
@@ -1746,6 +2178,15 @@ where
     }
 }
 
+/// Opens a new, empty in-memory database. Column families are created when a map is opened
+/// on them, or on first write.
+pub fn open_in_memory(metric_conf: MetricConf) -> Arc<Database> {
+    Arc::new(Database::new(
+        Storage::InMemory(InMemoryDB::new()),
+        metric_conf,
+    ))
+}
+
 /// Opens a database with options, and a number of column families with individual options that are created if they do not exist.
 #[instrument(level="debug", skip_all, fields(path = ?path.as_ref()), err)]
 pub fn open_cf_opts<P: AsRef<Path>>(
@@ -1780,6 +2221,7 @@ pub fn open_cf_opts<P: AsRef<Path>>(
         Ok(Arc::new(Database::new(
             Storage::Rocks(RocksDB {
                 underlying: rocksdb,
+                write_tracker: WriteTracker::default(),
             }),
             metric_conf,
         )))
@@ -1845,6 +2287,7 @@ pub fn open_cf_opts_secondary<P: AsRef<Path>>(
         Ok(Arc::new(Database::new(
             Storage::Rocks(RocksDB {
                 underlying: rocksdb,
+                write_tracker: WriteTracker::default(),
             }),
             metric_conf,
         )))
@@ -687,3 +687,212 @@ fn open_rocksdb<P: AsRef<Path>>(path: P, opt_cfs: &[&str]) -> Arc<Database> {
     )
     .expect("failed to open rocksdb")
 }
+
+fn open_map_with_storage<K, V>(in_memory: bool) -> DBMap<K, V> {
+    if in_memory {
+        DBMap::reopen(
+            &open_in_memory(MetricConf::default()),
+            None,
+            &ReadWriteOptions::default(),
+            false,
+        )
+        .expect("failed to open in-memory db")
+    } else {
+        // Range deletions are always visible in memory.
+        let options = ReadWriteOptions::default().set_ignore_range_deletions(false);
+        DBMap::reopen(
+            &open_rocksdb(temp_dir(), &[rocksdb::DEFAULT_COLUMN_FAMILY_NAME]),
+            None,
+            &options,
+            false,
+        )
+        .expect("failed to open rocksdb")
+    }
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_storage_consistency(#[values(true, false)] in_memory: bool) {
+    let db: DBMap<i32, String> = open_map_with_storage(in_memory);
+    let mut batch = db.batch();
+    batch
+        .insert_batch(&db, (1..100).map(|i| (i, i.to_string())))
+        .unwrap();
+    batch.write().unwrap();
+
+    let keys =
+        |iter: DbIterator<'_, (i32, String)>| iter.map(|item| item.unwrap().0).collect::<Vec<_>>();
+    assert_eq!(
+        keys(db.safe_iter_with_bounds(Some(20), Some(25))),
+        (20..25).collect::<Vec<_>>()
+    );
+    assert_eq!(
+        keys(db.safe_range_iter(20..=25)),
+        (20..=25).collect::<Vec<_>>()
+    );
+    assert_eq!(
+        keys(
+            db.reversed_safe_iter_with_bounds(Some(20), Some(25))
+                .unwrap()
+        ),
+        (20..=25).rev().collect::<Vec<_>>()
+    );
+
+    // An open iterator does not observe writes made after it was created.
+    let mut iter = db.safe_iter();
+    assert_eq!(iter.next().unwrap().unwrap().0, 1);
+    let mut batch = db.batch();
+    batch.insert_batch(&db, [(2, "new".to_string())]).unwrap();
+    batch.schedule_delete_range(&db, &50, &99).unwrap();
+    batch.write().unwrap();
+    assert_eq!(iter.next().unwrap().unwrap(), (2, "2".to_string()));
+    assert_eq!(iter.count(), 97);
+
+    assert_eq!(db.get(&2).unwrap(), Some("new".to_string()));
+    assert_eq!(keys(db.safe_range_iter(48..)), vec![48, 49, 99]);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_transaction_and_snapshot(#[values(true, false)] in_memory: bool) {
+    let db: DBMap<i32, String> = open_map_with_storage(in_memory);
+    db.insert(&1, &"1".to_string()).unwrap();
+
+    let snapshot = db.snapshot().unwrap();
+    let mut txn = db.db.transaction().unwrap();
+    assert_eq!(txn.get_for_update(&db, &1).unwrap(), Some("1".to_string()));
+    txn.insert_batch(&db, [(1, "txn".to_string()), (2, "txn".to_string())])
+        .unwrap();
+    db.insert(&3, &"3".to_string()).unwrap();
+    txn.commit().unwrap();
+
+    assert_eq!(db.get(&1).unwrap(), Some("txn".to_string()));
+    assert_eq!(snapshot.get(&db, &1).unwrap(), Some("1".to_string()));
+    assert_eq!(
+        snapshot
+            .safe_range_iter(&db, ..)
+            .unwrap()
+            .map(|item| item.unwrap().0)
+            .collect::<Vec<_>>(),
+        vec![1]
+    );
+
+    // A concurrent write to a key read for update fails the commit.
+    let mut txn = db.db.transaction().unwrap();
+    txn.get_for_update(&db, &3).unwrap();
+    txn.delete_batch(&db, [2]).unwrap();
+    db.insert(&3, &"changed".to_string()).unwrap();
+    assert_eq!(
+        txn.commit(),
+        Err(TypedStoreError::RetryableTransactionError)
+    );
+    assert_eq!(db.get(&2).unwrap(), Some("txn".to_string()));
+
+    // So does a batch writing a key written by the transaction, even with the same value.
+    let mut txn = db.db.transaction().unwrap();
+    assert_eq!(txn.get(&db, &1).unwrap(), Some("txn".to_string()));
+    txn.insert_batch(&db, [(4, "txn".to_string())]).unwrap();
+    let mut batch = db.batch();
+    batch.insert_batch(&db, [(4, "txn".to_string())]).unwrap();
+    batch.write().unwrap();
+    assert_eq!(
+        txn.commit(),
+        Err(TypedStoreError::RetryableTransactionError)
+    );
+
+    // Writes to other keys do not.
+    let mut txn = db.db.transaction().unwrap();
+    txn.get_for_update(&db, &1).unwrap();
+    txn.insert_batch(&db, [(5, "txn".to_string())]).unwrap();
+    let mut batch = db.batch();
+    batch.insert_batch(&db, [(6, "6".to_string())]).unwrap();
+    batch.write().unwrap();
+    db.remove(&4).unwrap();
+    txn.commit().unwrap();
+    assert_eq!(db.get(&5).unwrap(), Some("txn".to_string()));
+}
+
+#[tokio::test]
+async fn test_rocksdb_transaction_unknown_writes() {
+    let db: DBMap<i32, String> = open_map_with_storage(false);
+    db.multi_insert((1..=5).map(|i| (i, i.to_string())))
+        .unwrap();
+
+    // The keys of a batch created before the transaction started are not known, so writing it
+    // conflicts with the transaction.
+    let mut batch = db.batch();
+    batch.insert_batch(&db, [(5, "5".to_string())]).unwrap();
+    let mut txn = db.db.transaction().unwrap();
+    txn.get_for_update(&db, &1).unwrap();
+    batch.write().unwrap();
+    assert_eq!(
+        txn.commit(),
+        Err(TypedStoreError::RetryableTransactionError)
+    );
+
+    // So does a range deletion.
+    let mut txn = db.db.transaction().unwrap();
+    txn.insert_batch(&db, [(1, "txn".to_string())]).unwrap();
+    let mut batch = db.batch();
+    batch.schedule_delete_range(&db, &4, &6).unwrap();
+    batch.write().unwrap();
+    assert_eq!(
+        txn.commit(),
+        Err(TypedStoreError::RetryableTransactionError)
+    );
+    assert_eq!(db.get(&1).unwrap(), Some("1".to_string()));
+
+    // Once no transaction is open, previous writes are forgotten.
+    let mut txn = db.db.transaction().unwrap();
+    txn.get_for_update(&db, &1).unwrap();
+    txn.insert_batch(&db, [(1, "txn".to_string())]).unwrap();
+    txn.commit().unwrap();
+    assert_eq!(db.get(&1).unwrap(), Some("txn".to_string()));
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_snapshot(#[values(true, false)] is_in_memory: bool) {
+    let db: DBMap<i32, String> = open_map_with_storage(is_in_memory);
+    db.multi_insert((1..=5).map(|i| (i, i.to_string())))
+        .unwrap();
+
+    let snapshot = db.snapshot().unwrap();
+    let mut batch = db.batch();
+    batch
+        .insert_batch(&db, [(2, "new".to_string()), (6, "6".to_string())])
+        .unwrap();
+    batch.delete_batch(&db, [3]).unwrap();
+    batch.write().unwrap();
+
+    // The snapshot does not observe writes made after it was taken.
+    assert_eq!(snapshot.get(&db, &2).unwrap(), Some("2".to_string()));
+    assert_eq!(
+        snapshot.multi_get(&db, [3, 6]).unwrap(),
+        vec![Some("3".to_string()), None]
+    );
+    assert_eq!(
+        snapshot
+            .safe_range_iter(&db, 2..)
+            .unwrap()
+            .map(|item| item.unwrap())
+            .collect::<Vec<_>>(),
+        (2..=5).map(|i| (i, i.to_string())).collect::<Vec<_>>()
+    );
+    assert_eq!(
+        snapshot
+            .reversed_safe_iter_with_bounds(&db, Some(2), Some(4))
+            .unwrap()
+            .map(|item| item.unwrap().0)
+            .collect::<Vec<_>>(),
+        vec![4, 3, 2]
+    );
+
+    // The map itself does.
+    assert_eq!(db.get(&2).unwrap(), Some("new".to_string()));
+    assert_eq!(db.get(&3).unwrap(), None);
+
+    // A snapshot can only read the database it was taken from.
+    let other: DBMap<i32, String> = open_map_with_storage(is_in_memory);
+    assert_eq!(snapshot.get(&other, &1), Err(TypedStoreError::CrossDBBatch));
+}
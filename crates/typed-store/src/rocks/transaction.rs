@@ -0,0 +1,217 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Optimistic transactions over RocksDB storage, with the semantics of the in-memory storage:
+//! reads observe a snapshot taken when the transaction starts, writes are buffered until commit,
+//! and the commit fails if a key read for update or written by the transaction was written by
+//! anyone else after the snapshot, whatever the value written.
+//!
+//! RocksDB storage is not opened as an `OptimisticTransactionDB`, so conflicts are detected with
+//! the `WriteTracker` of the database instead. While no transaction is open, writes only take its
+//! lock shared. While transactions are open, writes are serialized and, like in the in-memory
+//! storage, the sequence number of the last write to each key is kept. Writes whose keys are not
+//! known, such as range deletions, conflict with every transaction open when they are made.
+
+use std::collections::{BTreeSet, HashMap};
+use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use rocksdb::{MultiThreaded, WriteBatch, WriteOptions};
+use typed_store_error::TypedStoreError;
+
+use super::errors::typed_store_err_from_rocks_err;
+use super::{RocksDB, rocks_cf};
+
+/// A key of a column family.
+pub(crate) type CfKey = (String, Vec<u8>);
+
+#[derive(Debug, Default)]
+pub(crate) struct WriteTracker {
+    state: RwLock<WriteTrackerState>,
+}
+
+#[derive(Debug, Default)]
+struct WriteTrackerState {
+    /// The sequence number of the last write made while transactions were open.
+    sequence: u64,
+    open_transactions: usize,
+    /// The sequence number of the last write to each key, kept while transactions are open.
+    versions: HashMap<CfKey, u64>,
+    /// The sequence number of the last write whose keys are not known.
+    unknown_keys_sequence: u64,
+}
+
+impl WriteTrackerState {
+    /// Records a write of `keys`, or of unknown keys if `None`, if an open transaction may need
+    /// to know.
+    fn record(&mut self, keys: Option<Vec<CfKey>>) {
+        if self.open_transactions == 0 {
+            return;
+        }
+        self.sequence += 1;
+        match keys {
+            Some(keys) => {
+                for key in keys {
+                    self.versions.insert(key, self.sequence);
+                }
+            }
+            None => self.unknown_keys_sequence = self.sequence,
+        }
+    }
+}
+
+/// A write in progress. It holds the lock of the `WriteTracker` until it is finished, so that
+/// transactions started meanwhile observe it in their snapshot or have it recorded.
+pub(crate) enum TrackedWrite<'a> {
+    Untracked(#[allow(dead_code)] RwLockReadGuard<'a, WriteTrackerState>),
+    Tracked(RwLockWriteGuard<'a, WriteTrackerState>),
+}
+
+impl TrackedWrite<'_> {
+    /// Whether the keys written need to be passed to `finish`.
+    pub(crate) fn is_tracked(&self) -> bool {
+        matches!(self, TrackedWrite::Tracked(_))
+    }
+
+    /// Records the keys written, or a write of unknown keys if `None`.
+    pub(crate) fn finish(self, keys: Option<Vec<CfKey>>) {
+        if let TrackedWrite::Tracked(mut state) = self {
+            state.record(keys);
+        }
+    }
+}
+
+impl WriteTracker {
+    pub(crate) fn has_open_transactions(&self) -> bool {
+        self.state
+            .read()
+            .expect("can't read write tracker")
+            .open_transactions
+            > 0
+    }
+
+    /// Starts a write, which must be made before the returned guard is finished.
+    pub(crate) fn start_write(&self) -> TrackedWrite<'_> {
+        let state = self.state.read().expect("can't read write tracker");
+        if state.open_transactions == 0 {
+            return TrackedWrite::Untracked(state);
+        }
+        drop(state);
+        TrackedWrite::Tracked(self.state.write().expect("can't write write tracker"))
+    }
+}
+
+/// An optimistic transaction over RocksDB storage, see `DBTransaction`.
+pub(crate) struct RocksTransaction<'a> {
+    pub(super) rocks: &'a RocksDB,
+    pub(super) snapshot:
+        rocksdb::SnapshotWithThreadMode<'a, rocksdb::DBWithThreadMode<MultiThreaded>>,
+    /// The sequence number of the last write tracked before the snapshot was taken.
+    sequence: u64,
+    /// Keys read for update or written by the transaction.
+    tracked_keys: BTreeSet<CfKey>,
+    written_keys: Vec<CfKey>,
+    batch: WriteBatch,
+}
+
+impl<'a> RocksTransaction<'a> {
+    pub(crate) fn new(rocks: &'a RocksDB) -> Self {
+        let mut state = rocks
+            .write_tracker
+            .state
+            .write()
+            .expect("can't write write tracker");
+        state.open_transactions += 1;
+        // Taken under the lock, so that every write is either in the snapshot or tracked.
+        let snapshot = rocks.underlying.snapshot();
+        Self {
+            rocks,
+            snapshot,
+            sequence: state.sequence,
+            tracked_keys: BTreeSet::new(),
+            written_keys: vec![],
+            batch: WriteBatch::default(),
+        }
+    }
+
+    /// Reads a key from the snapshot, without tracking it for conflicts.
+    pub(crate) fn get(
+        &self,
+        cf_name: &str,
+        key: &[u8],
+    ) -> Result<Option<Vec<u8>>, TypedStoreError> {
+        self.snapshot
+            .get_cf(&rocks_cf(self.rocks, cf_name), key)
+            .map_err(typed_store_err_from_rocks_err)
+    }
+
+    /// Reads a key from the snapshot, and fails the commit if the key is changed by another
+    /// writer before the transaction commits.
+    pub(crate) fn get_for_update(
+        &mut self,
+        cf_name: &str,
+        key: &[u8],
+    ) -> Result<Option<Vec<u8>>, TypedStoreError> {
+        self.tracked_keys
+            .insert((cf_name.to_string(), key.to_vec()));
+        self.get(cf_name, key)
+    }
+
+    pub(crate) fn put_cf(&mut self, cf_name: &str, key: Vec<u8>, value: Vec<u8>) {
+        self.track_write(cf_name, &key);
+        self.batch
+            .put_cf(&rocks_cf(self.rocks, cf_name), key, value);
+    }
+
+    pub(crate) fn delete_cf(&mut self, cf_name: &str, key: Vec<u8>) {
+        self.track_write(cf_name, &key);
+        self.batch.delete_cf(&rocks_cf(self.rocks, cf_name), key);
+    }
+
+    fn track_write(&mut self, cf_name: &str, key: &[u8]) {
+        let key = (cf_name.to_string(), key.to_vec());
+        self.tracked_keys.insert(key.clone());
+        self.written_keys.push(key);
+    }
+
+    /// Validates the tracked keys and writes the buffered changes atomically.
+    pub(crate) fn commit(mut self) -> Result<(), TypedStoreError> {
+        let mut state = self
+            .rocks
+            .write_tracker
+            .state
+            .write()
+            .expect("can't write write tracker");
+        if state.unknown_keys_sequence > self.sequence
+            || self.tracked_keys.iter().any(|key| {
+                state
+                    .versions
+                    .get(key)
+                    .is_some_and(|&written| written > self.sequence)
+            })
+        {
+            return Err(TypedStoreError::RetryableTransactionError);
+        }
+        self.rocks
+            .underlying
+            .write_opt(std::mem::take(&mut self.batch), &WriteOptions::default())
+            .map_err(typed_store_err_from_rocks_err)?;
+        state.record(Some(std::mem::take(&mut self.written_keys)));
+        Ok(())
+    }
+}
+
+impl Drop for RocksTransaction<'_> {
+    fn drop(&mut self) {
+        let mut state = self
+            .rocks
+            .write_tracker
+            .state
+            .write()
+            .expect("can't write write tracker");
+        state.open_transactions -= 1;
+        // Versions are only needed to validate open transactions.
+        if state.open_transactions == 0 {
+            state.versions.clear();
+        }
+    }
+}
@@ -11,6 +11,7 @@ use std::fmt::Debug;
 use std::sync::Mutex;
 use std::time::Duration;
 use typed_store::metrics::SamplingInterval;
+use typed_store::migration::{MigrationRegistry, SchemaMigrator};
 use typed_store::rocks::DBMap;
 use typed_store::rocks::MetricConf;
 use typed_store::traits::Map;
@@ -212,6 +213,102 @@ async fn deprecate_test() {
     }
 }
 
+#[derive(DBMapUtils)]
+struct VersionedTables1 {
+    objects: DBMap<u64, u32>,
+}
+
+#[derive(DBMapUtils)]
+struct VersionedTables2 {
+    #[schema_version = 1]
+    #[rename = "objects_v1"]
+    objects: DBMap<u64, u64>,
+}
+
+#[tokio::test]
+async fn migration_test() {
+    let dbdir = temp_dir();
+    {
+        let db = VersionedTables1::open_tables_read_write(
+            dbdir.clone(),
+            MetricConf::default(),
+            None,
+            None,
+        );
+        db.objects
+            .multi_insert((0..25).map(|i| (i, i as u32)))
+            .unwrap();
+        SchemaMigrator::new(db.objects.db.clone(), MigrationRegistry::new())
+            .unwrap()
+            .start(&VersionedTables1::table_descriptors())
+            .unwrap()
+            .wait_migrated("objects")
+            .await
+            .unwrap();
+    }
+
+    tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+
+    // Upgrade, the values are migrated into the renamed table.
+    {
+        let db = VersionedTables2::open_tables_read_write(
+            dbdir.clone(),
+            MetricConf::default(),
+            None,
+            None,
+        );
+        let registry = MigrationRegistry::new().register::<u64, u32, u64>(
+            "objects_v1",
+            1,
+            "objects",
+            0,
+            |value| value as u64 * 2,
+        );
+        let migrator = SchemaMigrator::new(db.objects.db.clone(), registry)
+            .unwrap()
+            .with_batch_size(10);
+        let pending = migrator
+            .prepare(&VersionedTables2::table_descriptors())
+            .unwrap();
+        assert_eq!(pending, vec!["objects_v1".to_string()]);
+        assert!(!migrator.is_migrated("objects_v1").unwrap());
+
+        migrator.migrate("objects_v1").unwrap();
+        assert!(migrator.is_migrated("objects_v1").unwrap());
+        let migrated: Vec<_> = db.objects.safe_iter().map(Result::unwrap).collect();
+        assert_eq!(migrated, (0..25).map(|i| (i, i * 2)).collect::<Vec<_>>());
+        let state = migrator.table_state("objects_v1").unwrap().unwrap();
+        assert_eq!(state.version, 1);
+        assert_eq!(
+            state.rollback.map(|marker| marker.cf_name),
+            Some("objects".to_string())
+        );
+    }
+
+    tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+
+    // Downgrade, the previous binary drops the renamed table and reads the original one.
+    {
+        let db = VersionedTables1::open_tables_read_write(
+            dbdir.clone(),
+            MetricConf::default(),
+            None,
+            None,
+        );
+        let migrator =
+            SchemaMigrator::new(db.objects.db.clone(), MigrationRegistry::new()).unwrap();
+        assert!(
+            migrator
+                .prepare(&VersionedTables1::table_descriptors())
+                .unwrap()
+                .is_empty()
+        );
+        assert!(!db.objects.db.has_cf("objects_v1").unwrap());
+        assert_eq!(migrator.table_state("objects_v1").unwrap(), None);
+        assert_eq!(db.objects.get(&24), Ok(Some(24)));
+    }
+}
+
 /// We show that custom functions can be applied
 #[derive(DBMapUtils)]
 struct TablesCustomOptions {